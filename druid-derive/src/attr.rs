@@ -24,6 +24,7 @@ use quote::{quote, quote_spanned};
 const BASE_DRUID_DEPRECATED_ATTR_PATH: &str = "druid";
 const BASE_DATA_ATTR_PATH: &str = "data";
 const BASE_LENS_ATTR_PATH: &str = "lens";
+const BASE_PRISM_ATTR_PATH: &str = "prism";
 const IGNORE_ATTR_PATH: &str = "ignore";
 const DATA_SAME_FN_ATTR_PATH: &str = "same_fn";
 const DATA_EQ_ATTR_PATH: &str = "eq";
@@ -83,6 +84,13 @@ pub struct LensAttrs {
     pub lens_name_override: Option<Ident>,
 }
 
+#[derive(Debug)]
+pub struct PrismAttrs {
+    /// `true` if this variant should be ignored.
+    pub ignore: bool,
+    pub prism_name_override: Option<Ident>,
+}
+
 impl Fields<DataAttr> {
     pub fn parse_ast(fields: &syn::Fields) -> Result<Self, Error> {
         let kind = match fields {
@@ -271,6 +279,55 @@ impl Field<LensAttrs> {
     }
 }
 
+impl PrismAttrs {
+    /// Parse the `prism` attributes of an enum variant.
+    pub fn parse_ast(variant: &syn::Variant) -> Result<Self, Error> {
+        let mut ignore = false;
+        let mut prism_name_override = None;
+
+        for attr in variant.attrs.iter() {
+            if !attr.path.is_ident(BASE_PRISM_ATTR_PATH) {
+                continue;
+            }
+            match attr.parse_meta()? {
+                Meta::List(meta) => {
+                    for nested in meta.nested.iter() {
+                        match nested {
+                            NestedMeta::Meta(Meta::Path(path))
+                                if path.is_ident(IGNORE_ATTR_PATH) =>
+                            {
+                                if ignore {
+                                    return Err(Error::new(nested.span(), "Duplicate attribute"));
+                                }
+                                ignore = true;
+                            }
+                            NestedMeta::Meta(Meta::NameValue(meta))
+                                if meta.path.is_ident(LENS_NAME_OVERRIDE_ATTR_PATH) =>
+                            {
+                                if prism_name_override.is_some() {
+                                    return Err(Error::new(meta.span(), "Duplicate attribute"));
+                                }
+                                prism_name_override = Some(parse_lit_into_ident(&meta.lit)?);
+                            }
+                            other => return Err(Error::new(other.span(), "Unknown attribute")),
+                        }
+                    }
+                }
+                other => {
+                    return Err(Error::new(
+                        other.span(),
+                        "Expected attribute list (the form #[prism(one, two)])",
+                    ));
+                }
+            }
+        }
+        Ok(PrismAttrs {
+            ignore,
+            prism_name_override,
+        })
+    }
+}
+
 impl<Attrs> Field<Attrs> {
     pub fn ident_tokens(&self) -> TokenTree {
         match self.ident {
@@ -56,11 +56,33 @@ fn derive_struct(
         quote!(true)
     };
 
+    // fields are numbered in declaration order, the same as their derived lenses
+    let changes = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.attrs != DataAttr::Ignore)
+        .map(|(index, f)| {
+            let same_fn = f.same_fn_path_tokens();
+            let field = f.ident_tokens();
+            quote! {
+                if !#same_fn(&self.#field, &other.#field) {
+                    changes = changes.union(::druid::ChangeSet::field(#index));
+                }
+            }
+        });
+
     let res = quote! {
         impl<#impl_generics> ::druid::Data for #ident #ty_generics #where_clause {
             fn same(&self, other: &Self) -> bool {
                 #diff
             }
+
+            #[allow(unused_mut)]
+            fn changes(&self, other: &Self) -> ::druid::ChangeSet {
+                let mut changes = ::druid::ChangeSet::NONE;
+                #( #changes )*
+                changes
+            }
         }
     };
 
@@ -134,7 +134,9 @@ fn derive_struct(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream, s
     let func_ty_par = gen_new_param("F");
     let val_ty_par = gen_new_param("V");
 
-    let impls = fields.iter().filter(|f| !f.attrs.ignore).map(|f| {
+    // fields are numbered in declaration order, the same as in the derived
+    // `Data::changes`
+    let impls = fields.iter().enumerate().filter(|(_, f)| !f.attrs.ignore).map(|(index, f)| {
         let field_name = &f.ident.unwrap_named();
         let field_ty = &f.ty;
 
@@ -148,6 +150,10 @@ fn derive_struct(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream, s
                 fn with_mut<#val_ty_par, #func_ty_par: FnOnce(&mut #field_ty) -> #val_ty_par>(&self, data: &mut #ty#ty_generics, f: #func_ty_par) -> #val_ty_par {
                     f(&mut data.#field_name)
                 }
+
+                fn depends_on(&self) -> druid::ChangeSet {
+                    druid::ChangeSet::field(#index)
+                }
             }
         }
     });
@@ -186,7 +192,7 @@ fn char_has_case(c: char) -> bool {
     c.is_lowercase() || c.is_uppercase()
 }
 
-fn is_camel_case(name: &str) -> bool {
+pub(crate) fn is_camel_case(name: &str) -> bool {
     let name = name.trim_matches('_');
     if name.is_empty() {
         return true;
@@ -202,7 +208,7 @@ fn is_camel_case(name: &str) -> bool {
         })
 }
 
-fn to_snake_case(mut str: &str) -> String {
+pub(crate) fn to_snake_case(mut str: &str) -> String {
     let mut words = vec![];
     // Preserve leading underscores
     str = str.trim_start_matches(|c: char| {
@@ -21,6 +21,7 @@ extern crate proc_macro;
 mod attr;
 mod data;
 mod lens;
+mod prism;
 
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
@@ -97,3 +98,45 @@ pub fn derive_lens(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Generates prisms to access the variants of an enum.
+///
+/// An associated constant is defined on the enum for each variant, named
+/// after the variant in snake case. The prism targets the variant's field if
+/// it has exactly one, or `()` if it has none; variants with several fields
+/// have to be skipped.
+///
+/// This macro supports a `prism` variant attribute with the following arguments:
+///
+/// - `#[prism(ignore)]` skips creating a prism for one variant.
+/// - `#[prism(name="foo")]` gives the prism the specified name (instead of the default, which
+///   is the variant's name in snake case).
+///
+/// # Example
+///
+/// ```rust
+/// use druid_derive::Prism;
+///
+/// #[derive(Clone, Prism)]
+/// enum Connection {
+///     // The Prism derive will create a `Connection::disconnected` constant
+///     // implementing `druid::Prism<Connection, ()>`
+///     Disconnected,
+///     // The Prism derive will create a `Connection::connected` constant
+///     // implementing `druid::Prism<Connection, String>`
+///     Connected(String),
+///     // The Prism derive will create a `Connection::retrying` constant
+///     // implementing `druid::Prism<Connection, u32>`
+///     Retrying { attempt: u32 },
+///     // The Prism derive won't create anything for this variant.
+///     #[prism(ignore)]
+///     Failed(String, u32),
+/// }
+/// ```
+#[proc_macro_derive(Prism, attributes(prism))]
+pub fn derive_prism(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    prism::derive_prism_impl(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
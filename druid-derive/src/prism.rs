@@ -0,0 +1,256 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::attr::PrismAttrs;
+use super::lens::{is_camel_case, to_snake_case};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use std::collections::HashSet;
+use syn::{spanned::Spanned, Data, GenericParam, TypeParam};
+
+pub(crate) fn derive_prism_impl(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    match &input.data {
+        Data::Enum(_) => derive_enum(&input),
+        Data::Struct(s) => Err(syn::Error::new(
+            s.struct_token.span(),
+            "Prism implementations cannot be derived from structs; use Lens instead",
+        )),
+        Data::Union(u) => Err(syn::Error::new(
+            u.union_token.span(),
+            "Prism implementations cannot be derived from unions",
+        )),
+    }
+}
+
+/// A variant we generate a prism for.
+struct PrismVariant<'a> {
+    variant: &'a syn::Ident,
+    /// The name of the prism type and of the associated constant.
+    name: Ident,
+    const_name: Ident,
+    /// The type the prism targets.
+    target: TokenStream,
+    /// A pattern binding the variant's field to `value`.
+    pattern: TokenStream,
+    /// An expression building the variant from `value`.
+    build: TokenStream,
+    /// How to get at the bound `value`, by reference.
+    value: TokenStream,
+}
+
+fn derive_enum(input: &syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    let ty = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(e) => &e.variants,
+        _ => unreachable!(),
+    };
+
+    let twizzled_name = if is_camel_case(&ty.to_string()) {
+        let temp_name = format!("{}_derived_prisms", to_snake_case(&ty.to_string()));
+        Ident::new(&temp_name, Span::call_site())
+    } else {
+        return Err(syn::Error::new(
+            ty.span(),
+            "Prism implementations can only be derived from CamelCase types",
+        ));
+    };
+
+    let mut prisms = Vec::new();
+    for variant in variants.iter() {
+        let attrs = PrismAttrs::parse_ast(variant)?;
+        if attrs.ignore {
+            continue;
+        }
+        let ident = &variant.ident;
+        let name = Ident::new(&to_snake_case(&ident.to_string()), Span::call_site());
+        let const_name = attrs.prism_name_override.unwrap_or_else(|| name.clone());
+        let (target, pattern, build, value) = match &variant.fields {
+            syn::Fields::Unit => (
+                quote! { () },
+                quote! { #ty::#ident },
+                quote! { #ty::#ident },
+                quote! { &mut () },
+            ),
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field_ty = &fields.unnamed[0].ty;
+                (
+                    quote! { #field_ty },
+                    quote! { #ty::#ident(value) },
+                    quote! { #ty::#ident(value) },
+                    quote! { value },
+                )
+            }
+            syn::Fields::Named(fields) if fields.named.len() == 1 => {
+                let field = &fields.named[0];
+                let field_ty = &field.ty;
+                let field_name = &field.ident;
+                (
+                    quote! { #field_ty },
+                    quote! { #ty::#ident { #field_name: value } },
+                    quote! { #ty::#ident { #field_name: value } },
+                    quote! { value },
+                )
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "Prisms can only be derived for variants with at most one field; \
+                    skip this variant with #[prism(ignore)]",
+                ))
+            }
+        };
+        prisms.push(PrismVariant {
+            variant: ident,
+            name,
+            const_name,
+            target,
+            pattern,
+            build,
+            value,
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut prism_ty_idents = Vec::new();
+    let mut phantom_decls = Vec::new();
+    let mut phantom_inits = Vec::new();
+
+    for gp in input.generics.params.iter() {
+        if let GenericParam::Type(TypeParam { ident, .. }) = gp {
+            prism_ty_idents.push(quote! {#ident});
+            phantom_decls.push(quote! {std::marker::PhantomData<*const #ident>});
+            phantom_inits.push(quote! {std::marker::PhantomData});
+        }
+    }
+
+    let prism_ty_generics = quote! {
+        <#(#prism_ty_idents),*>
+    };
+
+    // Define prism types for each variant
+    let defs = prisms.iter().map(|p| {
+        let name = &p.name;
+        let struct_docs = format!(
+            "Prism for the variant `{variant}` of [`{ty}`](super::{ty}).",
+            variant = p.variant,
+            ty = ty,
+        );
+        let fn_docs = format!(
+            "Creates a new prism for the variant `{variant}` of [`{ty}`](super::{ty}). \
+            Use [`{ty}::{name}`](super::{ty}::{name}) instead.",
+            variant = p.variant,
+            ty = ty,
+            name = p.const_name,
+        );
+
+        quote! {
+            #[doc = #struct_docs]
+            #[allow(non_camel_case_types)]
+            #[derive(Debug, Copy, Clone)]
+            pub struct #name#prism_ty_generics(#(#phantom_decls),*);
+
+            impl #prism_ty_generics #name#prism_ty_generics {
+                #[doc = #fn_docs]
+                pub const fn new() -> Self {
+                    Self(#(#phantom_inits),*)
+                }
+            }
+        }
+    });
+
+    let used_params: HashSet<String> = input
+        .generics
+        .params
+        .iter()
+        .flat_map(|gp: &GenericParam| match gp {
+            GenericParam::Type(TypeParam { ident, .. }) => Some(ident.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let gen_new_param = |name: &str| {
+        let mut candidate: String = name.into();
+        let mut count = 1usize;
+        while used_params.contains(&candidate) {
+            candidate = format!("{}_{}", name, count);
+            count += 1;
+        }
+        Ident::new(&candidate, Span::call_site())
+    };
+
+    let func_ty_par = gen_new_param("F");
+    let val_ty_par = gen_new_param("V");
+
+    let impls = prisms.iter().map(|p| {
+        let name = &p.name;
+        let target = &p.target;
+        let pattern = &p.pattern;
+        let build = &p.build;
+        let value = &p.value;
+
+        quote! {
+            impl #impl_generics druid::Prism<#ty#ty_generics, #target> for #twizzled_name::#name#prism_ty_generics #where_clause {
+                #[allow(unreachable_patterns, unused_variables)]
+                fn with<#val_ty_par, #func_ty_par: FnOnce(&#target) -> #val_ty_par>(&self, data: &#ty#ty_generics, f: #func_ty_par) -> Option<#val_ty_par> {
+                    match data {
+                        #pattern => Some(f(#value)),
+                        _ => None,
+                    }
+                }
+
+                #[allow(unreachable_patterns, unused_variables)]
+                fn with_mut<#val_ty_par, #func_ty_par: FnOnce(&mut #target) -> #val_ty_par>(&self, data: &mut #ty#ty_generics, f: #func_ty_par) -> Option<#val_ty_par> {
+                    match data {
+                        #pattern => Some(f(#value)),
+                        _ => None,
+                    }
+                }
+
+                #[allow(unused_variables)]
+                fn put(&self, data: &mut #ty#ty_generics, value: #target) {
+                    *data = #build;
+                }
+            }
+        }
+    });
+
+    let associated_items = prisms.iter().map(|p| {
+        let name = &p.name;
+        let const_name = &p.const_name;
+
+        quote! {
+            /// Prism for the corresponding variant.
+            pub const #const_name: #twizzled_name::#name#prism_ty_generics = #twizzled_name::#name::new();
+        }
+    });
+
+    let mod_docs = format!("Derived prisms for [`{}`].", ty);
+
+    let expanded = quote! {
+        #[doc = #mod_docs]
+        pub mod #twizzled_name {
+            #(#defs)*
+        }
+
+        #(#impls)*
+
+        #[allow(non_upper_case_globals)]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #(#associated_items)*
+        }
+    };
+
+    Ok(expanded)
+}
@@ -0,0 +1,52 @@
+use druid::Prism;
+
+#[derive(Debug, Clone, PartialEq, Prism)]
+enum State {
+    Empty,
+    Text(String),
+    Count {
+        count: u32,
+    },
+    #[prism(name = "pair_prism")]
+    Pair((u32, u32)),
+    #[prism(ignore)]
+    #[allow(dead_code)]
+    Many(u32, u32),
+}
+
+#[test]
+fn variants() {
+    let mut state = State::Empty;
+    assert!(State::empty.matches(&state));
+    assert_eq!(State::text.get(&state), None);
+
+    State::text.put(&mut state, "hi".into());
+    assert_eq!(state, State::Text("hi".into()));
+    State::text.with_mut(&mut state, |text| text.push('!'));
+    assert_eq!(State::text.get(&state), Some("hi!".to_string()));
+    assert!(!State::empty.matches(&state));
+
+    State::count.put(&mut state, 3);
+    assert_eq!(state, State::Count { count: 3 });
+    assert_eq!(State::count.with(&state, |count| count + 1), Some(4));
+
+    State::pair_prism.put(&mut state, (1, 2));
+    assert_eq!(State::pair_prism.get(&state), Some((1, 2)));
+
+    State::empty.put(&mut state, ());
+    assert_eq!(state, State::Empty);
+}
+
+#[derive(Clone, Prism)]
+enum Generic<T> {
+    Value(T),
+    Nothing,
+}
+
+#[test]
+fn generic() {
+    let mut value = Generic::<u64>::Nothing;
+    Generic::<u64>::value.put(&mut value, 42);
+    assert_eq!(Generic::<u64>::value.get(&value), Some(42));
+    assert!(!Generic::<u64>::nothing.matches(&value));
+}
@@ -70,6 +70,37 @@ fn mix_with_data_lens() {
     assert!(state.same(&two))
 }
 #[allow(clippy::trivially_copy_pass_by_ref)]
+#[test]
+fn changes_match_lenses() {
+    #[derive(Clone, Lens, Data)]
+    struct State {
+        #[data(ignore)]
+        #[lens(ignore)]
+        cache: usize,
+        text: String,
+        number: f64,
+    }
+
+    let state = State {
+        cache: 0,
+        text: "1.0".into(),
+        number: 1.0,
+    };
+    let changed = State {
+        number: 2.0,
+        ..state.clone()
+    };
+    let recached = State {
+        cache: state.cache + 1,
+        ..state.clone()
+    };
+
+    assert!(state.changes(&recached).is_empty());
+    let changes = state.changes(&changed);
+    assert!(!changes.intersects(State::text.depends_on()));
+    assert!(changes.intersects(State::number.depends_on()));
+}
+
 fn same_sign(one: &f64, two: &f64) -> bool {
     one.signum() == two.signum()
 }
@@ -0,0 +1,44 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The appearance chosen in the system settings.
+
+use crate::piet::Color;
+
+/// The light or dark preference and accent color of the system.
+///
+/// Get the current theme with [`Application::system_theme`]. When the user
+/// changes it, every window's [`WinHandler::system_theme_changed`] is called.
+///
+/// [`Application::system_theme`]: crate::Application::system_theme
+/// [`WinHandler::system_theme_changed`]: crate::WinHandler::system_theme_changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemTheme {
+    pub(crate) dark: bool,
+    pub(crate) accent_color: Option<Color>,
+}
+
+impl SystemTheme {
+    /// Whether the user prefers dark colors.
+    ///
+    /// This is `false` on platforms where the preference can't be detected.
+    pub fn is_dark(&self) -> bool {
+        self.dark
+    }
+
+    /// The accent color, if the platform has one.
+    pub fn accent_color(&self) -> Option<&Color> {
+        self.accent_color.as_ref()
+    }
+}
@@ -15,12 +15,16 @@
 //! The top-level application type.
 
 use std::cell::RefCell;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::appearance::SystemTheme;
 use crate::backend::application as backend;
 use crate::clipboard::Clipboard;
 use crate::error::Error;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::notification::{DesktopNotification, NotificationToken};
 use crate::util;
 
 /// A top-level handler that is not associated with any window.
@@ -40,6 +44,40 @@ pub trait AppHandler {
     /// Called when a menu item is selected.
     #[allow(unused_variables)]
     fn command(&mut self, id: u32) {}
+
+    /// Called when a [`TrayIcon`] is clicked.
+    ///
+    /// [`TrayIcon`]: crate::TrayIcon
+    fn tray_activated(&mut self) {}
+
+    /// Called when a notification shown with [`Application::show_notification`]
+    /// is clicked.
+    ///
+    /// `action` is the index of the clicked action button, or `None` if the
+    /// notification itself was clicked.
+    #[allow(unused_variables)]
+    fn notification_activated(&mut self, token: NotificationToken, action: Option<usize>) {}
+
+    /// Called when a hotkey registered with [`Application::register_global_hotkey`]
+    /// is pressed, whether or not the application has focus.
+    #[allow(unused_variables)]
+    fn global_hotkey(&mut self, token: GlobalHotKeyToken) {}
+
+    /// Called when the system asks the application to quit, for example from
+    /// the Dock on macOS, or because the user is logging out.
+    ///
+    /// To quit, call [`Application::quit`], right away or after asking the
+    /// user; to stay, call [`Application::cancel_quit`]. On macOS the system
+    /// waits for one of them before it carries on with, for example, a
+    /// logout. The default implementation quits right away.
+    ///
+    /// On Windows the session only ends once the application has quit; on
+    /// Linux it may end without waiting for the application.
+    fn request_quit(&mut self) {
+        if let Some(app) = Application::try_global() {
+            app.quit();
+        }
+    }
 }
 
 /// The top level application object.
@@ -170,11 +208,89 @@ impl Application {
         self.backend_app.quit()
     }
 
+    /// Tell the system that the quit it asked for with
+    /// [`AppHandler::request_quit`] won't happen, for example because the
+    /// user cancelled it.
+    ///
+    /// On macOS this also cancels the logout or shutdown that asked, if any.
+    /// Elsewhere it does nothing.
+    pub fn cancel_quit(&self) {
+        self.backend_app.cancel_quit()
+    }
+
     /// Returns a handle to the system clipboard.
     pub fn clipboard(&self) -> Clipboard {
         self.backend_app.clipboard().into()
     }
 
+    /// Show a notification on the desktop.
+    ///
+    /// The returned token is passed to [`AppHandler::notification_activated`]
+    /// when the user clicks the notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unsupported`] on platforms without desktop notifications.
+    ///
+    /// # Platform specific
+    ///
+    /// On Linux the notification is sent over D-Bus with the `gdbus` tool,
+    /// which comes with GLib. Icons of more than about 80x80 pixels are left
+    /// out.
+    pub fn show_notification(
+        &self,
+        notification: &DesktopNotification,
+    ) -> Result<NotificationToken, Error> {
+        let token = NotificationToken::next();
+        self.backend_app.show_notification(notification, token)?;
+        Ok(token)
+    }
+
+    /// Register a hotkey that works system-wide, even when the application
+    /// doesn't have focus.
+    ///
+    /// When the hotkey is pressed, [`AppHandler::global_hotkey`] is called
+    /// with the returned token. The hotkey stays registered until it is passed
+    /// to [`unregister_global_hotkey`], or the application quits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::HotKeyInUse`] if the hotkey is already taken, and
+    /// [`Error::Unsupported`] on platforms without system-wide hotkeys, such as
+    /// Wayland, and for keys that the keyboard layout can't type.
+    ///
+    /// [`unregister_global_hotkey`]: #method.unregister_global_hotkey
+    pub fn register_global_hotkey(&self, hotkey: &HotKey) -> Result<GlobalHotKeyToken, Error> {
+        let token = GlobalHotKeyToken::next();
+        self.backend_app.register_global_hotkey(hotkey, token)?;
+        Ok(token)
+    }
+
+    /// Unregister a hotkey registered with [`register_global_hotkey`].
+    ///
+    /// [`register_global_hotkey`]: #method.register_global_hotkey
+    pub fn unregister_global_hotkey(&self, token: GlobalHotKeyToken) {
+        self.backend_app.unregister_global_hotkey(token)
+    }
+
+    /// Open a URL with the user's preferred application, such as a web
+    /// browser for `https` links or a mail client for `mailto` links.
+    ///
+    /// This doesn't wait for the application to start. Failures that are only
+    /// detected once it's launching are logged instead of returned.
+    pub fn open_url(&self, url: &str) -> Result<(), Error> {
+        self.backend_app.open_url(url)
+    }
+
+    /// Show a file or directory in the system file manager, with it selected.
+    ///
+    /// On Linux this needs a file manager that supports the
+    /// `org.freedesktop.FileManager1` interface; otherwise the parent
+    /// directory is opened. Returns [`Error::Unsupported`] on the web.
+    pub fn reveal_path(&self, path: &Path) -> Result<(), Error> {
+        self.backend_app.reveal_path(path)
+    }
+
     /// Returns the current locale string.
     ///
     /// This should a [Unicode language identifier].
@@ -183,4 +299,33 @@ impl Application {
     pub fn get_locale() -> String {
         backend::Application::get_locale()
     }
+
+    /// Returns the light or dark preference and the accent color chosen in
+    /// the system settings.
+    pub fn system_theme() -> SystemTheme {
+        backend::Application::system_theme()
+    }
+
+    /// Shows a modal message box with a confirm and a cancel button, blocking
+    /// until the user dismisses it.
+    ///
+    /// This does not need a running application or an open window, so it can
+    /// be used to report errors that leave the application unusable. While the
+    /// message box is shown, some platforms run a nested event loop, so don't
+    /// call this while handling an event of a running application.
+    ///
+    /// Returns `true` if the user picked the confirm button.
+    ///
+    /// # Platform specific
+    ///
+    /// - On X11 the message box is shown by `zenity`, `kdialog`, or `xmessage`,
+    ///   whichever is installed. If none are, the message is logged and `false`
+    ///   is returned. The application's windows keep repainting meanwhile, but
+    ///   their input is held back until the message box is dismissed. `xmessage`
+    ///   can't show `:` or `,` in button labels, so they become spaces.
+    /// - On Windows and the web the buttons can't be labeled, so their labels
+    ///   are part of the message.
+    pub fn show_message_dialog(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+        backend::Application::show_message_dialog(title, message, confirm, cancel)
+    }
 }
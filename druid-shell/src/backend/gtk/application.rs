@@ -14,18 +14,48 @@
 
 //! GTK implementation of features at the application scope.
 
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::os::raw::{c_int, c_uint};
+use std::path::Path;
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+use anyhow::Context;
 use gio::prelude::ApplicationExtManual;
 use gio::{ApplicationExt, ApplicationFlags, Cancellable};
-use gtk::{Application as GtkApplication, GtkApplicationExt};
+use glib::translate::{ToGlib, ToGlibPtr};
+use gtk::prelude::{DialogExt, GtkWindowExt, SettingsExt};
+use gtk::{
+    Application as GtkApplication, ButtonsType, DialogFlags, GtkApplicationExt, MessageDialog,
+    MessageType, ResponseType,
+};
 
+use crate::appearance::SystemTheme;
 use crate::application::AppHandler;
+use crate::backend::shared::Notifications;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::keyboard::{KbKey, Modifiers};
+use crate::notification::{DesktopNotification, NotificationToken};
 
 use super::clipboard::Clipboard;
 use super::error::Error;
+use super::gdk_x11::{self, XKeyEvent};
+use super::keycodes;
+use super::portal::{self, ColorScheme};
 
 #[derive(Clone)]
 pub(crate) struct Application {
     gtk_app: GtkApplication,
+    handler: Rc<RefCell<Option<Box<dyn AppHandler>>>>,
+    /// The keycodes and modifier masks of the grabbed global hotkeys.
+    hotkeys: Rc<RefCell<HashMap<GlobalHotKeyToken, (c_uint, c_uint)>>>,
+    /// The keycode of the global hotkey being held down, to ignore its repeats.
+    hotkey_down: Rc<Cell<Option<c_uint>>>,
+    /// The notifications we have shown, once we have shown one.
+    notifications: Rc<RefCell<Option<Notifications>>>,
 }
 
 impl Application {
@@ -48,11 +78,27 @@ impl Application {
             tracing::info!("gtk: Activated application");
         });
 
+        // Ask the session manager to tell us when the user logs out. It doesn't
+        // wait for us to quit, so this is only a chance to save.
+        gtk_app.set_property_register_session(true);
+        gtk_app.connect_query_end(|_app| {
+            if let Some(app) = crate::Application::try_global() {
+                app.backend_app
+                    .with_handler(|handler| handler.request_quit());
+            }
+        });
+
         if let Err(err) = gtk_app.register(None as Option<&Cancellable>) {
             return Err(Error::Error(err));
         }
 
-        Ok(Application { gtk_app })
+        Ok(Application {
+            gtk_app,
+            handler: Rc::new(RefCell::new(None)),
+            hotkeys: Rc::new(RefCell::new(HashMap::new())),
+            hotkey_down: Rc::new(Cell::new(None)),
+            notifications: Rc::new(RefCell::new(None)),
+        })
     }
 
     #[inline]
@@ -60,9 +106,138 @@ impl Application {
         &self.gtk_app
     }
 
-    pub fn run(self, _handler: Option<Box<dyn AppHandler>>) {
+    /// Calls `f` with the handler passed to `run`, if there is one.
+    pub(crate) fn with_handler(&self, f: impl FnOnce(&mut dyn AppHandler)) {
+        // Take the handler out, so that it can call back into the application.
+        let handler = self.handler.borrow_mut().take();
+        if let Some(mut handler) = handler {
+            f(&mut *handler);
+            *self.handler.borrow_mut() = Some(handler);
+        }
+    }
+
+    pub fn register_global_hotkey(
+        &self,
+        hotkey: &HotKey,
+        token: GlobalHotKeyToken,
+    ) -> Result<(), crate::Error> {
+        // Wayland doesn't let clients grab keys, so this only works on X11.
+        let (x11, display, xdisplay) =
+            gdk_x11::default_display().ok_or(crate::Error::Unsupported)?;
+        let keycode = hotkey_keycode(&hotkey.key).ok_or(crate::Error::Unsupported)?;
+        let mods = hotkey_mod_mask(hotkey.mods.into());
+        let gdk_display = display.to_glib_none().0;
+        unsafe {
+            let root = (x11.x_default_root_window)(xdisplay);
+            // Caps Lock and Num Lock count as modifiers, so the key is grabbed with all of their
+            // combinations.
+            (x11.gdk_x11_display_error_trap_push)(gdk_display);
+            for &locks in LOCK_MASKS.iter() {
+                (x11.x_grab_key)(
+                    xdisplay,
+                    keycode as c_int,
+                    mods | locks,
+                    root,
+                    0,
+                    gdk_x11::GRAB_MODE_ASYNC,
+                    gdk_x11::GRAB_MODE_ASYNC,
+                );
+            }
+            match (x11.gdk_x11_display_error_trap_pop)(gdk_display) {
+                0 => {}
+                error => {
+                    ungrab_hotkey(keycode, mods);
+                    return Err(if error == gdk_x11::BAD_ACCESS {
+                        crate::Error::HotKeyInUse
+                    } else {
+                        anyhow::anyhow!("failed to grab global hotkey: X error {}", error).into()
+                    });
+                }
+            }
+        }
+
+        let mut hotkeys = self.hotkeys.borrow_mut();
+        if hotkeys.is_empty() {
+            unsafe {
+                gdk_sys::gdk_window_add_filter(
+                    ptr::null_mut(),
+                    Some(hotkey_filter),
+                    ptr::null_mut(),
+                )
+            };
+        }
+        hotkeys.insert(token, (keycode, mods));
+        Ok(())
+    }
+
+    pub fn unregister_global_hotkey(&self, token: GlobalHotKeyToken) {
+        let mut hotkeys = self.hotkeys.borrow_mut();
+        if let Some((keycode, mods)) = hotkeys.remove(&token) {
+            ungrab_hotkey(keycode, mods);
+            if hotkeys.is_empty() {
+                unsafe {
+                    gdk_sys::gdk_window_remove_filter(
+                        ptr::null_mut(),
+                        Some(hotkey_filter),
+                        ptr::null_mut(),
+                    )
+                };
+            }
+        }
+    }
+
+    pub fn show_notification(
+        &self,
+        notification: &DesktopNotification,
+        token: NotificationToken,
+    ) -> Result<(), crate::Error> {
+        let mut notifications = self.notifications.borrow_mut();
+        if notifications.is_none() {
+            // The activations are received on another thread; this gets them to the main loop.
+            let (sender, receiver) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
+            receiver.attach(None, |()| {
+                if let Some(app) = crate::Application::try_global() {
+                    app.backend_app.run_notification_activations();
+                }
+                glib::Continue(true)
+            });
+            let watching = Notifications::connect(move || {
+                let _ = sender.send(());
+            })
+            .context("failed to watch the notification server")?;
+            *notifications = Some(watching);
+        }
+        notifications.as_ref().unwrap().show(notification, token)?;
+        Ok(())
+    }
+
+    /// Tell the handler about the notifications that were clicked.
+    fn run_notification_activations(&self) {
+        let activations = match &*self.notifications.borrow() {
+            Some(notifications) => notifications.take_activations(),
+            None => return,
+        };
+        for (token, action) in activations {
+            self.with_handler(|handler| handler.notification_activated(token, action));
+        }
+    }
+
+    pub fn open_url(&self, url: &str) -> Result<(), crate::Error> {
+        // This goes through the OpenURI portal when we're sandboxed.
+        let window = self.gtk_app.get_active_window();
+        gtk::show_uri_on_window(window.as_ref(), url, gtk::get_current_event_time())
+            .map_err(|err| Error::Error(err).into())
+    }
+
+    pub fn reveal_path(&self, path: &Path) -> Result<(), crate::Error> {
+        crate::backend::shared::reveal_path(path)
+    }
+
+    pub fn run(self, handler: Option<Box<dyn AppHandler>>) {
+        *self.handler.borrow_mut() = handler;
         // TODO: should we pass the command line arguments?
         self.gtk_app.run(&[]);
+        *self.handler.borrow_mut() = None;
     }
 
     pub fn quit(&self) {
@@ -77,12 +252,35 @@ impl Application {
         }
     }
 
+    pub fn cancel_quit(&self) {
+        // Only macOS waits for an answer to its quit requests.
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard {
             selection: gdk::SELECTION_CLIPBOARD,
         }
     }
 
+    pub fn system_theme() -> SystemTheme {
+        // GTK has no accent colors. The portal has the desktop's preference; without one,
+        // themes are only dark by convention.
+        let dark = match portal::color_scheme() {
+            Some(ColorScheme::Dark) => true,
+            Some(ColorScheme::Light) => false,
+            _ => gtk::Settings::get_default().map_or(false, |settings| {
+                settings.get_property_gtk_application_prefer_dark_theme()
+                    || settings
+                        .get_property_gtk_theme_name()
+                        .map_or(false, |name| name.to_lowercase().ends_with("dark"))
+            }),
+        };
+        SystemTheme {
+            dark,
+            accent_color: None,
+        }
+    }
+
     pub fn get_locale() -> String {
         let mut locale: String = glib::get_language_names()[0].as_str().into();
         // This is done because the locale parsing library we use expects an unicode locale, but these vars have an ISO locale
@@ -91,6 +289,27 @@ impl Application {
         }
         locale
     }
+
+    pub fn show_message_dialog(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+        if !gtk::is_initialized_main_thread() && gtk::init().is_err() {
+            tracing::error!("gtk: can't show message dialog: {}", message);
+            return false;
+        }
+        let dialog = MessageDialog::new(
+            None::<&gtk::Window>,
+            DialogFlags::MODAL,
+            MessageType::Error,
+            ButtonsType::None,
+            message,
+        );
+        dialog.set_title(title);
+        dialog.add_button(cancel, ResponseType::Cancel);
+        dialog.add_button(confirm, ResponseType::Accept);
+        dialog.set_default_response(ResponseType::Accept);
+        let response = dialog.run();
+        dialog.close();
+        response == ResponseType::Accept
+    }
 }
 
 impl crate::platform::linux::ApplicationExt for crate::Application {
@@ -100,3 +319,116 @@ impl crate::platform::linux::ApplicationExt for crate::Application {
         })
     }
 }
+
+/// The modifier masks of Caps Lock and Num Lock.
+const LOCK_MASKS: [c_uint; 4] = [0, 2, 16, 2 | 16];
+
+/// The X11 modifier mask of a hotkey's modifiers.
+fn hotkey_mod_mask(mods: Modifiers) -> c_uint {
+    let mut mask = 0;
+    if mods.shift() {
+        mask |= 1;
+    }
+    if mods.ctrl() {
+        mask |= 4;
+    }
+    if mods.alt() {
+        mask |= 8;
+    }
+    if mods.meta() {
+        mask |= 64;
+    }
+    mask
+}
+
+/// The hardware keycode that types `key` without modifiers, in the first group.
+fn hotkey_keycode(key: &KbKey) -> Option<c_uint> {
+    let keyval = match key {
+        KbKey::Character(c) => {
+            let mut chars = c.chars().flat_map(char::to_lowercase);
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => unsafe { gdk_sys::gdk_unicode_to_keyval(c as u32) },
+                _ => return None,
+            }
+        }
+        key => keycodes::key_to_raw_key(key)?.to_glib(),
+    };
+
+    unsafe {
+        let keymap = gdk_sys::gdk_keymap_get_default();
+
+        let mut nkeys = 0;
+        let mut keys: *mut gdk_sys::GdkKeymapKey = ptr::null_mut();
+
+        gdk_sys::gdk_keymap_get_entries_for_keyval(
+            keymap,
+            keyval,
+            &mut keys as *mut *mut gdk_sys::GdkKeymapKey,
+            &mut nkeys as *mut c_int,
+        );
+
+        if nkeys > 0 {
+            let keys_slice = slice::from_raw_parts(keys, nkeys as usize);
+            let keycode = keys_slice
+                .iter()
+                .find(|key| key.group == 0 && key.level == 0)
+                .map(|key| key.keycode);
+
+            // notify glib to free the allocated array
+            glib_sys::g_free(keys as *mut c_void);
+
+            keycode
+        } else {
+            None
+        }
+    }
+}
+
+fn ungrab_hotkey(keycode: c_uint, mods: c_uint) {
+    if let Some((x11, _display, xdisplay)) = gdk_x11::default_display() {
+        for &locks in LOCK_MASKS.iter() {
+            unsafe {
+                let root = (x11.x_default_root_window)(xdisplay);
+                (x11.x_ungrab_key)(xdisplay, keycode as c_int, mods | locks, root);
+            }
+        }
+    }
+}
+
+/// Turns key presses on the root window, where grabbed keys are reported, into hotkey events.
+unsafe extern "C" fn hotkey_filter(
+    xevent: *mut gdk_sys::GdkXEvent,
+    _event: *mut gdk_sys::GdkEvent,
+    _data: glib_sys::gpointer,
+) -> gdk_sys::GdkFilterReturn {
+    let ev = &*(xevent as *const XKeyEvent);
+    if !(ev.type_ == gdk_x11::KEY_PRESS || ev.type_ == gdk_x11::KEY_RELEASE) || ev.window != ev.root
+    {
+        return gdk_sys::GDK_FILTER_CONTINUE;
+    }
+    let app = match crate::Application::try_global() {
+        Some(app) => app.backend_app,
+        None => return gdk_sys::GDK_FILTER_CONTINUE,
+    };
+
+    if ev.type_ == gdk_x11::KEY_RELEASE {
+        app.hotkey_down.set(None);
+        return gdk_sys::GDK_FILTER_CONTINUE;
+    }
+    let mods = ev.state & hotkey_mod_mask(Modifiers::all());
+    let token = app
+        .hotkeys
+        .borrow()
+        .iter()
+        .find(|(_, &hotkey)| hotkey == (ev.keycode, mods))
+        .map(|(&token, _)| token);
+    let token = match token {
+        Some(token) => token,
+        None => return gdk_sys::GDK_FILTER_CONTINUE,
+    };
+    // Holding the hotkey down repeats the press, but it only fires once.
+    if app.hotkey_down.replace(Some(ev.keycode)) != Some(ev.keycode) {
+        app.with_handler(|handler| handler.global_hotkey(token));
+    }
+    gdk_sys::GDK_FILTER_REMOVE
+}
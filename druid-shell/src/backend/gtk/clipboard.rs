@@ -14,10 +14,16 @@
 
 //! Interactions with the system pasteboard on GTK+.
 
+use std::path::PathBuf;
+
+use anyhow::anyhow;
 use gdk::Atom;
 use gtk::{TargetEntry, TargetFlags};
 
+use super::util::make_pixbuf;
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::piet::{ImageBuf, ImageFormat};
+use crate::Error;
 
 const CLIPBOARD_TARGETS: [&str; 5] = [
     "UTF8_STRING",
@@ -45,12 +51,16 @@ impl std::fmt::Debug for Clipboard {
 }
 
 impl Clipboard {
+    fn clipboard(&self) -> Result<gtk::Clipboard, Error> {
+        let display = gdk::Display::get_default().ok_or_else(|| anyhow!("no default display"))?;
+        Ok(gtk::Clipboard::get_for_display(&display, &self.selection))
+    }
+
     /// Put a string onto the system clipboard.
-    pub fn put_string(&mut self, string: impl AsRef<str>) {
+    pub fn put_string(&mut self, string: impl AsRef<str>) -> Result<(), Error> {
         let string = string.as_ref().to_string();
 
-        let display = gdk::Display::get_default().unwrap();
-        let clipboard = gtk::Clipboard::get_for_display(&display, &self.selection);
+        let clipboard = self.clipboard()?;
 
         let targets: Vec<TargetEntry> = CLIPBOARD_TARGETS
             .iter()
@@ -58,17 +68,21 @@ impl Clipboard {
             .map(|(i, target)| TargetEntry::new(target, TargetFlags::all(), i as u32))
             .collect();
 
-        clipboard.set_with_data(&targets, move |_, selection, _| {
+        let success = clipboard.set_with_data(&targets, move |_, selection, _| {
             const STRIDE_BITS: i32 = 8;
             selection.set(&selection.get_target(), STRIDE_BITS, string.as_bytes());
         });
+        if success {
+            Ok(())
+        } else {
+            Err(anyhow!("failed to set clipboard data").into())
+        }
     }
 
     /// Put multi-format data on the system clipboard.
-    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) {
+    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) -> Result<(), Error> {
         let entries = make_entries(formats);
-        let display = gdk::Display::get_default().unwrap();
-        let clipboard = gtk::Clipboard::get_for_display(&display, &self.selection);
+        let clipboard = self.clipboard()?;
         // this is gross: we need to reclone all the data in formats in order
         // to move it into the closure. :/
         let formats = formats.to_owned();
@@ -88,15 +102,80 @@ impl Clipboard {
                 }
             }
         });
-        if !success {
-            tracing::warn!("failed to set clipboard data.");
+        if success {
+            Ok(())
+        } else {
+            Err(anyhow!("failed to set clipboard data").into())
+        }
+    }
+
+    /// Put an image onto the system clipboard.
+    pub fn put_image(&mut self, image: &ImageBuf) -> Result<(), Error> {
+        self.clipboard()?.set_image(&make_pixbuf(image));
+        Ok(())
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        let pixbuf = self.clipboard().ok()?.wait_for_image()?;
+        let width = pixbuf.get_width() as usize;
+        let height = pixbuf.get_height() as usize;
+        let rowstride = pixbuf.get_rowstride() as usize;
+        let (format, bytes_per_pixel) = if pixbuf.get_has_alpha() {
+            (ImageFormat::RgbaSeparate, 4)
+        } else {
+            (ImageFormat::Rgb, 3)
+        };
+        let bytes = pixbuf.read_pixel_bytes()?;
+        // Rows may be padded.
+        let pixels = bytes
+            .chunks(rowstride)
+            .take(height)
+            .flat_map(|row| &row[..width * bytes_per_pixel])
+            .copied()
+            .collect::<Vec<u8>>();
+        Some(ImageBuf::from_raw(pixels, format, width, height))
+    }
+
+    /// Put a list of files onto the system clipboard.
+    pub fn put_files(&mut self, paths: &[PathBuf]) -> Result<(), Error> {
+        let uris = paths
+            .iter()
+            .map(|path| glib::filename_to_uri(path, None).map(String::from))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("invalid file path: {}", e))?;
+        let targets = [TargetEntry::new("text/uri-list", TargetFlags::all(), 0)];
+        let success = self
+            .clipboard()?
+            .set_with_data(&targets, move |_, selection, _| {
+                let uris = uris.iter().map(String::as_str).collect::<Vec<_>>();
+                selection.set_uris(&uris);
+            });
+        if success {
+            Ok(())
+        } else {
+            Err(anyhow!("failed to set clipboard data").into())
+        }
+    }
+
+    /// Get the list of files on the system clipboard, if there is one.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        let uris = self.clipboard().ok()?.wait_for_uris();
+        let files = uris
+            .iter()
+            .filter_map(|uri| glib::filename_from_uri(uri).ok())
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            None
+        } else {
+            Some(files)
         }
     }
 
     /// Get a string from the system clipboard, if one is available.
     pub fn get_string(&self) -> Option<String> {
-        let display = gdk::Display::get_default().unwrap();
-        let clipboard = gtk::Clipboard::get_for_display(&display, &self.selection);
+        let clipboard = self.clipboard().ok()?;
 
         for target in &CLIPBOARD_TARGETS {
             let atom = Atom::intern(target);
@@ -111,8 +190,7 @@ impl Clipboard {
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
-        let display = gdk::Display::get_default().unwrap();
-        let clipboard = gtk::Clipboard::get_for_display(&display, &self.selection);
+        let clipboard = self.clipboard().ok()?;
         let targets = clipboard.wait_for_targets()?;
         let format_atoms = formats
             .iter()
@@ -131,8 +209,7 @@ impl Clipboard {
     /// It is recommended that the `fmt` argument be a format returned by
     /// [`Clipboard::preferred_format`]
     pub fn get_format(&self, format: FormatId) -> Option<Vec<u8>> {
-        let display = gdk::Display::get_default().unwrap();
-        let clipboard = gtk::Clipboard::get_for_display(&display, &self.selection);
+        let clipboard = self.clipboard().ok()?;
         let atom = Atom::intern(format);
         clipboard
             .wait_for_contents(&atom)
@@ -140,8 +217,10 @@ impl Clipboard {
     }
 
     pub fn available_type_names(&self) -> Vec<String> {
-        let display = gdk::Display::get_default().unwrap();
-        let clipboard = gtk::Clipboard::get_for_display(&display, &self.selection);
+        let clipboard = match self.clipboard() {
+            Ok(clipboard) => clipboard,
+            Err(_) => return Vec::new(),
+        };
         let targets = clipboard.wait_for_targets().unwrap_or_default();
         targets
             .iter()
@@ -14,12 +14,11 @@
 
 //! File open/save dialogs, GTK implementation.
 
-use std::ffi::OsString;
-
 use anyhow::anyhow;
 use gtk::{FileChooserAction, FileChooserExt, FileFilter, NativeDialogExt, ResponseType, Window};
 
-use crate::dialog::{FileDialogOptions, FileDialogType, FileSpec};
+use super::{portal, util};
+use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo, FileSpec};
 use crate::Error;
 
 fn file_filter(fs: &FileSpec) -> FileFilter {
@@ -31,11 +30,12 @@ fn file_filter(fs: &FileSpec) -> FileFilter {
     ret
 }
 
+/// Show a file dialog, returning the chosen path or `None` if it was cancelled.
 pub(crate) fn get_file_dialog_path(
     window: &Window,
     ty: FileDialogType,
     options: FileDialogOptions,
-) -> Result<OsString, Error> {
+) -> Result<Option<FileInfo>, Error> {
     // TODO: support message localization
 
     let (title, action) = match (ty, options.select_directories) {
@@ -45,8 +45,19 @@ pub(crate) fn get_file_dialog_path(
     };
     let title = options.title.as_deref().unwrap_or(title);
 
+    // GTK only shows its dialogs through the portal inside Flatpak, so we use the portal
+    // ourselves to also get it in Snap packages.
+    if util::is_sandboxed() {
+        if let Some(info) = portal::file_dialog(window, ty, title, &options) {
+            return Ok(info);
+        }
+    }
+
+    // The dialog has to be modal and transient for the window, so that it is
+    // stacked above it, both as a GTK dialog and when it is shown by a portal.
     let mut dialog = gtk::FileChooserNativeBuilder::new()
         .transient_for(window)
+        .modal(true)
         .title(title);
     if let Some(button_text) = &options.button_text {
         dialog = dialog.accept_label(button_text);
@@ -93,22 +104,39 @@ pub(crate) fn get_file_dialog_path(
         dialog.set_current_name(default_name);
     }
 
+    let starting_directory = options
+        .starting_directory
+        .clone()
+        .or_else(|| options.remembered_directory());
+    if let Some(path) = starting_directory {
+        dialog.set_current_folder(path);
+    }
+
     let result = dialog.run();
 
     let result = match result {
         ResponseType::Accept => match dialog.get_filename() {
-            Some(path) => Ok(path.into_os_string()),
+            Some(path) => {
+                // Filters are matched by name, as they were made from the allowed types.
+                let filter_name = dialog.get_filter().and_then(|filter| filter.get_name());
+                let format = options.allowed_types.as_ref().and_then(|types| {
+                    let name = filter_name?;
+                    types
+                        .iter()
+                        .find(|spec| spec.name == name.as_str())
+                        .copied()
+                });
+                Ok(Some(FileInfo::chosen(path, format, &options)))
+            }
             None => Err(anyhow!("No path received for filename")),
         },
-        ResponseType::Cancel => Err(anyhow!("Dialog was deleted")),
+        ResponseType::Cancel | ResponseType::DeleteEvent => Ok(None),
         _ => {
             tracing::warn!("Unhandled dialog result: {:?}", result);
             Err(anyhow!("Unhandled dialog result"))
         }
     };
 
-    // TODO properly handle errors into the Error type
-
     dialog.destroy();
 
     Ok(result?)
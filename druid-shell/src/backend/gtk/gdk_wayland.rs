@@ -0,0 +1,58 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions of GDK's Wayland backend.
+//!
+//! Like the X11 ones in [`gdk_x11`](super::gdk_x11), these are looked up at runtime because
+//! `gdk-sys` doesn't bind them and GDK may be built without Wayland support.
+
+use std::os::raw::c_void;
+
+use glib::ObjectExt;
+use lazy_static::lazy_static;
+
+use crate::backend::shared::lookup_symbol;
+
+pub(crate) struct WaylandFunctions {
+    pub gdk_wayland_window_get_wl_surface:
+        unsafe extern "C" fn(*mut gdk_sys::GdkWindow) -> *mut c_void,
+    pub gdk_wayland_display_get_wl_display:
+        unsafe extern "C" fn(*mut gdk_sys::GdkDisplay) -> *mut c_void,
+}
+
+lazy_static! {
+    static ref FUNCTIONS: Option<WaylandFunctions> = unsafe { load() };
+}
+
+/// The Wayland functions, if `display` is a Wayland display.
+pub(crate) fn functions(display: &gdk::Display) -> Option<&'static WaylandFunctions> {
+    if display.get_type().name() == "GdkWaylandDisplay" {
+        FUNCTIONS.as_ref()
+    } else {
+        None
+    }
+}
+
+unsafe fn load() -> Option<WaylandFunctions> {
+    macro_rules! load {
+        ($name:literal) => {{
+            std::mem::transmute(lookup_symbol(concat!($name, "\0"))?)
+        }};
+    }
+
+    Some(WaylandFunctions {
+        gdk_wayland_window_get_wl_surface: load!("gdk_wayland_window_get_wl_surface"),
+        gdk_wayland_display_get_wl_display: load!("gdk_wayland_display_get_wl_display"),
+    })
+}
@@ -0,0 +1,111 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions of Xlib and of GDK's X11 backend.
+//!
+//! `gdk-sys` doesn't bind GDK's X11 backend, and GDK may be built without it, so instead of
+//! linking these functions we look them up in the libraries that GDK loaded. They are only there
+//! when GDK runs on X11.
+
+use std::os::raw::{c_int, c_uint, c_ulong, c_void};
+
+use glib::translate::ToGlibPtr;
+use glib::ObjectExt;
+use lazy_static::lazy_static;
+
+use crate::backend::shared::lookup_symbol;
+
+/// An Xlib `Display`.
+pub(crate) type XDisplay = c_void;
+
+/// Xlib's `KeyPress` event type.
+pub(crate) const KEY_PRESS: c_int = 2;
+/// Xlib's `KeyRelease` event type.
+pub(crate) const KEY_RELEASE: c_int = 3;
+/// Xlib's `BadAccess` error code.
+pub(crate) const BAD_ACCESS: c_int = 10;
+/// Xlib's `GrabModeAsync`.
+pub(crate) const GRAB_MODE_ASYNC: c_int = 1;
+
+/// Xlib's `XKeyEvent`.
+#[repr(C)]
+pub(crate) struct XKeyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: c_int,
+    pub display: *mut XDisplay,
+    pub window: c_ulong,
+    pub root: c_ulong,
+    pub subwindow: c_ulong,
+    pub time: c_ulong,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub keycode: c_uint,
+    pub same_screen: c_int,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) struct X11Functions {
+    pub gdk_x11_display_get_xdisplay:
+        unsafe extern "C" fn(*mut gdk_sys::GdkDisplay) -> *mut XDisplay,
+    pub gdk_x11_window_get_xid: unsafe extern "C" fn(*mut gdk_sys::GdkWindow) -> c_ulong,
+    pub gdk_x11_display_error_trap_push: unsafe extern "C" fn(*mut gdk_sys::GdkDisplay),
+    pub gdk_x11_display_error_trap_pop: unsafe extern "C" fn(*mut gdk_sys::GdkDisplay) -> c_int,
+    pub x_default_root_window: unsafe extern "C" fn(*mut XDisplay) -> c_ulong,
+    pub x_grab_key:
+        unsafe extern "C" fn(*mut XDisplay, c_int, c_uint, c_ulong, c_int, c_int, c_int) -> c_int,
+    pub x_ungrab_key: unsafe extern "C" fn(*mut XDisplay, c_int, c_uint, c_ulong) -> c_int,
+}
+
+lazy_static! {
+    static ref FUNCTIONS: Option<X11Functions> = unsafe { load() };
+}
+
+/// The X11 functions, if `display` is an X11 display.
+pub(crate) fn functions(display: &gdk::Display) -> Option<&'static X11Functions> {
+    if display.get_type().name() == "GdkX11Display" {
+        FUNCTIONS.as_ref()
+    } else {
+        None
+    }
+}
+
+/// The X11 functions and the Xlib `Display` of the default display, if GDK uses X11.
+pub(crate) fn default_display() -> Option<(&'static X11Functions, gdk::Display, *mut XDisplay)> {
+    let display = gdk::Display::get_default()?;
+    let x11 = functions(&display)?;
+    let xdisplay = unsafe { (x11.gdk_x11_display_get_xdisplay)(display.to_glib_none().0) };
+    Some((x11, display, xdisplay))
+}
+
+unsafe fn load() -> Option<X11Functions> {
+    macro_rules! load {
+        ($name:literal) => {{
+            std::mem::transmute(lookup_symbol(concat!($name, "\0"))?)
+        }};
+    }
+
+    Some(X11Functions {
+        gdk_x11_display_get_xdisplay: load!("gdk_x11_display_get_xdisplay"),
+        gdk_x11_window_get_xid: load!("gdk_x11_window_get_xid"),
+        gdk_x11_display_error_trap_push: load!("gdk_x11_display_error_trap_push"),
+        gdk_x11_display_error_trap_pop: load!("gdk_x11_display_error_trap_pop"),
+        x_default_root_window: load!("XDefaultRootWindow"),
+        x_grab_key: load!("XGrabKey"),
+        x_ungrab_key: load!("XUngrabKey"),
+    })
+}
@@ -14,6 +14,8 @@
 
 //! GTK implementation of menus.
 
+use std::rc::Rc;
+
 use gdk::ModifierType;
 use gtk::{
     AccelGroup, GtkMenuExt, GtkMenuItemExt, Menu as GtkMenu, MenuBar as GtkMenuBar,
@@ -82,8 +84,8 @@ impl Menu {
     fn append_items_to_menu<M: gtk::prelude::IsA<gtk::MenuShell>>(
         self,
         menu: &mut M,
-        handle: &WindowHandle,
         accel_group: &AccelGroup,
+        activate: &Rc<dyn Fn(u32)>,
     ) {
         for item in self.items {
             match item {
@@ -100,18 +102,14 @@ impl Menu {
                         register_accelerator(&item, accel_group, k);
                     }
 
-                    let handle = handle.clone();
-                    item.connect_activate(move |_| {
-                        if let Some(state) = handle.state.upgrade() {
-                            state.handler.borrow_mut().command(id);
-                        }
-                    });
+                    let activate = activate.clone();
+                    item.connect_activate(move |_| activate(id));
 
                     menu.append(&item);
                 }
                 MenuItem::SubMenu(name, submenu) => {
                     let item = GtkMenuItem::with_label(&name);
-                    item.set_submenu(Some(&submenu.into_gtk_menu(handle, accel_group)));
+                    item.set_submenu(Some(&submenu.into_gtk_menu_with(accel_group, activate)));
 
                     menu.append(&item);
                 }
@@ -127,21 +125,98 @@ impl Menu {
     ) -> GtkMenuBar {
         let mut menu = GtkMenuBar::new();
 
-        self.append_items_to_menu(&mut menu, handle, accel_group);
+        self.append_items_to_menu(&mut menu, accel_group, &window_command(handle));
 
         menu
     }
 
     pub fn into_gtk_menu(self, handle: &WindowHandle, accel_group: &AccelGroup) -> GtkMenu {
+        self.into_gtk_menu_with(accel_group, &window_command(handle))
+    }
+
+    /// Build a `GtkMenu` whose items call `activate` with their id.
+    pub(crate) fn into_gtk_menu_with(
+        self,
+        accel_group: &AccelGroup,
+        activate: &Rc<dyn Fn(u32)>,
+    ) -> GtkMenu {
         let mut menu = GtkMenu::new();
         menu.set_accel_group(Some(accel_group));
 
-        self.append_items_to_menu(&mut menu, handle, accel_group);
+        self.append_items_to_menu(&mut menu, accel_group, activate);
 
         menu
     }
 }
 
+/// A menu item flattened for exporting over D-Bus; see [`Menu::into_nodes`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct MenuNode {
+    pub label: String,
+    pub enabled: bool,
+    pub separator: bool,
+    /// The id of the command sent when the item is clicked.
+    pub command: Option<u32>,
+    /// The indices of the item's children.
+    pub children: Vec<i32>,
+}
+
+impl MenuNode {
+    fn new(label: String) -> MenuNode {
+        MenuNode {
+            label,
+            enabled: true,
+            separator: false,
+            command: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Menu {
+    /// Flatten the menu into a list of nodes that refer to their children by index. The first
+    /// node is the root.
+    pub(crate) fn into_nodes(self) -> Vec<MenuNode> {
+        let mut nodes = vec![MenuNode::new(String::new())];
+        self.flatten_into(0, &mut nodes);
+        nodes
+    }
+
+    fn flatten_into(self, parent: usize, nodes: &mut Vec<MenuNode>) {
+        for item in self.items {
+            let index = nodes.len();
+            nodes[parent].children.push(index as i32);
+            match item {
+                MenuItem::Entry {
+                    name, id, enabled, ..
+                } => nodes.push(MenuNode {
+                    enabled,
+                    command: Some(id),
+                    ..MenuNode::new(name)
+                }),
+                MenuItem::SubMenu(name, submenu) => {
+                    nodes.push(MenuNode::new(name));
+                    submenu.flatten_into(index, nodes);
+                }
+                MenuItem::Separator => nodes.push(MenuNode {
+                    separator: true,
+                    ..MenuNode::new(String::new())
+                }),
+            }
+        }
+    }
+}
+
+/// Sends the ids of activated menu items to the handler of a window.
+fn window_command(handle: &WindowHandle) -> Rc<dyn Fn(u32)> {
+    let handle = handle.clone();
+    Rc::new(move |id| {
+        if let Some(state) = handle.state.upgrade() {
+            state.handler.borrow_mut().command(id);
+        }
+    })
+}
+
 fn register_accelerator(item: &GtkMenuItem, accel_group: &AccelGroup, menu_key: HotKey) {
     let gdk_keyval = match &menu_key.key {
         KbKey::Character(text) => text.chars().next().unwrap() as u32,
@@ -176,3 +251,30 @@ fn modifiers_to_gdk_modifier_type(raw_modifiers: RawMods) -> gdk::ModifierType {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn nodes_refer_to_their_children() {
+        let mut submenu = Menu::new();
+        submenu.add_item(2, "Nested", None, false, false);
+        let mut menu = Menu::new();
+        menu.add_item(1, "&Open", None, true, false);
+        menu.add_separator();
+        menu.add_dropdown(submenu, "More", true);
+
+        let nodes = menu.into_nodes();
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes[0].children, vec![1, 2, 3]);
+        assert_eq!(nodes[1].label, "Open");
+        assert_eq!(nodes[1].command, Some(1));
+        assert!(nodes[2].separator);
+        assert_eq!(nodes[3].children, vec![4]);
+        assert_eq!(nodes[3].command, None);
+        assert_eq!(nodes[4].command, Some(2));
+        assert!(!nodes[4].enabled);
+    }
+}
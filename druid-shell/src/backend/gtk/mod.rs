@@ -18,8 +18,14 @@ pub mod application;
 pub mod clipboard;
 pub mod dialog;
 pub mod error;
+#[cfg(feature = "raw-win-handle")]
+mod gdk_wayland;
+mod gdk_x11;
 pub mod keycodes;
 pub mod menu;
+mod portal;
 pub mod screen;
+mod status_notifier;
+pub mod tray;
 pub mod util;
 pub mod window;
@@ -0,0 +1,512 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Settings and file dialogs of the XDG desktop portal.
+//!
+//! Desktops publish their appearance settings through the portal, whether or not we're
+//! sandboxed. Sandboxed applications show their file dialogs through it.
+//!
+//! https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Settings
+//! https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.FileChooser
+
+use std::cell::Cell;
+use std::ffi::{CStr, OsStr};
+use std::os::raw::{c_char, c_uint};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use gdk::WindowExt;
+use gio_sys::GDBusConnection;
+use glib::gobject_sys;
+use glib::translate::ToGlibPtr;
+use glib_sys::{gpointer, GMainLoop, GVariant, GFALSE};
+use gtk::WidgetExt;
+
+use super::gdk_x11;
+use super::status_notifier::{cstring, take_error, variant};
+use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo, FileSpec};
+
+const PORTAL_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const FILE_CHOOSER_INTERFACE: &str = "org.freedesktop.portal.FileChooser";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+/// The response code of a request that the user completed.
+const RESPONSE_SUCCESS: u32 = 0;
+/// The response code of a request that the user cancelled.
+const RESPONSE_CANCELLED: u32 = 1;
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+
+/// The color scheme that the user prefers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ColorScheme {
+    NoPreference,
+    Dark,
+    Light,
+}
+
+impl ColorScheme {
+    fn from_setting(value: u32) -> ColorScheme {
+        match value {
+            1 => ColorScheme::Dark,
+            2 => ColorScheme::Light,
+            _ => ColorScheme::NoPreference,
+        }
+    }
+}
+
+/// The `color-scheme` appearance setting, if there is a portal that has it.
+pub(crate) fn color_scheme() -> Option<ColorScheme> {
+    unsafe {
+        let connection = session_bus()?;
+        let params = glib_sys::g_variant_new_tuple(
+            [
+                variant::string(APPEARANCE_NAMESPACE),
+                variant::string(COLOR_SCHEME_KEY),
+            ]
+            .as_ptr(),
+            2,
+        );
+        let mut error = ptr::null_mut();
+        let reply = gio_sys::g_dbus_connection_call_sync(
+            connection,
+            cstring(PORTAL_NAME).as_ptr(),
+            cstring(PORTAL_PATH).as_ptr(),
+            cstring(SETTINGS_INTERFACE).as_ptr(),
+            cstring("Read").as_ptr(),
+            params,
+            ptr::null(),
+            gio_sys::G_DBUS_CALL_FLAGS_NO_AUTO_START,
+            1000,
+            ptr::null_mut(),
+            &mut error,
+        );
+        gobject_sys::g_object_unref(connection as *mut _);
+        if reply.is_null() {
+            tracing::debug!("no color-scheme from the portal: {}", take_error(error));
+            return None;
+        }
+        let value = glib_sys::g_variant_get_child_value(reply, 0);
+        glib_sys::g_variant_unref(reply);
+        let scheme = uint32(value).map(ColorScheme::from_setting);
+        glib_sys::g_variant_unref(value);
+        scheme
+    }
+}
+
+/// Calls a function whenever the `color-scheme` setting changes, until it is dropped.
+pub(crate) struct ColorSchemeWatch {
+    connection: *mut GDBusConnection,
+    subscription: c_uint,
+}
+
+type Callback = Box<dyn Fn(ColorScheme)>;
+
+impl ColorSchemeWatch {
+    /// Watch the `color-scheme` setting, calling `f` on the main thread with the new value.
+    pub(crate) fn new(f: impl Fn(ColorScheme) + 'static) -> Option<ColorSchemeWatch> {
+        let callback: Box<Callback> = Box::new(Box::new(f));
+        unsafe {
+            let connection = session_bus()?;
+            // The signal is emitted in the main context of this thread.
+            let subscription = gio_sys::g_dbus_connection_signal_subscribe(
+                connection,
+                cstring(PORTAL_NAME).as_ptr(),
+                cstring(SETTINGS_INTERFACE).as_ptr(),
+                cstring("SettingChanged").as_ptr(),
+                cstring(PORTAL_PATH).as_ptr(),
+                cstring(APPEARANCE_NAMESPACE).as_ptr(),
+                gio_sys::G_DBUS_SIGNAL_FLAGS_NONE,
+                Some(setting_changed),
+                Box::into_raw(callback) as gpointer,
+                Some(free_callback),
+            );
+            Some(ColorSchemeWatch {
+                connection,
+                subscription,
+            })
+        }
+    }
+}
+
+impl Drop for ColorSchemeWatch {
+    fn drop(&mut self) {
+        unsafe {
+            gio_sys::g_dbus_connection_signal_unsubscribe(self.connection, self.subscription);
+            gobject_sys::g_object_unref(self.connection as *mut _);
+        }
+    }
+}
+
+unsafe extern "C" fn setting_changed(
+    _connection: *mut GDBusConnection,
+    _sender: *const c_char,
+    _path: *const c_char,
+    _interface: *const c_char,
+    _signal: *const c_char,
+    params: *mut GVariant,
+    user_data: gpointer,
+) {
+    // (namespace, key, value); the namespace was matched by the subscription.
+    let key = glib_sys::g_variant_get_child_value(params, 1);
+    let is_color_scheme = CStr::from_ptr(glib_sys::g_variant_get_string(key, ptr::null_mut()))
+        .to_bytes()
+        == COLOR_SCHEME_KEY.as_bytes();
+    glib_sys::g_variant_unref(key);
+    if !is_color_scheme {
+        return;
+    }
+    let value = glib_sys::g_variant_get_child_value(params, 2);
+    let scheme = uint32(value).map(ColorScheme::from_setting);
+    glib_sys::g_variant_unref(value);
+    if let Some(scheme) = scheme {
+        let callback = &*(user_data as *const Callback);
+        callback(scheme);
+    }
+}
+
+unsafe extern "C" fn free_callback(user_data: gpointer) {
+    drop(Box::from_raw(user_data as *mut Callback));
+}
+
+/// A new reference to the session bus.
+unsafe fn session_bus() -> Option<*mut GDBusConnection> {
+    let mut error = ptr::null_mut();
+    let connection =
+        gio_sys::g_bus_get_sync(gio_sys::G_BUS_TYPE_SESSION, ptr::null_mut(), &mut error);
+    if connection.is_null() {
+        tracing::debug!(
+            "failed to connect to the session bus: {}",
+            take_error(error)
+        );
+        None
+    } else {
+        Some(connection)
+    }
+}
+
+/// The `u` in `value`, looking through the variants that the portal boxes it in.
+unsafe fn uint32(value: *mut GVariant) -> Option<u32> {
+    let ty = CStr::from_ptr(glib_sys::g_variant_get_type_string(value)).to_bytes();
+    match ty {
+        b"u" => Some(glib_sys::g_variant_get_uint32(value)),
+        b"v" => {
+            let inner = glib_sys::g_variant_get_variant(value);
+            let result = uint32(inner);
+            glib_sys::g_variant_unref(inner);
+            result
+        }
+        _ => None,
+    }
+}
+
+/// Makes the paths of requests unique.
+static NEXT_REQUEST: AtomicU32 = AtomicU32::new(0);
+
+/// Show a file dialog through the `FileChooser` portal, and return the chosen file.
+///
+/// Like a GTK dialog, this runs the main loop until the dialog is closed. Returns `None` if the
+/// portal couldn't show the dialog, so that the caller can show one of its own.
+pub(crate) fn file_dialog(
+    parent: &gtk::Window,
+    ty: FileDialogType,
+    title: &str,
+    options: &FileDialogOptions,
+) -> Option<Option<FileInfo>> {
+    unsafe {
+        let connection = session_bus()?;
+        let result = request_file(connection, parent, ty, title, options);
+        gobject_sys::g_object_unref(connection as *mut _);
+        result
+    }
+}
+
+/// The state of a request, shared with the handler of its `Response` signal.
+struct Request {
+    main_loop: *mut GMainLoop,
+    /// The parameters of the response, once it came.
+    response: Cell<*mut GVariant>,
+}
+
+unsafe fn request_file(
+    connection: *mut GDBusConnection,
+    parent: &gtk::Window,
+    ty: FileDialogType,
+    title: &str,
+    options: &FileDialogOptions,
+) -> Option<Option<FileInfo>> {
+    // The portal answers with a signal from a request object, whose path is made of our name on
+    // the bus and a token we choose. We subscribe before making the call, so that we can't miss
+    // the answer.
+    let sender = CStr::from_ptr(gio_sys::g_dbus_connection_get_unique_name(connection))
+        .to_string_lossy()
+        .trim_start_matches(':')
+        .replace('.', "_");
+    let token = format!("druid{}", NEXT_REQUEST.fetch_add(1, Ordering::Relaxed));
+    let handle = format!("{}/request/{}/{}", PORTAL_PATH, sender, token);
+
+    let request = Request {
+        main_loop: glib_sys::g_main_loop_new(ptr::null_mut(), GFALSE),
+        response: Cell::new(ptr::null_mut()),
+    };
+    let subscription = gio_sys::g_dbus_connection_signal_subscribe(
+        connection,
+        cstring(PORTAL_NAME).as_ptr(),
+        cstring(REQUEST_INTERFACE).as_ptr(),
+        cstring("Response").as_ptr(),
+        cstring(&handle).as_ptr(),
+        ptr::null(),
+        gio_sys::G_DBUS_SIGNAL_FLAGS_NONE,
+        Some(request_response),
+        &request as *const Request as gpointer,
+        None,
+    );
+
+    let method = match ty {
+        FileDialogType::Open => "OpenFile",
+        FileDialogType::Save => "SaveFile",
+    };
+    let params = variant::tuple(vec![
+        variant::string(&parent_window(parent)),
+        variant::string(title),
+        dialog_options(ty, options, &token),
+    ]);
+    let mut error = ptr::null_mut();
+    let reply = gio_sys::g_dbus_connection_call_sync(
+        connection,
+        cstring(PORTAL_NAME).as_ptr(),
+        cstring(PORTAL_PATH).as_ptr(),
+        cstring(FILE_CHOOSER_INTERFACE).as_ptr(),
+        cstring(method).as_ptr(),
+        params,
+        ptr::null(),
+        gio_sys::G_DBUS_CALL_FLAGS_NONE,
+        -1,
+        ptr::null_mut(),
+        &mut error,
+    );
+    if reply.is_null() {
+        tracing::warn!("the file chooser portal failed: {}", take_error(error));
+    } else {
+        glib_sys::g_variant_unref(reply);
+        // The signal is delivered by this thread's main context, which this runs.
+        glib_sys::g_main_loop_run(request.main_loop);
+    }
+    gio_sys::g_dbus_connection_signal_unsubscribe(connection, subscription);
+    glib_sys::g_main_loop_unref(request.main_loop);
+
+    let response = request.response.get();
+    if response.is_null() {
+        return None;
+    }
+    let info = chosen_file(response, options);
+    glib_sys::g_variant_unref(response);
+    Some(info)
+}
+
+unsafe extern "C" fn request_response(
+    _connection: *mut GDBusConnection,
+    _sender: *const c_char,
+    _path: *const c_char,
+    _interface: *const c_char,
+    _signal: *const c_char,
+    params: *mut GVariant,
+    user_data: gpointer,
+) {
+    let request = &*(user_data as *const Request);
+    if request.response.get().is_null() {
+        request.response.set(glib_sys::g_variant_ref(params));
+        glib_sys::g_main_loop_quit(request.main_loop);
+    }
+}
+
+/// The identifier of a window for the portal, so that it can put the dialog above the window.
+fn parent_window(window: &gtk::Window) -> String {
+    // On Wayland the window has to be exported first, which is asynchronous; the dialog is shown
+    // without a parent there.
+    let gdk_window = match window.get_window() {
+        Some(gdk_window) => gdk_window,
+        None => return String::new(),
+    };
+    match gdk_x11::functions(&gdk_window.get_display()) {
+        Some(x11) => {
+            let xid = unsafe { (x11.gdk_x11_window_get_xid)(gdk_window.to_glib_none().0) };
+            format!("x11:{:x}", xid)
+        }
+        None => String::new(),
+    }
+}
+
+/// The `a{sv}` options of an `OpenFile` or `SaveFile` call.
+fn dialog_options(ty: FileDialogType, options: &FileDialogOptions, token: &str) -> *mut GVariant {
+    let entry = |key: &str, value| variant::dict_entry(variant::string(key), variant::boxed(value));
+    let mut entries = vec![
+        entry("handle_token", variant::string(token)),
+        entry("modal", variant::boolean(true)),
+    ];
+    if let Some(label) = &options.button_text {
+        entries.push(entry("accept_label", variant::string(label)));
+    }
+    if ty == FileDialogType::Open {
+        entries.push(entry("multiple", variant::boolean(options.multi_selection)));
+        entries.push(entry(
+            "directory",
+            variant::boolean(options.select_directories),
+        ));
+    }
+    // As with the GTK dialog, filters would hide the folders to choose from.
+    if let (Some(types), false) = (&options.allowed_types, options.select_directories) {
+        let filters = types.iter().map(file_filter).collect();
+        entries.push(entry("filters", variant::array("(sa(us))", filters)));
+        if let Some(default) = options.default_type.filter(|d| types.contains(d)) {
+            entries.push(entry("current_filter", file_filter(&default)));
+        }
+    }
+    if let (Some(name), FileDialogType::Save) = (&options.default_name, ty) {
+        entries.push(entry("current_name", variant::string(name)));
+    }
+    let folder = options
+        .starting_directory
+        .clone()
+        .or_else(|| options.remembered_directory());
+    if let Some(folder) = folder {
+        // A NUL terminated byte string.
+        let mut bytes = folder.into_os_string().into_vec();
+        bytes.push(0);
+        entries.push(entry("current_folder", variant::bytes(&bytes)));
+    }
+    variant::array("{sv}", entries)
+}
+
+/// A `(sa(us))` filter, matching the extensions of `spec` with glob patterns.
+fn file_filter(spec: &FileSpec) -> *mut GVariant {
+    const GLOB_PATTERN: u32 = 0;
+    let patterns = spec
+        .extensions
+        .iter()
+        .map(|ext| {
+            variant::tuple(vec![
+                variant::uint32(GLOB_PATTERN),
+                variant::string(&format!("*.{}", ext)),
+            ])
+        })
+        .collect();
+    variant::tuple(vec![
+        variant::string(spec.name),
+        variant::array("(us)", patterns),
+    ])
+}
+
+/// The file chosen in the `(ua{sv})` parameters of a `Response` signal.
+unsafe fn chosen_file(response: *mut GVariant, options: &FileDialogOptions) -> Option<FileInfo> {
+    let code = glib_sys::g_variant_get_child_value(response, 0);
+    let result = uint32(code);
+    glib_sys::g_variant_unref(code);
+    match result {
+        Some(RESPONSE_SUCCESS) => (),
+        Some(RESPONSE_CANCELLED) => return None,
+        _ => {
+            tracing::warn!("the file chooser portal failed with code {:?}", result);
+            return None;
+        }
+    }
+    let results = glib_sys::g_variant_get_child_value(response, 1);
+    let uris = glib_sys::g_variant_lookup_value(results, cstring("uris").as_ptr(), ptr::null());
+    let filter =
+        glib_sys::g_variant_lookup_value(results, cstring("current_filter").as_ptr(), ptr::null());
+    glib_sys::g_variant_unref(results);
+
+    // Only the first file is returned, as with the GTK dialog.
+    let path = if !uris.is_null() && glib_sys::g_variant_n_children(uris) > 0 {
+        file_path(&variant::child_string(uris, 0))
+    } else {
+        None
+    };
+    // Filters are matched by name, as they were made from the allowed types.
+    let format = if filter.is_null() {
+        None
+    } else {
+        let name = variant::child_string(filter, 0);
+        options
+            .allowed_types
+            .as_ref()
+            .and_then(|types| types.iter().find(|spec| spec.name == name).copied())
+    };
+    for &value in [uris, filter].iter() {
+        if !value.is_null() {
+            glib_sys::g_variant_unref(value);
+        }
+    }
+    Some(FileInfo::chosen(path?, format, options))
+}
+
+/// The path of a `file://` URI.
+unsafe fn file_path(uri: &str) -> Option<PathBuf> {
+    let mut error = ptr::null_mut();
+    let path = glib_sys::g_filename_from_uri(cstring(uri).as_ptr(), ptr::null_mut(), &mut error);
+    if path.is_null() {
+        tracing::warn!(
+            "the file chooser portal returned {}: {}",
+            uri,
+            take_error(error)
+        );
+        return None;
+    }
+    let result = PathBuf::from(OsStr::from_bytes(CStr::from_ptr(path).to_bytes()));
+    glib_sys::g_free(path as gpointer);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    fn print(value: *mut GVariant) -> String {
+        unsafe {
+            let value = glib_sys::g_variant_ref_sink(value);
+            let text = glib_sys::g_variant_print(value, GFALSE);
+            let result = CStr::from_ptr(text).to_string_lossy().into_owned();
+            glib_sys::g_free(text as gpointer);
+            glib_sys::g_variant_unref(value);
+            result
+        }
+    }
+
+    #[test]
+    fn file_filters() {
+        assert_eq!(
+            print(file_filter(&FileSpec::HTML)),
+            "('Web Page', [(0, '*.htm'), (0, '*.html')])"
+        );
+    }
+
+    #[test]
+    fn save_options() {
+        let options = FileDialogOptions::new()
+            .allowed_types(vec![FileSpec::TEXT])
+            .default_type(FileSpec::TEXT)
+            .default_name("notes.txt");
+        assert_eq!(
+            print(dialog_options(FileDialogType::Save, &options, "druid0")),
+            "{'handle_token': <'druid0'>, 'modal': <true>, \
+             'filters': <[('Text', [(0, '*.txt')])]>, \
+             'current_filter': <('Text', [(0, '*.txt')])>, \
+             'current_name': <'notes.txt'>}"
+        );
+    }
+}
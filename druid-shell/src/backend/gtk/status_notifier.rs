@@ -0,0 +1,816 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tray icons exported over D-Bus with the StatusNotifierItem protocol.
+//!
+//! The icon is described by the `org.kde.StatusNotifierItem` interface, and its menu by the
+//! `com.canonical.dbusmenu` interface; the tray ("host") draws both.
+//!
+//! https://www.freedesktop.org/wiki/Specifications/StatusNotifierItem/
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_uint};
+use std::ptr;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use gio_sys::{GDBusConnection, GDBusInterfaceVTable, GDBusMethodInvocation, GDBusNodeInfo};
+use glib::gobject_sys;
+use glib_sys::{gpointer, GError, GVariant};
+
+use super::menu::{Menu, MenuNode};
+use crate::piet::ImageBuf;
+use crate::Application;
+
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+
+const INTROSPECTION: &str = r#"
+<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <method name="Activate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="SecondaryActivate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="ContextMenu">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="Scroll">
+      <arg name="delta" type="i" direction="in"/>
+      <arg name="orientation" type="s" direction="in"/>
+    </method>
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="WindowId" type="i" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="IconPixmap" type="a(iiay)" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+  </interface>
+  <interface name="com.canonical.dbusmenu">
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="GetProperty">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="EventGroup">
+      <arg name="events" type="a(isvu)" direction="in"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <method name="AboutToShowGroup">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="updatesNeeded" type="ai" direction="out"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconThemePath" type="as" access="read"/>
+    <signal name="LayoutUpdated">
+      <arg name="revision" type="u"/>
+      <arg name="parent" type="i"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+/// Used to give every item its own object path.
+static NEXT_ITEM: AtomicUsize = AtomicUsize::new(0);
+
+/// A tray icon exported on the session bus.
+pub(crate) struct StatusNotifierItem {
+    item: Rc<Item>,
+    node_info: *mut GDBusNodeInfo,
+    registrations: Vec<c_uint>,
+    watch: c_uint,
+}
+
+/// The part of the item that the D-Bus callbacks see.
+struct Item {
+    connection: *mut GDBusConnection,
+    path: CString,
+    menu_path: CString,
+    state: RefCell<ItemState>,
+}
+
+struct ItemState {
+    icon: ImageBuf,
+    tooltip: String,
+    menu: Vec<MenuNode>,
+    /// Incremented whenever the menu changes, as required by dbusmenu.
+    revision: u32,
+}
+
+/// A property of a menu item, in the dbusmenu format.
+enum MenuProperty {
+    Str(String),
+    Bool(bool),
+}
+
+impl StatusNotifierItem {
+    /// Export a new item, if a tray that shows StatusNotifierItems is running.
+    pub(crate) fn new(icon: &ImageBuf) -> Option<StatusNotifierItem> {
+        unsafe {
+            let mut error = ptr::null_mut();
+            let connection =
+                gio_sys::g_bus_get_sync(gio_sys::G_BUS_TYPE_SESSION, ptr::null_mut(), &mut error);
+            if connection.is_null() {
+                tracing::warn!(
+                    "failed to connect to the session bus: {}",
+                    take_error(error)
+                );
+                return None;
+            }
+            if !host_registered(connection) {
+                gobject_sys::g_object_unref(connection as *mut _);
+                return None;
+            }
+
+            let xml = cstring(INTROSPECTION);
+            let node_info = gio_sys::g_dbus_node_info_new_for_xml(xml.as_ptr(), &mut error);
+            if node_info.is_null() {
+                tracing::error!(
+                    "invalid StatusNotifierItem introspection: {}",
+                    take_error(error)
+                );
+                gobject_sys::g_object_unref(connection as *mut _);
+                return None;
+            }
+
+            let path = format!(
+                "/StatusNotifierItem/{}",
+                NEXT_ITEM.fetch_add(1, Ordering::Relaxed)
+            );
+            let item = Rc::new(Item {
+                connection,
+                menu_path: cstring(&format!("{}/Menu", path)),
+                path: cstring(&path),
+                state: RefCell::new(ItemState {
+                    icon: icon.clone(),
+                    tooltip: String::new(),
+                    menu: Menu::new().into_nodes(),
+                    revision: 0,
+                }),
+            });
+
+            let mut registrations = Vec::new();
+            let objects = [
+                (
+                    &item.path,
+                    ITEM_INTERFACE,
+                    item_method_call as MethodCall,
+                    item_get_property as GetProperty,
+                ),
+                (
+                    &item.menu_path,
+                    MENU_INTERFACE,
+                    menu_method_call,
+                    menu_get_property,
+                ),
+            ];
+            for (path, interface, method_call, get_property) in objects.iter() {
+                let interface = cstring(interface);
+                let vtable = GDBusInterfaceVTable {
+                    method_call: Some(*method_call),
+                    get_property: Some(*get_property),
+                    set_property: None,
+                    padding: [ptr::null_mut(); 8],
+                };
+                // GDBus copies the vtable.
+                let id = gio_sys::g_dbus_connection_register_object(
+                    connection,
+                    path.as_ptr(),
+                    gio_sys::g_dbus_node_info_lookup_interface(node_info, interface.as_ptr()),
+                    &vtable,
+                    Rc::into_raw(item.clone()) as gpointer,
+                    Some(drop_item),
+                    &mut error,
+                );
+                if id == 0 {
+                    tracing::error!("failed to export the tray icon: {}", take_error(error));
+                } else {
+                    registrations.push(id);
+                }
+            }
+
+            // Register with the watcher now, and again whenever it restarts.
+            let watcher = cstring(WATCHER_NAME);
+            let watch = gio_sys::g_bus_watch_name_on_connection(
+                connection,
+                watcher.as_ptr(),
+                gio_sys::G_BUS_NAME_WATCHER_FLAGS_NONE,
+                Some(watcher_appeared),
+                None,
+                Rc::into_raw(item.clone()) as gpointer,
+                Some(drop_item),
+            );
+
+            Some(StatusNotifierItem {
+                item,
+                node_info,
+                registrations,
+                watch,
+            })
+        }
+    }
+
+    pub(crate) fn set_icon(&self, icon: &ImageBuf) {
+        self.item.state.borrow_mut().icon = icon.clone();
+        self.item
+            .emit(&self.item.path, ITEM_INTERFACE, "NewIcon", ptr::null_mut());
+    }
+
+    pub(crate) fn set_tooltip(&self, tooltip: &str) {
+        self.item.state.borrow_mut().tooltip = tooltip.to_string();
+        self.item.emit(
+            &self.item.path,
+            ITEM_INTERFACE,
+            "NewToolTip",
+            ptr::null_mut(),
+        );
+    }
+
+    pub(crate) fn set_menu(&self, menu: Menu) {
+        let revision = {
+            let mut state = self.item.state.borrow_mut();
+            state.menu = menu.into_nodes();
+            state.revision += 1;
+            state.revision
+        };
+        let params = variant::tuple(vec![variant::uint32(revision), variant::int32(0)]);
+        self.item.emit(
+            &self.item.menu_path,
+            MENU_INTERFACE,
+            "LayoutUpdated",
+            params,
+        );
+    }
+}
+
+impl Drop for StatusNotifierItem {
+    fn drop(&mut self) {
+        unsafe {
+            gio_sys::g_bus_unwatch_name(self.watch);
+            for &id in &self.registrations {
+                gio_sys::g_dbus_connection_unregister_object(self.item.connection, id);
+            }
+            gio_sys::g_dbus_node_info_unref(self.node_info);
+        }
+    }
+}
+
+impl Item {
+    fn emit(&self, path: &CStr, interface: &str, signal: &str, params: *mut GVariant) {
+        let interface = cstring(interface);
+        let signal = cstring(signal);
+        unsafe {
+            let mut error = ptr::null_mut();
+            let emitted = gio_sys::g_dbus_connection_emit_signal(
+                self.connection,
+                ptr::null(),
+                path.as_ptr(),
+                interface.as_ptr(),
+                signal.as_ptr(),
+                params,
+                &mut error,
+            );
+            if emitted == glib_sys::GFALSE {
+                tracing::error!("failed to update the tray icon: {}", take_error(error));
+            }
+        }
+    }
+
+    /// Send the command of the menu item `id` to the application.
+    fn activate(&self, id: i32) {
+        let command = self
+            .state
+            .borrow()
+            .menu
+            .get(id as usize)
+            .and_then(|node| node.command);
+        if let (Some(command), Some(app)) = (command, Application::try_global()) {
+            app.backend_app
+                .with_handler(|handler| handler.command(command));
+        }
+    }
+}
+
+impl Drop for Item {
+    fn drop(&mut self) {
+        unsafe { gobject_sys::g_object_unref(self.connection as *mut _) };
+    }
+}
+
+type MethodCall = unsafe extern "C" fn(
+    *mut GDBusConnection,
+    *const c_char,
+    *const c_char,
+    *const c_char,
+    *const c_char,
+    *mut GVariant,
+    *mut GDBusMethodInvocation,
+    gpointer,
+);
+
+type GetProperty = unsafe extern "C" fn(
+    *mut GDBusConnection,
+    *const c_char,
+    *const c_char,
+    *const c_char,
+    *const c_char,
+    *mut *mut GError,
+    gpointer,
+) -> *mut GVariant;
+
+unsafe extern "C" fn drop_item(user_data: gpointer) {
+    drop(Rc::from_raw(user_data as *const Item));
+}
+
+unsafe extern "C" fn watcher_appeared(
+    connection: *mut GDBusConnection,
+    _name: *const c_char,
+    _name_owner: *const c_char,
+    user_data: gpointer,
+) {
+    let item = &*(user_data as *const Item);
+    // The watcher combines our object path with our unique bus name.
+    let params = variant::tuple(vec![variant::string(&item.path.to_string_lossy())]);
+    let name = cstring(WATCHER_NAME);
+    let path = cstring(WATCHER_PATH);
+    let method = cstring("RegisterStatusNotifierItem");
+    gio_sys::g_dbus_connection_call(
+        connection,
+        name.as_ptr(),
+        path.as_ptr(),
+        name.as_ptr(),
+        method.as_ptr(),
+        params,
+        ptr::null(),
+        gio_sys::G_DBUS_CALL_FLAGS_NONE,
+        -1,
+        ptr::null_mut(),
+        None,
+        ptr::null_mut(),
+    );
+}
+
+unsafe extern "C" fn item_method_call(
+    _connection: *mut GDBusConnection,
+    _sender: *const c_char,
+    _path: *const c_char,
+    _interface: *const c_char,
+    method: *const c_char,
+    _params: *mut GVariant,
+    invocation: *mut GDBusMethodInvocation,
+    _user_data: gpointer,
+) {
+    // Reply first, so that the host isn't kept waiting by the handler.
+    gio_sys::g_dbus_method_invocation_return_value(invocation, ptr::null_mut());
+    // The host shows the exported menu itself on ContextMenu; there is nothing to do for the
+    // other methods.
+    if CStr::from_ptr(method).to_bytes() == b"Activate" {
+        if let Some(app) = Application::try_global() {
+            app.backend_app
+                .with_handler(|handler| handler.tray_activated());
+        }
+    }
+}
+
+unsafe extern "C" fn item_get_property(
+    _connection: *mut GDBusConnection,
+    _sender: *const c_char,
+    _path: *const c_char,
+    _interface: *const c_char,
+    property: *const c_char,
+    error: *mut *mut GError,
+    user_data: gpointer,
+) -> *mut GVariant {
+    let item = &*(user_data as *const Item);
+    let state = item.state.borrow();
+    match CStr::from_ptr(property).to_bytes() {
+        b"Category" => variant::string("ApplicationStatus"),
+        b"Id" | b"Title" => variant::string(&app_id()),
+        b"Status" => variant::string("Active"),
+        b"WindowId" => variant::int32(0),
+        b"IconName" => variant::string(""),
+        b"IconPixmap" => icon_pixmaps(&state.icon),
+        b"ToolTip" => variant::tuple(vec![
+            variant::string(""),
+            variant::array("(iiay)", Vec::new()),
+            variant::string(&state.tooltip),
+            variant::string(""),
+        ]),
+        b"ItemIsMenu" => variant::boolean(false),
+        b"Menu" => variant::object_path(&item.menu_path),
+        _ => unknown_property(error),
+    }
+}
+
+unsafe extern "C" fn menu_method_call(
+    _connection: *mut GDBusConnection,
+    _sender: *const c_char,
+    _path: *const c_char,
+    _interface: *const c_char,
+    method: *const c_char,
+    params: *mut GVariant,
+    invocation: *mut GDBusMethodInvocation,
+    user_data: gpointer,
+) {
+    let item = &*(user_data as *const Item);
+    let reply = match CStr::from_ptr(method).to_bytes() {
+        b"GetLayout" => {
+            let parent = variant::child_int32(params, 0);
+            let depth = variant::child_int32(params, 1);
+            let state = item.state.borrow();
+            if state.menu.get(parent as usize).is_some() {
+                Some(variant::tuple(vec![
+                    variant::uint32(state.revision),
+                    menu_layout(&state.menu, parent, depth),
+                ]))
+            } else {
+                None
+            }
+        }
+        b"GetGroupProperties" => {
+            let ids = variant::child_int32_array(params, 0);
+            let state = item.state.borrow();
+            let properties = ids
+                .into_iter()
+                .filter_map(|id| {
+                    let node = state.menu.get(id as usize)?;
+                    Some(variant::tuple(vec![
+                        variant::int32(id),
+                        menu_properties(node),
+                    ]))
+                })
+                .collect();
+            Some(variant::tuple(vec![variant::array("(ia{sv})", properties)]))
+        }
+        b"GetProperty" => {
+            let id = variant::child_int32(params, 0);
+            let name = variant::child_string(params, 1);
+            let state = item.state.borrow();
+            state
+                .menu
+                .get(id as usize)
+                .and_then(|node| {
+                    menu_property_list(node)
+                        .into_iter()
+                        .find(|(key, _)| *key == name)
+                })
+                .map(|(_, value)| variant::tuple(vec![variant::boxed(value.into_variant())]))
+        }
+        b"Event" => {
+            let id = variant::child_int32(params, 0);
+            let event = variant::child_string(params, 1);
+            gio_sys::g_dbus_method_invocation_return_value(invocation, ptr::null_mut());
+            if event == "clicked" {
+                item.activate(id);
+            }
+            return;
+        }
+        b"EventGroup" => {
+            let events = glib_sys::g_variant_get_child_value(params, 0);
+            let mut clicked = Vec::new();
+            for i in 0..glib_sys::g_variant_n_children(events) {
+                let event = glib_sys::g_variant_get_child_value(events, i);
+                if variant::child_string(event, 1) == "clicked" {
+                    clicked.push(variant::child_int32(event, 0));
+                }
+                glib_sys::g_variant_unref(event);
+            }
+            glib_sys::g_variant_unref(events);
+            let reply = variant::tuple(vec![variant::array("i", Vec::new())]);
+            gio_sys::g_dbus_method_invocation_return_value(invocation, reply);
+            for id in clicked {
+                item.activate(id);
+            }
+            return;
+        }
+        b"AboutToShow" => Some(variant::tuple(vec![variant::boolean(false)])),
+        b"AboutToShowGroup" => Some(variant::tuple(vec![
+            variant::array("i", Vec::new()),
+            variant::array("i", Vec::new()),
+        ])),
+        _ => None,
+    };
+    match reply {
+        Some(reply) => gio_sys::g_dbus_method_invocation_return_value(invocation, reply),
+        None => {
+            let name = cstring("org.freedesktop.DBus.Error.InvalidArgs");
+            let message = cstring("unknown menu item");
+            gio_sys::g_dbus_method_invocation_return_dbus_error(
+                invocation,
+                name.as_ptr(),
+                message.as_ptr(),
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn menu_get_property(
+    _connection: *mut GDBusConnection,
+    _sender: *const c_char,
+    _path: *const c_char,
+    _interface: *const c_char,
+    property: *const c_char,
+    error: *mut *mut GError,
+    _user_data: gpointer,
+) -> *mut GVariant {
+    match CStr::from_ptr(property).to_bytes() {
+        b"Version" => variant::uint32(3),
+        b"TextDirection" => variant::string("ltr"),
+        b"Status" => variant::string("normal"),
+        b"IconThemePath" => variant::array("s", Vec::new()),
+        _ => unknown_property(error),
+    }
+}
+
+unsafe fn unknown_property(error: *mut *mut GError) -> *mut GVariant {
+    let message = cstring("unknown property");
+    glib_sys::g_set_error_literal(
+        error,
+        gio_sys::g_dbus_error_quark(),
+        gio_sys::G_DBUS_ERROR_UNKNOWN_PROPERTY,
+        message.as_ptr(),
+    );
+    ptr::null_mut()
+}
+
+/// Whether a tray is waiting for StatusNotifierItems.
+///
+/// Without a host, the watcher doesn't show items anywhere.
+unsafe fn host_registered(connection: *mut GDBusConnection) -> bool {
+    let name = cstring(WATCHER_NAME);
+    let path = cstring(WATCHER_PATH);
+    let interface = cstring("org.freedesktop.DBus.Properties");
+    let method = cstring("Get");
+    let params = variant::tuple(vec![
+        variant::string(WATCHER_NAME),
+        variant::string("IsStatusNotifierHostRegistered"),
+    ]);
+    let mut error = ptr::null_mut();
+    let reply = gio_sys::g_dbus_connection_call_sync(
+        connection,
+        name.as_ptr(),
+        path.as_ptr(),
+        interface.as_ptr(),
+        method.as_ptr(),
+        params,
+        ptr::null(),
+        gio_sys::G_DBUS_CALL_FLAGS_NO_AUTO_START,
+        1000,
+        ptr::null_mut(),
+        &mut error,
+    );
+    if reply.is_null() {
+        tracing::info!("no StatusNotifierItem watcher: {}", take_error(error));
+        return false;
+    }
+    let value = glib_sys::g_variant_get_child_value(reply, 0);
+    let registered = glib_sys::g_variant_get_variant(value);
+    let result = glib_sys::g_variant_get_boolean(registered) != glib_sys::GFALSE;
+    glib_sys::g_variant_unref(registered);
+    glib_sys::g_variant_unref(value);
+    glib_sys::g_variant_unref(reply);
+    result
+}
+
+/// The `(ia{sv}av)` layout of the menu item `id` and its children, up to `depth` levels down.
+///
+/// A negative `depth` includes all of them.
+fn menu_layout(nodes: &[MenuNode], id: i32, depth: i32) -> *mut GVariant {
+    let node = &nodes[id as usize];
+    let children = if depth == 0 {
+        Vec::new()
+    } else {
+        node.children
+            .iter()
+            .map(|&child| variant::boxed(menu_layout(nodes, child, depth - 1)))
+            .collect()
+    };
+    variant::tuple(vec![
+        variant::int32(id),
+        menu_properties(node),
+        variant::array("v", children),
+    ])
+}
+
+fn menu_properties(node: &MenuNode) -> *mut GVariant {
+    let entries = menu_property_list(node)
+        .into_iter()
+        .map(|(key, value)| {
+            variant::dict_entry(variant::string(key), variant::boxed(value.into_variant()))
+        })
+        .collect();
+    variant::array("{sv}", entries)
+}
+
+/// The properties of a menu item that differ from the dbusmenu defaults.
+fn menu_property_list(node: &MenuNode) -> Vec<(&'static str, MenuProperty)> {
+    let mut properties = Vec::new();
+    if node.separator {
+        properties.push(("type", MenuProperty::Str("separator".into())));
+    } else if !node.label.is_empty() {
+        // Underscores mark access keys in dbusmenu labels.
+        let label = node.label.replace('_', "__");
+        properties.push(("label", MenuProperty::Str(label)));
+    }
+    if !node.enabled {
+        properties.push(("enabled", MenuProperty::Bool(false)));
+    }
+    if !node.children.is_empty() {
+        properties.push(("children-display", MenuProperty::Str("submenu".into())));
+    }
+    properties
+}
+
+impl MenuProperty {
+    fn into_variant(self) -> *mut GVariant {
+        match self {
+            MenuProperty::Str(s) => variant::string(&s),
+            MenuProperty::Bool(b) => variant::boolean(b),
+        }
+    }
+}
+
+/// The icon as `a(iiay)`: width, height, and ARGB32 pixels in network byte order.
+fn icon_pixmaps(icon: &ImageBuf) -> *mut GVariant {
+    let mut pixmaps = Vec::new();
+    if icon.width() > 0 && icon.height() > 0 {
+        let mut pixels = Vec::with_capacity(icon.width() * icon.height() * 4);
+        for row in icon.pixel_colors() {
+            for color in row {
+                let (r, g, b, a) = color.as_rgba8();
+                pixels.extend_from_slice(&[a, r, g, b]);
+            }
+        }
+        pixmaps.push(variant::tuple(vec![
+            variant::int32(icon.width() as i32),
+            variant::int32(icon.height() as i32),
+            variant::bytes(&pixels),
+        ]));
+    }
+    variant::array("(iiay)", pixmaps)
+}
+
+fn app_id() -> String {
+    glib::get_prgname()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| "druid".into())
+}
+
+pub(super) fn cstring(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).unwrap()
+}
+
+pub(super) unsafe fn take_error(error: *mut GError) -> String {
+    let message = CStr::from_ptr((*error).message)
+        .to_string_lossy()
+        .into_owned();
+    glib_sys::g_error_free(error);
+    message
+}
+
+/// Helpers for building and reading `GVariant`s.
+///
+/// The builders return floating references, which are taken over by the variant, reply, or
+/// signal that they are passed to.
+pub(super) mod variant {
+    use std::ffi::CStr;
+    use std::os::raw::c_void;
+
+    use glib_sys::{GVariant, GFALSE, GTRUE};
+
+    use super::cstring;
+
+    pub(crate) fn string(s: &str) -> *mut GVariant {
+        let s = cstring(s);
+        unsafe { glib_sys::g_variant_new_string(s.as_ptr()) }
+    }
+
+    pub(crate) fn object_path(path: &CStr) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_object_path(path.as_ptr()) }
+    }
+
+    pub(crate) fn int32(value: i32) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_int32(value) }
+    }
+
+    pub(crate) fn uint32(value: u32) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_uint32(value) }
+    }
+
+    pub(crate) fn boolean(value: bool) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_boolean(if value { GTRUE } else { GFALSE }) }
+    }
+
+    pub(crate) fn bytes(bytes: &[u8]) -> *mut GVariant {
+        let ty = cstring("y");
+        unsafe {
+            let ty = glib_sys::g_variant_type_new(ty.as_ptr());
+            let array = glib_sys::g_variant_new_fixed_array(
+                ty,
+                bytes.as_ptr() as *const c_void,
+                bytes.len(),
+                1,
+            );
+            glib_sys::g_variant_type_free(ty);
+            array
+        }
+    }
+
+    pub(crate) fn boxed(value: *mut GVariant) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_variant(value) }
+    }
+
+    pub(crate) fn tuple(items: Vec<*mut GVariant>) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_tuple(items.as_ptr(), items.len()) }
+    }
+
+    pub(crate) fn dict_entry(key: *mut GVariant, value: *mut GVariant) -> *mut GVariant {
+        unsafe { glib_sys::g_variant_new_dict_entry(key, value) }
+    }
+
+    /// An array of `element_type` items; the type is needed for empty arrays.
+    pub(crate) fn array(element_type: &str, items: Vec<*mut GVariant>) -> *mut GVariant {
+        let ty = cstring(element_type);
+        unsafe {
+            let ty = glib_sys::g_variant_type_new(ty.as_ptr());
+            let array = glib_sys::g_variant_new_array(ty, items.as_ptr(), items.len());
+            glib_sys::g_variant_type_free(ty);
+            array
+        }
+    }
+
+    pub(crate) unsafe fn child_int32(container: *mut GVariant, index: usize) -> i32 {
+        let child = glib_sys::g_variant_get_child_value(container, index);
+        let value = glib_sys::g_variant_get_int32(child);
+        glib_sys::g_variant_unref(child);
+        value
+    }
+
+    pub(crate) unsafe fn child_string(container: *mut GVariant, index: usize) -> String {
+        let child = glib_sys::g_variant_get_child_value(container, index);
+        let value = CStr::from_ptr(glib_sys::g_variant_get_string(child, std::ptr::null_mut()))
+            .to_string_lossy()
+            .into_owned();
+        glib_sys::g_variant_unref(child);
+        value
+    }
+
+    pub(crate) unsafe fn child_int32_array(container: *mut GVariant, index: usize) -> Vec<i32> {
+        let array = glib_sys::g_variant_get_child_value(container, index);
+        let values = (0..glib_sys::g_variant_n_children(array))
+            .map(|i| child_int32(array, i))
+            .collect();
+        glib_sys::g_variant_unref(array);
+        values
+    }
+}
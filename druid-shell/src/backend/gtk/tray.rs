@@ -0,0 +1,127 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! GTK tray icons.
+//!
+//! Icons are exported with the StatusNotifierItem protocol when a tray that shows them is
+//! running, as on KDE, or on GNOME with the AppIndicator extension. Otherwise they fall back to
+//! the deprecated `GtkStatusIcon`, which only works with the older XEmbed trays on X11.
+
+// `GtkStatusIcon` is deprecated, and GTK 3 has no replacement for it.
+#![allow(deprecated)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gio::ApplicationExt;
+use gtk::prelude::GtkMenuExtManual;
+use gtk::{AccelGroup, Menu as GtkMenu, StatusIcon, StatusIconExt, WidgetExt};
+
+use super::menu::Menu;
+use super::status_notifier::StatusNotifierItem;
+use super::util::make_pixbuf;
+use crate::piet::ImageBuf;
+use crate::{Application, Error};
+
+pub struct TrayIcon {
+    backend: Backend,
+}
+
+enum Backend {
+    StatusNotifier(StatusNotifierItem),
+    StatusIcon {
+        status_icon: StatusIcon,
+        menu: Rc<RefCell<Option<GtkMenu>>>,
+    },
+}
+
+impl TrayIcon {
+    pub fn new(icon: &ImageBuf) -> Result<TrayIcon, Error> {
+        let backend = match StatusNotifierItem::new(icon) {
+            Some(item) => Backend::StatusNotifier(item),
+            None => new_status_icon(icon),
+        };
+
+        // Keep the run loop going while the icon is shown, even without windows.
+        if let Some(app) = Application::try_global() {
+            app.backend_app.gtk_app().hold();
+        }
+        Ok(TrayIcon { backend })
+    }
+
+    pub fn set_icon(&mut self, icon: &ImageBuf) {
+        match &self.backend {
+            Backend::StatusNotifier(item) => item.set_icon(icon),
+            Backend::StatusIcon { status_icon, .. } => {
+                status_icon.set_from_pixbuf(Some(&make_pixbuf(icon)))
+            }
+        }
+    }
+
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        match &self.backend {
+            Backend::StatusNotifier(item) => item.set_tooltip(tooltip),
+            Backend::StatusIcon { status_icon, .. } => status_icon.set_tooltip_text(Some(tooltip)),
+        }
+    }
+
+    pub fn set_menu(&mut self, menu: Menu) -> Result<(), crate::Error> {
+        match &self.backend {
+            Backend::StatusNotifier(item) => item.set_menu(menu),
+            Backend::StatusIcon {
+                menu: status_menu, ..
+            } => {
+                let activate: Rc<dyn Fn(u32)> = Rc::new(|id| {
+                    if let Some(app) = Application::try_global() {
+                        app.backend_app.with_handler(|handler| handler.command(id));
+                    }
+                });
+                let menu = menu.into_gtk_menu_with(&AccelGroup::new(), &activate);
+                *status_menu.borrow_mut() = Some(menu);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        if let Backend::StatusIcon { status_icon, .. } = &self.backend {
+            status_icon.set_visible(false);
+        }
+        if let Some(app) = Application::try_global() {
+            app.backend_app.gtk_app().release();
+        }
+    }
+}
+
+fn new_status_icon(icon: &ImageBuf) -> Backend {
+    let status_icon = StatusIcon::from_pixbuf(&make_pixbuf(icon));
+    status_icon.connect_activate(|_| {
+        if let Some(app) = Application::try_global() {
+            app.backend_app
+                .with_handler(|handler| handler.tray_activated());
+        }
+    });
+
+    let menu: Rc<RefCell<Option<GtkMenu>>> = Rc::new(RefCell::new(None));
+    let popup_menu = menu.clone();
+    status_icon.connect_popup_menu(move |_, button, time| {
+        if let Some(menu) = popup_menu.borrow().as_ref() {
+            menu.show_all();
+            menu.popup_easy(button, time);
+        }
+    });
+    Backend::StatusIcon { status_icon, menu }
+}
@@ -14,6 +14,32 @@
 
 //! Utilities, GTK specific.
 
+use crate::piet::{ImageBuf, ImageFormat};
+
 pub(crate) fn assert_main_thread() {
     assert!(gtk::is_initialized_main_thread());
 }
+
+/// Whether the application runs in a Flatpak or Snap sandbox, where files
+/// and other applications have to be reached through XDG desktop portals.
+pub(crate) fn is_sandboxed() -> bool {
+    std::path::Path::new("/.flatpak-info").exists() || std::env::var_os("SNAP").is_some()
+}
+
+/// Create a new Pixbuf from an `ImageBuf`.
+pub(crate) fn make_pixbuf(image: &ImageBuf) -> gdk_pixbuf::Pixbuf {
+    // TODO: gtk::Pixbuf expects unpremultiplied alpha. We should convert.
+    let has_alpha = !matches!(image.format(), ImageFormat::Rgb);
+    let bytes_per_pixel = image.format().bytes_per_pixel();
+    gdk_pixbuf::Pixbuf::from_mut_slice(
+        image.raw_pixels().to_owned(),
+        gdk_pixbuf::Colorspace::Rgb,
+        has_alpha,
+        // bits_per_sample
+        8,
+        image.width() as i32,
+        image.height() as i32,
+        // row stride (in bytes)
+        (image.width() * bytes_per_pixel) as i32,
+    )
+}
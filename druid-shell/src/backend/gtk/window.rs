@@ -26,35 +26,46 @@ use std::time::Instant;
 
 use anyhow::anyhow;
 use cairo::Surface;
-use gdk::{EventKey, EventMask, ModifierType, ScrollDirection, WindowExt, WindowTypeHint};
+use gdk::{
+    EventKey, EventMask, InputSource, ModifierType, ScrollDirection, WindowExt, WindowTypeHint,
+};
 use gio::ApplicationExt;
 use gtk::prelude::*;
 use gtk::{AccelGroup, ApplicationWindow, DrawingArea, SettingsExt};
 use tracing::{error, warn};
 
 #[cfg(feature = "raw-win-handle")]
-use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use raw_window_handle::{
+    unix::{WaylandHandle, XlibHandle},
+    HasRawWindowHandle, RawWindowHandle,
+};
 
 use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
 use crate::piet::{Piet, PietText, RenderContext};
 
 use crate::common_util::{ClickCounter, IdleCallback};
 use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
-use crate::error::Error as ShellError;
+use crate::dnd::{DropData, DropEvent};
+use crate::error::{Error, ShellError, ShellOperation};
 use crate::keyboard::{KbKey, KeyEvent, KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
-use crate::piet::ImageFormat;
+use crate::pen::{PenEvent, PenPhase};
 use crate::region::Region;
 use crate::scale::{Scalable, Scale, ScaledArea};
+use crate::screen::Monitor;
 use crate::text::{simulate_input, Event};
+use crate::touch::{TouchEvent, TouchId};
 use crate::window::{
-    self, FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+    self, FileDialogToken, IdleToken, ResizeEdge, TextFieldToken, TextRenderingOptions, TimerToken,
+    WinHandler, WindowCorners, WindowLevel,
 };
 
 use super::application::Application;
 use super::dialog;
 use super::keycodes;
 use super::menu::Menu;
+use super::portal;
+use super::screen;
 use super::util;
 
 /// The backend target DPI.
@@ -102,9 +113,46 @@ pub struct WindowHandle {
 #[cfg(feature = "raw-win-handle")]
 unsafe impl HasRawWindowHandle for WindowHandle {
     fn raw_window_handle(&self) -> RawWindowHandle {
-        error!("HasRawWindowHandle trait not implemented for gtk.");
-        // GTK is not a platform, and there's no empty generic handle. Pick XCB randomly as fallback.
-        RawWindowHandle::Xcb(XcbHandle::empty())
+        use glib::translate::ToGlibPtr;
+
+        use super::{gdk_wayland, gdk_x11};
+
+        // GTK is not a platform, so we need to find out which windowing system GDK is using.
+        // If we can't, there's no empty generic handle, so we fall back to an empty Xlib handle.
+        let gdk_window = match self.state.upgrade().and_then(|s| s.window.get_window()) {
+            Some(gdk_window) => gdk_window,
+            None => {
+                error!("Window has already been dropped, returning empty handle");
+                return RawWindowHandle::Xlib(XlibHandle::empty());
+            }
+        };
+        let display = gdk_window.get_display();
+        let window_ptr: *mut gdk_sys::GdkWindow = gdk_window.to_glib_none().0;
+        let display_ptr: *mut gdk_sys::GdkDisplay = display.to_glib_none().0;
+
+        if let Some(x11) = gdk_x11::functions(&display) {
+            return unsafe {
+                RawWindowHandle::Xlib(XlibHandle {
+                    window: (x11.gdk_x11_window_get_xid)(window_ptr),
+                    display: (x11.gdk_x11_display_get_xdisplay)(display_ptr),
+                    ..XlibHandle::empty()
+                })
+            };
+        }
+        if let Some(wayland) = gdk_wayland::functions(&display) {
+            return unsafe {
+                RawWindowHandle::Wayland(WaylandHandle {
+                    surface: (wayland.gdk_wayland_window_get_wl_surface)(window_ptr),
+                    display: (wayland.gdk_wayland_display_get_wl_display)(display_ptr),
+                    ..WaylandHandle::empty()
+                })
+            };
+        }
+        error!(
+            "HasRawWindowHandle not implemented for GDK backend {}",
+            display.get_type().name()
+        );
+        RawWindowHandle::Xlib(XlibHandle::empty())
     }
 }
 
@@ -180,6 +228,22 @@ pub(crate) struct WindowState {
 
     request_animation: Cell<bool>,
     in_draw: Cell<bool>,
+
+    // What is dragged over the window from another application, once GTK handed it to us.
+    drop_data: RefCell<Option<DropData>>,
+    // Where the drag from another application is, in display points.
+    drop_pos: Cell<Point>,
+    // Whether the dragged data was requested for a drop, rather than for a drag motion.
+    dropping: Cell<bool>,
+    // The monitor the window was on when it was last configured.
+    monitor: RefCell<Option<gdk::Monitor>>,
+    // The position and size of the window when it was last configured while restored, in display
+    // points.
+    restored_rect: Cell<Option<Rect>>,
+    // Whether the handler took the contact that GTK would turn into mouse events.
+    primary_touch_handled: Cell<bool>,
+    // Whether the mouse is over an area that should be treated as the titlebar.
+    handle_titlebar: Cell<bool>,
 }
 
 #[derive(Clone, PartialEq)]
@@ -247,7 +311,7 @@ impl WindowBuilder {
         self.menu = Some(menu);
     }
 
-    pub fn build(self) -> Result<WindowHandle, ShellError> {
+    pub fn build(self) -> Result<WindowHandle, Error> {
         let handler = self
             .handler
             .expect("Tried to build a window without setting the handler");
@@ -301,6 +365,13 @@ impl WindowBuilder {
             deferred_queue: RefCell::new(Vec::new()),
             request_animation: Cell::new(false),
             in_draw: Cell::new(false),
+            drop_data: RefCell::new(None),
+            drop_pos: Cell::new(Point::ZERO),
+            dropping: Cell::new(false),
+            monitor: RefCell::new(None),
+            restored_rect: Cell::new(None),
+            primary_touch_handled: Cell::new(false),
+            handle_titlebar: Cell::new(false),
         });
 
         self.app
@@ -342,6 +413,7 @@ impl WindowBuilder {
                 | EventMask::KEY_RELEASE_MASK
                 | EventMask::SCROLL_MASK
                 | EventMask::SMOOTH_SCROLL_MASK
+                | EventMask::TOUCH_MASK
                 | EventMask::FOCUS_CHANGE_MASK,
         );
 
@@ -501,6 +573,11 @@ impl WindowBuilder {
                             0
                         };
                         if gtk_count == 0 || gtk_count == 1 {
+                            if button == MouseButton::Left {
+                                if let Some(pen) = get_pen_event(event, pos.to_dp(scale), PenPhase::Down, button_state) {
+                                    handler.pen(&pen);
+                                }
+                            }
                             handler.mouse_down(
                                 &MouseEvent {
                                     pos: pos.to_dp(scale),
@@ -509,12 +586,20 @@ impl WindowBuilder {
                                     count,
                                     focus: false,
                                     button,
-                                    wheel_delta: Vec2::ZERO
+                                    wheel_delta: Vec2::ZERO,
+                                    precise_wheel: false
                                 },
                             );
                         }
                     }
                 });
+                if event.get_event_type() == gdk::EventType::ButtonPress
+                    && event.get_button() == 1
+                    && state.handle_titlebar.get()
+                {
+                    let (x, y) = event.get_root();
+                    state.window.begin_move_drag(1, x as i32, y as i32, event.get_time());
+                }
             }
 
             Inhibit(true)
@@ -526,15 +611,22 @@ impl WindowBuilder {
                     if let Some(button) = get_mouse_button(event.get_button()) {
                         let scale = state.scale.get();
                         let button_state = event.get_state();
+                        let pos = Point::from(event.get_position()).to_dp(scale);
+                        if button == MouseButton::Left {
+                            if let Some(pen) = get_pen_event(event, pos, PenPhase::Up, button_state) {
+                                handler.pen(&pen);
+                            }
+                        }
                         handler.mouse_up(
                             &MouseEvent {
-                                pos: Point::from(event.get_position()).to_dp(scale),
+                                pos,
                                 buttons: get_mouse_buttons_from_modifiers(button_state).without(button),
                                 mods: get_modifiers(button_state),
                                 count: 0,
                                 focus: false,
                                 button,
-                                wheel_delta: Vec2::ZERO
+                                wheel_delta: Vec2::ZERO,
+                                precise_wheel: false
                             },
                         );
                     }
@@ -556,16 +648,58 @@ impl WindowBuilder {
                         count: 0,
                         focus: false,
                         button: MouseButton::None,
-                        wheel_delta: Vec2::ZERO
+                        wheel_delta: Vec2::ZERO,
+                        precise_wheel: false
                     };
 
-                    state.with_handler(|h| h.mouse_move(&mouse_event));
+                    let pen = get_pen_event(motion, mouse_event.pos, PenPhase::Move, motion_state);
+                    state.with_handler(|h| {
+                        if let Some(pen) = &pen {
+                            h.pen(pen);
+                        }
+                        h.mouse_move(&mouse_event)
+                    });
                 }
 
                 Inhibit(true)
             }),
         );
 
+        win_state
+            .drawing_area
+            .connect_touch_event(clone!(handle => move |_widget, event| {
+                if let Some(state) = handle.state.upgrade() {
+                    if let Some(touch) = event.downcast_ref::<gdk::EventTouch>() {
+                        let scale = state.scale.get();
+                        let event = TouchEvent {
+                            id: get_touch_id(touch),
+                            pos: Point::from(touch.get_position()).to_dp(scale),
+                            mods: get_modifiers(touch.get_state()),
+                            primary: touch.is_emulating_pointer(),
+                        };
+                        let handled = state.with_handler(|handler| match touch.get_event_type() {
+                            gdk::EventType::TouchBegin => handler.touch_down(&event),
+                            gdk::EventType::TouchUpdate => handler.touch_move(&event),
+                            gdk::EventType::TouchEnd | gdk::EventType::TouchCancel => {
+                                handler.touch_up(&event)
+                            }
+                            _ => false,
+                        });
+                        if event.primary {
+                            // GTK turns the contact into mouse events unless we stop the signal.
+                            // Which way it goes is decided when it touches down, so that the
+                            // mouse events are balanced.
+                            if touch.get_event_type() == gdk::EventType::TouchBegin {
+                                state.primary_touch_handled.set(handled.unwrap_or(false));
+                            }
+                            return Inhibit(state.primary_touch_handled.get());
+                        }
+                    }
+                }
+
+                Inhibit(true)
+            }));
+
         win_state.drawing_area.connect_leave_notify_event(
             clone!(handle => move |_widget, crossing| {
                 if let Some(state) = handle.state.upgrade() {
@@ -578,7 +712,8 @@ impl WindowBuilder {
                         count: 0,
                         focus: false,
                         button: MouseButton::None,
-                        wheel_delta: Vec2::ZERO
+                        wheel_delta: Vec2::ZERO,
+                        precise_wheel: false
                     };
 
                     state.with_handler(|h| h.mouse_move(&mouse_event));
@@ -588,6 +723,78 @@ impl WindowBuilder {
             }),
         );
 
+        // Drags from other applications. GTK only hands over the dragged data on request, so
+        // the first motion of a drag asks for it, and the drop asks for it again.
+        win_state.drawing_area.drag_dest_set(
+            gtk::DestDefaults::empty(),
+            &[],
+            gdk::DragAction::COPY,
+        );
+        win_state.drawing_area.drag_dest_add_uri_targets();
+        win_state.drawing_area.drag_dest_add_text_targets();
+
+        win_state.drawing_area.connect_drag_motion(
+            clone!(handle => move |widget, context, x, y, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.drop_pos.set(Point::new(x as f64, y as f64).to_dp(state.scale.get()));
+                    let data = state.drop_data.borrow().clone();
+                    match data {
+                        Some(data) => state.drag_over(context, data, time),
+                        None => match widget.drag_dest_find_target(context, None) {
+                            Some(target) => widget.drag_get_data(context, &target, time),
+                            None => return Inhibit(false),
+                        },
+                    }
+                }
+
+                Inhibit(true)
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_leave(
+            clone!(handle => move |_widget, _context, _time| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.drop_data.replace(None);
+                    state.with_handler(|h| h.drag_leave());
+                }
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_drop(
+            clone!(handle => move |widget, context, x, y, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    if let Some(target) = widget.drag_dest_find_target(context, None) {
+                        state.drop_pos.set(Point::new(x as f64, y as f64).to_dp(state.scale.get()));
+                        state.dropping.set(true);
+                        widget.drag_get_data(context, &target, time);
+                        return Inhibit(true);
+                    }
+                }
+
+                Inhibit(false)
+            }),
+        );
+
+        win_state.drawing_area.connect_drag_data_received(
+            clone!(handle => move |_widget, context, _x, _y, selection, _info, time| {
+                if let Some(state) = handle.state.upgrade() {
+                    let data = get_drop_data(selection);
+                    if state.dropping.replace(false) {
+                        let event = DropEvent {
+                            pos: state.drop_pos.get(),
+                            data,
+                            mods: Modifiers::empty(),
+                        };
+                        let taken = state.with_handler(|h| h.drag_drop(&event)).unwrap_or(false);
+                        context.drag_finish(taken, false, time);
+                    } else {
+                        state.drop_data.replace(Some(data.clone()));
+                        state.drag_over(context, data, time);
+                    }
+                }
+            }),
+        );
+
         win_state
             .drawing_area
             .connect_scroll_event(clone!(handle => move |_widget, scroll| {
@@ -625,6 +832,13 @@ impl WindowBuilder {
                         }
                     };
 
+                    // GTK also sends smooth scrolling for some mice, so ask the device.
+                    let precise_wheel = scroll.get_direction() == ScrollDirection::Smooth
+                        && scroll
+                            .get_source_device()
+                            .map(|device| device.get_source() == InputSource::Touchpad)
+                            .unwrap_or(false);
+
                     if let Some(wheel_delta) = wheel_delta {
                         let mouse_event = MouseEvent {
                             pos: Point::from(scroll.get_position()).to_dp(scale),
@@ -633,7 +847,8 @@ impl WindowBuilder {
                             count: 0,
                             focus: false,
                             button: MouseButton::None,
-                            wheel_delta
+                            wheel_delta,
+                            precise_wheel
                         };
 
                         state.with_handler(|h| h.wheel(&mouse_event));
@@ -708,9 +923,89 @@ impl WindowBuilder {
                 }
             }));
 
+        let clipboard = win_state.window.get_clipboard(&gdk::SELECTION_CLIPBOARD);
+        let owner_change =
+            clipboard.connect_owner_change(clone!(handle => move |_clipboard, _event| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.with_handler(|h| h.clipboard_changed());
+                }
+            }));
+        // the clipboard outlives the window, so we stop listening when it goes away.
+        let owner_change = Cell::new(Some(owner_change));
+
+        // Themes are switched by changing these settings, or the portal's color scheme.
+        let theme_handle = handle.clone();
+        let theme_changed = move || {
+            if let Some(state) = theme_handle.state.upgrade() {
+                let theme = Application::system_theme();
+                state.with_handler(|h| h.system_theme_changed(&theme));
+            }
+        };
+        let settings = gtk::Settings::get_default();
+        let theme_notify = settings.as_ref().map(|settings| {
+            let name_changed = theme_changed.clone();
+            let dark_changed = theme_changed.clone();
+            [
+                settings.connect_property_gtk_theme_name_notify(move |_| name_changed()),
+                settings.connect_property_gtk_application_prefer_dark_theme_notify(move |_| {
+                    dark_changed()
+                }),
+            ]
+        });
+        let theme_notify = Cell::new(theme_notify);
+        let color_scheme_watch = Cell::new(portal::ColorSchemeWatch::new(move |_| theme_changed()));
+
+        win_state
+            .window
+            .connect_configure_event(clone!(handle => move |window, _event| {
+                if let (Some(state), Some(gdk_window)) = (handle.state.upgrade(), window.get_window()) {
+                    let not_restored = gdk::WindowState::MAXIMIZED
+                        | gdk::WindowState::FULLSCREEN
+                        | gdk::WindowState::ICONIFIED
+                        | gdk::WindowState::TILED;
+                    if !gdk_window.get_state().intersects(not_restored) {
+                        state.restored_rect.set(Some(Rect::from_origin_size(
+                            handle.get_position(),
+                            handle.get_size(),
+                        )));
+                    }
+                    let monitor = window.get_display().get_monitor_at_window(&gdk_window);
+                    let previous = state.monitor.replace(monitor.clone());
+                    if let (Some(previous), Some(monitor)) = (previous, monitor) {
+                        if previous != monitor {
+                            let monitor = screen::translate_gdk_monitor(monitor);
+                            state.with_handler(|h| h.monitor_changed(&monitor));
+                        }
+                    }
+                }
+                false
+            }));
+        // the screen outlives the window too.
+        let gdk_screen = win_state.window.get_screen();
+        let monitors_changed = gdk_screen.as_ref().map(|gdk_screen| {
+            gdk_screen.connect_monitors_changed(clone!(handle => move |_screen| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.with_handler(|h| h.monitors_changed());
+                }
+            }))
+        });
+        let monitors_changed = Cell::new(monitors_changed);
+
         win_state
             .drawing_area
             .connect_destroy(clone!(handle => move |_widget| {
+                if let Some(id) = owner_change.take() {
+                    clipboard.disconnect(id);
+                }
+                if let (Some(gdk_screen), Some(id)) = (gdk_screen.as_ref(), monitors_changed.take()) {
+                    gdk_screen.disconnect(id);
+                }
+                if let (Some(settings), Some(ids)) = (settings.as_ref(), theme_notify.take()) {
+                    for id in ids {
+                        settings.disconnect(id);
+                    }
+                }
+                drop(color_scheme_watch.take());
                 if let Some(state) = handle.state.upgrade() {
                     state.with_handler(|h| h.destroy());
                 }
@@ -721,7 +1016,7 @@ impl WindowBuilder {
         win_state
             .drawing_area
             .get_window()
-            .expect("realize didn't create window")
+            .ok_or_else(|| anyhow!("realize didn't create window"))?
             .set_event_compression(false);
 
         if let Some(level) = self.level {
@@ -740,7 +1035,36 @@ impl WindowBuilder {
 }
 
 impl WindowState {
+    /// Show a file dialog, reporting failures to the handler.
+    fn file_dialog_path(&self, ty: FileDialogType, options: FileDialogOptions) -> Option<FileInfo> {
+        match dialog::get_file_dialog_path(self.window.upcast_ref(), ty, options) {
+            Ok(info) => info,
+            Err(e) => {
+                let error = ShellError::new(ShellOperation::FileDialog, e);
+                self.with_handler(|h| h.shell_error(error));
+                None
+            }
+        }
+    }
+
     #[track_caller]
+    /// Tell the handler about a drag from another application moving over the window, and tell
+    /// GTK whether it can be dropped there.
+    fn drag_over(&self, context: &gdk::DragContext, data: DropData, time: u32) {
+        let event = DropEvent {
+            pos: self.drop_pos.get(),
+            data,
+            mods: Modifiers::empty(),
+        };
+        let accepted = self.with_handler(|h| h.drag_over(&event)).unwrap_or(false);
+        let action = if accepted {
+            gdk::DragAction::COPY
+        } else {
+            gdk::DragAction::empty()
+        };
+        context.drag_status(action, time);
+    }
+
     fn with_handler<T, F: FnOnce(&mut dyn WinHandler) -> T>(&self, f: F) -> Option<T> {
         if self.invalid.try_borrow_mut().is_err() || self.surface.try_borrow_mut().is_err() {
             error!("other RefCells were borrowed when calling into the handler");
@@ -836,23 +1160,11 @@ impl WindowState {
         for op in queue {
             match op {
                 DeferredOp::Open(options, token) => {
-                    let file_info = dialog::get_file_dialog_path(
-                        self.window.upcast_ref(),
-                        FileDialogType::Open,
-                        options,
-                    )
-                    .ok()
-                    .map(|s| FileInfo { path: s.into() });
+                    let file_info = self.file_dialog_path(FileDialogType::Open, options);
                     self.with_handler(|h| h.open_file(token, file_info));
                 }
                 DeferredOp::SaveAs(options, token) => {
-                    let file_info = dialog::get_file_dialog_path(
-                        self.window.upcast_ref(),
-                        FileDialogType::Save,
-                        options,
-                    )
-                    .ok()
-                    .map(|s| FileInfo { path: s.into() });
+                    let file_info = self.file_dialog_path(FileDialogType::Save, options);
                     self.with_handler(|h| h.save_as(token, file_info));
                 }
                 DeferredOp::ContextMenu(menu, handle) => {
@@ -869,6 +1181,19 @@ impl WindowState {
     }
 }
 
+impl crate::platform::linux::WindowHandleExt for crate::WindowHandle {
+    fn open_uri(&self, uri: &str) {
+        if let Some(state) = self.0.state.upgrade() {
+            // this uses the OpenURI portal when we're sandboxed, with the
+            // window as the parent of any dialog it shows.
+            let time = gtk::get_current_event_time();
+            if let Err(err) = gtk::show_uri_on_window(Some(&state.window), uri, time) {
+                warn!("Failed to open {}: {}", uri, err);
+            }
+        }
+    }
+}
+
 impl WindowHandle {
     pub fn show(&self) {
         if let Some(state) = self.state.upgrade() {
@@ -904,6 +1229,15 @@ impl WindowHandle {
         }
     }
 
+    /// GTK doesn't tell where a window goes when it is restored, so this is where it was when it
+    /// was last configured while restored.
+    pub fn get_restored_rect(&self) -> Rect {
+        self.state
+            .upgrade()
+            .and_then(|state| state.restored_rect.get())
+            .unwrap_or_else(|| Rect::from_origin_size(self.get_position(), self.get_size()))
+    }
+
     /// The GTK implementation of content_insets differs from, e.g., the Windows one in that it
     /// doesn't try to account for window decorations. Depending on the platform, GTK might not
     /// even be aware of the size of the window decorations. And anyway, GTK's `Window::resize`
@@ -928,6 +1262,11 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_text_rendering(&self, options: TextRenderingOptions) {
+        crate::backend::shared::set_text_rendering(options);
+        self.invalidate();
+    }
+
     pub fn set_level(&self, level: WindowLevel) {
         if let Some(state) = self.state.upgrade() {
             let hint = match level {
@@ -935,9 +1274,16 @@ impl WindowHandle {
                 WindowLevel::Tooltip => WindowTypeHint::Tooltip,
                 WindowLevel::DropDown => WindowTypeHint::DropdownMenu,
                 WindowLevel::Modal => WindowTypeHint::Dialog,
+                WindowLevel::Floating => WindowTypeHint::Normal,
+                WindowLevel::Utility => WindowTypeHint::Utility,
             };
 
             state.window.set_type_hint(hint);
+            state.window.set_keep_above(matches!(
+                level,
+                WindowLevel::Floating | WindowLevel::Utility
+            ));
+            state.window.set_focus_on_map(level != WindowLevel::Utility);
         }
 
         self.set_override_redirect(level);
@@ -950,7 +1296,7 @@ impl WindowHandle {
     /// realized.
     fn set_override_redirect(&self, level: WindowLevel) {
         let override_redirect = match level {
-            WindowLevel::AppWindow => false,
+            WindowLevel::AppWindow | WindowLevel::Floating | WindowLevel::Utility => false,
             WindowLevel::Tooltip | WindowLevel::DropDown | WindowLevel::Modal => true,
         };
         if let Some(state) = self.state.upgrade() {
@@ -1011,8 +1357,80 @@ impl WindowHandle {
         Restored
     }
 
-    pub fn handle_titlebar(&self, _val: bool) {
-        warn!("WindowHandle::handle_titlebar is currently unimplemented for gtk.");
+    pub fn set_fullscreen(&self, fullscreen: bool, monitor: Option<&Monitor>) {
+        if let Some(state) = self.state.upgrade() {
+            if !fullscreen {
+                state.window.unfullscreen();
+                return;
+            }
+            let target = monitor.and_then(|monitor| {
+                let display = state.window.get_display();
+                let screen = state.window.get_screen()?;
+                let index = (0..display.get_n_monitors()).find(|&i| {
+                    display
+                        .get_monitor(i)
+                        .map(|m| screen::translate_gdk_monitor(m) == *monitor)
+                        .unwrap_or(false)
+                })?;
+                Some((screen, index))
+            });
+            match target {
+                Some((screen, index)) => state.window.fullscreen_on_monitor(&screen, index),
+                None => state.window.fullscreen(),
+            }
+        }
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.state
+            .upgrade()
+            .and_then(|state| state.window.get_window())
+            .map(|window| window.get_state().contains(gdk::WindowState::FULLSCREEN))
+            .unwrap_or(false)
+    }
+
+    pub fn handle_titlebar(&self, val: bool) {
+        if let Some(state) = self.state.upgrade() {
+            state.handle_titlebar.set(val);
+        }
+    }
+
+    pub fn set_maximize_button(&self, _rect: Option<Rect>) {
+        warn!("WindowHandle::set_maximize_button is currently unimplemented for gtk.");
+    }
+
+    pub fn begin_move_drag(&self) {
+        if let Some(state) = self.state.upgrade() {
+            if let Some((button, x, y, time)) = current_button_press() {
+                state.window.begin_move_drag(button, x, y, time);
+            }
+        }
+    }
+
+    pub fn begin_resize_drag(&self, edge: ResizeEdge) {
+        let edge = match edge {
+            ResizeEdge::Top => gdk::WindowEdge::North,
+            ResizeEdge::Bottom => gdk::WindowEdge::South,
+            ResizeEdge::Left => gdk::WindowEdge::West,
+            ResizeEdge::Right => gdk::WindowEdge::East,
+            ResizeEdge::TopLeft => gdk::WindowEdge::NorthWest,
+            ResizeEdge::TopRight => gdk::WindowEdge::NorthEast,
+            ResizeEdge::BottomLeft => gdk::WindowEdge::SouthWest,
+            ResizeEdge::BottomRight => gdk::WindowEdge::SouthEast,
+        };
+        if let Some(state) = self.state.upgrade() {
+            if let Some((button, x, y, time)) = current_button_press() {
+                state.window.begin_resize_drag(edge, button, x, y, time);
+            }
+        }
+    }
+
+    pub fn set_dark_titlebar(&self, _dark: bool) {
+        warn!("WindowHandle::set_dark_titlebar is currently unimplemented for gtk.");
+    }
+
+    pub fn set_corners(&self, _corners: WindowCorners) {
+        warn!("WindowHandle::set_corners is currently unimplemented for gtk.");
     }
 
     /// Close the window.
@@ -1116,20 +1534,7 @@ impl WindowHandle {
     pub fn make_cursor(&self, desc: &CursorDesc) -> Option<Cursor> {
         if let Some(state) = self.state.upgrade() {
             if let Some(gdk_window) = state.window.get_window() {
-                // TODO: gtk::Pixbuf expects unpremultiplied alpha. We should convert.
-                let has_alpha = !matches!(desc.image.format(), ImageFormat::Rgb);
-                let bytes_per_pixel = desc.image.format().bytes_per_pixel();
-                let pixbuf = gdk_pixbuf::Pixbuf::from_mut_slice(
-                    desc.image.raw_pixels().to_owned(),
-                    gdk_pixbuf::Colorspace::Rgb,
-                    has_alpha,
-                    // bits_per_sample
-                    8,
-                    desc.image.width() as i32,
-                    desc.image.height() as i32,
-                    // row stride (in bytes)
-                    (desc.image.width() * bytes_per_pixel) as i32,
-                );
+                let pixbuf = util::make_pixbuf(&desc.image);
                 let c = gdk::Cursor::from_pixbuf(
                     &gdk_window.get_display(),
                     &pixbuf,
@@ -1174,15 +1579,25 @@ impl WindowHandle {
     }
 
     /// Get the `Scale` of the window.
-    pub fn get_scale(&self) -> Result<Scale, ShellError> {
+    pub fn get_scale(&self) -> Result<Scale, Error> {
         Ok(self
             .state
             .upgrade()
-            .ok_or(ShellError::WindowDropped)?
+            .ok_or(Error::WindowDropped)?
             .scale
             .get())
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let state = self.state.upgrade()?;
+        let gdk_window = state.window.get_window()?;
+        state
+            .window
+            .get_display()
+            .get_monitor_at_window(&gdk_window)
+            .map(screen::translate_gdk_monitor)
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         if let Some(state) = self.state.upgrade() {
             let window = &state.window;
@@ -1292,7 +1707,6 @@ fn make_gdk_cursor(cursor: &Cursor, gdk_window: &gdk::Window) -> Option<gdk::Cur
     } else {
         gdk::Cursor::from_name(
             &gdk_window.get_display(),
-            #[allow(deprecated)]
             match cursor {
                 // cursor name values from https://www.w3.org/TR/css-ui-3/#cursor
                 Cursor::Arrow => "default",
@@ -1300,15 +1714,33 @@ fn make_gdk_cursor(cursor: &Cursor, gdk_window: &gdk::Window) -> Option<gdk::Cur
                 Cursor::Pointer => "pointer",
                 Cursor::Crosshair => "crosshair",
                 Cursor::OpenHand => "grab",
+                Cursor::ClosedHand => "grabbing",
                 Cursor::NotAllowed => "not-allowed",
                 Cursor::ResizeLeftRight => "ew-resize",
                 Cursor::ResizeUpDown => "ns-resize",
+                Cursor::ResizeNwSe => "nwse-resize",
+                Cursor::ResizeNeSw => "nesw-resize",
+                Cursor::Move => "move",
+                Cursor::ZoomIn => "zoom-in",
+                Cursor::ZoomOut => "zoom-out",
+                Cursor::Wait => "wait",
+                Cursor::Progress => "progress",
+                Cursor::Help => "help",
                 Cursor::Custom(_) => unreachable!(),
             },
         )
     }
 }
 
+/// The button, root coordinates and time of the button press being handled,
+/// which GTK needs to start a move or resize of the window.
+fn current_button_press() -> Option<(i32, i32, i32, u32)> {
+    let event = gtk::get_current_event()?;
+    let button = event.get_button()?;
+    let (x, y) = event.get_root_coords()?;
+    Some((button as i32, x as i32, y as i32, event.get_time()))
+}
+
 fn get_mouse_button(button: u32) -> Option<MouseButton> {
     match button {
         1 => Some(MouseButton::Left),
@@ -1344,6 +1776,70 @@ fn get_mouse_buttons_from_modifiers(modifiers: gdk::ModifierType) -> MouseButton
     buttons
 }
 
+/// The files, URLs or text of a drag from another application.
+fn get_drop_data(selection: &gtk::SelectionData) -> DropData {
+    let mut data = DropData::default();
+    for uri in selection.get_uris() {
+        match glib::filename_from_uri(&uri) {
+            Ok((path, _)) => data.files.push(path),
+            Err(_) => data.urls.push(uri.into()),
+        }
+    }
+    if data.files.is_empty() && data.urls.is_empty() {
+        data.text = selection.get_text().map(String::from);
+    }
+    data
+}
+
+/// The pen event of an event from a pen, or `None` if the event comes from
+/// another device.
+fn get_pen_event(
+    event: &gdk::Event,
+    pos: Point,
+    phase: PenPhase,
+    state: ModifierType,
+) -> Option<PenEvent> {
+    let eraser = match event.get_source_device()?.get_source() {
+        gdk::InputSource::Pen => false,
+        gdk::InputSource::Eraser => true,
+        _ => return None,
+    };
+    let touching = match phase {
+        PenPhase::Down => true,
+        PenPhase::Move => state.contains(ModifierType::BUTTON1_MASK),
+        PenPhase::Up => false,
+    };
+    let pressure = if touching {
+        event.get_axis(gdk::AxisUse::Pressure).unwrap_or(0.5)
+    } else {
+        0.0
+    };
+    // GDK reports the tilt from -1 to 1
+    let tilt = Vec2::new(
+        event.get_axis(gdk::AxisUse::Xtilt).unwrap_or(0.0),
+        event.get_axis(gdk::AxisUse::Ytilt).unwrap_or(0.0),
+    ) * 90.0;
+    Some(PenEvent {
+        pos,
+        phase,
+        pressure,
+        tilt,
+        barrel_button: state.intersects(ModifierType::BUTTON2_MASK | ModifierType::BUTTON3_MASK),
+        eraser,
+        mods: get_modifiers(state),
+    })
+}
+
+/// The sequence of a touch event is an opaque pointer that stays the same
+/// until the contact is lifted.
+fn get_touch_id(touch: &gdk::EventTouch) -> TouchId {
+    use glib::translate::ToGlibPtr;
+
+    let sequence = touch.get_event_sequence();
+    let ptr: *const gdk_sys::GdkEventSequence = sequence.to_glib_none().0;
+    TouchId::from_raw(ptr as usize as u64)
+}
+
 fn get_mouse_click_count(event_type: gdk::EventType) -> u8 {
     match event_type {
         gdk::EventType::ButtonPress => 1,
@@ -1405,6 +1901,7 @@ fn make_key_event(key: &EventKey, repeat: bool, state: KeyState) -> KeyEvent {
         state,
         key,
         code,
+        scancode: hardware_keycode as u32,
         location,
         mods,
         repeat,
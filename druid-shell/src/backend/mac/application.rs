@@ -16,22 +16,28 @@
 
 #![allow(non_upper_case_globals)]
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ffi::c_void;
+use std::path::Path;
 use std::rc::Rc;
 
-use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular};
-use cocoa::base::{id, nil, NO, YES};
-use cocoa::foundation::{NSArray, NSAutoreleasePool};
+use cocoa::appkit::{CGFloat, NSApp, NSApplication, NSApplicationActivationPolicyRegular};
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSArray, NSAutoreleasePool, NSInteger, NSUInteger};
 use lazy_static::lazy_static;
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{class, msg_send, sel, sel_impl};
 
+use crate::appearance::SystemTheme;
 use crate::application::AppHandler;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::notification::{DesktopNotification, NotificationToken};
+use crate::piet::Color;
 
 use super::clipboard::Clipboard;
 use super::error::Error;
+use super::global_hotkey;
 use super::util;
 
 static APP_HANDLER_IVAR: &str = "druidAppHandler";
@@ -46,6 +52,38 @@ struct State {
     quitting: bool,
 }
 
+/// Where we are in answering `applicationShouldTerminate:`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Termination {
+    /// The system isn't asking us to terminate.
+    Idle,
+    /// The handler is being asked, and may answer before it returns.
+    Asking,
+    /// The handler answered while it was being asked.
+    Answered(bool),
+    /// We returned `NSTerminateLater`, and still owe the system a reply.
+    Waiting,
+}
+
+thread_local! {
+    static TERMINATION: Cell<Termination> = Cell::new(Termination::Idle);
+}
+
+/// Answer a pending `applicationShouldTerminate:`, if there is one.
+fn answer_termination(ns_app: id, terminate: bool) {
+    match TERMINATION.with(Cell::get) {
+        Termination::Asking => TERMINATION.with(|t| t.set(Termination::Answered(terminate))),
+        Termination::Waiting => {
+            TERMINATION.with(|t| t.set(Termination::Idle));
+            let reply = if terminate { YES } else { NO };
+            unsafe {
+                let () = msg_send![ns_app, replyToApplicationShouldTerminate: reply];
+            }
+        }
+        Termination::Idle | Termination::Answered(_) => (),
+    }
+}
+
 impl Application {
     pub fn new() -> Result<Application, Error> {
         // macOS demands that we run not just on one thread,
@@ -60,6 +98,127 @@ impl Application {
         }
     }
 
+    pub fn show_message_dialog(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+        unsafe {
+            let _pool = NSAutoreleasePool::new(nil);
+            let alert: id = msg_send![class!(NSAlert), new];
+            let () = msg_send![alert, setMessageText: util::make_nsstring(title)];
+            let () = msg_send![alert, setInformativeText: util::make_nsstring(message)];
+            let _: id = msg_send![alert, addButtonWithTitle: util::make_nsstring(confirm)];
+            let _: id = msg_send![alert, addButtonWithTitle: util::make_nsstring(cancel)];
+            // NSAlertFirstButtonReturn
+            let response: isize = msg_send![alert, runModal];
+            let () = msg_send![alert, release];
+            response == 1000
+        }
+    }
+
+    pub fn show_notification(
+        &self,
+        notification: &DesktopNotification,
+        token: NotificationToken,
+    ) -> Result<(), crate::Error> {
+        unsafe {
+            let _pool = NSAutoreleasePool::new(nil);
+            let center: id = msg_send![
+                class!(NSUserNotificationCenter),
+                defaultUserNotificationCenter
+            ];
+            // There is no notification center for apps outside of a bundle.
+            if center == nil {
+                return Err(crate::Error::Unsupported);
+            }
+            // The app delegate receives clicks on notifications.
+            let delegate: id = msg_send![self.ns_app, delegate];
+            let () = msg_send![center, setDelegate: delegate];
+
+            let ns_notification: id = msg_send![class!(NSUserNotification), new];
+            let () = msg_send![ns_notification, setTitle: util::make_nsstring(&notification.title)];
+            let () = msg_send![
+                ns_notification,
+                setInformativeText: util::make_nsstring(&notification.body)
+            ];
+            let identifier = util::make_nsstring(&token.into_raw().to_string());
+            let () = msg_send![ns_notification, setIdentifier: identifier];
+            if let Some(icon) = &notification.icon {
+                let image = util::make_nsimage(icon);
+                if image != nil {
+                    let () = msg_send![ns_notification, setContentImage: image];
+                    let () = msg_send![image, release];
+                }
+            }
+            match notification.actions.split_first() {
+                Some((first, others)) => {
+                    let () = msg_send![ns_notification, setHasActionButton: YES];
+                    let () = msg_send![
+                        ns_notification,
+                        setActionButtonTitle: util::make_nsstring(first)
+                    ];
+                    // The other actions are listed in a dropdown on the action button.
+                    let others: Vec<id> = others
+                        .iter()
+                        .enumerate()
+                        .map(|(i, label)| {
+                            msg_send![class!(NSUserNotificationAction),
+                                actionWithIdentifier: util::make_nsstring(&(i + 1).to_string())
+                                title: util::make_nsstring(label)]
+                        })
+                        .collect();
+                    if !others.is_empty() {
+                        let others = NSArray::arrayWithObjects(nil, &others);
+                        let () = msg_send![ns_notification, setAdditionalActions: others];
+                    }
+                }
+                None => {
+                    let () = msg_send![ns_notification, setHasActionButton: NO];
+                }
+            }
+            let () = msg_send![center, deliverNotification: ns_notification];
+            let () = msg_send![ns_notification, release];
+            Ok(())
+        }
+    }
+
+    pub fn open_url(&self, url: &str) -> Result<(), crate::Error> {
+        unsafe {
+            let _pool = NSAutoreleasePool::new(nil);
+            let ns_url: id = msg_send![class!(NSURL), URLWithString: util::make_nsstring(url)];
+            if ns_url == nil {
+                return Err(anyhow::anyhow!("invalid URL: {}", url).into());
+            }
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let opened: BOOL = msg_send![workspace, openURL: ns_url];
+            if opened == NO {
+                return Err(anyhow::anyhow!("no application can open {}", url).into());
+            }
+            Ok(())
+        }
+    }
+
+    pub fn reveal_path(&self, path: &Path) -> Result<(), crate::Error> {
+        unsafe {
+            let _pool = NSAutoreleasePool::new(nil);
+            let path = util::make_nsstring(&path.to_string_lossy());
+            let ns_url: id = msg_send![class!(NSURL), fileURLWithPath: path];
+            let urls = NSArray::arrayWithObject(nil, ns_url);
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let () = msg_send![workspace, activateFileViewerSelectingURLs: urls];
+            Ok(())
+        }
+    }
+
+    pub fn register_global_hotkey(
+        &self,
+        hotkey: &HotKey,
+        token: GlobalHotKeyToken,
+    ) -> Result<(), crate::Error> {
+        global_hotkey::register(hotkey, token)
+    }
+
+    pub fn unregister_global_hotkey(&self, token: GlobalHotKeyToken) {
+        global_hotkey::unregister(token)
+    }
+
     pub fn run(self, handler: Option<Box<dyn AppHandler>>) {
         unsafe {
             // Initialize the application delegate
@@ -80,6 +239,9 @@ impl Application {
     }
 
     pub fn quit(&self) {
+        // If the system asked us to terminate, let it; this also lets a
+        // logout or shutdown carry on.
+        answer_termination(self.ns_app, true);
         if let Ok(mut state) = self.state.try_borrow_mut() {
             if !state.quitting {
                 state.quitting = true;
@@ -101,10 +263,28 @@ impl Application {
         }
     }
 
+    pub fn cancel_quit(&self) {
+        answer_termination(self.ns_app, false);
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard
     }
 
+    pub fn system_theme() -> SystemTheme {
+        unsafe {
+            let _pool = NSAutoreleasePool::new(nil);
+            let defaults: id = msg_send![class!(NSUserDefaults), standardUserDefaults];
+            let key = util::make_nsstring("AppleInterfaceStyle");
+            let style: id = msg_send![defaults, stringForKey: key];
+            let dark = style != nil && util::from_nsstring(style) == "Dark";
+            SystemTheme {
+                dark,
+                accent_color: accent_color(),
+            }
+        }
+    }
+
     pub fn get_locale() -> String {
         unsafe {
             let nslocale_class = class!(NSLocale);
@@ -140,6 +320,33 @@ impl crate::platform::mac::ApplicationExt for crate::Application {
             NSApp().setMainMenu_(menu.0.menu);
         }
     }
+
+    fn show_about_panel(&self, name: &str, version: &str, credits: &str) {
+        unsafe {
+            let credits_text: id = msg_send![class!(NSAttributedString), alloc];
+            let credits_text: id =
+                msg_send![credits_text, initWithString: util::make_nsstring(credits)];
+            let keys = NSArray::arrayWithObjects(
+                nil,
+                &[
+                    util::make_nsstring("ApplicationName"),
+                    util::make_nsstring("ApplicationVersion"),
+                    util::make_nsstring("Credits"),
+                ],
+            );
+            let values = NSArray::arrayWithObjects(
+                nil,
+                &[
+                    util::make_nsstring(name),
+                    util::make_nsstring(version),
+                    credits_text,
+                ],
+            );
+            let options: id =
+                msg_send![class!(NSDictionary), dictionaryWithObjects: values forKeys: keys];
+            let () = msg_send![self.backend_app.ns_app, orderFrontStandardAboutPanelWithOptions: options];
+        }
+    }
 }
 
 struct DelegateState {
@@ -152,6 +359,53 @@ impl DelegateState {
             inner.command(command)
         }
     }
+
+    fn tray_activated(&mut self) {
+        if let Some(inner) = self.handler.as_mut() {
+            inner.tray_activated()
+        }
+    }
+
+    fn notification_activated(&mut self, token: NotificationToken, action: Option<usize>) {
+        if let Some(inner) = self.handler.as_mut() {
+            inner.notification_activated(token, action)
+        }
+    }
+
+    fn global_hotkey(&mut self, token: GlobalHotKeyToken) {
+        if let Some(inner) = self.handler.as_mut() {
+            inner.global_hotkey(token)
+        }
+    }
+
+    fn request_quit(&mut self) {
+        match self.handler.as_mut() {
+            Some(inner) => inner.request_quit(),
+            None => {
+                if let Some(app) = crate::Application::try_global() {
+                    app.quit();
+                }
+            }
+        }
+    }
+}
+
+/// The accent color chosen in the system preferences.
+unsafe fn accent_color() -> Option<Color> {
+    // `controlAccentColor` is new in macOS 10.14.
+    let responds: BOOL = msg_send![class!(NSColor), respondsToSelector: sel!(controlAccentColor)];
+    if responds == NO {
+        return None;
+    }
+    let color: id = msg_send![class!(NSColor), controlAccentColor];
+    let color_space: id = msg_send![class!(NSColorSpace), sRGBColorSpace];
+    let color: id = msg_send![color, colorUsingColorSpace: color_space];
+    if color == nil {
+        return None;
+    }
+    let (mut r, mut g, mut b, mut a): (CGFloat, CGFloat, CGFloat, CGFloat) = (0., 0., 0., 0.);
+    let () = msg_send![color, getRed: &mut r green: &mut g blue: &mut b alpha: &mut a];
+    Some(Color::rgba(r, g, b, a))
 }
 
 struct AppDelegate(*const Class);
@@ -172,6 +426,30 @@ lazy_static! {
             sel!(handleMenuItem:),
             handle_menu_item as extern "C" fn(&mut Object, Sel, id),
         );
+
+        decl.add_method(
+            sel!(handleStatusItem:),
+            handle_status_item as extern "C" fn(&mut Object, Sel, id),
+        );
+
+        decl.add_method(
+            sel!(userNotificationCenter:didActivateNotification:),
+            did_activate_notification as extern "C" fn(&mut Object, Sel, id, id),
+        );
+        decl.add_method(
+            sel!(userNotificationCenter:shouldPresentNotification:),
+            should_present_notification as extern "C" fn(&mut Object, Sel, id, id) -> BOOL,
+        );
+
+        decl.add_method(
+            sel!(applicationShouldTerminate:),
+            application_should_terminate as extern "C" fn(&mut Object, Sel, id) -> NSUInteger,
+        );
+
+        decl.add_method(
+            sel!(handleGlobalHotKey:),
+            handle_global_hotkey as extern "C" fn(&mut Object, Sel, u64),
+        );
         AppDelegate(decl.register())
     };
 }
@@ -195,3 +473,89 @@ extern "C" fn handle_menu_item(this: &mut Object, _: Sel, item: id) {
         (*inner).command(tag as u32);
     }
 }
+
+/// This handles clicks on tray icons without a menu.
+extern "C" fn handle_status_item(this: &mut Object, _: Sel, _sender: id) {
+    unsafe {
+        let inner: *mut c_void = *this.get_ivar(APP_HANDLER_IVAR);
+        let inner = &mut *(inner as *mut DelegateState);
+        (*inner).tray_activated();
+    }
+}
+
+/// This handles clicks on notifications and their actions.
+extern "C" fn did_activate_notification(this: &mut Object, _: Sel, center: id, notification: id) {
+    unsafe {
+        let identifier: id = msg_send![notification, identifier];
+        let token = match util::from_nsstring(identifier).parse() {
+            Ok(raw) => NotificationToken::from_raw(raw),
+            Err(_) => return,
+        };
+        let activation_type: NSInteger = msg_send![notification, activationType];
+        let action = match activation_type {
+            // NSUserNotificationActivationTypeActionButtonClicked
+            2 => Some(0),
+            // NSUserNotificationActivationTypeAdditionalActionClicked
+            4 => {
+                let action: id = msg_send![notification, additionalActivationAction];
+                let identifier: id = msg_send![action, identifier];
+                util::from_nsstring(identifier).parse().ok()
+            }
+            _ => None,
+        };
+        let () = msg_send![center, removeDeliveredNotification: notification];
+        let inner: *mut c_void = *this.get_ivar(APP_HANDLER_IVAR);
+        let inner = &mut *(inner as *mut DelegateState);
+        (*inner).notification_activated(token, action);
+    }
+}
+
+/// This handles system-wide hotkeys, forwarded by the Carbon event handler.
+extern "C" fn handle_global_hotkey(this: &mut Object, _: Sel, token: u64) {
+    unsafe {
+        let inner: *mut c_void = *this.get_ivar(APP_HANDLER_IVAR);
+        let inner = &mut *(inner as *mut DelegateState);
+        (*inner).global_hotkey(GlobalHotKeyToken::from_raw(token));
+    }
+}
+
+/// This handles quit requests from outside the application, such as from the
+/// Dock or when the user logs out.
+///
+/// The handler may need to ask the user first, so unless it answers right
+/// away we return `NSTerminateLater`, and reply once it calls
+/// `Application::quit` or `Application::cancel_quit`.
+extern "C" fn application_should_terminate(this: &mut Object, _: Sel, _sender: id) -> NSUInteger {
+    if TERMINATION.with(Cell::get) != Termination::Idle {
+        // already asked, the pending answer covers this request too.
+        // NSTerminateLater
+        return 2;
+    }
+    TERMINATION.with(|t| t.set(Termination::Asking));
+    unsafe {
+        let inner: *mut c_void = *this.get_ivar(APP_HANDLER_IVAR);
+        let inner = &mut *(inner as *mut DelegateState);
+        (*inner).request_quit();
+    }
+    match TERMINATION.with(|t| t.replace(Termination::Idle)) {
+        // NSTerminateNow
+        Termination::Answered(true) => 1,
+        // NSTerminateCancel
+        Termination::Answered(false) => 0,
+        _ => {
+            TERMINATION.with(|t| t.set(Termination::Waiting));
+            // NSTerminateLater
+            2
+        }
+    }
+}
+
+/// Show notifications even while the application is active.
+extern "C" fn should_present_notification(
+    _this: &mut Object,
+    _: Sel,
+    _center: id,
+    _notification: id,
+) -> BOOL {
+    YES
+}
@@ -14,24 +14,30 @@
 
 //! Interactions with the system pasteboard on macOS.
 
+use std::path::PathBuf;
+
+use anyhow::anyhow;
 use cocoa::appkit::NSPasteboardTypeString;
-use cocoa::base::{id, nil, BOOL, YES};
-use cocoa::foundation::{NSArray, NSInteger, NSUInteger};
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSArray, NSInteger, NSPoint, NSRect, NSSize, NSUInteger};
 use objc::{class, msg_send, sel, sel_impl};
 
 use super::util;
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::dnd::DropData;
+use crate::piet::{ImageBuf, ImageFormat};
+use crate::Error;
 
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
 
 impl Clipboard {
     /// Put a string onto the system clipboard.
-    pub fn put_string(&mut self, s: impl AsRef<str>) {
+    pub fn put_string(&mut self, s: impl AsRef<str>) -> Result<(), Error> {
         let s = s.as_ref();
-        put_string_impl(s);
+        return put_string_impl(s);
 
-        fn put_string_impl(s: &str) {
+        fn put_string_impl(s: &str) -> Result<(), Error> {
             unsafe {
                 let nsstring = util::make_nsstring(s);
                 let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
@@ -39,14 +45,15 @@ impl Clipboard {
                 let result: BOOL =
                     msg_send![pasteboard, setString: nsstring forType: NSPasteboardTypeString];
                 if result != YES {
-                    tracing::warn!("failed to set clipboard");
+                    return Err(anyhow!("failed to set clipboard").into());
                 }
+                Ok(())
             }
         }
     }
 
     /// Put multi-format data on the system clipboard.
-    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) {
+    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) -> Result<(), Error> {
         unsafe {
             let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
             let _: NSInteger = msg_send![pasteboard, clearContents];
@@ -61,13 +68,107 @@ impl Clipboard {
                 let data_type = idents[i];
                 let result: BOOL = msg_send![pasteboard, setData: data forType: data_type];
                 if result != YES {
-                    tracing::warn!(
+                    return Err(anyhow!(
                         "failed to set clipboard contents for type '{}'",
                         format.identifier
-                    );
+                    )
+                    .into());
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Put an image onto the system clipboard.
+    pub fn put_image(&mut self, image: &ImageBuf) -> Result<(), Error> {
+        let nsimage = util::make_nsimage(image);
+        if nsimage == nil {
+            return Err(anyhow!("cannot put an empty image on the clipboard").into());
+        }
+        let result = write_objects(&[nsimage]);
+        unsafe {
+            let () = msg_send![nsimage, release];
+        }
+        result
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let source: id = msg_send![class!(NSImageRep), imageRepWithPasteboard: pasteboard];
+            if source == nil {
+                return None;
+            }
+            let width: NSInteger = msg_send![source, pixelsWide];
+            let height: NSInteger = msg_send![source, pixelsHigh];
+            if width <= 0 || height <= 0 {
+                return None;
+            }
+            let (width, height) = (width as usize, height as usize);
+            // Draw the image into a bitmap whose layout we know. Bitmap graphics
+            // contexts only support premultiplied alpha.
+            let rep: id = msg_send![class!(NSBitmapImageRep), alloc];
+            let rep: id = msg_send![rep,
+                initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>()
+                pixelsWide: width as NSInteger
+                pixelsHigh: height as NSInteger
+                bitsPerSample: 8 as NSInteger
+                samplesPerPixel: 4 as NSInteger
+                hasAlpha: YES
+                isPlanar: NO
+                colorSpaceName: util::make_nsstring("NSDeviceRGBColorSpace")
+                bytesPerRow: (width * 4) as NSInteger
+                bitsPerPixel: 32 as NSInteger
+            ];
+            if rep == nil {
+                return None;
+            }
+            let context: id =
+                msg_send![class!(NSGraphicsContext), graphicsContextWithBitmapImageRep: rep];
+            let () = msg_send![class!(NSGraphicsContext), saveGraphicsState];
+            let () = msg_send![class!(NSGraphicsContext), setCurrentContext: context];
+            let rect = NSRect::new(
+                NSPoint::new(0.0, 0.0),
+                NSSize::new(width as f64, height as f64),
+            );
+            let _: BOOL = msg_send![source, drawInRect: rect];
+            let () = msg_send![class!(NSGraphicsContext), restoreGraphicsState];
+            let bitmap: *const u8 = msg_send![rep, bitmapData];
+            let pixels = std::slice::from_raw_parts(bitmap, width * height * 4).to_vec();
+            let () = msg_send![rep, release];
+            Some(ImageBuf::from_raw(
+                pixels,
+                ImageFormat::RgbaPremul,
+                width,
+                height,
+            ))
+        }
+    }
+
+    /// Put a list of files onto the system clipboard.
+    pub fn put_files(&mut self, paths: &[PathBuf]) -> Result<(), Error> {
+        let urls = paths
+            .iter()
+            .map(|path| unsafe {
+                let path = util::make_nsstring(&path.to_string_lossy());
+                msg_send![class!(NSURL), fileURLWithPath: path]
+            })
+            .collect::<Vec<id>>();
+        write_objects(&urls)
+    }
+
+    /// Get the list of files on the system clipboard, if there is one.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        let files = unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            drop_data(pasteboard).files
+        };
+        if files.is_empty() {
+            None
+        } else {
+            Some(files)
+        }
     }
 
     /// Get a string from the system clipboard, if one is available.
@@ -138,3 +239,56 @@ impl Clipboard {
         }
     }
 }
+
+/// Replace the contents of the general pasteboard with objects that know how
+/// to write themselves to it, such as images and URLs.
+fn write_objects(objects: &[id]) -> Result<(), Error> {
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let _: NSInteger = msg_send![pasteboard, clearContents];
+        let array = NSArray::arrayWithObjects(nil, objects);
+        let result: BOOL = msg_send![pasteboard, writeObjects: array];
+        if result != YES {
+            return Err(anyhow!("failed to set clipboard").into());
+        }
+        Ok(())
+    }
+}
+
+/// The change count of the general pasteboard, which increases whenever its
+/// contents change.
+pub(crate) fn change_count() -> NSInteger {
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        msg_send![pasteboard, changeCount]
+    }
+}
+
+/// The files, URLs or text on the pasteboard of a drag.
+pub(crate) fn drop_data(pasteboard: id) -> DropData {
+    let mut data = DropData::default();
+    unsafe {
+        let classes = NSArray::arrayWithObjects(nil, &[class!(NSURL) as *const _ as id]);
+        let urls: id = msg_send![pasteboard, readObjectsForClasses: classes options: nil];
+        if urls != nil {
+            for i in 0..urls.count() {
+                let url = urls.objectAtIndex(i);
+                let is_file: BOOL = msg_send![url, isFileURL];
+                if is_file != NO {
+                    let path: id = msg_send![url, path];
+                    data.files.push(util::from_nsstring(path).into());
+                } else {
+                    let string: id = msg_send![url, absoluteString];
+                    data.urls.push(util::from_nsstring(string));
+                }
+            }
+        }
+        if data.files.is_empty() && data.urls.is_empty() {
+            let string: id = msg_send![pasteboard, stringForType: NSPasteboardTypeString];
+            if string != nil {
+                data.text = Some(util::from_nsstring(string));
+            }
+        }
+    }
+    data
+}
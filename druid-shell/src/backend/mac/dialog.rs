@@ -16,26 +16,28 @@
 
 #![allow(non_upper_case_globals, clippy::upper_case_acronyms)]
 
-use std::ffi::OsString;
-
 use cocoa::base::{id, nil, NO, YES};
 use cocoa::foundation::{NSArray, NSAutoreleasePool, NSInteger, NSURL};
 use objc::{class, msg_send, sel, sel_impl};
 
 use super::util::{from_nsstring, make_nsstring};
-use crate::dialog::{FileDialogOptions, FileDialogType};
+use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
 
 pub(crate) type NSModalResponse = NSInteger;
 const NSModalResponseOK: NSInteger = 1;
 const NSModalResponseCancel: NSInteger = 0;
 
-pub(crate) unsafe fn get_path(panel: id, result: NSModalResponse) -> Option<OsString> {
+pub(crate) unsafe fn get_file_info(
+    panel: id,
+    result: NSModalResponse,
+    options: &FileDialogOptions,
+) -> Option<FileInfo> {
     match result {
         NSModalResponseOK => {
             let url: id = msg_send![panel, URL];
             let path: id = msg_send![url, path];
-            let path: OsString = from_nsstring(path).into();
-            Some(path)
+            // The panel has no way to pick a type; it's deduced from the extension.
+            Some(FileInfo::chosen(from_nsstring(path).into(), None, options))
         }
         NSModalResponseCancel => None,
         _ => unreachable!(),
@@ -70,6 +72,9 @@ pub(crate) unsafe fn build_panel(ty: FileDialogType, mut options: FileDialogOpti
             // that match the filters as if they were directories too.
             set_type_filter = !options.packages_as_directories;
         }
+        if options.select_files_and_directories && !options.select_directories {
+            let () = msg_send![panel, setCanChooseDirectories: YES];
+        }
         if options.multi_selection {
             let () = msg_send![panel, setAllowsMultipleSelection: YES];
         }
@@ -100,7 +105,11 @@ pub(crate) unsafe fn build_panel(ty: FileDialogType, mut options: FileDialogOpti
         let () = msg_send![panel, setPrompt: make_nsstring(text)];
     }
 
-    if let Some(path) = &options.starting_directory {
+    let starting_directory = options
+        .starting_directory
+        .clone()
+        .or_else(|| options.remembered_directory());
+    if let Some(path) = &starting_directory {
         if let Some(path) = path.to_str() {
             let url = NSURL::alloc(nil)
                 .initFileURLWithPath_isDirectory_(make_nsstring(path), YES)
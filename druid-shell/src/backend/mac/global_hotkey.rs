@@ -0,0 +1,207 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! System-wide hotkeys.
+//!
+//! AppKit has no API for these, so we use the hotkeys of the Carbon event manager.
+
+#![allow(non_upper_case_globals)]
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::mem;
+use std::ptr::null_mut;
+
+use cocoa::appkit::NSApp;
+use cocoa::base::{id, nil};
+use objc::{msg_send, sel, sel_impl};
+
+use super::keyboard::key_to_key_code;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::keyboard::Modifiers;
+use crate::Error;
+
+type OSStatus = i32;
+type EventRef = *mut c_void;
+type EventTargetRef = *mut c_void;
+type EventHandlerCallRef = *mut c_void;
+type EventHotKeyRef = *mut c_void;
+type EventHandlerProcPtr = extern "C" fn(EventHandlerCallRef, EventRef, *mut c_void) -> OSStatus;
+
+#[repr(C)]
+struct EventHotKeyID {
+    signature: u32,
+    id: u32,
+}
+
+#[repr(C)]
+struct EventTypeSpec {
+    event_class: u32,
+    event_kind: u32,
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn GetApplicationEventTarget() -> EventTargetRef;
+    fn InstallEventHandler(
+        target: EventTargetRef,
+        handler: EventHandlerProcPtr,
+        num_types: u32,
+        list: *const EventTypeSpec,
+        user_data: *mut c_void,
+        out_ref: *mut *mut c_void,
+    ) -> OSStatus;
+    fn RegisterEventHotKey(
+        key_code: u32,
+        modifiers: u32,
+        id: EventHotKeyID,
+        target: EventTargetRef,
+        options: u32,
+        out_ref: *mut EventHotKeyRef,
+    ) -> OSStatus;
+    fn UnregisterEventHotKey(hotkey: EventHotKeyRef) -> OSStatus;
+    fn GetEventParameter(
+        event: EventRef,
+        name: u32,
+        desired_type: u32,
+        actual_type: *mut u32,
+        buffer_size: usize,
+        actual_size: *mut usize,
+        data: *mut c_void,
+    ) -> OSStatus;
+}
+
+const fn four_char_code(code: &[u8; 4]) -> u32 {
+    (code[0] as u32) << 24 | (code[1] as u32) << 16 | (code[2] as u32) << 8 | code[3] as u32
+}
+
+const kEventClassKeyboard: u32 = four_char_code(b"keyb");
+const kEventHotKeyPressed: u32 = 5;
+const kEventParamDirectObject: u32 = four_char_code(b"----");
+const typeEventHotKeyID: u32 = four_char_code(b"hkid");
+const eventHotKeyExistsErr: OSStatus = -9878;
+
+const cmdKey: u32 = 1 << 8;
+const shiftKey: u32 = 1 << 9;
+const optionKey: u32 = 1 << 11;
+const controlKey: u32 = 1 << 12;
+
+/// Marks the hotkeys registered by druid.
+const SIGNATURE: u32 = four_char_code(b"drud");
+
+thread_local! {
+    static HOTKEYS: RefCell<HashMap<u32, EventHotKeyRef>> = RefCell::new(HashMap::new());
+    static HANDLER_INSTALLED: Cell<bool> = Cell::new(false);
+}
+
+pub(crate) fn register(hotkey: &HotKey, token: GlobalHotKeyToken) -> Result<(), Error> {
+    let key_code = key_to_key_code(&hotkey.key).ok_or(Error::Unsupported)?;
+    let key_mods: Modifiers = hotkey.mods.into();
+    let mut modifiers = 0;
+    if key_mods.meta() {
+        modifiers |= cmdKey;
+    }
+    if key_mods.shift() {
+        modifiers |= shiftKey;
+    }
+    if key_mods.alt() {
+        modifiers |= optionKey;
+    }
+    if key_mods.ctrl() {
+        modifiers |= controlKey;
+    }
+    let id = token.into_raw() as u32;
+    unsafe {
+        install_handler();
+        let mut hotkey_ref = null_mut();
+        let hotkey_id = EventHotKeyID {
+            signature: SIGNATURE,
+            id,
+        };
+        let status = RegisterEventHotKey(
+            key_code as u32,
+            modifiers,
+            hotkey_id,
+            GetApplicationEventTarget(),
+            0,
+            &mut hotkey_ref,
+        );
+        match status {
+            0 => {
+                HOTKEYS.with(|hotkeys| hotkeys.borrow_mut().insert(id, hotkey_ref));
+                Ok(())
+            }
+            eventHotKeyExistsErr => Err(Error::HotKeyInUse),
+            status => Err(anyhow::anyhow!("RegisterEventHotKey failed: {}", status).into()),
+        }
+    }
+}
+
+pub(crate) fn unregister(token: GlobalHotKeyToken) {
+    let hotkey_ref =
+        HOTKEYS.with(|hotkeys| hotkeys.borrow_mut().remove(&(token.into_raw() as u32)));
+    if let Some(hotkey_ref) = hotkey_ref {
+        unsafe {
+            UnregisterEventHotKey(hotkey_ref);
+        }
+    }
+}
+
+unsafe fn install_handler() {
+    if HANDLER_INSTALLED.with(|installed| installed.replace(true)) {
+        return;
+    }
+    let spec = EventTypeSpec {
+        event_class: kEventClassKeyboard,
+        event_kind: kEventHotKeyPressed,
+    };
+    let status = InstallEventHandler(
+        GetApplicationEventTarget(),
+        hotkey_pressed,
+        1,
+        &spec,
+        null_mut(),
+        null_mut(),
+    );
+    if status != 0 {
+        tracing::error!("failed to install the hotkey handler: {}", status);
+    }
+}
+
+extern "C" fn hotkey_pressed(_: EventHandlerCallRef, event: EventRef, _: *mut c_void) -> OSStatus {
+    unsafe {
+        let mut hotkey_id = EventHotKeyID {
+            signature: 0,
+            id: 0,
+        };
+        let status = GetEventParameter(
+            event,
+            kEventParamDirectObject,
+            typeEventHotKeyID,
+            null_mut(),
+            mem::size_of::<EventHotKeyID>(),
+            null_mut(),
+            &mut hotkey_id as *mut EventHotKeyID as *mut c_void,
+        );
+        if status == 0 && hotkey_id.signature == SIGNATURE {
+            // The application delegate passes it on to the `AppHandler`.
+            let delegate: id = msg_send![NSApp(), delegate];
+            if delegate != nil {
+                let () = msg_send![delegate, handleGlobalHotKey: hotkey_id.id as u64];
+            }
+        }
+    }
+    0
+}
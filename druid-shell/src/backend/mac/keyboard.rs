@@ -233,6 +233,20 @@ fn code_to_key(code: Code) -> Option<KbKey> {
     })
 }
 
+/// Find the key code of the key that produces `key`.
+///
+/// Characters are looked up on a US keyboard layout.
+pub(crate) fn key_to_key_code(key: &KbKey) -> Option<u16> {
+    let code = match key {
+        KbKey::Character(s) if s.chars().count() == 1 => shared::char_to_code(s.chars().next()?)?,
+        KbKey::Character(_) => return None,
+        key => (0..0x80)
+            .map(key_code_to_code)
+            .find(|&code| code_to_key(code).as_ref() == Some(key))?,
+    };
+    (0..0x80).find(|&key_code| key_code_to_code(key_code) == code)
+}
+
 fn is_valid_key(s: &str) -> bool {
     match s.chars().next() {
         None => false,
@@ -318,6 +332,7 @@ impl KeyboardState {
                 state,
                 key,
                 code,
+                scancode: key_code as u32,
                 location,
                 mods,
                 repeat,
@@ -21,9 +21,12 @@ pub mod application;
 pub mod clipboard;
 pub mod dialog;
 pub mod error;
+mod global_hotkey;
 mod keyboard;
 pub mod menu;
 pub mod screen;
 pub mod text_input;
+mod toolbar;
+pub mod tray;
 pub mod util;
 pub mod window;
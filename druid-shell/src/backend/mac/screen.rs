@@ -15,16 +15,17 @@
 //! macOS Monitors and Screen information.
 
 use crate::kurbo::Rect;
+use crate::scale::Scale;
 use crate::screen::Monitor;
 use cocoa::appkit::NSScreen;
-use cocoa::base::id;
+use cocoa::base::{id, nil};
 use cocoa::foundation::NSArray;
 use objc::{class, msg_send, sel, sel_impl};
 
 pub(crate) fn get_monitors() -> Vec<Monitor> {
     unsafe {
         let screens: id = msg_send![class![NSScreen], screens];
-        let mut monitors = Vec::<(Rect, Rect)>::new();
+        let mut monitors = Vec::<(Rect, Rect, Scale)>::new();
         let mut total_rect = Rect::ZERO;
 
         for idx in 0..screens.count() {
@@ -40,7 +41,8 @@ pub(crate) fn get_monitors() -> Vec<Monitor> {
                 (vis_frame.origin.x, vis_frame.origin.y),
                 (vis_frame.size.width, vis_frame.size.height),
             );
-            monitors.push((frame_r, vis_frame_r));
+            let scale = NSScreen::backingScaleFactor(screen);
+            monitors.push((frame_r, vis_frame_r, Scale::new(scale, scale)));
             total_rect = total_rect.union(frame_r)
         }
         // TODO save this total_rect.y1 for screen coord transformations in get_position/set_position
@@ -49,7 +51,19 @@ pub(crate) fn get_monitors() -> Vec<Monitor> {
     }
 }
 
-fn transform_coords(monitors_build: Vec<(Rect, Rect)>, max_y: f64) -> Vec<Monitor> {
+/// The `Monitor` for `screen`, which must be one of `NSScreen.screens`.
+pub(crate) fn get_monitor(screen: id) -> Option<Monitor> {
+    if screen == nil {
+        return None;
+    }
+    unsafe {
+        let screens: id = msg_send![class![NSScreen], screens];
+        let idx: usize = msg_send![screens, indexOfObject: screen];
+        get_monitors().into_iter().nth(idx)
+    }
+}
+
+fn transform_coords(monitors_build: Vec<(Rect, Rect, Scale)>, max_y: f64) -> Vec<Monitor> {
     //Flip y and move to opposite horizontal edges (On mac, Y goes up and origin is bottom left corner)
     let fix_rect = |frame: &Rect| {
         Rect::new(
@@ -63,8 +77,8 @@ fn transform_coords(monitors_build: Vec<(Rect, Rect)>, max_y: f64) -> Vec<Monito
     monitors_build
         .iter()
         .enumerate()
-        .map(|(idx, (frame, vis_frame))| {
-            Monitor::new(idx == 0, fix_rect(frame), fix_rect(vis_frame))
+        .map(|(idx, (frame, vis_frame, scale))| {
+            Monitor::new(idx == 0, fix_rect(frame), fix_rect(vis_frame), *scale)
         })
         .collect()
 }
@@ -72,21 +86,21 @@ fn transform_coords(monitors_build: Vec<(Rect, Rect)>, max_y: f64) -> Vec<Monito
 #[cfg(test)]
 mod test {
     use crate::backend::mac::screen::transform_coords;
-    use crate::Monitor;
+    use crate::{Monitor, Scale};
     use kurbo::Rect;
     use test_env_log::test;
 
-    fn pair(rect: Rect) -> (Rect, Rect) {
-        (rect, rect)
+    fn frames(rect: Rect) -> (Rect, Rect, Scale) {
+        (rect, rect, Scale::default())
     }
 
     fn monitor(primary: bool, rect: Rect) -> Monitor {
-        Monitor::new(primary, rect, rect)
+        Monitor::new(primary, rect, rect, Scale::default())
     }
 
     #[test]
     fn test_transform_coords_1() {
-        let mons = transform_coords(vec![pair(Rect::new(0., 0., 100., 100.))], 100.);
+        let mons = transform_coords(vec![frames(Rect::new(0., 0., 100., 100.))], 100.);
 
         assert_eq!(vec![monitor(true, Rect::new(0., 0., 100., 100.))], mons)
     }
@@ -95,8 +109,8 @@ mod test {
     fn test_transform_coords_2_right() {
         let mons = transform_coords(
             vec![
-                pair(Rect::new(0., 0., 100., 100.)),
-                pair(Rect::new(100., 0., 200., 100.)),
+                frames(Rect::new(0., 0., 100., 100.)),
+                frames(Rect::new(100., 0., 200., 100.)),
             ],
             100.,
         );
@@ -114,8 +128,8 @@ mod test {
     fn test_transform_coords_2_up() {
         let mons = transform_coords(
             vec![
-                pair(Rect::new(0., 0., 100., 100.)),
-                pair(Rect::new(0., 100., 0., 200.)),
+                frames(Rect::new(0., 0., 100., 100.)),
+                frames(Rect::new(0., 100., 0., 200.)),
             ],
             100.,
         );
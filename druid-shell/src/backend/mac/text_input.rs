@@ -28,11 +28,11 @@ use std::os::raw::c_uchar;
 use super::window::with_edit_lock_from_window;
 use crate::kurbo::Point;
 use crate::text::{
-    Action, Affinity, Direction, InputHandler, Movement, Selection, VerticalMovement,
+    Action, Affinity, Direction, InputHandler, InputPurpose, Movement, Selection, VerticalMovement,
     WritingDirection,
 };
 use cocoa::base::{id, nil, BOOL};
-use cocoa::foundation::{NSArray, NSPoint, NSRect, NSSize, NSString, NSUInteger};
+use cocoa::foundation::{NSArray, NSInteger, NSPoint, NSRect, NSSize, NSString, NSUInteger};
 use cocoa::{appkit::NSWindow, foundation::NSNotFound};
 use objc::runtime::{Object, Sel};
 use objc::{class, msg_send, sel, sel_impl};
@@ -65,6 +65,28 @@ unsafe impl objc::Encode for NSRange {
     }
 }
 
+// NSTextInputTraitType
+const NSTextInputTraitTypeDefault: NSInteger = 0;
+const NSTextInputTraitTypeNo: NSInteger = 1;
+
+/// The answer to the `NSTextInputTraits` properties (macOS 14 and up) that
+/// control autocorrection, completion and smart punctuation.
+///
+/// These are turned off for fields that don't allow autocorrection, and for
+/// fields that expect something other than prose, such as an email address.
+pub extern "C" fn autocorrection_trait(this: &mut Object, _: Sel) -> NSInteger {
+    let autocorrect = with_edit_lock_from_window(this, false, |edit_lock| {
+        let hints = edit_lock.input_hints();
+        hints.allows_autocorrect() && hints.purpose() == InputPurpose::Text
+    })
+    .unwrap_or(true);
+    if autocorrect {
+        NSTextInputTraitTypeDefault
+    } else {
+        NSTextInputTraitTypeNo
+    }
+}
+
 // BOOL is i8 on x86, but bool on aarch64
 #[cfg_attr(target_arch = "aarch64", allow(clippy::useless_conversion))]
 pub extern "C" fn has_marked_text(this: &mut Object, _: Sel) -> BOOL {
@@ -0,0 +1,298 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Toolbars and titlebar accessories.
+//!
+//! Toolbar items and accessory buttons target the window's view with the
+//! `handleMenuItem:` action, so that clicks are delivered to
+//! `WinHandler::command` in the same way as menu items.
+
+use std::ffi::c_void;
+
+use cocoa::appkit::{NSWindow, NSWindowStyleMask};
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSArray, NSInteger, NSSize, NSUInteger};
+use lazy_static::lazy_static;
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+
+use super::util::{from_nsstring, make_nsstring};
+use crate::platform::mac::{AccessoryPosition, ToolbarItem, ToolbarStyle};
+
+// NSLayoutAttribute values used for titlebar accessories.
+const NS_LAYOUT_ATTRIBUTE_RIGHT: NSInteger = 2;
+const NS_LAYOUT_ATTRIBUTE_BOTTOM: NSInteger = 4;
+const NS_BEZEL_STYLE_TEXTURED_ROUNDED: NSUInteger = 11;
+const NS_IMAGE_LEFT: NSUInteger = 2;
+const NS_WINDOW_TITLE_VISIBLE: NSInteger = 0;
+const NS_WINDOW_TITLE_HIDDEN: NSInteger = 1;
+
+struct DelegateClass(*const Class);
+unsafe impl Sync for DelegateClass {}
+
+/// The state of a toolbar delegate.
+struct ToolbarState {
+    items: Vec<ToolbarItem>,
+    /// The view that the items target; not retained, as it owns the window.
+    target: id,
+}
+
+lazy_static! {
+    static ref TOOLBAR_DELEGATE_CLASS: DelegateClass = unsafe {
+        let mut decl = ClassDecl::new("DruidToolbarDelegate", class!(NSObject))
+            .expect("Toolbar delegate class defined");
+        decl.add_ivar::<*mut c_void>("toolbarState");
+
+        decl.add_method(
+            sel!(toolbar:itemForItemIdentifier:willBeInsertedIntoToolbar:),
+            item_for_identifier as extern "C" fn(&Object, Sel, id, id, BOOL) -> id,
+        );
+        decl.add_method(
+            sel!(toolbarDefaultItemIdentifiers:),
+            item_identifiers as extern "C" fn(&Object, Sel, id) -> id,
+        );
+        decl.add_method(
+            sel!(toolbarAllowedItemIdentifiers:),
+            item_identifiers as extern "C" fn(&Object, Sel, id) -> id,
+        );
+        decl.add_method(sel!(dealloc), dealloc as extern "C" fn(&Object, Sel));
+        extern "C" fn dealloc(this: &Object, _sel: Sel) {
+            unsafe {
+                let state: *mut c_void = *this.get_ivar("toolbarState");
+                if !state.is_null() {
+                    drop(Box::from_raw(state as *mut ToolbarState));
+                }
+                let () = msg_send![super(this, class!(NSObject)), dealloc];
+            }
+        }
+
+        DelegateClass(decl.register())
+    };
+}
+
+fn item_identifier(item: &ToolbarItem) -> String {
+    format!("druid.toolbar.{}", item.id)
+}
+
+unsafe fn toolbar_state(this: &Object) -> &ToolbarState {
+    let state: *mut c_void = *this.get_ivar("toolbarState");
+    &*(state as *const ToolbarState)
+}
+
+extern "C" fn item_identifiers(this: &Object, _: Sel, _toolbar: id) -> id {
+    unsafe {
+        let identifiers: Vec<id> = toolbar_state(this)
+            .items
+            .iter()
+            .map(|item| make_nsstring(&item_identifier(item)))
+            .collect();
+        NSArray::arrayWithObjects(nil, &identifiers)
+    }
+}
+
+extern "C" fn item_for_identifier(
+    this: &Object,
+    _: Sel,
+    _toolbar: id,
+    identifier: id,
+    _will_be_inserted: BOOL,
+) -> id {
+    unsafe {
+        let state = toolbar_state(this);
+        let identifier_str = from_nsstring(identifier);
+        let item = match state
+            .items
+            .iter()
+            .find(|item| item_identifier(item) == identifier_str)
+        {
+            Some(item) => item,
+            None => return nil,
+        };
+
+        let ns_item: id = msg_send![class!(NSToolbarItem), alloc];
+        let ns_item: id = msg_send![ns_item, initWithItemIdentifier: identifier];
+        let label = make_nsstring(&item.label);
+        let () = msg_send![ns_item, setLabel: label];
+        let () = msg_send![ns_item, setPaletteLabel: label];
+        if let Some(tooltip) = &item.tooltip {
+            let () = msg_send![ns_item, setToolTip: make_nsstring(tooltip)];
+        }
+        if let Some(image) = symbol_image(item) {
+            let () = msg_send![ns_item, setImage: image];
+        }
+        let responds: BOOL = msg_send![ns_item, respondsToSelector: sel!(setBordered:)];
+        if responds != NO {
+            let () = msg_send![ns_item, setBordered: YES];
+        }
+        let () = msg_send![ns_item, setTag: item.id as NSInteger];
+        let () = msg_send![ns_item, setTarget: state.target];
+        let () = msg_send![ns_item, setAction: sel!(handleMenuItem:)];
+        msg_send![ns_item, autorelease]
+    }
+}
+
+/// The item's SF Symbol image, if it has one and symbols are supported.
+unsafe fn symbol_image(item: &ToolbarItem) -> Option<id> {
+    let symbol = item.symbol.as_ref()?;
+    let image_class = class!(NSImage);
+    let responds: BOOL = msg_send![
+        image_class,
+        respondsToSelector: sel!(imageWithSystemSymbolName:accessibilityDescription:)
+    ];
+    if responds == NO {
+        return None;
+    }
+    let image: id = msg_send![
+        image_class,
+        imageWithSystemSymbolName: make_nsstring(symbol)
+        accessibilityDescription: make_nsstring(&item.label)
+    ];
+    if image == nil {
+        None
+    } else {
+        Some(image)
+    }
+}
+
+/// Replace the toolbar of the window containing `view`.
+pub(crate) fn set_toolbar(view: id, items: Vec<ToolbarItem>) {
+    unsafe {
+        let window: id = msg_send![view, window];
+        let old_toolbar: id = msg_send![window, toolbar];
+        // the toolbar doesn't retain its delegate, so we own it.
+        let old_delegate: id = if old_toolbar == nil {
+            nil
+        } else {
+            msg_send![old_toolbar, delegate]
+        };
+
+        if items.is_empty() {
+            let () = msg_send![window, setToolbar: nil];
+        } else {
+            let state = Box::new(ToolbarState {
+                items,
+                target: view,
+            });
+            let delegate: id = msg_send![TOOLBAR_DELEGATE_CLASS.0, new];
+            (*delegate).set_ivar("toolbarState", Box::into_raw(state) as *mut c_void);
+
+            // toolbars with the same identifier share their configuration,
+            // so each window gets its own.
+            let identifier = make_nsstring(&format!("druid.toolbar.{:p}", view));
+            let toolbar: id = msg_send![class!(NSToolbar), alloc];
+            let toolbar: id = msg_send![toolbar, initWithIdentifier: identifier];
+            let () = msg_send![toolbar, setAllowsUserCustomization: NO];
+            let () = msg_send![toolbar, setDelegate: delegate];
+            let () = msg_send![window, setToolbar: toolbar];
+            let () = msg_send![toolbar, release];
+        }
+
+        if old_delegate != nil {
+            let () = msg_send![old_delegate, release];
+        }
+    }
+}
+
+pub(crate) fn set_toolbar_style(view: id, style: ToolbarStyle) {
+    let style: NSInteger = match style {
+        ToolbarStyle::Automatic => 0,
+        ToolbarStyle::Expanded => 1,
+        ToolbarStyle::Preference => 2,
+        ToolbarStyle::Unified => 3,
+        ToolbarStyle::UnifiedCompact => 4,
+    };
+    unsafe {
+        let window: id = msg_send![view, window];
+        // this is only available on macOS 11 and later.
+        let responds: BOOL = msg_send![window, respondsToSelector: sel!(setToolbarStyle:)];
+        if responds != NO {
+            let () = msg_send![window, setToolbarStyle: style];
+        }
+    }
+}
+
+/// Replace the titlebar accessories of the window containing `view`.
+pub(crate) fn set_titlebar_accessory(
+    view: id,
+    items: Vec<ToolbarItem>,
+    position: AccessoryPosition,
+) {
+    unsafe {
+        let window: id = msg_send![view, window];
+        let controllers: id = msg_send![window, titlebarAccessoryViewControllers];
+        let count: NSUInteger = msg_send![controllers, count];
+        for idx in (0..count).rev() {
+            let () =
+                msg_send![window, removeTitlebarAccessoryViewControllerAtIndex: idx as NSInteger];
+        }
+        if items.is_empty() {
+            return;
+        }
+
+        let buttons: Vec<id> = items
+            .iter()
+            .map(|item| {
+                let button: id = msg_send![
+                    class!(NSButton),
+                    buttonWithTitle: make_nsstring(&item.label)
+                    target: view
+                    action: sel!(handleMenuItem:)
+                ];
+                let () = msg_send![button, setTag: item.id as NSInteger];
+                let () = msg_send![button, setBezelStyle: NS_BEZEL_STYLE_TEXTURED_ROUNDED];
+                if let Some(tooltip) = &item.tooltip {
+                    let () = msg_send![button, setToolTip: make_nsstring(tooltip)];
+                }
+                if let Some(image) = symbol_image(item) {
+                    let () = msg_send![button, setImage: image];
+                    let () = msg_send![button, setImagePosition: NS_IMAGE_LEFT];
+                }
+                button
+            })
+            .collect();
+        let buttons = NSArray::arrayWithObjects(nil, &buttons);
+        let stack: id = msg_send![class!(NSStackView), stackViewWithViews: buttons];
+        let size: NSSize = msg_send![stack, fittingSize];
+        let () = msg_send![stack, setFrameSize: size];
+
+        let attribute = match position {
+            AccessoryPosition::Right => NS_LAYOUT_ATTRIBUTE_RIGHT,
+            AccessoryPosition::Bottom => NS_LAYOUT_ATTRIBUTE_BOTTOM,
+        };
+        let controller: id = msg_send![class!(NSTitlebarAccessoryViewController), new];
+        let () = msg_send![controller, setView: stack];
+        let () = msg_send![controller, setLayoutAttribute: attribute];
+        let () = msg_send![window, addTitlebarAccessoryViewController: controller];
+        let () = msg_send![controller, release];
+    }
+}
+
+pub(crate) fn set_titlebar_transparent(view: id, transparent: bool) {
+    unsafe {
+        let window: id = msg_send![view, window];
+        let mut style_mask: NSWindowStyleMask = window.styleMask();
+        let visibility = if transparent {
+            style_mask |= NSWindowStyleMask::NSFullSizeContentViewWindowMask;
+            NS_WINDOW_TITLE_HIDDEN
+        } else {
+            style_mask &= !NSWindowStyleMask::NSFullSizeContentViewWindowMask;
+            NS_WINDOW_TITLE_VISIBLE
+        };
+        window.setStyleMask_(style_mask);
+        let transparent = if transparent { YES } else { NO };
+        let () = msg_send![window, setTitlebarAppearsTransparent: transparent];
+        let () = msg_send![window, setTitleVisibility: visibility];
+    }
+}
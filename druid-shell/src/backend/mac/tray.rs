@@ -0,0 +1,91 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! macOS status bar items.
+
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSSize;
+use objc::{class, msg_send, sel, sel_impl};
+
+use super::menu::Menu;
+use super::util::{make_nsimage, make_nsstring};
+use crate::piet::ImageBuf;
+use crate::Error;
+
+#[allow(non_upper_case_globals)]
+const NSVariableStatusItemLength: f64 = -1.0;
+
+/// The height of icons in the status bar, in points.
+const STATUS_ICON_SIZE: f64 = 18.0;
+
+pub struct TrayIcon {
+    status_item: id,
+}
+
+impl TrayIcon {
+    pub fn new(icon: &ImageBuf) -> Result<TrayIcon, Error> {
+        unsafe {
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let status_item: id =
+                msg_send![status_bar, statusItemWithLength: NSVariableStatusItemLength];
+            let () = msg_send![status_item, retain];
+            let button: id = msg_send![status_item, button];
+            // A nil target sends the action along the responder chain, which
+            // ends at the application delegate.
+            let () = msg_send![button, setTarget: nil];
+            let () = msg_send![button, setAction: sel!(handleStatusItem:)];
+            let mut tray = TrayIcon { status_item };
+            tray.set_icon(icon);
+            Ok(tray)
+        }
+    }
+
+    pub fn set_icon(&mut self, icon: &ImageBuf) {
+        unsafe {
+            let image = make_nsimage(icon);
+            if image == nil {
+                return;
+            }
+            let () = msg_send![image, setSize: NSSize::new(STATUS_ICON_SIZE, STATUS_ICON_SIZE)];
+            let button: id = msg_send![self.status_item, button];
+            let () = msg_send![button, setImage: image];
+            let () = msg_send![image, release];
+        }
+    }
+
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        unsafe {
+            let button: id = msg_send![self.status_item, button];
+            let () = msg_send![button, setToolTip: make_nsstring(tooltip)];
+        }
+    }
+
+    pub fn set_menu(&mut self, menu: Menu) -> Result<(), crate::Error> {
+        // Status items with a menu show it on any click, as is usual on macOS.
+        unsafe {
+            let () = msg_send![self.status_item, setMenu: menu.menu];
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        unsafe {
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let () = msg_send![status_bar, removeStatusItem: self.status_item];
+            let () = msg_send![self.status_item, release];
+        }
+    }
+}
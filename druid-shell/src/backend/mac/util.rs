@@ -16,10 +16,15 @@
 
 use std::ffi::c_void;
 
-use cocoa::base::{id, nil, BOOL, YES};
-use cocoa::foundation::{NSAutoreleasePool, NSString, NSUInteger};
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSAutoreleasePool, NSInteger, NSSize, NSString, NSUInteger};
 use objc::{class, msg_send, sel, sel_impl};
 
+use crate::piet::ImageBuf;
+
+#[allow(non_upper_case_globals)]
+const NSAlphaNonpremultipliedBitmapFormat: NSUInteger = 1 << 1;
+
 /// Panic if not on the main thread.
 ///
 /// Many Cocoa operations are only valid on the main thread, and (I think)
@@ -45,6 +50,53 @@ pub(crate) fn from_nsstring(s: id) -> String {
     }
 }
 
+/// Create a new NSImage from an `ImageBuf`.
+///
+/// The returned image is retained, and is `nil` if the image is empty.
+pub(crate) fn make_nsimage(image: &ImageBuf) -> id {
+    let width = image.width();
+    let height = image.height();
+    if width == 0 || height == 0 {
+        return nil;
+    }
+    unsafe {
+        // a bitmap with unpremultiplied RGBA pixels.
+        let rep: id = msg_send![class!(NSBitmapImageRep), alloc];
+        let rep: id = msg_send![rep,
+            initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>()
+            pixelsWide: width as NSInteger
+            pixelsHigh: height as NSInteger
+            bitsPerSample: 8 as NSInteger
+            samplesPerPixel: 4 as NSInteger
+            hasAlpha: YES
+            isPlanar: NO
+            colorSpaceName: make_nsstring("NSDeviceRGBColorSpace")
+            bitmapFormat: NSAlphaNonpremultipliedBitmapFormat
+            bytesPerRow: (width * 4) as NSInteger
+            bitsPerPixel: 32 as NSInteger
+        ];
+        if rep == nil {
+            return nil;
+        }
+        let bitmap: *mut u8 = msg_send![rep, bitmapData];
+        let pixels = std::slice::from_raw_parts_mut(bitmap, width * height * 4);
+        for (row_idx, row) in image.pixel_colors().enumerate() {
+            for (col_idx, p) in row.enumerate() {
+                let (r, g, b, a) = p.as_rgba8();
+                let offset = (row_idx * width + col_idx) * 4;
+                pixels[offset..offset + 4].copy_from_slice(&[r, g, b, a]);
+            }
+        }
+
+        let nsimage: id = msg_send![class!(NSImage), alloc];
+        let nsimage: id =
+            msg_send![nsimage, initWithSize: NSSize::new(width as f64, height as f64)];
+        let () = msg_send![nsimage, addRepresentation: rep];
+        let () = msg_send![rep, release];
+        nsimage
+    }
+}
+
 pub(crate) fn make_nsdata(bytes: &[u8]) -> id {
     let dlen = bytes.len() as NSUInteger;
     unsafe {
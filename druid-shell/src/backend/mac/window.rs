@@ -24,7 +24,8 @@ use std::time::Instant;
 use block::ConcreteBlock;
 use cocoa::appkit::{
     CGFloat, NSApp, NSApplication, NSAutoresizingMaskOptions, NSBackingStoreBuffered, NSColor,
-    NSEvent, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindow, NSWindowStyleMask,
+    NSEvent, NSEventModifierFlags, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindow,
+    NSWindowStyleMask,
 };
 use cocoa::base::{id, nil, BOOL, NO, YES};
 use cocoa::foundation::{
@@ -49,20 +50,28 @@ use super::appkit::{
     NSRunLoopCommonModes, NSTrackingArea, NSTrackingAreaOptions, NSView as NSViewExt,
 };
 use super::application::Application;
+use super::clipboard;
 use super::dialog;
 use super::keyboard::{make_modifiers, KeyboardState};
 use super::menu::Menu;
+use super::screen;
 use super::text_input::NSRange;
-use super::util::{assert_main_thread, make_nsstring};
+use super::toolbar;
+use super::util::{assert_main_thread, make_nsimage, make_nsstring};
 use crate::common_util::IdleCallback;
-use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
+use crate::dialog::{FileDialogOptions, FileDialogType};
+use crate::dnd::DropEvent;
 use crate::keyboard_types::KeyState;
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
+use crate::pen::{PenEvent, PenPhase};
+use crate::platform::mac::{AccessoryPosition, ToolbarItem, ToolbarStyle};
 use crate::region::Region;
 use crate::scale::Scale;
+use crate::screen::Monitor;
 use crate::text::{Event, InputHandler};
 use crate::window::{
-    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel, WindowState,
+    FileDialogToken, IdleToken, ResizeEdge, TextAntialiasing, TextFieldToken, TextHinting,
+    TextRenderingOptions, TimerToken, WinHandler, WindowCorners, WindowLevel, WindowState,
 };
 use crate::Error;
 
@@ -92,6 +101,8 @@ mod levels {
             Tooltip => NSFloatingWindowLevel,
             DropDown => NSFloatingWindowLevel,
             Modal => NSModalPanelWindowLevel,
+            Floating => NSFloatingWindowLevel,
+            Utility => NSFloatingWindowLevel,
         }
     }
 }
@@ -138,8 +149,18 @@ pub(crate) struct IdleHandle {
 enum DeferredOp {
     SetSize(Size),
     SetPosition(Point),
+    /// Enter or leave fullscreen; entering it moves the window to the monitor
+    /// at the given position first.
+    SetFullscreen(bool, Option<Point>),
+    /// Resize the window by dragging an edge, until the left mouse button is released.
+    ResizeDrag(ResizeEdge),
 }
 
+/// `NSWindowStyleMaskFullScreen`, which cocoa doesn't define.
+const NS_FULL_SCREEN_WINDOW_MASK: u64 = 1 << 14;
+/// `NSWindowCollectionBehaviorFullScreenPrimary`
+const NS_FULL_SCREEN_PRIMARY: u64 = 1 << 7;
+
 /// This represents different Idle Callback Mechanism
 enum IdleKind {
     Callback(Box<dyn IdleCallback>),
@@ -156,14 +177,40 @@ struct ViewState {
     focus_click: bool,
     // Tracks whether we have already received the mouseExited event
     mouse_left: bool,
+    /// Whether the pen that is close to the tablet is an eraser
+    pen_eraser: bool,
     keyboard_state: KeyboardState,
     text: PietText,
     active_text_input: Option<TextFieldToken>,
+    /// The pasteboard's change count when we last looked at it
+    clipboard_change_count: NSInteger,
+    /// The frame of the window when it last moved or resized while restored
+    restored_rect: Option<Rect>,
+    /// Whether the mouse is over an area that should be treated as the titlebar
+    handle_titlebar: bool,
+    /// How text is drawn in this view
+    text_rendering: TextRenderingOptions,
+}
+
+#[derive(Clone)]
+pub struct CustomCursor(Arc<NSCursorHandle>);
+
+/// An owned `NSCursor`.
+struct NSCursorHandle(id);
+
+impl Drop for NSCursorHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let () = msg_send![self.0, release];
+        }
+    }
 }
 
-#[derive(Clone, PartialEq)]
-// TODO: support custom cursors
-pub struct CustomCursor;
+impl PartialEq for CustomCursor {
+    fn eq(&self, other: &CustomCursor) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
 
 impl WindowBuilder {
     pub fn new(_app: Application) -> WindowBuilder {
@@ -360,6 +407,30 @@ lazy_static! {
             sel!(windowDidResignKey:),
             window_did_resign_key as extern "C" fn(&mut Object, Sel, id),
         );
+        decl.add_method(
+            sel!(viewDidChangeEffectiveAppearance),
+            view_did_change_effective_appearance as extern "C" fn(&mut Object, Sel),
+        );
+        decl.add_method(
+            sel!(systemColorsDidChange:),
+            system_colors_did_change as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(windowDidMove:),
+            window_did_move_or_resize as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(windowDidResize:),
+            window_did_move_or_resize as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(windowDidChangeScreen:),
+            window_did_change_screen as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(screenParametersDidChange:),
+            screen_parameters_did_change as extern "C" fn(&mut Object, Sel, id),
+        );
         decl.add_method(
             sel!(setFrameSize:),
             set_frame_size as extern "C" fn(&mut Object, Sel, NSSize),
@@ -408,6 +479,26 @@ lazy_static! {
             sel!(mouseExited:),
             mouse_leave as extern "C" fn(&mut Object, Sel, id),
         );
+        decl.add_method(
+            sel!(draggingEntered:),
+            dragging_updated as extern "C" fn(&mut Object, Sel, id) -> NSUInteger,
+        );
+        decl.add_method(
+            sel!(draggingUpdated:),
+            dragging_updated as extern "C" fn(&mut Object, Sel, id) -> NSUInteger,
+        );
+        decl.add_method(
+            sel!(draggingExited:),
+            dragging_exited as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(performDragOperation:),
+            perform_drag_operation as extern "C" fn(&mut Object, Sel, id) -> BOOL,
+        );
+        decl.add_method(
+            sel!(tabletProximity:),
+            tablet_proximity as extern "C" fn(&mut Object, Sel, id),
+        );
         decl.add_method(
             sel!(scrollWheel:),
             scroll_wheel as extern "C" fn(&mut Object, Sel, id),
@@ -492,6 +583,23 @@ lazy_static! {
             super::text_input::do_command_by_selector as extern fn(&mut Object, Sel, Sel),
         );
 
+        // methods for NSTextInputTraits, which older systems don't ask for
+        for &trait_sel in &[
+            sel!(autocorrectionType),
+            sel!(spellCheckingType),
+            sel!(textCompletionType),
+            sel!(inlinePredictionType),
+            sel!(textReplacementType),
+            sel!(smartQuotesType),
+            sel!(smartDashesType),
+            sel!(smartInsertDeleteType),
+        ] {
+            decl.add_method(
+                trait_sel,
+                super::text_input::autocorrection_trait as extern fn(&mut Object, Sel) -> NSInteger,
+            );
+        }
+
         let protocol = Protocol::get("NSTextInputClient").unwrap();
         decl.add_protocol(protocol);
 
@@ -530,9 +638,14 @@ fn make_view(handler: Box<dyn WinHandler>) -> (id, Weak<Mutex<Vec<IdleKind>>>) {
             idle_queue,
             focus_click: false,
             mouse_left: true,
+            pen_eraser: false,
             keyboard_state,
             text: PietText::new_with_unique_state(),
             active_text_input: None,
+            clipboard_change_count: clipboard::change_count(),
+            restored_rect: None,
+            handle_titlebar: false,
+            text_rendering: TextRenderingOptions::default(),
         };
         let state_ptr = Box::into_raw(Box::new(state));
         (*view).set_ivar("viewState", state_ptr as *mut c_void);
@@ -551,6 +664,17 @@ fn make_view(handler: Box<dyn WinHandler>) -> (id, Weak<Mutex<Vec<IdleKind>>>) {
             .autorelease();
         view.addTrackingArea(tracking_area);
 
+        // accept files, URLs and text dragged from other applications.
+        let dragged_types = NSArray::arrayWithObjects(
+            nil,
+            &[
+                make_nsstring("public.file-url"),
+                make_nsstring("public.url"),
+                make_nsstring("public.utf8-plain-text"),
+            ],
+        );
+        let () = msg_send![view, registerForDraggedTypes: dragged_types];
+
         (view.autorelease(), queue_handle)
     }
 }
@@ -607,7 +731,53 @@ fn mouse_event(
             focus,
             button,
             wheel_delta,
+            precise_wheel: false,
+        }
+    }
+}
+
+/// The pen event of a mouse event, or `None` if the event doesn't come from a tablet.
+fn pen_event(nsevent: id, view: id, phase: PenPhase, eraser: bool) -> Option<PenEvent> {
+    // NSEventSubtypeTabletPoint
+    const TABLET_POINT: i16 = 1;
+    // NSEventButtonMaskPenLowerSide | NSEventButtonMaskPenUpperSide
+    const BARREL_BUTTONS: NSUInteger = 2 | 4;
+    unsafe {
+        let subtype: i16 = msg_send![nsevent, subtype];
+        if subtype != TABLET_POINT {
+            return None;
         }
+        let point = nsevent.locationInWindow();
+        let view_point = view.convertPoint_fromView_(point, nil);
+        let pressure: f32 = msg_send![nsevent, pressure];
+        // macOS reports the tilt from -1 to 1
+        let tilt: NSPoint = msg_send![nsevent, tilt];
+        let buttons: NSUInteger = msg_send![nsevent, buttonMask];
+        Some(PenEvent {
+            pos: Point::new(view_point.x as f64, view_point.y as f64),
+            phase,
+            pressure: if phase == PenPhase::Up {
+                0.0
+            } else {
+                pressure as f64
+            },
+            tilt: Vec2::new(tilt.x as f64, tilt.y as f64) * 90.0,
+            barrel_button: buttons & BARREL_BUTTONS != 0,
+            eraser,
+            mods: make_modifiers(nsevent.modifierFlags()),
+        })
+    }
+}
+
+extern "C" fn tablet_proximity(this: &mut Object, _: Sel, nsevent: id) {
+    // NSPointingDeviceTypeEraser
+    const ERASER: NSUInteger = 3;
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let entering: BOOL = msg_send![nsevent, isEnteringProximity];
+        let device: NSUInteger = msg_send![nsevent, pointingDeviceType];
+        view_state.pen_eraser = entering == YES && device == ERASER;
     }
 }
 
@@ -664,8 +834,18 @@ fn mouse_down(this: &mut Object, nsevent: id, button: MouseButton) {
         let view_state = &mut *(view_state as *mut ViewState);
         let count = nsevent.clickCount() as u8;
         let focus = view_state.focus_click && button == MouseButton::Left;
+        if button == MouseButton::Left {
+            let pen = pen_event(nsevent, this as id, PenPhase::Down, view_state.pen_eraser);
+            if let Some(pen) = pen {
+                (*view_state).handler.pen(&pen);
+            }
+        }
         let event = mouse_event(nsevent, this as id, count, focus, button, Vec2::ZERO);
         (*view_state).handler.mouse_down(&event);
+        if button == MouseButton::Left && (*view_state).handle_titlebar {
+            let window: id = msg_send![this, window];
+            let () = msg_send![window, performWindowDragWithEvent: nsevent];
+        }
     }
 }
 
@@ -695,6 +875,12 @@ fn mouse_up(this: &mut Object, nsevent: id, button: MouseButton) {
         } else {
             false
         };
+        if button == MouseButton::Left {
+            let pen = pen_event(nsevent, this as id, PenPhase::Up, view_state.pen_eraser);
+            if let Some(pen) = pen {
+                (*view_state).handler.pen(&pen);
+            }
+        }
         let event = mouse_event(nsevent, this as id, 0, focus, button, Vec2::ZERO);
         (*view_state).handler.mouse_up(&event);
         // If we have already received a mouseExited event then that means
@@ -712,6 +898,10 @@ extern "C" fn mouse_move(this: &mut Object, _: Sel, nsevent: id) {
     unsafe {
         let view_state: *mut c_void = *this.get_ivar("viewState");
         let view_state = &mut *(view_state as *mut ViewState);
+        let pen = pen_event(nsevent, this as id, PenPhase::Move, view_state.pen_eraser);
+        if let Some(pen) = pen {
+            (*view_state).handler.pen(&pen);
+        }
         let event = mouse_event(nsevent, this as id, 0, false, MouseButton::None, Vec2::ZERO);
         (*view_state).handler.mouse_move(&event);
     }
@@ -736,21 +926,73 @@ extern "C" fn mouse_leave(this: &mut Object, _: Sel, _nsevent: id) {
     }
 }
 
+/// The event of a drag from another application, from its `NSDraggingInfo`.
+fn drop_event(info: id, view: id) -> DropEvent {
+    unsafe {
+        let point: NSPoint = msg_send![info, draggingLocation];
+        let view_point = view.convertPoint_fromView_(point, nil);
+        let pasteboard: id = msg_send![info, draggingPasteboard];
+        let flags: NSEventModifierFlags = msg_send![class!(NSEvent), modifierFlags];
+        DropEvent {
+            pos: Point::new(view_point.x as f64, view_point.y as f64),
+            data: clipboard::drop_data(pasteboard),
+            mods: make_modifiers(flags),
+        }
+    }
+}
+
+extern "C" fn dragging_updated(this: &mut Object, _: Sel, info: id) -> NSUInteger {
+    // NSDragOperationCopy
+    const DRAG_OPERATION_COPY: NSUInteger = 1;
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let event = drop_event(info, this as id);
+        if (*view_state).handler.drag_over(&event) {
+            DRAG_OPERATION_COPY
+        } else {
+            0
+        }
+    }
+}
+
+extern "C" fn dragging_exited(this: &mut Object, _: Sel, _info: id) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        (*view_state).handler.drag_leave();
+    }
+}
+
+extern "C" fn perform_drag_operation(this: &mut Object, _: Sel, info: id) -> BOOL {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let event = drop_event(info, this as id);
+        if (*view_state).handler.drag_drop(&event) {
+            YES
+        } else {
+            NO
+        }
+    }
+}
+
 extern "C" fn scroll_wheel(this: &mut Object, _: Sel, nsevent: id) {
     unsafe {
         let view_state: *mut c_void = *this.get_ivar("viewState");
         let view_state = &mut *(view_state as *mut ViewState);
+        let precise = nsevent.hasPreciseScrollingDeltas() == cocoa::base::YES;
         let (dx, dy) = {
             let dx = -nsevent.scrollingDeltaX() as f64;
             let dy = -nsevent.scrollingDeltaY() as f64;
-            if nsevent.hasPreciseScrollingDeltas() == cocoa::base::YES {
+            if precise {
                 (dx, dy)
             } else {
                 (dx * 32.0, dy * 32.0)
             }
         };
 
-        let event = mouse_event(
+        let mut event = mouse_event(
             nsevent,
             this as id,
             0,
@@ -758,6 +1000,7 @@ extern "C" fn scroll_wheel(this: &mut Object, _: Sel, nsevent: id) {
             MouseButton::None,
             Vec2::new(dx, dy),
         );
+        event.precise_wheel = precise;
         (*view_state).handler.wheel(&event);
     }
 }
@@ -839,6 +1082,7 @@ extern "C" fn draw_rect(this: &mut Object, _: Sel, dirtyRect: NSRect) {
 
         let view_state: *mut c_void = *this.get_ivar("viewState");
         let view_state = &mut *(view_state as *mut ViewState);
+        apply_text_rendering(cgcontext_ref, view_state.text_rendering);
         let mut piet_ctx = Piet::new_y_down(cgcontext_ref, Some(view_state.text.clone()));
 
         (*view_state).handler.paint(&mut piet_ctx, &invalid);
@@ -851,13 +1095,132 @@ extern "C" fn draw_rect(this: &mut Object, _: Sel, dirtyRect: NSRect) {
     }
 }
 
+/// Set up `context` to draw text with `options`.
+///
+/// Core Text doesn't hint outlines, so hinting is approximated: `Medium` and `Full`
+/// place glyphs on whole pixels, the other modes on fractions of pixels.
+fn apply_text_rendering(context: &CGContextRef, options: TextRenderingOptions) {
+    match options.antialiasing {
+        TextAntialiasing::Default => (),
+        TextAntialiasing::None => context.set_should_antialias(false),
+        TextAntialiasing::Grayscale => {
+            context.set_should_antialias(true);
+            context.set_should_smooth_fonts(false);
+        }
+        TextAntialiasing::Subpixel => {
+            context.set_should_antialias(true);
+            context.set_allows_font_smoothing(true);
+            context.set_should_smooth_fonts(true);
+        }
+    }
+    let whole_pixels = match options.hinting {
+        TextHinting::Default => return,
+        TextHinting::None | TextHinting::Slight => false,
+        TextHinting::Medium | TextHinting::Full => true,
+    };
+    context.set_allows_font_subpixel_positioning(true);
+    context.set_should_subpixel_position_fonts(!whole_pixels);
+    context.set_allows_font_subpixel_quantization(true);
+    context.set_should_subpixel_quantize_fonts(whole_pixels);
+}
+
 fn run_deferred(this: &mut Object, view_state: &mut ViewState, op: DeferredOp) {
     match op {
         DeferredOp::SetSize(size) => set_size_deferred(this, view_state, size),
         DeferredOp::SetPosition(pos) => set_position_deferred(this, view_state, pos),
+        DeferredOp::SetFullscreen(fullscreen, monitor) => {
+            set_fullscreen_deferred(this, view_state, fullscreen, monitor)
+        }
+        DeferredOp::ResizeDrag(edge) => resize_drag_deferred(this, view_state, edge),
     }
 }
 
+fn resize_drag_deferred(this: &mut Object, _view_state: &mut ViewState, edge: ResizeEdge) {
+    // NSEventMaskLeftMouseUp | NSEventMaskLeftMouseDragged
+    const MASK: NSUInteger = (1 << 2) | (1 << 6);
+    const NS_EVENT_TYPE_LEFT_MOUSE_UP: NSUInteger = 2;
+    unsafe {
+        // The button may have been released before we got here.
+        let pressed: NSUInteger = msg_send![class!(NSEvent), pressedMouseButtons];
+        if pressed & 1 == 0 {
+            return;
+        }
+        let window: id = msg_send![this, window];
+        let start: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+        let start_frame: NSRect = msg_send![window, frame];
+        let min_size: NSSize = msg_send![window, minSize];
+        loop {
+            let event: id = msg_send![window, nextEventMatchingMask: MASK];
+            if event == nil {
+                break;
+            }
+            let location: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+            let delta = Vec2::new(location.x - start.x, location.y - start.y);
+            let frame = resized_frame(start_frame, min_size, edge, delta);
+            let () = msg_send![window, setFrame: frame display: YES];
+            let event_type: NSUInteger = msg_send![event, type];
+            if event_type == NS_EVENT_TYPE_LEFT_MOUSE_UP {
+                break;
+            }
+        }
+    }
+}
+
+/// The frame of a window after dragging `edge` by `delta`, in the coordinates of the screen,
+/// where y goes up.
+fn resized_frame(frame: NSRect, min_size: NSSize, edge: ResizeEdge, delta: Vec2) -> NSRect {
+    let mut new_frame = frame;
+    let (left, right, top, bottom) = match edge {
+        ResizeEdge::Top => (false, false, true, false),
+        ResizeEdge::Bottom => (false, false, false, true),
+        ResizeEdge::Left => (true, false, false, false),
+        ResizeEdge::Right => (false, true, false, false),
+        ResizeEdge::TopLeft => (true, false, true, false),
+        ResizeEdge::TopRight => (false, true, true, false),
+        ResizeEdge::BottomLeft => (true, false, false, true),
+        ResizeEdge::BottomRight => (false, true, false, true),
+    };
+    if left {
+        new_frame.size.width = (frame.size.width - delta.x).max(min_size.width);
+        new_frame.origin.x = frame.origin.x + frame.size.width - new_frame.size.width;
+    } else if right {
+        new_frame.size.width = (frame.size.width + delta.x).max(min_size.width);
+    }
+    if bottom {
+        new_frame.size.height = (frame.size.height - delta.y).max(min_size.height);
+        new_frame.origin.y = frame.origin.y + frame.size.height - new_frame.size.height;
+    } else if top {
+        new_frame.size.height = (frame.size.height + delta.y).max(min_size.height);
+    }
+    new_frame
+}
+
+fn set_fullscreen_deferred(
+    this: &mut Object,
+    view_state: &mut ViewState,
+    fullscreen: bool,
+    monitor: Option<Point>,
+) {
+    unsafe {
+        let window: id = msg_send![this, window];
+        if is_fullscreen(window) == fullscreen {
+            return;
+        }
+        if let Some(position) = monitor {
+            // The window goes fullscreen on the screen it is on.
+            set_position_deferred(this, view_state, position);
+        }
+        let behavior: u64 = msg_send![window, collectionBehavior];
+        let () = msg_send![window, setCollectionBehavior: behavior | NS_FULL_SCREEN_PRIMARY];
+        let () = msg_send![window, toggleFullScreen: nil];
+    }
+}
+
+unsafe fn is_fullscreen(window: id) -> bool {
+    let style_mask: u64 = msg_send![window, styleMask];
+    style_mask & NS_FULL_SCREEN_WINDOW_MASK != 0
+}
+
 fn set_size_deferred(this: &mut Object, _view_state: &mut ViewState, size: Size) {
     unsafe {
         let window: id = msg_send![this, window];
@@ -952,6 +1315,78 @@ extern "C" fn window_did_become_key(this: &mut Object, _: Sel, _notification: id
         let view_state: *mut c_void = *this.get_ivar("viewState");
         let view_state = &mut *(view_state as *mut ViewState);
         (*view_state).handler.got_focus();
+        // there are no pasteboard notifications, so check whether it changed
+        // while we were in the background.
+        let change_count = clipboard::change_count();
+        if change_count != (*view_state).clipboard_change_count {
+            (*view_state).clipboard_change_count = change_count;
+            (*view_state).handler.clipboard_changed();
+        }
+    }
+}
+
+/// Called when switching between light and dark mode.
+extern "C" fn view_did_change_effective_appearance(this: &mut Object, _: Sel) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let theme = crate::Application::system_theme();
+        (*view_state).handler.system_theme_changed(&theme);
+    }
+}
+
+/// Called when the accent color changes.
+extern "C" fn system_colors_did_change(this: &mut Object, _: Sel, _notification: id) {
+    view_did_change_effective_appearance(this, sel!(viewDidChangeEffectiveAppearance));
+}
+
+extern "C" fn window_did_change_screen(this: &mut Object, _: Sel, notification: id) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let window: id = msg_send![notification, object];
+        let screen: id = msg_send![window, screen];
+        if let Some(monitor) = screen::get_monitor(screen) {
+            (*view_state).handler.monitor_changed(&monitor);
+        }
+    }
+}
+
+/// Remember the frame of a restored window, since AppKit doesn't tell where a zoomed or
+/// fullscreen window goes back to.
+extern "C" fn window_did_move_or_resize(this: &mut Object, _: Sel, notification: id) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        let window: id = msg_send![notification, object];
+        let zoomed: BOOL = msg_send![window, isZoomed];
+        let miniaturized: BOOL = msg_send![window, isMiniaturized];
+        if zoomed == NO && miniaturized == NO && !is_fullscreen(window) {
+            (*view_state).restored_rect = Some(window_frame(window));
+        }
+    }
+}
+
+/// The frame of the window, with the origin at the top left of the screen.
+unsafe fn window_frame(window: id) -> Rect {
+    // TODO this should be the max y in orig mac coords
+    let screen_height = crate::Screen::get_display_rect().height();
+    let frame: NSRect = msg_send![window, frame];
+    Rect::from_origin_size(
+        (
+            frame.origin.x,
+            screen_height - frame.origin.y - frame.size.height,
+        ),
+        (frame.size.width, frame.size.height),
+    )
+}
+
+/// Called when screens are connected or disconnected, or their arrangement changes.
+extern "C" fn screen_parameters_did_change(this: &mut Object, _: Sel, _notification: id) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        (*view_state).handler.monitors_changed();
     }
 }
 
@@ -980,6 +1415,24 @@ extern "C" fn window_will_close(this: &mut Object, _: Sel, _notification: id) {
     }
 }
 
+impl crate::platform::mac::WindowHandleExt for crate::WindowHandle {
+    fn set_toolbar(&self, items: Vec<ToolbarItem>) {
+        toolbar::set_toolbar(*self.0.nsview.load(), items)
+    }
+
+    fn set_toolbar_style(&self, style: ToolbarStyle) {
+        toolbar::set_toolbar_style(*self.0.nsview.load(), style)
+    }
+
+    fn set_titlebar_accessory(&self, items: Vec<ToolbarItem>, position: AccessoryPosition) {
+        toolbar::set_titlebar_accessory(*self.0.nsview.load(), items, position)
+    }
+
+    fn set_titlebar_transparent(&self, transparent: bool) {
+        toolbar::set_titlebar_transparent(*self.0.nsview.load(), transparent)
+    }
+}
+
 impl WindowHandle {
     pub fn show(&self) {
         unsafe {
@@ -991,7 +1444,21 @@ impl WindowHandle {
                 .autorelease();
             let notif_center: id = msg_send![notif_center_class, defaultCenter];
             let () = msg_send![notif_center, addObserver:*self.nsview.load() selector: sel!(windowDidBecomeKey:) name: notif_string object: window];
-            window.makeKeyAndOrderFront_(nil)
+            let colors_string = NSString::alloc(nil)
+                .init_str("NSSystemColorsDidChangeNotification")
+                .autorelease();
+            let () = msg_send![notif_center, addObserver:*self.nsview.load() selector: sel!(systemColorsDidChange:) name: colors_string object: nil];
+            let screens_string = NSString::alloc(nil)
+                .init_str("NSApplicationDidChangeScreenParametersNotification")
+                .autorelease();
+            let () = msg_send![notif_center, addObserver:*self.nsview.load() selector: sel!(screenParametersDidChange:) name: screens_string object: nil];
+            // Only utility windows hide on deactivation; they shouldn't take focus when shown.
+            let hides_on_deactivate: BOOL = msg_send![window, hidesOnDeactivate];
+            if hides_on_deactivate == YES {
+                let () = msg_send![window, orderFront: nil];
+            } else {
+                window.makeKeyAndOrderFront_(nil)
+            }
         }
     }
 
@@ -1042,26 +1509,47 @@ impl WindowHandle {
     pub fn set_cursor(&mut self, cursor: &Cursor) {
         unsafe {
             let nscursor = class!(NSCursor);
-            #[allow(deprecated)]
             let cursor: id = match cursor {
-                Cursor::Arrow => msg_send![nscursor, arrowCursor],
+                Cursor::Arrow | Cursor::Wait | Cursor::Progress | Cursor::Help => {
+                    msg_send![nscursor, arrowCursor]
+                }
                 Cursor::IBeam => msg_send![nscursor, IBeamCursor],
                 Cursor::Pointer => msg_send![nscursor, pointingHandCursor],
-                Cursor::Crosshair => msg_send![nscursor, crosshairCursor],
-                Cursor::OpenHand => msg_send![nscursor, openHandCursor],
+                Cursor::Crosshair
+                | Cursor::ResizeNwSe
+                | Cursor::ResizeNeSw
+                | Cursor::ZoomIn
+                | Cursor::ZoomOut => msg_send![nscursor, crosshairCursor],
+                Cursor::OpenHand | Cursor::Move => msg_send![nscursor, openHandCursor],
+                Cursor::ClosedHand => msg_send![nscursor, closedHandCursor],
                 Cursor::NotAllowed => msg_send![nscursor, operationNotAllowedCursor],
                 Cursor::ResizeLeftRight => msg_send![nscursor, resizeLeftRightCursor],
                 Cursor::ResizeUpDown => msg_send![nscursor, resizeUpDownCursor],
-                // TODO: support custom cursors
-                Cursor::Custom(_) => msg_send![nscursor, arrowCursor],
+                Cursor::Custom(custom) => (custom.0).0,
             };
             let () = msg_send![cursor, set];
         }
     }
 
-    pub fn make_cursor(&self, _cursor_desc: &CursorDesc) -> Option<Cursor> {
-        tracing::warn!("Custom cursors are not yet supported in the macOS backend");
-        None
+    pub fn make_cursor(&self, cursor_desc: &CursorDesc) -> Option<Cursor> {
+        unsafe {
+            let image = make_nsimage(&cursor_desc.image);
+            if image == nil {
+                return None;
+            }
+
+            // NSCursor measures the hot spot from the top left, like we do.
+            let hot = NSPoint::new(cursor_desc.hot.x, cursor_desc.hot.y);
+            let cursor: id = msg_send![class!(NSCursor), alloc];
+            let cursor: id = msg_send![cursor, initWithImage: image hotSpot: hot];
+            let () = msg_send![image, release];
+            if cursor == nil {
+                return None;
+            }
+            Some(Cursor::Custom(CustomCursor(Arc::new(NSCursorHandle(
+                cursor,
+            )))))
+        }
     }
 
     pub fn request_timer(&self, deadline: std::time::Instant) -> TimerToken {
@@ -1165,9 +1653,9 @@ impl WindowHandle {
         let token = FileDialogToken::next();
         let self_clone = self.clone();
         unsafe {
-            let panel = dialog::build_panel(ty, opts);
+            let panel = dialog::build_panel(ty, opts.clone());
             let block = ConcreteBlock::new(move |response: dialog::NSModalResponse| {
-                let url = dialog::get_path(panel, response).map(|s| FileInfo { path: s.into() });
+                let url = dialog::get_file_info(panel, response, &opts);
                 let view = self_clone.nsview.load();
                 if let Some(view) = (*view).as_ref() {
                     let view_state: *mut c_void = *view.get_ivar("viewState");
@@ -1202,18 +1690,34 @@ impl WindowHandle {
         self.defer(DeferredOp::SetPosition(position))
     }
 
-    pub fn get_position(&self) -> Point {
+    pub fn set_fullscreen(&self, fullscreen: bool, monitor: Option<&Monitor>) {
+        let position = monitor.map(|monitor| monitor.virtual_work_rect().origin());
+        self.defer(DeferredOp::SetFullscreen(fullscreen, position))
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
         unsafe {
-            // TODO this should be the max y in orig mac coords
-            let screen_height = crate::Screen::get_display_rect().height();
+            let window: id = msg_send![*self.nsview.load(), window];
+            is_fullscreen(window)
+        }
+    }
 
+    pub fn get_position(&self) -> Point {
+        unsafe {
             let window: id = msg_send![*self.nsview.load(), window];
-            let current_frame: NSRect = msg_send![window, frame];
+            window_frame(window).origin()
+        }
+    }
 
-            Point::new(
-                current_frame.origin.x,
-                screen_height - current_frame.origin.y - current_frame.size.height,
-            )
+    pub fn get_restored_rect(&self) -> Rect {
+        unsafe {
+            let view = self.nsview.load();
+            let window: id = msg_send![*view, window];
+            let restored_rect = (*view).as_ref().and_then(|view| {
+                let state: *mut c_void = *view.get_ivar("viewState");
+                (*(state as *mut ViewState)).restored_rect
+            });
+            restored_rect.unwrap_or_else(|| window_frame(window))
         }
     }
 
@@ -1245,10 +1749,28 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_text_rendering(&self, options: TextRenderingOptions) {
+        unsafe {
+            let view = self.nsview.load();
+            if let Some(view) = (*view).as_ref() {
+                let state: *mut c_void = *view.get_ivar("viewState");
+                (*(state as *mut ViewState)).text_rendering = options;
+            }
+        }
+        self.invalidate();
+    }
+
     pub fn set_level(&self, level: WindowLevel) {
         unsafe {
-            let level = levels::as_raw_window_level(level);
             let window: id = msg_send![*self.nsview.load(), window];
+            // Like palettes, utility windows only float above the windows of the active app.
+            let hides_on_deactivate = if level == WindowLevel::Utility {
+                YES
+            } else {
+                NO
+            };
+            let () = msg_send![window, setHidesOnDeactivate: hides_on_deactivate];
+            let level = levels::as_raw_window_level(level);
             let () = msg_send![window, setLevel: level];
         }
     }
@@ -1303,8 +1825,42 @@ impl WindowHandle {
         }
     }
 
-    pub fn handle_titlebar(&self, _val: bool) {
-        tracing::warn!("WindowHandle::handle_titlebar is currently unimplemented for Mac.");
+    pub fn handle_titlebar(&self, val: bool) {
+        unsafe {
+            let view = self.nsview.load();
+            if let Some(view) = (*view).as_ref() {
+                let state: *mut c_void = *view.get_ivar("viewState");
+                (*(state as *mut ViewState)).handle_titlebar = val;
+            }
+        }
+    }
+
+    pub fn set_maximize_button(&self, _rect: Option<Rect>) {
+        tracing::warn!("WindowHandle::set_maximize_button is currently unimplemented for Mac.");
+    }
+
+    pub fn begin_move_drag(&self) {
+        unsafe {
+            let window: id = msg_send![*self.nsview.load(), window];
+            // The mouse down we're handling is still the current event.
+            let event: id = msg_send![NSApp(), currentEvent];
+            if event != nil {
+                let () = msg_send![window, performWindowDragWithEvent: event];
+            }
+        }
+    }
+
+    pub fn begin_resize_drag(&self, edge: ResizeEdge) {
+        // This runs its own event loop, so it can't run while the mouse down is being handled.
+        self.defer(DeferredOp::ResizeDrag(edge));
+    }
+
+    pub fn set_dark_titlebar(&self, _dark: bool) {
+        tracing::warn!("WindowHandle::set_dark_titlebar is currently unimplemented for Mac.");
+    }
+
+    pub fn set_corners(&self, _corners: WindowCorners) {
+        tracing::warn!("WindowHandle::set_corners is currently unimplemented for Mac.");
     }
 
     pub fn resizable(&self, resizable: bool) {
@@ -1360,6 +1916,17 @@ impl WindowHandle {
         // TODO: Get actual Scale
         Ok(Scale::new(1.0, 1.0))
     }
+
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        unsafe {
+            let window: id = msg_send![*self.nsview.load(), window];
+            if window == nil {
+                return None;
+            }
+            let screen: id = msg_send![window, screen];
+            screen::get_monitor(screen)
+        }
+    }
 }
 
 #[cfg(feature = "raw-win-handle")]
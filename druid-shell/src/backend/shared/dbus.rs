@@ -0,0 +1,161 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Talking to D-Bus services with the `gdbus` tool, which comes with GLib.
+//!
+//! As with `xdg-open`, this spares us a client of our own. Unlike `dbus-send`,
+//! `gdbus` can send empty and nested containers and watch for signals. Values
+//! go in and come out in the GVariant text format.
+//!
+//! https://docs.gtk.org/glib/gvariant-text-format.html
+
+use std::process::{Child, Command, Stdio};
+
+use anyhow::{anyhow, Error};
+
+/// Call `method` (`interface.member`) of the object at `path` of `dest` on the
+/// session bus, and wait for the reply.
+///
+/// The `args` are GVariant text, and so is the reply.
+pub(crate) fn call(dest: &str, path: &str, method: &str, args: &[String]) -> Result<String, Error> {
+    let output = Command::new("gdbus")
+        .args(&["call", "--session", "--dest", dest, "--object-path", path])
+        .args(&["--method", method])
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| anyhow!("failed to run gdbus: {}", err))?;
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} failed: {}", method, err.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Watch the signals of the object at `path` of `dest` on the session bus.
+///
+/// The child prints a line for each signal, like
+/// `/path: interface.member (uint32 1, 'text')`, until it is killed.
+pub(crate) fn monitor(dest: &str, path: &str) -> Result<Child, Error> {
+    Command::new("gdbus")
+        .args(&[
+            "monitor",
+            "--session",
+            "--dest",
+            dest,
+            "--object-path",
+            path,
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| anyhow!("failed to run gdbus: {}", err))
+}
+
+/// The arguments of the signal `member` of `interface` in a line printed by
+/// [`monitor`], without the parentheses around them.
+pub(crate) fn signal_args<'a>(line: &'a str, interface: &str, member: &str) -> Option<&'a str> {
+    let (_path, signal) = line.split_at(line.find(": ")?);
+    let signal = signal[2..].strip_prefix(interface)?.strip_prefix('.')?;
+    let args = signal.strip_prefix(member)?.trim().strip_prefix('(')?;
+    args.strip_suffix(')')
+}
+
+/// A string in the GVariant text format.
+pub(crate) fn string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('\'');
+    for c in s.chars() {
+        match c {
+            '\'' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// An array of strings in the GVariant text format.
+pub(crate) fn strings<'a>(items: impl IntoIterator<Item = &'a str>) -> String {
+    let items: Vec<_> = items.into_iter().map(string).collect();
+    format!("@as [{}]", items.join(", "))
+}
+
+/// The value of a `uint32` in GVariant text, like the one in a reply
+/// `(uint32 7,)`.
+pub(crate) fn parse_u32(text: &str) -> Option<u32> {
+    let text = text.trim().trim_start_matches('(').trim_start();
+    let text = text.strip_prefix("uint32 ")?;
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+/// The value of a string in GVariant text, as printed by `gdbus`.
+pub(crate) fn parse_string(text: &str) -> Option<String> {
+    let text = text.trim();
+    let quote = text.chars().next().filter(|&c| c == '\'' || c == '"')?;
+    let mut chars = text[1..].chars();
+    let mut s = String::new();
+    loop {
+        match chars.next()? {
+            '\\' => s.push(chars.next()?),
+            c if c == quote => return Some(s),
+            c => s.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn strings_are_quoted() {
+        assert_eq!(string("it's"), r"'it\'s'");
+        assert_eq!(string("a\\b\nc"), r"'a\\b\nc'");
+        assert_eq!(strings(vec!["a", ""]), "@as ['a', '']");
+        assert_eq!(strings(vec![]), "@as []");
+    }
+
+    #[test]
+    fn values_are_parsed() {
+        assert_eq!(parse_u32("(uint32 7,)\n"), Some(7));
+        assert_eq!(parse_u32("uint32 12, 'default'"), Some(12));
+        assert_eq!(parse_u32("('x',)"), None);
+        assert_eq!(parse_string(" 'default'"), Some("default".into()));
+        assert_eq!(parse_string(r#""it's""#), Some("it's".into()));
+    }
+
+    #[test]
+    fn signals_are_parsed() {
+        let line = "/org/freedesktop/Notifications: \
+                    org.freedesktop.Notifications.ActionInvoked (uint32 3, '1')";
+        let interface = "org.freedesktop.Notifications";
+        assert_eq!(
+            signal_args(line, interface, "ActionInvoked"),
+            Some("uint32 3, '1'")
+        );
+        assert_eq!(signal_args(line, interface, "NotificationClosed"), None);
+    }
+}
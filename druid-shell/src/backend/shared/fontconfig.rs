@@ -0,0 +1,186 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Text rendering options for the cairo-based backends.
+//!
+//! piet-cairo does not expose the pango context it uses for text, so we can't
+//! set cairo font options on it. It does use the default pango fontmap, though,
+//! and on Linux that is a fontconfig fontmap, which lets us install a
+//! substitute function that edits the fontconfig pattern of every font it loads.
+
+use std::cell::Cell;
+use std::os::raw::{c_char, c_int, c_void};
+
+use lazy_static::lazy_static;
+
+use super::lookup_symbol;
+use crate::window::{TextAntialiasing, TextHinting, TextRenderingOptions};
+
+type FcPattern = c_void;
+type PangoFontMap = c_void;
+type SubstituteFunc = unsafe extern "C" fn(*mut FcPattern, *mut c_void);
+type DestroyNotify = unsafe extern "C" fn(*mut c_void);
+
+const FC_ANTIALIAS: &[u8] = b"antialias\0";
+const FC_HINTING: &[u8] = b"hinting\0";
+const FC_HINT_STYLE: &[u8] = b"hintstyle\0";
+const FC_RGBA: &[u8] = b"rgba\0";
+
+const FC_HINT_NONE: c_int = 0;
+const FC_HINT_SLIGHT: c_int = 1;
+const FC_HINT_MEDIUM: c_int = 2;
+const FC_HINT_FULL: c_int = 3;
+
+const FC_RGBA_RGB: c_int = 1;
+const FC_RGBA_NONE: c_int = 5;
+
+/// The functions of pango, GObject and fontconfig that we need.
+///
+/// piet-cairo already loads these libraries, so instead of linking them again we look the
+/// functions up in the loaded ones.
+#[allow(clippy::type_complexity)]
+struct Functions {
+    pango_cairo_font_map_get_default: unsafe extern "C" fn() -> *mut PangoFontMap,
+    pango_fc_font_map_get_type: unsafe extern "C" fn() -> usize,
+    pango_fc_font_map_set_default_substitute: unsafe extern "C" fn(
+        *mut PangoFontMap,
+        Option<SubstituteFunc>,
+        *mut c_void,
+        Option<DestroyNotify>,
+    ),
+    pango_fc_font_map_substitute_changed: unsafe extern "C" fn(*mut PangoFontMap),
+    g_type_check_instance_is_a: unsafe extern "C" fn(*mut c_void, usize) -> c_int,
+    fc_pattern_del: unsafe extern "C" fn(*mut FcPattern, *const c_char) -> c_int,
+    fc_pattern_add_bool: unsafe extern "C" fn(*mut FcPattern, *const c_char, c_int) -> c_int,
+    fc_pattern_add_integer: unsafe extern "C" fn(*mut FcPattern, *const c_char, c_int) -> c_int,
+}
+
+lazy_static! {
+    static ref FUNCTIONS: Option<Functions> = unsafe { load() };
+}
+
+thread_local! {
+    /// The options that were applied last, so that they are only applied again when they change.
+    static APPLIED: Cell<TextRenderingOptions> = Cell::new(TextRenderingOptions::default());
+}
+
+unsafe fn load() -> Option<Functions> {
+    macro_rules! load {
+        ($name:literal) => {{
+            std::mem::transmute(lookup_symbol(concat!($name, "\0"))?)
+        }};
+    }
+
+    Some(Functions {
+        pango_cairo_font_map_get_default: load!("pango_cairo_font_map_get_default"),
+        pango_fc_font_map_get_type: load!("pango_fc_font_map_get_type"),
+        pango_fc_font_map_set_default_substitute: load!("pango_fc_font_map_set_default_substitute"),
+        pango_fc_font_map_substitute_changed: load!("pango_fc_font_map_substitute_changed"),
+        g_type_check_instance_is_a: load!("g_type_check_instance_is_a"),
+        fc_pattern_del: load!("FcPatternDel"),
+        fc_pattern_add_bool: load!("FcPatternAddBool"),
+        fc_pattern_add_integer: load!("FcPatternAddInteger"),
+    })
+}
+
+/// Apply `options` to all text drawn by this process.
+///
+/// Nothing happens if they are the options that were applied last, so every
+/// window can pass on its options without flushing the font caches.
+pub fn set_text_rendering(options: TextRenderingOptions) {
+    if APPLIED.with(|applied| applied.replace(options)) == options {
+        return;
+    }
+    let f = match FUNCTIONS.as_ref() {
+        Some(f) => f,
+        None => {
+            tracing::warn!("text rendering options require pango and fontconfig");
+            return;
+        }
+    };
+    unsafe {
+        let fontmap = (f.pango_cairo_font_map_get_default)();
+        if fontmap.is_null()
+            || (f.g_type_check_instance_is_a)(fontmap, (f.pango_fc_font_map_get_type)()) == 0
+        {
+            tracing::warn!("text rendering options require a fontconfig font map");
+            return;
+        }
+        if options == TextRenderingOptions::default() {
+            (f.pango_fc_font_map_set_default_substitute)(fontmap, None, std::ptr::null_mut(), None);
+        } else {
+            let data = Box::into_raw(Box::new(options)) as *mut c_void;
+            (f.pango_fc_font_map_set_default_substitute)(
+                fontmap,
+                Some(substitute),
+                data,
+                Some(destroy),
+            );
+        }
+        (f.pango_fc_font_map_substitute_changed)(fontmap);
+    }
+}
+
+unsafe extern "C" fn substitute(pattern: *mut FcPattern, data: *mut c_void) {
+    let options = &*(data as *const TextRenderingOptions);
+    match options.antialiasing {
+        TextAntialiasing::Default => (),
+        TextAntialiasing::None => {
+            set_bool(pattern, FC_ANTIALIAS, false);
+        }
+        TextAntialiasing::Grayscale => {
+            set_bool(pattern, FC_ANTIALIAS, true);
+            set_int(pattern, FC_RGBA, FC_RGBA_NONE);
+        }
+        TextAntialiasing::Subpixel => {
+            set_bool(pattern, FC_ANTIALIAS, true);
+            set_int(pattern, FC_RGBA, FC_RGBA_RGB);
+        }
+    }
+    let hint_style = match options.hinting {
+        TextHinting::Default => return,
+        TextHinting::None => {
+            set_bool(pattern, FC_HINTING, false);
+            FC_HINT_NONE
+        }
+        TextHinting::Slight => FC_HINT_SLIGHT,
+        TextHinting::Medium => FC_HINT_MEDIUM,
+        TextHinting::Full => FC_HINT_FULL,
+    };
+    if hint_style != FC_HINT_NONE {
+        set_bool(pattern, FC_HINTING, true);
+    }
+    set_int(pattern, FC_HINT_STYLE, hint_style);
+}
+
+unsafe extern "C" fn destroy(data: *mut c_void) {
+    drop(Box::from_raw(data as *mut TextRenderingOptions));
+}
+
+// Fontconfig uses the first value of a property, so any value set by the
+// system configuration has to be removed before ours is added.
+unsafe fn set_bool(pattern: *mut FcPattern, object: &[u8], value: bool) {
+    // the substitute function is only installed once the functions were found
+    if let Some(f) = FUNCTIONS.as_ref() {
+        (f.fc_pattern_del)(pattern, object.as_ptr() as *const c_char);
+        (f.fc_pattern_add_bool)(pattern, object.as_ptr() as *const c_char, value as c_int);
+    }
+}
+
+unsafe fn set_int(pattern: *mut FcPattern, object: &[u8], value: c_int) {
+    if let Some(f) = FUNCTIONS.as_ref() {
+        (f.fc_pattern_del)(pattern, object.as_ptr() as *const c_char);
+        (f.fc_pattern_add_integer)(pattern, object.as_ptr() as *const c_char, value);
+    }
+}
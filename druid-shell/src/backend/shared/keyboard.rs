@@ -209,3 +209,28 @@ pub fn hardware_keycode_to_code(hw_keycode: u16) -> Code {
         _ => Code::Unidentified,
     }
 }
+
+#[cfg(target_os = "macos")]
+/// Map a character to the code of the key that types it on a US keyboard.
+///
+/// Letters are matched regardless of case. This is used to find the key of
+/// a hotkey, when it has to be registered with the system by key code.
+pub fn char_to_code(c: char) -> Option<Code> {
+    Some(match c.to_ascii_uppercase() {
+        c @ 'A'..='Z' => format!("Key{}", c).parse().ok()?,
+        c @ '0'..='9' => format!("Digit{}", c).parse().ok()?,
+        ' ' => Code::Space,
+        '-' => Code::Minus,
+        '=' => Code::Equal,
+        '[' => Code::BracketLeft,
+        ']' => Code::BracketRight,
+        '\\' => Code::Backslash,
+        ';' => Code::Semicolon,
+        '\'' => Code::Quote,
+        '`' => Code::Backquote,
+        ',' => Code::Comma,
+        '.' => Code::Period,
+        '/' => Code::Slash,
+        _ => return None,
+    })
+}
@@ -20,3 +20,16 @@ cfg_if::cfg_if! {
         pub use keyboard::*;
     }
 }
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        mod dbus;
+        mod fontconfig;
+        pub use fontconfig::*;
+        mod notification;
+        pub(crate) use notification::Notifications;
+        mod symbol;
+        pub(crate) use symbol::lookup_symbol;
+        mod xdg;
+        pub use xdg::*;
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Desktop notifications, using the freedesktop notification protocol over D-Bus.
+//!
+//! https://specifications.freedesktop.org/notification-spec/notification-spec-latest.html
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader};
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Error};
+
+use super::dbus;
+use crate::notification::{DesktopNotification, NotificationToken};
+
+const NOTIFICATIONS_NAME: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+/// The action key of clicks on the notification itself.
+const DEFAULT_ACTION: &str = "default";
+/// The longest argument the kernel passes to a process; larger icons are left out.
+const MAX_ARG_LEN: usize = 128 * 1024;
+
+/// The notifications of an application, and the `gdbus` process that watches
+/// for clicks on them.
+pub(crate) struct Notifications {
+    monitor: Child,
+    shared: Arc<Shared>,
+}
+
+/// The state shared with the threads that wait for the server.
+#[derive(Default)]
+struct Shared {
+    /// The notifications being shown, by their id.
+    shown: Mutex<HashMap<u32, NotificationToken>>,
+    /// The activations that the application hasn't handled yet.
+    activated: Mutex<VecDeque<(NotificationToken, Option<usize>)>>,
+}
+
+impl Notifications {
+    /// Start watching the notification server.
+    ///
+    /// Activations are received on a thread of their own, which calls `wake`
+    /// so that the event loop calls [`take_activations`].
+    ///
+    /// [`take_activations`]: Notifications::take_activations
+    pub(crate) fn connect(wake: impl Fn() + Send + 'static) -> Result<Notifications, Error> {
+        let mut monitor = dbus::monitor(NOTIFICATIONS_NAME, NOTIFICATIONS_PATH)?;
+        let output = monitor.stdout.take().unwrap();
+        let shared = Arc::new(Shared::default());
+        let thread_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("druid-notifications".into())
+            .spawn(move || {
+                for line in BufReader::new(output).lines() {
+                    match line {
+                        Ok(line) => thread_shared.handle_signal(&line, &wake),
+                        Err(e) => {
+                            tracing::debug!("The notification monitor closed: {}", e);
+                            return;
+                        }
+                    }
+                }
+            })
+            .context("failed to start the notification thread")?;
+        Ok(Notifications { monitor, shared })
+    }
+
+    /// Ask the server to show `notification`.
+    ///
+    /// The server replies on another thread, so the event loop keeps running.
+    pub(crate) fn show(
+        &self,
+        notification: &DesktopNotification,
+        token: NotificationToken,
+    ) -> Result<(), Error> {
+        let args = notify_args(notification);
+        let shared = self.shared.clone();
+        std::thread::Builder::new()
+            .name("druid-notify".into())
+            .spawn(move || {
+                let reply = dbus::call(
+                    NOTIFICATIONS_NAME,
+                    NOTIFICATIONS_PATH,
+                    "org.freedesktop.Notifications.Notify",
+                    &args,
+                )
+                .and_then(|reply| {
+                    dbus::parse_u32(&reply).ok_or_else(|| anyhow!("unexpected reply {}", reply))
+                });
+                match reply {
+                    Ok(id) => {
+                        shared.shown.lock().unwrap().insert(id, token);
+                    }
+                    Err(e) => tracing::warn!("Failed to show a notification: {:#}", e),
+                }
+            })
+            .context("failed to start the notification thread")?;
+        Ok(())
+    }
+
+    /// Take the activations received since the last call.
+    pub(crate) fn take_activations(&self) -> Vec<(NotificationToken, Option<usize>)> {
+        self.shared.activated.lock().unwrap().drain(..).collect()
+    }
+}
+
+impl Drop for Notifications {
+    fn drop(&mut self) {
+        // This ends the thread.
+        let _ = self.monitor.kill();
+        let _ = self.monitor.wait();
+    }
+}
+
+impl Shared {
+    /// Handle a line printed by the monitor.
+    fn handle_signal(&self, line: &str, wake: &impl Fn()) {
+        if let Some(args) = dbus::signal_args(line, NOTIFICATIONS_NAME, "ActionInvoked") {
+            let (id, key) = match args.find(',') {
+                Some(comma) => (&args[..comma], &args[comma + 1..]),
+                None => return,
+            };
+            let token = match dbus::parse_u32(id).and_then(|id| self.shown_token(id)) {
+                Some(token) => token,
+                None => return,
+            };
+            let action = match dbus::parse_string(key).and_then(|key| parse_action(&key)) {
+                Some(action) => action,
+                None => return,
+            };
+            self.activated.lock().unwrap().push_back((token, action));
+            wake();
+        } else if let Some(args) = dbus::signal_args(line, NOTIFICATIONS_NAME, "NotificationClosed")
+        {
+            if let Some(id) = dbus::parse_u32(args) {
+                self.shown.lock().unwrap().remove(&id);
+            }
+        }
+    }
+
+    fn shown_token(&self, id: u32) -> Option<NotificationToken> {
+        self.shown.lock().unwrap().get(&id).copied()
+    }
+}
+
+/// The arguments of a `Notify` call, in GVariant text.
+fn notify_args(notification: &DesktopNotification) -> Vec<String> {
+    let app_name = std::env::args_os()
+        .next()
+        .and_then(|path| {
+            let path: &std::path::Path = path.as_ref();
+            path.file_name()?.to_str().map(str::to_owned)
+        })
+        .unwrap_or_default();
+    let indices: Vec<_> = (0..notification.actions.len())
+        .map(|i| i.to_string())
+        .collect();
+    let mut actions = vec![DEFAULT_ACTION, ""];
+    for (i, label) in indices.iter().zip(&notification.actions) {
+        actions.push(i);
+        actions.push(label);
+    }
+    let mut hints = String::from("@a{sv} {}");
+    if let Some(icon) = &notification.icon {
+        let mut pixels = Vec::with_capacity(icon.width() * icon.height() * 4);
+        for row in icon.pixel_colors() {
+            for p in row {
+                let (r, g, b, a) = p.as_rgba8();
+                pixels.extend([r, g, b, a].iter().map(u8::to_string));
+            }
+        }
+        // width, height, rowstride, has_alpha, bits_per_sample, channels, data
+        let image = format!(
+            "@a{{sv}} {{'image-data': <(int32 {}, int32 {}, int32 {}, true, int32 8, int32 4, @ay [{}])>}}",
+            icon.width(),
+            icon.height(),
+            icon.width() * 4,
+            pixels.join(", ")
+        );
+        if image.len() <= MAX_ARG_LEN {
+            hints = image;
+        } else {
+            tracing::warn!("The notification icon is too large to send; leaving it out");
+        }
+    }
+    vec![
+        dbus::string(&app_name),
+        // replaces_id
+        "uint32 0".into(),
+        // app_icon
+        dbus::string(""),
+        dbus::string(&notification.title),
+        dbus::string(&notification.body),
+        dbus::strings(actions),
+        hints,
+        // expire_timeout: the server's default
+        "int32 -1".into(),
+    ]
+}
+
+/// The action index of an action key, or `Some(None)` for the notification itself.
+fn parse_action(key: &str) -> Option<Option<usize>> {
+    if key == DEFAULT_ACTION {
+        Some(None)
+    } else {
+        key.parse().ok().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn action_keys() {
+        assert_eq!(parse_action("default"), Some(None));
+        assert_eq!(parse_action("2"), Some(Some(2)));
+        assert_eq!(parse_action("other"), None);
+    }
+
+    #[test]
+    fn activations() {
+        let shared = Shared::default();
+        let token = NotificationToken::next();
+        shared.shown.lock().unwrap().insert(3, token);
+        let woken = std::cell::Cell::new(0);
+        let wake = || woken.set(woken.get() + 1);
+
+        shared.handle_signal(
+            "/org/freedesktop/Notifications: \
+             org.freedesktop.Notifications.ActionInvoked (uint32 3, '1')",
+            &wake,
+        );
+        // someone else's notification
+        shared.handle_signal(
+            "/org/freedesktop/Notifications: \
+             org.freedesktop.Notifications.ActionInvoked (uint32 4, 'default')",
+            &wake,
+        );
+        assert_eq!(woken.get(), 1);
+        let activations = std::mem::take(&mut *shared.activated.lock().unwrap());
+        assert_eq!(activations, vec![(token, Some(1))]);
+
+        shared.handle_signal(
+            "/org/freedesktop/Notifications: \
+             org.freedesktop.Notifications.NotificationClosed (uint32 3, uint32 2)",
+            &wake,
+        );
+        assert!(shared.shown.lock().unwrap().is_empty());
+    }
+}
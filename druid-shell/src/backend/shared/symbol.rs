@@ -0,0 +1,32 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Looking up functions in the libraries that are already loaded.
+
+use std::os::raw::{c_char, c_void};
+
+/// Look up a function in the libraries that are loaded, such as the parts of GDK that
+/// `gdk-sys` doesn't bind. `name` must end in a NUL byte.
+///
+/// Returns `None`, and logs why, if there is no such function.
+pub(crate) fn lookup_symbol(name: &str) -> Option<*mut c_void> {
+    debug_assert!(name.ends_with('\0'));
+    let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr() as *const c_char) };
+    if symbol.is_null() {
+        tracing::info!("{} not found", name.trim_end_matches('\0'));
+        None
+    } else {
+        Some(symbol)
+    }
+}
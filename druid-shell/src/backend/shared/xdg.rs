@@ -0,0 +1,102 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opening URLs and files with the freedesktop.org tools.
+
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::anyhow;
+
+use super::dbus;
+use crate::Error;
+
+/// Open `uri` with the user's preferred application, using `xdg-open`.
+///
+/// `xdg-open` forwards to the OpenURI portal when we're sandboxed.
+pub(crate) fn open_uri(uri: &str) -> Result<(), Error> {
+    let mut child = Command::new("xdg-open")
+        .arg(uri)
+        .spawn()
+        .map_err(|err| anyhow!("failed to run xdg-open: {}", err))?;
+    // reap the child when it exits, without blocking the event loop.
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Show `path` in the file manager, with the file selected.
+///
+/// This asks the file manager over the `org.freedesktop.FileManager1` D-Bus
+/// interface. If no file manager implements it, the parent directory is
+/// opened instead, without selecting anything.
+pub(crate) fn reveal_path(path: &Path) -> Result<(), Error> {
+    // File managers only know what to do with absolute paths.
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    let args = [
+        dbus::strings(vec![file_uri(&path).as_str()]),
+        dbus::string(""),
+    ];
+    let fallback = file_uri(path.parent().unwrap_or(&path));
+    // Wait for the reply on another thread, so the event loop keeps running.
+    std::thread::spawn(move || {
+        let shown = dbus::call(
+            "org.freedesktop.FileManager1",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+            &args,
+        );
+        if let Err(err) = shown {
+            tracing::debug!("Failed to show the path in the file manager: {:#}", err);
+            if let Err(err) = open_uri(&fallback) {
+                tracing::warn!("Failed to open {}: {}", fallback, err);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// The `file://` URI of a path.
+pub(crate) fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// The path of a `file://` URI.
+pub(crate) fn uri_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // Skip the host, which is empty or `localhost` for local files.
+    let path = &rest[rest.find('/')?..];
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    Some(PathBuf::from(OsString::from_vec(bytes)))
+}
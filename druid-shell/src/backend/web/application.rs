@@ -14,7 +14,12 @@
 
 //! Web implementation of features at the application scope.
 
+use std::path::Path;
+
+use crate::appearance::SystemTheme;
 use crate::application::AppHandler;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::notification::{DesktopNotification, NotificationToken};
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -31,10 +36,64 @@ impl Application {
 
     pub fn quit(&self) {}
 
+    pub fn cancel_quit(&self) {}
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard
     }
 
+    pub fn register_global_hotkey(
+        &self,
+        _hotkey: &HotKey,
+        _token: GlobalHotKeyToken,
+    ) -> Result<(), crate::Error> {
+        Err(crate::Error::Unsupported)
+    }
+
+    pub fn unregister_global_hotkey(&self, _token: GlobalHotKeyToken) {}
+
+    pub fn show_notification(
+        &self,
+        _notification: &DesktopNotification,
+        _token: NotificationToken,
+    ) -> Result<(), crate::Error> {
+        Err(crate::Error::Unsupported)
+    }
+
+    pub fn open_url(&self, url: &str) -> Result<(), crate::Error> {
+        let window = web_sys::window().ok_or(crate::Error::Unsupported)?;
+        match window.open_with_url_and_target(url, "_blank") {
+            Ok(Some(_)) => Ok(()),
+            // Popup blockers make `open` return `null`.
+            _ => Err(crate::Error::Unsupported),
+        }
+    }
+
+    pub fn reveal_path(&self, _path: &Path) -> Result<(), crate::Error> {
+        Err(crate::Error::Unsupported)
+    }
+
+    pub fn show_message_dialog(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+        // confirm() doesn't let us label the buttons, so we name them in the text.
+        let text = format!(
+            "{}\n\n{}\n\nOK: {}\nCancel: {}",
+            title, message, confirm, cancel
+        );
+        web_sys::window()
+            .and_then(|w| w.confirm_with_message(&text).ok())
+            .unwrap_or(false)
+    }
+
+    pub fn system_theme() -> SystemTheme {
+        let dark = web_sys::window()
+            .and_then(|w| w.match_media("(prefers-color-scheme: dark)").ok().flatten())
+            .map_or(false, |query| query.matches());
+        SystemTheme {
+            dark,
+            accent_color: None,
+        }
+    }
+
     pub fn get_locale() -> String {
         web_sys::window()
             .and_then(|w| w.navigator().language())
@@ -14,7 +14,11 @@
 
 //! Interactions with the browser pasteboard.
 
+use std::path::PathBuf;
+
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::piet::ImageBuf;
+use crate::Error;
 
 /// The browser clipboard.
 #[derive(Debug, Clone, Default)]
@@ -22,13 +26,13 @@ pub struct Clipboard;
 
 impl Clipboard {
     /// Put a string onto the system clipboard.
-    pub fn put_string(&mut self, _s: impl AsRef<str>) {
-        tracing::warn!("unimplemented");
+    pub fn put_string(&mut self, _s: impl AsRef<str>) -> Result<(), Error> {
+        Err(Error::Unsupported)
     }
 
     /// Put multi-format data on the system clipboard.
-    pub fn put_formats(&mut self, _formats: &[ClipboardFormat]) {
-        tracing::warn!("unimplemented");
+    pub fn put_formats(&mut self, _formats: &[ClipboardFormat]) -> Result<(), Error> {
+        Err(Error::Unsupported)
     }
 
     /// Get a string from the system clipboard, if one is available.
@@ -37,6 +41,28 @@ impl Clipboard {
         None
     }
 
+    /// Put an image onto the system clipboard.
+    pub fn put_image(&mut self, _image: &ImageBuf) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        tracing::warn!("unimplemented");
+        None
+    }
+
+    /// Put a list of files onto the system clipboard.
+    pub fn put_files(&mut self, _paths: &[PathBuf]) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// Get the list of files on the system clipboard, if there is one.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        tracing::warn!("unimplemented");
+        None
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, _formats: &[FormatId]) -> Option<FormatId> {
@@ -28,6 +28,7 @@ pub(crate) fn convert_keyboard_event(
         state,
         key: event.key().parse().unwrap_or(KbKey::Unidentified),
         code: convert_code(&event.code()),
+        scancode: event.key_code(),
         location: convert_location(event.location()),
         mods,
         repeat: event.repeat(),
@@ -0,0 +1,35 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The browser has no system tray.
+
+use super::menu::Menu;
+use crate::piet::ImageBuf;
+use crate::Error;
+
+pub struct TrayIcon;
+
+impl TrayIcon {
+    pub fn new(_icon: &ImageBuf) -> Result<TrayIcon, Error> {
+        Err(Error::Unsupported)
+    }
+
+    pub fn set_icon(&mut self, _icon: &ImageBuf) {}
+
+    pub fn set_tooltip(&mut self, _tooltip: &str) {}
+
+    pub fn set_menu(&mut self, _menu: Menu) -> Result<(), Error> {
+        Err(Error::Unsupported)
+    }
+}
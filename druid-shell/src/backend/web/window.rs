@@ -37,16 +37,17 @@ use super::keycodes::convert_keyboard_event;
 use super::menu::Menu;
 use crate::common_util::{ClickCounter, IdleCallback};
 use crate::dialog::{FileDialogOptions, FileDialogType};
-use crate::error::Error as ShellError;
 use crate::scale::{Scale, ScaledArea};
 
 use crate::keyboard::{KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::region::Region;
+use crate::screen::Monitor;
 use crate::text::{simulate_input, Event};
 use crate::window;
 use crate::window::{
-    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+    FileDialogToken, IdleToken, ResizeEdge, TextFieldToken, TextRenderingOptions, TimerToken,
+    WinHandler, WindowCorners, WindowLevel,
 };
 
 // This is a macro instead of a function since KeyboardEvent and MouseEvent has identical functions
@@ -194,6 +195,7 @@ fn setup_mouse_down_callback(ws: &Rc<WindowState>) {
                 focus: false,
                 button,
                 wheel_delta: Vec2::ZERO,
+                precise_wheel: false,
             };
             state.handler.borrow_mut().mouse_down(&event);
         }
@@ -213,6 +215,7 @@ fn setup_mouse_up_callback(ws: &Rc<WindowState>) {
                 focus: false,
                 button,
                 wheel_delta: Vec2::ZERO,
+                precise_wheel: false,
             };
             state.handler.borrow_mut().mouse_up(&event);
         }
@@ -231,6 +234,7 @@ fn setup_mouse_move_callback(ws: &Rc<WindowState>) {
             focus: false,
             button: MouseButton::None,
             wheel_delta: Vec2::ZERO,
+            precise_wheel: false,
         };
         state.handler.borrow_mut().mouse_move(&event);
     });
@@ -266,6 +270,8 @@ fn setup_scroll_callback(ws: &Rc<WindowState>) {
             focus: false,
             button: MouseButton::None,
             wheel_delta,
+            // browsers report mouse wheels in pixels too, so we can't tell
+            precise_wheel: false,
         };
         state.handler.borrow_mut().wheel(&event);
     });
@@ -479,6 +485,10 @@ impl WindowHandle {
         warn!("WindowHandle::set_position unimplemented for web");
     }
 
+    pub fn set_text_rendering(&self, _options: TextRenderingOptions) {
+        warn!("set_text_rendering unimplemented for web");
+    }
+
     pub fn set_level(&self, _level: WindowLevel) {
         warn!("WindowHandle::set_level  is currently unimplemented for web.");
     }
@@ -497,6 +507,11 @@ impl WindowHandle {
         Size::new(0.0, 0.0)
     }
 
+    pub fn get_restored_rect(&self) -> Rect {
+        warn!("WindowHandle::get_restored_rect unimplemented for web.");
+        Rect::ZERO
+    }
+
     pub fn content_insets(&self) -> Insets {
         warn!("WindowHandle::content_insets unimplemented for web.");
         Insets::ZERO
@@ -511,10 +526,38 @@ impl WindowHandle {
         window::WindowState::Restored
     }
 
+    pub fn set_fullscreen(&self, _fullscreen: bool, _monitor: Option<&Monitor>) {
+        warn!("WindowHandle::set_fullscreen unimplemented for web.");
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        false
+    }
+
     pub fn handle_titlebar(&self, _val: bool) {
         warn!("WindowHandle::handle_titlebar unimplemented for web.");
     }
 
+    pub fn set_maximize_button(&self, _rect: Option<Rect>) {
+        warn!("WindowHandle::set_maximize_button unimplemented for web.");
+    }
+
+    pub fn begin_move_drag(&self) {
+        warn!("WindowHandle::begin_move_drag unimplemented for web.");
+    }
+
+    pub fn begin_resize_drag(&self, _edge: ResizeEdge) {
+        warn!("WindowHandle::begin_resize_drag unimplemented for web.");
+    }
+
+    pub fn set_dark_titlebar(&self, _dark: bool) {
+        warn!("WindowHandle::set_dark_titlebar unimplemented for web.");
+    }
+
+    pub fn set_corners(&self, _corners: WindowCorners) {
+        warn!("WindowHandle::set_corners unimplemented for web.");
+    }
+
     pub fn close(&self) {
         // TODO
     }
@@ -644,8 +687,8 @@ impl WindowHandle {
         &self,
         _ty: FileDialogType,
         _options: FileDialogOptions,
-    ) -> Result<OsString, ShellError> {
-        Err(ShellError::Platform(Error::Unimplemented))
+    ) -> Result<OsString, crate::Error> {
+        Err(crate::Error::Platform(Error::Unimplemented))
     }
 
     /// Get a handle that can be used to schedule an idle task.
@@ -657,15 +700,19 @@ impl WindowHandle {
     }
 
     /// Get the `Scale` of the window.
-    pub fn get_scale(&self) -> Result<Scale, ShellError> {
+    pub fn get_scale(&self) -> Result<Scale, crate::Error> {
         Ok(self
             .0
             .upgrade()
-            .ok_or(ShellError::WindowDropped)?
+            .ok_or(crate::Error::WindowDropped)?
             .scale
             .get())
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        None
+    }
+
     pub fn set_menu(&self, _menu: Menu) {
         warn!("set_menu unimplemented for web");
     }
@@ -757,16 +804,24 @@ fn set_cursor(canvas: &web_sys::HtmlCanvasElement, cursor: &Cursor) {
         .style()
         .set_property(
             "cursor",
-            #[allow(deprecated)]
             match cursor {
                 Cursor::Arrow => "default",
                 Cursor::IBeam => "text",
                 Cursor::Pointer => "pointer",
                 Cursor::Crosshair => "crosshair",
                 Cursor::OpenHand => "grab",
+                Cursor::ClosedHand => "grabbing",
                 Cursor::NotAllowed => "not-allowed",
                 Cursor::ResizeLeftRight => "ew-resize",
                 Cursor::ResizeUpDown => "ns-resize",
+                Cursor::ResizeNwSe => "nwse-resize",
+                Cursor::ResizeNeSw => "nesw-resize",
+                Cursor::Move => "move",
+                Cursor::ZoomIn => "zoom-in",
+                Cursor::ZoomOut => "zoom-out",
+                Cursor::Wait => "wait",
+                Cursor::Progress => "progress",
+                Cursor::Help => "help",
                 // TODO: support custom cursors
                 Cursor::Custom(_) => "default",
             },
@@ -17,31 +17,49 @@
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::mem;
+use std::path::Path;
 use std::ptr;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use winapi::shared::minwindef::{FALSE, HINSTANCE};
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, HINSTANCE, LPARAM, LRESULT, UINT, WPARAM};
 use winapi::shared::ntdef::LPCWSTR;
 use winapi::shared::windef::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, HCURSOR, HWND};
-use winapi::shared::winerror::HRESULT_FROM_WIN32;
+use winapi::shared::winerror::{
+    ERROR_HOTKEY_ALREADY_REGISTERED, ERROR_SUCCESS, FAILED, HRESULT_FROM_WIN32,
+};
+use winapi::um::combaseapi::{CoInitializeEx, CoUninitialize};
+use winapi::um::dwmapi::DwmGetColorizationColor;
 use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::objbase::{COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE};
+use winapi::um::ole2::OleInitialize;
+use winapi::um::shellapi::ShellExecuteW;
 use winapi::um::shellscalingapi::PROCESS_PER_MONITOR_DPI_AWARE;
 use winapi::um::winnls::GetUserDefaultLocaleName;
 use winapi::um::winnt::LOCALE_NAME_MAX_LENGTH;
+use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, LSTATUS, RRF_RT_REG_DWORD};
 use winapi::um::winuser::{
-    DispatchMessageW, GetAncestor, GetMessageW, LoadIconW, PeekMessageW, PostMessageW,
-    PostQuitMessage, RegisterClassW, TranslateAcceleratorW, TranslateMessage, GA_ROOT,
-    IDI_APPLICATION, MSG, PM_NOREMOVE, WM_TIMER, WNDCLASSW,
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetAncestor, GetMessageW, LoadIconW,
+    MessageBoxW, PeekMessageW, PostMessageW, PostQuitMessage, RegisterClassW, RegisterHotKey,
+    TranslateAcceleratorW, TranslateMessage, UnregisterHotKey, GA_ROOT, HWND_MESSAGE,
+    IDI_APPLICATION, IDOK, MB_ICONERROR, MB_OKCANCEL, MB_TASKMODAL, MOD_ALT, MOD_CONTROL,
+    MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, MSG, PM_NOREMOVE, SW_SHOWNORMAL, WM_HOTKEY, WM_TIMER,
+    WNDCLASSW,
 };
 
 use piet_common::D2DLoadedFonts;
 
+use crate::appearance::SystemTheme;
 use crate::application::AppHandler;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::keyboard::Modifiers;
+use crate::notification::{DesktopNotification, NotificationToken};
+use crate::piet::Color;
 
 use super::accels;
 use super::clipboard::Clipboard;
 use super::error::Error;
+use super::keyboard;
 use super::util::{self, FromWide, ToWide, CLASS_NAME, OPTIONAL_FUNCTIONS};
 use super::window::{self, DS_REQUEST_DESTROY};
 
@@ -54,17 +72,30 @@ pub(crate) struct Application {
 struct State {
     quitting: bool,
     windows: HashSet<HWND>,
+    handler: Option<Box<dyn AppHandler>>,
+    /// The message-only window that global hotkeys are registered with, once there is one.
+    hotkey_hwnd: HWND,
+    /// The AppUserModelID to show toasts under, if the application set one.
+    app_user_model_id: Option<String>,
 }
 
 /// Used to ensure the window class is registered only once per process.
 static WINDOW_CLASS_REGISTERED: AtomicBool = AtomicBool::new(false);
 
+const HOTKEY_CLASS_NAME: &str = "druid-hotkeys";
+
+/// Used to ensure the hotkey window class is registered only once per process.
+static HOTKEY_CLASS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
 impl Application {
     pub fn new() -> Result<Application, Error> {
         Application::init()?;
         let state = Rc::new(RefCell::new(State {
             quitting: false,
             windows: HashSet::new(),
+            handler: None,
+            hotkey_hwnd: ptr::null_mut(),
+            app_user_model_id: None,
         }));
         let fonts = D2DLoadedFonts::default();
         Ok(Application { state, fonts })
@@ -75,6 +106,13 @@ impl Application {
     #[allow(clippy::unnecessary_wraps)]
     fn init() -> Result<(), Error> {
         util::attach_console();
+        // drags from other applications need OLE on this thread.
+        unsafe {
+            let hr = OleInitialize(ptr::null_mut());
+            if FAILED(hr) {
+                tracing::warn!("failed to initialize OLE: {}", Error::Hr(hr));
+            }
+        }
         if let Some(func) = OPTIONAL_FUNCTIONS.SetProcessDpiAwarenessContext {
             // This function is only supported on windows 10
             unsafe {
@@ -121,7 +159,27 @@ impl Application {
         self.state.borrow_mut().windows.remove(&hwnd)
     }
 
-    pub fn run(self, _handler: Option<Box<dyn AppHandler>>) {
+    /// Calls `f` with the handler passed to `run`, if there is one.
+    pub(crate) fn with_handler(&self, f: impl FnOnce(&mut dyn AppHandler)) {
+        // Take the handler out, so that it can call back into the application.
+        let handler = self.state.borrow_mut().handler.take();
+        if let Some(mut handler) = handler {
+            f(&mut *handler);
+            self.state.borrow_mut().handler = Some(handler);
+        }
+    }
+
+    /// Whether [`quit`] has been called.
+    ///
+    /// [`quit`]: Application::quit
+    pub(crate) fn is_quitting(&self) -> bool {
+        self.state
+            .try_borrow()
+            .map_or(false, |state| state.quitting)
+    }
+
+    pub fn run(self, handler: Option<Box<dyn AppHandler>>) {
+        self.state.borrow_mut().handler = handler;
         unsafe {
             // Handle windows messages.
             //
@@ -162,6 +220,7 @@ impl Application {
                 }
             }
         }
+        self.state.borrow_mut().handler = None;
     }
 
     pub fn quit(&self) {
@@ -191,6 +250,10 @@ impl Application {
         }
     }
 
+    pub fn cancel_quit(&self) {
+        // Only macOS waits for an answer to its quit requests.
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard
     }
@@ -209,4 +272,230 @@ impl Application {
             "en-US".into()
         })
     }
+
+    pub fn register_global_hotkey(
+        &self,
+        hotkey: &HotKey,
+        token: GlobalHotKeyToken,
+    ) -> Result<(), crate::Error> {
+        let (mods, vk) = hotkey_to_vk(hotkey).ok_or(crate::Error::Unsupported)?;
+        let hwnd = self.hotkey_hwnd()?;
+        unsafe {
+            if RegisterHotKey(hwnd, token.into_raw() as i32, mods, vk) == FALSE {
+                let error = GetLastError();
+                if error == ERROR_HOTKEY_ALREADY_REGISTERED {
+                    return Err(crate::Error::HotKeyInUse);
+                }
+                return Err(Error::Hr(HRESULT_FROM_WIN32(error)).into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn unregister_global_hotkey(&self, token: GlobalHotKeyToken) {
+        let hwnd = self.state.borrow().hotkey_hwnd;
+        if !hwnd.is_null() {
+            unsafe {
+                UnregisterHotKey(hwnd, token.into_raw() as i32);
+            }
+        }
+    }
+
+    /// The window that receives `WM_HOTKEY`, created on first use.
+    ///
+    /// Hotkeys registered without a window are posted to the thread, and get
+    /// lost while a modal loop (like the one that moves a window) is running.
+    fn hotkey_hwnd(&self) -> Result<HWND, Error> {
+        let hwnd = self.state.borrow().hotkey_hwnd;
+        if !hwnd.is_null() {
+            return Ok(hwnd);
+        }
+        unsafe {
+            if HOTKEY_CLASS_REGISTERED
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                let class_name = HOTKEY_CLASS_NAME.to_wide();
+                let mut wnd: WNDCLASSW = mem::zeroed();
+                wnd.lpfnWndProc = Some(hotkey_proc);
+                wnd.lpszClassName = class_name.as_ptr();
+                if RegisterClassW(&wnd) == 0 {
+                    return Err(Error::Hr(HRESULT_FROM_WIN32(GetLastError())));
+                }
+            }
+            let hwnd = CreateWindowExW(
+                0,
+                HOTKEY_CLASS_NAME.to_wide().as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            if hwnd.is_null() {
+                return Err(Error::NullHwnd);
+            }
+            self.state.borrow_mut().hotkey_hwnd = hwnd;
+            Ok(hwnd)
+        }
+    }
+
+    pub fn show_notification(
+        &self,
+        notification: &DesktopNotification,
+        token: NotificationToken,
+    ) -> Result<(), crate::Error> {
+        let app_id = self.state.borrow().app_user_model_id.clone();
+        if let Some(app_id) = app_id {
+            match unsafe { super::toast::show_toast(&app_id, notification, token) } {
+                Ok(()) => return Ok(()),
+                Err(e) => tracing::warn!("failed to show a toast, showing a balloon: {}", e),
+            }
+        }
+        super::tray::show_notification(notification, token)
+    }
+
+    pub fn open_url(&self, url: &str) -> Result<(), crate::Error> {
+        shell_execute(url.to_owned(), None);
+        Ok(())
+    }
+
+    pub fn reveal_path(&self, path: &Path) -> Result<(), crate::Error> {
+        let params = format!("/select,\"{}\"", path.display());
+        shell_execute("explorer.exe".to_owned(), Some(params));
+        Ok(())
+    }
+
+    pub fn system_theme() -> SystemTheme {
+        SystemTheme {
+            dark: apps_use_dark_theme(),
+            accent_color: colorization_color(),
+        }
+    }
+
+    pub fn show_message_dialog(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+        // MessageBoxW doesn't let us label the buttons, so we name them in the text.
+        let text = format!("{}\n\nOK: {}\nCancel: {}", message, confirm, cancel);
+        let result = unsafe {
+            MessageBoxW(
+                ptr::null_mut(),
+                text.to_wide().as_ptr(),
+                title.to_wide().as_ptr(),
+                MB_OKCANCEL | MB_ICONERROR | MB_TASKMODAL,
+            )
+        };
+        result == IDOK
+    }
+}
+
+/// Whether "Choose your app mode" is set to dark in the personalization settings.
+fn apps_use_dark_theme() -> bool {
+    let subkey = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize".to_wide();
+    let value = "AppsUseLightTheme".to_wide();
+    let mut data: DWORD = 1;
+    let mut size = mem::size_of::<DWORD>() as DWORD;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            ptr::null_mut(),
+            &mut data as *mut DWORD as *mut _,
+            &mut size,
+        )
+    };
+    // Before Windows 10 there is no such setting, and everything is light.
+    result == ERROR_SUCCESS as LSTATUS && data == 0
+}
+
+/// The color of window frames, which follows the accent color.
+fn colorization_color() -> Option<Color> {
+    let mut argb: DWORD = 0;
+    let mut opaque: BOOL = FALSE;
+    let hr = unsafe { DwmGetColorizationColor(&mut argb, &mut opaque) };
+    if FAILED(hr) {
+        return None;
+    }
+    let [_, r, g, b] = argb.to_be_bytes();
+    Some(Color::rgb8(r, g, b))
+}
+
+/// Open `file` with the shell on another thread, as `ShellExecuteW` may
+/// block for a while, for example to start the browser.
+fn shell_execute(file: String, params: Option<String>) {
+    std::thread::spawn(move || unsafe {
+        // The shell may use COM to launch the application.
+        CoInitializeEx(
+            ptr::null_mut(),
+            COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE,
+        );
+        let params = params.map(|params| params.to_wide());
+        let result = ShellExecuteW(
+            ptr::null_mut(),
+            "open".to_wide().as_ptr(),
+            file.to_wide().as_ptr(),
+            params
+                .as_ref()
+                .map_or(ptr::null(), |params| params.as_ptr()),
+            ptr::null(),
+            SW_SHOWNORMAL,
+        );
+        // Values of 32 or less are errors.
+        if result as usize <= 32 {
+            tracing::warn!("failed to open {}: error {}", file, result as usize);
+        }
+        CoUninitialize();
+    });
+}
+
+unsafe extern "system" fn hotkey_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_HOTKEY {
+        let token = GlobalHotKeyToken::from_raw(wparam as u64);
+        if let Some(app) = crate::Application::try_global() {
+            app.backend_app
+                .with_handler(|handler| handler.global_hotkey(token));
+        }
+        return 0;
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// The `RegisterHotKey` modifiers and virtual key code of a hotkey.
+fn hotkey_to_vk(hotkey: &HotKey) -> Option<(UINT, UINT)> {
+    let vk_code = keyboard::key_to_vk(&hotkey.key)?;
+    let key_mods: Modifiers = hotkey.mods.into();
+    let mut mods = MOD_NOREPEAT as UINT;
+    // Like for menu accelerators, the high byte holds the modifiers needed
+    // to type the key.
+    let mod_code = vk_code >> 8;
+    if key_mods.shift() || mod_code & 0x01 != 0 {
+        mods |= MOD_SHIFT as UINT;
+    }
+    if key_mods.ctrl() || mod_code & 0x02 != 0 {
+        mods |= MOD_CONTROL as UINT;
+    }
+    if key_mods.alt() || mod_code & 0x04 != 0 {
+        mods |= MOD_ALT as UINT;
+    }
+    if key_mods.meta() {
+        mods |= MOD_WIN as UINT;
+    }
+    Some((mods, (vk_code & 0xff) as UINT))
+}
+
+impl crate::platform::windows::ApplicationExt for crate::Application {
+    fn set_app_user_model_id(&self, id: &str) {
+        self.backend_app.state.borrow_mut().app_user_model_id = Some(id.to_owned());
+    }
 }
@@ -14,40 +14,59 @@
 
 //! Interactions with the system pasteboard on Windows.
 
-use std::ffi::CString;
+use std::ffi::{CString, OsString};
 use std::mem;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
 use std::ptr;
 
-use winapi::shared::minwindef::{FALSE, UINT};
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, TRUE, UINT};
 use winapi::shared::ntdef::{CHAR, HANDLE, LPWSTR, WCHAR};
-use winapi::shared::winerror::ERROR_SUCCESS;
+use winapi::shared::windef::POINT;
+use winapi::shared::winerror::{ERROR_SUCCESS, HRESULT_FROM_WIN32};
 use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellapi::{DragQueryFileW, HDROP};
 use winapi::um::winbase::{GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE};
+use winapi::um::wingdi::{BITMAPINFOHEADER, BI_BITFIELDS, BI_RGB};
 use winapi::um::winuser::{
     CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData,
     GetClipboardFormatNameA, IsClipboardFormatAvailable, OpenClipboard, RegisterClipboardFormatA,
-    SetClipboardData, CF_UNICODETEXT,
+    SetClipboardData, CF_DIB, CF_HDROP, CF_UNICODETEXT,
 };
 
+use super::error::Error;
 use super::util::{FromWide, ToWide};
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::piet::{ImageBuf, ImageFormat};
+
+/// The header of `CF_HDROP` data.
+///
+/// This is `DROPFILES` from `shlobj.h`, which winapi only has behind a feature we don't use.
+#[repr(C)]
+struct DropFiles {
+    p_files: DWORD,
+    pt: POINT,
+    f_nc: BOOL,
+    f_wide: BOOL,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Clipboard;
 
 impl Clipboard {
     /// Put a string onto the system clipboard.
-    pub fn put_string(&mut self, s: impl AsRef<str>) {
+    pub fn put_string(&mut self, s: impl AsRef<str>) -> Result<(), crate::Error> {
         let s = s.as_ref();
         let format: ClipboardFormat = s.into();
         self.put_formats(&[format])
     }
 
     /// Put multi-format data on the system clipboard.
-    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) {
-        with_clipboard(|| unsafe {
+    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) -> Result<(), crate::Error> {
+        let result = with_clipboard(|| unsafe {
             EmptyClipboard();
 
+            let mut result = Ok(());
             for format in formats {
                 let handle = make_handle(format);
                 let format_id = match get_format_id(format.identifier) {
@@ -60,16 +79,82 @@ impl Clipboard {
                         continue;
                     }
                 };
-                let result = SetClipboardData(format_id, handle);
-                if result.is_null() {
+                let set = SetClipboardData(format_id, handle);
+                if set.is_null() {
+                    let error = GetLastError();
                     tracing::warn!(
                         "failed to set clipboard for fmt {}, error: {}",
                         &format.identifier,
-                        GetLastError()
+                        error
                     );
+                    result = Err(Error::Hr(HRESULT_FROM_WIN32(error)));
                 }
             }
+            result
         });
+        match result {
+            Some(result) => result.map_err(Into::into),
+            None => Err(Error::Hr(HRESULT_FROM_WIN32(unsafe { GetLastError() })).into()),
+        }
+    }
+
+    /// Put an image onto the system clipboard, as a device independent bitmap.
+    pub fn put_image(&mut self, image: &ImageBuf) -> Result<(), crate::Error> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(anyhow::anyhow!("cannot put an empty image on the clipboard").into());
+        }
+        put_data(CF_DIB, &make_dib(image))
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        with_clipboard(|| unsafe { get_data(CF_DIB) })
+            .flatten()
+            .and_then(|dib| read_dib(&dib))
+    }
+
+    /// Put a list of files onto the system clipboard.
+    pub fn put_files(&mut self, paths: &[PathBuf]) -> Result<(), crate::Error> {
+        let header = DropFiles {
+            p_files: mem::size_of::<DropFiles>() as DWORD,
+            pt: POINT { x: 0, y: 0 },
+            f_nc: FALSE,
+            f_wide: TRUE,
+        };
+        let mut data = unsafe {
+            std::slice::from_raw_parts(
+                &header as *const DropFiles as *const u8,
+                mem::size_of::<DropFiles>(),
+            )
+        }
+        .to_vec();
+        // Null terminated paths, with an empty one at the end.
+        let wide = paths.iter().flat_map(|path| path.to_wide()).chain(Some(0));
+        for c in wide {
+            data.extend_from_slice(&c.to_ne_bytes());
+        }
+        put_data(CF_HDROP, &data)
+    }
+
+    /// Get the list of files on the system clipboard, if there is one.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        with_clipboard(|| unsafe {
+            let hdrop = GetClipboardData(CF_HDROP) as HDROP;
+            if hdrop.is_null() {
+                return None;
+            }
+            let count = DragQueryFileW(hdrop, 0xFFFF_FFFF, ptr::null_mut(), 0);
+            let files = (0..count)
+                .map(|i| {
+                    let len = DragQueryFileW(hdrop, i, ptr::null_mut(), 0);
+                    let mut buf = vec![0u16; len as usize + 1];
+                    DragQueryFileW(hdrop, i, buf.as_mut_ptr(), len + 1);
+                    PathBuf::from(OsString::from_wide(&buf[..len as usize]))
+                })
+                .collect();
+            Some(files)
+        })
+        .flatten()
     }
 
     /// Get a string from the system clipboard, if one is available.
@@ -124,19 +209,11 @@ impl Clipboard {
                 }
             };
 
-            unsafe {
-                if IsClipboardFormatAvailable(format_id) != 0 {
-                    let handle = GetClipboardData(format_id);
-                    let size = GlobalSize(handle);
-                    let locked = GlobalLock(handle) as *const u8;
-                    let mut dest = Vec::<u8>::with_capacity(size);
-                    ptr::copy_nonoverlapping(locked, dest.as_mut_ptr(), size);
-                    dest.set_len(size);
-                    GlobalUnlock(handle);
-                    Some(dest)
-                } else {
-                    None
-                }
+            let data = unsafe { get_data(format_id) };
+            if format == ClipboardFormat::HTML {
+                data.and_then(|data| read_cf_html(&data))
+            } else {
+                data
             }
         })
         .flatten()
@@ -166,6 +243,42 @@ fn with_clipboard<V>(f: impl FnOnce() -> V) -> Option<V> {
     }
 }
 
+/// Replace the contents of the clipboard with data in a single format.
+fn put_data(format_id: UINT, data: &[u8]) -> Result<(), crate::Error> {
+    let result = with_clipboard(|| unsafe {
+        EmptyClipboard();
+        if SetClipboardData(format_id, make_global(data)).is_null() {
+            Err(Error::Hr(HRESULT_FROM_WIN32(GetLastError())))
+        } else {
+            Ok(())
+        }
+    });
+    match result {
+        Some(result) => result.map_err(Into::into),
+        None => Err(Error::Hr(HRESULT_FROM_WIN32(unsafe { GetLastError() })).into()),
+    }
+}
+
+/// Copy the clipboard data in the given format, if available.
+///
+/// The clipboard must be open.
+unsafe fn get_data(format_id: UINT) -> Option<Vec<u8>> {
+    if IsClipboardFormatAvailable(format_id) == 0 {
+        return None;
+    }
+    let handle = GetClipboardData(format_id);
+    if handle.is_null() {
+        return None;
+    }
+    let size = GlobalSize(handle);
+    let locked = GlobalLock(handle) as *const u8;
+    let mut dest = Vec::<u8>::with_capacity(size);
+    ptr::copy_nonoverlapping(locked, dest.as_mut_ptr(), size);
+    dest.set_len(size);
+    GlobalUnlock(handle);
+    Some(dest)
+}
+
 unsafe fn make_handle(format: &ClipboardFormat) -> HANDLE {
     if format.identifier == ClipboardFormat::TEXT {
         let s = std::str::from_utf8_unchecked(&format.data);
@@ -175,15 +288,154 @@ unsafe fn make_handle(format: &ClipboardFormat) -> HANDLE {
         ptr::copy_nonoverlapping(wstr.as_ptr(), locked, wstr.len());
         GlobalUnlock(handle);
         handle
+    } else if format.identifier == ClipboardFormat::HTML {
+        make_global(&make_cf_html(&format.data))
     } else {
-        let handle = GlobalAlloc(GMEM_MOVEABLE, format.data.len() * mem::size_of::<CHAR>());
-        let locked = GlobalLock(handle) as *mut u8;
-        ptr::copy_nonoverlapping(format.data.as_ptr(), locked, format.data.len());
-        GlobalUnlock(handle);
-        handle
+        make_global(&format.data)
     }
 }
 
+unsafe fn make_global(data: &[u8]) -> HANDLE {
+    let handle = GlobalAlloc(GMEM_MOVEABLE, data.len() * mem::size_of::<CHAR>());
+    let locked = GlobalLock(handle) as *mut u8;
+    ptr::copy_nonoverlapping(data.as_ptr(), locked, data.len());
+    GlobalUnlock(handle);
+    handle
+}
+
+/// Wrap an HTML fragment in the header and document of the CF_HTML format.
+///
+/// https://docs.microsoft.com/en-us/windows/win32/dataxchg/html-clipboard-format
+fn make_cf_html(fragment: &[u8]) -> Vec<u8> {
+    const PREFIX: &[u8] = b"<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &[u8] = b"<!--EndFragment-->\r\n</body></html>";
+    // The offsets have a fixed width, so the header has the same length whatever they are.
+    let header =
+        |start_html: usize, end_html: usize, start_fragment: usize, end_fragment: usize| {
+            format!(
+                concat!(
+                    "Version:0.9\r\n",
+                    "StartHTML:{:010}\r\n",
+                    "EndHTML:{:010}\r\n",
+                    "StartFragment:{:010}\r\n",
+                    "EndFragment:{:010}\r\n",
+                ),
+                start_html, end_html, start_fragment, end_fragment
+            )
+        };
+    let start_html = header(0, 0, 0, 0).len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+    let mut data = header(start_html, end_html, start_fragment, end_fragment).into_bytes();
+    data.extend_from_slice(PREFIX);
+    data.extend_from_slice(fragment);
+    data.extend_from_slice(SUFFIX);
+    data
+}
+
+/// The HTML fragment of CF_HTML data.
+fn read_cf_html(data: &[u8]) -> Option<Vec<u8>> {
+    let text = String::from_utf8_lossy(data);
+    let offset = |key: &str| -> Option<usize> {
+        text.lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    let start = offset("StartFragment:")?;
+    let end = offset("EndFragment:")?;
+    data.get(start..end).map(<[u8]>::to_vec)
+}
+
+/// Encode an image as a `CF_DIB`: a bitmap header followed by bottom-up BGRA rows.
+fn make_dib(image: &ImageBuf) -> Vec<u8> {
+    let width = image.width();
+    let height = image.height();
+    let header = BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as DWORD,
+        biWidth: width as i32,
+        biHeight: height as i32,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB,
+        biSizeImage: (width * height * 4) as DWORD,
+        biXPelsPerMeter: 0,
+        biYPelsPerMeter: 0,
+        biClrUsed: 0,
+        biClrImportant: 0,
+    };
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for row in image.pixel_colors() {
+        for p in row {
+            let (r, g, b, a) = p.as_rgba8();
+            pixels.extend_from_slice(&[b, g, r, a]);
+        }
+    }
+    let mut data = unsafe {
+        std::slice::from_raw_parts(
+            &header as *const BITMAPINFOHEADER as *const u8,
+            mem::size_of::<BITMAPINFOHEADER>(),
+        )
+    }
+    .to_vec();
+    for row in pixels.chunks(width * 4).rev() {
+        data.extend_from_slice(row);
+    }
+    data
+}
+
+/// Decode a `CF_DIB` with 24 or 32 bits per pixel.
+fn read_dib(dib: &[u8]) -> Option<ImageBuf> {
+    if dib.len() < mem::size_of::<BITMAPINFOHEADER>() {
+        return None;
+    }
+    let header: BITMAPINFOHEADER = unsafe { ptr::read_unaligned(dib.as_ptr() as *const _) };
+    if header.biWidth <= 0 || header.biHeight == 0 {
+        return None;
+    }
+    let width = header.biWidth as usize;
+    let height = header.biHeight.abs() as usize;
+    let bytes_per_pixel = match (header.biBitCount, header.biCompression) {
+        (32, BI_RGB) | (32, BI_BITFIELDS) => 4,
+        (24, BI_RGB) => 3,
+        _ => return None,
+    };
+    let mut offset = header.biSize as usize;
+    // The color masks follow a plain header; we assume they are the usual ones.
+    if header.biCompression == BI_BITFIELDS && offset == mem::size_of::<BITMAPINFOHEADER>() {
+        offset += 3 * mem::size_of::<DWORD>();
+    }
+    // Rows are padded to 4 bytes.
+    let stride = (width * bytes_per_pixel + 3) & !3;
+    let rows = dib.get(offset..offset + stride * height)?;
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        // Positive heights mean bottom-up rows.
+        let row_idx = if header.biHeight > 0 {
+            height - 1 - y
+        } else {
+            y
+        };
+        let row = &rows[row_idx * stride..row_idx * stride + width * bytes_per_pixel];
+        for p in row.chunks(bytes_per_pixel) {
+            let a = if bytes_per_pixel == 4 { p[3] } else { 255 };
+            pixels.extend_from_slice(&[p[2], p[1], p[0], a]);
+        }
+    }
+    // Most applications leave the alpha channel of 32 bit bitmaps empty.
+    if pixels.chunks(4).all(|p| p[3] == 0) {
+        for p in pixels.chunks_mut(4) {
+            p[3] = 255;
+        }
+    }
+    Some(ImageBuf::from_raw(
+        pixels,
+        ImageFormat::RgbaSeparate,
+        width,
+        height,
+    ))
+}
+
 fn get_format_id(format: FormatId) -> Option<UINT> {
     if let Some((id, _)) = STANDARD_FORMATS.iter().find(|(_, s)| s == &format) {
         return Some(*id);
@@ -326,3 +578,97 @@ fn get_standard_format_name(format: UINT) -> Option<&'static str> {
         .find(|(id, _)| *id == format)
         .map(|(_, s)| *s)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn cf_html_round_trip() {
+        let fragment = "<b>bold</b> and <i>ünïcode</i>".as_bytes();
+        let data = make_cf_html(fragment);
+        assert_eq!(read_cf_html(&data).as_deref(), Some(fragment));
+
+        // the offsets are in bytes, from the start of the data
+        let text = std::str::from_utf8(&data).unwrap();
+        let start_html = text.find("<html>").unwrap();
+        assert!(text.contains(&format!("StartHTML:{:010}\r\n", start_html)));
+        assert!(text.contains(&format!("EndHTML:{:010}\r\n", data.len())));
+    }
+
+    #[test]
+    fn cf_html_from_other_applications() {
+        // spaces after the colons, no EndHTML, and a different document around the fragment
+        let fragment = "<p>hello</p>";
+        let body = format!(
+            "<html><body><!--StartFragment-->{}<!--EndFragment--></body></html>",
+            fragment
+        );
+        let header_len = "Version:1.0\r\nStartFragment: 0000\r\nEndFragment: 0000\r\n".len();
+        let start = header_len + body.find(fragment).unwrap();
+        let end = start + fragment.len();
+        let data = format!(
+            "Version:1.0\r\nStartFragment: {:04}\r\nEndFragment: {:04}\r\n{}",
+            start, end, body
+        );
+        assert_eq!(
+            read_cf_html(data.as_bytes()).as_deref(),
+            Some(fragment.as_bytes())
+        );
+        assert_eq!(read_cf_html(b"Version:0.9\r\n"), None);
+    }
+
+    #[test]
+    fn dib_round_trip() {
+        #[rustfmt::skip]
+        let pixels = vec![
+            255, 0, 0, 255,    0, 255, 0, 128,    0, 0, 255, 255,
+            10, 20, 30, 40,    1, 2, 3, 255,      200, 100, 50, 255,
+        ];
+        let image = ImageBuf::from_raw(pixels.clone(), ImageFormat::RgbaSeparate, 3, 2);
+        let dib = make_dib(&image);
+        assert_eq!(dib.len(), mem::size_of::<BITMAPINFOHEADER>() + pixels.len());
+        let decoded = read_dib(&dib).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (3, 2));
+        assert_eq!(decoded.raw_pixels(), &pixels[..]);
+    }
+
+    #[test]
+    fn dib_24_bit_top_down_with_padding() {
+        let header = BITMAPINFOHEADER {
+            biSize: mem::size_of::<BITMAPINFOHEADER>() as DWORD,
+            biWidth: 1,
+            // negative heights mean top-down rows
+            biHeight: -2,
+            biPlanes: 1,
+            biBitCount: 24,
+            biCompression: BI_RGB,
+            biSizeImage: 0,
+            biXPelsPerMeter: 0,
+            biYPelsPerMeter: 0,
+            biClrUsed: 0,
+            biClrImportant: 0,
+        };
+        let mut dib = unsafe {
+            std::slice::from_raw_parts(
+                &header as *const BITMAPINFOHEADER as *const u8,
+                mem::size_of::<BITMAPINFOHEADER>(),
+            )
+        }
+        .to_vec();
+        // BGR rows, each padded to 4 bytes
+        dib.extend_from_slice(&[3, 2, 1, 0, 6, 5, 4, 0]);
+        let decoded = read_dib(&dib).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (1, 2));
+        assert_eq!(decoded.raw_pixels(), &[1, 2, 3, 255, 4, 5, 6, 255]);
+    }
+
+    #[test]
+    fn truncated_dib() {
+        let image = ImageBuf::from_raw(vec![0; 16], ImageFormat::RgbaSeparate, 2, 2);
+        let dib = make_dib(&image);
+        assert!(read_dib(&dib[..dib.len() - 1]).is_none());
+        assert!(read_dib(&dib[..10]).is_none());
+    }
+}
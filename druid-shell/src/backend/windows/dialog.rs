@@ -23,13 +23,14 @@
 #![allow(non_upper_case_globals)]
 
 use std::convert::TryInto;
-use std::ffi::OsString;
+use std::path::Path;
 use std::ptr::null_mut;
 
 use winapi::ctypes::c_void;
 use winapi::shared::minwindef::*;
 use winapi::shared::ntdef::LPWSTR;
 use winapi::shared::windef::*;
+use winapi::shared::winerror::{ERROR_CANCELLED, HRESULT_FROM_WIN32};
 use winapi::shared::wtypesbase::*;
 use winapi::um::combaseapi::*;
 use winapi::um::shobjidl::*;
@@ -40,7 +41,7 @@ use wio::com::ComPtr;
 
 use super::error::Error;
 use super::util::{as_result, FromWide, ToWide};
-use crate::dialog::{FileDialogOptions, FileDialogType, FileSpec};
+use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo, FileSpec};
 
 // TODO: remove these when they get added to winapi
 DEFINE_GUID! {CLSID_FileOpenDialog,
@@ -72,11 +73,24 @@ fn normalize_extension(ext: &str) -> String {
     format!("*.{}", ext.trim_start_matches('*').trim_start_matches('.'))
 }
 
+/// Create a shell item for a path.
+unsafe fn make_shell_item(path: &Path) -> Result<ComPtr<IShellItem>, Error> {
+    let mut item: *mut IShellItem = null_mut();
+    as_result(SHCreateItemFromParsingName(
+        path.as_os_str().to_wide().as_ptr(),
+        null_mut(),
+        &IShellItem::uuidof(),
+        &mut item as *mut *mut IShellItem as *mut *mut c_void,
+    ))?;
+    Ok(ComPtr::from_raw(item))
+}
+
+/// Show a file dialog, returning the chosen path or `None` if it was cancelled.
 pub(crate) unsafe fn get_file_dialog_path(
     hwnd_owner: HWND,
     ty: FileDialogType,
     options: FileDialogOptions,
-) -> Result<OsString, Error> {
+) -> Result<Option<FileInfo>, Error> {
     let mut pfd: *mut IFileDialog = null_mut();
     let (class, id) = match ty {
         FileDialogType::Open => (&CLSID_FileOpenDialog, IFileOpenDialog::uuidof()),
@@ -149,22 +163,25 @@ pub(crate) unsafe fn get_file_dialog_path(
     as_result(file_dialog.SetOptions(flags))?;
 
     // set a starting directory
-    if let Some(path) = options.starting_directory {
-        let mut item: *mut IShellItem = null_mut();
-        if let Err(err) = as_result(SHCreateItemFromParsingName(
-            path.as_os_str().to_wide().as_ptr(),
-            null_mut(),
-            &IShellItem::uuidof(),
-            &mut item as *mut *mut IShellItem as *mut *mut c_void,
-        )) {
-            tracing::warn!("Failed to convert path: {}", err.to_string());
-        } else {
-            as_result(file_dialog.SetDefaultFolder(item))?;
+    if let Some(path) = &options.starting_directory {
+        match make_shell_item(path) {
+            Ok(item) => as_result(file_dialog.SetDefaultFolder(item.as_raw()))?,
+            Err(err) => tracing::warn!("Failed to convert path: {}", err.to_string()),
+        }
+    } else if let Some(path) = options.remembered_directory() {
+        // The system remembers a folder of its own, which we want to override.
+        match make_shell_item(&path) {
+            Ok(item) => as_result(file_dialog.SetFolder(item.as_raw()))?,
+            Err(err) => tracing::warn!("Failed to convert path: {}", err.to_string()),
         }
     }
 
     // show the dialog
-    as_result(file_dialog.Show(hwnd_owner))?;
+    let hr = file_dialog.Show(hwnd_owner);
+    if hr == HRESULT_FROM_WIN32(ERROR_CANCELLED) {
+        return Ok(None);
+    }
+    as_result(hr)?;
     let mut result_ptr: *mut IShellItem = null_mut();
     as_result(file_dialog.GetResult(&mut result_ptr))?;
     let shell_item = ComPtr::from_raw(result_ptr);
@@ -173,5 +190,16 @@ pub(crate) unsafe fn get_file_dialog_path(
     let filename = display_name.to_os_string();
     CoTaskMemFree(display_name as LPVOID);
 
-    Ok(filename)
+    let mut format = None;
+    if let (Some(allowed_types), false) = (&options.allowed_types, options.select_directories) {
+        let mut index: UINT = 0;
+        as_result(file_dialog.GetFileTypeIndex(&mut index))?;
+        // The index is one-based.
+        format = (index as usize)
+            .checked_sub(1)
+            .and_then(|index| allowed_types.get(index))
+            .copied();
+    }
+
+    Ok(Some(FileInfo::chosen(filename.into(), format, &options)))
 }
@@ -0,0 +1,305 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An OLE drop target, for drags from other applications.
+//!
+//! The drop target forwards the drag to the window procedure with a
+//! [`DS_DROP`] message, so that the handler is only ever called from there.
+//!
+//! [`DS_DROP`]: super::window::DS_DROP
+
+use std::cell::{Cell, RefCell};
+use std::ffi::OsString;
+use std::mem;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+use std::ptr::{self, null_mut};
+
+use tracing::warn;
+use winapi::shared::guiddef::{IsEqualGUID, REFIID};
+use winapi::shared::minwindef::{DWORD, HGLOBAL, LPARAM, UINT, ULONG};
+use winapi::shared::ntdef::{HRESULT, LPWSTR};
+use winapi::shared::windef::{HWND, POINT, POINTL};
+use winapi::shared::winerror::{E_NOINTERFACE, FAILED, S_OK};
+use winapi::shared::wtypes::{CLIPFORMAT, DVASPECT_CONTENT};
+use winapi::um::objidl::{IDataObject, FORMATETC, STGMEDIUM, TYMED_HGLOBAL};
+use winapi::um::ole2::RegisterDragDrop;
+use winapi::um::oleidl::{IDropTarget, IDropTargetVtbl, DROPEFFECT_COPY, DROPEFFECT_NONE};
+use winapi::um::shellapi::{DragQueryFileW, HDROP};
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winapi::um::winbase::{GlobalFree, GlobalLock, GlobalUnlock};
+use winapi::um::winuser::{
+    RegisterClipboardFormatW, ScreenToClient, SendMessageW, CF_HDROP, CF_UNICODETEXT, MK_CONTROL,
+    MK_SHIFT,
+};
+use winapi::Interface;
+
+use crate::dnd::DropData;
+use crate::keyboard::Modifiers;
+use crate::kurbo::Point;
+
+use super::error::Error;
+use super::util::{FromWide, ToWide};
+use super::window::DS_DROP;
+
+// winapi has this one wrong.
+const MK_ALT: DWORD = 0x20;
+
+/// What happened to a drag from another application.
+pub(crate) enum DropPhase {
+    Over,
+    Leave,
+    Drop,
+}
+
+/// The message that the drop target sends to the window procedure.
+pub(crate) struct DropMessage {
+    pub(crate) phase: DropPhase,
+    /// The position of the drag in pixels, relative to the client area.
+    pub(crate) pos: Point,
+    pub(crate) data: DropData,
+    pub(crate) mods: Modifiers,
+    /// Set by the window procedure to whether the handler takes the drag.
+    pub(crate) accepted: bool,
+}
+
+#[repr(C)]
+struct DropTarget {
+    vtbl: *const IDropTargetVtbl,
+    refs: Cell<ULONG>,
+    hwnd: HWND,
+    /// What is dragged, while a drag is over the window.
+    data: RefCell<Option<DropData>>,
+}
+
+static DROP_TARGET_VTBL: IDropTargetVtbl = IDropTargetVtbl {
+    parent: IUnknownVtbl {
+        QueryInterface: query_interface,
+        AddRef: add_ref,
+        Release: release,
+    },
+    DragEnter: drag_enter,
+    DragOver: drag_over,
+    DragLeave: drag_leave,
+    Drop: drop,
+};
+
+/// Accept drags from other applications in `hwnd`.
+///
+/// OLE holds on to the drop target until `RevokeDragDrop` is called.
+pub(crate) fn register_drop_target(hwnd: HWND) {
+    let target = Box::into_raw(Box::new(DropTarget {
+        vtbl: &DROP_TARGET_VTBL,
+        refs: Cell::new(1),
+        hwnd,
+        data: RefCell::new(None),
+    }));
+    unsafe {
+        let hr = RegisterDragDrop(hwnd, target as *mut IDropTarget);
+        if FAILED(hr) {
+            warn!("failed to accept drags: {}", Error::Hr(hr));
+        }
+        release(target as *mut IUnknown);
+    }
+}
+
+impl DropTarget {
+    /// Tell the window about the drag, and return the effect of dropping it.
+    fn send(&self, phase: DropPhase, key_state: DWORD, pt: POINTL) -> DWORD {
+        let data = match &*self.data.borrow() {
+            Some(data) => data.clone(),
+            None => return DROPEFFECT_NONE,
+        };
+        let mut pos = POINT { x: pt.x, y: pt.y };
+        unsafe {
+            ScreenToClient(self.hwnd, &mut pos);
+        }
+        let mut message = DropMessage {
+            phase,
+            pos: Point::new(pos.x as f64, pos.y as f64),
+            data,
+            mods: get_modifiers(key_state),
+            accepted: false,
+        };
+        unsafe {
+            SendMessageW(
+                self.hwnd,
+                DS_DROP,
+                0,
+                &mut message as *mut DropMessage as LPARAM,
+            );
+        }
+        if message.accepted {
+            DROPEFFECT_COPY
+        } else {
+            DROPEFFECT_NONE
+        }
+    }
+}
+
+unsafe extern "system" fn query_interface(
+    this: *mut IUnknown,
+    riid: REFIID,
+    object: *mut *mut winapi::ctypes::c_void,
+) -> HRESULT {
+    if IsEqualGUID(&*riid, &IUnknown::uuidof()) || IsEqualGUID(&*riid, &IDropTarget::uuidof()) {
+        add_ref(this);
+        *object = this as *mut _;
+        S_OK
+    } else {
+        *object = null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn add_ref(this: *mut IUnknown) -> ULONG {
+    let target = &*(this as *const DropTarget);
+    let refs = target.refs.get() + 1;
+    target.refs.set(refs);
+    refs
+}
+
+unsafe extern "system" fn release(this: *mut IUnknown) -> ULONG {
+    let target = &*(this as *const DropTarget);
+    let refs = target.refs.get() - 1;
+    target.refs.set(refs);
+    if refs == 0 {
+        mem::drop(Box::from_raw(this as *mut DropTarget));
+    }
+    refs
+}
+
+unsafe extern "system" fn drag_enter(
+    this: *mut IDropTarget,
+    data_object: *const IDataObject,
+    key_state: DWORD,
+    pt: *const POINTL,
+    effect: *mut DWORD,
+) -> HRESULT {
+    let target = &*(this as *const DropTarget);
+    target.data.replace(Some(get_drop_data(&*data_object)));
+    *effect = target.send(DropPhase::Over, key_state, *pt);
+    S_OK
+}
+
+unsafe extern "system" fn drag_over(
+    this: *mut IDropTarget,
+    key_state: DWORD,
+    pt: *const POINTL,
+    effect: *mut DWORD,
+) -> HRESULT {
+    let target = &*(this as *const DropTarget);
+    *effect = target.send(DropPhase::Over, key_state, *pt);
+    S_OK
+}
+
+unsafe extern "system" fn drag_leave(this: *mut IDropTarget) -> HRESULT {
+    let target = &*(this as *const DropTarget);
+    target.send(DropPhase::Leave, 0, POINTL { x: 0, y: 0 });
+    target.data.replace(None);
+    S_OK
+}
+
+unsafe extern "system" fn drop(
+    this: *mut IDropTarget,
+    data_object: *const IDataObject,
+    key_state: DWORD,
+    pt: *const POINTL,
+    effect: *mut DWORD,
+) -> HRESULT {
+    let target = &*(this as *const DropTarget);
+    target.data.replace(Some(get_drop_data(&*data_object)));
+    *effect = target.send(DropPhase::Drop, key_state, *pt);
+    target.data.replace(None);
+    S_OK
+}
+
+fn get_modifiers(key_state: DWORD) -> Modifiers {
+    let mut mods = Modifiers::empty();
+    mods.set(Modifiers::SHIFT, key_state & MK_SHIFT as DWORD != 0);
+    mods.set(Modifiers::CONTROL, key_state & MK_CONTROL as DWORD != 0);
+    mods.set(Modifiers::ALT, key_state & MK_ALT != 0);
+    mods
+}
+
+/// The files, URL or text in `data_object`.
+unsafe fn get_drop_data(data_object: &IDataObject) -> DropData {
+    let mut data = DropData::default();
+    with_global(data_object, CF_HDROP, |global| {
+        let hdrop = global as HDROP;
+        let count = DragQueryFileW(hdrop, 0xFFFF_FFFF, null_mut(), 0);
+        for i in 0..count {
+            let len = DragQueryFileW(hdrop, i, null_mut(), 0);
+            let mut buf = vec![0u16; len as usize + 1];
+            DragQueryFileW(hdrop, i, buf.as_mut_ptr(), len + 1);
+            let path = OsString::from_wide(&buf[..len as usize]);
+            data.files.push(PathBuf::from(path));
+        }
+    });
+    let url_format = RegisterClipboardFormatW("UniformResourceLocatorW".to_wide().as_ptr());
+    if let Some(Some(url)) =
+        with_global(data_object, url_format, |global| get_global_string(global))
+    {
+        data.urls.push(url);
+    }
+    if data.files.is_empty() && data.urls.is_empty() {
+        data.text = with_global(data_object, CF_UNICODETEXT, |global| {
+            get_global_string(global)
+        })
+        .flatten();
+    }
+    data
+}
+
+/// Call `f` with the data of `format` in `data_object`, if it has any.
+unsafe fn with_global<V>(
+    data_object: &IDataObject,
+    format: UINT,
+    f: impl FnOnce(HGLOBAL) -> V,
+) -> Option<V> {
+    let format = FORMATETC {
+        cfFormat: format as CLIPFORMAT,
+        ptd: ptr::null(),
+        dwAspect: DVASPECT_CONTENT,
+        lindex: -1,
+        tymed: TYMED_HGLOBAL,
+    };
+    let mut medium: STGMEDIUM = mem::zeroed();
+    if FAILED(data_object.GetData(&format, &mut medium)) {
+        return None;
+    }
+    // winapi declares the union as a pointer to it, but it is the handle itself.
+    let global = medium.u as HGLOBAL;
+    let result = if medium.tymed == TYMED_HGLOBAL {
+        Some(f(global))
+    } else {
+        None
+    };
+    if medium.pUnkForRelease.is_null() {
+        GlobalFree(global);
+    } else {
+        (*medium.pUnkForRelease).Release();
+    }
+    result
+}
+
+unsafe fn get_global_string(global: HGLOBAL) -> Option<String> {
+    let locked = GlobalLock(global) as LPWSTR;
+    if locked.is_null() {
+        return None;
+    }
+    let string = locked.from_wide();
+    GlobalUnlock(global);
+    string
+}
@@ -569,6 +569,7 @@ impl KeyboardState {
                         state,
                         mods,
                         code,
+                        scancode: scan_code,
                         key,
                         is_composing: false,
                         location,
@@ -594,6 +595,7 @@ impl KeyboardState {
                     state,
                     mods,
                     code,
+                    scancode: scan_code,
                     key,
                     is_composing: false,
                     location,
@@ -628,6 +630,7 @@ impl KeyboardState {
                         state,
                         mods,
                         code,
+                        scancode: scan_code,
                         key,
                         is_composing: false,
                         location,
@@ -790,3 +793,42 @@ impl KeyboardState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr::null_mut;
+
+    /// The `lparam` of a key up message of `scan_code`.
+    fn key_up_lparam(scan_code: u32) -> LPARAM {
+        ((scan_code as LPARAM) << 16) | 0xc000_0001_u32 as LPARAM
+    }
+
+    #[test]
+    fn scancode_of_key_up() {
+        let mut state = KeyboardState::new();
+        let event = unsafe {
+            state.process_message(null_mut(), WM_KEYUP, b'A' as WPARAM, key_up_lparam(0x1e))
+        }
+        .unwrap();
+        assert_eq!(event.scancode, 0x1e);
+        assert_eq!(event.code, Code::KeyA);
+    }
+
+    #[test]
+    fn scancode_of_extended_key() {
+        let mut state = KeyboardState::new();
+        // Right Control is the extended Control.
+        let event = unsafe {
+            state.process_message(
+                null_mut(),
+                WM_KEYUP,
+                VK_CONTROL as WPARAM,
+                key_up_lparam(0x11d),
+            )
+        }
+        .unwrap();
+        assert_eq!(event.scancode, 0x11d);
+        assert_eq!(event.code, Code::ControlRight);
+    }
+}
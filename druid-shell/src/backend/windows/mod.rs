@@ -19,12 +19,15 @@ pub mod application;
 pub mod clipboard;
 pub mod dcomp;
 pub mod dialog;
+mod drop_target;
 pub mod error;
 mod keyboard;
 pub mod menu;
 pub mod paint;
 pub mod screen;
 mod timers;
+mod toast;
+pub mod tray;
 pub mod util;
 pub mod window;
 
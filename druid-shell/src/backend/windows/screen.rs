@@ -15,6 +15,8 @@
 //! Windows Monitors and Screen information.
 
 use super::error::Error;
+use super::util::OPTIONAL_FUNCTIONS;
+use super::window::SCALE_TARGET_DPI;
 use std::mem::size_of;
 use std::ptr::null_mut;
 use tracing::warn;
@@ -22,17 +24,15 @@ use winapi::shared::minwindef::*;
 use winapi::shared::windef::*;
 use winapi::shared::winerror::*;
 use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellscalingapi::MDT_EFFECTIVE_DPI;
 use winapi::um::winuser::*;
 
 use crate::kurbo::Rect;
+use crate::scale::Scale;
 use crate::screen::Monitor;
 
-unsafe extern "system" fn monitorenumproc(
-    hmonitor: HMONITOR,
-    _hdc: HDC,
-    _lprect: LPRECT,
-    _lparam: LPARAM,
-) -> BOOL {
+/// The `Monitor` for `hmonitor`.
+pub(crate) unsafe fn get_monitor(hmonitor: HMONITOR) -> Option<Monitor> {
     let rect = RECT {
         left: 0,
         top: 0,
@@ -50,6 +50,7 @@ unsafe extern "system" fn monitorenumproc(
             "failed to get Monitor Info: {}",
             Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
         );
+        return None;
     };
     let primary = info.dwFlags == MONITORINFOF_PRIMARY;
     let rect = Rect::new(
@@ -64,11 +65,42 @@ unsafe extern "system" fn monitorenumproc(
         info.rcWork.right as f64,
         info.rcWork.bottom as f64,
     );
-    let monitors = _lparam as *mut Vec<Monitor>;
-    (*monitors).push(Monitor::new(primary, rect, work_rect));
-    TRUE
+    Some(Monitor::new(
+        primary,
+        rect,
+        work_rect,
+        monitor_scale(hmonitor),
+    ))
+}
+
+/// The scale of `hmonitor`, or of the system before Windows 8.1.
+unsafe fn monitor_scale(hmonitor: HMONITOR) -> Scale {
+    let dpi = if let Some(func) = OPTIONAL_FUNCTIONS.GetDpiForMonitor {
+        let mut dpi_x = 0;
+        let mut dpi_y = 0;
+        func(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+        dpi_x as f64
+    } else if let Some(func) = OPTIONAL_FUNCTIONS.GetDpiForSystem {
+        func() as f64
+    } else {
+        SCALE_TARGET_DPI
+    };
+    let scale = dpi / SCALE_TARGET_DPI;
+    Scale::new(scale, scale)
 }
 
+unsafe extern "system" fn monitorenumproc(
+    hmonitor: HMONITOR,
+    _hdc: HDC,
+    _lprect: LPRECT,
+    _lparam: LPARAM,
+) -> BOOL {
+    if let Some(monitor) = get_monitor(hmonitor) {
+        let monitors = _lparam as *mut Vec<Monitor>;
+        (*monitors).push(monitor);
+    }
+    TRUE
+}
 pub(crate) fn get_monitors() -> Vec<Monitor> {
     unsafe {
         let monitors = Vec::<Monitor>::new();
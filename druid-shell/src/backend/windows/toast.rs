@@ -0,0 +1,526 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Toast notifications, through the Windows Runtime.
+//!
+//! winapi doesn't declare the Windows Runtime interfaces of notifications, so
+//! we declare the part of them that we use.
+
+#![allow(non_snake_case, non_upper_case_globals)]
+
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use winapi::ctypes::c_void;
+use winapi::shared::guiddef::{IsEqualGUID, REFIID};
+use winapi::shared::minwindef::{LPARAM, ULONG, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::shared::winerror::{E_NOINTERFACE, HRESULT, S_OK};
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winapi::um::winuser::{PostMessageW, HWND_MESSAGE, WM_APP};
+use winapi::winrt::hstring::HSTRING;
+use winapi::winrt::inspectable::{IInspectable, IInspectableVtbl};
+use winapi::winrt::roapi::{RoActivateInstance, RoGetActivationFactory};
+use winapi::winrt::winstring::{
+    WindowsCreateString, WindowsDeleteString, WindowsGetStringRawBuffer,
+};
+use winapi::{Interface, DEFINE_GUID, RIDL};
+use wio::com::ComPtr;
+
+use super::error::Error;
+use super::util::as_result;
+use crate::notification::{DesktopNotification, NotificationToken};
+use crate::piet::ImageBuf;
+
+/// The message that tells the notification window about an activated toast.
+///
+/// The `LPARAM` is a `Box<(NotificationToken, Option<usize>)>`.
+pub(super) const DS_TOAST_ACTIVATED: u32 = WM_APP + 2;
+
+RIDL! {#[uuid(0x6cd0_e74e, 0xee65, 0x4489, 0x9e, 0xbf, 0xca, 0x43, 0xe8, 0x7b, 0xa6, 0x37)]
+interface IXmlDocumentIO(IXmlDocumentIOVtbl): IInspectable(IInspectableVtbl) {
+    fn LoadXml(
+        xml: HSTRING,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x50ac_103f, 0xd235, 0x4598, 0xbb, 0xef, 0x98, 0xfe, 0x4d, 0x1a, 0x3a, 0xd4)]
+interface IToastNotificationManagerStatics(IToastNotificationManagerStaticsVtbl):
+    IInspectable(IInspectableVtbl) {
+    fn CreateToastNotifier(
+        notifier: *mut *mut IToastNotifier,
+    ) -> HRESULT,
+    fn CreateToastNotifierWithId(
+        application_id: HSTRING,
+        notifier: *mut *mut IToastNotifier,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x7592_7b93, 0x03f3, 0x41ec, 0x91, 0xd3, 0x6e, 0x5b, 0xac, 0x1b, 0x38, 0xe7)]
+interface IToastNotifier(IToastNotifierVtbl): IInspectable(IInspectableVtbl) {
+    fn Show(
+        notification: *mut IToastNotification,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x0412_4b20, 0x82c6, 0x4229, 0xb1, 0x09, 0xfd, 0x9e, 0xd4, 0x66, 0x2b, 0x53)]
+interface IToastNotificationFactory(IToastNotificationFactoryVtbl):
+    IInspectable(IInspectableVtbl) {
+    fn CreateToastNotification(
+        content: *mut IInspectable,
+        notification: *mut *mut IToastNotification,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0x997e_2675, 0x059e, 0x4e60, 0x8b, 0x06, 0x17, 0x60, 0x91, 0x7c, 0x8b, 0x80)]
+interface IToastNotification(IToastNotificationVtbl): IInspectable(IInspectableVtbl) {
+    fn get_Content(
+        content: *mut *mut IInspectable,
+    ) -> HRESULT,
+    fn put_ExpirationTime(
+        value: *mut IInspectable,
+    ) -> HRESULT,
+    fn get_ExpirationTime(
+        value: *mut *mut IInspectable,
+    ) -> HRESULT,
+    fn add_Dismissed(
+        handler: *mut IUnknown,
+        token: *mut i64,
+    ) -> HRESULT,
+    fn remove_Dismissed(
+        token: i64,
+    ) -> HRESULT,
+    fn add_Activated(
+        handler: *mut IUnknown,
+        token: *mut i64,
+    ) -> HRESULT,
+}}
+
+RIDL! {#[uuid(0xe3bf_92f3, 0xc197, 0x436f, 0x82, 0x65, 0x06, 0x25, 0x82, 0x4f, 0x8d, 0xac)]
+interface IToastActivatedEventArgs(IToastActivatedEventArgsVtbl):
+    IInspectable(IInspectableVtbl) {
+    fn get_Arguments(
+        arguments: *mut HSTRING,
+    ) -> HRESULT,
+}}
+
+// `TypedEventHandler<ToastNotification, Object>`
+DEFINE_GUID! {IID_ToastActivatedHandler,
+0xab54_de2d, 0x97d9, 0x5528, 0xb6, 0xad, 0x10, 0x5a, 0xfe, 0x15, 0x65, 0x30}
+DEFINE_GUID! {IID_IAgileObject,
+0x94ea_2b94, 0xe9cc, 0x49e0, 0xc0, 0xff, 0xee, 0x64, 0xca, 0x8f, 0x5b, 0x90}
+
+/// The handler of the `Activated` event of a toast.
+///
+/// The event comes on a thread pool thread, so the handler posts the
+/// activation to the notification window of the thread that showed the toast.
+#[repr(C)]
+struct ActivatedHandler {
+    vtbl: *const ActivatedHandlerVtbl,
+    refs: AtomicU32,
+    /// The notification window, as an integer because `HWND` isn't `Send`.
+    hwnd: usize,
+    token: NotificationToken,
+}
+
+#[repr(C)]
+struct ActivatedHandlerVtbl {
+    parent: IUnknownVtbl,
+    Invoke: unsafe extern "system" fn(
+        this: *mut IUnknown,
+        sender: *mut IInspectable,
+        args: *mut IInspectable,
+    ) -> HRESULT,
+}
+
+static ACTIVATED_HANDLER_VTBL: ActivatedHandlerVtbl = ActivatedHandlerVtbl {
+    parent: IUnknownVtbl {
+        QueryInterface: handler_query_interface,
+        AddRef: handler_add_ref,
+        Release: handler_release,
+    },
+    Invoke: handler_invoke,
+};
+
+thread_local! {
+    /// The message-only window that receives the activations of this thread's toasts.
+    static NOTIFICATION_HWND: Cell<HWND> = Cell::new(null_mut());
+}
+
+/// An `HSTRING` that is deleted when dropped.
+struct HString(HSTRING);
+
+impl HString {
+    fn new(text: &str) -> Result<HString, Error> {
+        let wide: Vec<u16> = text.encode_utf16().collect();
+        let mut hstring = null_mut();
+        unsafe {
+            as_result(WindowsCreateString(
+                wide.as_ptr(),
+                wide.len() as u32,
+                &mut hstring,
+            ))?;
+        }
+        Ok(HString(hstring))
+    }
+}
+
+impl Drop for HString {
+    fn drop(&mut self) {
+        unsafe {
+            WindowsDeleteString(self.0);
+        }
+    }
+}
+
+/// Show a notification as a toast of the application `app_id`.
+pub(crate) unsafe fn show_toast(
+    app_id: &str,
+    notification: &DesktopNotification,
+    token: NotificationToken,
+) -> Result<(), Error> {
+    let icon = match &notification.icon {
+        Some(icon) => Some(icon_file(icon)?),
+        None => None,
+    };
+    let xml = toast_xml(notification, icon.as_deref());
+
+    let mut document: *mut IInspectable = null_mut();
+    as_result(RoActivateInstance(
+        HString::new("Windows.Data.Xml.Dom.XmlDocument")?.0,
+        &mut document,
+    ))?;
+    let document = ComPtr::from_raw(document);
+    let document_io = document.cast::<IXmlDocumentIO>()?;
+    as_result(document_io.LoadXml(HString::new(&xml)?.0))?;
+
+    let manager: ComPtr<IToastNotificationManagerStatics> =
+        activation_factory("Windows.UI.Notifications.ToastNotificationManager")?;
+    let mut notifier = null_mut();
+    as_result(manager.CreateToastNotifierWithId(HString::new(app_id)?.0, &mut notifier))?;
+    let notifier = ComPtr::from_raw(notifier);
+
+    let factory: ComPtr<IToastNotificationFactory> =
+        activation_factory("Windows.UI.Notifications.ToastNotification")?;
+    let mut toast = null_mut();
+    as_result(factory.CreateToastNotification(document.as_raw(), &mut toast))?;
+    let toast = ComPtr::from_raw(toast);
+
+    let handler = Box::into_raw(Box::new(ActivatedHandler {
+        vtbl: &ACTIVATED_HANDLER_VTBL,
+        refs: AtomicU32::new(1),
+        hwnd: notification_hwnd()? as usize,
+        token,
+    })) as *mut IUnknown;
+    let mut registration = 0;
+    let added = as_result(toast.add_Activated(handler, &mut registration));
+    // The toast holds its own reference.
+    (*handler).Release();
+    added?;
+
+    as_result(notifier.Show(toast.as_raw()))
+}
+
+/// Handle a `DS_TOAST_ACTIVATED` message.
+pub(super) unsafe fn activated(lparam: LPARAM) {
+    let activation = Box::from_raw(lparam as *mut (NotificationToken, Option<usize>));
+    let (token, action) = *activation;
+    if let Some(app) = crate::Application::try_global() {
+        app.backend_app
+            .with_handler(|handler| handler.notification_activated(token, action));
+    }
+}
+
+unsafe fn activation_factory<T: Interface>(class: &str) -> Result<ComPtr<T>, Error> {
+    let mut factory = null_mut();
+    as_result(RoGetActivationFactory(
+        HString::new(class)?.0,
+        &T::uuidof(),
+        &mut factory,
+    ))?;
+    Ok(ComPtr::from_raw(factory as *mut T))
+}
+
+/// The window that receives the activations of this thread's toasts.
+unsafe fn notification_hwnd() -> Result<HWND, Error> {
+    let hwnd = NOTIFICATION_HWND.with(Cell::get);
+    if !hwnd.is_null() {
+        return Ok(hwnd);
+    }
+    let hwnd = super::tray::create_window(HWND_MESSAGE)?;
+    NOTIFICATION_HWND.with(|cell| cell.set(hwnd));
+    Ok(hwnd)
+}
+
+/// The toast XML of a notification.
+///
+/// The toast itself has no arguments, and each action has its index.
+fn toast_xml(notification: &DesktopNotification, icon: Option<&str>) -> String {
+    let mut xml = String::from("<toast><visual><binding template=\"ToastGeneric\">");
+    xml.push_str(&format!("<text>{}</text>", escape(&notification.title)));
+    xml.push_str(&format!("<text>{}</text>", escape(&notification.body)));
+    if let Some(icon) = icon {
+        xml.push_str(&format!(
+            "<image placement=\"appLogoOverride\" src=\"{}\"/>",
+            escape(icon)
+        ));
+    }
+    xml.push_str("</binding></visual>");
+    if !notification.actions.is_empty() {
+        xml.push_str("<actions>");
+        for (i, label) in notification.actions.iter().enumerate() {
+            xml.push_str(&format!(
+                "<action content=\"{}\" arguments=\"{}\"/>",
+                escape(label),
+                i
+            ));
+        }
+        xml.push_str("</actions>");
+    }
+    xml.push_str("</toast>");
+    xml
+}
+
+/// The action of toast arguments, as set by `toast_xml`.
+fn parse_arguments(arguments: &str) -> Option<Option<usize>> {
+    if arguments.is_empty() {
+        Some(None)
+    } else {
+        arguments.parse().ok().map(Some)
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Write the icon to a PNG file for the toast to load, returning its URI.
+///
+/// Toasts only load images from files. The file is named after its content,
+/// so that notifications with the same icon share it.
+fn icon_file(icon: &ImageBuf) -> Result<String, Error> {
+    let png = encode_png(icon);
+    let mut hasher = DefaultHasher::new();
+    png.hash(&mut hasher);
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("druid-notification-{:016x}.png", hasher.finish()));
+    if !path.exists() {
+        std::fs::write(&path, &png).map_err(|e| {
+            Error::Hr(winapi::shared::winerror::HRESULT_FROM_WIN32(
+                e.raw_os_error().unwrap_or(0) as u32,
+            ))
+        })?;
+    }
+    Ok(format!("file:///{}", path.display()))
+}
+
+/// Encode an image as an uncompressed PNG.
+fn encode_png(image: &ImageBuf) -> Vec<u8> {
+    let (width, height) = (image.width(), image.height());
+    // Each row starts with the "None" filter.
+    let mut raw = Vec::with_capacity(height * (1 + width * 4));
+    for row in image.pixel_colors() {
+        raw.push(0);
+        for p in row {
+            let (r, g, b, a) = p.as_rgba8();
+            raw.extend_from_slice(&[r, g, b, a]);
+        }
+    }
+
+    // A zlib stream of stored blocks.
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bit RGBA, default compression and filtering, no interlacing
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in &[(b"IHDR", ihdr), (b"IDAT", zlib), (b"IEND", Vec::new())] {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(*kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+    png
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+unsafe extern "system" fn handler_query_interface(
+    this: *mut IUnknown,
+    riid: REFIID,
+    object: *mut *mut c_void,
+) -> HRESULT {
+    let riid = &*riid;
+    if IsEqualGUID(riid, &IUnknown::uuidof())
+        || IsEqualGUID(riid, &IID_IAgileObject)
+        || IsEqualGUID(riid, &IID_ToastActivatedHandler)
+    {
+        handler_add_ref(this);
+        *object = this as *mut c_void;
+        S_OK
+    } else {
+        *object = null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn handler_add_ref(this: *mut IUnknown) -> ULONG {
+    let handler = &*(this as *const ActivatedHandler);
+    handler.refs.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+unsafe extern "system" fn handler_release(this: *mut IUnknown) -> ULONG {
+    let refs = (*(this as *const ActivatedHandler))
+        .refs
+        .fetch_sub(1, Ordering::Release)
+        - 1;
+    if refs == 0 {
+        std::sync::atomic::fence(Ordering::Acquire);
+        drop(Box::from_raw(this as *mut ActivatedHandler));
+    }
+    refs
+}
+
+unsafe extern "system" fn handler_invoke(
+    this: *mut IUnknown,
+    _sender: *mut IInspectable,
+    args: *mut IInspectable,
+) -> HRESULT {
+    let handler = &*(this as *const ActivatedHandler);
+    let mut arguments = String::new();
+    if let Some(args) = args.as_ref() {
+        let mut event_args: *mut IToastActivatedEventArgs = null_mut();
+        let hr = args.QueryInterface(
+            &IToastActivatedEventArgs::uuidof(),
+            &mut event_args as *mut _ as *mut *mut c_void,
+        );
+        if hr == S_OK {
+            let event_args = ComPtr::from_raw(event_args);
+            let mut hstring = null_mut();
+            if event_args.get_Arguments(&mut hstring) == S_OK {
+                let mut len = 0;
+                let buffer = WindowsGetStringRawBuffer(hstring, &mut len);
+                if !buffer.is_null() {
+                    let wide = std::slice::from_raw_parts(buffer, len as usize);
+                    arguments = String::from_utf16_lossy(wide);
+                }
+                WindowsDeleteString(hstring);
+            }
+        }
+    }
+    if let Some(action) = parse_arguments(&arguments) {
+        let activation = Box::into_raw(Box::new((handler.token, action)));
+        if PostMessageW(
+            handler.hwnd as HWND,
+            DS_TOAST_ACTIVATED,
+            0 as WPARAM,
+            activation as LPARAM,
+        ) == 0
+        {
+            drop(Box::from_raw(activation));
+        }
+    }
+    S_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::ImageFormat;
+
+    #[test]
+    fn xml_has_escaped_text_and_actions() {
+        let notification = DesktopNotification::new("A & B", "<body>")
+            .action("Yes")
+            .action("No");
+        assert_eq!(
+            toast_xml(&notification, None),
+            "<toast><visual><binding template=\"ToastGeneric\">\
+             <text>A &amp; B</text><text>&lt;body&gt;</text></binding></visual>\
+             <actions><action content=\"Yes\" arguments=\"0\"/>\
+             <action content=\"No\" arguments=\"1\"/></actions></toast>"
+        );
+    }
+
+    #[test]
+    fn arguments_name_the_action() {
+        assert_eq!(parse_arguments(""), Some(None));
+        assert_eq!(parse_arguments("1"), Some(Some(1)));
+        assert_eq!(parse_arguments("launch"), None);
+    }
+
+    #[test]
+    fn png_checksums() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        let image = ImageBuf::from_raw(vec![1, 2, 3, 4], ImageFormat::RgbaSeparate, 1, 1);
+        let png = encode_png(&image);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+}
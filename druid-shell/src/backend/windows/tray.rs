@@ -0,0 +1,385 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Icons and notifications in the Windows notification area.
+
+use std::mem;
+use std::ptr::{null, null_mut};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use winapi::shared::minwindef::{LPARAM, LRESULT, UINT, WPARAM};
+use winapi::shared::windef::{HICON, HMENU, HWND, POINT};
+use winapi::shared::winerror::HRESULT_FROM_WIN32;
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellapi::{
+    Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_MESSAGE, NIF_TIP, NIIF_USER, NIM_ADD, NIM_DELETE,
+    NIM_MODIFY, NOTIFYICONDATAW,
+};
+use winapi::um::wingdi::{CreateBitmap, DeleteObject};
+use winapi::um::winuser::*;
+
+use super::error::Error;
+use super::menu::Menu;
+use super::util::ToWide;
+use crate::notification::{DesktopNotification, NotificationToken};
+use crate::piet::ImageBuf;
+use crate::Application;
+
+/// The message the notification area sends to our window.
+const DS_TRAY_CALLBACK: UINT = WM_APP + 1;
+
+/// The id of tray icons, which have a window of their own.
+const TRAY_ICON_ID: UINT = 0;
+/// The id of the temporary icons that show notifications.
+const NOTIFICATION_ICON_ID: UINT = 1;
+
+// These are missing from winapi.
+const NIN_BALLOONHIDE: UINT = WM_USER + 3;
+const NIN_BALLOONTIMEOUT: UINT = WM_USER + 4;
+const NIN_BALLOONUSERCLICK: UINT = WM_USER + 5;
+
+const TRAY_CLASS_NAME: &str = "druid-tray";
+
+/// Used to ensure the window class is registered only once per process.
+static TRAY_CLASS_REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// An icon in the notification area.
+///
+/// Each icon owns a hidden window that receives its mouse events. The
+/// window's user data points to the icon's [`TrayState`], so that the icon
+/// can be added again when Explorer restarts. For notifications, it holds
+/// the raw `NotificationToken`.
+pub struct TrayIcon {
+    hwnd: HWND,
+    state: Box<TrayState>,
+}
+
+/// What the window of a tray icon needs to show it and its menu.
+struct TrayState {
+    hicon: HICON,
+    hmenu: HMENU,
+    tooltip: String,
+}
+
+impl TrayIcon {
+    pub fn new(icon: &ImageBuf) -> Result<TrayIcon, crate::Error> {
+        unsafe {
+            // Message-only windows don't get the broadcast that the taskbar
+            // was created, so the icon gets a hidden top-level window.
+            let hwnd = create_window(null_mut())?;
+            let state = Box::new(TrayState {
+                hicon: make_icon(icon),
+                hmenu: null_mut(),
+                tooltip: String::new(),
+            });
+            if !add_icon(hwnd, &state) {
+                let error = Error::Hr(HRESULT_FROM_WIN32(GetLastError()));
+                DestroyWindow(hwnd);
+                DestroyIcon(state.hicon);
+                return Err(error.into());
+            }
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, &*state as *const TrayState as _);
+            Ok(TrayIcon { hwnd, state })
+        }
+    }
+
+    pub fn set_icon(&mut self, icon: &ImageBuf) {
+        unsafe {
+            let hicon = make_icon(icon);
+            let mut data = notify_icon_data(self.hwnd, TRAY_ICON_ID);
+            data.uFlags = NIF_ICON;
+            data.hIcon = hicon;
+            if Shell_NotifyIconW(NIM_MODIFY, &mut data) == 0 {
+                tracing::warn!("failed to change the tray icon");
+                DestroyIcon(hicon);
+                return;
+            }
+            DestroyIcon(self.state.hicon);
+            self.state.hicon = hicon;
+        }
+    }
+
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        self.state.tooltip = tooltip.to_owned();
+        unsafe {
+            let mut data = notify_icon_data(self.hwnd, TRAY_ICON_ID);
+            data.uFlags = NIF_TIP;
+            copy_wide(&mut data.szTip, tooltip);
+            if Shell_NotifyIconW(NIM_MODIFY, &mut data) == 0 {
+                tracing::warn!("failed to change the tray tooltip");
+            }
+        }
+    }
+
+    pub fn set_menu(&mut self, menu: Menu) -> Result<(), crate::Error> {
+        unsafe {
+            if !self.state.hmenu.is_null() {
+                DestroyMenu(self.state.hmenu);
+            }
+            self.state.hmenu = menu.into_hmenu();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        unsafe {
+            let mut data = notify_icon_data(self.hwnd, TRAY_ICON_ID);
+            Shell_NotifyIconW(NIM_DELETE, &mut data);
+            DestroyWindow(self.hwnd);
+            DestroyIcon(self.state.hicon);
+            if !self.state.hmenu.is_null() {
+                DestroyMenu(self.state.hmenu);
+            }
+        }
+    }
+}
+
+/// Add the icon of a tray window to the notification area.
+unsafe fn add_icon(hwnd: HWND, state: &TrayState) -> bool {
+    let mut data = notify_icon_data(hwnd, TRAY_ICON_ID);
+    data.uFlags = NIF_MESSAGE | NIF_ICON | NIF_TIP;
+    data.uCallbackMessage = DS_TRAY_CALLBACK;
+    data.hIcon = state.hicon;
+    copy_wide(&mut data.szTip, &state.tooltip);
+    Shell_NotifyIconW(NIM_ADD, &mut data) != 0
+}
+
+/// The message Explorer broadcasts when it (re)creates the taskbar, which
+/// loses the icons that were in the notification area.
+fn taskbar_created_message() -> UINT {
+    static MESSAGE: AtomicU32 = AtomicU32::new(0);
+    let message = MESSAGE.load(Ordering::Acquire);
+    if message != 0 {
+        return message;
+    }
+    let message = unsafe { RegisterWindowMessageW("TaskbarCreated".to_wide().as_ptr()) };
+    MESSAGE.store(message, Ordering::Release);
+    message
+}
+
+/// Show a notification as a balloon of a temporary notification area icon,
+/// for applications without an AppUserModelID to show toasts under.
+///
+/// Each notification has a window of its own, which is destroyed along with
+/// the icon when the balloon goes away.
+pub(crate) fn show_notification(
+    notification: &DesktopNotification,
+    token: NotificationToken,
+) -> Result<(), crate::Error> {
+    unsafe {
+        let hwnd = create_window(HWND_MESSAGE)?;
+        SetWindowLongPtrW(hwnd, GWLP_USERDATA, token.into_raw() as _);
+        let hicon = match &notification.icon {
+            Some(icon) => make_icon(icon),
+            None => null_mut(),
+        };
+        let mut data = notify_icon_data(hwnd, NOTIFICATION_ICON_ID);
+        data.uFlags = NIF_MESSAGE | NIF_ICON | NIF_INFO;
+        data.uCallbackMessage = DS_TRAY_CALLBACK;
+        data.hIcon = LoadIconW(null_mut(), IDI_APPLICATION);
+        copy_wide(&mut data.szInfoTitle, &notification.title);
+        copy_wide(&mut data.szInfo, &notification.body);
+        if !hicon.is_null() {
+            data.dwInfoFlags = NIIF_USER;
+            data.hBalloonIcon = hicon;
+        }
+        let added = Shell_NotifyIconW(NIM_ADD, &mut data);
+        // The shell keeps its own copy of the icon.
+        if !hicon.is_null() {
+            DestroyIcon(hicon);
+        }
+        if added == 0 {
+            DestroyWindow(hwnd);
+            return Err(Error::Hr(HRESULT_FROM_WIN32(GetLastError())).into());
+        }
+        Ok(())
+    }
+}
+
+unsafe fn remove_notification(hwnd: HWND) {
+    let mut data = notify_icon_data(hwnd, NOTIFICATION_ICON_ID);
+    Shell_NotifyIconW(NIM_DELETE, &mut data);
+    DestroyWindow(hwnd);
+}
+
+/// Create a window to receive the events of a notification area icon; with
+/// `HWND_MESSAGE` as the `parent`, a message-only one.
+pub(super) unsafe fn create_window(parent: HWND) -> Result<HWND, Error> {
+    register_class();
+    let hwnd = CreateWindowExW(
+        0,
+        TRAY_CLASS_NAME.to_wide().as_ptr(),
+        null(),
+        0,
+        0,
+        0,
+        0,
+        0,
+        parent,
+        null_mut(),
+        null_mut(),
+        null_mut(),
+    );
+    if hwnd.is_null() {
+        return Err(Error::NullHwnd);
+    }
+    Ok(hwnd)
+}
+
+unsafe fn register_class() {
+    if TRAY_CLASS_REGISTERED
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_ok()
+    {
+        let class_name = TRAY_CLASS_NAME.to_wide();
+        let mut wnd: WNDCLASSW = mem::zeroed();
+        wnd.lpfnWndProc = Some(tray_proc);
+        wnd.lpszClassName = class_name.as_ptr();
+        if RegisterClassW(&wnd) == 0 {
+            tracing::error!(
+                "failed to register the tray window class: {}",
+                Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+            );
+        }
+    }
+}
+
+/// The `NOTIFYICONDATAW` identifying the icon `id` of `hwnd`.
+unsafe fn notify_icon_data(hwnd: HWND, id: UINT) -> NOTIFYICONDATAW {
+    let mut data: NOTIFYICONDATAW = mem::zeroed();
+    data.cbSize = mem::size_of::<NOTIFYICONDATAW>() as u32;
+    data.hWnd = hwnd;
+    data.uID = id;
+    data
+}
+
+/// Copy `text` into a fixed size buffer, truncating it to leave room for the
+/// terminating null.
+fn copy_wide(buf: &mut [u16], text: &str) {
+    for (dst, src) in buf.iter_mut().zip(text.to_wide()) {
+        *dst = src;
+    }
+    if let Some(last) = buf.last_mut() {
+        *last = 0;
+    }
+}
+
+unsafe fn make_icon(icon: &ImageBuf) -> HICON {
+    let width = icon.width();
+    let height = icon.height();
+    // 32 bit BGRA, with unpremultiplied alpha.
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for row in icon.pixel_colors() {
+        for p in row {
+            let (r, g, b, a) = p.as_rgba8();
+            pixels.extend_from_slice(&[b, g, r, a]);
+        }
+    }
+    let color = CreateBitmap(width as i32, height as i32, 1, 32, pixels.as_ptr() as _);
+    // With an alpha channel in the color bitmap, the mask is ignored.
+    let mask = CreateBitmap(width as i32, height as i32, 1, 1, null());
+    let mut icon_info = ICONINFO {
+        fIcon: 1,
+        xHotspot: 0,
+        yHotspot: 0,
+        hbmMask: mask,
+        hbmColor: color,
+    };
+    let hicon = CreateIconIndirect(&mut icon_info);
+    DeleteObject(color as _);
+    DeleteObject(mask as _);
+    hicon
+}
+
+/// The state of the tray icon that owns `hwnd`.
+unsafe fn tray_state<'a>(hwnd: HWND) -> Option<&'a TrayState> {
+    (GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const TrayState).as_ref()
+}
+
+unsafe fn show_menu(hwnd: HWND) {
+    let hmenu = match tray_state(hwnd) {
+        Some(state) if !state.hmenu.is_null() => state.hmenu,
+        _ => return,
+    };
+    let mut pos = POINT { x: 0, y: 0 };
+    GetCursorPos(&mut pos);
+    // Without this, the menu doesn't close when clicking elsewhere.
+    // See the remarks of the `TrackPopupMenu` documentation.
+    SetForegroundWindow(hwnd);
+    let id = TrackPopupMenu(
+        hmenu,
+        TPM_RETURNCMD | TPM_NONOTIFY | TPM_RIGHTBUTTON,
+        pos.x,
+        pos.y,
+        0,
+        hwnd,
+        null(),
+    );
+    PostMessageW(hwnd, WM_NULL, 0, 0);
+    if id != 0 {
+        with_handler(|handler| handler.command(id as u32));
+    }
+}
+
+fn with_handler(f: impl FnOnce(&mut dyn crate::AppHandler)) {
+    if let Some(app) = Application::try_global() {
+        app.backend_app.with_handler(f);
+    }
+}
+
+unsafe extern "system" fn tray_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == super::toast::DS_TOAST_ACTIVATED {
+        super::toast::activated(lparam);
+        return 0;
+    }
+    if msg == DS_TRAY_CALLBACK && wparam as UINT == NOTIFICATION_ICON_ID {
+        match lparam as UINT {
+            NIN_BALLOONUSERCLICK => {
+                let token = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as u64;
+                remove_notification(hwnd);
+                with_handler(|handler| {
+                    handler.notification_activated(NotificationToken::from_raw(token), None)
+                });
+            }
+            NIN_BALLOONTIMEOUT | NIN_BALLOONHIDE => remove_notification(hwnd),
+            _ => (),
+        }
+        return 0;
+    }
+    let taskbar_created = taskbar_created_message();
+    if taskbar_created != 0 && msg == taskbar_created {
+        if let Some(state) = tray_state(hwnd) {
+            if !add_icon(hwnd, state) {
+                tracing::warn!("failed to add the tray icon to the new taskbar");
+            }
+        }
+        return 0;
+    }
+    if msg == DS_TRAY_CALLBACK {
+        match lparam as UINT {
+            WM_LBUTTONUP => with_handler(|handler| handler.tray_activated()),
+            WM_RBUTTONUP => show_menu(hwnd),
+            _ => (),
+        }
+        return 0;
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
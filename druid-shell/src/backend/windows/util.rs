@@ -150,6 +150,8 @@ type GetSystemMetricsForDpi =
 // from shcore.dll
 type GetDpiForMonitor = unsafe extern "system" fn(HMONITOR, MONITOR_DPI_TYPE, *mut UINT, *mut UINT);
 type SetProcessDpiAwareness = unsafe extern "system" fn(PROCESS_DPI_AWARENESS) -> HRESULT;
+// from msctf.dll
+type SetInputScope = unsafe extern "system" fn(HWND, InputScope) -> HRESULT;
 type DCompositionCreateDevice = unsafe extern "system" fn(
     dxgiDevice: *const IDXGIDevice,
     iid: REFIID,
@@ -165,6 +167,7 @@ pub struct OptionalFunctions {
     pub SetProcessDpiAwareness: Option<SetProcessDpiAwareness>,
     pub GetSystemMetricsForDpi: Option<GetSystemMetricsForDpi>,
     pub DCompositionCreateDevice: Option<DCompositionCreateDevice>,
+    pub SetInputScope: Option<SetInputScope>,
 }
 
 #[allow(non_snake_case)] // For local variables
@@ -211,6 +214,7 @@ fn load_optional_functions() -> OptionalFunctions {
     let shcore = load_library("shcore.dll");
     let user32 = load_library("user32.dll");
     let dcomp = load_library("dcomp.dll");
+    let msctf = load_library("msctf.dll");
 
     let mut GetDpiForSystem = None;
     let mut GetDpiForMonitor = None;
@@ -219,6 +223,7 @@ fn load_optional_functions() -> OptionalFunctions {
     let mut SetProcessDpiAwareness = None;
     let mut GetSystemMetricsForDpi = None;
     let mut DCompositionCreateDevice = None;
+    let mut SetInputScope = None;
 
     if shcore.is_null() {
         tracing::info!("No shcore.dll");
@@ -242,6 +247,12 @@ fn load_optional_functions() -> OptionalFunctions {
         load_function!(dcomp, DCompositionCreateDevice, "8.1");
     }
 
+    if msctf.is_null() {
+        tracing::info!("No msctf.dll");
+    } else {
+        load_function!(msctf, SetInputScope, "Vista");
+    }
+
     OptionalFunctions {
         GetDpiForSystem,
         GetDpiForWindow,
@@ -250,9 +261,18 @@ fn load_optional_functions() -> OptionalFunctions {
         SetProcessDpiAwareness,
         GetSystemMetricsForDpi,
         DCompositionCreateDevice,
+        SetInputScope,
     }
 }
 
+/// The `InputScope` values for the kinds of text we know about.
+pub(crate) type InputScope = i32;
+pub(crate) const IS_DEFAULT: InputScope = 0;
+pub(crate) const IS_URL: InputScope = 1;
+pub(crate) const IS_EMAIL_SMTPEMAILADDRESS: InputScope = 5;
+pub(crate) const IS_NUMBER: InputScope = 29;
+pub(crate) const IS_TELEPHONE_FULLTELEPHONENUMBER: InputScope = 32;
+
 lazy_static! {
     pub static ref OPTIONAL_FUNCTIONS: OptionalFunctions = load_optional_functions();
 }
@@ -17,6 +17,7 @@
 #![allow(non_snake_case, clippy::cast_lossless)]
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::mem;
 use std::panic::Location;
 use std::ptr::{null, null_mut};
@@ -34,9 +35,12 @@ use winapi::shared::dxgitype::*;
 use winapi::shared::minwindef::*;
 use winapi::shared::windef::*;
 use winapi::shared::winerror::*;
+use winapi::um::d2d1::*;
 use winapi::um::dcomp::{IDCompositionDevice, IDCompositionTarget, IDCompositionVisual};
-use winapi::um::dwmapi::DwmExtendFrameIntoClientArea;
+use winapi::um::dwmapi::{DwmExtendFrameIntoClientArea, DwmSetWindowAttribute};
+use winapi::um::dwrite::*;
 use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::ole2::RevokeDragDrop;
 use winapi::um::shellscalingapi::MDT_EFFECTIVE_DPI;
 use winapi::um::unknwnbase::*;
 use winapi::um::uxtheme::*;
@@ -59,24 +63,31 @@ use super::accels::register_accel;
 use super::application::Application;
 use super::dcomp::D3D11Device;
 use super::dialog::get_file_dialog_path;
+use super::drop_target::{register_drop_target, DropMessage, DropPhase};
 use super::error::Error;
 use super::keyboard::KeyboardState;
 use super::menu::Menu;
 use super::paint;
+use super::screen;
 use super::timers::TimerSlots;
 use super::util::{self, as_result, FromWide, ToWide, OPTIONAL_FUNCTIONS};
 
 use crate::common_util::IdleCallback;
 use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
-use crate::error::Error as ShellError;
-use crate::keyboard::{KbKey, KeyState};
+use crate::dnd::DropEvent;
+use crate::error::{ShellError, ShellOperation};
+use crate::keyboard::{KbKey, KeyState, Modifiers};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
+use crate::pen::{PenEvent, PenPhase};
 use crate::region::Region;
 use crate::scale::{Scalable, Scale, ScaledArea};
-use crate::text::{simulate_input, Event};
+use crate::screen::Monitor;
+use crate::text::{simulate_input, Event, InputPurpose};
+use crate::touch::{TouchEvent, TouchId};
 use crate::window;
 use crate::window::{
-    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+    FileDialogToken, IdleToken, ResizeEdge, TextAntialiasing, TextFieldToken, TextHinting,
+    TextRenderingOptions, TimerToken, WinHandler, WindowCorners, WindowLevel,
 };
 
 /// The backend target DPI.
@@ -84,6 +95,11 @@ use crate::window::{
 /// Windows considers 96 the default value which represents a 1.0 scale factor.
 pub(crate) const SCALE_TARGET_DPI: f64 = 96.0;
 
+// DWM window attributes that are missing from winapi. Versions of Windows
+// that don't know them fail the call, which is harmless.
+const DWMWA_USE_IMMERSIVE_DARK_MODE: DWORD = 20;
+const DWMWA_WINDOW_CORNER_PREFERENCE: DWORD = 33;
+
 /// Builder abstraction for creating new windows.
 pub(crate) struct WindowBuilder {
     app: Application,
@@ -160,7 +176,37 @@ enum DeferredOp {
     SetSize(Size),
     SetResizable(bool),
     SetWindowState(window::WindowState),
+    /// Enter or leave fullscreen, on the monitor with the given rect in pixels.
+    SetFullscreen(bool, Option<Rect>),
+    SetLevel(WindowLevel),
     ReleaseMouseCapture,
+    /// Tell the touch keyboard what the focused text field expects.
+    UpdateInputScope,
+}
+
+/// Set the input scope of the window, which the touch keyboard uses to pick
+/// a layout. `None` means no text field is focused.
+fn set_input_scope(hwnd: HWND, purpose: Option<InputPurpose>) {
+    let scope = match purpose {
+        Some(InputPurpose::Number) => util::IS_NUMBER,
+        Some(InputPurpose::Phone) => util::IS_TELEPHONE_FULLTELEPHONENUMBER,
+        Some(InputPurpose::Email) => util::IS_EMAIL_SMTPEMAILADDRESS,
+        Some(InputPurpose::Url) => util::IS_URL,
+        _ => util::IS_DEFAULT,
+    };
+    if let Some(func) = OPTIONAL_FUNCTIONS.SetInputScope {
+        let hr = unsafe { func(hwnd, scope) };
+        if FAILED(hr) {
+            warn!("failed to set the input scope: {}", Error::Hr(hr));
+        }
+    }
+}
+
+/// What's needed to restore a window when it leaves fullscreen.
+#[derive(Clone, Copy)]
+struct FullscreenRestore {
+    style: u32,
+    placement: WINDOWPLACEMENT,
 }
 
 #[derive(Clone)]
@@ -222,10 +268,15 @@ struct WindowState {
     // For resizable borders, window can still be resized with code.
     is_resizable: Cell<bool>,
     handle_titlebar: Cell<bool>,
+    // The custom maximize button, in client coordinates, in display points.
+    maximize_button: Cell<Option<Rect>>,
+    // Set while the window is fullscreen.
+    fullscreen: Cell<Option<FullscreenRestore>>,
     active_text_input: Cell<Option<TextFieldToken>>,
+    text_rendering: Cell<TextRenderingOptions>,
     // Is the window focusable ("activatable" in Win32 terminology)?
-    // False for tooltips, to prevent stealing focus from owner window.
-    is_focusable: bool,
+    // False for tooltips and utility windows, to prevent stealing focus from owner window.
+    is_focusable: Cell<bool>,
 }
 
 /// Generic handler trait for the winapi window procedure entry point.
@@ -267,6 +318,13 @@ struct WndState {
     last_click_time: Instant,
     last_click_pos: (i32, i32),
     click_count: u8,
+    // Custom DirectWrite parameters, and the options they were created for.
+    text_params: Option<(TextRenderingOptions, ComPtr<IDWriteRenderingParams>)>,
+    // The monitor holding most of the window, null until the window is first moved.
+    monitor: HMONITOR,
+    // The touch contacts that the handler took when they touched down. Windows doesn't
+    // make mouse messages of them.
+    handled_touches: HashSet<u32>,
 }
 
 /// State for DXGI swapchains.
@@ -311,6 +369,13 @@ const DS_RUN_IDLE: UINT = WM_USER;
 /// time it is handled, we can successfully borrow the handler.
 pub(crate) const DS_REQUEST_DESTROY: UINT = WM_USER + 1;
 
+/// Message relaying a drag from another application, sent by the window's
+/// drop target while OLE runs the drag.
+///
+/// `lparam` points to a [`DropMessage`], whose `accepted` field is set to
+/// the answer of the handler.
+pub(crate) const DS_DROP: UINT = WM_USER + 2;
+
 impl Default for PresentStrategy {
     fn default() -> PresentStrategy {
         PresentStrategy::Sequential
@@ -338,6 +403,83 @@ fn get_buttons(wparam: WPARAM) -> MouseButtons {
     buttons
 }
 
+/// The type of a pointer, such as `PT_PEN` or `PT_TOUCH`.
+fn get_pointer_type(pointer_id: u32) -> Option<POINTER_INPUT_TYPE> {
+    let mut pointer_type = 0;
+    if unsafe { GetPointerType(pointer_id, &mut pointer_type) } == FALSE {
+        return None;
+    }
+    Some(pointer_type)
+}
+
+/// The touch event of a pointer message from a touch screen, and whether it is cancelled.
+fn get_touch_event(
+    hwnd: HWND,
+    pointer_id: u32,
+    scale: Scale,
+    mods: Modifiers,
+) -> Option<(TouchEvent, bool)> {
+    unsafe {
+        let mut info: POINTER_INFO = mem::zeroed();
+        if GetPointerInfo(pointer_id, &mut info) == FALSE {
+            warn!(
+                "failed to get pointer info: {}",
+                Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+            );
+            return None;
+        }
+        let mut point = info.ptPixelLocation;
+        ScreenToClient(hwnd, &mut point);
+        let event = TouchEvent {
+            id: TouchId::from_raw(pointer_id as u64),
+            pos: Point::new(point.x as f64, point.y as f64).to_dp(scale),
+            mods,
+            primary: info.pointerFlags & POINTER_FLAG_PRIMARY != 0,
+        };
+        Some((event, info.pointerFlags & POINTER_FLAG_CANCELED != 0))
+    }
+}
+
+/// The pen event of a pointer message from a pen.
+fn get_pen_event(
+    hwnd: HWND,
+    pointer_id: u32,
+    phase: PenPhase,
+    scale: Scale,
+    mods: Modifiers,
+) -> Option<PenEvent> {
+    unsafe {
+        let mut info: POINTER_PEN_INFO = mem::zeroed();
+        if GetPointerPenInfo(pointer_id, &mut info) == FALSE {
+            warn!(
+                "failed to get pen info: {}",
+                Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+            );
+            return None;
+        }
+        let mut point = info.pointerInfo.ptPixelLocation;
+        ScreenToClient(hwnd, &mut point);
+        let touching = info.pointerInfo.pointerFlags & POINTER_FLAG_INCONTACT != 0;
+        let pressure = if !touching {
+            0.0
+        } else if info.penMask & PEN_MASK_PRESSURE != 0 {
+            // Windows reports the pressure from 0 to 1024
+            info.pressure as f64 / 1024.0
+        } else {
+            0.5
+        };
+        Some(PenEvent {
+            pos: Point::new(point.x as f64, point.y as f64).to_dp(scale),
+            phase,
+            pressure,
+            tilt: Vec2::new(info.tiltX as f64, info.tiltY as f64),
+            barrel_button: info.penFlags & PEN_FLAG_BARREL != 0,
+            eraser: info.penFlags & (PEN_FLAG_ERASER | PEN_FLAG_INVERTED) != 0,
+            mods,
+        })
+    }
+}
+
 fn is_point_in_client_rect(hwnd: HWND, x: i32, y: i32) -> bool {
     unsafe {
         let mut client_rect = mem::MaybeUninit::uninit();
@@ -421,7 +563,14 @@ impl WndState {
     }
 
     // Renders but does not present.
-    fn render(&mut self, d2d: &D2DFactory, text: &PietText, invalid: &Region) {
+    fn render(
+        &mut self,
+        d2d: &D2DFactory,
+        text: &PietText,
+        invalid: &Region,
+        text_rendering: TextRenderingOptions,
+    ) {
+        self.apply_text_rendering(text_rendering);
         let rt = self.render_target.as_mut().unwrap();
 
         rt.begin_draw();
@@ -443,6 +592,36 @@ impl WndState {
         }
     }
 
+    // Applies the text rendering options to the render target.
+    //
+    // This is done before every render, because the render target is
+    // recreated when the window is resized.
+    fn apply_text_rendering(&mut self, options: TextRenderingOptions) {
+        let rt = match self.render_target.as_ref() {
+            Some(rt) => rt.get_raw(),
+            None => return,
+        };
+        let antialias_mode = match options.antialiasing {
+            TextAntialiasing::Default => D2D1_TEXT_ANTIALIAS_MODE_DEFAULT,
+            TextAntialiasing::None => D2D1_TEXT_ANTIALIAS_MODE_ALIASED,
+            TextAntialiasing::Grayscale => D2D1_TEXT_ANTIALIAS_MODE_GRAYSCALE,
+            TextAntialiasing::Subpixel => D2D1_TEXT_ANTIALIAS_MODE_CLEARTYPE,
+        };
+        if options.hinting == TextHinting::Default && options.gamma.is_none() {
+            self.text_params = None;
+        } else if self.text_params.as_ref().map(|(o, _)| *o) != Some(options) {
+            self.text_params = create_rendering_params(options).map(|p| (options, p));
+        }
+        unsafe {
+            (*rt).SetTextAntialiasMode(antialias_mode);
+            let params = match self.text_params.as_ref() {
+                Some((_, params)) => params.as_raw(),
+                None => null_mut(),
+            };
+            (*rt).SetTextRenderingParams(params);
+        }
+    }
+
     fn enter_mouse_capture(&mut self, hwnd: HWND, button: MouseButton) {
         if self.captured_mouse_buttons.is_empty() {
             unsafe {
@@ -458,6 +637,51 @@ impl WndState {
     }
 }
 
+/// Create DirectWrite rendering parameters for `options`, starting from the
+/// parameters of the primary monitor.
+fn create_rendering_params(
+    options: TextRenderingOptions,
+) -> Option<ComPtr<IDWriteRenderingParams>> {
+    let factory = DwriteFactory::new().ok()?;
+    unsafe {
+        let factory = factory.get_raw();
+        let mut defaults = null_mut();
+        if !SUCCEEDED((*factory).CreateRenderingParams(&mut defaults)) {
+            return None;
+        }
+        let defaults = ComPtr::from_raw(defaults);
+        let rendering_mode = match options.hinting {
+            TextHinting::Default => defaults.GetRenderingMode(),
+            TextHinting::None => DWRITE_RENDERING_MODE_OUTLINE,
+            TextHinting::Slight => DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC,
+            TextHinting::Medium => DWRITE_RENDERING_MODE_NATURAL,
+            TextHinting::Full => DWRITE_RENDERING_MODE_GDI_CLASSIC,
+        };
+        let clear_type_level = match options.antialiasing {
+            TextAntialiasing::Grayscale | TextAntialiasing::None => 0.0,
+            _ => defaults.GetClearTypeLevel(),
+        };
+        let mut params = null_mut();
+        let hr = (*factory).CreateCustomRenderingParams(
+            options
+                .gamma
+                .map(|g| g as f32)
+                .unwrap_or_else(|| defaults.GetGamma()),
+            defaults.GetEnhancedContrast(),
+            clear_type_level,
+            defaults.GetPixelGeometry(),
+            rendering_mode,
+            &mut params,
+        );
+        if SUCCEEDED(hr) {
+            Some(ComPtr::from_raw(params))
+        } else {
+            warn!("CreateCustomRenderingParams failed: {:x}", hr);
+            None
+        }
+    }
+}
+
 impl MyWndProc {
     fn with_window_state<F, R>(&self, f: F) -> R
     where
@@ -494,6 +718,10 @@ impl MyWndProc {
         self.with_window_state(|state| state.scale.get())
     }
 
+    fn text_rendering(&self) -> TextRenderingOptions {
+        self.with_window_state(|state| state.text_rendering.get())
+    }
+
     fn set_scale(&self, scale: Scale) {
         self.with_window_state(move |state| state.scale.set(scale))
     }
@@ -517,6 +745,19 @@ impl MyWndProc {
         self.with_window_state(|state| state.has_menu.get())
     }
 
+    /// Convert the screen coordinates in the `lparam` of a non-client
+    /// message to client coordinates, in display points.
+    fn screen_to_client_dp(&self, hwnd: HWND, lparam: LPARAM) -> Option<Point> {
+        let mut point = POINT {
+            x: LOWORD(lparam as u32) as i16 as i32,
+            y: HIWORD(lparam as u32) as i16 as i32,
+        };
+        if unsafe { ScreenToClient(hwnd, &mut point) } == FALSE {
+            return None;
+        }
+        Some(Point::new(point.x as f64, point.y as f64).to_dp(self.scale()))
+    }
+
     fn has_titlebar(&self) -> bool {
         self.with_window_state(|state| state.has_titlebar.get())
     }
@@ -525,6 +766,10 @@ impl MyWndProc {
         self.with_window_state(|state| state.is_resizable.get())
     }
 
+    fn is_fullscreen(&self) -> bool {
+        self.with_window_state(|state| state.fullscreen.get().is_some())
+    }
+
     fn is_transparent(&self) -> bool {
         self.with_window_state(|state| state.is_transparent.get())
     }
@@ -536,6 +781,23 @@ impl MyWndProc {
         }
     }
 
+    /// Show a file dialog, reporting failures to the handler.
+    fn file_dialog_path(
+        &self,
+        hwnd: HWND,
+        ty: FileDialogType,
+        options: FileDialogOptions,
+    ) -> Option<FileInfo> {
+        match unsafe { get_file_dialog_path(hwnd, ty, options) } {
+            Ok(info) => info,
+            Err(e) => {
+                let error = ShellError::new(ShellOperation::FileDialog, e);
+                self.with_wnd_state(|s| s.handler.shell_error(error));
+                None
+            }
+        }
+    }
+
     fn handle_deferred(&self, op: DeferredOp) {
         if let Some(hwnd) = self.handle.borrow().get_hwnd() {
             match op {
@@ -597,22 +859,44 @@ impl MyWndProc {
                         ShowWindow(hwnd, show);
                     }
                 }
-                DeferredOp::SaveAs(options, token) => {
-                    let info = unsafe {
-                        get_file_dialog_path(hwnd, FileDialogType::Save, options)
-                            .ok()
-                            .map(|os_str| FileInfo {
-                                path: os_str.into(),
-                            })
+                DeferredOp::SetFullscreen(fullscreen, monitor) => unsafe {
+                    self.set_fullscreen(hwnd, fullscreen, monitor);
+                },
+                DeferredOp::SetLevel(level) => unsafe {
+                    let utility = level == WindowLevel::Utility;
+                    self.with_window_state(|s| s.is_focusable.set(!utility));
+                    let mut ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as DWORD;
+                    ex_style &= !(WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE);
+                    if utility {
+                        ex_style |= WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE;
+                    }
+                    SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style as _);
+                    let insert_after = match level {
+                        WindowLevel::AppWindow => HWND_NOTOPMOST,
+                        _ => HWND_TOPMOST,
                     };
+                    if SetWindowPos(
+                        hwnd,
+                        insert_after,
+                        0,
+                        0,
+                        0,
+                        0,
+                        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+                    ) == 0
+                    {
+                        warn!(
+                            "failed to change the window level: {}",
+                            Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                        );
+                    }
+                },
+                DeferredOp::SaveAs(options, token) => {
+                    let info = self.file_dialog_path(hwnd, FileDialogType::Save, options);
                     self.with_wnd_state(|s| s.handler.save_as(token, info));
                 }
                 DeferredOp::Open(options, token) => {
-                    let info = unsafe {
-                        get_file_dialog_path(hwnd, FileDialogType::Open, options)
-                            .ok()
-                            .map(|s| FileInfo { path: s.into() })
-                    };
+                    let info = self.file_dialog_path(hwnd, FileDialogType::Open, options);
                     self.with_wnd_state(|s| s.handler.open_file(token, info));
                 }
                 DeferredOp::ContextMenu(menu, pos) => {
@@ -631,6 +915,22 @@ impl MyWndProc {
                         }
                     }
                 }
+                DeferredOp::UpdateInputScope => {
+                    let purpose = self
+                        .with_window_state(|state| state.active_text_input.get())
+                        .and_then(|token| {
+                            self.with_wnd_state(|s| {
+                                let purpose = s
+                                    .handler
+                                    .acquire_input_lock(token, false)
+                                    .input_hints()
+                                    .purpose();
+                                s.handler.release_input_lock(token);
+                                purpose
+                            })
+                        });
+                    set_input_scope(hwnd, purpose);
+                }
                 DeferredOp::ReleaseMouseCapture => unsafe {
                     if ReleaseCapture() == FALSE {
                         let result = HRESULT_FROM_WIN32(GetLastError());
@@ -647,6 +947,73 @@ impl MyWndProc {
         }
     }
 
+    /// Cover the whole monitor with a window without decorations, or restore
+    /// the window's style and placement from before it went fullscreen.
+    unsafe fn set_fullscreen(&self, hwnd: HWND, fullscreen: bool, monitor: Option<Rect>) {
+        let restore = self.with_window_state(|state| state.fullscreen.get());
+        if fullscreen {
+            let rect = match monitor {
+                Some(rect) => rect,
+                None => {
+                    let hmonitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+                    let mut info: MONITORINFO = mem::zeroed();
+                    info.cbSize = mem::size_of::<MONITORINFO>() as u32;
+                    if GetMonitorInfoW(hmonitor, &mut info) == 0 {
+                        warn!(
+                            "failed to get monitor info: {}",
+                            Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                        );
+                        return;
+                    }
+                    let r = info.rcMonitor;
+                    Rect::new(r.left as f64, r.top as f64, r.right as f64, r.bottom as f64)
+                }
+            };
+            if restore.is_none() {
+                let style = GetWindowLongPtrW(hwnd, GWL_STYLE) as u32;
+                let mut placement: WINDOWPLACEMENT = mem::zeroed();
+                placement.length = mem::size_of::<WINDOWPLACEMENT>() as u32;
+                GetWindowPlacement(hwnd, &mut placement);
+                let restore = FullscreenRestore { style, placement };
+                self.with_window_state(|state| state.fullscreen.set(Some(restore)));
+                // A maximized window would keep its maximized placement.
+                if IsZoomed(hwnd) != 0 {
+                    ShowWindow(hwnd, SW_RESTORE);
+                }
+                let style = style & !(WS_CAPTION | WS_THICKFRAME);
+                SetWindowLongPtrW(hwnd, GWL_STYLE, style as _);
+            }
+            if SetWindowPos(
+                hwnd,
+                HWND_TOP,
+                rect.x0 as i32,
+                rect.y0 as i32,
+                rect.width() as i32,
+                rect.height() as i32,
+                SWP_NOOWNERZORDER | SWP_FRAMECHANGED,
+            ) == 0
+            {
+                warn!(
+                    "failed to make the window fullscreen: {}",
+                    Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                );
+            }
+        } else if let Some(restore) = restore {
+            self.with_window_state(|state| state.fullscreen.set(None));
+            SetWindowLongPtrW(hwnd, GWL_STYLE, restore.style as _);
+            SetWindowPlacement(hwnd, &restore.placement);
+            SetWindowPos(
+                hwnd,
+                null_mut(),
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOOWNERZORDER | SWP_FRAMECHANGED,
+            );
+        }
+    }
+
     fn get_system_metric(&self, metric: c_int) -> i32 {
         unsafe {
             // This is only supported on windows 10.
@@ -713,6 +1080,16 @@ impl WndProc for MyWndProc {
                 if let Some(state) = self.handle.borrow().state.upgrade() {
                     state.hwnd.set(hwnd);
                 }
+                register_drop_target(hwnd);
+                unsafe {
+                    if AddClipboardFormatListener(hwnd) == FALSE {
+                        let result = HRESULT_FROM_WIN32(GetLastError());
+                        warn!(
+                            "failed to listen for clipboard changes: {}",
+                            Error::Hr(result)
+                        );
+                    }
+                }
                 if let Some(state) = self.state.borrow_mut().as_mut() {
                     let dxgi_state = unsafe {
                         create_dxgi_state(self.present_strategy, hwnd, self.is_transparent())
@@ -781,6 +1158,40 @@ impl WndProc for MyWndProc {
                 self.with_wnd_state(|s| s.handler.lost_focus());
                 Some(0)
             }
+            WM_CLIPBOARDUPDATE => {
+                self.with_wnd_state(|s| s.handler.clipboard_changed());
+                Some(0)
+            }
+            WM_DISPLAYCHANGE => {
+                self.with_wnd_state(|s| s.handler.monitors_changed());
+                None
+            }
+            WM_MOVE => {
+                let hmonitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+                let previous = self.with_wnd_state(|s| mem::replace(&mut s.monitor, hmonitor));
+                if matches!(previous, Some(previous) if !previous.is_null() && previous != hmonitor)
+                {
+                    if let Some(monitor) = unsafe { screen::get_monitor(hmonitor) } {
+                        self.with_wnd_state(|s| s.handler.monitor_changed(&monitor));
+                    }
+                }
+                None
+            }
+            WM_SETTINGCHANGE | WM_DWMCOLORIZATIONCOLORCHANGED => {
+                // Switching between light and dark mode changes the "ImmersiveColorSet".
+                let theme_changed = msg == WM_DWMCOLORIZATIONCOLORCHANGED
+                    || (lparam != 0
+                        && (lparam as LPWSTR).from_wide().as_deref() == Some("ImmersiveColorSet"));
+                if theme_changed {
+                    let theme = Application::system_theme();
+                    self.with_wnd_state(|s| s.handler.system_theme_changed(&theme));
+                }
+                // The taskbar or an app bar moved, changing a monitor's work area.
+                if msg == WM_SETTINGCHANGE && wparam == SPI_SETWORKAREA as WPARAM {
+                    self.with_wnd_state(|s| s.handler.monitors_changed());
+                }
+                None
+            }
             WM_PAINT => unsafe {
                 self.with_wnd_state(|s| {
                     // We call prepare_paint before GetUpdateRect, so that anything invalidated during
@@ -798,7 +1209,12 @@ impl WndProc for MyWndProc {
                     let invalid = self.take_invalid();
                     if !invalid.rects().is_empty() {
                         s.handler.rebuild_resources();
-                        s.render(&self.d2d_factory, &self.text, &invalid);
+                        s.render(
+                            &self.d2d_factory,
+                            &self.text,
+                            &invalid,
+                            self.text_rendering(),
+                        );
                         if let Some(ref mut ds) = s.dxgi_state {
                             let mut dirty_rects = util::region_to_rectis(&invalid, self.scale());
                             let params = DXGI_PRESENT_PARAMETERS {
@@ -818,6 +1234,7 @@ impl WndProc for MyWndProc {
                 let y = LOWORD(wparam as u32) as f64 / SCALE_TARGET_DPI;
                 let scale = Scale::new(x, y);
                 self.set_scale(scale);
+                let previous = self.with_wnd_state(|s| s.monitor);
                 let rect: *mut RECT = lparam as *mut RECT;
                 SetWindowPos(
                     hwnd,
@@ -832,6 +1249,12 @@ impl WndProc for MyWndProc {
                         | SWP_NOOWNERZORDER
                         | SWP_NOACTIVATE,
                 );
+                // If the window moved to another monitor, WM_MOVE reported it. Otherwise the
+                // scale of its monitor changed.
+                let hmonitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+                if previous == Some(hmonitor) {
+                    self.with_wnd_state(|s| s.handler.monitors_changed());
+                }
                 Some(0)
             },
             WM_NCCALCSIZE => unsafe {
@@ -857,7 +1280,7 @@ impl WndProc for MyWndProc {
             },
             WM_NCHITTEST => unsafe {
                 let mut hit = DefWindowProcW(hwnd, msg, wparam, lparam);
-                if !self.has_titlebar() && self.resizable() {
+                if !self.has_titlebar() && self.resizable() && !self.is_fullscreen() {
                     if let Ok(handle) = self.handle.try_borrow() {
                         if handle.get_window_state() != window::WindowState::Maximized {
                             let mut rect = RECT {
@@ -908,8 +1331,49 @@ impl WndProc for MyWndProc {
                 if self.with_window_state(|state| state.handle_titlebar.get()) && hit == HTCLIENT {
                     hit = HTCAPTION;
                 }
+                // Windows 11 shows snap layouts when a maximize button is hovered.
+                if let Some(button) = self.with_window_state(|state| state.maximize_button.get()) {
+                    if let Some(pos) = self.screen_to_client_dp(hwnd, lparam) {
+                        if button.contains(pos) {
+                            hit = HTMAXBUTTON;
+                        }
+                    }
+                }
                 Some(hit)
             },
+            WM_NCMOUSEMOVE if wparam == HTMAXBUTTON as WPARAM => {
+                // The custom maximize button is a non-client area now, so it
+                // gets no client mouse moves; forward these so it can show
+                // its hover state.
+                if let Some(pos) = self.screen_to_client_dp(hwnd, lparam) {
+                    self.with_wnd_state(|s| {
+                        let event = MouseEvent {
+                            pos,
+                            buttons: MouseButtons::new(),
+                            mods: s.keyboard_state.get_modifiers(),
+                            count: 0,
+                            focus: false,
+                            button: MouseButton::None,
+                            wheel_delta: Vec2::ZERO,
+                            precise_wheel: false,
+                        };
+                        s.handler.mouse_move(&event);
+                    });
+                }
+                None
+            }
+            // Keep the default handling from drawing a classic maximize
+            // button over the custom one.
+            WM_NCLBUTTONDOWN if wparam == HTMAXBUTTON as WPARAM => Some(0),
+            WM_NCLBUTTONUP if wparam == HTMAXBUTTON as WPARAM => unsafe {
+                let show = if IsZoomed(hwnd) != FALSE {
+                    SW_RESTORE
+                } else {
+                    SW_MAXIMIZE
+                };
+                ShowWindow(hwnd, show);
+                Some(0)
+            },
             WM_SIZE => unsafe {
                 let width = LOWORD(lparam as u32) as u32;
                 let height = HIWORD(lparam as u32) as u32;
@@ -937,7 +1401,12 @@ impl WndProc for MyWndProc {
                         if let Err(e) = s.rebuild_render_target(&self.d2d_factory, scale) {
                             error!("error building render target: {}", e);
                         }
-                        s.render(&self.d2d_factory, &self.text, &size_dp.to_rect().into());
+                        s.render(
+                            &self.d2d_factory,
+                            &self.text,
+                            &size_dp.to_rect().into(),
+                            self.text_rendering(),
+                        );
                         let present_after = match self.present_strategy {
                             PresentStrategy::Sequential => 1,
                             _ => 0,
@@ -1038,6 +1507,8 @@ impl WndProc for MyWndProc {
                         focus: false,
                         button: MouseButton::None,
                         wheel_delta,
+                        // precision touchpads scroll by fractions of a notch
+                        precise_wheel: system_delta % WHEEL_DELTA as f64 != 0.0,
                     };
                     s.handler.wheel(&event);
                     true
@@ -1087,11 +1558,67 @@ impl WndProc for MyWndProc {
                         focus: false,
                         button: MouseButton::None,
                         wheel_delta: Vec2::ZERO,
+                        precise_wheel: false,
                     };
                     s.handler.mouse_move(&event);
                 });
                 Some(0)
             }
+            WM_POINTERDOWN | WM_POINTERUPDATE | WM_POINTERUP => {
+                let pointer_id = LOWORD(wparam as u32) as u32;
+                match get_pointer_type(pointer_id) {
+                    Some(PT_PEN) => {
+                        let phase = match msg {
+                            WM_POINTERDOWN => PenPhase::Down,
+                            WM_POINTERUP => PenPhase::Up,
+                            _ => PenPhase::Move,
+                        };
+                        self.with_wnd_state(|s| {
+                            let mods = s.keyboard_state.get_modifiers();
+                            if let Some(pen) =
+                                get_pen_event(hwnd, pointer_id, phase, self.scale(), mods)
+                            {
+                                s.handler.pen(&pen);
+                            }
+                        });
+                        // let Windows go on to make mouse messages of the pointer
+                        None
+                    }
+                    Some(PT_TOUCH) => self
+                        .with_wnd_state(|s| {
+                            let mods = s.keyboard_state.get_modifiers();
+                            let (touch, cancelled) =
+                                get_touch_event(hwnd, pointer_id, self.scale(), mods)?;
+                            match msg {
+                                WM_POINTERDOWN => {
+                                    if s.handler.touch_down(&touch) {
+                                        s.handled_touches.insert(pointer_id);
+                                    }
+                                }
+                                WM_POINTERUPDATE if !cancelled => {
+                                    s.handler.touch_move(&touch);
+                                }
+                                _ => {
+                                    s.handler.touch_up(&touch);
+                                }
+                            }
+                            // Windows makes mouse messages of the contacts we leave to it. Which
+                            // ones those are is decided when they touch down, so that the mouse
+                            // messages are balanced.
+                            let handled = s.handled_touches.contains(&pointer_id);
+                            if msg == WM_POINTERUP || cancelled {
+                                s.handled_touches.remove(&pointer_id);
+                            }
+                            if handled {
+                                Some(0)
+                            } else {
+                                None
+                            }
+                        })
+                        .flatten(),
+                    _ => None,
+                }
+            }
             WM_MOUSELEAVE => {
                 self.with_wnd_state(|s| {
                     s.has_mouse_focus = false;
@@ -1167,6 +1694,7 @@ impl WndProc for MyWndProc {
                             focus: false,
                             button,
                             wheel_delta: Vec2::ZERO,
+                            precise_wheel: false,
                         };
                         if count > 0 {
                             s.enter_mouse_capture(hwnd, button);
@@ -1182,6 +1710,25 @@ impl WndProc for MyWndProc {
 
                 Some(0)
             }
+            WM_QUERYENDSESSION => {
+                // The session ends once the app has quit; until then, Windows
+                // tells the user that we're blocking it.
+                let quitting = match crate::Application::try_global() {
+                    Some(app) => {
+                        let mut asked = false;
+                        app.backend_app.with_handler(|handler| {
+                            asked = true;
+                            handler.request_quit();
+                        });
+                        if !asked {
+                            app.quit();
+                        }
+                        app.backend_app.is_quitting()
+                    }
+                    None => true,
+                };
+                Some(quitting as LRESULT)
+            }
             WM_CLOSE => self
                 .with_wnd_state(|s| s.handler.request_close())
                 .map(|_| 0),
@@ -1192,6 +1739,10 @@ impl WndProc for MyWndProc {
                 Some(0)
             }
             WM_DESTROY => {
+                unsafe {
+                    RemoveClipboardFormatListener(hwnd);
+                    RevokeDragDrop(hwnd);
+                }
                 self.with_wnd_state(|s| s.handler.destroy());
                 Some(0)
             }
@@ -1220,6 +1771,25 @@ impl WndProc for MyWndProc {
                 });
                 Some(0)
             }
+            DS_DROP => {
+                let message = unsafe { &mut *(lparam as *mut DropMessage) };
+                self.with_wnd_state(|s| {
+                    let event = DropEvent {
+                        pos: message.pos.to_dp(self.scale()),
+                        data: message.data.clone(),
+                        mods: message.mods,
+                    };
+                    message.accepted = match message.phase {
+                        DropPhase::Over => s.handler.drag_over(&event),
+                        DropPhase::Leave => {
+                            s.handler.drag_leave();
+                            false
+                        }
+                        DropPhase::Drop => s.handler.drag_drop(&event),
+                    };
+                });
+                Some(0)
+            }
             DS_RUN_IDLE => self
                 .with_wnd_state(|s| {
                     let queue = self.handle.borrow().take_idle_queue();
@@ -1307,7 +1877,10 @@ impl WindowBuilder {
 
     pub fn set_level(&mut self, level: WindowLevel) {
         match level {
-            WindowLevel::AppWindow | WindowLevel::Tooltip => self.level = Some(level),
+            WindowLevel::AppWindow
+            | WindowLevel::Tooltip
+            | WindowLevel::Floating
+            | WindowLevel::Utility => self.level = Some(level),
             _ => {
                 warn!("WindowBuilder::set_level({:?}) is currently unimplemented for Windows backend.", level);
             }
@@ -1317,13 +1890,13 @@ impl WindowBuilder {
     pub fn build(self) -> Result<WindowHandle, Error> {
         unsafe {
             let class_name = super::util::CLASS_NAME.to_wide();
-            let dwrite_factory = DwriteFactory::new().unwrap();
+            let dwrite_factory = DwriteFactory::new().map_err(|_| Error::Direct2D)?;
             let fonts = self.app.fonts.clone();
             let text = PietText::new_with_shared_fonts(dwrite_factory, Some(fonts));
             let wndproc = MyWndProc {
                 app: self.app.clone(),
                 handle: Default::default(),
-                d2d_factory: D2DFactory::new().unwrap(),
+                d2d_factory: D2DFactory::new().map_err(|_| Error::Direct2D)?,
                 text: text.clone(),
                 state: RefCell::new(None),
                 present_strategy: self.present_strategy,
@@ -1372,6 +1945,13 @@ impl WindowBuilder {
                         dwStyle = WS_OVERLAPPED;
                         dwExStyle = WS_EX_TOPMOST;
                     }
+                    WindowLevel::Floating => {
+                        dwExStyle = WS_EX_TOPMOST;
+                    }
+                    WindowLevel::Utility => {
+                        dwExStyle = WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE;
+                        focusable = false;
+                    }
                 }
             }
 
@@ -1389,8 +1969,11 @@ impl WindowBuilder {
                 is_resizable: Cell::new(self.resizable),
                 is_transparent: Cell::new(self.transparent),
                 handle_titlebar: Cell::new(false),
+                maximize_button: Cell::new(None),
+                fullscreen: Cell::new(None),
                 active_text_input: Cell::new(None),
-                is_focusable: focusable,
+                text_rendering: Cell::new(TextRenderingOptions::default()),
+                is_focusable: Cell::new(focusable),
             };
             let win = Rc::new(window);
             let handle = WindowHandle {
@@ -1410,6 +1993,9 @@ impl WindowBuilder {
                 last_click_time: Instant::now(),
                 last_click_pos: (0, 0),
                 click_count: 0,
+                text_params: None,
+                monitor: null_mut(),
+                handled_touches: HashSet::new(),
             };
             win.wndproc.connect(&handle, state);
 
@@ -1701,19 +2287,20 @@ unsafe fn create_window(
 
 impl Cursor {
     fn get_hcursor(&self) -> HCURSOR {
-        #[allow(deprecated)]
         let name = match self {
             Cursor::Arrow => IDC_ARROW,
             Cursor::IBeam => IDC_IBEAM,
-            Cursor::Pointer => IDC_HAND,
-            Cursor::Crosshair => IDC_CROSS,
-            Cursor::OpenHand => {
-                warn!("Cursor::OpenHand not available on windows");
-                IDC_ARROW
-            }
+            Cursor::Pointer | Cursor::OpenHand => IDC_HAND,
+            Cursor::Crosshair | Cursor::ZoomIn | Cursor::ZoomOut => IDC_CROSS,
+            Cursor::ClosedHand | Cursor::Move => IDC_SIZEALL,
             Cursor::NotAllowed => IDC_NO,
             Cursor::ResizeLeftRight => IDC_SIZEWE,
             Cursor::ResizeUpDown => IDC_SIZENS,
+            Cursor::ResizeNwSe => IDC_SIZENWSE,
+            Cursor::ResizeNeSw => IDC_SIZENESW,
+            Cursor::Wait => IDC_WAIT,
+            Cursor::Progress => IDC_APPSTARTING,
+            Cursor::Help => IDC_HELP,
             Cursor::Custom(c) => {
                 return (c.0).0;
             }
@@ -1726,7 +2313,7 @@ impl WindowHandle {
     pub fn show(&self) {
         if let Some(w) = self.state.upgrade() {
             let hwnd = w.hwnd.get();
-            let show = if w.is_focusable {
+            let show = if w.is_focusable.get() {
                 match self.get_window_state() {
                     window::WindowState::Maximized => SW_MAXIMIZE,
                     window::WindowState::Minimized => SW_MINIMIZE,
@@ -1824,8 +2411,23 @@ impl WindowHandle {
         self.defer(DeferredOp::SetPosition(position));
     }
 
-    pub fn set_level(&self, _level: WindowLevel) {
-        warn!("Window level unimplemented for Windows!");
+    pub fn set_text_rendering(&self, options: TextRenderingOptions) {
+        if let Some(w) = self.state.upgrade() {
+            w.text_rendering.set(options);
+        }
+        self.invalidate();
+    }
+
+    pub fn set_level(&self, level: WindowLevel) {
+        match level {
+            WindowLevel::AppWindow | WindowLevel::Floating | WindowLevel::Utility => {
+                self.defer(DeferredOp::SetLevel(level))
+            }
+            _ => warn!(
+                "WindowHandle::set_level({:?}) is currently unimplemented for Windows backend.",
+                level
+            ),
+        }
     }
 
     // Gets the position of the window in virtual screen coordinates
@@ -1851,6 +2453,55 @@ impl WindowHandle {
         Point::new(0.0, 0.0)
     }
 
+    pub fn get_restored_rect(&self) -> Rect {
+        if let Some(w) = self.state.upgrade() {
+            let hwnd = w.hwnd.get();
+            unsafe {
+                // A fullscreen window was restored before it went fullscreen, and our own
+                // placement is what it goes back to.
+                let placement = match w.fullscreen.get() {
+                    Some(restore) => restore.placement,
+                    None => {
+                        let mut placement: WINDOWPLACEMENT = mem::zeroed();
+                        placement.length = mem::size_of::<WINDOWPLACEMENT>() as u32;
+                        if GetWindowPlacement(hwnd, &mut placement) == 0 {
+                            warn!(
+                                "failed to get window placement: {}",
+                                Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                            );
+                            return Rect::from_origin_size(self.get_position(), self.get_size());
+                        }
+                        placement
+                    }
+                };
+                let r = placement.rcNormalPosition;
+                let rect = Rect::new(r.left as f64, r.top as f64, r.right as f64, r.bottom as f64);
+                // The normal position of a window that isn't a tool window is in workspace
+                // coordinates, which are relative to the work area of its monitor.
+                let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32;
+                if ex_style & WS_EX_TOOLWINDOW != 0 {
+                    return rect;
+                }
+                let hmonitor = MonitorFromRect(&r, MONITOR_DEFAULTTONEAREST);
+                let mut info: MONITORINFO = mem::zeroed();
+                info.cbSize = mem::size_of::<MONITORINFO>() as u32;
+                if GetMonitorInfoW(hmonitor, &mut info) == 0 {
+                    warn!(
+                        "failed to get monitor info: {}",
+                        Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                    );
+                    return rect;
+                }
+                let offset = Vec2::new(
+                    (info.rcWork.left - info.rcMonitor.left) as f64,
+                    (info.rcWork.top - info.rcMonitor.top) as f64,
+                );
+                return rect + offset;
+            }
+        }
+        Rect::ZERO
+    }
+
     pub fn content_insets(&self) -> Insets {
         if let Some(w) = self.state.upgrade() {
             let hwnd = w.hwnd.get();
@@ -1920,6 +2571,30 @@ impl WindowHandle {
         self.defer(DeferredOp::SetWindowState(state));
     }
 
+    pub fn set_fullscreen(&self, fullscreen: bool, monitor: Option<&Monitor>) {
+        let rect = monitor.map(|monitor| monitor.virtual_rect());
+        self.defer(DeferredOp::SetFullscreen(fullscreen, rect));
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.state
+            .upgrade()
+            .map(|w| {
+                // A change that is still queued is the state the window is about to have.
+                let pending = w
+                    .deferred_queue
+                    .borrow()
+                    .iter()
+                    .rev()
+                    .find_map(|op| match op {
+                        DeferredOp::SetFullscreen(fullscreen, _) => Some(*fullscreen),
+                        _ => None,
+                    });
+                pending.unwrap_or_else(|| w.fullscreen.get().is_some())
+            })
+            .unwrap_or(false)
+    }
+
     // Gets the window state.
     pub fn get_window_state(&self) -> window::WindowState {
         // We can not store state internally because it could be modified externally.
@@ -1953,6 +2628,84 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_maximize_button(&self, rect: Option<Rect>) {
+        if let Some(w) = self.state.upgrade() {
+            w.maximize_button.set(rect);
+        }
+    }
+
+    pub fn begin_move_drag(&self) {
+        self.begin_nc_drag(HTCAPTION);
+    }
+
+    pub fn begin_resize_drag(&self, edge: ResizeEdge) {
+        let hit = match edge {
+            ResizeEdge::Top => HTTOP,
+            ResizeEdge::Bottom => HTBOTTOM,
+            ResizeEdge::Left => HTLEFT,
+            ResizeEdge::Right => HTRIGHT,
+            ResizeEdge::TopLeft => HTTOPLEFT,
+            ResizeEdge::TopRight => HTTOPRIGHT,
+            ResizeEdge::BottomLeft => HTBOTTOMLEFT,
+            ResizeEdge::BottomRight => HTBOTTOMRIGHT,
+        };
+        self.begin_nc_drag(hit);
+    }
+
+    /// Make the system act as if the left button was pressed on the non-client
+    /// area `hit`, which starts its modal move or resize loop.
+    ///
+    /// The message is posted rather than sent, so the loop starts after the
+    /// handler is done with the current mouse event.
+    fn begin_nc_drag(&self, hit: LRESULT) {
+        if let Some(hwnd) = self.get_hwnd() {
+            unsafe {
+                let mut pos = POINT { x: 0, y: 0 };
+                GetCursorPos(&mut pos);
+                ReleaseCapture();
+                let lparam = MAKELONG(pos.x as u16, pos.y as u16) as LPARAM;
+                PostMessageW(hwnd, WM_NCLBUTTONDOWN, hit as WPARAM, lparam);
+            }
+        }
+    }
+
+    pub fn set_dark_titlebar(&self, dark: bool) {
+        let value: BOOL = dark.into();
+        self.set_dwm_attribute(DWMWA_USE_IMMERSIVE_DARK_MODE, &value);
+    }
+
+    pub fn set_corners(&self, corners: WindowCorners) {
+        // The values of DWM_WINDOW_CORNER_PREFERENCE.
+        let value: DWORD = match corners {
+            WindowCorners::Default => 0,
+            WindowCorners::Square => 1,
+            WindowCorners::Round => 2,
+            WindowCorners::RoundSmall => 3,
+        };
+        self.set_dwm_attribute(DWMWA_WINDOW_CORNER_PREFERENCE, &value);
+    }
+
+    fn set_dwm_attribute<V>(&self, attribute: DWORD, value: &V) {
+        if let Some(w) = self.state.upgrade() {
+            let hwnd = w.hwnd.get();
+            unsafe {
+                let hr = DwmSetWindowAttribute(
+                    hwnd,
+                    attribute,
+                    value as *const V as LPCVOID,
+                    mem::size_of::<V>() as DWORD,
+                );
+                if FAILED(hr) {
+                    debug!(
+                        "failed to set DWM window attribute {}: {}",
+                        attribute,
+                        Error::Hr(hr)
+                    );
+                }
+            }
+        }
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         let accels = menu.accels();
         let hmenu = menu.into_hmenu();
@@ -1997,10 +2750,16 @@ impl WindowHandle {
         if let Some(state) = self.state.upgrade() {
             state.active_text_input.set(active_field);
         }
+        self.defer(DeferredOp::UpdateInputScope);
     }
 
-    pub fn update_text_field(&self, _token: TextFieldToken, _update: Event) {
-        // noop until we get a real text input implementation
+    pub fn update_text_field(&self, token: TextFieldToken, update: Event) {
+        // We don't have a real text input implementation yet, but the touch
+        // keyboard still wants to know what kind of text the field expects.
+        let active = self.state.upgrade().and_then(|s| s.active_text_input.get());
+        if update == Event::Reset && active == Some(token) {
+            self.defer(DeferredOp::UpdateInputScope);
+        }
     }
 
     /// Request a timer event.
@@ -2113,7 +2872,10 @@ impl WindowHandle {
     /// Check whether the window can receive keyboard focus. This is generally true,
     /// except for special windows like tooltips.
     pub fn is_focusable(&self) -> bool {
-        self.state.upgrade().map(|w| w.is_focusable).unwrap_or(true)
+        self.state
+            .upgrade()
+            .map(|w| w.is_focusable.get())
+            .unwrap_or(true)
     }
 
     /// Get a handle that can be used to schedule an idle task.
@@ -2133,15 +2895,20 @@ impl WindowHandle {
     }
 
     /// Get the `Scale` of the window.
-    pub fn get_scale(&self) -> Result<Scale, ShellError> {
+    pub fn get_scale(&self) -> Result<Scale, crate::Error> {
         Ok(self
             .state
             .upgrade()
-            .ok_or(ShellError::WindowDropped)?
+            .ok_or(crate::Error::WindowDropped)?
             .scale
             .get())
     }
 
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        let hwnd = self.get_hwnd().filter(|hwnd| !hwnd.is_null())?;
+        unsafe { screen::get_monitor(MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST)) }
+    }
+
     /// Allocate a timer slot.
     ///
     /// Returns an id and an elapsed time in ms
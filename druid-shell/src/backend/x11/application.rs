@@ -17,27 +17,47 @@
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
 use std::convert::{TryFrom, TryInto};
-use std::os::unix::io::RawFd;
-use std::rc::Rc;
+use std::fs::File;
+use std::io::Write;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::path::Path;
+use std::rc::{Rc, Weak};
+use std::sync::mpsc;
+use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Error};
 use x11rb::connection::{Connection, RequestConnection};
+use x11rb::errors::ReplyError;
 use x11rb::protocol::present::ConnectionExt as _;
+use x11rb::protocol::randr::{self, ConnectionExt as _};
 use x11rb::protocol::render::{self, ConnectionExt as _, Pictformat};
-use x11rb::protocol::xfixes::ConnectionExt as _;
+use x11rb::protocol::xfixes::{ConnectionExt as _, SelectionEventMask};
+use x11rb::protocol::xkb::{self, ConnectionExt as _};
 use x11rb::protocol::xproto::{
-    self, ConnectionExt, CreateWindowAux, EventMask, Timestamp, Visualtype, WindowClass,
+    self, ChangeWindowAttributesAux, ConnectionExt, CreateWindowAux, EventMask, GrabMode,
+    KeyPressEvent, Keycode, ModMask, Timestamp, Visualtype, WindowClass,
 };
-use x11rb::protocol::Event;
+use x11rb::protocol::{ErrorKind, Event};
 use x11rb::resource_manager::Database as ResourceDb;
 use x11rb::xcb_ffi::XCBConnection;
 
+use crate::appearance::SystemTheme;
 use crate::application::AppHandler;
+use crate::backend::shared::Notifications;
+use crate::hotkey::{GlobalHotKeyToken, HotKey};
+use crate::keyboard::Modifiers;
+use crate::notification::{DesktopNotification, NotificationToken};
+use crate::scale::Scale;
+use crate::screen::Monitor;
 
 use super::clipboard::Clipboard;
+use super::keycodes;
+use super::screen;
+use super::tray::Tray;
 use super::util;
 use super::window::Window;
+use super::xsettings;
 
 // This creates a `struct WindowAtoms` containing the specified atoms as members (along with some
 // convenience methods to intern and query those atoms). We use the following atoms:
@@ -90,6 +110,59 @@ use super::window::Window;
 // INCR
 //
 // Type used for incremental selection transfers
+//
+// _NET_WM_STATE
+//
+// The state of a window, which clients change by sending messages to the root window.
+//
+// https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm45805407959456
+//
+// _NET_WM_STATE_FULLSCREEN
+//
+// The state of fullscreen windows.
+//
+// _NET_WM_STATE_ABOVE
+//
+// The state of windows that stay above other windows.
+//
+// _NET_WM_STATE_MAXIMIZED_VERT, _NET_WM_STATE_MAXIMIZED_HORZ
+//
+// The states of windows that are maximized vertically and horizontally.
+//
+// _NET_WM_STATE_HIDDEN
+//
+// The state of minimized windows.
+//
+// MANAGER
+//
+// The type of the message announcing that a client took over a manager selection, such as the
+// one of the system tray.
+//
+// https://www.x.org/releases/X11R7.6/doc/xorg-docs/specs/ICCCM/icccm.html#manager_selections
+//
+// _NET_SYSTEM_TRAY_OPCODE
+//
+// The type of the messages sent to the system tray, such as the request to dock an icon.
+//
+// https://specifications.freedesktop.org/systemtray-spec/systemtray-spec-0.3.html#messages
+//
+// _NET_SYSTEM_TRAY_VISUAL
+//
+// The visual that the system tray wants icons to use.
+//
+// https://specifications.freedesktop.org/systemtray-spec/systemtray-spec-0.3.html#visuals
+//
+// _XEMBED_INFO
+//
+// The XEmbed version and flags of a window that is embedded into another, like tray icons.
+//
+// https://specifications.freedesktop.org/xembed-spec/xembed-spec-latest.html#lifecycle
+//
+// _XSETTINGS_SETTINGS
+//
+// The property of the XSETTINGS manager's window that holds the settings, like the theme name.
+//
+// https://specifications.freedesktop.org/xsettings-spec/xsettings-latest.html
 x11rb::atom_manager! {
     pub(crate) AppAtoms: AppAtomsCookie {
         WM_PROTOCOLS,
@@ -102,10 +175,23 @@ x11rb::atom_manager! {
         _NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
         _NET_WM_WINDOW_TYPE_TOOLTIP,
         _NET_WM_WINDOW_TYPE_DIALOG,
+        _NET_WM_WINDOW_TYPE_UTILITY,
         CLIPBOARD,
         PRIMARY,
         TARGETS,
         INCR,
+        _NET_WM_STATE,
+        _NET_WM_STATE_FULLSCREEN,
+        _NET_WM_STATE_ABOVE,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_MOVERESIZE,
+        MANAGER,
+        _NET_SYSTEM_TRAY_OPCODE,
+        _NET_SYSTEM_TRAY_VISUAL,
+        _XEMBED_INFO,
+        _XSETTINGS_SETTINGS,
     }
 }
 
@@ -175,6 +261,12 @@ pub(crate) struct Application {
     render_argb32_pictformat_cursor: Option<Pictformat>,
     /// Newest timestamp that we received
     timestamp: Rc<Cell<Timestamp>>,
+    /// Whether RandR tells us when the monitors change.
+    tracks_monitors: bool,
+    /// The `_XSETTINGS_S{screen_num}` selection, whose owner publishes the XSETTINGS.
+    xsettings_selection: xproto::Atom,
+    /// The window of the XSETTINGS manager, or `NONE` if there isn't one.
+    xsettings_owner: Rc<Cell<xproto::Window>>,
 }
 
 /// The mutable `Application` state.
@@ -183,6 +275,18 @@ struct State {
     quitting: bool,
     /// A collection of all the `Application` windows.
     windows: HashMap<u32, Rc<Window>>,
+    /// The tray icons, which are owned by their `TrayIcon`s.
+    trays: Vec<Weak<Tray>>,
+    /// The application's handler, while the run loop is running.
+    handler: Option<Box<dyn AppHandler>>,
+    /// The keycodes and modifier masks of the grabbed global hotkeys.
+    hotkeys: HashMap<GlobalHotKeyToken, (Keycode, u16)>,
+    /// The keycode of the global hotkey being held down, to ignore its repeats.
+    hotkey_down: Option<Keycode>,
+    /// The monitors, if we know them and RandR tells us when they change.
+    monitors: Option<Vec<Monitor>>,
+    /// The connection to the notification server, once we have shown a notification.
+    notifications: Option<Notifications>,
 }
 
 #[derive(Clone, Debug)]
@@ -194,6 +298,16 @@ pub(crate) struct Cursors {
     pub not_allowed: Option<xproto::Cursor>,
     pub row_resize: Option<xproto::Cursor>,
     pub col_resize: Option<xproto::Cursor>,
+    pub grab: Option<xproto::Cursor>,
+    pub grabbing: Option<xproto::Cursor>,
+    pub nwse_resize: Option<xproto::Cursor>,
+    pub nesw_resize: Option<xproto::Cursor>,
+    pub move_: Option<xproto::Cursor>,
+    pub zoom_in: Option<xproto::Cursor>,
+    pub zoom_out: Option<xproto::Cursor>,
+    pub wait: Option<xproto::Cursor>,
+    pub progress: Option<xproto::Cursor>,
+    pub help: Option<xproto::Cursor>,
 }
 
 impl Application {
@@ -213,6 +327,12 @@ impl Application {
         let state = Rc::new(RefCell::new(State {
             quitting: false,
             windows: HashMap::new(),
+            trays: Vec::new(),
+            handler: None,
+            hotkeys: HashMap::new(),
+            hotkey_down: None,
+            monitors: None,
+            notifications: None,
         }));
 
         let (idle_read, idle_write) = nix::unistd::pipe2(nix::fcntl::OFlag::O_NONBLOCK)?;
@@ -276,6 +396,16 @@ impl Application {
             not_allowed: load_cursor("not-allowed"),
             row_resize: load_cursor("row-resize"),
             col_resize: load_cursor("col-resize"),
+            grab: load_cursor("grab"),
+            grabbing: load_cursor("grabbing"),
+            nwse_resize: load_cursor("nwse-resize"),
+            nesw_resize: load_cursor("nesw-resize"),
+            move_: load_cursor("move"),
+            zoom_in: load_cursor("zoom-in"),
+            zoom_out: load_cursor("zoom-out"),
+            wait: load_cursor("wait"),
+            progress: load_cursor("progress"),
+            help: load_cursor("help"),
         };
 
         let atoms = Rc::new(
@@ -293,6 +423,30 @@ impl Application {
             .ok_or_else(|| anyhow!("Couldn't get visual from screen"))?;
         let argb_visual_type = util::get_argb_visual_type(&*connection, screen)?;
 
+        if let Err(e) = Application::select_clipboard_changes(&connection, window_id, &atoms) {
+            tracing::info!("not listening for clipboard changes: {}", e);
+        }
+        let xsettings_selection = connection
+            .intern_atom(false, format!("_XSETTINGS_S{}", screen_num).as_bytes())?
+            .reply()
+            .context("intern _XSETTINGS_S")?
+            .atom;
+        if let Err(e) =
+            Application::select_xsettings_changes(&connection, window_id, xsettings_selection)
+        {
+            tracing::info!("not listening for a new XSETTINGS manager: {}", e);
+        }
+        if let Err(e) = Application::enable_detectable_autorepeat(&connection) {
+            tracing::info!("key repeats will look like separate presses: {}", e);
+        }
+        let tracks_monitors = match Application::select_monitor_changes(&connection, screen.root) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::info!("not listening for monitor changes: {}", e);
+                false
+            }
+        };
+
         let timestamp = Rc::new(Cell::new(x11rb::CURRENT_TIME));
         let pending_events = Default::default();
         let clipboard = Clipboard::new(
@@ -312,7 +466,7 @@ impl Application {
             Rc::clone(&timestamp),
         );
 
-        Ok(Application {
+        let app = Application {
             connection,
             rdb,
             screen_num,
@@ -331,7 +485,150 @@ impl Application {
             marker: std::marker::PhantomData,
             render_argb32_pictformat_cursor,
             timestamp,
-        })
+            tracks_monitors,
+            xsettings_selection,
+            xsettings_owner: Rc::new(Cell::new(x11rb::NONE)),
+        };
+        app.watch_xsettings();
+        Ok(app)
+    }
+
+    // Ask RandR to tell us when monitors are connected, disconnected, or rearranged.
+    fn select_monitor_changes(conn: &Rc<XCBConnection>, root: xproto::Window) -> Result<(), Error> {
+        if conn
+            .extension_information(randr::X11_EXTENSION_NAME)?
+            .is_none()
+        {
+            return Err(anyhow!("RandR is not supported"));
+        }
+        conn.randr_query_version(1, 2)?
+            .reply()
+            .context("query RandR version")?;
+        conn.randr_select_input(root, randr::NotifyMask::SCREEN_CHANGE)?
+            .check()
+            .context("select RandR input")?;
+        Ok(())
+    }
+
+    // Ask for XFIXES selection notifications when the owner of the clipboard changes, which
+    // happens whenever something new is copied.
+    fn select_clipboard_changes(
+        conn: &Rc<XCBConnection>,
+        window_id: u32,
+        atoms: &AppAtoms,
+    ) -> Result<(), Error> {
+        // As with regions, XFIXES needs version negotiation before it can be used.
+        conn.xfixes_query_version(5, 0)?
+            .reply()
+            .context("query XFIXES version")?;
+        conn.xfixes_select_selection_input(
+            window_id,
+            atoms.CLIPBOARD,
+            SelectionEventMask::SET_SELECTION_OWNER
+                | SelectionEventMask::SELECTION_WINDOW_DESTROY
+                | SelectionEventMask::SELECTION_CLIENT_CLOSE,
+        )?
+        .check()
+        .context("select XFIXES selection input")?;
+        Ok(())
+    }
+
+    // Ask for XFIXES selection notifications when another XSETTINGS manager takes over. This
+    // relies on `select_clipboard_changes` having negotiated the XFIXES version.
+    fn select_xsettings_changes(
+        conn: &Rc<XCBConnection>,
+        window_id: u32,
+        selection: xproto::Atom,
+    ) -> Result<(), Error> {
+        conn.xfixes_select_selection_input(
+            window_id,
+            selection,
+            SelectionEventMask::SET_SELECTION_OWNER
+                | SelectionEventMask::SELECTION_WINDOW_DESTROY
+                | SelectionEventMask::SELECTION_CLIENT_CLOSE,
+        )?
+        .check()
+        .context("select XFIXES selection input")?;
+        Ok(())
+    }
+
+    /// Find the XSETTINGS manager and ask it to tell us when the settings change.
+    fn watch_xsettings(&self) {
+        let owner = self
+            .connection
+            .get_selection_owner(self.xsettings_selection)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map_or(x11rb::NONE, |reply| reply.owner);
+        self.xsettings_owner.set(owner);
+        if owner == x11rb::NONE {
+            tracing::debug!("no XSETTINGS manager is running");
+            return;
+        }
+        let attributes = ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE);
+        let selected = self
+            .connection
+            .change_window_attributes(owner, &attributes)
+            .map_err(Error::from)
+            .and_then(|cookie| cookie.check().map_err(Error::from));
+        if let Err(e) = selected {
+            // The manager may have just gone away, and then we'll hear about its successor.
+            tracing::info!("not listening for XSETTINGS changes: {}", e);
+        }
+    }
+
+    /// The system theme, as far as the XSETTINGS theme name tells.
+    fn read_system_theme(&self) -> SystemTheme {
+        let owner = self.xsettings_owner.get();
+        if owner == x11rb::NONE {
+            return SystemTheme::default();
+        }
+        let settings = self
+            .connection
+            .get_property(
+                false,
+                owner,
+                self.atoms._XSETTINGS_SETTINGS,
+                self.atoms._XSETTINGS_SETTINGS,
+                0,
+                u32::MAX,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+        let theme_name =
+            settings.and_then(|reply| xsettings::string(&reply.value, "Net/ThemeName"));
+        // There are no accent colors, and themes are only dark by convention.
+        SystemTheme {
+            dark: theme_name.map_or(false, |name| name.to_lowercase().ends_with("dark")),
+            accent_color: None,
+        }
+    }
+
+    fn system_theme_changed(&self) -> Result<(), Error> {
+        let windows: Vec<_> = borrow!(self.state)?.windows.values().cloned().collect();
+        for w in windows {
+            w.handle_system_theme_changed();
+        }
+        Ok(())
+    }
+
+    // Ask XKB to not send a key release before each repeated key press, so that repeats can be
+    // told apart from new presses.
+    fn enable_detectable_autorepeat(conn: &Rc<XCBConnection>) -> Result<(), Error> {
+        conn.xkb_use_extension(1, 0)?
+            .reply()
+            .context("query XKB version")?;
+        conn.xkb_per_client_flags(
+            xkb::ID::USE_CORE_KBD.into(),
+            xkb::PerClientFlag::DETECTABLE_AUTO_REPEAT,
+            xkb::PerClientFlag::DETECTABLE_AUTO_REPEAT,
+            0u32,
+            0u32,
+            0u32,
+        )?
+        .reply()
+        .context("enable detectable autorepeat")?;
+        Ok(())
     }
 
     // Check if the Present extension is supported, returning its opcode if it is.
@@ -446,16 +743,126 @@ impl Application {
             .ok_or_else(|| anyhow!("No window with id {}", id))
     }
 
+    pub(crate) fn add_tray(&self, tray: &Rc<Tray>) -> Result<(), Error> {
+        let mut state = borrow_mut!(self.state)?;
+        state.trays.retain(|tray| tray.strong_count() > 0);
+        if state.trays.is_empty() {
+            // Trays announce themselves with a MANAGER message to the root window.
+            self.connection
+                .change_window_attributes(
+                    self.root_window(),
+                    &ChangeWindowAttributesAux::new().event_mask(EventMask::STRUCTURE_NOTIFY),
+                )?
+                .check()
+                .context("select root window events")?;
+        }
+        state.trays.push(Rc::downgrade(tray));
+        Ok(())
+    }
+
+    fn trays(&self) -> Result<Vec<Rc<Tray>>, Error> {
+        Ok(borrow!(self.state)?
+            .trays
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect())
+    }
+
+    fn tray(&self, window: u32) -> Result<Option<Rc<Tray>>, Error> {
+        Ok(self
+            .trays()?
+            .into_iter()
+            .find(|tray| tray.window() == Some(window)))
+    }
+
+    /// Give events about tray icons to their `Tray`, returning `Ok(true)` if the event was
+    /// handled.
+    fn handle_tray_event(&self, ev: &Event) -> Result<bool, Error> {
+        match ev {
+            Event::ClientMessage(ev) if ev.type_ == self.atoms.MANAGER => {
+                // A new tray started; the previous one, if any, took our icons with it.
+                for tray in self.trays()? {
+                    tray.dock().context("MANAGER - failed to dock tray icon")?;
+                }
+                Ok(true)
+            }
+            Event::Expose(ev) => match self.tray(ev.window)? {
+                Some(tray) => {
+                    tray.handle_expose(ev)
+                        .context("EXPOSE - failed to handle tray icon")?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+            Event::ButtonPress(ev) => Ok(self.tray(ev.event)?.is_some()),
+            Event::ButtonRelease(ev) => match self.tray(ev.event)? {
+                Some(tray) => {
+                    tray.handle_button_release(ev);
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+            Event::ConfigureNotify(ev) => match self.tray(ev.window)? {
+                Some(tray) => {
+                    tray.handle_configure_notify(ev)
+                        .context("CONFIGURE_NOTIFY - failed to handle tray icon")?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+            Event::DestroyNotify(ev) => match self.tray(ev.window)? {
+                Some(tray) => {
+                    tray.handle_destroy_notify();
+                    Ok(true)
+                }
+                None => Ok(false),
+            },
+            _ => Ok(false),
+        }
+    }
+
     #[inline]
     pub(crate) fn connection(&self) -> &Rc<XCBConnection> {
         &self.connection
     }
 
+    #[inline]
+    pub(crate) fn root_window(&self) -> xproto::Window {
+        self.connection.setup().roots[self.screen_num].root
+    }
+
     #[inline]
     pub(crate) fn screen_num(&self) -> usize {
         self.screen_num
     }
 
+    /// The scale that windows are drawn at.
+    pub(crate) fn scale(&self) -> Scale {
+        util::scale(&self.rdb)
+    }
+
+    /// The monitors of our screen.
+    ///
+    /// These are remembered until RandR tells us that they changed.
+    pub(crate) fn monitors(&self) -> Vec<Monitor> {
+        if let Some(monitors) = self
+            .state
+            .try_borrow()
+            .ok()
+            .and_then(|s| s.monitors.clone())
+        {
+            return monitors;
+        }
+        let monitors =
+            screen::query_monitors(self.connection.as_ref(), self.screen_num, self.scale());
+        if self.tracks_monitors {
+            if let Ok(mut state) = self.state.try_borrow_mut() {
+                state.monitors = Some(monitors.clone());
+            }
+        }
+        monitors
+    }
+
     #[inline]
     pub(crate) fn argb_visual_type(&self) -> Option<Visualtype> {
         // Check if a composite manager is running
@@ -505,6 +912,9 @@ impl Application {
             };
             self.timestamp.set(timestamp);
         }
+        if self.handle_tray_event(ev)? {
+            return Ok(false);
+        }
         match ev {
             // NOTE: When adding handling for any of the following events,
             //       there must be a check against self.window_id
@@ -519,12 +929,24 @@ impl Application {
                     .context("EXPOSE - failed to get window")?;
                 w.handle_expose(ev).context("EXPOSE - failed to handle")?;
             }
+            Event::KeyPress(ev) if ev.event == self.root_window() => {
+                self.handle_hotkey_press(ev)?;
+            }
+            Event::KeyRelease(ev) if ev.event == self.root_window() => {
+                borrow_mut!(self.state)?.hotkey_down = None;
+            }
             Event::KeyPress(ev) => {
                 let w = self
                     .window(ev.event)
                     .context("KEY_PRESS - failed to get window")?;
                 w.handle_key_press(ev);
             }
+            Event::KeyRelease(ev) => {
+                let w = self
+                    .window(ev.event)
+                    .context("KEY_RELEASE - failed to get window")?;
+                w.handle_key_release(ev);
+            }
             Event::ButtonPress(ev) => {
                 let w = self
                     .window(ev.event)
@@ -584,7 +1006,7 @@ impl Application {
                 }
             }
             Event::ConfigureNotify(ev) => {
-                if ev.window != self.window_id {
+                if ev.window != self.window_id && ev.window != self.root_window() {
                     let w = self
                         .window(ev.window)
                         .context("CONFIGURE_NOTIFY - failed to get window")?;
@@ -629,6 +1051,31 @@ impl Application {
                 self.primary
                     .handle_property_notify(*ev)
                     .context("PROPERTY_NOTIFY event handling for primary")?;
+                if ev.window == self.xsettings_owner.get()
+                    && ev.atom == self.atoms._XSETTINGS_SETTINGS
+                {
+                    self.system_theme_changed()?;
+                }
+            }
+            Event::RandrScreenChangeNotify(_) => {
+                let windows: Vec<_> = {
+                    let mut state = borrow_mut!(self.state)?;
+                    state.monitors = None;
+                    state.windows.values().cloned().collect()
+                };
+                for w in windows {
+                    w.handle_monitors_changed();
+                }
+            }
+            Event::XfixesSelectionNotify(ev) if ev.selection == self.xsettings_selection => {
+                self.watch_xsettings();
+                self.system_theme_changed()?;
+            }
+            Event::XfixesSelectionNotify(ev) if ev.selection == self.atoms.CLIPBOARD => {
+                let windows: Vec<_> = borrow!(self.state)?.windows.values().cloned().collect();
+                for w in windows {
+                    w.handle_clipboard_changed();
+                }
             }
             Event::Error(e) => {
                 // TODO: if an error is caused by the present extension, disable it and fall back
@@ -717,6 +1164,7 @@ impl Application {
             if now >= next_idle_time {
                 last_idle_time = now;
                 drain_idle_pipe(self.idle_read)?;
+                self.run_notification_activations();
 
                 if let Ok(state) = self.state.try_borrow() {
                     for w in state.windows.values() {
@@ -729,10 +1177,33 @@ impl Application {
         }
     }
 
-    pub fn run(self, _handler: Option<Box<dyn AppHandler>>) {
-        if let Err(e) = self.run_inner() {
+    pub fn run(self, handler: Option<Box<dyn AppHandler>>) {
+        if let Ok(mut state) = self.state.try_borrow_mut() {
+            state.handler = handler;
+        }
+        if let Err(e) = self.clone().run_inner() {
             tracing::error!("{}", e);
         }
+        if let Ok(mut state) = self.state.try_borrow_mut() {
+            state.handler = None;
+        }
+    }
+
+    pub(crate) fn with_handler(&self, f: impl FnOnce(&mut dyn AppHandler)) {
+        // Take the handler out, so that it can call back into the application.
+        let handler = match self.state.try_borrow_mut() {
+            Ok(mut state) => state.handler.take(),
+            Err(_) => {
+                tracing::error!("Application state already borrowed");
+                return;
+            }
+        };
+        if let Some(mut handler) = handler {
+            f(&mut *handler);
+            if let Ok(mut state) = self.state.try_borrow_mut() {
+                state.handler = Some(handler);
+            }
+        }
     }
 
     pub fn quit(&self) {
@@ -755,6 +1226,10 @@ impl Application {
         }
     }
 
+    pub fn cancel_quit(&self) {
+        // Only macOS waits for an answer to its quit requests.
+    }
+
     fn finalize_quit(&self) {
         log_x11!(self.connection.destroy_window(self.window_id));
         if let Err(e) = nix::unistd::close(self.idle_read) {
@@ -769,6 +1244,197 @@ impl Application {
         self.clipboard.clone()
     }
 
+    pub fn register_global_hotkey(
+        &self,
+        hotkey: &HotKey,
+        token: GlobalHotKeyToken,
+    ) -> Result<(), crate::Error> {
+        let keycode =
+            keycodes::key_to_hardware_keycode(&hotkey.key).ok_or(crate::Error::Unsupported)?;
+        let mods = hotkey_mod_mask(hotkey.mods.into());
+        let root = self.root_window();
+        // Caps Lock and Num Lock count as modifiers, so the key is grabbed with all of their
+        // combinations.
+        for &locks in LOCK_MASKS.iter() {
+            let grabbed = self
+                .connection
+                .grab_key(
+                    false,
+                    root,
+                    mods | locks,
+                    keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )
+                .map_err(Error::from)?
+                .check();
+            if let Err(e) = grabbed {
+                self.ungrab_hotkey(keycode, mods);
+                return Err(match e {
+                    ReplyError::X11Error(e) if e.error_kind == ErrorKind::Access => {
+                        crate::Error::HotKeyInUse
+                    }
+                    e => Error::from(e).context("grab global hotkey").into(),
+                });
+            }
+        }
+        borrow_mut!(self.state)?
+            .hotkeys
+            .insert(token, (keycode, mods));
+        Ok(())
+    }
+
+    pub fn unregister_global_hotkey(&self, token: GlobalHotKeyToken) {
+        let hotkey = match self.state.try_borrow_mut() {
+            Ok(mut state) => state.hotkeys.remove(&token),
+            Err(_) => {
+                tracing::error!("Application state already borrowed");
+                return;
+            }
+        };
+        if let Some((keycode, mods)) = hotkey {
+            self.ungrab_hotkey(keycode, mods);
+            log_x11!(self.connection.flush());
+        }
+    }
+
+    fn ungrab_hotkey(&self, keycode: Keycode, mods: u16) {
+        for &locks in LOCK_MASKS.iter() {
+            log_x11!(self
+                .connection
+                .ungrab_key(keycode, self.root_window(), mods | locks));
+        }
+    }
+
+    fn handle_hotkey_press(&self, ev: &KeyPressEvent) -> Result<(), Error> {
+        let token = {
+            let mut state = borrow_mut!(self.state)?;
+            // With detectable autorepeat, a held key sends presses without releases.
+            if state.hotkey_down == Some(ev.detail) {
+                return Ok(());
+            }
+            state.hotkey_down = Some(ev.detail);
+            let mods = ev.state & hotkey_mod_mask(Modifiers::all());
+            state
+                .hotkeys
+                .iter()
+                .find(|(_, &hotkey)| hotkey == (ev.detail, mods))
+                .map(|(&token, _)| token)
+        };
+        if let Some(token) = token {
+            self.with_handler(|handler| handler.global_hotkey(token));
+        }
+        Ok(())
+    }
+
+    pub fn show_notification(
+        &self,
+        notification: &DesktopNotification,
+        token: NotificationToken,
+    ) -> Result<(), crate::Error> {
+        let mut state = self
+            .state
+            .try_borrow_mut()
+            .map_err(|_| anyhow!("application state already borrowed"))?;
+        if state.notifications.is_none() {
+            // Our own copy of the pipe, so that it stays open as long as the notifications do.
+            let wake = nix::unistd::dup(self.idle_write).context("failed to copy the idle pipe")?;
+            let wake = unsafe { File::from_raw_fd(wake) };
+            let notifications = Notifications::connect(move || match (&wake).write_all(&[0]) {
+                // A full pipe wakes up the event loop anyway.
+                Err(e) if e.kind() != std::io::ErrorKind::WouldBlock => {
+                    tracing::error!("Failed to wake up the event loop: {}", e);
+                }
+                _ => {}
+            })
+            .context("failed to watch the notification server")?;
+            state.notifications = Some(notifications);
+        }
+        state
+            .notifications
+            .as_ref()
+            .unwrap()
+            .show(notification, token)?;
+        Ok(())
+    }
+
+    /// Tell the handler about the notifications that were clicked.
+    fn run_notification_activations(&self) {
+        let activations = match self.state.try_borrow() {
+            Ok(state) => match &state.notifications {
+                Some(notifications) => notifications.take_activations(),
+                None => return,
+            },
+            Err(_) => {
+                tracing::error!("Application state already borrowed");
+                return;
+            }
+        };
+        for (token, action) in activations {
+            self.with_handler(|handler| handler.notification_activated(token, action));
+        }
+    }
+
+    pub fn open_url(&self, url: &str) -> Result<(), crate::Error> {
+        crate::backend::shared::open_uri(url)
+    }
+
+    pub fn reveal_path(&self, path: &Path) -> Result<(), crate::Error> {
+        crate::backend::shared::reveal_path(path)
+    }
+
+    pub fn show_message_dialog(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+        let (tx, rx) = mpsc::channel();
+        let (title, message) = (title.to_owned(), message.to_owned());
+        let (confirm, cancel) = (confirm.to_owned(), cancel.to_owned());
+        thread::spawn(move || {
+            let _ = tx.send(run_dialog_tool(&title, &message, &confirm, &cancel));
+        });
+        // While the user looks at the dialog, our windows still repaint, so they don't look hung.
+        let app = crate::util::is_main_thread()
+            .then(crate::Application::try_global)
+            .flatten();
+        loop {
+            match rx.recv_timeout(Duration::from_millis(16)) {
+                Ok(confirmed) => return confirmed,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return false,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if let Some(app) = &app {
+                        app.backend_app.handle_expose_events();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handle the expose events that are ready, and queue the others for the run loop.
+    fn handle_expose_events(&self) {
+        loop {
+            match self.connection.poll_for_event() {
+                Ok(Some(ev @ Event::Expose(_))) => {
+                    if let Err(e) = self.handle_event(&ev) {
+                        tracing::error!("Error handling event: {:#}", e);
+                    }
+                }
+                Ok(Some(ev)) => self.pending_events.borrow_mut().push_back(ev),
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Error polling for events: {}", e);
+                    break;
+                }
+            }
+        }
+        if let Err(e) = self.connection.flush() {
+            tracing::error!("Error flushing the X11 connection: {}", e);
+        }
+    }
+
+    pub fn system_theme() -> SystemTheme {
+        crate::Application::try_global().map_or_else(SystemTheme::default, |app| {
+            app.backend_app.read_system_theme()
+        })
+    }
+
     pub fn get_locale() -> String {
         fn locale_env_var(var: &str) -> Option<String> {
             match std::env::var(var) {
@@ -814,6 +1480,27 @@ impl Application {
     }
 }
 
+/// The combinations of the masks of Caps Lock (`ModMask::LOCK`) and Num Lock (`ModMask::M2`).
+const LOCK_MASKS: [u16; 4] = [0, 2, 16, 2 | 16];
+
+/// The X11 modifier mask of a hotkey's modifiers.
+///
+/// This uses the same modifier mapping as key events; see `window::key_mods`.
+fn hotkey_mod_mask(mods: Modifiers) -> u16 {
+    let mut mask = 0;
+    for &(modifier, mod_mask) in &[
+        (Modifiers::SHIFT, ModMask::SHIFT),
+        (Modifiers::CONTROL, ModMask::CONTROL),
+        (Modifiers::ALT, ModMask::M1),
+        (Modifiers::META, ModMask::M4),
+    ] {
+        if mods.contains(modifier) {
+            mask |= u16::from(mod_mask);
+        }
+    }
+    mask
+}
+
 impl crate::platform::linux::ApplicationExt for crate::Application {
     fn primary_clipboard(&self) -> crate::Clipboard {
         self.backend_app.primary.clone().into()
@@ -822,6 +1509,70 @@ impl crate::platform::linux::ApplicationExt for crate::Application {
 
 /// Clears out our idle pipe; `idle_read` should be the reading end of a pipe that was opened with
 /// O_NONBLOCK.
+/// Ask the dialog tool of the desktop for a yes or no, falling back to xmessage, which comes
+/// with X11. We have no widgets to build a dialog from.
+fn run_dialog_tool(title: &str, message: &str, confirm: &str, cancel: &str) -> bool {
+    // xmessage separates buttons with commas and their exit codes with colons, and has no way
+    // to escape either.
+    let xmessage_label = |label: &str| label.replace(|c| c == ':' || c == ',', " ");
+    let (xmessage_confirm, xmessage_cancel) = (xmessage_label(confirm), xmessage_label(cancel));
+    let buttons = format!("{}:0,{}:1", xmessage_confirm, xmessage_cancel);
+    let tools: [(&str, Vec<&str>); 3] = [
+        (
+            "zenity",
+            vec![
+                "--question",
+                "--title",
+                title,
+                "--text",
+                message,
+                "--ok-label",
+                confirm,
+                "--cancel-label",
+                cancel,
+            ],
+        ),
+        (
+            "kdialog",
+            vec![
+                "--title",
+                title,
+                "--yes-label",
+                confirm,
+                "--no-label",
+                cancel,
+                "--yesno",
+                message,
+            ],
+        ),
+        (
+            "xmessage",
+            vec![
+                "-title",
+                title,
+                "-center",
+                "-default",
+                &xmessage_confirm,
+                "-buttons",
+                &buttons,
+                message,
+            ],
+        ),
+    ];
+    for (tool, args) in &tools {
+        match std::process::Command::new(tool).args(args).status() {
+            Ok(status) => return status.success(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                tracing::error!("x11: failed to run {}: {}", tool, e);
+                break;
+            }
+        }
+    }
+    tracing::error!("x11: can't show message dialog; {}: {}", title, message);
+    false
+}
+
 fn drain_idle_pipe(idle_read: RawFd) -> Result<(), Error> {
     // Each write to the idle pipe adds one byte; it's unlikely that there will be much in it, but
     // read it 16 bytes at a time just in case.
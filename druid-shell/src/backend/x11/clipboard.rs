@@ -17,6 +17,7 @@
 use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::convert::TryFrom;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
@@ -33,7 +34,9 @@ use x11rb::wrapper::ConnectionExt as _;
 use x11rb::xcb_ffi::XCBConnection;
 
 use super::application::AppAtoms;
+use crate::backend::shared::{file_uri, uri_path};
 use crate::clipboard::{ClipboardFormat, FormatId};
+use crate::piet::{ImageBuf, ImageFormat};
 use tracing::{debug, error, warn};
 
 // We can pick an arbitrary atom that is used for the transfer. This is our pick.
@@ -47,6 +50,14 @@ const STRING_TARGETS: [&str; 5] = [
     "text/plain",
 ];
 
+/// The target for images, which are exchanged as PNG.
+const PNG_TARGET: &str = "image/png";
+
+/// The standard target for lists of files.
+const URI_LIST_TARGET: &str = "text/uri-list";
+/// The target that GNOME file managers use for copied files.
+const GNOME_FILES_TARGET: &str = "x-special/gnome-copied-files";
+
 #[derive(Debug, Clone)]
 pub struct Clipboard(Rc<RefCell<ClipboardState>>);
 
@@ -87,25 +98,73 @@ impl Clipboard {
         self.0.borrow_mut().handle_property_notify(event)
     }
 
-    pub fn put_string(&mut self, s: impl AsRef<str>) {
+    pub fn put_string(&mut self, s: impl AsRef<str>) -> Result<(), crate::Error> {
         let bytes = s.as_ref().as_bytes();
         let formats = STRING_TARGETS
             .iter()
             .map(|format| ClipboardFormat::new(format, bytes))
             .collect::<Vec<_>>();
-        self.put_formats(&formats);
+        self.put_formats(&formats)
     }
 
-    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) {
-        if let Err(err) = self.0.borrow_mut().put_formats(formats) {
-            error!("Error in Clipboard::put_formats: {:?}", err);
-        }
+    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) -> Result<(), crate::Error> {
+        self.0
+            .borrow_mut()
+            .put_formats(formats)
+            .map_err(|err| anyhow::Error::new(err).into())
     }
 
     pub fn get_string(&self) -> Option<String> {
         self.0.borrow().get_string()
     }
 
+    pub fn put_image(&mut self, image: &ImageBuf) -> Result<(), crate::Error> {
+        let png = encode_png(image).map_err(anyhow::Error::new)?;
+        self.put_formats(&[ClipboardFormat::new(PNG_TARGET, png)])
+    }
+
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        let png = self.get_format(PNG_TARGET)?;
+        match decode_png(&png) {
+            Ok(image) => Some(image),
+            Err(e) => {
+                warn!("Failed to decode the clipboard image: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn put_files(&mut self, paths: &[PathBuf]) -> Result<(), crate::Error> {
+        let uris = paths.iter().map(|path| file_uri(path)).collect::<Vec<_>>();
+        let uri_list = uris
+            .iter()
+            .map(|uri| format!("{}\r\n", uri))
+            .collect::<String>();
+        let gnome_files = std::iter::once("copy".to_string())
+            .chain(uris)
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.put_formats(&[
+            ClipboardFormat::new(URI_LIST_TARGET, uri_list),
+            ClipboardFormat::new(GNOME_FILES_TARGET, gnome_files),
+        ])
+    }
+
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        let uri_list = String::from_utf8(self.get_format(URI_LIST_TARGET)?).ok()?;
+        let files = uri_list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(uri_path)
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            None
+        } else {
+            Some(files)
+        }
+    }
+
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
         self.0.borrow().preferred_format(formats)
     }
@@ -707,3 +766,67 @@ fn nix_error_to_io(e: nix::Error) -> std::io::Error {
         nix::Error::UnsupportedOperation => std::io::Error::new(ErrorKind::Other, e),
     }
 }
+
+/// Encode an image as PNG, with separate alpha.
+fn encode_png(image: &ImageBuf) -> Result<Vec<u8>, image::ImageError> {
+    let mut pixels = Vec::with_capacity(image.width() * image.height() * 4);
+    for row in image.pixel_colors() {
+        for p in row {
+            let (r, g, b, a) = p.as_rgba8();
+            pixels.extend_from_slice(&[r, g, b, a]);
+        }
+    }
+    let mut png = Vec::new();
+    image::png::PngEncoder::new(&mut png).encode(
+        &pixels,
+        image.width() as u32,
+        image.height() as u32,
+        image::ColorType::Rgba8,
+    )?;
+    Ok(png)
+}
+
+/// Decode a PNG image.
+fn decode_png(png: &[u8]) -> Result<ImageBuf, image::ImageError> {
+    let decoded = image::load_from_memory_with_format(png, image::ImageFormat::Png)?.to_rgba8();
+    let (width, height) = decoded.dimensions();
+    Ok(ImageBuf::from_raw(
+        decoded.into_raw(),
+        ImageFormat::RgbaSeparate,
+        width as usize,
+        height as usize,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn png_round_trip() {
+        #[rustfmt::skip]
+        let pixels = vec![
+            255, 0, 0, 255,    0, 255, 0, 128,
+            0, 0, 255, 255,    10, 20, 30, 0,
+            1, 2, 3, 4,        200, 100, 50, 255,
+        ];
+        let image = ImageBuf::from_raw(pixels.clone(), ImageFormat::RgbaSeparate, 2, 3);
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (2, 3));
+        assert_eq!(decoded.format(), ImageFormat::RgbaSeparate);
+        assert_eq!(decoded.raw_pixels(), &pixels[..]);
+    }
+
+    #[test]
+    fn png_rgb_gets_opaque() {
+        let image = ImageBuf::from_raw(vec![1, 2, 3, 4, 5, 6], ImageFormat::Rgb, 2, 1);
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!(decoded.raw_pixels(), &[1, 2, 3, 255, 4, 5, 6, 255]);
+    }
+
+    #[test]
+    fn garbage_is_not_an_image() {
+        assert!(decode_png(b"not a png").is_err());
+    }
+}
@@ -199,3 +199,19 @@ pub fn code_to_key(code: Code, m: Modifiers) -> KbKey {
 pub fn hardware_keycode_to_code(hw_keycode: Keycode) -> Code {
     shared::hardware_keycode_to_code(hw_keycode as u16)
 }
+
+/// Find the hardware keycode that produces `key` without modifiers, in the layout that
+/// [`code_to_key`] assumes.
+///
+/// Letters match regardless of case.
+pub fn key_to_hardware_keycode(key: &KbKey) -> Option<Keycode> {
+    let matches = |candidate: &KbKey| match (candidate, key) {
+        (KbKey::Character(a), KbKey::Character(b)) => a.to_lowercase() == b.to_lowercase(),
+        (KbKey::Unidentified, _) => false,
+        (a, b) => a == b,
+    };
+    (8..=255).find(|&keycode| {
+        let code = hardware_keycode_to_code(keycode);
+        code != Code::Unidentified && matches(&code_to_key(code, Modifiers::empty()))
+    })
+}
@@ -39,4 +39,6 @@ pub mod error;
 pub mod keycodes;
 pub mod menu;
 pub mod screen;
+pub mod tray;
 pub mod window;
+mod xsettings;
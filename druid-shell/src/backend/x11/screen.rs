@@ -18,11 +18,20 @@ use x11rb::connection::Connection;
 use x11rb::errors::ReplyOrIdError;
 use x11rb::protocol::randr::{self, ConnectionExt as _, Crtc};
 use x11rb::protocol::xproto::{Screen, Timestamp};
+use x11rb::resource_manager::Database as ResourceDb;
 
 use crate::kurbo::Rect;
+use crate::scale::Scale;
 use crate::screen::Monitor;
 
-fn monitor<Pos>(primary: bool, (x, y): (Pos, Pos), (width, height): (u16, u16)) -> Monitor
+use super::util;
+
+fn monitor<Pos>(
+    primary: bool,
+    (x, y): (Pos, Pos),
+    (width, height): (u16, u16),
+    scale: Scale,
+) -> Monitor
 where
     Pos: Into<i32>,
 {
@@ -31,24 +40,36 @@ where
         (width as f64, height as f64),
     );
     // TODO: Support for work_rect. It's complicated...
-    Monitor::new(primary, rect, rect)
+    Monitor::new(primary, rect, rect, scale)
 }
 
 pub(crate) fn get_monitors() -> Vec<Monitor> {
-    let result = if let Some(app) = crate::Application::try_global() {
-        let app = app.backend_app;
-        get_monitors_impl(app.connection().as_ref(), app.screen_num())
-    } else {
-        let (conn, screen_num) = match x11rb::connect(None) {
-            Ok(res) => res,
-            Err(err) => {
-                tracing::error!("Error in Screen::get_monitors(): {:?}", err);
-                return Vec::new();
-            }
-        };
-        get_monitors_impl(&conn, screen_num)
+    if let Some(app) = crate::Application::try_global() {
+        return app.backend_app.monitors();
+    }
+    let (conn, screen_num) = match x11rb::connect(None) {
+        Ok(res) => res,
+        Err(err) => {
+            tracing::error!("Error in Screen::get_monitors(): {:?}", err);
+            return Vec::new();
+        }
+    };
+    let scale = match ResourceDb::new_from_default(&conn) {
+        Ok(rdb) => util::scale(&rdb),
+        Err(_) => Scale::default(),
     };
-    match result {
+    query_monitors(&conn, screen_num, scale)
+}
+
+/// Ask the X server for the monitors of the screen `screen_num`.
+///
+/// X11 has a single DPI setting, so every monitor has the same `scale`.
+pub(crate) fn query_monitors(
+    conn: &impl Connection,
+    screen_num: usize,
+    scale: Scale,
+) -> Vec<Monitor> {
+    match get_monitors_impl(conn, screen_num, scale) {
         Ok(monitors) => monitors,
         Err(err) => {
             tracing::error!("Error in Screen::get_monitors(): {:?}", err);
@@ -60,6 +81,7 @@ pub(crate) fn get_monitors() -> Vec<Monitor> {
 fn get_monitors_impl(
     conn: &impl Connection,
     screen_num: usize,
+    scale: Scale,
 ) -> Result<Vec<Monitor>, ReplyOrIdError> {
     let screen = &conn.setup().roots[screen_num];
 
@@ -67,38 +89,49 @@ fn get_monitors_impl(
         .extension_information(randr::X11_EXTENSION_NAME)?
         .is_none()
     {
-        return get_monitors_core(screen);
+        return get_monitors_core(screen, scale);
     }
 
     // Monitor support was added in RandR 1.5
     let version = conn.randr_query_version(1, 5)?.reply()?;
     match (version.major_version, version.minor_version) {
-        (major, _) if major >= 2 => get_monitors_randr_monitors(conn, screen),
-        (1, minor) if minor >= 5 => get_monitors_randr_monitors(conn, screen),
-        (1, minor) if minor >= 3 => get_monitors_randr_screen_resources_current(conn, screen),
-        (1, minor) if minor >= 2 => get_monitors_randr_screen_resources(conn, screen),
-        _ => get_monitors_core(screen),
+        (major, _) if major >= 2 => get_monitors_randr_monitors(conn, screen, scale),
+        (1, minor) if minor >= 5 => get_monitors_randr_monitors(conn, screen, scale),
+        (1, minor) if minor >= 3 => {
+            get_monitors_randr_screen_resources_current(conn, screen, scale)
+        }
+        (1, minor) if minor >= 2 => get_monitors_randr_screen_resources(conn, screen, scale),
+        _ => get_monitors_core(screen, scale),
     }
 }
 
-fn get_monitors_core(screen: &Screen) -> Result<Vec<Monitor>, ReplyOrIdError> {
+fn get_monitors_core(screen: &Screen, scale: Scale) -> Result<Vec<Monitor>, ReplyOrIdError> {
     Ok(vec![monitor(
         true,
         (0, 0),
         (screen.width_in_pixels, screen.height_in_pixels),
+        scale,
     )])
 }
 
 fn get_monitors_randr_monitors(
     conn: &impl Connection,
     screen: &Screen,
+    scale: Scale,
 ) -> Result<Vec<Monitor>, ReplyOrIdError> {
     let result = conn
         .randr_get_monitors(screen.root, true)?
         .reply()?
         .monitors
         .iter()
-        .map(|info| monitor(info.primary, (info.x, info.y), (info.width, info.height)))
+        .map(|info| {
+            monitor(
+                info.primary,
+                (info.x, info.y),
+                (info.width, info.height),
+                scale,
+            )
+        })
         .collect();
     Ok(result)
 }
@@ -106,19 +139,21 @@ fn get_monitors_randr_monitors(
 fn get_monitors_randr_screen_resources_current(
     conn: &impl Connection,
     screen: &Screen,
+    scale: Scale,
 ) -> Result<Vec<Monitor>, ReplyOrIdError> {
     let reply = conn
         .randr_get_screen_resources_current(screen.root)?
         .reply()?;
-    get_monitors_randr_crtcs_timestamp(conn, &reply.crtcs, reply.config_timestamp)
+    get_monitors_randr_crtcs_timestamp(conn, &reply.crtcs, reply.config_timestamp, scale)
 }
 
 fn get_monitors_randr_screen_resources(
     conn: &impl Connection,
     screen: &Screen,
+    scale: Scale,
 ) -> Result<Vec<Monitor>, ReplyOrIdError> {
     let reply = conn.randr_get_screen_resources(screen.root)?.reply()?;
-    get_monitors_randr_crtcs_timestamp(conn, &reply.crtcs, reply.config_timestamp)
+    get_monitors_randr_crtcs_timestamp(conn, &reply.crtcs, reply.config_timestamp, scale)
 }
 
 // This function first sends a number of requests, collect()ing them into a Vec and then gets the
@@ -128,6 +163,7 @@ fn get_monitors_randr_crtcs_timestamp(
     conn: &impl Connection,
     crtcs: &[Crtc],
     config_timestamp: Timestamp,
+    scale: Scale,
 ) -> Result<Vec<Monitor>, ReplyOrIdError> {
     // Request information about all CRTCs
     let requests = crtcs
@@ -146,6 +182,7 @@ fn get_monitors_randr_crtcs_timestamp(
                 primary,
                 (reply.x, reply.y),
                 (reply.width, reply.height),
+                scale,
             ));
         }
     }
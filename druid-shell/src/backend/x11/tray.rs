@@ -0,0 +1,451 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! X11 system tray, using the XEmbed system tray protocol.
+//!
+//! https://specifications.freedesktop.org/systemtray-spec/systemtray-spec-0.3.html
+
+use std::cell::RefCell;
+use std::convert::TryInto;
+use std::rc::Rc;
+
+use anyhow::{anyhow, Context, Error};
+use x11rb::connection::Connection;
+use x11rb::protocol::render::{self, ConnectionExt as _, PictOp, Pictformat, Transform};
+use x11rb::protocol::xproto::{
+    self, AtomEnum, BackPixmap, ButtonReleaseEvent, ChangeWindowAttributesAux, ColormapAlloc,
+    ConfigureNotifyEvent, ConnectionExt as _, CreateWindowAux, EventMask, ExposeEvent, ImageFormat,
+    PropMode, Visualid, WindowClass,
+};
+use x11rb::wrapper::ConnectionExt as _;
+use x11rb::xcb_ffi::XCBConnection;
+
+use super::application::Application;
+use super::menu::Menu;
+use super::util;
+use crate::piet::ImageBuf;
+
+/// The opcode asking the tray to embed a window.
+const SYSTEM_TRAY_REQUEST_DOCK: u32 = 0;
+/// The `_XEMBED_INFO` flag asking the embedder to map our window.
+const XEMBED_MAPPED: u32 = 1;
+/// The size of the icon window until the tray gives it one.
+const DEFAULT_SIZE: u16 = 24;
+
+pub struct TrayIcon {
+    tray: Rc<Tray>,
+}
+
+/// The state of a tray icon, shared with the `Application` so it can route events to it.
+pub(crate) struct Tray {
+    app: Application,
+    /// The embedded window, while a tray is showing the icon.
+    docked: RefCell<Option<Docked>>,
+    icon: RefCell<ImageBuf>,
+    tooltip: RefCell<String>,
+}
+
+/// The X11 resources of a docked icon.
+struct Docked {
+    window: u32,
+    /// The colormap of the tray's ARGB visual, if we use it.
+    colormap: Option<u32>,
+    /// The RENDER picture of `window`.
+    picture: u32,
+    /// The picture format used for the icon image.
+    argb32_format: Pictformat,
+    /// The RENDER picture holding the icon at its own size, created when first painted.
+    icon: Option<u32>,
+    size: (u16, u16),
+}
+
+impl TrayIcon {
+    pub fn new(icon: &ImageBuf) -> Result<TrayIcon, crate::Error> {
+        let app = crate::Application::try_global()
+            .ok_or_else(|| anyhow!("tray icons need an Application"))?
+            .backend_app;
+        let tray = Rc::new(Tray {
+            app: app.clone(),
+            docked: RefCell::new(None),
+            icon: RefCell::new(icon.clone()),
+            tooltip: RefCell::new(String::new()),
+        });
+        app.add_tray(&tray)?;
+        // Without a running tray this does nothing; the icon docks once a tray announces itself.
+        tray.dock()?;
+        Ok(TrayIcon { tray })
+    }
+
+    pub fn set_icon(&mut self, icon: &ImageBuf) {
+        *self.tray.icon.borrow_mut() = icon.clone();
+        if let Some(docked) = self.tray.docked.borrow_mut().as_mut() {
+            if let Some(picture) = docked.icon.take() {
+                log_x11!(self.tray.app.connection().render_free_picture(picture));
+            }
+        }
+        if let Err(e) = self.tray.paint() {
+            tracing::error!("failed to paint the tray icon: {:#}", e);
+        }
+    }
+
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        *self.tray.tooltip.borrow_mut() = tooltip.to_string();
+        if let Some(window) = self.tray.window() {
+            // Trays show the window's name as its tooltip.
+            let conn = self.tray.app.connection();
+            let atoms = self.tray.app.atoms();
+            log_x11!(conn.change_property8(
+                PropMode::REPLACE,
+                window,
+                atoms._NET_WM_NAME,
+                atoms.UTF8_STRING,
+                tooltip.as_bytes(),
+            ));
+            log_x11!(conn.flush());
+        }
+    }
+
+    pub fn set_menu(&mut self, _menu: Menu) -> Result<(), crate::Error> {
+        // XEmbed trays leave menus to the icon, and we can't show popup menus on X11 yet.
+        Err(crate::Error::Unsupported)
+    }
+}
+
+impl Drop for TrayIcon {
+    fn drop(&mut self) {
+        self.tray.undock();
+    }
+}
+
+impl Tray {
+    /// The embedded window, if the icon is docked.
+    pub(crate) fn window(&self) -> Option<u32> {
+        self.docked.borrow().as_ref().map(|docked| docked.window)
+    }
+
+    /// Create a new icon window and ask the tray, if there is one, to embed it.
+    ///
+    /// This is also called when a new tray starts, since the icons of the old one are gone.
+    pub(crate) fn dock(&self) -> Result<(), Error> {
+        self.undock();
+        let conn = self.app.connection();
+        let screen = &conn.setup().roots[self.app.screen_num()];
+        let atoms = self.app.atoms();
+
+        let selection = format!("_NET_SYSTEM_TRAY_S{}", self.app.screen_num());
+        let selection = conn
+            .intern_atom(false, selection.as_bytes())?
+            .reply()
+            .context("intern the system tray selection")?
+            .atom;
+        let manager = conn
+            .get_selection_owner(selection)?
+            .reply()
+            .context("get the system tray owner")?
+            .owner;
+        if manager == x11rb::NONE {
+            tracing::info!("no system tray is running, waiting for one to start");
+            return Ok(());
+        }
+
+        // Use the tray's ARGB visual if it offers one, so the icon keeps its transparency.
+        // Otherwise the window shows the tray's background, and the icon is blended onto it.
+        let tray_visual = conn
+            .get_property(
+                false,
+                manager,
+                atoms._NET_SYSTEM_TRAY_VISUAL,
+                AtomEnum::VISUALID,
+                0,
+                1,
+            )?
+            .reply()
+            .context("get _NET_SYSTEM_TRAY_VISUAL")?
+            .value32()
+            .and_then(|mut values| values.next());
+        let argb_visual = tray_visual.filter(|&id| {
+            screen
+                .allowed_depths
+                .iter()
+                .any(|depth| depth.depth == 32 && depth.visuals.iter().any(|v| v.visual_id == id))
+        });
+
+        let window = conn.generate_id()?;
+        let mut colormap = None;
+        let (depth, visual, aux) = match argb_visual {
+            Some(visual) => {
+                let id = conn.generate_id()?;
+                conn.create_colormap(ColormapAlloc::NONE, id, screen.root, visual)?;
+                colormap = Some(id);
+                let aux = CreateWindowAux::new()
+                    .colormap(id)
+                    .background_pixel(0)
+                    .border_pixel(0);
+                (32, visual, aux)
+            }
+            None => {
+                let aux = CreateWindowAux::new()
+                    .background_pixmap(u32::from(BackPixmap::PARENT_RELATIVE));
+                (screen.root_depth, screen.root_visual, aux)
+            }
+        };
+        let aux = aux.event_mask(
+            EventMask::EXPOSURE
+                | EventMask::STRUCTURE_NOTIFY
+                | EventMask::BUTTON_PRESS
+                | EventMask::BUTTON_RELEASE,
+        );
+        conn.create_window(
+            depth,
+            window,
+            screen.root,
+            0,
+            0,
+            DEFAULT_SIZE,
+            DEFAULT_SIZE,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            visual,
+            &aux,
+        )?
+        .check()
+        .context("create tray icon window")?;
+
+        // XEmbed version 0; the tray maps the window once it is embedded.
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            atoms._XEMBED_INFO,
+            atoms._XEMBED_INFO,
+            &[0, XEMBED_MAPPED],
+        )?;
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            atoms._NET_WM_NAME,
+            atoms.UTF8_STRING,
+            self.tooltip.borrow().as_bytes(),
+        )?;
+
+        let (window_format, argb32_format) = pict_formats(conn, visual)?;
+        let picture = conn.generate_id()?;
+        conn.render_create_picture(picture, window, window_format, &Default::default())?;
+        *self.docked.borrow_mut() = Some(Docked {
+            window,
+            colormap,
+            picture,
+            argb32_format,
+            icon: None,
+            size: (DEFAULT_SIZE, DEFAULT_SIZE),
+        });
+
+        let event = xproto::ClientMessageEvent::new(
+            32,
+            manager,
+            atoms._NET_SYSTEM_TRAY_OPCODE,
+            [x11rb::CURRENT_TIME, SYSTEM_TRAY_REQUEST_DOCK, window, 0, 0],
+        );
+        conn.send_event(false, manager, EventMask::NO_EVENT, event)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Remove the icon from the tray and free its resources.
+    fn undock(&self) {
+        if let Some(docked) = self.docked.borrow_mut().take() {
+            let conn = self.app.connection();
+            // Don't report the window's destruction, nobody is looking for it anymore.
+            log_x11!(conn.change_window_attributes(
+                docked.window,
+                &ChangeWindowAttributesAux::new().event_mask(EventMask::NO_EVENT),
+            ));
+            if let Some(icon) = docked.icon {
+                log_x11!(conn.render_free_picture(icon));
+            }
+            log_x11!(conn.render_free_picture(docked.picture));
+            log_x11!(conn.destroy_window(docked.window));
+            if let Some(colormap) = docked.colormap {
+                log_x11!(conn.free_colormap(colormap));
+            }
+            log_x11!(conn.flush());
+        }
+    }
+
+    /// Draw the icon, scaled to fit the window and centered in it.
+    fn paint(&self) -> Result<(), Error> {
+        let mut docked = borrow_mut!(self.docked)?;
+        let docked = match docked.as_mut() {
+            Some(docked) => docked,
+            None => return Ok(()),
+        };
+        let icon = borrow!(self.icon)?;
+        if icon.width() == 0 || icon.height() == 0 {
+            return Ok(());
+        }
+        let conn = self.app.connection();
+        let source = match docked.icon {
+            Some(picture) => picture,
+            None => {
+                let root = conn.setup().roots[self.app.screen_num()].root;
+                let picture = make_picture(conn, root, docked.argb32_format, &icon)?;
+                docked.icon = Some(picture);
+                picture
+            }
+        };
+
+        let (width, height) = (f64::from(docked.size.0), f64::from(docked.size.1));
+        let scale = (width / icon.width() as f64).min(height / icon.height() as f64);
+        let (icon_width, icon_height) = (
+            (icon.width() as f64 * scale).round(),
+            (icon.height() as f64 * scale).round(),
+        );
+        // RENDER transforms map destination coordinates to source coordinates.
+        let inverse = to_fixed(1.0 / scale);
+        let transform = Transform {
+            matrix11: inverse,
+            matrix12: 0,
+            matrix13: 0,
+            matrix21: 0,
+            matrix22: inverse,
+            matrix23: 0,
+            matrix31: 0,
+            matrix32: 0,
+            matrix33: to_fixed(1.0),
+        };
+        conn.render_set_picture_transform(source, transform)?;
+        conn.render_set_picture_filter(source, b"bilinear", &[])?;
+
+        // Restore the background (the tray's, or transparency) before blending the icon onto it.
+        conn.clear_area(false, docked.window, 0, 0, 0, 0)?;
+        conn.render_composite(
+            PictOp::OVER,
+            source,
+            x11rb::NONE,
+            docked.picture,
+            0,
+            0,
+            0,
+            0,
+            ((width - icon_width) / 2.0) as i16,
+            ((height - icon_height) / 2.0) as i16,
+            icon_width as u16,
+            icon_height as u16,
+        )?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    pub(crate) fn handle_expose(&self, expose: &ExposeEvent) -> Result<(), Error> {
+        // Only paint once for a series of exposures.
+        if expose.count == 0 {
+            self.paint()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn handle_configure_notify(
+        &self,
+        event: &ConfigureNotifyEvent,
+    ) -> Result<(), Error> {
+        if let Some(docked) = borrow_mut!(self.docked)?.as_mut() {
+            docked.size = (event.width, event.height);
+        }
+        // Shrinking the window doesn't expose it, so repaint here.
+        self.paint()
+    }
+
+    pub(crate) fn handle_button_release(&self, event: &ButtonReleaseEvent) {
+        if event.detail == 1 {
+            self.app.with_handler(|handler| handler.tray_activated());
+        }
+    }
+
+    pub(crate) fn handle_destroy_notify(&self) {
+        // The tray destroyed the window instead of giving it back. Destroying the window freed
+        // its picture; free the rest, and dock again when a new tray starts.
+        if let Ok(mut docked) = self.docked.try_borrow_mut() {
+            if let Some(docked) = docked.take() {
+                let conn = self.app.connection();
+                if let Some(icon) = docked.icon {
+                    log_x11!(conn.render_free_picture(icon));
+                }
+                if let Some(colormap) = docked.colormap {
+                    log_x11!(conn.free_colormap(colormap));
+                }
+            }
+        }
+    }
+}
+
+/// Find the RENDER picture formats for windows with `visual`, and for ARGB32 images.
+fn pict_formats(conn: &XCBConnection, visual: Visualid) -> Result<(Pictformat, Pictformat), Error> {
+    let reply = conn
+        .render_query_pict_formats()?
+        .reply()
+        .context("query picture formats")?;
+    let window_format = reply
+        .screens
+        .iter()
+        .flat_map(|screen| &screen.depths)
+        .flat_map(|depth| &depth.visuals)
+        .find(|v| v.visual == visual)
+        .map(|v| v.format)
+        .ok_or_else(|| anyhow!("no picture format for visual {}", visual))?;
+    let argb32_format = reply
+        .formats
+        .iter()
+        .find(|format| util::is_argb32_format(format))
+        .map(|format| format.id)
+        .ok_or_else(|| anyhow!("no ARGB32 picture format"))?;
+    Ok((window_format, argb32_format))
+}
+
+/// Upload `image` into a new RENDER picture.
+fn make_picture(
+    conn: &XCBConnection,
+    root: u32,
+    format: Pictformat,
+    image: &ImageBuf,
+) -> Result<render::Picture, Error> {
+    let width = image.width().try_into().context("icon too wide")?;
+    let height = image.height().try_into().context("icon too tall")?;
+    let pixels = util::premultiplied_argb32(image, conn.setup().image_byte_order);
+
+    let pixmap = conn.generate_id()?;
+    let gc = conn.generate_id()?;
+    let picture = conn.generate_id()?;
+    conn.create_pixmap(32, pixmap, root, width, height)?;
+    conn.create_gc(gc, pixmap, &Default::default())?;
+    conn.put_image(
+        ImageFormat::Z_PIXMAP,
+        pixmap,
+        gc,
+        width,
+        height,
+        0,
+        0,
+        0,
+        32,
+        &pixels,
+    )?;
+    conn.render_create_picture(picture, pixmap, format, &Default::default())?;
+    conn.free_gc(gc)?;
+    conn.free_pixmap(pixmap)?;
+    Ok(picture)
+}
+
+/// Convert to RENDER's 16.16 fixed point numbers.
+fn to_fixed(value: f64) -> render::Fixed {
+    (value * 65536.0).round() as render::Fixed
+}
@@ -23,11 +23,41 @@ use x11rb::connection::RequestConnection;
 use x11rb::errors::ReplyError;
 use x11rb::protocol::randr::{ConnectionExt, ModeFlag};
 use x11rb::protocol::render::{self, ConnectionExt as _};
-use x11rb::protocol::xproto::{Screen, Visualid, Visualtype, Window};
+use x11rb::protocol::xproto::{ImageOrder, Screen, Visualid, Visualtype, Window};
+use x11rb::resource_manager::Database as ResourceDb;
 use x11rb::xcb_ffi::XCBConnection;
 
+use crate::piet::ImageBuf;
+use crate::scale::Scale;
 use crate::window::TimerToken;
 
+/// The scale that windows are drawn at, from `Xft.dpi` or the `DRUID_X11_DPI` environment
+/// variable.
+///
+/// X11 has a single DPI setting for every monitor.
+pub fn scale(rdb: &ResourceDb) -> Scale {
+    let env_dpi = std::env::var("DRUID_X11_DPI")
+        .ok()
+        .map(|x| x.parse::<f64>());
+
+    match env_dpi.or_else(|| rdb.get_value("Xft.dpi", "").transpose()) {
+        Some(Ok(dpi)) => {
+            let scale = dpi / 96.;
+            Scale::new(scale, scale)
+        }
+        None => Scale::default(),
+        Some(Err(err)) => {
+            let default = Scale::default();
+            tracing::warn!(
+                "Unable to parse dpi: {:?}, defaulting to {:?}",
+                err,
+                default
+            );
+            default
+        }
+    }
+}
+
 // See: https://github.com/rtbo/rust-xcb/blob/master/examples/randr_screen_modes.rs
 pub fn refresh_rate(conn: &Rc<XCBConnection>, window_id: Window) -> Option<f64> {
     let try_refresh_rate = || -> Result<f64, Error> {
@@ -122,18 +152,7 @@ pub fn get_argb_visual_type(
         let res = pict_formats
             .formats
             .iter()
-            .find(|format| {
-                format.type_ == render::PictType::DIRECT
-                    && format.depth == 32
-                    && format.direct.red_shift == 16
-                    && format.direct.red_mask == 0xff
-                    && format.direct.green_shift == 8
-                    && format.direct.green_mask == 0xff
-                    && format.direct.blue_shift == 0
-                    && format.direct.blue_mask == 0xff
-                    && format.direct.alpha_shift == 24
-                    && format.direct.alpha_mask == 0xff
-            })
+            .find(|format| is_argb32_format(format))
             // Now find the corresponding visual ID
             .and_then(|format| find_visual_for_format(&pict_formats, format.id))
             // And finally, we can find the visual
@@ -142,6 +161,48 @@ pub fn get_argb_visual_type(
     }
 }
 
+/// Whether `format` is RENDER's standard ARGB32 picture format.
+pub(crate) fn is_argb32_format(format: &render::Pictforminfo) -> bool {
+    format.type_ == render::PictType::DIRECT
+        && format.depth == 32
+        && format.direct.red_shift == 16
+        && format.direct.red_mask == 0xff
+        && format.direct.green_shift == 8
+        && format.direct.green_mask == 0xff
+        && format.direct.blue_shift == 0
+        && format.direct.blue_mask == 0xff
+        && format.direct.alpha_shift == 24
+        && format.direct.alpha_mask == 0xff
+}
+
+/// Convert `image` to the premultiplied ARGB32 pixels that RENDER expects, in the server's byte
+/// order.
+pub(crate) fn premultiplied_argb32(image: &ImageBuf, byte_order: ImageOrder) -> Vec<u8> {
+    fn multiply_alpha(color: u8, alpha: u8) -> u8 {
+        let (color, alpha) = (u16::from(color), u16::from(alpha));
+        let temp = color * alpha + 0x80u16;
+        ((temp + (temp >> 8)) >> 8) as u8
+    }
+
+    let mut pixels = Vec::with_capacity(image.width() * image.height() * 4);
+    for row in image.pixel_colors() {
+        for color in row {
+            let (r, g, b, a) = color.as_rgba8();
+            let (r, g, b) = (
+                multiply_alpha(r, a),
+                multiply_alpha(g, a),
+                multiply_alpha(b, a),
+            );
+            // piet gives us rgba in this order, the server expects an u32 with argb.
+            match byte_order {
+                ImageOrder::LSB_FIRST => pixels.extend_from_slice(&[b, g, r, a]),
+                _ => pixels.extend_from_slice(&[a, r, g, b]),
+            }
+        }
+    }
+    pixels
+}
+
 macro_rules! log_x11 {
     ($val:expr) => {
         if let Err(e) = $val {
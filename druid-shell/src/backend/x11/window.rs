@@ -44,18 +44,19 @@ use x11rb::xcb_ffi::XCBConnection;
 #[cfg(feature = "raw-win-handle")]
 use raw_window_handle::{unix::XcbHandle, HasRawWindowHandle, RawWindowHandle};
 
-use crate::common_util::IdleCallback;
+use crate::common_util::{ClickCounter, IdleCallback};
 use crate::dialog::FileDialogOptions;
-use crate::error::Error as ShellError;
 use crate::keyboard::{KeyEvent, KeyState, Modifiers};
 use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
 use crate::mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 use crate::piet::{Piet, PietText, RenderContext};
 use crate::region::Region;
 use crate::scale::Scale;
+use crate::screen::Monitor;
 use crate::text::{simulate_input, Event};
 use crate::window::{
-    FileDialogToken, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowLevel,
+    FileDialogToken, IdleToken, ResizeEdge, TextFieldToken, TextRenderingOptions, TimerToken,
+    WinHandler, WindowCorners, WindowLevel,
 };
 use crate::{window, ScaledArea};
 
@@ -225,25 +226,7 @@ impl WindowBuilder {
         let id = conn.generate_id()?;
         let setup = conn.setup();
 
-        let env_dpi = std::env::var("DRUID_X11_DPI")
-            .ok()
-            .map(|x| x.parse::<f64>());
-
-        let scale = match env_dpi.or_else(|| self.app.rdb.get_value("Xft.dpi", "").transpose()) {
-            Some(Ok(dpi)) => {
-                let scale = dpi / 96.;
-                Scale::new(scale, scale)
-            }
-            None => Scale::default(),
-            Some(Err(err)) => {
-                let default = Scale::default();
-                warn!(
-                    "Unable to parse dpi: {:?}, defaulting to {:?}",
-                    err, default
-                );
-                default
-            }
-        };
+        let scale = self.app.scale();
 
         let size_px = self.size.to_px(scale);
         let screen = setup
@@ -433,6 +416,8 @@ impl WindowBuilder {
                 WindowLevel::Tooltip => atoms._NET_WM_WINDOW_TYPE_TOOLTIP,
                 WindowLevel::Modal => atoms._NET_WM_WINDOW_TYPE_DIALOG,
                 WindowLevel::DropDown => atoms._NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
+                WindowLevel::Floating => atoms._NET_WM_WINDOW_TYPE_NORMAL,
+                WindowLevel::Utility => atoms._NET_WM_WINDOW_TYPE_UTILITY,
             };
 
             let conn = self.app.connection();
@@ -452,6 +437,16 @@ impl WindowBuilder {
                     &ChangeWindowAttributesAux::new().override_redirect(1),
                 ));
             }
+            // Before the window is mapped, we can set its state ourselves.
+            if matches!(self.level, WindowLevel::Floating | WindowLevel::Utility) {
+                log_x11!(conn.change_property32(
+                    xproto::PropMode::REPLACE,
+                    id,
+                    atoms._NET_WM_STATE,
+                    AtomEnum::ATOM,
+                    &[atoms._NET_WM_STATE_ABOVE],
+                ));
+            }
         }
 
         let window = Rc::new(Window {
@@ -465,12 +460,20 @@ impl WindowBuilder {
             min_size,
             invalid: RefCell::new(Region::EMPTY),
             destroyed: Cell::new(false),
+            mapped: Cell::new(false),
+            click_counter: ClickCounter::default(),
             timer_queue: Mutex::new(BinaryHeap::new()),
             idle_queue: Arc::new(Mutex::new(Vec::new())),
             idle_pipe: self.app.idle_pipe(),
             present_data: RefCell::new(present_data),
             buffers,
             active_text_field: Cell::new(None),
+            current_keycode: Cell::new(None),
+            configured_rect: Cell::new(None),
+            monitor: RefCell::new(None),
+            restored_rect: Cell::new(None),
+            button_press: Cell::new(None),
+            handle_titlebar: Cell::new(false),
         });
 
         window.set_title(&self.title);
@@ -549,6 +552,11 @@ pub(crate) struct Window {
     min_size: Size,
     /// We've told X11 to destroy this window, so don't so any more X requests with this window id.
     destroyed: Cell<bool>,
+    /// Whether we asked X11 to map the window. Until then, the window manager doesn't handle
+    /// requests to change its state.
+    mapped: Cell<bool>,
+    /// X11 has no setting for the double-click time, so we use the defaults.
+    click_counter: ClickCounter,
     /// The region that was invalidated since the last time we rendered.
     invalid: RefCell<Region>,
     /// Timers, sorted by "earliest deadline first"
@@ -589,6 +597,21 @@ pub(crate) struct Window {
     present_data: RefCell<Option<PresentData>>,
     buffers: RefCell<Buffers>,
     active_text_field: Cell<Option<TextFieldToken>>,
+    /// The keycode of the last key pressed, while it is held down.
+    current_keycode: Cell<Option<u8>>,
+    /// The geometry of the last `ConfigureNotify` event, to only look for a new monitor when the
+    /// window was moved or resized.
+    configured_rect: Cell<Option<(i16, i16, u16, u16)>>,
+    /// The monitor the window was on when it was last configured.
+    monitor: RefCell<Option<Monitor>>,
+    /// The position and size of the window when it was last configured while restored, in display
+    /// points.
+    restored_rect: Cell<Option<Rect>>,
+    /// The root position and the button of the last button press, while the button is held down,
+    /// to start a move or resize drag from.
+    button_press: Cell<Option<(i16, i16, u8)>>,
+    /// Whether the mouse is over an area that should be treated as the titlebar.
+    handle_titlebar: Cell<bool>,
 }
 
 /// A collection of pixmaps for rendering to. This gets used in two different ways: if the present
@@ -833,6 +856,7 @@ impl Window {
     fn show(&self) {
         if !self.destroyed() {
             log_x11!(self.app.connection().map_window(self.id));
+            self.mapped.set(true);
         }
     }
 
@@ -890,6 +914,145 @@ impl Window {
         ));
     }
 
+    fn set_fullscreen(&self, fullscreen: bool, monitor: Option<Rect>) {
+        let conn = self.app.connection();
+        let atoms = self.app.atoms();
+        if let Some(rect) = monitor {
+            // The window manager makes the window fullscreen on its monitor.
+            log_x11!(conn.configure_window(
+                self.id,
+                &ConfigureWindowAux::new()
+                    .x(rect.x0 as i32)
+                    .y(rect.y0 as i32),
+            ));
+        }
+        self.change_net_wm_state(fullscreen, atoms._NET_WM_STATE_FULLSCREEN);
+    }
+
+    /// Ask the window manager to add or remove one of the `_NET_WM_STATE`s of the window.
+    fn change_net_wm_state(&self, add: bool, state: u32) {
+        let conn = self.app.connection();
+        if !self.mapped.get() {
+            // Before the window is mapped, we can set its state ourselves.
+            let mut states = self.net_wm_states().unwrap_or_default();
+            states.retain(|&s| s != state);
+            if add {
+                states.push(state);
+            }
+            log_x11!(conn.change_property32(
+                xproto::PropMode::REPLACE,
+                self.id,
+                self.app.atoms()._NET_WM_STATE,
+                AtomEnum::ATOM,
+                &states,
+            ));
+            return;
+        }
+        // https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm45805407959456
+        const _NET_WM_STATE_REMOVE: u32 = 0;
+        const _NET_WM_STATE_ADD: u32 = 1;
+        let action = if add {
+            _NET_WM_STATE_ADD
+        } else {
+            _NET_WM_STATE_REMOVE
+        };
+        let event = xproto::ClientMessageEvent::new(
+            32,
+            self.id,
+            self.app.atoms()._NET_WM_STATE,
+            [action, state, 0, 1, 0],
+        );
+        let root = conn.setup().roots[self.app.screen_num()].root;
+        log_x11!(conn.send_event(
+            false,
+            root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        ));
+        log_x11!(conn.flush());
+    }
+
+    fn is_fullscreen(&self) -> Result<bool, Error> {
+        let fullscreen = self.app.atoms()._NET_WM_STATE_FULLSCREEN;
+        Ok(self.net_wm_states()?.contains(&fullscreen))
+    }
+
+    fn window_state(&self) -> Result<window::WindowState, Error> {
+        let atoms = self.app.atoms();
+        let states = self.net_wm_states()?;
+        Ok(if states.contains(&atoms._NET_WM_STATE_HIDDEN) {
+            window::WindowState::Minimized
+        } else if states.contains(&atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            && states.contains(&atoms._NET_WM_STATE_MAXIMIZED_HORZ)
+        {
+            window::WindowState::Maximized
+        } else {
+            window::WindowState::Restored
+        })
+    }
+
+    /// The `_NET_WM_STATE`s the window manager gave the window.
+    fn net_wm_states(&self) -> Result<Vec<u32>, Error> {
+        let atoms = self.app.atoms();
+        let reply = self
+            .app
+            .connection()
+            .get_property(
+                false,
+                self.id,
+                atoms._NET_WM_STATE,
+                AtomEnum::ATOM,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        Ok(reply
+            .value32()
+            .map(|states| states.collect())
+            .unwrap_or_default())
+    }
+
+    /// Let the window manager move or resize the window with the button that is held down.
+    ///
+    /// `direction` is one of the `_NET_WM_MOVERESIZE_*` values of the EWMH spec.
+    fn begin_move_resize(&self, direction: u32) {
+        if self.destroyed() {
+            return;
+        }
+        let (x_root, y_root, button) = match self.button_press.take() {
+            Some(press) => press,
+            None => {
+                warn!("A move or resize drag can only start while a mouse button is down");
+                return;
+            }
+        };
+        let conn = self.app.connection();
+        // The window manager can't grab the pointer while we have it grabbed implicitly.
+        log_x11!(conn.ungrab_pointer(x11rb::CURRENT_TIME));
+        // https://specifications.freedesktop.org/wm-spec/wm-spec-1.3.html#idm46035372536800
+        const SOURCE_APPLICATION: u32 = 1;
+        let event = xproto::ClientMessageEvent::new(
+            32,
+            self.id,
+            self.app.atoms()._NET_WM_MOVERESIZE,
+            [
+                x_root as u32,
+                y_root as u32,
+                direction,
+                button as u32,
+                SOURCE_APPLICATION,
+            ],
+        );
+        let root = conn.setup().roots[self.app.screen_num()].root;
+        log_x11!(conn.send_event(
+            false,
+            root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        ));
+        log_x11!(conn.flush());
+    }
+
     /// Bring this window to the front of the window stack and give it focus.
     fn bring_to_front_and_focus(&self) {
         if self.destroyed() {
@@ -991,19 +1154,24 @@ impl Window {
 
     fn set_cursor(&self, cursor: &Cursor) {
         let cursors = &self.app.cursors;
-        #[allow(deprecated)]
         let cursor = match cursor {
             Cursor::Arrow => cursors.default,
             Cursor::IBeam => cursors.text,
             Cursor::Pointer => cursors.pointer,
             Cursor::Crosshair => cursors.crosshair,
-            Cursor::OpenHand => {
-                warn!("Cursor::OpenHand not supported for x11 backend. using arrow cursor");
-                None
-            }
+            Cursor::OpenHand => cursors.grab,
+            Cursor::ClosedHand => cursors.grabbing,
             Cursor::NotAllowed => cursors.not_allowed,
             Cursor::ResizeLeftRight => cursors.col_resize,
             Cursor::ResizeUpDown => cursors.row_resize,
+            Cursor::ResizeNwSe => cursors.nwse_resize,
+            Cursor::ResizeNeSw => cursors.nesw_resize,
+            Cursor::Move => cursors.move_,
+            Cursor::ZoomIn => cursors.zoom_in,
+            Cursor::ZoomOut => cursors.zoom_out,
+            Cursor::Wait => cursors.wait,
+            Cursor::Progress => cursors.progress,
+            Cursor::Help => cursors.help,
             Cursor::Custom(custom) => Some(custom.0),
         };
         if cursor.is_none() {
@@ -1043,20 +1211,8 @@ impl Window {
 
     pub fn handle_key_press(&self, key_press: &xproto::KeyPressEvent) {
         let hw_keycode = key_press.detail;
-        let code = keycodes::hardware_keycode_to_code(hw_keycode);
-        let mods = key_mods(key_press.state);
-        let key = keycodes::code_to_key(code, mods);
-        let location = keycodes::code_to_location(code);
-        let state = KeyState::Down;
-        let key_event = KeyEvent {
-            code,
-            key,
-            mods,
-            location,
-            state,
-            repeat: false,
-            is_composing: false,
-        };
+        let repeat = self.current_keycode.replace(Some(hw_keycode)) == Some(hw_keycode);
+        let key_event = make_key_event(hw_keycode, key_press.state, KeyState::Down, repeat);
         self.with_handler(|h| {
             if !h.key_down(key_event.clone()) {
                 simulate_input(h, self.active_text_field.get(), key_event);
@@ -1064,25 +1220,43 @@ impl Window {
         });
     }
 
+    pub fn handle_key_release(&self, key_release: &xproto::KeyReleaseEvent) {
+        let hw_keycode = key_release.detail;
+        if self.current_keycode.get() == Some(hw_keycode) {
+            self.current_keycode.set(None);
+        }
+        let key_event = make_key_event(hw_keycode, key_release.state, KeyState::Up, false);
+        self.with_handler(|h| h.key_up(key_event));
+    }
+
     pub fn handle_button_press(
         &self,
         button_press: &xproto::ButtonPressEvent,
     ) -> Result<(), Error> {
         let button = mouse_button(button_press.detail);
         let scale = self.scale.get();
+        let pos = Point::new(button_press.event_x as f64, button_press.event_y as f64).to_dp(scale);
         let mouse_event = MouseEvent {
-            pos: Point::new(button_press.event_x as f64, button_press.event_y as f64).to_dp(scale),
+            pos,
             // The xcb state field doesn't include the newly pressed button, but
             // druid wants it to be included.
             buttons: mouse_buttons(button_press.state).with(button),
             mods: key_mods(button_press.state),
-            // TODO: detect the count
-            count: 1,
+            count: self.click_counter.count_for_click(pos),
             focus: false,
             button,
             wheel_delta: Vec2::ZERO,
+            precise_wheel: false,
         };
+        self.button_press.set(Some((
+            button_press.root_x,
+            button_press.root_y,
+            button_press.detail,
+        )));
         self.with_handler(|h| h.mouse_down(&mouse_event));
+        if button == MouseButton::Left && self.handle_titlebar.get() {
+            self.begin_move_resize(_NET_WM_MOVERESIZE_MOVE);
+        }
         Ok(())
     }
 
@@ -1092,6 +1266,7 @@ impl Window {
     ) -> Result<(), Error> {
         let scale = self.scale.get();
         let button = mouse_button(button_release.detail);
+        self.button_press.set(None);
         let mouse_event = MouseEvent {
             pos: Point::new(button_release.event_x as f64, button_release.event_y as f64)
                 .to_dp(scale),
@@ -1103,6 +1278,7 @@ impl Window {
             focus: false,
             button,
             wheel_delta: Vec2::ZERO,
+            precise_wheel: false,
         };
         self.with_handler(|h| h.mouse_up(&mouse_event));
         Ok(())
@@ -1132,6 +1308,8 @@ impl Window {
             focus: false,
             button: MouseButton::None,
             wheel_delta: delta.into(),
+            // core protocol wheel events are notches, even from a touchpad
+            precise_wheel: false,
         };
 
         self.with_handler(|h| h.wheel(&mouse_event));
@@ -1152,6 +1330,7 @@ impl Window {
             focus: false,
             button: MouseButton::None,
             wheel_delta: Vec2::ZERO,
+            precise_wheel: false,
         };
         self.with_handler(|h| h.mouse_move(&mouse_event));
         Ok(())
@@ -1174,8 +1353,63 @@ impl Window {
         self.with_handler(|h| h.destroy());
     }
 
+    pub fn handle_clipboard_changed(&self) {
+        self.with_handler(|h| h.clipboard_changed());
+    }
+
     pub fn handle_configure_notify(&self, event: &ConfigureNotifyEvent) -> Result<(), Error> {
-        self.size_changed(Size::new(event.width as f64, event.height as f64))
+        self.size_changed(Size::new(event.width as f64, event.height as f64))?;
+        let rect = Some((event.x, event.y, event.width, event.height));
+        if self.configured_rect.replace(rect) != rect {
+            let atoms = self.app.atoms();
+            let states = self.net_wm_states()?;
+            let not_restored = [
+                atoms._NET_WM_STATE_FULLSCREEN,
+                atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                atoms._NET_WM_STATE_HIDDEN,
+            ];
+            if !states.iter().any(|state| not_restored.contains(state)) {
+                let size = self.size().size_dp();
+                self.restored_rect
+                    .set(Some(Rect::from_origin_size(self.get_position(), size)));
+            }
+            let monitor = self.monitor();
+            let previous = self.monitor.replace(monitor.clone());
+            if let (Some(previous), Some(monitor)) = (previous, monitor) {
+                if previous != monitor {
+                    self.with_handler(|h| h.monitor_changed(&monitor));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_system_theme_changed(&self) {
+        let theme = crate::Application::system_theme();
+        self.with_handler(|h| h.system_theme_changed(&theme));
+    }
+
+    pub fn handle_monitors_changed(&self) {
+        // Our monitor may look different now, but we didn't move to another one.
+        self.monitor.replace(self.monitor());
+        self.with_handler(|h| h.monitors_changed());
+    }
+
+    /// The monitor holding most of the window.
+    fn monitor(&self) -> Option<Monitor> {
+        let scale = self.scale.get();
+        let rect = Rect::from_origin_size(self.get_position(), self.size().size_dp()).to_px(scale);
+        let overlap = |monitor: &Monitor| rect.intersect(monitor.virtual_rect()).area();
+        self.app
+            .monitors()
+            .into_iter()
+            .filter(|monitor| overlap(monitor) > 0.0)
+            .max_by(|a, b| {
+                overlap(a)
+                    .partial_cmp(&overlap(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
     }
 
     pub fn handle_complete_notify(&self, event: &CompleteNotifyEvent) -> Result<(), Error> {
@@ -1447,6 +1681,30 @@ impl PresentData {
     }
 }
 
+// The `_NET_WM_MOVERESIZE` directions of the EWMH spec.
+const _NET_WM_MOVERESIZE_SIZE_TOPLEFT: u32 = 0;
+const _NET_WM_MOVERESIZE_SIZE_TOP: u32 = 1;
+const _NET_WM_MOVERESIZE_SIZE_TOPRIGHT: u32 = 2;
+const _NET_WM_MOVERESIZE_SIZE_RIGHT: u32 = 3;
+const _NET_WM_MOVERESIZE_SIZE_BOTTOMRIGHT: u32 = 4;
+const _NET_WM_MOVERESIZE_SIZE_BOTTOM: u32 = 5;
+const _NET_WM_MOVERESIZE_SIZE_BOTTOMLEFT: u32 = 6;
+const _NET_WM_MOVERESIZE_SIZE_LEFT: u32 = 7;
+const _NET_WM_MOVERESIZE_MOVE: u32 = 8;
+
+fn move_resize_direction(edge: ResizeEdge) -> u32 {
+    match edge {
+        ResizeEdge::TopLeft => _NET_WM_MOVERESIZE_SIZE_TOPLEFT,
+        ResizeEdge::Top => _NET_WM_MOVERESIZE_SIZE_TOP,
+        ResizeEdge::TopRight => _NET_WM_MOVERESIZE_SIZE_TOPRIGHT,
+        ResizeEdge::Right => _NET_WM_MOVERESIZE_SIZE_RIGHT,
+        ResizeEdge::BottomRight => _NET_WM_MOVERESIZE_SIZE_BOTTOMRIGHT,
+        ResizeEdge::Bottom => _NET_WM_MOVERESIZE_SIZE_BOTTOM,
+        ResizeEdge::BottomLeft => _NET_WM_MOVERESIZE_SIZE_BOTTOMLEFT,
+        ResizeEdge::Left => _NET_WM_MOVERESIZE_SIZE_LEFT,
+    }
+}
+
 // Converts from, e.g., the `details` field of `xcb::xproto::ButtonPressEvent`
 fn mouse_button(button: u8) -> MouseButton {
     match button {
@@ -1483,6 +1741,21 @@ fn mouse_buttons(mods: u16) -> MouseButtons {
     buttons
 }
 
+fn make_key_event(hw_keycode: u8, mods: u16, state: KeyState, repeat: bool) -> KeyEvent {
+    let code = keycodes::hardware_keycode_to_code(hw_keycode);
+    let mods = key_mods(mods);
+    KeyEvent {
+        code,
+        scancode: hw_keycode as u32,
+        key: keycodes::code_to_key(code, mods),
+        mods,
+        location: keycodes::code_to_location(code),
+        state,
+        repeat,
+        is_composing: false,
+    }
+}
+
 // Extracts the keyboard modifiers from, e.g., the `state` field of
 // `xcb::xproto::ButtonPressEvent`
 fn key_mods(mods: u16) -> Modifiers {
@@ -1565,6 +1838,14 @@ pub(crate) struct WindowHandle {
     window: Weak<Window>,
 }
 
+impl crate::platform::linux::WindowHandleExt for crate::WindowHandle {
+    fn open_uri(&self, uri: &str) {
+        if let Err(err) = crate::backend::shared::open_uri(uri) {
+            warn!("Failed to open {}: {}", uri, err);
+        }
+    }
+}
+
 impl WindowHandle {
     fn new(id: u32, window: Weak<Window>) -> WindowHandle {
         WindowHandle { id, window }
@@ -1619,13 +1900,42 @@ impl WindowHandle {
         }
     }
 
+    pub fn get_restored_rect(&self) -> Rect {
+        if let Some(w) = self.window.upgrade() {
+            w.restored_rect
+                .get()
+                .unwrap_or_else(|| Rect::from_origin_size(w.get_position(), w.size().size_dp()))
+        } else {
+            error!("Window {} has already been dropped", self.id);
+            Rect::ZERO
+        }
+    }
+
     pub fn content_insets(&self) -> Insets {
         warn!("WindowHandle::content_insets unimplemented for X11 backend.");
         Insets::ZERO
     }
 
-    pub fn set_level(&self, _level: WindowLevel) {
-        warn!("WindowHandle::set_level unimplemented for X11 backend.");
+    pub fn set_text_rendering(&self, options: TextRenderingOptions) {
+        crate::backend::shared::set_text_rendering(options);
+        self.invalidate();
+    }
+
+    pub fn set_level(&self, level: WindowLevel) {
+        if let Some(w) = self.window.upgrade() {
+            match level {
+                WindowLevel::AppWindow | WindowLevel::Floating | WindowLevel::Utility => {
+                    let above = level != WindowLevel::AppWindow;
+                    w.change_net_wm_state(above, w.app.atoms()._NET_WM_STATE_ABOVE);
+                }
+                _ => warn!(
+                    "WindowHandle::set_level({:?}) unimplemented for X11 backend.",
+                    level
+                ),
+            }
+        } else {
+            error!("Window {} has already been dropped", self.id);
+        }
     }
 
     pub fn set_size(&self, size: Size) {
@@ -1649,13 +1959,76 @@ impl WindowHandle {
         warn!("WindowHandle::set_window_state is currently unimplemented for X11 backend.");
     }
 
+    pub fn set_fullscreen(&self, fullscreen: bool, monitor: Option<&Monitor>) {
+        if let Some(w) = self.window.upgrade() {
+            w.set_fullscreen(fullscreen, monitor.map(|monitor| monitor.virtual_rect()));
+        } else {
+            error!("Window {} has already been dropped", self.id);
+        }
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        if let Some(w) = self.window.upgrade() {
+            match w.is_fullscreen() {
+                Ok(fullscreen) => fullscreen,
+                Err(e) => {
+                    error!("Failed to get the window state: {}", e);
+                    false
+                }
+            }
+        } else {
+            false
+        }
+    }
+
     pub fn get_window_state(&self) -> window::WindowState {
-        warn!("WindowHandle::get_window_state is currently unimplemented for X11 backend.");
-        window::WindowState::Restored
+        if let Some(w) = self.window.upgrade() {
+            match w.window_state() {
+                Ok(state) => state,
+                Err(e) => {
+                    error!("Failed to get the window state: {}", e);
+                    window::WindowState::Restored
+                }
+            }
+        } else {
+            window::WindowState::Restored
+        }
+    }
+
+    pub fn handle_titlebar(&self, val: bool) {
+        if let Some(w) = self.window.upgrade() {
+            w.handle_titlebar.set(val);
+        } else {
+            error!("Window {} has already been dropped", self.id);
+        }
+    }
+
+    pub fn set_maximize_button(&self, _rect: Option<Rect>) {
+        warn!("WindowHandle::set_maximize_button is currently unimplemented for X11 backend.");
     }
 
-    pub fn handle_titlebar(&self, _val: bool) {
-        warn!("WindowHandle::handle_titlebar is currently unimplemented for X11 backend.");
+    pub fn begin_move_drag(&self) {
+        if let Some(w) = self.window.upgrade() {
+            w.begin_move_resize(_NET_WM_MOVERESIZE_MOVE);
+        } else {
+            error!("Window {} has already been dropped", self.id);
+        }
+    }
+
+    pub fn begin_resize_drag(&self, edge: ResizeEdge) {
+        if let Some(w) = self.window.upgrade() {
+            w.begin_move_resize(move_resize_direction(edge));
+        } else {
+            error!("Window {} has already been dropped", self.id);
+        }
+    }
+
+    pub fn set_dark_titlebar(&self, _dark: bool) {
+        warn!("WindowHandle::set_dark_titlebar is currently unimplemented for X11 backend.");
+    }
+
+    pub fn set_corners(&self, _corners: WindowCorners) {
+        warn!("WindowHandle::set_corners is currently unimplemented for X11 backend.");
     }
 
     pub fn bring_to_front_and_focus(&self) {
@@ -1798,7 +2171,7 @@ impl WindowHandle {
         })
     }
 
-    pub fn get_scale(&self) -> Result<Scale, ShellError> {
+    pub fn get_scale(&self) -> Result<Scale, crate::Error> {
         if let Some(w) = self.window.upgrade() {
             Ok(w.get_scale()?)
         } else {
@@ -1806,6 +2179,10 @@ impl WindowHandle {
             Ok(Scale::new(1.0, 1.0))
         }
     }
+
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        self.window.upgrade()?.monitor()
+    }
 }
 
 #[cfg(feature = "raw-win-handle")]
@@ -1837,36 +2214,7 @@ fn make_cursor(
 ) -> Result<Cursor, ReplyOrIdError> {
     // BEGIN: Lots of code just to get the image into a RENDER Picture
 
-    fn multiply_alpha(color: u8, alpha: u8) -> u8 {
-        let (color, alpha) = (u16::from(color), u16::from(alpha));
-        let temp = color * alpha + 0x80u16;
-        ((temp + (temp >> 8)) >> 8) as u8
-    }
-
-    // No idea how to sanely get the pixel values, so I'll go with 'insane':
-    // Iterate over all pixels and build an array
-    let pixels = desc
-        .image
-        .pixel_colors()
-        .flat_map(|row| {
-            row.flat_map(|color| {
-                let (r, g, b, a) = color.as_rgba8();
-                // RENDER wants premultiplied alpha
-                let (r, g, b) = (
-                    multiply_alpha(r, a),
-                    multiply_alpha(g, a),
-                    multiply_alpha(b, a),
-                );
-                // piet gives us rgba in this order, the server expects an u32 with argb.
-                let (b0, b1, b2, b3) = match byte_order {
-                    X11ImageOrder::LSB_FIRST => (b, g, r, a),
-                    _ => (a, r, g, b),
-                };
-                // TODO Ownership and flat_map don't go well together :-(
-                vec![b0, b1, b2, b3]
-            })
-        })
-        .collect::<Vec<u8>>();
+    let pixels = super::util::premultiplied_argb32(&desc.image, byte_order);
     let width = desc.image.width().try_into().expect("Invalid cursor width");
     let height = desc
         .image
@@ -1904,3 +2252,18 @@ fn make_cursor(
 
     Ok(Cursor::Custom(CustomCursor(cursor)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::Code;
+
+    #[test]
+    fn key_event_has_the_keycode() {
+        // 38 is the keycode of the A key with the evdev driver.
+        let event = make_key_event(38, 0, KeyState::Down, true);
+        assert_eq!(event.scancode, 38);
+        assert_eq!(event.code, Code::KeyA);
+        assert!(event.repeat);
+    }
+}
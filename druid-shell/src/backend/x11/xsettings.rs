@@ -0,0 +1,191 @@
+// Copyright 2020 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading the settings that an XSETTINGS manager publishes, like the theme name.
+//!
+//! https://specifications.freedesktop.org/xsettings-spec/xsettings-latest.html
+
+use std::convert::TryInto;
+
+const TYPE_INTEGER: u8 = 0;
+const TYPE_STRING: u8 = 1;
+const TYPE_COLOR: u8 = 2;
+
+/// The value of the string setting called `name` in `data`, the contents of an
+/// `_XSETTINGS_SETTINGS` property.
+pub(crate) fn string(data: &[u8], name: &str) -> Option<String> {
+    let mut reader = Reader::new(data)?;
+    let count = reader.u32()?;
+    for _ in 0..count {
+        let ty = reader.u8()?;
+        reader.skip(1)?;
+        let name_len = reader.u16()? as usize;
+        let setting_name = reader.padded(name_len)?;
+        // The serial of the last change to this setting.
+        reader.skip(4)?;
+        match ty {
+            TYPE_INTEGER => reader.skip(4)?,
+            TYPE_STRING => {
+                let len = reader.u32()? as usize;
+                let value = reader.padded(len)?;
+                if setting_name == name.as_bytes() {
+                    return String::from_utf8(value.to_vec()).ok();
+                }
+            }
+            TYPE_COLOR => reader.skip(8)?,
+            _ => return None,
+        }
+    }
+    None
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    /// Reads the byte order, and skips the header up to the number of settings.
+    fn new(data: &'a [u8]) -> Option<Reader<'a>> {
+        let big_endian = match data.first()? {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        let mut reader = Reader { data, big_endian };
+        // The byte order, padding, and the serial of the settings.
+        reader.skip(8)?;
+        Some(reader)
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    /// Takes `len` bytes and skips the padding up to the next multiple of 4.
+    fn padded(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.take(len)?;
+        self.skip((4 - len % 4) % 4)?;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.take(2)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.take(4)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    fn settings(big_endian: bool) -> Vec<u8> {
+        let u16_bytes = |n: usize| {
+            let n = n as u16;
+            if big_endian {
+                n.to_be_bytes()
+            } else {
+                n.to_le_bytes()
+            }
+        };
+        let u32_bytes = |n: usize| {
+            let n = n as u32;
+            if big_endian {
+                n.to_be_bytes()
+            } else {
+                n.to_le_bytes()
+            }
+        };
+        let padded = |data: &mut Vec<u8>, bytes: &[u8]| {
+            data.extend(bytes);
+            data.resize((data.len() + 3) / 4 * 4, 0);
+        };
+
+        let mut data = vec![big_endian as u8, 0, 0, 0];
+        data.extend(&u32_bytes(7));
+        data.extend(&u32_bytes(3));
+
+        let name = b"Xft/Antialias";
+        data.extend(&[TYPE_INTEGER, 0]);
+        data.extend(&u16_bytes(name.len()));
+        padded(&mut data, name);
+        data.extend(&u32_bytes(0));
+        data.extend(&u32_bytes(1));
+
+        let name = b"Gtk/Accent";
+        data.extend(&[TYPE_COLOR, 0]);
+        data.extend(&u16_bytes(name.len()));
+        padded(&mut data, name);
+        data.extend(&u32_bytes(0));
+        data.extend(&[0xff; 8]);
+
+        let (name, value) = (b"Net/ThemeName", b"Adwaita-dark");
+        data.extend(&[TYPE_STRING, 0]);
+        data.extend(&u16_bytes(name.len()));
+        padded(&mut data, name);
+        data.extend(&u32_bytes(2));
+        data.extend(&u32_bytes(value.len()));
+        padded(&mut data, value);
+        data
+    }
+
+    #[test]
+    fn reads_string_settings() {
+        for &big_endian in &[false, true] {
+            let data = settings(big_endian);
+            assert_eq!(
+                string(&data, "Net/ThemeName").as_deref(),
+                Some("Adwaita-dark")
+            );
+            assert_eq!(string(&data, "Net/IconThemeName"), None);
+            // Integers are not strings.
+            assert_eq!(string(&data, "Xft/Antialias"), None);
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_settings() {
+        let data = settings(false);
+        for len in 0..data.len() {
+            assert_eq!(string(&data[..len], "Net/ThemeName"), None);
+        }
+    }
+}
@@ -13,7 +13,11 @@
 // limitations under the License.
 
 //! Interacting with the system pasteboard/clipboard.
+use std::path::PathBuf;
+
 pub use crate::backend::clipboard as backend;
+use crate::error::{ShellError, ShellOperation};
+use crate::piet::ImageBuf;
 
 /// A handle to the system clipboard.
 ///
@@ -25,6 +29,13 @@ pub use crate::backend::clipboard as backend;
 /// Copying and pasting text is simple, using [`Clipboard::put_string`] and
 /// [`Clipboard::get_string`]. If this is all you need, you're in luck.
 ///
+/// # Images, HTML and files
+///
+/// Images, HTML and lists of files have methods of their own, which take
+/// care of the platform's encoding of the data: [`Clipboard::put_image`],
+/// [`Clipboard::put_html`] and [`Clipboard::put_files`], and their `get_`
+/// counterparts.
+///
 /// # Advanced usage
 ///
 /// When working with data more complicated than plaintext, you will generally
@@ -60,8 +71,10 @@ pub use crate::backend::clipboard as backend;
 /// If you wish to put text on the clipboard in addition to other formats,
 /// take special care to use `ClipboardFormat::TEXT` as the [`FormatId`]. On
 /// windows, we treat this identifier specially, and make sure the data is
-/// encoded as a wide string; all other data going into and out of the
-/// clipboard is treated as an array of bytes.
+/// encoded as a wide string. `ClipboardFormat::HTML` is also special there;
+/// we wrap the HTML in the header the platform expects, and remove it again
+/// when reading. All other data going into and out of the clipboard is
+/// treated as an array of bytes.
 ///
 /// # Examples
 ///
@@ -71,7 +84,9 @@ pub use crate::backend::clipboard as backend;
 /// use druid_shell::{Application, Clipboard};
 ///
 /// let mut clipboard = Application::global().clipboard();
-/// clipboard.put_string("watch it there pal");
+/// if let Err(e) = clipboard.put_string("watch it there pal") {
+///     eprintln!("{}", e);
+/// }
 /// if let Some(contents) = clipboard.get_string() {
 ///     assert_eq!("what it there pal", contents.as_str());
 /// }
@@ -93,7 +108,7 @@ pub use crate::backend::clipboard as backend;
 ///     ClipboardFormat::new(ClipboardFormat::PDF, make_pdf_data()),
 /// ];
 ///
-/// clipboard.put_formats(&formats);
+/// clipboard.put_formats(&formats).expect("failed to set the clipboard");
 ///
 /// # fn make_custom_data() -> Vec<u8> { unimplemented!() }
 /// # fn make_svg_data() -> Vec<u8> { unimplemented!() }
@@ -130,13 +145,26 @@ pub struct Clipboard(pub(crate) backend::Clipboard);
 
 impl Clipboard {
     /// Put a string onto the system clipboard.
-    pub fn put_string(&mut self, s: impl AsRef<str>) {
-        self.0.put_string(s);
+    ///
+    /// # Errors
+    ///
+    /// Errors if the clipboard could not be set, for instance because another
+    /// application is holding on to it.
+    pub fn put_string(&mut self, s: impl AsRef<str>) -> Result<(), ShellError> {
+        self.0
+            .put_string(s)
+            .map_err(|e| ShellError::new(ShellOperation::Clipboard, e))
     }
 
     /// Put multi-format data on the system clipboard.
-    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) {
-        self.0.put_formats(formats)
+    ///
+    /// # Errors
+    ///
+    /// Errors if the clipboard could not be set.
+    pub fn put_formats(&mut self, formats: &[ClipboardFormat]) -> Result<(), ShellError> {
+        self.0
+            .put_formats(formats)
+            .map_err(|e| ShellError::new(ShellOperation::Clipboard, e))
     }
 
     /// Get a string from the system clipboard, if one is available.
@@ -144,6 +172,60 @@ impl Clipboard {
         self.0.get_string()
     }
 
+    /// Put an image onto the system clipboard.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the clipboard could not be set, or if the platform doesn't
+    /// support images on the clipboard.
+    pub fn put_image(&mut self, image: &ImageBuf) -> Result<(), ShellError> {
+        self.0
+            .put_image(image)
+            .map_err(|e| ShellError::new(ShellOperation::Clipboard, e))
+    }
+
+    /// Get an image from the system clipboard, if one is available.
+    pub fn get_image(&self) -> Option<ImageBuf> {
+        self.0.get_image()
+    }
+
+    /// Put HTML onto the system clipboard, along with a plain text version
+    /// for applications that don't accept HTML.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the clipboard could not be set.
+    pub fn put_html(&mut self, html: &str, text: &str) -> Result<(), ShellError> {
+        self.put_formats(&[
+            ClipboardFormat::new(ClipboardFormat::HTML, html),
+            text.into(),
+        ])
+    }
+
+    /// Get HTML from the system clipboard, if it is available.
+    pub fn get_html(&self) -> Option<String> {
+        self.get_format(ClipboardFormat::HTML)
+            .and_then(|data| String::from_utf8(data).ok())
+    }
+
+    /// Put a list of files onto the system clipboard, for pasting them in a
+    /// file manager.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the clipboard could not be set, or if the platform doesn't
+    /// support files on the clipboard.
+    pub fn put_files(&mut self, paths: &[PathBuf]) -> Result<(), ShellError> {
+        self.0
+            .put_files(paths)
+            .map_err(|e| ShellError::new(ShellOperation::Clipboard, e))
+    }
+
+    /// Get the list of files on the system clipboard, if there is one.
+    pub fn get_files(&self) -> Option<Vec<PathBuf>> {
+        self.0.get_files()
+    }
+
     /// Given a list of supported clipboard types, returns the supported type which has
     /// highest priority on the system clipboard, or `None` if no types are supported.
     pub fn preferred_format(&self, formats: &[FormatId]) -> Option<FormatId> {
@@ -220,6 +302,7 @@ cfg_if::cfg_if! {
             pub const PDF: &'static str = "com.adobe.pdf";
             pub const TEXT: &'static str = "public.utf8-plain-text";
             pub const SVG: &'static str = "public.svg-image";
+            pub const HTML: &'static str = "public.html";
         }
     } else {
         impl ClipboardFormat {
@@ -231,6 +314,14 @@ cfg_if::cfg_if! {
                     pub const TEXT: &'static str = "text/plain";
                 }
             }
+            cfg_if::cfg_if! {
+                if #[cfg(target_os = "windows")] {
+                    // The name of the registered CF_HTML format.
+                    pub const HTML: &'static str = "HTML Format";
+                } else {
+                    pub const HTML: &'static str = "text/html";
+                }
+            }
             pub const PDF: &'static str = "application/pdf";
             pub const SVG: &'static str = "image/svg+xml";
         }
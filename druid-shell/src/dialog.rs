@@ -14,14 +14,23 @@
 
 //! File open/save dialogs.
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+thread_local! {
+    /// The last directory chosen in dialogs, by the id passed to
+    /// [`FileDialogOptions::remember_directory`].
+    static RECENT_DIRECTORIES: RefCell<HashMap<String, PathBuf>> = RefCell::new(HashMap::new());
+}
+
 /// Information about the path to be opened or saved.
 ///
 /// This path might point to a file or a directory.
 #[derive(Debug, Clone)]
 pub struct FileInfo {
     pub(crate) path: PathBuf,
+    pub(crate) format: Option<FileSpec>,
 }
 
 /// Type of file dialog.
@@ -128,6 +137,7 @@ pub struct FileDialogOptions {
     pub(crate) allowed_types: Option<Vec<FileSpec>>,
     pub(crate) default_type: Option<FileSpec>,
     pub(crate) select_directories: bool,
+    pub(crate) select_files_and_directories: bool,
     pub(crate) packages_as_directories: bool,
     pub(crate) multi_selection: bool,
     pub(crate) default_name: Option<String>,
@@ -135,6 +145,7 @@ pub struct FileDialogOptions {
     pub(crate) title: Option<String>,
     pub(crate) button_text: Option<String>,
     pub(crate) starting_directory: Option<PathBuf>,
+    pub(crate) remember_id: Option<String>,
 }
 
 /// A description of a filetype, for specifiying allowed types in a file dialog.
@@ -167,10 +178,54 @@ pub struct FileSpec {
 }
 
 impl FileInfo {
+    /// The info for a path chosen in a dialog with the given options.
+    ///
+    /// `format` is the file type chosen in the dialog, if the platform tells us.
+    /// This also remembers the directory of the path, if the options ask for it.
+    pub(crate) fn chosen(
+        path: PathBuf,
+        format: Option<FileSpec>,
+        options: &FileDialogOptions,
+    ) -> FileInfo {
+        if let Some(id) = &options.remember_id {
+            let dir = if options.select_directories {
+                Some(path.as_path())
+            } else {
+                path.parent()
+            };
+            if let Some(dir) = dir {
+                RECENT_DIRECTORIES.with(|dirs| dirs.borrow_mut().insert(id.clone(), dir.into()));
+            }
+        }
+        let format = format.or_else(|| {
+            let ext = path.extension()?.to_str()?;
+            let allowed_types = options.allowed_types.as_ref()?;
+            // Prefer the default type, if several types share the extension.
+            options
+                .default_type
+                .iter()
+                .chain(allowed_types)
+                .find(|spec| spec.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+                .copied()
+        });
+        FileInfo { path, format }
+    }
+
     /// Returns the underlying path.
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Returns the file type chosen from the [`allowed_types`] of the dialog.
+    ///
+    /// Where the user can't pick a type in the dialog, as on macOS, this is
+    /// the allowed type that matches the extension of the path. It is `None`
+    /// if the dialog had no allowed types, or if no type matches.
+    ///
+    /// [`allowed_types`]: FileDialogOptions::allowed_types
+    pub fn format(&self) -> Option<FileSpec> {
+        self.format
+    }
 }
 
 impl FileDialogOptions {
@@ -193,6 +248,16 @@ impl FileDialogOptions {
         self
     }
 
+    /// Set both files and directories to be selectable.
+    ///
+    /// This is only relevant for open dialogs.
+    ///
+    /// Only macOS supports this; the other platforms fall back to *files mode*.
+    pub fn select_files_and_directories(mut self) -> Self {
+        self.select_files_and_directories = true;
+        self
+    }
+
     /// Set [packages] to be treated as directories instead of files.
     ///
     /// This allows for writing more universal cross-platform code at the cost of user experience.
@@ -284,6 +349,55 @@ impl FileDialogOptions {
         self.starting_directory = Some(path.into());
         self
     }
+
+    /// Remember the directory of the chosen path under `id`, and start later dialogs
+    /// with the same `id` in that directory.
+    ///
+    /// Use different ids for dialogs that deal with different kinds of files, like
+    /// importing images and opening projects. A directory set with
+    /// [`force_starting_directory`] takes precedence.
+    ///
+    /// The directories are only remembered while the application runs. To keep them
+    /// across runs, save the [`remembered_directories`] on exit and pass them to
+    /// [`set_remembered_directory`] on the next start.
+    ///
+    /// [`force_starting_directory`]: #method.force_starting_directory
+    /// [`remembered_directories`]: FileDialogOptions::remembered_directories
+    /// [`set_remembered_directory`]: FileDialogOptions::set_remembered_directory
+    pub fn remember_directory(mut self, id: impl Into<String>) -> Self {
+        self.remember_id = Some(id.into());
+        self
+    }
+
+    /// The directory remembered for the id of these options, if any.
+    pub(crate) fn remembered_directory(&self) -> Option<PathBuf> {
+        let id = self.remember_id.as_ref()?;
+        RECENT_DIRECTORIES.with(|dirs| dirs.borrow().get(id).cloned())
+    }
+
+    /// The directories remembered for the ids passed to [`remember_directory`],
+    /// sorted by id.
+    ///
+    /// [`remember_directory`]: FileDialogOptions::remember_directory
+    pub fn remembered_directories() -> Vec<(String, PathBuf)> {
+        let mut dirs: Vec<_> = RECENT_DIRECTORIES.with(|dirs| {
+            dirs.borrow()
+                .iter()
+                .map(|(id, dir)| (id.clone(), dir.clone()))
+                .collect()
+        });
+        dirs.sort();
+        dirs
+    }
+
+    /// Remember `dir` for `id`, as if it was chosen in a dialog with that id.
+    ///
+    /// This is for restoring the [`remembered_directories`] of a previous run.
+    ///
+    /// [`remembered_directories`]: FileDialogOptions::remembered_directories
+    pub fn set_remembered_directory(id: impl Into<String>, dir: impl Into<PathBuf>) {
+        RECENT_DIRECTORIES.with(|dirs| dirs.borrow_mut().insert(id.into(), dir.into()));
+    }
 }
 
 impl FileSpec {
@@ -299,3 +413,58 @@ impl FileSpec {
         FileSpec { name, extensions }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    const RUST: FileSpec = FileSpec::new("Rust", &["rs"]);
+
+    #[test]
+    fn chosen_format_matches_the_extension() {
+        let options = FileDialogOptions::new()
+            .allowed_types(vec![FileSpec::TEXT, RUST])
+            .default_type(RUST);
+        let info = FileInfo::chosen(PathBuf::from("/src/LIB.RS"), None, &options);
+        assert_eq!(info.format(), Some(RUST));
+
+        // the platform's choice wins
+        let info = FileInfo::chosen(PathBuf::from("/src/lib.rs"), Some(FileSpec::TEXT), &options);
+        assert_eq!(info.format(), Some(FileSpec::TEXT));
+
+        let info = FileInfo::chosen(PathBuf::from("/src/lib.c"), None, &options);
+        assert_eq!(info.format(), None);
+    }
+
+    #[test]
+    fn chosen_path_is_remembered() {
+        let options = FileDialogOptions::new().remember_directory("sources");
+        assert_eq!(options.remembered_directory(), None);
+
+        FileInfo::chosen(PathBuf::from("/src/lib.rs"), None, &options);
+        assert_eq!(options.remembered_directory(), Some(PathBuf::from("/src")));
+
+        // a chosen directory is remembered itself
+        let options = options.select_directories();
+        FileInfo::chosen(PathBuf::from("/examples"), None, &options);
+        assert_eq!(
+            FileDialogOptions::remembered_directories(),
+            vec![("sources".to_string(), PathBuf::from("/examples"))]
+        );
+
+        // without an id, nothing is remembered
+        FileInfo::chosen(PathBuf::from("/tmp/a.rs"), None, &FileDialogOptions::new());
+        assert_eq!(FileDialogOptions::remembered_directories().len(), 1);
+    }
+
+    #[test]
+    fn restored_directories_are_used() {
+        FileDialogOptions::set_remembered_directory("images", "/pictures");
+        let options = FileDialogOptions::new().remember_directory("images");
+        assert_eq!(
+            options.remembered_directory(),
+            Some(PathBuf::from("/pictures"))
+        );
+    }
+}
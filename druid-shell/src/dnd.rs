@@ -0,0 +1,58 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Common types for drag and drop from other applications.
+
+use std::path::PathBuf;
+
+use crate::kurbo::Point;
+use crate::Modifiers;
+
+/// What is dragged onto a window from another application.
+///
+/// Which of these are filled in depends on what the other application
+/// offers; a file manager offers files, a browser offers URLs or text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DropData {
+    /// Paths of the dragged files.
+    pub files: Vec<PathBuf>,
+    /// The dragged text.
+    pub text: Option<String>,
+    /// Dragged URLs that aren't local files.
+    pub urls: Vec<String>,
+}
+
+/// A drag from another application moving over a window, or being dropped
+/// on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropEvent {
+    /// The location of the drag in [display points] in relation to the current window.
+    ///
+    /// [display points]: struct.Scale.html
+    pub pos: Point,
+    /// What is dragged.
+    pub data: DropData,
+    /// Keyboard modifiers at the time of the event.
+    ///
+    /// Not all platforms report the modifiers during a drag; they are empty
+    /// on those.
+    pub mods: Modifiers,
+}
+
+impl DropData {
+    /// Returns `true` if nothing is dragged that druid understands.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.text.is_none() && self.urls.is_empty()
+    }
+}
@@ -26,6 +26,10 @@ pub enum Error {
     ApplicationAlreadyExists,
     /// The window has already been destroyed.
     WindowDropped,
+    /// The operation is not supported on this platform.
+    Unsupported,
+    /// The hotkey is already registered, by this or another application.
+    HotKeyInUse,
     /// Platform specific error.
     Platform(backend::Error),
     /// Other miscellaneous error.
@@ -40,6 +44,8 @@ impl fmt::Display for Error {
             }
             Error::Platform(err) => fmt::Display::fmt(err, f),
             Error::WindowDropped => write!(f, "The window has already been destroyed."),
+            Error::Unsupported => write!(f, "The operation is not supported on this platform."),
+            Error::HotKeyInUse => write!(f, "The hotkey is already registered."),
             Error::Other(s) => write!(f, "{}", s),
         }
     }
@@ -58,3 +64,77 @@ impl From<backend::Error> for Error {
         Error::Platform(src)
     }
 }
+
+/// A kind of shell operation that can fail, as reported by a [`ShellError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShellOperation {
+    /// Creating a window.
+    CreateWindow,
+    /// Reading or writing the clipboard.
+    Clipboard,
+    /// Showing a file dialog.
+    FileDialog,
+    /// Registering a system-wide hotkey.
+    GlobalHotKey,
+    /// Opening a URL in another application.
+    OpenUrl,
+    /// Showing a path in the file manager.
+    RevealPath,
+}
+
+/// The failure of a shell operation.
+///
+/// Operations that depend on the system, such as creating a window or setting
+/// the clipboard, may fail on unusual setups. They report a `ShellError`
+/// instead of panicking, so that the application can carry on without them.
+#[derive(Debug, Clone)]
+pub struct ShellError {
+    operation: ShellOperation,
+    error: Error,
+}
+
+impl ShellError {
+    /// Create a new `ShellError` for a failed `operation`.
+    pub fn new(operation: ShellOperation, error: impl Into<Error>) -> ShellError {
+        ShellError {
+            operation,
+            error: error.into(),
+        }
+    }
+
+    /// The operation that failed.
+    pub fn operation(&self) -> ShellOperation {
+        self.operation
+    }
+
+    /// The underlying error.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+}
+
+impl fmt::Display for ShellOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ShellOperation::CreateWindow => write!(f, "creating a window"),
+            ShellOperation::Clipboard => write!(f, "accessing the clipboard"),
+            ShellOperation::FileDialog => write!(f, "showing a file dialog"),
+            ShellOperation::GlobalHotKey => write!(f, "registering a global hotkey"),
+            ShellOperation::OpenUrl => write!(f, "opening a URL"),
+            ShellOperation::RevealPath => write!(f, "revealing a path"),
+        }
+    }
+}
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Failed {}: {}", self.operation, self.error)
+    }
+}
+
+impl std::error::Error for ShellError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -18,6 +18,7 @@ use std::borrow::Borrow;
 
 use tracing::warn;
 
+use crate::common_util::Counter;
 use crate::{IntoKey, KbKey, KeyEvent, Modifiers};
 
 // TODO: fix docstring
@@ -118,6 +119,35 @@ impl HotKey {
     }
 }
 
+/// A token that identifies a system-wide hotkey.
+///
+/// See [`Application::register_global_hotkey`].
+///
+/// [`Application::register_global_hotkey`]: crate::Application::register_global_hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub struct GlobalHotKeyToken(u64);
+
+impl GlobalHotKeyToken {
+    /// A token that does not correspond to any hotkey.
+    pub const INVALID: GlobalHotKeyToken = GlobalHotKeyToken(0);
+
+    /// Create a new token.
+    pub fn next() -> GlobalHotKeyToken {
+        static COUNTER: Counter = Counter::new();
+        GlobalHotKeyToken(COUNTER.next())
+    }
+
+    /// Create a new token from a raw value.
+    pub const fn from_raw(id: u64) -> GlobalHotKeyToken {
+        GlobalHotKeyToken(id)
+    }
+
+    /// Get the raw value for a token.
+    pub const fn into_raw(self) -> u64 {
+        self.0
+    }
+}
+
 /// A platform-agnostic representation of keyboard modifiers, for command handling.
 ///
 /// This does one thing: it allows specifying hotkeys that use the Command key
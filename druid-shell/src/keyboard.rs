@@ -41,6 +41,17 @@ pub struct KeyEvent {
     pub key: KbKey,
     /// Physical key position.
     pub code: Code,
+    /// The platform's code for the physical key, or `0` if the platform
+    /// doesn't report one.
+    ///
+    /// This is the X11 keycode on Linux, the scan code on Windows (with
+    /// `0x100` added for extended keys), the virtual key code on macOS and
+    /// the legacy `keyCode` on the web, which doesn't report scan codes.
+    /// Unlike [`code`], it is also set for keys that have no [`Code`], which
+    /// makes it useful for keybinding editors.
+    ///
+    /// [`code`]: KeyEvent::code
+    pub scancode: u32,
     /// Location for keys with multiple instances on common keyboards.
     pub location: Location,
     /// Flags for pressed modifier keys.
@@ -83,6 +94,7 @@ impl KeyEvent {
         KeyEvent {
             key,
             code: Code::Unidentified,
+            scancode: 0,
             location: Location::Standard,
             state: KeyState::Down,
             mods,
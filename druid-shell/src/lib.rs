@@ -50,38 +50,51 @@ pub use raw_window_handle;
 #[macro_use]
 mod util;
 
+mod appearance;
 mod application;
 mod backend;
 mod clipboard;
 mod common_util;
 mod dialog;
+mod dnd;
 mod error;
 mod hotkey;
 mod keyboard;
 mod menu;
 mod mouse;
+mod notification;
+mod pen;
 mod region;
 mod scale;
 mod screen;
+mod touch;
+mod tray;
 mod window;
 
 pub mod platform;
 pub mod text;
 
+pub use appearance::SystemTheme;
 pub use application::{AppHandler, Application};
 pub use clipboard::{Clipboard, ClipboardFormat, FormatId};
 pub use common_util::Counter;
 pub use dialog::{FileDialogOptions, FileInfo, FileSpec};
-pub use error::Error;
-pub use hotkey::{HotKey, RawMods, SysMods};
+pub use dnd::{DropData, DropEvent};
+pub use error::{Error, ShellError, ShellOperation};
+pub use hotkey::{GlobalHotKeyToken, HotKey, RawMods, SysMods};
 pub use keyboard::{Code, IntoKey, KbKey, KeyEvent, KeyState, Location, Modifiers};
 pub use menu::Menu;
 pub use mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
+pub use notification::{DesktopNotification, NotificationToken};
+pub use pen::{PenEvent, PenPhase};
 pub use region::Region;
 pub use scale::{Scalable, Scale, ScaledArea};
 pub use screen::{Monitor, Screen};
+pub use touch::{TouchEvent, TouchId};
+pub use tray::TrayIcon;
 pub use window::{
-    FileDialogToken, IdleHandle, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowBuilder,
+    FileDialogToken, IdleHandle, IdleToken, ResizeEdge, TextAntialiasing, TextFieldToken,
+    TextHinting, TextRenderingOptions, TimerToken, WinHandler, WindowBuilder, WindowCorners,
     WindowHandle, WindowLevel, WindowState,
 };
 
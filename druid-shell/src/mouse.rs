@@ -54,6 +54,11 @@ pub struct MouseEvent {
     ///
     /// [WheelEvent]: https://w3c.github.io/uievents/#event-type-wheel
     pub wheel_delta: Vec2,
+    /// Whether `wheel_delta` comes from a device that scrolls smoothly, such
+    /// as a touchpad, rather than from the notches of a mouse wheel.
+    ///
+    /// This is always `false` for events that aren't wheel events.
+    pub precise_wheel: bool,
 }
 
 /// An indicator of which mouse button was pressed.
@@ -245,10 +250,11 @@ impl std::fmt::Debug for MouseButtons {
     }
 }
 
-//NOTE: this currently only contains cursors that are included by default on
-//both Windows and macOS. We may want to provide polyfills for various additional cursors.
+//NOTE: not every platform has every cursor. Where a platform lacks one, the backend
+//uses the closest cursor it has, as noted on each variant.
 /// Mouse cursors.
 #[derive(Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Cursor {
     /// The default arrow cursor.
     Arrow,
@@ -256,12 +262,54 @@ pub enum Cursor {
     IBeam,
     Pointer,
     Crosshair,
-
-    #[deprecated(note = "this will be removed in future because it is not available on windows")]
+    /// An open hand, for something that can be grabbed.
+    ///
+    /// Windows has no such cursor, and uses [`Cursor::Pointer`].
     OpenHand,
+    /// A closed hand, for something that is being dragged.
+    ///
+    /// Windows has no such cursor, and uses [`Cursor::Move`].
+    ClosedHand,
     NotAllowed,
+    /// A resize arrow pointing left and right, also used for moving a
+    /// column divider.
     ResizeLeftRight,
+    /// A resize arrow pointing up and down, also used for moving a row
+    /// divider.
     ResizeUpDown,
+    /// A resize arrow pointing to the top left and the bottom right corners.
+    ///
+    /// macOS has no public cursor for this, and uses [`Cursor::Crosshair`].
+    ResizeNwSe,
+    /// A resize arrow pointing to the top right and the bottom left corners.
+    ///
+    /// macOS has no public cursor for this, and uses [`Cursor::Crosshair`].
+    ResizeNeSw,
+    /// Arrows in all four directions, for something that can be moved.
+    ///
+    /// macOS has no such cursor, and uses [`Cursor::OpenHand`].
+    Move,
+    /// A magnifying glass with a plus sign.
+    ///
+    /// macOS and Windows have no such cursor, and use [`Cursor::Crosshair`].
+    ZoomIn,
+    /// A magnifying glass with a minus sign.
+    ///
+    /// macOS and Windows have no such cursor, and use [`Cursor::Crosshair`].
+    ZoomOut,
+    /// The application is busy, and doesn't take input.
+    ///
+    /// macOS has no such cursor, as the system shows one itself when an
+    /// application is unresponsive, and uses [`Cursor::Arrow`].
+    Wait,
+    /// The application is busy, but still takes input.
+    ///
+    /// macOS has no such cursor, and uses [`Cursor::Arrow`].
+    Progress,
+    /// Help is available for what is under the cursor.
+    ///
+    /// macOS has no such cursor, and uses [`Cursor::Arrow`].
+    Help,
     // The platform cursor should be small. Any image data that it uses should be shared (i.e.
     // behind an `Arc` or using a platform API that does the sharing).
     Custom(backend::window::CustomCursor),
@@ -291,16 +339,24 @@ impl CursorDesc {
 
 impl std::fmt::Debug for Cursor {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        #[allow(deprecated)]
         match self {
             Cursor::Arrow => write!(f, "Cursor::Arrow"),
             Cursor::IBeam => write!(f, "Cursor::IBeam"),
             Cursor::Pointer => write!(f, "Cursor::Pointer"),
             Cursor::Crosshair => write!(f, "Cursor::Crosshair"),
             Cursor::OpenHand => write!(f, "Cursor::OpenHand"),
+            Cursor::ClosedHand => write!(f, "Cursor::ClosedHand"),
             Cursor::NotAllowed => write!(f, "Cursor::NotAllowed"),
             Cursor::ResizeLeftRight => write!(f, "Cursor::ResizeLeftRight"),
             Cursor::ResizeUpDown => write!(f, "Cursor::ResizeUpDown"),
+            Cursor::ResizeNwSe => write!(f, "Cursor::ResizeNwSe"),
+            Cursor::ResizeNeSw => write!(f, "Cursor::ResizeNeSw"),
+            Cursor::Move => write!(f, "Cursor::Move"),
+            Cursor::ZoomIn => write!(f, "Cursor::ZoomIn"),
+            Cursor::ZoomOut => write!(f, "Cursor::ZoomOut"),
+            Cursor::Wait => write!(f, "Cursor::Wait"),
+            Cursor::Progress => write!(f, "Cursor::Progress"),
+            Cursor::Help => write!(f, "Cursor::Help"),
             Cursor::Custom(_) => write!(f, "Cursor::Custom"),
         }
     }
@@ -0,0 +1,91 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Desktop notifications.
+
+use crate::common_util::Counter;
+use crate::piet::ImageBuf;
+
+/// A notification shown by the desktop, outside of the application's windows.
+///
+/// Show it with [`Application::show_notification`]. When the user clicks the
+/// notification or one of its actions, the [`AppHandler`]'s
+/// [`notification_activated()`] is called.
+///
+/// [`Application::show_notification`]: crate::Application::show_notification
+/// [`AppHandler`]: crate::AppHandler
+/// [`notification_activated()`]: crate::AppHandler::notification_activated
+#[derive(Debug, Clone, Default)]
+pub struct DesktopNotification {
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) icon: Option<ImageBuf>,
+    pub(crate) actions: Vec<String>,
+}
+
+/// A token that uniquely identifies a shown notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub struct NotificationToken(u64);
+
+impl DesktopNotification {
+    /// Create a new notification with a title and a body text.
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> DesktopNotification {
+        DesktopNotification {
+            title: title.into(),
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Builder-style method to set the icon of the notification.
+    ///
+    /// Without an icon, platforms show the application's icon.
+    pub fn icon(mut self, icon: ImageBuf) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Builder-style method to add an action button to the notification.
+    ///
+    /// Actions are reported by their index, in the order they were added.
+    /// On Windows, they are only shown when the application has an
+    /// AppUserModelID, as set with [`set_app_user_model_id`].
+    ///
+    /// [`set_app_user_model_id`]: crate::platform::windows::ApplicationExt::set_app_user_model_id
+    pub fn action(mut self, label: impl Into<String>) -> Self {
+        self.actions.push(label.into());
+        self
+    }
+}
+
+impl NotificationToken {
+    /// A token that does not correspond to any notification.
+    pub const INVALID: NotificationToken = NotificationToken(0);
+
+    /// Create a new token.
+    pub fn next() -> NotificationToken {
+        static COUNTER: Counter = Counter::new();
+        NotificationToken(COUNTER.next())
+    }
+
+    /// Create a new token from a raw value.
+    pub const fn from_raw(id: u64) -> NotificationToken {
+        NotificationToken(id)
+    }
+
+    /// Get the raw value for a token.
+    pub const fn into_raw(self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Common types for representing pen (stylus) events
+
+use crate::kurbo::{Point, Vec2};
+use crate::Modifiers;
+
+/// What the pen did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PenPhase {
+    /// The pen touched the tablet.
+    Down,
+    /// The pen moved, either on the tablet or hovering over it.
+    Move,
+    /// The pen was lifted from the tablet.
+    Up,
+}
+
+/// Information about a pen event, from a graphics tablet or a screen that
+/// takes a stylus.
+///
+/// Platforms also turn the pen into mouse events, so that applications that
+/// don't handle pens can still be used with one. Those mouse events are
+/// delivered as well as the pen events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PenEvent {
+    /// The location of the pen in [display points] in relation to the current window.
+    ///
+    /// [display points]: struct.Scale.html
+    pub pos: Point,
+    /// Whether the pen touched down, moved, or was lifted.
+    pub phase: PenPhase,
+    /// How hard the pen is pressed, from `0.0` to `1.0`.
+    ///
+    /// This is `0.0` while the pen hovers, and `0.5` for pens that don't
+    /// report pressure while they touch the tablet.
+    pub pressure: f64,
+    /// The tilt of the pen in degrees, from `-90.0` to `90.0`; `x` is
+    /// positive when the pen leans to the right, and `y` is positive when it
+    /// leans towards the user.
+    ///
+    /// Platforms that report the tilt as a fraction of its largest value are
+    /// scaled to this range.
+    pub tilt: Vec2,
+    /// Whether the button on the side of the pen is held down.
+    pub barrel_button: bool,
+    /// Whether the eraser end of the pen is used.
+    pub eraser: bool,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+}
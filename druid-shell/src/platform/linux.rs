@@ -25,12 +25,25 @@ pub trait ApplicationExt {
     fn primary_clipboard(&self) -> Clipboard;
 }
 
+/// Linux specific extensions to [`WindowHandle`]
+///
+/// [`WindowHandle`]: crate::WindowHandle
+pub trait WindowHandleExt {
+    /// Open a URI, such as a web page or a file, in the user's preferred
+    /// application.
+    ///
+    /// When the application is sandboxed, for example by Flatpak or Snap,
+    /// this goes through the OpenURI desktop portal.
+    fn open_uri(&self, uri: &str);
+}
+
 #[cfg(test)]
 #[allow(unused_imports)]
 mod test {
-    use crate::Application;
+    use crate::{Application, WindowHandle};
 
     use super::*;
     use static_assertions as sa;
     sa::assert_impl_all!(Application: ApplicationExt);
+    sa::assert_impl_all!(WindowHandle: WindowHandleExt);
 }
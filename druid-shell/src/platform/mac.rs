@@ -28,13 +28,113 @@ pub trait ApplicationExt {
     ///
     /// On platforms with no global application menu, this has no effect.
     fn set_menu(&self, menu: crate::Menu);
+
+    /// Show the standard About panel, with the given application name,
+    /// version and credits.
+    ///
+    /// The panel shows the application's icon from its bundle.
+    fn show_about_panel(&self, name: &str, version: &str, credits: &str);
+}
+
+/// An item in a window's toolbar, or a button in a titlebar accessory.
+///
+/// When the item is clicked, [`WinHandler::command`] is called with its id,
+/// just like for a menu item.
+///
+/// [`WinHandler::command`]: crate::WinHandler::command
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolbarItem {
+    pub(crate) id: u32,
+    pub(crate) label: String,
+    pub(crate) symbol: Option<String>,
+    pub(crate) tooltip: Option<String>,
+}
+
+impl ToolbarItem {
+    /// Create a new item with the given command id and label.
+    pub fn new(id: u32, label: impl Into<String>) -> Self {
+        ToolbarItem {
+            id,
+            label: label.into(),
+            symbol: None,
+            tooltip: None,
+        }
+    }
+
+    /// Builder-style method to set the name of an SF Symbol to use as the
+    /// item's image.
+    ///
+    /// Symbols are only available on macOS 11 and later; on earlier versions
+    /// only the label is shown.
+    pub fn symbol(mut self, name: impl Into<String>) -> Self {
+        self.symbol = Some(name.into());
+        self
+    }
+
+    /// Builder-style method to set the item's tooltip.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+}
+
+/// The style of a window's toolbar, and how it is combined with the titlebar.
+///
+/// This is only supported on macOS 11 and later, and has no effect on
+/// earlier versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarStyle {
+    /// Let the system choose the style.
+    Automatic,
+    /// The toolbar is shown below the title.
+    Expanded,
+    /// Like `Expanded`, with the items centered, as in preference windows.
+    Preference,
+    /// The toolbar and the title share a single unified bar.
+    Unified,
+    /// Like `Unified`, with less space around the items.
+    UnifiedCompact,
+}
+
+/// Where a titlebar accessory is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessoryPosition {
+    /// At the trailing edge of the titlebar.
+    Right,
+    /// Below the titlebar, and the toolbar if there is one.
+    Bottom,
+}
+
+/// macOS specific extensions to [`WindowHandle`]
+///
+/// [`WindowHandle`]: crate::WindowHandle
+pub trait WindowHandleExt {
+    /// Set the items in the window's toolbar.
+    ///
+    /// This replaces any existing toolbar; an empty list removes it.
+    fn set_toolbar(&self, items: Vec<ToolbarItem>);
+
+    /// Set the style of the window's toolbar.
+    fn set_toolbar_style(&self, style: ToolbarStyle);
+
+    /// Set the buttons of an accessory view in the window's titlebar.
+    ///
+    /// This replaces any existing accessory; an empty list removes it.
+    fn set_titlebar_accessory(&self, items: Vec<ToolbarItem>, position: AccessoryPosition);
+
+    /// Set whether the titlebar is transparent, with the window's content
+    /// extending underneath it.
+    ///
+    /// The title text is hidden while the titlebar is transparent.
+    fn set_titlebar_transparent(&self, transparent: bool);
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Application;
+    use crate::{Application, WindowHandle};
 
     use super::*;
     use static_assertions as sa;
     sa::assert_impl_all!(Application: ApplicationExt);
+    sa::assert_impl_all!(WindowHandle: WindowHandleExt);
 }
@@ -19,3 +19,6 @@ pub mod linux;
 
 #[cfg(any(doc, target_os = "macos"))]
 pub mod mac;
+
+#[cfg(any(doc, target_os = "windows"))]
+pub mod windows;
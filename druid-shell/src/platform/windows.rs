@@ -0,0 +1,35 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windows specific extensions.
+
+pub trait ApplicationExt {
+    /// Set the AppUserModelID that identifies the application to the shell.
+    ///
+    /// Notifications are shown as toasts, with their action buttons, under
+    /// this id. Windows only shows toasts of ids that belong to a Start menu
+    /// shortcut, which the application's installer creates. Without an id,
+    /// notifications are shown as notification area balloons, which have no
+    /// action buttons.
+    fn set_app_user_model_id(&self, id: &str);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Application;
+
+    use super::*;
+    use static_assertions as sa;
+    sa::assert_impl_all!(Application: ApplicationExt);
+}
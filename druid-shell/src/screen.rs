@@ -16,6 +16,7 @@
 
 use crate::backend;
 use crate::kurbo::Rect;
+use crate::scale::Scale;
 use std::fmt;
 use std::fmt::Display;
 
@@ -32,15 +33,17 @@ pub struct Monitor {
     // https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-monitorinfo
     // Unsure about x11
     work_rect: Rect,
+    scale: Scale,
 }
 
 impl Monitor {
     #[allow(dead_code)]
-    pub(crate) fn new(primary: bool, rect: Rect, work_rect: Rect) -> Self {
+    pub(crate) fn new(primary: bool, rect: Rect, work_rect: Rect, scale: Scale) -> Self {
         Monitor {
             primary,
             rect,
             work_rect,
+            scale,
         }
     }
     /// Returns true if the monitor is the primary monitor.
@@ -59,6 +62,17 @@ impl Monitor {
     pub fn virtual_work_rect(&self) -> Rect {
         self.work_rect
     }
+
+    /// Returns the scale of the monitor, which windows on it are drawn at.
+    ///
+    /// Windows that move to a monitor with a different scale are told so
+    /// with [`WinHandler::scale`]. On X11 every monitor has the scale of the
+    /// `Xft.dpi` setting.
+    ///
+    /// [`WinHandler::scale`]: crate::WinHandler::scale
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
 }
 
 impl Display for Monitor {
@@ -267,6 +267,78 @@ impl Selection {
     }
 }
 
+/// The kind of text that a text field expects.
+///
+/// Input methods and on-screen keyboards can use this to choose a suitable
+/// layout, such as a keypad for numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InputPurpose {
+    /// Any text.
+    Text,
+    /// A number.
+    Number,
+    /// A phone number.
+    Phone,
+    /// An email address.
+    Email,
+    /// A URL.
+    Url,
+}
+
+/// Hints about a text field, for input methods and on-screen keyboards.
+///
+/// These are advisory; platforms that have no use for a hint ignore it.
+///
+/// ## Platform support
+///
+/// - On macOS 14 and later, autocorrection, completion and smart punctuation
+///   are turned off for fields that don't allow autocorrection, or expect
+///   something other than [`InputPurpose::Text`].
+/// - On Windows, the purpose picks the layout of the touch keyboard.
+/// - Other platforms don't have an input method integration yet, and ignore
+///   the hints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputHints {
+    purpose: InputPurpose,
+    autocorrect: bool,
+}
+
+impl InputHints {
+    /// Create hints for a text field that expects text of the given `purpose`.
+    pub const fn new(purpose: InputPurpose) -> Self {
+        InputHints {
+            purpose,
+            autocorrect: true,
+        }
+    }
+
+    /// Builder-style method to set whether the input method may correct,
+    /// complete or capitalize the text automatically.
+    ///
+    /// This should be turned off for things such as user names and codes.
+    pub const fn autocorrect(mut self, autocorrect: bool) -> Self {
+        self.autocorrect = autocorrect;
+        self
+    }
+
+    /// The kind of text the field expects.
+    pub fn purpose(&self) -> InputPurpose {
+        self.purpose
+    }
+
+    /// Whether the input method may correct the text automatically.
+    pub fn allows_autocorrect(&self) -> bool {
+        self.autocorrect
+    }
+}
+
+impl Default for InputHints {
+    fn default() -> Self {
+        InputHints::new(InputPurpose::Text)
+    }
+}
+
 /// A lock on a text field that allows the platform to retrieve state and make
 /// edits.
 ///
@@ -444,6 +516,14 @@ pub trait InputHandler {
     ///
     /// Requries a mutable lock.
     fn handle_action(&mut self, action: Action);
+
+    /// Hints about the kind of text the field expects.
+    ///
+    /// When these change, the platform should be notified with
+    /// [`Event::Reset`].
+    fn input_hints(&self) -> InputHints {
+        InputHints::default()
+    }
 }
 
 #[allow(dead_code)]
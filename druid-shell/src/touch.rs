@@ -0,0 +1,58 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Common types for representing touch events
+
+use crate::kurbo::Point;
+use crate::Modifiers;
+
+/// Identifies one contact with a touch screen, from when it touches down
+/// until it is lifted.
+///
+/// Ids are only unique among the contacts that are down at the same time;
+/// the platform may reuse the id of a lifted contact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub struct TouchId(u64);
+
+/// Information about a touch event.
+///
+/// Platforms also turn the first contact into mouse events, so that
+/// applications that don't handle touch can still be used with a touch
+/// screen, unless the handler handled the contact when it touched down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchEvent {
+    /// The contact that this event belongs to.
+    pub id: TouchId,
+    /// The location of the contact in [display points] in relation to the current window.
+    ///
+    /// [display points]: struct.Scale.html
+    pub pos: Point,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+    /// Whether this is the first contact of the gesture, the one the
+    /// platform turns into mouse events.
+    pub primary: bool,
+}
+
+impl TouchId {
+    /// Create an id from a raw value.
+    pub const fn from_raw(id: u64) -> TouchId {
+        TouchId(id)
+    }
+
+    /// Get the raw value of the id.
+    pub const fn into_raw(self) -> u64 {
+        self.0
+    }
+}
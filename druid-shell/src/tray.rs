@@ -0,0 +1,70 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Icons in the system tray.
+
+use crate::backend::tray as backend;
+use crate::error::Error;
+use crate::menu::Menu;
+use crate::piet::ImageBuf;
+
+/// An icon in the system tray.
+///
+/// This is the notification area on Windows, the status bar on macOS and a
+/// status icon on Linux. Clicking the icon calls the [`AppHandler`]'s
+/// [`tray_activated()`], and selecting an item of its menu calls
+/// [`command()`] with the item's id.
+///
+/// The icon is removed from the tray when the `TrayIcon` is dropped.
+///
+/// [`AppHandler`]: crate::AppHandler
+/// [`tray_activated()`]: crate::AppHandler::tray_activated
+/// [`command()`]: crate::AppHandler::command
+pub struct TrayIcon(backend::TrayIcon);
+
+impl TrayIcon {
+    /// Add a new icon to the system tray.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unsupported`] on platforms without a system tray.
+    pub fn new(icon: &ImageBuf) -> Result<TrayIcon, Error> {
+        backend::TrayIcon::new(icon).map(TrayIcon)
+    }
+
+    /// Replace the image shown in the tray.
+    pub fn set_icon(&mut self, icon: &ImageBuf) {
+        self.0.set_icon(icon)
+    }
+
+    /// Set the text shown when hovering over the icon.
+    pub fn set_tooltip(&mut self, tooltip: &str) {
+        self.0.set_tooltip(tooltip)
+    }
+
+    /// Set the menu shown when the icon is right-clicked.
+    ///
+    /// On macOS the menu is shown on any click, and [`tray_activated()`] is
+    /// no longer called. The menu should be created with [`Menu::new_for_popup`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unsupported`] on X11, which can't show the menus of
+    /// tray icons yet.
+    ///
+    /// [`tray_activated()`]: crate::AppHandler::tray_activated
+    pub fn set_menu(&mut self, menu: Menu) -> Result<(), Error> {
+        self.0.set_menu(menu.into_inner())
+    }
+}
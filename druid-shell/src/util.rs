@@ -33,16 +33,20 @@ fn current_thread_id() -> u64 {
 ///
 /// Panics when called from a non-main thread.
 pub fn assert_main_thread() {
-    let thread_id = current_thread_id();
-    let main_thread_id = MAIN_THREAD_ID.load(Ordering::Acquire);
-    if thread_id != main_thread_id {
+    if !is_main_thread() {
         panic!(
             "Main thread assertion failed {} != {}",
-            thread_id, main_thread_id
+            current_thread_id(),
+            MAIN_THREAD_ID.load(Ordering::Acquire)
         );
     }
 }
 
+/// Whether the current thread is the registered main thread.
+pub(crate) fn is_main_thread() -> bool {
+    current_thread_id() == MAIN_THREAD_ID.load(Ordering::Acquire)
+}
+
 /// Register the current thread as the main thread.
 ///
 /// # Panics
@@ -17,18 +17,23 @@
 use std::any::Any;
 use std::time::Duration;
 
+use crate::appearance::SystemTheme;
 use crate::application::Application;
 use crate::backend::window as backend;
 use crate::common_util::Counter;
 use crate::dialog::{FileDialogOptions, FileInfo};
-use crate::error::Error;
+use crate::dnd::DropEvent;
+use crate::error::{Error, ShellError};
 use crate::keyboard::KeyEvent;
 use crate::kurbo::{Insets, Point, Rect, Size};
 use crate::menu::Menu;
 use crate::mouse::{Cursor, CursorDesc, MouseEvent};
+use crate::pen::PenEvent;
 use crate::region::Region;
 use crate::scale::Scale;
+use crate::screen::Monitor;
 use crate::text::{Event, InputHandler};
+use crate::touch::TouchEvent;
 use piet_common::PietText;
 #[cfg(feature = "raw-win-handle")]
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
@@ -148,7 +153,7 @@ impl FileDialogToken {
 /// Levels in the window system - Z order for display purposes.
 /// Describes the purpose of a window and should be mapped appropriately to match platform
 /// conventions.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum WindowLevel {
     /// A top level app window.
     AppWindow,
@@ -158,6 +163,38 @@ pub enum WindowLevel {
     DropDown,
     /// A modal dialog
     Modal,
+    /// A top level window that stays above other windows, even those of other
+    /// applications - like a picture-in-picture video.
+    Floating,
+    /// A tool palette or inspector, that stays above the app's windows and
+    /// doesn't take focus from them when shown.
+    Utility,
+}
+
+/// How the corners of a window are drawn, where the platform supports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowCorners {
+    /// The platform's default.
+    Default,
+    /// Square corners.
+    Square,
+    /// Rounded corners.
+    Round,
+    /// Corners with a small radius.
+    RoundSmall,
+}
+
+/// An edge or corner of a window, for [`WindowHandle::begin_resize_drag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResizeEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
 }
 
 /// Contains the different states a Window can be in.
@@ -168,9 +205,63 @@ pub enum WindowState {
     Restored,
 }
 
+/// How the edges of glyphs are smoothed when text is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextAntialiasing {
+    /// Use the platform or system configuration.
+    Default,
+    /// No antialiasing; glyphs are drawn with hard edges.
+    None,
+    /// Grayscale antialiasing.
+    Grayscale,
+    /// Subpixel (ClearType-style) antialiasing, assuming an RGB pixel layout.
+    Subpixel,
+}
+
+/// How strongly glyph outlines are fitted to the pixel grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextHinting {
+    /// Use the platform or system configuration.
+    Default,
+    /// Do not hint glyph outlines.
+    None,
+    /// Hint vertically only, preserving glyph shapes as much as possible.
+    Slight,
+    /// A compromise between `Slight` and `Full`.
+    Medium,
+    /// Fit glyph outlines to the pixel grid as much as possible.
+    Full,
+}
+
+/// Options controlling the quality of text rendering in a window.
+///
+/// The default value leaves every option to the platform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextRenderingOptions {
+    /// The antialiasing mode.
+    pub antialiasing: TextAntialiasing,
+    /// The hinting mode.
+    pub hinting: TextHinting,
+    /// The gamma used when blending glyphs, or `None` for the platform default.
+    ///
+    /// This is only respected on Windows; Cairo and Core Text have no gamma
+    /// setting.
+    pub gamma: Option<f64>,
+}
+
+impl Default for TextRenderingOptions {
+    fn default() -> Self {
+        TextRenderingOptions {
+            antialiasing: TextAntialiasing::Default,
+            hinting: TextHinting::Default,
+            gamma: None,
+        }
+    }
+}
+
 /// A handle to a platform window object.
 #[derive(Clone, Default)]
-pub struct WindowHandle(backend::WindowHandle);
+pub struct WindowHandle(pub(crate) backend::WindowHandle);
 
 impl WindowHandle {
     /// Make this window visible.
@@ -201,21 +292,125 @@ impl WindowHandle {
         self.0.get_window_state()
     }
 
+    /// Make the window fullscreen on its current monitor, or leave fullscreen.
+    ///
+    /// This is the platform's fullscreen mode, where the window covers the
+    /// whole monitor without any decorations, and not just a maximized window.
+    /// On macOS, the window gets a space of its own.
+    ///
+    /// This is currently unimplemented on web.
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        self.0.set_fullscreen(fullscreen, None)
+    }
+
+    /// Make the window fullscreen on the given monitor.
+    ///
+    /// See [`set_fullscreen`] for details.
+    ///
+    /// [`set_fullscreen`]: WindowHandle::set_fullscreen
+    pub fn set_fullscreen_on(&self, monitor: &Monitor) {
+        self.0.set_fullscreen(true, Some(monitor))
+    }
+
+    /// Returns `true` if the window is fullscreen.
+    ///
+    /// Like [`get_window_state`], this reflects changes made by the user, for
+    /// instance with the platform's fullscreen button or shortcut.
+    ///
+    /// On Windows, a change requested with [`set_fullscreen`] is reflected
+    /// right away. On other platforms the change is made asynchronously, and
+    /// this returns the previous state until it is done.
+    ///
+    /// [`get_window_state`]: WindowHandle::get_window_state
+    /// [`set_fullscreen`]: WindowHandle::set_fullscreen
+    pub fn is_fullscreen(&self) -> bool {
+        self.0.is_fullscreen()
+    }
+
     /// Informs the system that the current location of the mouse should be treated as part of the
     /// window's titlebar. This can be used to implement a custom titlebar widget. Note that
     /// because this refers to the current location of the mouse, you should probably call this
     /// function in response to every relevant [`WinHandler::mouse_move`].
     ///
-    /// This is currently only implemented on Windows.
+    /// A left mouse down over the titlebar starts moving the window, like
+    /// [`begin_move_drag`] does. On Windows, the titlebar also gets the rest
+    /// of the system behavior, like maximizing on a double click.
+    ///
+    /// This is unimplemented on web, where the browser window can't be moved.
+    ///
+    /// [`begin_move_drag`]: WindowHandle::begin_move_drag
     pub fn handle_titlebar(&self, val: bool) {
         self.0.handle_titlebar(val);
     }
 
+    /// Start moving the window with the mouse, as if its titlebar was dragged.
+    ///
+    /// Call this in response to a [`WinHandler::mouse_down`] of the left
+    /// button; the move ends when the button is released. Moves started this
+    /// way get the platform's snapping behavior, like a drag of the system
+    /// titlebar does. This is meant for windows that draw their own titlebar,
+    /// see [`WindowBuilder::show_titlebar`].
+    ///
+    /// Unlike [`handle_titlebar`], which marks an area of the window ahead of
+    /// time, this starts the move from the mouse down being handled, so the
+    /// handler can decide whether to move the window when the click arrives.
+    ///
+    /// This is unimplemented on web, where the browser window can't be moved.
+    ///
+    /// [`handle_titlebar`]: WindowHandle::handle_titlebar
+    pub fn begin_move_drag(&self) {
+        self.0.begin_move_drag()
+    }
+
+    /// Start resizing the window with the mouse, by dragging the given edge.
+    ///
+    /// Like [`begin_move_drag`], this is meant to be called in response to a
+    /// [`WinHandler::mouse_down`], for windows without system decorations.
+    ///
+    /// This is unimplemented on web, where the browser window can't be resized.
+    ///
+    /// [`begin_move_drag`]: WindowHandle::begin_move_drag
+    pub fn begin_resize_drag(&self, edge: ResizeEdge) {
+        self.0.begin_resize_drag(edge)
+    }
+
     /// Set whether the window should show titlebar.
     pub fn show_titlebar(&self, show_titlebar: bool) {
         self.0.show_titlebar(show_titlebar)
     }
 
+    /// Informs the system where a custom maximize button is, in
+    /// [display points] relative to the window, so that it behaves like the
+    /// system's own button. Pass `None` when there is no such button.
+    ///
+    /// On Windows 11 this shows the snap layouts flyout when the mouse hovers
+    /// the button. Clicks on the button are handled by the system, which
+    /// maximizes or restores the window; mouse moves over it are still
+    /// delivered to [`WinHandler::mouse_move`], so that it can show a hover
+    /// state.
+    ///
+    /// This is currently only implemented on Windows.
+    ///
+    /// [display points]: crate::Scale
+    pub fn set_maximize_button(&self, rect: Option<Rect>) {
+        self.0.set_maximize_button(rect)
+    }
+
+    /// Set whether the system should draw the window's titlebar and frame in
+    /// dark colors.
+    ///
+    /// This is currently only implemented on Windows 10 and later.
+    pub fn set_dark_titlebar(&self, dark: bool) {
+        self.0.set_dark_titlebar(dark)
+    }
+
+    /// Set how the corners of the window are drawn.
+    ///
+    /// This is currently only implemented on Windows 11.
+    pub fn set_corners(&self, corners: WindowCorners) {
+        self.0.set_corners(corners)
+    }
+
     /// Sets the position of the window in [display points](crate::Scale), relative to the origin of the
     /// virtual screen.
     pub fn set_position(&self, position: impl Into<Point>) {
@@ -230,6 +425,19 @@ impl WindowHandle {
         self.0.get_position()
     }
 
+    /// Returns the position and size the window has when it is neither maximized, minimized nor
+    /// fullscreen, in the same coordinates as [`get_position`] and [`get_size`].
+    ///
+    /// This is where the window goes back to when it is restored, so it is what to save, along
+    /// with the [`WindowState`] and [`is_fullscreen`], to reopen the window as the user left it.
+    ///
+    /// [`get_position`]: WindowHandle::get_position
+    /// [`get_size`]: WindowHandle::get_size
+    /// [`is_fullscreen`]: WindowHandle::is_fullscreen
+    pub fn get_restored_rect(&self) -> Rect {
+        self.0.get_restored_rect()
+    }
+
     /// Returns the insets of the window content from its position and size in [display points].
     ///
     /// This is to account for any window system provided chrome, e.g. title bars. For example, if
@@ -273,10 +481,33 @@ impl WindowHandle {
     /// We do not currently have a getter method, mostly because the system's levels aren't a
     /// perfect one-to-one map to `druid_shell`'s levels. A getter method may be added in the
     /// future.
+    ///
+    /// Switching between [`AppWindow`], [`Floating`] and [`Utility`] works on open windows; the
+    /// other levels should be set with [`WindowBuilder::set_level`] instead.
+    ///
+    /// [`AppWindow`]: WindowLevel::AppWindow
+    /// [`Floating`]: WindowLevel::Floating
+    /// [`Utility`]: WindowLevel::Utility
     pub fn set_level(&self, level: WindowLevel) {
         self.0.set_level(level)
     }
 
+    /// Set the [`TextRenderingOptions`] used when drawing text in this window.
+    ///
+    /// The options take effect the next time the window is painted.
+    ///
+    /// On Linux, text rendering is configured through fontconfig, which is
+    /// shared by the whole process; this affects all windows. Gamma is ignored.
+    ///
+    /// On macOS, Core Text doesn't hint glyph outlines, so the hinting modes
+    /// only choose between placing glyphs on fractions of pixels (`None` and
+    /// `Slight`) and on whole pixels (`Medium` and `Full`). Gamma is ignored.
+    ///
+    /// This is currently a no-op on the web.
+    pub fn set_text_rendering(&self, options: TextRenderingOptions) {
+        self.0.set_text_rendering(options)
+    }
+
     /// Bring this window to the front of the window stack and give it focus.
     pub fn bring_to_front_and_focus(&self) {
         self.0.bring_to_front_and_focus()
@@ -419,6 +650,14 @@ impl WindowHandle {
     pub fn get_scale(&self) -> Result<Scale, Error> {
         self.0.get_scale().map_err(Into::into)
     }
+
+    /// Get the monitor the window is on.
+    ///
+    /// For windows spanning several monitors, this is the one holding most of
+    /// the window. Returns `None` if the window isn't shown yet, or on the web.
+    pub fn get_monitor(&self) -> Option<Monitor> {
+        self.0.get_monitor()
+    }
 }
 
 #[cfg(feature = "raw-win-handle")]
@@ -586,6 +825,17 @@ pub trait WinHandler {
     #[allow(unused_variables)]
     fn open_file(&mut self, token: FileDialogToken, file: Option<FileInfo>) {}
 
+    /// Called when an operation on this window that completes later, such as
+    /// showing a file dialog, failed.
+    ///
+    /// The operation's usual callback is still called afterwards; for a file
+    /// dialog, that is [`open_file`] or [`save_as`] with no file.
+    ///
+    /// [`open_file`]: WinHandler::open_file
+    /// [`save_as`]: WinHandler::save_as
+    #[allow(unused_variables)]
+    fn shell_error(&mut self, error: ShellError) {}
+
     /// Called on a key down event.
     ///
     /// Return `true` if the event is handled.
@@ -659,6 +909,64 @@ pub trait WinHandler {
     /// Called when the mouse cursor has left the application window
     fn mouse_leave(&mut self) {}
 
+    /// Called when a contact touches down on a touch screen.
+    ///
+    /// Return `true` if the event is handled. The platform then doesn't turn
+    /// the contact into mouse events, until it is lifted.
+    ///
+    /// Only the GTK and Windows backends deliver touch events so far.
+    #[allow(unused_variables)]
+    fn touch_down(&mut self, event: &TouchEvent) -> bool {
+        false
+    }
+
+    /// Called when a contact that is down moves.
+    ///
+    /// Return `true` if the event is handled.
+    #[allow(unused_variables)]
+    fn touch_move(&mut self, event: &TouchEvent) -> bool {
+        false
+    }
+
+    /// Called when a contact is lifted, or when the platform cancels it.
+    ///
+    /// Return `true` if the event is handled.
+    #[allow(unused_variables)]
+    fn touch_up(&mut self, event: &TouchEvent) -> bool {
+        false
+    }
+
+    /// Called when a pen touches down, moves, or is lifted.
+    ///
+    /// This is called before the mouse event that the platform makes of the
+    /// same input. The GTK, macOS and Windows backends deliver pen events.
+    #[allow(unused_variables)]
+    fn pen(&mut self, event: &PenEvent) {}
+
+    /// Called when something dragged from another application enters the
+    /// window or moves over it.
+    ///
+    /// Returns `true` if it can be dropped at this position, which the
+    /// platform shows to the user. The GTK, macOS and Windows backends
+    /// deliver drags from other applications.
+    #[allow(unused_variables)]
+    fn drag_over(&mut self, event: &DropEvent) -> bool {
+        false
+    }
+
+    /// Called when a drag from another application leaves the window, or is
+    /// cancelled.
+    fn drag_leave(&mut self) {}
+
+    /// Called when something dragged from another application is dropped on
+    /// the window.
+    ///
+    /// Returns `true` if the drop was taken.
+    #[allow(unused_variables)]
+    fn drag_drop(&mut self, event: &DropEvent) -> bool {
+        false
+    }
+
     /// Called on timer event.
     ///
     /// This is called at (approximately) the requested deadline by a
@@ -675,6 +983,44 @@ pub trait WinHandler {
     #[allow(unused_variables)]
     fn lost_focus(&mut self) {}
 
+    /// Called when the contents of the system clipboard may have changed.
+    ///
+    /// This is called on every open window. Platforms differ in when this is
+    /// reported: on Windows, GTK and X11 it is called whenever the clipboard
+    /// changes; macOS has no change notifications, so it is called when the
+    /// window becomes the key window and the clipboard changed in the meantime.
+    /// It is never called on the web.
+    #[allow(unused_variables)]
+    fn clipboard_changed(&mut self) {}
+
+    /// Called when the user changes the light or dark preference or the accent
+    /// color in the system settings.
+    ///
+    /// This is called on every open window. It is never called on the web,
+    /// which doesn't report changes. On Linux the preference comes from the
+    /// desktop portal's color scheme, and otherwise from the theme name, which
+    /// counts as dark when it ends with "dark".
+    #[allow(unused_variables)]
+    fn system_theme_changed(&mut self, theme: &SystemTheme) {}
+
+    /// Called when a monitor is connected or disconnected, or the arrangement,
+    /// resolution or scale of the monitors changes.
+    ///
+    /// This is called on every open window; use [`Screen::get_monitors`] to
+    /// get the new monitors. On X11 this needs the RandR extension; it is never
+    /// called on the web.
+    ///
+    /// [`Screen::get_monitors`]: crate::Screen::get_monitors
+    #[allow(unused_variables)]
+    fn monitors_changed(&mut self) {}
+
+    /// Called when the window moves to another monitor.
+    ///
+    /// The `monitor` is the one now holding most of the window, as returned
+    /// by [`WindowHandle::get_monitor`]. It is never called on the web.
+    #[allow(unused_variables)]
+    fn monitor_changed(&mut self, monitor: &Monitor) {}
+
     /// Called when the shell requests to close the window, for example because the user clicked
     /// the little "X" in the titlebar.
     ///
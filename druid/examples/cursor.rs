@@ -90,10 +90,20 @@ impl AppState {
             Cursor::Arrow => Cursor::IBeam,
             Cursor::IBeam => Cursor::Pointer,
             Cursor::Pointer => Cursor::Crosshair,
-            Cursor::Crosshair => Cursor::NotAllowed,
+            Cursor::Crosshair => Cursor::OpenHand,
+            Cursor::OpenHand => Cursor::ClosedHand,
+            Cursor::ClosedHand => Cursor::Move,
+            Cursor::Move => Cursor::NotAllowed,
             Cursor::NotAllowed => Cursor::ResizeLeftRight,
             Cursor::ResizeLeftRight => Cursor::ResizeUpDown,
-            Cursor::ResizeUpDown => {
+            Cursor::ResizeUpDown => Cursor::ResizeNwSe,
+            Cursor::ResizeNwSe => Cursor::ResizeNeSw,
+            Cursor::ResizeNeSw => Cursor::ZoomIn,
+            Cursor::ZoomIn => Cursor::ZoomOut,
+            Cursor::ZoomOut => Cursor::Wait,
+            Cursor::Wait => Cursor::Progress,
+            Cursor::Progress => Cursor::Help,
+            Cursor::Help => {
                 if let Some(custom) = &self.custom {
                     custom.clone()
                 } else {
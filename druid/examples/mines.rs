@@ -0,0 +1,211 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minesweeper game, showing how a `BoardGrid` paints and hit-tests
+//! many cells without a widget for each one.
+
+use std::sync::Arc;
+
+// `std::time::SystemTime` panics on the web.
+use instant::Instant;
+
+use druid::piet::{Text, TextLayout, TextLayoutBuilder};
+use druid::widget::{AspectRatioBox, BoardCell, BoardGrid, Button, Flex, Label};
+use druid::{
+    AppLauncher, Color, Data, Lens, MouseButton, RenderContext, Widget, WidgetExt, WindowDesc,
+};
+
+const SIZE: usize = 15;
+const MINES: usize = 30;
+
+#[derive(Clone, Copy, PartialEq, Data)]
+enum Cell {
+    Hidden,
+    Flagged,
+    Revealed,
+}
+
+#[allow(clippy::rc_buffer)]
+#[derive(Clone, Data, Lens)]
+struct Board {
+    /// The mines, which are placed when the first cell is revealed.
+    mines: Arc<Vec<bool>>,
+    cells: Arc<Vec<Cell>>,
+    lost: bool,
+    #[data(ignore)]
+    created: Instant,
+}
+
+impl Board {
+    fn new() -> Board {
+        Board {
+            mines: Arc::new(vec![false; SIZE * SIZE]),
+            cells: Arc::new(vec![Cell::Hidden; SIZE * SIZE]),
+            lost: false,
+            created: Instant::now(),
+        }
+    }
+
+    /// Place the mines anywhere but under the first cell that is revealed.
+    fn place_mines(&mut self, first: BoardCell) {
+        // the time until the first click seeds a small linear congruential
+        // generator, which is random enough for a game.
+        let mut seed = self.created.elapsed().as_nanos() as u64;
+        let mines = Arc::make_mut(&mut self.mines);
+        let mut placed = 0;
+        while placed < MINES {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let idx = (seed >> 33) as usize % mines.len();
+            if !mines[idx] && idx != index(first) {
+                mines[idx] = true;
+                placed += 1;
+            }
+        }
+    }
+
+    fn neighbors(cell: BoardCell) -> impl Iterator<Item = BoardCell> {
+        let rows = cell.row.saturating_sub(1)..(cell.row + 2).min(SIZE);
+        rows.flat_map(move |row| {
+            let cols = cell.col.saturating_sub(1)..(cell.col + 2).min(SIZE);
+            cols.map(move |col| BoardCell { row, col })
+        })
+        .filter(move |other| *other != cell)
+    }
+
+    fn adjacent_mines(&self, cell: BoardCell) -> usize {
+        count_mines(&self.mines, cell)
+    }
+
+    fn reveal(&mut self, cell: BoardCell) {
+        if self.lost || self.cells[index(cell)] != Cell::Hidden {
+            return;
+        }
+        if !self.mines.contains(&true) {
+            self.place_mines(cell);
+        }
+        if self.mines[index(cell)] {
+            self.lost = true;
+            return;
+        }
+        let cells = Arc::make_mut(&mut self.cells);
+        let mut to_reveal = vec![cell];
+        while let Some(cell) = to_reveal.pop() {
+            if cells[index(cell)] != Cell::Hidden {
+                continue;
+            }
+            cells[index(cell)] = Cell::Revealed;
+            if count_mines(&self.mines, cell) == 0 {
+                to_reveal.extend(Board::neighbors(cell));
+            }
+        }
+    }
+
+    fn toggle_flag(&mut self, cell: BoardCell) {
+        let cells = Arc::make_mut(&mut self.cells);
+        cells[index(cell)] = match cells[index(cell)] {
+            Cell::Hidden => Cell::Flagged,
+            Cell::Flagged => Cell::Hidden,
+            Cell::Revealed => Cell::Revealed,
+        };
+    }
+}
+
+fn count_mines(mines: &[bool], cell: BoardCell) -> usize {
+    Board::neighbors(cell)
+        .filter(|cell| mines[index(*cell)])
+        .count()
+}
+
+fn index(cell: BoardCell) -> usize {
+    cell.row * SIZE + cell.col
+}
+
+fn build_board() -> impl Widget<Board> {
+    BoardGrid::new(SIZE, SIZE, |ctx, state, board: &Board, _env| {
+        let idx = index(state.cell);
+        let revealed = board.cells[idx] == Cell::Revealed;
+        let background = if revealed {
+            Color::grey8(0xd0)
+        } else if state.is_pressed {
+            Color::grey8(0x60)
+        } else if state.is_hot {
+            Color::grey8(0x90)
+        } else {
+            Color::grey8(0x78)
+        };
+        ctx.fill(state.rect, &background);
+
+        let label = match board.cells[idx] {
+            _ if board.lost && board.mines[idx] => "*".to_string(),
+            Cell::Flagged => "!".to_string(),
+            Cell::Revealed => match board.adjacent_mines(state.cell) {
+                0 => return,
+                n => n.to_string(),
+            },
+            Cell::Hidden => return,
+        };
+        let layout = ctx
+            .text()
+            .new_text_layout(label)
+            .text_color(Color::BLACK)
+            .build()
+            .unwrap();
+        let offset = (state.rect.size().to_vec2() - layout.size().to_vec2()) / 2.0;
+        ctx.draw_text(&layout, state.rect.origin() + offset);
+    })
+    .with_spacing(1.0)
+    // only repaint the cells that changed, rather than the whole board.
+    .with_cell_changed(|old, new, cell| {
+        let idx = index(cell);
+        !old.mines.same(&new.mines)
+            || old.cells[idx] != new.cells[idx]
+            || (old.lost != new.lost && new.mines[idx])
+    })
+    .on_cell_click(
+        |_ctx, cell, mouse, board: &mut Board, _env| match mouse.button {
+            MouseButton::Left => board.reveal(cell),
+            MouseButton::Right => board.toggle_flag(cell),
+            _ => (),
+        },
+    )
+}
+
+fn make_widget() -> impl Widget<Board> {
+    let status = Label::dynamic(|board: &Board, _| {
+        if board.lost {
+            "Boom!".to_string()
+        } else {
+            "Left click to reveal, right click to flag".to_string()
+        }
+    });
+    Flex::column()
+        .with_child(status)
+        .with_default_spacer()
+        .with_flex_child(AspectRatioBox::new(build_board(), 1.0), 1.0)
+        .with_default_spacer()
+        .with_child(Button::new("New game").on_click(|_, board: &mut Board, _| {
+            *board = Board::new();
+        }))
+        .padding(10.0)
+}
+
+pub fn main() {
+    let window = WindowDesc::new(make_widget())
+        .window_size((420.0, 500.0))
+        .title("Mines");
+    AppLauncher::with_window(window)
+        .log_to_console()
+        .launch(Board::new())
+        .expect("launch failed");
+}
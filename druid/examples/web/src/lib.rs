@@ -70,6 +70,7 @@ impl_example!(invalidation);
 impl_example!(layout);
 impl_example!(lens);
 impl_example!(list);
+impl_example!(mines);
 impl_example!(multiwin);
 impl_example!(open_save);
 impl_example!(panels.unwrap());
@@ -39,3 +39,6 @@ common-menu-copy = Copy
 common-menu-paste = Paste
 common-menu-undo = Undo
 common-menu-redo = Redo
+
+# task progress
+task-progress-cancel = Cancel
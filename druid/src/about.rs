@@ -0,0 +1,118 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The application's About window.
+
+use crate::text::TextAlignment;
+use crate::widget::{Flex, Image, Label, LineBreaking, Scroll};
+use crate::{theme, Data, ImageBuf, Widget, WidgetExt, WindowDesc};
+
+/// What the About window of an application shows, for
+/// [`AppLauncher::about`].
+///
+/// [`AppLauncher::about`]: crate::AppLauncher::about
+#[derive(Clone, Default)]
+pub struct AboutInfo {
+    /// The name of the application.
+    pub name: String,
+    /// The version of the application.
+    pub version: String,
+    /// The application's icon.
+    ///
+    /// The native About panel on macOS shows the icon of the application
+    /// bundle instead.
+    pub icon: Option<ImageBuf>,
+    /// The people and projects to credit, one per line.
+    pub credits: Vec<String>,
+    /// The texts of the licenses of the application and its dependencies.
+    ///
+    /// These are not shown by the native About panel on macOS.
+    pub licenses: Vec<String>,
+}
+
+impl AboutInfo {
+    /// Create the information for an application called `name` at `version`.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        AboutInfo {
+            name: name.into(),
+            version: version.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Builder-style method to set the application's icon.
+    pub fn icon(mut self, icon: ImageBuf) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Builder-style method to add a line of credits.
+    pub fn credit(mut self, credit: impl Into<String>) -> Self {
+        self.credits.push(credit.into());
+        self
+    }
+
+    /// Builder-style method to add the text of a license.
+    pub fn license(mut self, license: impl Into<String>) -> Self {
+        self.licenses.push(license.into());
+        self
+    }
+
+    /// The About window drawn by druid, used on platforms without a native
+    /// About panel.
+    pub(crate) fn window<T: Data>(&self) -> WindowDesc<T> {
+        WindowDesc::new(self.content())
+            .title(format!("About {}", self.name))
+            .window_size((360.0, 420.0))
+            .resizable(false)
+    }
+
+    fn content<T: Data>(&self) -> impl Widget<T> {
+        let centered = |text: String| {
+            Label::new(text)
+                .with_line_break_mode(LineBreaking::WordWrap)
+                .with_text_alignment(TextAlignment::Center)
+        };
+        let mut column = Flex::column();
+        if let Some(icon) = &self.icon {
+            column.add_child(Image::new(icon.clone()).fix_size(64.0, 64.0));
+            column.add_default_spacer();
+        }
+        column.add_child(
+            centered(self.name.clone())
+                .with_font(theme::UI_FONT_BOLD)
+                .with_text_size(theme::TEXT_SIZE_LARGE),
+        );
+        if !self.version.is_empty() {
+            column.add_child(centered(format!("Version {}", self.version)));
+        }
+        if !self.credits.is_empty() {
+            column.add_default_spacer();
+            column.add_child(centered(self.credits.join("\n")));
+        }
+        if !self.licenses.is_empty() {
+            column.add_default_spacer();
+            let licenses = Label::new(self.licenses.join("\n\n"))
+                .with_line_break_mode(LineBreaking::WordWrap)
+                .padding(8.0);
+            column.add_flex_child(
+                Scroll::new(licenses)
+                    .vertical()
+                    .border(theme::BORDER_DARK, 1.0),
+                1.0,
+            );
+        }
+        column.padding(16.0)
+    }
+}
@@ -0,0 +1,441 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Animations and transitions.
+//!
+//! An [`Animation`] keeps track of the time that passed in
+//! [`Event::AnimFrame`]s, and how far along the animation is. Values that
+//! implement [`Interpolate`] can be tweened with it; the [`Animated`] widget
+//! does so with its data. [`Transition`]s animate the switch between the
+//! views of an [`Either`] or a [`ViewSwitcher`].
+//!
+//! ```
+//! use std::time::Duration;
+//! use druid::animation::{Animation, Easing, Interpolate};
+//! use druid::Point;
+//!
+//! let mut animation = Animation::new(Duration::from_millis(200), Easing::Linear);
+//! // Half of the animation passes in an animation frame.
+//! assert!(animation.advance(100_000_000));
+//! let pos = Point::ZERO.interpolate(&Point::new(10.0, 0.0), animation.progress());
+//! assert_eq!(pos, Point::new(5.0, 0.0));
+//! ```
+//!
+//! [`Event::AnimFrame`]: crate::Event::AnimFrame
+//! [`Animated`]: crate::widget::Animated
+//! [`Either`]: crate::widget::Either
+//! [`ViewSwitcher`]: crate::widget::ViewSwitcher
+
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::color::ColorExt;
+use crate::core::Layer;
+use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
+use crate::piet::{ImageBuf, ImageFormat, InterpolationMode};
+use crate::{Color, Data, Env, PaintCtx, RenderContext, Widget, WidgetPod};
+
+/// How the progress of an animation speeds up and slows down over time.
+#[derive(Debug, Clone, Copy)]
+pub enum Easing {
+    /// The same speed throughout.
+    Linear,
+    /// Starts slowly and speeds up towards the end.
+    EaseIn,
+    /// Starts fast and slows down towards the end.
+    EaseOut,
+    /// Starts slowly, speeds up, and slows down again towards the end.
+    EaseInOut,
+    /// A custom curve, mapping the time from 0 to 1 to the progress.
+    Custom(fn(f64) -> f64),
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::EaseInOut
+    }
+}
+
+impl Easing {
+    /// The progress at time `t`, for `t` from 0 to 1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t.powi(3),
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t.powi(3),
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
+
+/// Values that can be tweened between.
+pub trait Interpolate: Clone {
+    /// The value `t` of the way from this value to `other`.
+    ///
+    /// `t` usually goes from 0 to 1, but easing curves may overshoot it.
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t as f32
+    }
+}
+
+impl Interpolate for Point {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Size {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Size::new(
+            self.width.interpolate(&other.width, t),
+            self.height.interpolate(&other.height, t),
+        )
+    }
+}
+
+impl Interpolate for Rect {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Rect::from_points(
+            self.origin().interpolate(&other.origin(), t),
+            Point::new(self.x1, self.y1).interpolate(&Point::new(other.x1, other.y1), t),
+        )
+    }
+}
+
+impl Interpolate for Insets {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Insets::new(
+            self.x0.interpolate(&other.x0, t),
+            self.y0.interpolate(&other.y0, t),
+            self.x1.interpolate(&other.x1, t),
+            self.y1.interpolate(&other.y1, t),
+        )
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.mix(other, t)
+    }
+}
+
+impl<A: Interpolate, B: Interpolate> Interpolate for (A, B) {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        (
+            self.0.interpolate(&other.0, t),
+            self.1.interpolate(&other.1, t),
+        )
+    }
+}
+
+/// The progress of an animation, driven by [`Event::AnimFrame`].
+///
+/// Widgets start an animation by creating one and calling
+/// [`request_anim_frame`]. On each animation frame they call [`advance`],
+/// and ask for another frame while it returns `true`.
+///
+/// [`Event::AnimFrame`]: crate::Event::AnimFrame
+/// [`request_anim_frame`]: crate::EventCtx::request_anim_frame
+/// [`advance`]: Animation::advance
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    duration: Duration,
+    easing: Easing,
+    elapsed: Duration,
+}
+
+impl Animation {
+    /// Create an animation of the given length, that hasn't started yet.
+    pub fn new(duration: Duration, easing: Easing) -> Self {
+        Animation {
+            duration,
+            easing,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Let the `interval` of an [`Event::AnimFrame`] pass, in nanoseconds.
+    ///
+    /// Returns whether the animation is still running.
+    ///
+    /// [`Event::AnimFrame`]: crate::Event::AnimFrame
+    pub fn advance(&mut self, interval: u64) -> bool {
+        self.elapsed = (self.elapsed + Duration::from_nanos(interval)).min(self.duration);
+        self.is_running()
+    }
+
+    /// Whether the animation hasn't reached its end yet.
+    pub fn is_running(&self) -> bool {
+        self.elapsed < self.duration
+    }
+
+    /// How far along the animation is, from 0 to 1, with the easing applied.
+    pub fn progress(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        self.easing
+            .apply(self.elapsed.as_secs_f64() / self.duration.as_secs_f64())
+    }
+}
+
+/// The direction in which a [`Transition::slide`] moves the views.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideDirection {
+    /// The new view comes in from the right.
+    Left,
+    /// The new view comes in from the left.
+    Right,
+    /// The new view comes in from the bottom.
+    Up,
+    /// The new view comes in from the top.
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TransitionKind {
+    Fade,
+    Slide(SlideDirection),
+}
+
+/// How a widget that switches between views, such as [`Either`] or
+/// [`ViewSwitcher`], animates the switch.
+///
+/// [`Either`]: crate::widget::Either
+/// [`ViewSwitcher`]: crate::widget::ViewSwitcher
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    kind: TransitionKind,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Transition {
+    /// Crossfade from the old view to the new view.
+    ///
+    /// Both views are painted into offscreen layers, like the child of an
+    /// [`Opacity`], and the layers are mixed, so that what is behind the
+    /// views doesn't show through in the middle of the transition.
+    ///
+    /// [`Opacity`]: crate::widget::Opacity
+    pub fn fade(duration: Duration) -> Self {
+        Transition {
+            kind: TransitionKind::Fade,
+            duration,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Slide the old view out and the new view in, in the given direction.
+    pub fn slide(duration: Duration, direction: SlideDirection) -> Self {
+        Transition {
+            kind: TransitionKind::Slide(direction),
+            duration,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    /// Builder-style method to set the easing of the transition.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// The animation of a transition that is starting.
+    pub(crate) fn start(&self) -> Animation {
+        Animation::new(self.duration, self.easing)
+    }
+
+    /// Paint a transition at `progress`, from the old view showing the old
+    /// data to the `new` view showing `data`.
+    ///
+    /// The views are painted into layers, which are kept while they don't
+    /// change; the old view gets no more updates, so it is only painted once.
+    pub(crate) fn paint<T: Data, W: Widget<T>>(
+        &self,
+        ctx: &mut PaintCtx,
+        progress: f64,
+        (old, old_data): (&mut WidgetPod<T, W>, &T),
+        new: &mut WidgetPod<T, W>,
+        data: &T,
+        env: &Env,
+    ) {
+        let old_rect = old.paint_rect();
+        let new_rect = new.paint_rect();
+        let old = match old.paint_layer(ctx, old_data, env) {
+            Ok((layer, _)) => layer,
+            Err(e) => {
+                warn!("failed to paint the old view of a transition: {}", e);
+                return new.paint(ctx, data, env);
+            }
+        };
+        let new = match new.paint_layer(ctx, data, env) {
+            Ok((layer, _)) => layer,
+            Err(e) => {
+                warn!("failed to paint the new view of a transition: {}", e);
+                return new.paint(ctx, data, env);
+            }
+        };
+
+        match self.kind {
+            TransitionKind::Fade => {
+                let (mixed, rect) = crossfade(old, old_rect, new, new_rect, progress);
+                let image = mixed.to_image(ctx.render_ctx);
+                ctx.draw_image(&image, rect, InterpolationMode::Bilinear);
+            }
+            TransitionKind::Slide(direction) => {
+                let size = ctx.size();
+                let offset = match direction {
+                    SlideDirection::Left => Vec2::new(-size.width, 0.0),
+                    SlideDirection::Right => Vec2::new(size.width, 0.0),
+                    SlideDirection::Up => Vec2::new(0.0, -size.height),
+                    SlideDirection::Down => Vec2::new(0.0, size.height),
+                };
+                let old_image = old.image(ctx);
+                let new_image = new.image(ctx);
+                ctx.with_save(|ctx| {
+                    ctx.clip(size.to_rect());
+                    let old_rect = old_rect + offset * progress;
+                    ctx.draw_image(&old_image, old_rect, InterpolationMode::Bilinear);
+                    let new_rect = new_rect + offset * (progress - 1.0);
+                    ctx.draw_image(&new_image, new_rect, InterpolationMode::Bilinear);
+                });
+            }
+        }
+    }
+}
+
+/// Mix two layers with the same scale, `t` of the way from `a` to `b`.
+///
+/// The layers cover `a_rect` and `b_rect`, and the mixed image covers the
+/// union of them, which is returned with it.
+fn crossfade(a: &Layer, a_rect: Rect, b: &Layer, b_rect: Rect, t: f64) -> (ImageBuf, Rect) {
+    let scale = b.scale;
+    let rect = a_rect.union(b_rect);
+    let width = (rect.width() * scale).ceil().max(1.0) as usize;
+    let height = (rect.height() * scale).ceil().max(1.0) as usize;
+    let mut mixed = vec![0.0; width * height * 4];
+    for &(layer, layer_rect, weight) in &[(a, a_rect, 1.0 - t), (b, b_rect, t)] {
+        let x = ((layer_rect.x0 - rect.x0) * scale).round() as usize;
+        let y = ((layer_rect.y0 - rect.y0) * scale).round() as usize;
+        let row_len = layer.buf.width() * 4;
+        let rows = layer.buf.raw_pixels().chunks_exact(row_len);
+        for (row_y, row) in (y..height).zip(rows) {
+            let start = (row_y * width + x.min(width)) * 4;
+            let end = (start + row_len).min((row_y + 1) * width * 4);
+            for (out, &c) in mixed[start..end].iter_mut().zip(row) {
+                // The pixels are premultiplied, so the channels mix linearly.
+                *out += c as f64 * weight;
+            }
+        }
+    }
+    let pixels: Vec<u8> = mixed
+        .into_iter()
+        .map(|c| c.round().min(255.0) as u8)
+        .collect();
+    let image = ImageBuf::from_raw(pixels, ImageFormat::RgbaPremul, width, height);
+    (image, rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn easings_start_and_end_in_place() {
+        let easings = [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::Custom(|t| t * t),
+        ];
+        for &easing in &easings {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            // Times outside of the animation are clamped.
+            assert_eq!(easing.apply(-1.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(2.0), 1.0, "{:?}", easing);
+        }
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn animation_advances_to_its_end() {
+        let mut animation = Animation::new(Duration::from_secs(4), Easing::Linear);
+        assert!(animation.is_running());
+        assert_eq!(animation.progress(), 0.0);
+
+        assert!(animation.advance(1_000_000_000));
+        assert_eq!(animation.progress(), 0.25);
+
+        // The last frame may overshoot the end.
+        assert!(!animation.advance(4_000_000_000));
+        assert!(!animation.is_running());
+        assert_eq!(animation.progress(), 1.0);
+
+        let instant = Animation::new(Duration::ZERO, Easing::Linear);
+        assert!(!instant.is_running());
+        assert_eq!(instant.progress(), 1.0);
+    }
+
+    #[test]
+    fn crossfade_mixes_premultiplied_pixels() {
+        let layer = |pixel: [u8; 4], width| {
+            let pixels = pixel.iter().copied().cycle().take(width * 4).collect();
+            Layer::new(
+                ImageBuf::from_raw(pixels, ImageFormat::RgbaPremul, width, 1),
+                1.0,
+            )
+        };
+        let red = layer([255, 0, 0, 255], 1);
+        let blue = layer([0, 0, 255, 255], 2);
+        let (mixed, rect) = crossfade(
+            &red,
+            Rect::new(0.0, 0.0, 1.0, 1.0),
+            &blue,
+            Rect::new(0.0, 0.0, 2.0, 1.0),
+            0.25,
+        );
+        assert_eq!(rect, Rect::new(0.0, 0.0, 2.0, 1.0));
+        assert_eq!(
+            mixed.raw_pixels(),
+            // Only the blue layer covers the second pixel.
+            &[191, 0, 64, 255, 0, 0, 64, 64]
+        );
+    }
+}
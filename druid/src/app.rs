@@ -14,26 +14,52 @@
 
 //! Window building and app lifecycle.
 
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::dialog;
 use crate::ext_event::{ExtEventHost, ExtEventSink};
+use crate::geometry::WindowGeometry;
+use crate::interaction::{Interaction, InteractionSinkFn};
 use crate::kurbo::{Point, Size};
 use crate::menu::MenuManager;
-use crate::shell::{Application, Error as PlatformError, WindowBuilder, WindowHandle, WindowLevel};
-use crate::widget::LabelText;
-use crate::win_handler::{AppHandler, AppState};
+use crate::session::{SessionStore, SessionWriter};
+use crate::shell::{
+    Application, Error as PlatformError, Monitor, SystemTheme, WindowBuilder, WindowHandle,
+    WindowLevel,
+};
+use crate::theme;
+use crate::widget::{LabelText, LensWrap, Scope, ScopePolicy};
+use crate::win_handler::{AppConfig, AppHandler, AppState, Autosave, IdleMonitor};
 use crate::window::WindowId;
-use crate::{AppDelegate, Data, Env, LocalizedString, Menu, Widget};
+use crate::window_set::WindowSet;
+use crate::{AboutInfo, CrashReporter, TrayDesc};
+use crate::{AppDelegate, Command, Data, Env, Lens, LocalizedString, Menu, ShortcutMap, Widget};
 
 use druid_shell::WindowState;
 
 /// A function that modifies the initial environment.
 type EnvSetupFn<T> = dyn FnOnce(&mut Env, &T);
 
+/// A function that modifies the environment for a light or dark system theme.
+type ThemeEnvFn<T> = dyn Fn(&mut Env, &T);
+
 /// Handles initial setup of an application, and starts the runloop.
 pub struct AppLauncher<T> {
     windows: Vec<WindowDesc<T>>,
     env_setup: Option<Box<EnvSetupFn<T>>>,
+    theme_env_setup: Option<(Box<ThemeEnvFn<T>>, Box<ThemeEnvFn<T>>)>,
     l10n_resources: Option<(Vec<String>, String)>,
     delegate: Option<Box<dyn AppDelegate<T>>>,
+    interaction_sink: Option<Box<InteractionSinkFn>>,
+    idle_monitor: Option<IdleMonitor>,
+    autosave: Option<(SessionStore, Duration)>,
+    window_set: Option<WindowSet<T>>,
+    about: Option<AboutInfo>,
+    shortcuts: ShortcutMap,
+    crash_reporter: Option<CrashReporter>,
+    tray: Option<TrayDesc<T>>,
+    dialog_directories: Option<PathBuf>,
     ext_event_host: ExtEventHost,
 }
 
@@ -51,7 +77,7 @@ pub enum WindowSizePolicy {
 
 /// Window configuration that can be applied to a WindowBuilder, or to an existing WindowHandle.
 /// It does not include anything related to app data.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WindowConfig {
     pub(crate) size_policy: WindowSizePolicy,
     pub(crate) size: Option<Size>,
@@ -62,6 +88,8 @@ pub struct WindowConfig {
     pub(crate) show_titlebar: Option<bool>,
     pub(crate) level: Option<WindowLevel>,
     pub(crate) state: Option<WindowState>,
+    pub(crate) fullscreen: Option<bool>,
+    pub(crate) fullscreen_monitor: Option<Monitor>,
 }
 
 /// A description of a window to be instantiated.
@@ -84,7 +112,12 @@ pub struct PendingWindow<T> {
     pub(crate) transparent: bool,
     pub(crate) menu: Option<MenuManager<T>>,
     pub(crate) size_policy: WindowSizePolicy, // This is copied over from the WindowConfig
-                                              // when the native window is constructed.
+    // when the native window is constructed.
+    pub(crate) content_scale: f64,
+    pub(crate) shortcuts: ShortcutMap,
+    pub(crate) raw_key_input: bool,
+    /// Where the window saves its geometry when it closes.
+    pub(crate) geometry_path: Option<PathBuf>,
 }
 
 impl<T: Data> PendingWindow<T> {
@@ -100,6 +133,10 @@ impl<T: Data> PendingWindow<T> {
             menu: MenuManager::platform_default(),
             transparent: false,
             size_policy: WindowSizePolicy::User,
+            content_scale: 1.0,
+            shortcuts: ShortcutMap::new(),
+            raw_key_input: false,
+            geometry_path: None,
         }
     }
 
@@ -120,6 +157,30 @@ impl<T: Data> PendingWindow<T> {
         self
     }
 
+    /// Set the factor by which the window scales its content.
+    ///
+    /// See [`WindowDesc::content_scale`] for details.
+    pub fn content_scale(mut self, scale: f64) -> Self {
+        self.content_scale = scale;
+        self
+    }
+
+    /// Set the keyboard shortcuts that only apply to this window.
+    ///
+    /// See [`WindowDesc::shortcuts`] for details.
+    pub fn shortcuts(mut self, shortcuts: ShortcutMap) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    /// Set whether the window gets raw key input.
+    ///
+    /// See [`WindowDesc::raw_key_input`] for details.
+    pub fn raw_key_input(mut self, raw: bool) -> Self {
+        self.raw_key_input = raw;
+        self
+    }
+
     /// Set the menu for this window.
     ///
     /// `menu` is a callback for creating the menu. Its first argument is the id of the window that
@@ -140,12 +201,117 @@ impl<T: Data> AppLauncher<T> {
         AppLauncher {
             windows: vec![window],
             env_setup: None,
+            theme_env_setup: None,
             l10n_resources: None,
             delegate: None,
+            interaction_sink: None,
+            idle_monitor: None,
+            autosave: None,
+            window_set: None,
+            about: None,
+            shortcuts: ShortcutMap::new(),
+            crash_reporter: None,
+            tray: None,
+            dialog_directories: None,
             ext_event_host: ExtEventHost::new(),
         }
     }
 
+    /// Create a new `AppLauncher` whose windows are all described by the
+    /// app data, through a [`WindowSet`].
+    ///
+    /// [`WindowSet`]: crate::WindowSet
+    pub fn with_window_set(window_set: WindowSet<T>) -> Self {
+        AppLauncher {
+            windows: Vec::new(),
+            env_setup: None,
+            theme_env_setup: None,
+            l10n_resources: None,
+            delegate: None,
+            interaction_sink: None,
+            idle_monitor: None,
+            autosave: None,
+            window_set: Some(window_set),
+            about: None,
+            shortcuts: ShortcutMap::new(),
+            crash_reporter: None,
+            tray: None,
+            dialog_directories: None,
+            ext_event_host: ExtEventHost::new(),
+        }
+    }
+
+    /// Open and close windows to follow a list in the app data, in addition
+    /// to the window this launcher was created with.
+    ///
+    /// See [`WindowSet`] for details.
+    ///
+    /// [`WindowSet`]: crate::WindowSet
+    pub fn window_set(mut self, window_set: WindowSet<T>) -> Self {
+        self.window_set = Some(window_set);
+        self
+    }
+
+    /// Show an About window with `info` when the [`SHOW_ABOUT`] command is
+    /// submitted, for instance by the standard About menu item.
+    ///
+    /// On macOS this is the native About panel; on other platforms it is a
+    /// window drawn by druid. Only one About window is open at a time. The
+    /// command still goes to the delegate first, which can handle it to
+    /// prevent the window from opening.
+    ///
+    /// [`SHOW_ABOUT`]: crate::commands::SHOW_ABOUT
+    pub fn about(mut self, info: AboutInfo) -> Self {
+        self.about = Some(info);
+        self
+    }
+
+    /// Set the keyboard shortcuts of the application.
+    ///
+    /// These apply in every window, whether or not it has a menu. Windows can
+    /// override them with [`WindowDesc::shortcuts`], and parts of a window
+    /// with a [`ShortcutScope`]. See [`ShortcutMap`] for details.
+    ///
+    /// [`WindowDesc::shortcuts`]: crate::WindowDesc::shortcuts
+    /// [`ShortcutScope`]: crate::widget::ShortcutScope
+    /// [`ShortcutMap`]: crate::ShortcutMap
+    pub fn shortcuts(mut self, shortcuts: ShortcutMap) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    /// Report panics on the UI thread with a [`CrashReporter`].
+    ///
+    /// The reporter writes a crash report and offers the user to restart the
+    /// application, instead of the application just disappearing. See
+    /// [`CrashReporter`] for details.
+    ///
+    /// If this instance of the application was started to show the crash
+    /// dialog of one that crashed, [`launch`] shows the dialog and returns
+    /// without opening any windows.
+    ///
+    /// This replaces the process' panic hook when the application launches;
+    /// the previous hook still runs for every panic.
+    ///
+    /// [`launch`]: AppLauncher::launch
+    pub fn crash_reporter(mut self, reporter: CrashReporter) -> Self {
+        self.crash_reporter = Some(reporter);
+        self
+    }
+
+    /// Show an icon for the application in the system tray.
+    ///
+    /// While the icon is shown, the application keeps running when its last
+    /// window is closed; it quits on [`QUIT_APP`]. On platforms without a
+    /// system tray, a warning is logged and the application launches without
+    /// the icon, quitting with its last window as usual.
+    ///
+    /// [`QUIT_APP`]: crate::commands::QUIT_APP
+    pub fn tray(mut self, tray: TrayDesc<T>) -> Self {
+        self.tray = Some(tray);
+        self
+    }
+
     /// Provide an optional closure that will be given mutable access to
     /// the environment and immutable access to the app state before launch.
     ///
@@ -155,6 +321,24 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Provide two closures that set up the environment for a light and a
+    /// dark system theme.
+    ///
+    /// The one matching the system theme is applied after the closure given
+    /// to [`configure_env`], and the environment is switched over whenever
+    /// the user changes the theme. The closures also get the app state at
+    /// the time of the switch.
+    ///
+    /// [`configure_env`]: AppLauncher::configure_env
+    pub fn configure_env_for_theme(
+        mut self,
+        light: impl Fn(&mut Env, &T) + 'static,
+        dark: impl Fn(&mut Env, &T) + 'static,
+    ) -> Self {
+        self.theme_env_setup = Some((Box::new(light), Box::new(dark)));
+        self
+    }
+
     /// Set the [`AppDelegate`].
     ///
     /// [`AppDelegate`]: trait.AppDelegate.html
@@ -163,6 +347,71 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Report user interactions to the provided closure.
+    ///
+    /// Once a sink is installed, every mouse press that makes a widget active,
+    /// every focus change, and every dispatched command is reported as a
+    /// structured [`Interaction`], including the widget's id and type where
+    /// known. Command interactions also record how long the dispatch took.
+    ///
+    /// This is intended for usage analytics and UX research; no interactions
+    /// are reported unless this method is called.
+    ///
+    pub fn log_interactions(mut self, sink: impl FnMut(&Interaction) + 'static) -> Self {
+        self.interaction_sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Submit `command` once the user has been inactive for `threshold`.
+    ///
+    /// Any mouse or keyboard input in any window resets the idle clock; the
+    /// command is submitted at most once per idle period. This can be used to
+    /// lock or dim the UI in kiosk or security-sensitive applications.
+    ///
+    /// The time since the last input is also available from
+    /// [`DelegateCtx::idle_duration`].
+    ///
+    /// If the command's target is [`Target::Auto`], it is submitted to
+    /// [`Target::Global`]: the [`AppDelegate`] sees it first, then each window
+    /// in turn until one of them handles it.
+    ///
+    /// [`DelegateCtx::idle_duration`]: crate::DelegateCtx::idle_duration
+    /// [`Target::Auto`]: crate::Target::Auto
+    /// [`Target::Global`]: crate::Target::Global
+    pub fn idle_timeout(mut self, threshold: Duration, command: impl Into<Command>) -> Self {
+        self.idle_monitor = Some(IdleMonitor::new(threshold, command.into()));
+        self
+    }
+
+    /// Periodically save the application state to `store`, and offer to
+    /// restore it if the app exits unexpectedly.
+    ///
+    /// Every `interval`, the delegate's [`save_state`] method is asked for
+    /// the current state. If the previous session did not exit cleanly, the
+    /// last saved state is passed to [`on_unexpected_exit`] after the first
+    /// window is added. A clean exit removes the saved state. The state is
+    /// written on a background thread, and another instance of the app that is
+    /// still running doesn't count as an unclean exit.
+    ///
+    /// This requires a [`delegate`] to be set.
+    ///
+    /// [`save_state`]: crate::AppDelegate::save_state
+    /// [`on_unexpected_exit`]: crate::AppDelegate::on_unexpected_exit
+    /// [`delegate`]: #method.delegate
+    pub fn autosave(mut self, store: SessionStore, interval: Duration) -> Self {
+        self.autosave = Some((store, interval));
+        self
+    }
+
+    /// Restore the directories that file dialogs [remember] from the file at
+    /// `path`, and save them there when the application exits.
+    ///
+    /// [remember]: crate::FileDialogOptions::remember_directory
+    pub fn persist_dialog_directories(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dialog_directories = Some(path.into());
+        self
+    }
+
     /// Initialize a minimal logger with DEBUG max level for printing logs out to stderr.
     ///
     /// This is meant for use during development only.
@@ -224,6 +473,35 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Run the futures of [`AppDelegate::command_async`] and of
+    /// [`FutureWidget`]s with the provided closure.
+    ///
+    /// By default, each future is driven on a background thread of its own
+    /// by a minimal executor. That executor doesn't run the reactor of any
+    /// async runtime, so futures that need one, like most of tokio's I/O and
+    /// timers, never complete. Hand them to your runtime instead:
+    ///
+    /// ```ignore
+    /// let runtime = tokio::runtime::Runtime::new()?;
+    /// let handle = runtime.handle().clone();
+    /// AppLauncher::with_window(window)
+    ///     .spawner(move |future| {
+    ///         handle.spawn(future);
+    ///     })
+    ///     .launch(data)?;
+    /// ```
+    ///
+    /// This is only available with the `async` feature.
+    ///
+    /// [`AppDelegate::command_async`]: crate::AppDelegate::command_async
+    /// [`FutureWidget`]: crate::widget::FutureWidget
+    #[cfg(feature = "async")]
+    pub fn spawner(self, spawner: impl Fn(crate::SpawnedFuture) + Send + Sync + 'static) -> Self {
+        self.ext_event_host
+            .set_spawner(std::sync::Arc::new(spawner));
+        self
+    }
+
     /// Returns an [`ExtEventSink`] that can be moved between threads,
     /// and can be used to submit commands back to the application.
     ///
@@ -239,34 +517,137 @@ impl<T: Data> AppLauncher<T> {
     pub fn launch(mut self, data: T) -> Result<(), PlatformError> {
         let app = Application::new()?;
 
+        if let Some(reporter) = self.crash_reporter.take() {
+            if reporter.show_pending_report() {
+                return Ok(());
+            }
+            reporter.install();
+        }
+
+        let dialog_directories = self.dialog_directories.take();
+        if let Some(path) = &dialog_directories {
+            if let Err(e) = dialog::load_remembered_directories(path) {
+                tracing::warn!("failed to load dialog directories from {:?}: {}", path, e);
+            }
+        }
+
         let mut env = self
             .l10n_resources
             .map(|it| Env::with_i10n(it.0, &it.1))
             .unwrap_or_else(Env::with_default_i10n);
 
+        let system_theme = Application::system_theme();
+        theme::set_system_theme(&mut env, &system_theme);
         if let Some(f) = self.env_setup.take() {
             f(&mut env, &data);
         }
+        let theme_envs = self.theme_env_setup.take().map(|(light, dark)| ThemeEnvs {
+            base: env.clone(),
+            light,
+            dark,
+        });
+        if let Some(theme_envs) = &theme_envs {
+            env = theme_envs.env_for(&system_theme, &data);
+        }
 
+        let mut session_writer = None;
+        let autosave = self.autosave.take().and_then(|(store, interval)| {
+            let recovered = match store.begin_session(interval) {
+                Ok(true) => store.load().unwrap_or_else(|e| {
+                    tracing::error!("failed to load autosaved state: {}", e);
+                    None
+                }),
+                Ok(false) => None,
+                Err(e) => {
+                    tracing::error!("failed to start session in {:?}: {}", store.dir(), e);
+                    None
+                }
+            };
+            match SessionWriter::spawn(store, interval) {
+                Ok(writer) => {
+                    let saver = writer.saver();
+                    session_writer = Some(writer);
+                    Some(Autosave::new(saver, interval, recovered))
+                }
+                Err(e) => {
+                    tracing::error!("failed to start the autosave thread: {}", e);
+                    None
+                }
+            }
+        });
+
+        let config = AppConfig {
+            interaction_sink: self.interaction_sink.take(),
+            idle_monitor: self.idle_monitor.take(),
+            autosave,
+            window_set: self.window_set.take(),
+            about: self.about.take(),
+            shortcuts: self.shortcuts,
+            system_theme,
+            theme_envs,
+        };
         let mut state = AppState::new(
             app.clone(),
             data,
             env,
             self.delegate.take(),
             self.ext_event_host,
+            config,
         );
 
+        if let Some(tray) = self.tray.take() {
+            if let Err(e) = state.add_tray(tray) {
+                tracing::warn!("failed to add the tray icon: {}", e);
+            }
+        }
+
         for desc in self.windows {
             let window = desc.build_native(&mut state)?;
             window.show();
         }
+        for desc in state.declared_windows() {
+            let window = desc.build_native(&mut state)?;
+            window.show();
+        }
 
         let handler = AppHandler::new(state);
         app.run(Some(Box::new(handler)));
+
+        if let Some(path) = &dialog_directories {
+            if let Err(e) = dialog::save_remembered_directories(path) {
+                tracing::warn!("failed to save dialog directories to {:?}: {}", path, e);
+            }
+        }
+
+        if let Some(writer) = session_writer {
+            writer.finish();
+        }
         Ok(())
     }
 }
 
+/// The environments for the light and dark system themes.
+pub(crate) struct ThemeEnvs<T> {
+    /// The environment before either theme is applied.
+    base: Env,
+    light: Box<ThemeEnvFn<T>>,
+    dark: Box<ThemeEnvFn<T>>,
+}
+
+impl<T> ThemeEnvs<T> {
+    /// The environment for `system_theme`.
+    pub(crate) fn env_for(&self, system_theme: &SystemTheme, data: &T) -> Env {
+        let mut env = self.base.clone();
+        theme::set_system_theme(&mut env, system_theme);
+        if system_theme.is_dark() {
+            (self.dark)(&mut env, data);
+        } else {
+            (self.light)(&mut env, data);
+        }
+        env
+    }
+}
+
 impl Default for WindowConfig {
     fn default() -> Self {
         WindowConfig {
@@ -279,6 +660,8 @@ impl Default for WindowConfig {
             transparent: None,
             level: None,
             state: None,
+            fullscreen: None,
+            fullscreen_monitor: None,
         }
     }
 }
@@ -359,7 +742,13 @@ impl WindowConfig {
 
     /// Sets the [`WindowLevel`] of the window
     ///
+    /// To keep an open window on top of others, or to turn it into a tool
+    /// palette, submit a [`CONFIGURE_WINDOW`] command with
+    /// [`WindowLevel::Floating`] or [`WindowLevel::Utility`]; switch back with
+    /// [`WindowLevel::AppWindow`].
+    ///
     /// [`WindowLevel`]: enum.WindowLevel.html
+    /// [`CONFIGURE_WINDOW`]: crate::commands::CONFIGURE_WINDOW
     pub fn set_level(mut self, level: WindowLevel) -> Self {
         self.level = Some(level);
         self
@@ -373,6 +762,30 @@ impl WindowConfig {
         self
     }
 
+    /// Set whether the window should be fullscreen.
+    ///
+    /// A new window goes fullscreen once it is created; an open window is
+    /// changed with the [`CONFIGURE_WINDOW`] command. To toggle fullscreen,
+    /// like the F11 key does in many applications, use [`TOGGLE_FULLSCREEN`].
+    ///
+    /// [`CONFIGURE_WINDOW`]: crate::commands::CONFIGURE_WINDOW
+    /// [`TOGGLE_FULLSCREEN`]: crate::commands::TOGGLE_FULLSCREEN
+    pub fn set_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = Some(fullscreen);
+        self
+    }
+
+    /// Make the window fullscreen on the given monitor.
+    ///
+    /// Like [`set_fullscreen`], this applies to new and open windows.
+    ///
+    /// [`set_fullscreen`]: WindowConfig::set_fullscreen
+    pub fn set_fullscreen_monitor(mut self, monitor: Monitor) -> Self {
+        self.fullscreen = Some(true);
+        self.fullscreen_monitor = Some(monitor);
+        self
+    }
+
     /// Set whether the window background should be transparent
     pub fn transparent(mut self, transparent: bool) -> Self {
         self.transparent = Some(transparent);
@@ -444,6 +857,12 @@ impl WindowConfig {
         if let Some(state) = self.state {
             win_handle.set_window_state(state);
         }
+
+        match (self.fullscreen, &self.fullscreen_monitor) {
+            (Some(true), Some(monitor)) => win_handle.set_fullscreen_on(monitor),
+            (Some(fullscreen), _) => win_handle.set_fullscreen(fullscreen),
+            (None, _) => (),
+        }
     }
 }
 
@@ -462,6 +881,42 @@ impl<T: Data> WindowDesc<T> {
         }
     }
 
+    /// Create a new `WindowDesc` whose root [`Widget`] works on the part of
+    /// the application data selected by `lens`.
+    ///
+    /// This lets each window of a multi-document application bind to its own
+    /// document: open one window per document, each with a lens to that
+    /// document in the application data. A window should be closed before
+    /// its document is removed, as the lens may no longer find it.
+    ///
+    /// [`Widget`]: trait.Widget.html
+    pub fn with_lens<U, L, W>(lens: L, root: W) -> WindowDesc<T>
+    where
+        U: Data,
+        L: Lens<T, U> + 'static,
+        W: Widget<U> + 'static,
+    {
+        WindowDesc::new(LensWrap::new(root, lens))
+    }
+
+    /// Create a new `WindowDesc` whose root [`Widget`] works on state of its
+    /// own, kept in sync with the application data by a [`ScopePolicy`].
+    ///
+    /// The state is created from the application data when the window
+    /// opens; this is for windows, such as editors and inspectors, that
+    /// change a copy of some data and only write it back when the transfer
+    /// says so.
+    ///
+    /// [`Widget`]: trait.Widget.html
+    /// [`ScopePolicy`]: widget/trait.ScopePolicy.html
+    pub fn with_scope<SP, W>(policy: SP, root: W) -> WindowDesc<T>
+    where
+        SP: ScopePolicy<In = T> + 'static,
+        W: Widget<SP::State> + 'static,
+    {
+        WindowDesc::new(Scope::new(policy, root))
+    }
+
     /// Set the title for this window. This is a [`LabelText`]; it can be either
     /// a `String`, a [`LocalizedString`], or a closure that computes a string;
     /// it will be kept up to date as the application's state changes.
@@ -558,6 +1013,57 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set the factor by which the window scales its content, on top of the
+    /// platform's own scaling.
+    ///
+    /// Widgets are laid out in a space that is smaller by this factor and then
+    /// painted scaled up to fill the window, so text stays crisp. This is
+    /// useful for presentations and for magnifying an application. The scale
+    /// of an open window can be changed with [`SET_CONTENT_SCALE`].
+    ///
+    /// Mouse positions are in the scaled space, except for
+    /// [`MouseEvent::window_pos`], which is in window coordinates.
+    ///
+    /// [`SET_CONTENT_SCALE`]: crate::commands::SET_CONTENT_SCALE
+    /// [`MouseEvent::window_pos`]: crate::MouseEvent::window_pos
+    pub fn content_scale(mut self, scale: f64) -> Self {
+        self.pending = self.pending.content_scale(scale);
+        self
+    }
+
+    /// Set the keyboard shortcuts that only apply to this window.
+    ///
+    /// They take precedence over the shortcuts of the application, set with
+    /// [`AppLauncher::shortcuts`]. See [`ShortcutMap`] for details.
+    ///
+    /// [`AppLauncher::shortcuts`]: crate::AppLauncher::shortcuts
+    /// [`ShortcutMap`]: crate::ShortcutMap
+    pub fn shortcuts(mut self, shortcuts: ShortcutMap) -> Self {
+        self.pending = self.pending.shortcuts(shortcuts);
+        self
+    }
+
+    /// Set whether the window gets raw key input, for games and keybinding
+    /// editors.
+    ///
+    /// Normally, key presses that no widget handles go on to the platform's
+    /// text input system, which composes accented characters from dead keys
+    /// and runs input methods. With raw key input every key press is only
+    /// delivered as a [`KeyEvent`], and the physical key is in its [`code`]
+    /// and [`scancode`], whatever the keyboard layout. Text boxes in the
+    /// window get no input while this is on.
+    ///
+    /// This can be changed later with the [`SET_RAW_KEY_INPUT`] command.
+    ///
+    /// [`KeyEvent`]: crate::KeyEvent
+    /// [`code`]: crate::KeyEvent::code
+    /// [`scancode`]: crate::KeyEvent::scancode
+    /// [`SET_RAW_KEY_INPUT`]: crate::commands::SET_RAW_KEY_INPUT
+    pub fn raw_key_input(mut self, raw: bool) -> Self {
+        self.pending = self.pending.raw_key_input(raw);
+        self
+    }
+
     /// Sets the initial window position in [display points], relative to the origin
     /// of the [virtual screen].
     ///
@@ -582,6 +1088,48 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set the initial size, position and state of the window from a saved
+    /// [`WindowGeometry`].
+    ///
+    /// The position is only used if the monitor the window was on is still
+    /// there, so that the window doesn't open off screen. A minimized window
+    /// opens restored.
+    pub fn geometry(mut self, geometry: &WindowGeometry) -> Self {
+        self.config = self.config.window_size(geometry.size);
+        if geometry.monitor_available() {
+            self.config = self.config.set_position(geometry.position);
+        }
+        let state = match geometry.state {
+            WindowState::Minimized => WindowState::Restored,
+            state => state,
+        };
+        self.config = self
+            .config
+            .set_window_state(state)
+            .set_fullscreen(geometry.fullscreen);
+        self
+    }
+
+    /// Restore the window's geometry from the file at `path`, and save it
+    /// there when the window closes.
+    ///
+    /// If the file doesn't exist yet, the window opens with the size and
+    /// position it is otherwise given; so call this after [`window_size`]
+    /// and [`set_position`].
+    ///
+    /// [`window_size`]: WindowDesc::window_size
+    /// [`set_position`]: WindowDesc::set_position
+    pub fn persist_geometry(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match WindowGeometry::load(&path) {
+            Ok(Some(geometry)) => self = self.geometry(&geometry),
+            Ok(None) => (),
+            Err(e) => tracing::warn!("failed to load window geometry from {:?}: {}", path, e),
+        }
+        self.pending.geometry_path = Some(path);
+        self
+    }
+
     /// Attempt to create a platform window from this `WindowDesc`.
     pub(crate) fn build_native(
         self,
@@ -15,12 +15,36 @@
 //! Customizing application-level behaviour.
 
 use std::any::{Any, TypeId};
+use std::time::Duration;
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin};
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
 
 use crate::{
-    commands, core::CommandQueue, ext_event::ExtEventHost, Command, Data, Env, Event, ExtEventSink,
-    Handled, SingleUse, Target, WindowDesc, WindowId,
+    commands, core::CommandQueue, ext_event::ExtEventHost, widget::mutable::mutation, Command,
+    Data, Env, Event, EventCtx, ExtEventSink, Handled, SingleUse, Target, WidgetId, WindowDesc,
+    WindowId,
 };
 
+/// The follow-up of a future returned from [`AppDelegate::command_async`].
+///
+/// Once the future completes, this is run on the UI thread, where it can
+/// update the application data and submit commands.
+#[cfg(feature = "async")]
+pub type AsyncUpdate<T> = Box<dyn FnOnce(&mut DelegateCtx, &mut T, &Env) + Send>;
+
+/// A future returned from [`AppDelegate::command_async`].
+#[cfg(feature = "async")]
+pub type DelegateFuture<T> = Pin<Box<dyn Future<Output = AsyncUpdate<T>> + Send>>;
+
+/// A future handed to the spawner set with [`AppLauncher::spawner`].
+///
+/// [`AppLauncher::spawner`]: crate::AppLauncher::spawner
+#[cfg(feature = "async")]
+pub type SpawnedFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
 /// A context passed in to [`AppDelegate`] functions.
 ///
 /// [`AppDelegate`]: trait.AppDelegate.html
@@ -28,6 +52,8 @@ pub struct DelegateCtx<'a> {
     pub(crate) command_queue: &'a mut CommandQueue,
     pub(crate) ext_event_host: &'a ExtEventHost,
     pub(crate) app_data_type: TypeId,
+    pub(crate) last_input: Instant,
+    pub(crate) window_ids: &'a [WindowId],
 }
 
 impl<'a> DelegateCtx<'a> {
@@ -46,6 +72,21 @@ impl<'a> DelegateCtx<'a> {
             .push_back(command.into().default_to(Target::Global))
     }
 
+    /// Run `f` on the [`Mutable`] widget with the given id, once the
+    /// current event has been handled.
+    ///
+    /// See [`EventCtx::mutate_later`] for details.
+    ///
+    /// [`Mutable`]: crate::widget::Mutable
+    /// [`EventCtx::mutate_later`]: crate::EventCtx::mutate_later
+    pub fn mutate_later<W: 'static>(
+        &mut self,
+        id: WidgetId,
+        f: impl FnOnce(&mut W, &mut EventCtx) + Send + 'static,
+    ) {
+        self.submit_command(commands::MUTATE_WIDGET.with(mutation(f)).to(id))
+    }
+
     /// Returns an [`ExtEventSink`] that can be moved between threads,
     /// and can be used to submit commands back to the application.
     ///
@@ -54,6 +95,37 @@ impl<'a> DelegateCtx<'a> {
         self.ext_event_host.make_sink()
     }
 
+    /// Spawn `future`, and submit its output to be applied on the UI thread
+    /// once it completes.
+    #[cfg(feature = "async")]
+    pub(crate) fn spawn<T: 'static>(&self, future: DelegateFuture<T>) {
+        let sink = self.get_external_handle();
+        self.get_external_handle().spawn(async move {
+            let update: Box<dyn Any + Send> = Box::new(future.await);
+            if sink
+                .submit_command(
+                    commands::APPLY_ASYNC_UPDATE,
+                    SingleUse::new(update),
+                    Target::Global,
+                )
+                .is_err()
+            {
+                tracing::warn!("Application exited before an async command completed.");
+            }
+        });
+    }
+
+    /// Returns how long it has been since the user last interacted with
+    /// any window, via the mouse or the keyboard.
+    ///
+    /// See [`AppLauncher::idle_timeout`] for being notified when this exceeds
+    /// a threshold.
+    ///
+    /// [`AppLauncher::idle_timeout`]: crate::AppLauncher::idle_timeout
+    pub fn idle_duration(&self) -> Duration {
+        self.last_input.elapsed()
+    }
+
     /// Create a new window.
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
@@ -69,6 +141,23 @@ impl<'a> DelegateCtx<'a> {
             debug_panic!("DelegateCtx::new_window<T> - T must match the application data type.");
         }
     }
+
+    /// Returns the ids of the application's windows, in the order they were
+    /// created.
+    ///
+    /// This includes windows that have been created but are not open yet.
+    pub fn window_ids(&self) -> &[WindowId] {
+        self.window_ids
+    }
+
+    /// Close the window with the given id.
+    ///
+    /// This submits a [`CLOSE_WINDOW`] command to the window.
+    ///
+    /// [`CLOSE_WINDOW`]: crate::commands::CLOSE_WINDOW
+    pub fn close_window(&mut self, id: WindowId) {
+        self.submit_command(commands::CLOSE_WINDOW.to(id));
+    }
 }
 
 /// A type that provides hooks for handling and modifying top-level events.
@@ -125,6 +214,49 @@ pub trait AppDelegate<T: Data> {
         Handled::No
     }
 
+    /// Handle a [`Command`] with IO-bound work, such as a network request or
+    /// reading a file, without blocking the UI.
+    ///
+    /// This is called before [`command`]. If it returns a future, the command
+    /// is considered handled and the future is run on a background thread;
+    /// its output is then run on the UI thread, with mutable access to the
+    /// application data. Anything the future needs from `data` has to be
+    /// cloned into it.
+    ///
+    /// ```ignore
+    /// fn command_async(&mut self, _ctx: &mut DelegateCtx, _target: Target, cmd: &Command,
+    ///     _data: &AppState, _env: &Env) -> Option<DelegateFuture<AppState>> {
+    ///     let path = cmd.get(LOAD)?.clone();
+    ///     Some(Box::pin(async move {
+    ///         let text = std::fs::read_to_string(path).unwrap_or_default();
+    ///         Box::new(move |_ctx: &mut DelegateCtx, data: &mut AppState, _env: &Env| {
+    ///             data.text = text.into();
+    ///         }) as AsyncUpdate<AppState>
+    ///     }))
+    /// }
+    /// ```
+    ///
+    /// The future is run by the spawner set with [`AppLauncher::spawner`].
+    /// Without one, it is driven on a background thread by a minimal executor,
+    /// which doesn't run the reactor of any async runtime: futures that need
+    /// one, like most of tokio's, never complete unless a spawner hands them
+    /// to that runtime.
+    ///
+    /// [`AppLauncher::spawner`]: crate::AppLauncher::spawner
+    ///
+    /// [`command`]: #method.command
+    #[cfg(feature = "async")]
+    fn command_async(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        target: Target,
+        cmd: &Command,
+        data: &T,
+        env: &Env,
+    ) -> Option<DelegateFuture<T>> {
+        None
+    }
+
     /// The handler for window creation events.
     /// This function is called after a window has been added,
     /// allowing you to customize the window creation behavior of your app.
@@ -133,4 +265,92 @@ pub trait AppDelegate<T: Data> {
     /// The handler for window deletion events.
     /// This function is called after a window has been removed.
     fn window_removed(&mut self, id: WindowId, data: &mut T, env: &Env, ctx: &mut DelegateCtx) {}
+
+    /// Serialize the application state for autosave.
+    ///
+    /// When autosave is enabled with [`AppLauncher::autosave`], this is called
+    /// periodically, and the returned bytes are written to the [`SessionStore`].
+    /// Returning `None` skips this save.
+    ///
+    /// [`AppLauncher::autosave`]: crate::AppLauncher::autosave
+    /// [`SessionStore`]: crate::SessionStore
+    fn save_state(&mut self, data: &T, env: &Env) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Called on launch if the previous session ended unexpectedly, with the
+    /// last state returned from [`save_state`].
+    ///
+    /// This is called after the first window has been added, so it is a good
+    /// place to ask the user whether they want to restore their work; if so,
+    /// deserialize `saved_state` into `data`.
+    ///
+    /// [`save_state`]: #method.save_state
+    fn on_unexpected_exit(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        saved_state: Vec<u8>,
+        data: &mut T,
+        env: &Env,
+    ) {
+    }
+}
+
+#[cfg(feature = "async")]
+pub(crate) mod executor {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+
+    /// Wakes the thread that is blocked on a future.
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Run `future` to completion on the current thread.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::pin::Pin;
+
+        /// A future that is pending until it has been polled a few times.
+        struct Countdown(usize);
+
+        impl Future for Countdown {
+            type Output = &'static str;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+                if self.0 == 0 {
+                    Poll::Ready("done")
+                } else {
+                    self.0 -= 1;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+
+        #[test]
+        fn block_on_wakes_up() {
+            assert_eq!(block_on(Countdown(3)), "done");
+            assert_eq!(block_on(async { 4 + 2 }), 6);
+        }
+    }
 }
@@ -0,0 +1,371 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Color conversions and adjustments.
+//!
+//! This module adds the [`Hsl`] and [`Oklch`] color spaces, and the
+//! [`ColorExt`] trait with methods to derive colors from each other and to
+//! check that text is readable on a background.
+//!
+//! ```
+//! use druid::color::ColorExt;
+//! use druid::Color;
+//!
+//! let accent = Color::rgb8(0x35, 0x84, 0xe4);
+//! let hover = accent.lighten(0.05);
+//! let text = accent.readable_foreground();
+//! assert!(text.contrast_ratio(&accent) >= 3.0);
+//! ```
+
+use crate::{Color, Data};
+
+/// The contrast ratio that [WCAG] level AA asks for between normal text and
+/// its background.
+///
+/// [WCAG]: https://www.w3.org/TR/WCAG21/#contrast-minimum
+pub const WCAG_AA_CONTRAST: f64 = 4.5;
+
+/// The contrast ratio that [WCAG] level AAA asks for between normal text and
+/// its background.
+///
+/// [WCAG]: https://www.w3.org/TR/WCAG21/#contrast-enhanced
+pub const WCAG_AAA_CONTRAST: f64 = 7.0;
+
+/// A color in the HSL color space.
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub struct Hsl {
+    /// The hue, in degrees from 0 to 360.
+    pub hue: f64,
+    /// The saturation, from 0 to 1.
+    pub saturation: f64,
+    /// The lightness, from 0 to 1.
+    pub lightness: f64,
+    /// The opacity, from 0 to 1.
+    pub alpha: f64,
+}
+
+/// A color in the [OKLCH] color space, where changes of lightness and chroma
+/// look uniform across hues.
+///
+/// [OKLCH]: https://bottosson.github.io/posts/oklab/
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub struct Oklch {
+    /// The perceived lightness, from 0 to 1.
+    pub lightness: f64,
+    /// The chroma, from 0 to about 0.4.
+    pub chroma: f64,
+    /// The hue, in degrees from 0 to 360.
+    pub hue: f64,
+    /// The opacity, from 0 to 1.
+    pub alpha: f64,
+}
+
+/// Conversions and adjustments for [`Color`].
+pub trait ColorExt {
+    /// The color in the HSL color space.
+    fn to_hsl(&self) -> Hsl;
+
+    /// The color in the OKLCH color space.
+    fn to_oklch(&self) -> Oklch;
+
+    /// A lighter color, with the OKLCH lightness raised by `amount`.
+    fn lighten(&self, amount: f64) -> Color;
+
+    /// A darker color, with the OKLCH lightness lowered by `amount`.
+    fn darken(&self, amount: f64) -> Color;
+
+    /// The color `t` of the way from this color to `other`, with `t` from 0
+    /// to 1.
+    ///
+    /// The components are interpolated in sRGB, like CSS gradients do.
+    fn mix(&self, other: &Color, t: f64) -> Color;
+
+    /// The relative luminance, as defined by WCAG, from 0 for black to 1 for
+    /// white. The opacity is ignored.
+    fn relative_luminance(&self) -> f64;
+
+    /// The WCAG contrast ratio between this color and `other`, from 1 for the
+    /// same luminance to 21 for black and white.
+    fn contrast_ratio(&self, other: &Color) -> f64;
+
+    /// The one of `candidates` that contrasts most with this color, used as
+    /// a background.
+    ///
+    /// Returns `None` if there are no candidates.
+    fn most_readable<'a>(&self, candidates: &'a [Color]) -> Option<&'a Color>;
+
+    /// Black or white, whichever is more readable on this color.
+    fn readable_foreground(&self) -> Color;
+}
+
+impl Hsl {
+    /// Create an opaque color from its HSL components.
+    pub fn new(hue: f64, saturation: f64, lightness: f64) -> Self {
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+            alpha: 1.0,
+        }
+    }
+
+    /// Builder-style method to set the opacity.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// The color in the sRGB color space.
+    pub fn to_color(self) -> Color {
+        let hue = self.hue.rem_euclid(360.0) / 60.0;
+        let saturation = self.saturation.clamp(0.0, 1.0);
+        let lightness = self.lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        Color::rgba(r + m, g + m, b + m, self.alpha)
+    }
+}
+
+impl Oklch {
+    /// Create an opaque color from its OKLCH components.
+    pub fn new(lightness: f64, chroma: f64, hue: f64) -> Self {
+        Oklch {
+            lightness,
+            chroma,
+            hue,
+            alpha: 1.0,
+        }
+    }
+
+    /// Builder-style method to set the opacity.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// The color in the sRGB color space.
+    ///
+    /// Colors that sRGB can't show are clipped to the nearest one it can.
+    pub fn to_color(self) -> Color {
+        let (sin, cos) = self.hue.to_radians().sin_cos();
+        let (a, b) = (self.chroma * cos, self.chroma * sin);
+        let l = self.lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b;
+        let m = self.lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b;
+        let s = self.lightness - 0.089_484_177_5 * a - 1.291_485_548 * b;
+        let (l, m, s) = (l.powi(3), m.powi(3), s.powi(3));
+        let r = 4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s;
+        let g = -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s;
+        let b = -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701 * s;
+        Color::rgba(
+            linear_to_srgb(r),
+            linear_to_srgb(g),
+            linear_to_srgb(b),
+            self.alpha,
+        )
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Color {
+        hsl.to_color()
+    }
+}
+
+impl From<Oklch> for Color {
+    fn from(oklch: Oklch) -> Color {
+        oklch.to_color()
+    }
+}
+
+impl ColorExt for Color {
+    fn to_hsl(&self) -> Hsl {
+        let (r, g, b, alpha) = self.as_rgba();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+        if chroma == 0.0 {
+            return Hsl {
+                hue: 0.0,
+                saturation: 0.0,
+                lightness,
+                alpha,
+            };
+        }
+        let hue = if max == r {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+        Hsl {
+            hue: hue * 60.0,
+            saturation: chroma / (1.0 - (2.0 * lightness - 1.0).abs()),
+            lightness,
+            alpha,
+        }
+    }
+
+    fn to_oklch(&self) -> Oklch {
+        let (r, g, b, alpha) = self.as_rgba();
+        let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+        let l = 0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b;
+        let m = 0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b;
+        let s = 0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b;
+        let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+        let lightness = 0.210_454_255_3 * l + 0.793_617_785 * m - 0.004_072_046_8 * s;
+        let a = 1.977_998_495_1 * l - 2.428_592_205 * m + 0.450_593_709_9 * s;
+        let b = 0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766 * s;
+        Oklch {
+            lightness,
+            chroma: a.hypot(b),
+            hue: b.atan2(a).to_degrees().rem_euclid(360.0),
+            alpha,
+        }
+    }
+
+    fn lighten(&self, amount: f64) -> Color {
+        let mut oklch = self.to_oklch();
+        oklch.lightness = (oklch.lightness + amount).clamp(0.0, 1.0);
+        oklch.to_color()
+    }
+
+    fn darken(&self, amount: f64) -> Color {
+        self.lighten(-amount)
+    }
+
+    fn mix(&self, other: &Color, t: f64) -> Color {
+        let (r1, g1, b1, a1) = self.as_rgba();
+        let (r2, g2, b2, a2) = other.as_rgba();
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        Color::rgba(lerp(r1, r2), lerp(g1, g2), lerp(b1, b2), lerp(a1, a2))
+    }
+
+    fn relative_luminance(&self) -> f64 {
+        let (r, g, b, _) = self.as_rgba();
+        0.2126 * srgb_to_linear(r) + 0.7152 * srgb_to_linear(g) + 0.0722 * srgb_to_linear(b)
+    }
+
+    fn contrast_ratio(&self, other: &Color) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    fn most_readable<'a>(&self, candidates: &'a [Color]) -> Option<&'a Color> {
+        candidates.iter().max_by(|a, b| {
+            self.contrast_ratio(a)
+                .partial_cmp(&self.contrast_ratio(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+
+    fn readable_foreground(&self) -> Color {
+        if self.contrast_ratio(&Color::BLACK) >= self.contrast_ratio(&Color::WHITE) {
+            Color::BLACK
+        } else {
+            Color::WHITE
+        }
+    }
+}
+
+/// Convert an sRGB component to linear light.
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear light component to sRGB, clipped to the sRGB gamut.
+fn linear_to_srgb(c: f64) -> f64 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Color, b: &Color) {
+        let (a, b) = (a.as_rgba8(), b.as_rgba8());
+        let diff = [
+            a.0 as i32 - b.0 as i32,
+            a.1 as i32 - b.1 as i32,
+            a.2 as i32 - b.2 as i32,
+            a.3 as i32 - b.3 as i32,
+        ];
+        // allow for rounding to 8 bits
+        assert!(diff.iter().all(|d| d.abs() <= 1), "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn round_trips() {
+        let colors = [
+            Color::rgb8(0x35, 0x84, 0xe4),
+            Color::rgba8(0xe0, 0x1b, 0x24, 0x80),
+            Color::rgb8(0x26, 0xa2, 0x69),
+            Color::grey(0.5),
+            Color::WHITE,
+            Color::BLACK,
+        ];
+        for color in &colors {
+            assert_close(&color.to_hsl().to_color(), color);
+            assert_close(&color.to_oklch().to_color(), color);
+        }
+        let red = Color::rgb(1.0, 0.0, 0.0).to_hsl();
+        assert_eq!((red.hue, red.saturation, red.lightness), (0.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn contrast() {
+        assert!((Color::BLACK.contrast_ratio(&Color::WHITE) - 21.0).abs() < 1e-9);
+        assert!((Color::WHITE.contrast_ratio(&Color::WHITE) - 1.0).abs() < 1e-9);
+        assert_eq!(
+            Color::rgb8(0xf0, 0xe0, 0x40).readable_foreground(),
+            Color::BLACK
+        );
+        assert_eq!(
+            Color::rgb8(0x20, 0x30, 0x80).readable_foreground(),
+            Color::WHITE
+        );
+
+        let background = Color::grey(0.2);
+        let candidates = [Color::grey(0.3), Color::grey(0.9), Color::grey(0.1)];
+        assert_eq!(background.most_readable(&candidates), Some(&candidates[1]));
+    }
+
+    #[test]
+    fn adjustments() {
+        let accent = Color::rgb8(0x35, 0x84, 0xe4);
+        assert!(accent.lighten(0.1).relative_luminance() > accent.relative_luminance());
+        assert!(accent.darken(0.1).relative_luminance() < accent.relative_luminance());
+        assert_close(&Color::BLACK.mix(&Color::WHITE, 0.5), &Color::grey(0.5));
+    }
+}
@@ -173,16 +173,41 @@ pub enum Target {
 /// [`Command`]: ../struct.Command.html
 pub mod sys {
     use std::any::Any;
+    use std::path::PathBuf;
 
-    use super::Selector;
+    use super::{Command, Selector};
     use crate::{
         sub_window::{SubWindowDesc, SubWindowUpdate},
-        FileDialogOptions, FileInfo, SingleUse, WidgetId, WindowConfig,
+        AccessoryPosition, ExtInputEvent, FileDialogOptions, FileInfo, HotKey, ImageBuf,
+        NotificationToken, Rect, ShellError, ShortcutMap, SingleUse, TaskProgress, ToolbarItem,
+        ToolbarStyle, WidgetId, WindowConfig,
     };
 
     /// Quit the running application. This command is handled by the druid library.
+    ///
+    /// The application quits right away, without asking the windows; use
+    /// [`REQUEST_QUIT`] to let them veto.
+    ///
+    /// [`REQUEST_QUIT`]: REQUEST_QUIT
     pub const QUIT_APP: Selector = Selector::new("druid-builtin.quit-app");
 
+    /// Ask the windows whether the application may quit, and quit if they
+    /// agree. This command is handled by the druid library.
+    ///
+    /// Every window is asked first, as with [`CLOSE_WINDOW`]. If any of them
+    /// vetoes, the quit waits until each of those windows has been sent
+    /// [`CONFIRM_CLOSE`], and is abandoned if one of them is sent [`CANCEL_CLOSE`].
+    /// [`QUIT_APP`] quits even while the windows are being asked.
+    ///
+    /// This is sent by the 'Quit' and 'Exit' menu items, and when the system
+    /// asks the application to quit, for example because the user logs out.
+    ///
+    /// [`CLOSE_WINDOW`]: CLOSE_WINDOW
+    /// [`CONFIRM_CLOSE`]: CONFIRM_CLOSE
+    /// [`CANCEL_CLOSE`]: CANCEL_CLOSE
+    /// [`QUIT_APP`]: QUIT_APP
+    pub const REQUEST_QUIT: Selector = Selector::new("druid-builtin.request-quit");
+
     /// Hide the application. (mac only)
     #[cfg_attr(
         not(target_os = "macos"),
@@ -201,6 +226,20 @@ pub mod sys {
     pub(crate) const NEW_WINDOW: Selector<SingleUse<Box<dyn Any>>> =
         Selector::new("druid-builtin.new-window");
 
+    /// Make the target window fullscreen, or leave fullscreen if it already is.
+    ///
+    /// The window goes fullscreen on the monitor it is on. To pick the
+    /// monitor, use [`CONFIGURE_WINDOW`] with
+    /// [`WindowConfig::set_fullscreen_monitor`]. Whether a window is
+    /// fullscreen is reported by [`WindowHandle::is_fullscreen`].
+    ///
+    /// The command must target a specific window.
+    ///
+    /// [`CONFIGURE_WINDOW`]: CONFIGURE_WINDOW
+    /// [`WindowConfig::set_fullscreen_monitor`]: crate::WindowConfig::set_fullscreen_monitor
+    /// [`WindowHandle::is_fullscreen`]: crate::WindowHandle::is_fullscreen
+    pub const TOGGLE_FULLSCREEN: Selector = Selector::new("druid-builtin.toggle-fullscreen");
+
     /// The selector for a command to close a window.
     ///
     /// The command must target a specific window.
@@ -211,6 +250,28 @@ pub mod sys {
     /// Close all windows.
     pub const CLOSE_ALL_WINDOWS: Selector = Selector::new("druid-builtin.close-all-windows");
 
+    /// Finish closing a window that vetoed [`CLOSE_WINDOW`], for instance
+    /// after the user chose to save or discard their changes.
+    ///
+    /// The window is closed without asking again. If it vetoed while the
+    /// application was quitting, the quit carries on instead.
+    ///
+    /// The command must target a specific window.
+    ///
+    /// [`CLOSE_WINDOW`]: CLOSE_WINDOW
+    pub const CONFIRM_CLOSE: Selector = Selector::new("druid-builtin.confirm-close");
+
+    /// Keep a window that vetoed [`CLOSE_WINDOW`] open, for instance because
+    /// the user cancelled the "save changes?" dialog.
+    ///
+    /// If the window vetoed while the application was quitting, this
+    /// abandons the quit. Otherwise it has no effect.
+    ///
+    /// The command must target a specific window.
+    ///
+    /// [`CLOSE_WINDOW`]: CLOSE_WINDOW
+    pub const CANCEL_CLOSE: Selector = Selector::new("druid-builtin.cancel-close");
+
     /// The selector for a command to bring a window to the front, and give it focus.
     ///
     /// The command must target a specific window.
@@ -218,10 +279,78 @@ pub mod sys {
     /// will automatically target the window containing the widget.
     pub const SHOW_WINDOW: Selector = Selector::new("druid-builtin.show-window");
 
+    /// Set the factor by which a window scales its content, for instance to zoom
+    /// in for a presentation. The target should be a WindowId.
+    ///
+    /// See [`WindowDesc::content_scale`] for details.
+    ///
+    /// [`WindowDesc::content_scale`]: crate::WindowDesc::content_scale
+    pub const SET_CONTENT_SCALE: Selector<f64> = Selector::new("druid-builtin.set-content-scale");
+
+    /// Replace a set of keyboard shortcuts.
+    ///
+    /// With [`Target::Global`] this replaces the shortcuts of the application,
+    /// with a [`Target::Window`] those of that window, and with a
+    /// [`Target::Widget`] those of a [`ShortcutScope`].
+    ///
+    /// See [`ShortcutMap`] for details.
+    ///
+    /// [`Target::Global`]: crate::Target::Global
+    /// [`Target::Window`]: crate::Target::Window
+    /// [`Target::Widget`]: crate::Target::Widget
+    /// [`ShortcutScope`]: crate::widget::ShortcutScope
+    /// [`ShortcutMap`]: crate::ShortcutMap
+    pub const SET_SHORTCUTS: Selector<ShortcutMap> = Selector::new("druid-builtin.set-shortcuts");
+
+    /// Turn raw key input on or off for a window.
+    ///
+    /// See [`WindowDesc::raw_key_input`] for details. The command must
+    /// target a specific window.
+    ///
+    /// [`WindowDesc::raw_key_input`]: crate::WindowDesc::raw_key_input
+    pub const SET_RAW_KEY_INPUT: Selector<bool> = Selector::new("druid-builtin.set-raw-key-input");
+
+    /// Sent to the widget that started a drag with [`EventCtx::start_drag`]
+    /// when the drag ends, with `true` if the contents were dropped on a
+    /// widget that took them.
+    ///
+    /// [`EventCtx::start_drag`]: crate::EventCtx::start_drag
+    pub const DRAG_ENDED: Selector<bool> = Selector::new("druid-builtin.drag-ended");
+
     /// Apply the configuration payload to an existing window. The target should be a WindowId.
     pub const CONFIGURE_WINDOW: Selector<WindowConfig> =
         Selector::new("druid-builtin.configure-window");
 
+    /// Set the items in a window's toolbar, replacing any existing toolbar. An
+    /// empty list removes the toolbar. (mac only)
+    ///
+    /// The command must target a specific window. On other platforms it has
+    /// no effect.
+    pub const SET_TOOLBAR: Selector<Vec<ToolbarItem>> = Selector::new("druid-builtin.set-toolbar");
+
+    /// Set the style of a window's toolbar. (mac only)
+    ///
+    /// The command must target a specific window. On other platforms it has
+    /// no effect.
+    pub const SET_TOOLBAR_STYLE: Selector<ToolbarStyle> =
+        Selector::new("druid-builtin.set-toolbar-style");
+
+    /// Set the buttons of an accessory in a window's titlebar, replacing any
+    /// existing accessory. An empty list removes the accessory. (mac only)
+    ///
+    /// The command must target a specific window. On other platforms it has
+    /// no effect.
+    pub const SET_TITLEBAR_ACCESSORY: Selector<(Vec<ToolbarItem>, AccessoryPosition)> =
+        Selector::new("druid-builtin.set-titlebar-accessory");
+
+    /// Set whether a window's titlebar is transparent, with the window's
+    /// content extending underneath it. (mac only)
+    ///
+    /// The command must target a specific window. On other platforms it has
+    /// no effect.
+    pub const SET_TITLEBAR_TRANSPARENT: Selector<bool> =
+        Selector::new("druid-builtin.set-titlebar-transparent");
+
     /// Display a context (right-click) menu. The payload must be the [`ContextMenu`]
     /// object to be displayed.
     ///
@@ -242,12 +371,155 @@ pub mod sys {
     pub(crate) const SUB_WINDOW_HOST_TO_PARENT: Selector<Box<dyn Any>> =
         Selector::new("druid-builtin.host_to_parent");
 
+    /// Mutate a [`Mutable`] widget. The payload is the mutation closure.
+    ///
+    /// [`Mutable`]: crate::widget::Mutable
+    pub(crate) const MUTATE_WIDGET: Selector<SingleUse<Box<dyn Any + Send>>> =
+        Selector::new("druid-builtin.mutate-widget");
+
+    /// Apply the result of a future returned from [`AppDelegate::command_async`].
+    /// The payload is the boxed [`AsyncUpdate`].
+    ///
+    /// [`AppDelegate::command_async`]: crate::AppDelegate::command_async
+    /// [`AsyncUpdate`]: crate::AsyncUpdate
+    #[cfg(feature = "async")]
+    pub(crate) const APPLY_ASYNC_UPDATE: Selector<SingleUse<Box<dyn Any + Send>>> =
+        Selector::new("druid-builtin.apply-async-update");
+
+    /// A notification asking enclosing scroll containers to bring a rect,
+    /// in window coordinates, into view.
+    ///
+    /// This is usually submitted with [`EventCtx::scroll_to_view`] or
+    /// [`EventCtx::scroll_area_to_view`]. A container that handles it should
+    /// scroll the rect into view, and then submit a new notification with the
+    /// part of the rect that is visible in it, so that its own enclosing
+    /// containers can bring that into view in turn.
+    ///
+    /// [`EventCtx::scroll_to_view`]: crate::EventCtx::scroll_to_view
+    /// [`EventCtx::scroll_area_to_view`]: crate::EventCtx::scroll_area_to_view
+    pub const SCROLL_TO_VIEW: Selector<Rect> = Selector::new("druid-builtin.scroll-to-view");
+
+    /// Sent to a widget to ask for its layout rect, in window coordinates.
+    ///
+    /// This is handled by the widget's [`WidgetPod`], which answers by sending
+    /// a [`WIDGET_RECT`] command to the widget in the payload.
+    ///
+    /// [`WidgetPod`]: crate::WidgetPod
+    pub(crate) const REQUEST_WIDGET_RECT: Selector<WidgetId> =
+        Selector::new("druid-builtin.request-widget-rect");
+
+    /// The answer to a [`REQUEST_WIDGET_RECT`] command, with the id of the
+    /// widget that was asked and its layout rect in window coordinates.
+    pub(crate) const WIDGET_RECT: Selector<(WidgetId, Rect)> =
+        Selector::new("druid-builtin.widget-rect");
+
+    /// Sent to a widget to ask for an image of it, with the id of the widget
+    /// asking and the scale of the image.
+    ///
+    /// This is handled by the widget's [`WidgetPod`], which paints the image
+    /// during the next paint and answers with an [`IMAGE_CAPTURED`] command.
+    ///
+    /// [`WidgetPod`]: crate::WidgetPod
+    pub(crate) const CAPTURE_IMAGE: Selector<(WidgetId, f64)> =
+        Selector::new("druid-builtin.capture-image");
+
+    /// An image of a widget, asked for with [`EventCtx::capture_image`].
+    ///
+    /// The payload is the id of the widget that was painted, and the image.
+    /// The image has premultiplied alpha, and is transparent wherever the
+    /// widget doesn't paint.
+    ///
+    /// [`EventCtx::capture_image`]: crate::EventCtx::capture_image
+    pub const IMAGE_CAPTURED: Selector<(WidgetId, ImageBuf)> =
+        Selector::new("druid-builtin.image-captured");
+
+    /// Sent by a [`ProgressReporter`] when the progress of its task changes.
+    ///
+    /// [`ProgressReporter`]: crate::ProgressReporter
+    pub(crate) const UPDATE_TASK_PROGRESS: Selector<TaskProgress> =
+        Selector::new("druid-builtin.update-task-progress");
+
+    /// Dispatch an input event that was submitted with
+    /// [`ExtEventSink::submit_event`] to the target window.
+    ///
+    /// [`ExtEventSink::submit_event`]: crate::ExtEventSink::submit_event
+    pub(crate) const SUBMIT_EVENT: Selector<ExtInputEvent> =
+        Selector::new("druid-builtin.submit-event");
+
     /// Show the application preferences.
     pub const SHOW_PREFERENCES: Selector = Selector::new("druid-builtin.menu-show-preferences");
 
     /// Show the application about window.
+    ///
+    /// This is handled by druid if the app has an [`AboutInfo`]; see
+    /// [`AppLauncher::about`].
+    ///
+    /// [`AboutInfo`]: crate::AboutInfo
+    /// [`AppLauncher::about`]: crate::AppLauncher::about
     pub const SHOW_ABOUT: Selector = Selector::new("druid-builtin.menu-show-about");
 
+    /// Sent to the application when its icon in the system tray is clicked.
+    ///
+    /// See [`AppLauncher::tray`].
+    ///
+    /// [`AppLauncher::tray`]: crate::AppLauncher::tray
+    pub const TRAY_ACTIVATED: Selector = Selector::new("druid-builtin.tray-activated");
+
+    /// Sent to the application when a notification is clicked.
+    ///
+    /// The payload is the token returned by [`Application::show_notification`],
+    /// and the index of the clicked action button, if any.
+    ///
+    /// [`Application::show_notification`]: crate::Application::show_notification
+    pub const NOTIFICATION_ACTIVATED: Selector<(NotificationToken, Option<usize>)> =
+        Selector::new("druid-builtin.notification-activated");
+
+    /// Register a system-wide hotkey, which submits the given [`Command`]
+    /// whenever it is pressed, even while the application doesn't have focus.
+    ///
+    /// Registering a hotkey again replaces its command. If the hotkey can't be
+    /// registered, for instance because another application already uses it,
+    /// a [`SHELL_ERROR`] is submitted to the application.
+    ///
+    /// This command is handled by druid, and can have any target.
+    ///
+    /// [`SHELL_ERROR`]: SHELL_ERROR
+    pub const REGISTER_GLOBAL_HOTKEY: Selector<(HotKey, Command)> =
+        Selector::new("druid-builtin.register-global-hotkey");
+
+    /// Unregister a hotkey registered with [`REGISTER_GLOBAL_HOTKEY`].
+    pub const UNREGISTER_GLOBAL_HOTKEY: Selector<HotKey> =
+        Selector::new("druid-builtin.unregister-global-hotkey");
+
+    /// Open a URL with the user's preferred application, such as the web
+    /// browser.
+    ///
+    /// Only `http`, `https` and `mailto` URLs are opened, so that a link, for
+    /// instance in a document the user opened, can't start a program. Other
+    /// URLs, including `file:` URLs and paths, fail with
+    /// [`Error::Unsupported`]; use [`REVEAL_PATH`] to show local files.
+    ///
+    /// This doesn't block the UI while the application starts. If the URL
+    /// can't be opened, a [`SHELL_ERROR`] is submitted to the application.
+    ///
+    /// This command is handled by druid, and can have any target.
+    ///
+    /// [`Error::Unsupported`]: crate::PlatformError::Unsupported
+    /// [`REVEAL_PATH`]: REVEAL_PATH
+    /// [`SHELL_ERROR`]: SHELL_ERROR
+    pub const OPEN_URL: Selector<String> = Selector::new("druid-builtin.open-url");
+
+    /// Show a file or directory in the system file manager, with it selected.
+    ///
+    /// See [`Application::reveal_path`] for the platform differences. If the
+    /// path can't be shown, a [`SHELL_ERROR`] is submitted to the application.
+    ///
+    /// This command is handled by druid, and can have any target.
+    ///
+    /// [`Application::reveal_path`]: crate::Application::reveal_path
+    /// [`SHELL_ERROR`]: SHELL_ERROR
+    pub const REVEAL_PATH: Selector<PathBuf> = Selector::new("druid-builtin.reveal-path");
+
     /// Show all applications.
     pub const SHOW_ALL: Selector = Selector::new("druid-builtin.menu-show-all");
 
@@ -315,6 +587,26 @@ pub mod sys {
     /// Paste.
     pub const PASTE: Selector = Selector::new("druid-builtin.menu-paste");
 
+    /// Sent to every window when the contents of the system clipboard may have
+    /// changed, for instance to enable or disable a paste button.
+    ///
+    /// Not every platform can report this as it happens; see
+    /// [`WinHandler::clipboard_changed`] for the details.
+    ///
+    /// [`WinHandler::clipboard_changed`]: crate::shell::WinHandler::clipboard_changed
+    pub const CLIPBOARD_CHANGED: Selector = Selector::new("druid-builtin.clipboard-changed");
+
+    /// Sent when an operation of the platform shell fails, such as setting
+    /// the clipboard or showing a file dialog.
+    ///
+    /// Failures that belong to a window are sent to that window; failures to
+    /// create a window are sent with [`Target::Global`], so they reach the
+    /// [`AppDelegate`] first.
+    ///
+    /// [`Target::Global`]: crate::Target::Global
+    /// [`AppDelegate`]: crate::AppDelegate
+    pub const SHELL_ERROR: Selector<ShellError> = Selector::new("druid-builtin.shell-error");
+
     /// Undo.
     pub const UNDO: Selector = Selector::new("druid-builtin.menu-undo");
 
@@ -324,6 +616,17 @@ pub mod sys {
     /// Select all.
     pub const SELECT_ALL: Selector = Selector::new("druid-builtin.menu-select-all");
 
+    /// Add a caret on the line above the topmost caret.
+    pub const ADD_CURSOR_ABOVE: Selector = Selector::new("druid-builtin.add-cursor-above");
+
+    /// Add a caret on the line below the bottommost caret.
+    pub const ADD_CURSOR_BELOW: Selector = Selector::new("druid-builtin.add-cursor-below");
+
+    /// Select the word under the caret, or add a selection for the next
+    /// occurrence of the selected text.
+    pub const SELECT_NEXT_OCCURRENCE: Selector =
+        Selector::new("druid-builtin.select-next-occurrence");
+
     /// Text input state has changed, and we need to notify the platform.
     pub(crate) const INVALIDATE_IME: Selector<ImeInvalidation> =
         Selector::new("druid-builtin.invalidate-ime");
@@ -437,6 +740,11 @@ impl Command {
         self.target
     }
 
+    /// The symbol of the [`Selector`] this command was created with.
+    pub(crate) fn symbol(&self) -> SelectorSymbol {
+        self.symbol
+    }
+
     /// Returns `true` if `self` matches this `selector`.
     pub fn is<T>(&self, selector: Selector<T>) -> bool {
         self.symbol == selector.symbol()
@@ -574,6 +882,14 @@ impl Target {
             *self = target;
         }
     }
+
+    /// The window this targets, if it targets a window.
+    pub(crate) fn window_id(self) -> Option<WindowId> {
+        match self {
+            Target::Window(id) => Some(id),
+            _ => None,
+        }
+    }
 }
 
 impl From<WindowId> for Target {
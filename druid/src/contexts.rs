@@ -23,17 +23,23 @@ use std::{
 };
 use tracing::{error, trace, warn};
 
-use crate::core::{CommandQueue, CursorChange, FocusChange, WidgetState};
+use crate::core::{CommandQueue, CursorChange, FocusChange, FocusEntry, WidgetState};
+use crate::dnd::DragSession;
 use crate::env::KeyLike;
+use crate::kurbo::{RoundedRect, Shape};
 use crate::menu::ContextMenu;
-use crate::piet::{Piet, PietText, RenderContext};
+use crate::piet::{
+    Device, Error as PietError, ImageFormat, IntoBrush, Piet, PietText, RenderContext,
+};
 use crate::shell::text::Event as ImeInvalidation;
 use crate::shell::Region;
 use crate::text::{ImeHandlerRef, TextFieldRegistration};
+use crate::widget::mutable::mutation;
 use crate::{
-    commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data, Env,
-    ExtEventSink, Insets, Menu, Notification, Point, Rect, SingleUse, Size, Target, TimerToken,
-    Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
+    commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data,
+    DragContents, DragImage, Env, ExtEventSink, ImageBuf, Insets, Menu, Notification, Point, Rect,
+    Scale, SingleUse, Size, Target, TimerToken, Vec2, WidgetId, WindowConfig, WindowDesc,
+    WindowHandle, WindowId,
 };
 
 /// A macro for implementing methods on multiple contexts.
@@ -60,6 +66,36 @@ pub(crate) struct ContextState<'a> {
     /// The id of the widget that currently has focus.
     pub(crate) focus_widget: Option<WidgetId>,
     pub(crate) root_app_data_type: TypeId,
+    /// The window's content scale; see [`WindowDesc::content_scale`].
+    ///
+    /// [`WindowDesc::content_scale`]: crate::WindowDesc::content_scale
+    pub(crate) content_scale: f64,
+    /// The raised widgets that were painted in this paint pass.
+    pub(crate) raised_widgets: Vec<RaisedWidget>,
+    /// The topmost raised widget under the mouse, during a mouse event.
+    ///
+    /// It is cleared while the event is passed through the widget itself.
+    pub(crate) raised_under_mouse: Option<RaisedWidget>,
+    /// The ids of the widgets being painted, from the root down.
+    pub(crate) paint_ancestors: Vec<WidgetId>,
+    /// How many offscreen images are being painted into.
+    pub(crate) offscreen_depth: u32,
+    /// The device for painting offscreen images, kept by the window between
+    /// paint passes; see [`PaintCtx::paint_to_image`].
+    pub(crate) offscreen_device: Option<Device>,
+    /// Whether widgets record [`Interaction`]s, because the app has a sink
+    /// for them.
+    ///
+    /// [`Interaction`]: crate::Interaction
+    pub(crate) record_interactions: bool,
+    /// Whether the [`PixelSnapping`] policy of the widget being painted snaps
+    /// strokes; see [`PaintCtx::stroke`].
+    ///
+    /// [`PixelSnapping`]: crate::PixelSnapping
+    pub(crate) snap_strokes: bool,
+    /// How far the widgets being painted were moved to put their origins on
+    /// device pixels, in window coordinates.
+    pub(crate) paint_snap_offset: Vec2,
 }
 
 /// A mutable context provided to event handling methods of widgets.
@@ -121,6 +157,20 @@ pub(crate) struct ZOrderPaintOp {
     pub transform: Affine,
 }
 
+/// A widget raised above its siblings with [`set_z_index`], as it was last
+/// painted.
+///
+/// [`set_z_index`]: EventCtx::set_z_index
+#[derive(Debug, Clone)]
+pub(crate) struct RaisedWidget {
+    pub id: WidgetId,
+    pub z_index: u32,
+    /// The layout rect, in window coordinates.
+    pub rect: Rect,
+    /// The ids of the widget's ancestors, from the root down.
+    pub ancestors: Vec<WidgetId>,
+}
+
 /// A context passed to paint methods of widgets.
 ///
 /// In addition to the API below, [`PaintCtx`] derefs to an implemention of
@@ -166,6 +216,12 @@ impl_context_method!(
         pub fn text(&mut self) -> &mut PietText {
             &mut self.state.text
         }
+
+        /// The scale factor of the current window, from display points to
+        /// device pixels.
+        pub fn scale(&self) -> Scale {
+            self.state.window.get_scale().unwrap_or_default()
+        }
     }
 );
 
@@ -189,17 +245,58 @@ impl_context_method!(
             self.widget_state.size()
         }
 
+        /// How far this widget is raised above its siblings.
+        ///
+        /// See [`set_z_index`] for more information.
+        ///
+        /// [`set_z_index`]: EventCtx::set_z_index
+        pub fn z_index(&self) -> u32 {
+            self.widget_state.z_index
+        }
+
         /// The origin of the widget in window coordinates, relative to the top left corner of the
         /// content area.
         pub fn window_origin(&self) -> Point {
             self.widget_state.window_origin()
         }
 
+        /// The part of the widget that can be seen through the ancestors that
+        /// clip it, like a [`Scroll`], in the widget's coordinate space.
+        ///
+        /// This is the whole widget unless it is clipped. It is up to date
+        /// once the widget is laid out, and whenever it is scrolled.
+        ///
+        /// [`Scroll`]: crate::widget::Scroll
+        pub fn visible_rect(&self) -> Rect {
+            let rect = self.size().to_rect();
+            match self.widget_state.clip {
+                Some(clip) => rect.intersect(clip - self.window_origin().to_vec2()),
+                None => rect,
+            }
+        }
+
         /// Convert a point from the widget's coordinate space to the window's.
         ///
         /// The returned point is relative to the content area; it excludes window chrome.
+        /// It accounts for the window's [`content_scale`].
+        ///
+        /// [`content_scale`]: Self::content_scale
         pub fn to_window(&self, widget_point: Point) -> Point {
-            self.window_origin() + widget_point.to_vec2()
+            let point = self.window_origin() + widget_point.to_vec2();
+            let scale = self.state.content_scale;
+            Point::new(point.x * scale, point.y * scale)
+        }
+
+        /// The factor by which the window scales its content, on top of the
+        /// platform's own scaling.
+        ///
+        /// This is `1.0` unless it was changed with [`WindowDesc::content_scale`]
+        /// or [`SET_CONTENT_SCALE`].
+        ///
+        /// [`WindowDesc::content_scale`]: crate::WindowDesc::content_scale
+        /// [`SET_CONTENT_SCALE`]: crate::commands::SET_CONTENT_SCALE
+        pub fn content_scale(&self) -> f64 {
+            self.state.content_scale
         }
 
         /// Convert a point from the widget's coordinate space to the screen's.
@@ -244,6 +341,16 @@ impl_context_method!(
             self.widget_state.is_active
         }
 
+        /// Returns `true` if any descendant of this widget is [active].
+        ///
+        /// During event handling this reflects the children the event was
+        /// already passed to.
+        ///
+        /// [active]: #method.is_active
+        pub fn has_active(&self) -> bool {
+            self.widget_state.has_active
+        }
+
         /// The focus status of a widget.
         ///
         /// Returns `true` if this specific widget is focused.
@@ -304,10 +411,15 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, {
     /// cursor, the child widget's cursor will take precedence. (If that isn't what you want, use
     /// [`override_cursor`] instead.)
     ///
+    /// Besides the standard cursors, a cursor can be made from an image with
+    /// [`WindowHandle::make_cursor`], once [`Event::WindowConnected`] has been received.
+    ///
     /// [`clear_cursor`]: EventCtx::clear_cursor
     /// [`override_cursor`]: EventCtx::override_cursor
     /// [`hot`]: EventCtx::is_hot
     /// [`active`]: EventCtx::is_active
+    /// [`WindowHandle::make_cursor`]: crate::WindowHandle::make_cursor
+    /// [`Event::WindowConnected`]: crate::Event::WindowConnected
     pub fn set_cursor(&mut self, cursor: &Cursor) {
         trace!("set_cursor {:?}", cursor);
         self.widget_state.cursor_change = CursorChange::Set(cursor.clone());
@@ -364,6 +476,20 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
         self.widget_state.invalid.add_rect(rect);
     }
 
+    /// Request a [`paint`] pass for redrawing several rectangles, which are
+    /// given relative to our layout rectangle.
+    ///
+    /// This is useful for widgets that manage many items of their own, such
+    /// as a [`BoardGrid`], and can tell which of those items have changed.
+    ///
+    /// [`paint`]: trait.Widget.html#tymethod.paint
+    /// [`BoardGrid`]: crate::widget::BoardGrid
+    pub fn request_paint_rects(&mut self, rects: impl IntoIterator<Item = Rect>) {
+        for rect in rects {
+            self.request_paint_rect(rect);
+        }
+    }
+
     /// Request a layout pass.
     ///
     /// A Widget's [`layout`] method is always called when the widget tree
@@ -377,6 +503,7 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
     pub fn request_layout(&mut self) {
         trace!("request_layout");
         self.widget_state.needs_layout = true;
+        self.widget_state.layout_requested = true;
     }
 
     /// Request an animation frame.
@@ -429,6 +556,47 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
         self.submit_command(cmd);
     }
 
+    /// Ask for an image of the widget `widget_id` and its children.
+    ///
+    /// The widget is painted offscreen during the next paint, with `scale`
+    /// image pixels per display point, and the image is sent to this widget
+    /// as an [`IMAGE_CAPTURED`] command. Nothing is sent if the widget isn't
+    /// painted, for instance because it is in a hidden tab.
+    ///
+    /// [`IMAGE_CAPTURED`]: crate::commands::IMAGE_CAPTURED
+    pub fn capture_image(&mut self, widget_id: WidgetId, scale: f64) {
+        let request = commands::CAPTURE_IMAGE
+            .with((self.widget_id(), scale))
+            .to(widget_id);
+        self.submit_command(request);
+    }
+
+    /// Paint this widget above its siblings, and above the rest of the window.
+    ///
+    /// Widgets are normally painted in tree order, clipped by their
+    /// ancestors. A widget with a `z_index` above zero is painted after the
+    /// rest of the window instead, in order of the `z_index` along with the
+    /// operations from [`paint_with_z_index`], and isn't clipped by its
+    /// ancestors; this lets a dropdown, a drag preview or a focused card
+    /// extend over the widgets around it. While the mouse is over a raised
+    /// widget, the widgets below it don't become hot, and mouse events go to
+    /// the raised widget even if it is outside of its parent.
+    ///
+    /// A raised widget is painted into an offscreen image, which is kept
+    /// while the widget and its descendants don't request a paint or layout.
+    /// A `z_index` of zero paints the widget in tree order again.
+    ///
+    /// [`paint_with_z_index`]: PaintCtx::paint_with_z_index
+    pub fn set_z_index(&mut self, z_index: u32) {
+        if self.widget_state.z_index != z_index {
+            self.widget_state.z_index = z_index;
+            // The widget may have been painted outside of its parent.
+            let rect = self.widget_state.window_paint_rect();
+            self.widget_state.window_invalid.add_rect(rect);
+            self.request_paint();
+        }
+    }
+
     /// Create a new sub-window.
     ///
     /// The sub-window will have its app data synchronised with caller's nearest ancestor [`WidgetPod`].
@@ -478,6 +646,21 @@ impl_context_method!(
             self.state.submit_command(cmd.into())
         }
 
+        /// Run `f` on the [`Mutable`] widget with the given id, after this
+        /// event is handled.
+        ///
+        /// `W` must be the type of the widget inside the [`Mutable`];
+        /// if it isn't, the mutation is skipped and a warning is logged.
+        ///
+        /// [`Mutable`]: crate::widget::Mutable
+        pub fn mutate_later<W: 'static>(
+            &mut self,
+            id: WidgetId,
+            f: impl FnOnce(&mut W, &mut EventCtx) + Send + 'static,
+        ) {
+            self.submit_command(commands::MUTATE_WIDGET.with(mutation(f)).to(id))
+        }
+
         /// Returns an [`ExtEventSink`] that can be moved between threads,
         /// and can be used to submit commands back to the application.
         ///
@@ -491,10 +674,32 @@ impl_context_method!(
         ///
         /// The return value is a token, which can be used to associate the
         /// request with the event.
+        ///
+        /// The timer belongs to this widget: it is only delivered to this
+        /// widget, and it is cancelled if the widget is removed from the tree
+        /// before it fires.
         pub fn request_timer(&mut self, deadline: Duration) -> TimerToken {
             trace!("request_timer deadline={:?}", deadline);
             self.state.request_timer(&mut self.widget_state, deadline)
         }
+
+        /// Cancel a timer that this widget requested, so that its event is
+        /// never delivered.
+        ///
+        /// Returns `false` if the timer already fired or was cancelled, or if
+        /// it wasn't requested by this widget.
+        pub fn cancel_timer(&mut self, token: TimerToken) -> bool {
+            trace!("cancel_timer token={:?}", token);
+            self.widget_state.cancel_timer(token)
+        }
+
+        /// The time left before a timer that this widget requested fires.
+        ///
+        /// Returns `None` if the timer already fired or was cancelled, or if
+        /// it wasn't requested by this widget.
+        pub fn timer_remaining(&self, token: TimerToken) -> Option<Duration> {
+            self.widget_state.timer_remaining(token)
+        }
     }
 );
 
@@ -528,6 +733,29 @@ impl EventCtx<'_, '_> {
         self.notifications.push_back(note);
     }
 
+    /// Ask enclosing scroll containers to scroll so that this widget is
+    /// visible.
+    ///
+    /// This submits a [`SCROLL_TO_VIEW`] notification with the widget's
+    /// layout rect. Nested scroll containers each bring the rect into view.
+    ///
+    /// [`SCROLL_TO_VIEW`]: crate::commands::SCROLL_TO_VIEW
+    pub fn scroll_to_view(&mut self) {
+        self.scroll_area_to_view(self.size().to_rect());
+    }
+
+    /// Ask enclosing scroll containers to scroll so that `area`, in this
+    /// widget's coordinate space, is visible.
+    ///
+    /// See [`scroll_to_view`] for more information.
+    ///
+    /// [`scroll_to_view`]: EventCtx::scroll_to_view
+    pub fn scroll_area_to_view(&mut self, area: Rect) {
+        trace!("scroll_area_to_view {:?}", area);
+        let area = area + self.window_origin().to_vec2();
+        self.submit_notification(commands::SCROLL_TO_VIEW.with(area));
+    }
+
     /// Set the "active" state of the widget.
     ///
     /// See [`EventCtx::is_active`](struct.EventCtx.html#method.is_active).
@@ -615,6 +843,45 @@ impl EventCtx<'_, '_> {
         self.widget_state.request_focus = Some(FocusChange::Focus(target));
     }
 
+    /// Start dragging `contents`, with `image` following the cursor.
+    ///
+    /// This is usually called while handling a mouse move with the left
+    /// button down, once the mouse moved far enough from where the button
+    /// went down. The drag lasts until the button is released, or until
+    /// Escape is pressed. The widgets the drag moves over receive
+    /// [`Event::DragEnter`], [`Event::DragOver`], [`Event::DragLeave`] and
+    /// [`Event::Drop`] events.
+    ///
+    /// When the drag ends this widget receives a [`DRAG_ENDED`] command,
+    /// which says whether the contents were dropped on a widget that took
+    /// them. See [`Draggable`] for a convenient way to start drags.
+    ///
+    /// [`DRAG_ENDED`]: crate::commands::DRAG_ENDED
+    /// [`Draggable`]: crate::widget::Draggable
+    pub fn start_drag(&mut self, contents: DragContents, image: impl Into<Option<DragImage>>) {
+        trace!("start_drag contents={:?}", contents);
+        self.widget_state.start_drag = Some(DragSession {
+            source: self.widget_id(),
+            contents,
+            image: image.into(),
+        });
+    }
+
+    /// Transfer focus to the first widget in the tab order that is the widget
+    /// with the given `WidgetId` or one of its descendants.
+    ///
+    /// This is useful to move the focus into a dialog or a [focus scope]
+    /// when it is shown. Nothing happens if there is no such widget.
+    ///
+    /// See [`is_focused`] for more information about focus.
+    ///
+    /// [focus scope]: crate::WidgetExt::focus_scope
+    /// [`is_focused`]: struct.EventCtx.html#method.is_focused
+    pub fn focus_first_in(&mut self, widget: WidgetId) {
+        trace!("focus_first_in widget={:?}", widget);
+        self.widget_state.request_focus = Some(FocusChange::FirstIn(widget));
+    }
+
     /// Transfer focus to the next focusable widget.
     ///
     /// This should only be called by a widget that currently has focus.
@@ -749,7 +1016,8 @@ impl LifeCycleCtx<'_, '_> {
     /// [`EventCtx::is_focused`]: struct.EventCtx.html#method.is_focused
     pub fn register_for_focus(&mut self) {
         trace!("register_for_focus");
-        self.widget_state.focus_chain.push(self.widget_id());
+        let id = self.widget_id();
+        self.widget_state.focus_chain.push(FocusEntry::new(id));
     }
 
     /// Register this widget as accepting text input.
@@ -795,7 +1063,58 @@ impl LayoutCtx<'_, '_> {
     }
 }
 
-impl PaintCtx<'_, '_, '_> {
+impl<'c> PaintCtx<'_, '_, 'c> {
+    /// Stroke a shape, as [`RenderContext::stroke`] does.
+    ///
+    /// If the [`PixelSnapping`] policy asks for it, rects and rounded rects are
+    /// adjusted so that the stroke is drawn on whole device pixels, as with
+    /// [`snap_stroke`]; other shapes are stroked as they are.
+    ///
+    /// [`PixelSnapping`]: crate::PixelSnapping
+    /// [`snap_stroke`]: PaintCtx::snap_stroke
+    pub fn stroke(&mut self, shape: impl Shape, brush: &impl IntoBrush<Piet<'c>>, width: f64) {
+        if self.state.snap_strokes {
+            let scale = self.scale();
+            if let Some(rect) = shape.as_rect() {
+                let (rect, width) = crate::pixel_snap::snap_stroke(rect, width, scale);
+                return self.render_ctx.stroke(rect, brush, width);
+            }
+            if let Some(rounded) = shape.as_rounded_rect() {
+                let (rect, width) = crate::pixel_snap::snap_stroke(rounded.rect(), width, scale);
+                let rounded = RoundedRect::from_rect(rect, rounded.radii());
+                return self.render_ctx.stroke(rounded, brush, width);
+            }
+        }
+        self.render_ctx.stroke(shape, brush, width)
+    }
+
+    /// Adjust a rect and stroke width so that the stroke is drawn on whole
+    /// device pixels, if the [`PixelSnapping`] policy in `env` asks for it.
+    ///
+    /// [`stroke`] does this for rects and rounded rects on its own; use this
+    /// to fill the same rect that a border is stroked around:
+    ///
+    /// ```no_run
+    /// # use druid::{Env, PaintCtx, RenderContext, theme};
+    /// # fn paint(ctx: &mut PaintCtx, env: &Env) {
+    /// let border_width = 1.0;
+    /// let rect = ctx.size().to_rect().inset(-border_width / 2.0);
+    /// let (rect, border_width) = ctx.snap_stroke(rect, border_width, env);
+    /// ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+    /// ctx.stroke(rect, &env.get(theme::BORDER_DARK), border_width);
+    /// # }
+    /// ```
+    ///
+    /// [`PixelSnapping`]: crate::PixelSnapping
+    /// [`stroke`]: PaintCtx::stroke
+    pub fn snap_stroke(&self, rect: Rect, stroke_width: f64, env: &Env) -> (Rect, f64) {
+        if env.get(crate::theme::PIXEL_SNAPPING).snaps_strokes() {
+            crate::pixel_snap::snap_stroke(rect, stroke_width, self.scale())
+        } else {
+            (rect, stroke_width)
+        }
+    }
+
     /// The depth in the tree of the currently painting widget.
     ///
     /// This may be used in combination with [`paint_with_z_index`] in order
@@ -883,6 +1202,107 @@ impl PaintCtx<'_, '_, '_> {
             transform: current_transform,
         })
     }
+
+    /// The pixels per display point of offscreen layers that are drawn back
+    /// into this context, which accounts for the window's content scale.
+    pub(crate) fn layer_scale(&self) -> f64 {
+        let scale = self.scale();
+        scale.x().max(scale.y()) * self.state.content_scale
+    }
+
+    /// Paint into an offscreen image instead of the window.
+    ///
+    /// The closure is given a context that draws into an image of the given
+    /// `size`, with `scale` pixels per display point, and the image is
+    /// returned. This lets a widget export what it draws, for instance as a
+    /// PNG, with the same code that paints it on screen.
+    ///
+    /// The offscreen images of a window share one device, which is made the
+    /// first time it is needed, but each call still paints and reads back a
+    /// new image; keep the image if what it shows hasn't changed.
+    ///
+    /// To capture another widget, or to capture a widget from outside of
+    /// `paint`, use [`capture_image`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::{Color, Env, PaintCtx, RenderContext};
+    /// # struct Chart { export_requested: bool }
+    /// # impl Chart {
+    /// fn draw(ctx: &mut PaintCtx) {
+    ///     let bounds = ctx.size().to_rect();
+    ///     ctx.fill(bounds, &Color::grey(0.9));
+    /// }
+    ///
+    /// fn paint(&mut self, ctx: &mut PaintCtx, _data: &(), _env: &Env) {
+    ///     draw(ctx);
+    ///     if std::mem::take(&mut self.export_requested) {
+    ///         let size = ctx.size();
+    ///         if let Ok(image) = ctx.paint_to_image(size, 2.0, draw) {
+    ///             // save `image` somewhere
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [`capture_image`]: EventCtx::capture_image
+    pub fn paint_to_image(
+        &mut self,
+        size: Size,
+        scale: f64,
+        f: impl FnOnce(&mut PaintCtx),
+    ) -> Result<ImageBuf, PietError> {
+        // A nested call, from inside `f`, makes its own device.
+        let mut device = match self.state.offscreen_device.take() {
+            Some(device) => device,
+            None => Device::new()?,
+        };
+        self.state.offscreen_depth += 1;
+        let image = self.paint_with_device(&mut device, size, scale, f);
+        self.state.offscreen_depth -= 1;
+        if self.state.offscreen_device.is_none() {
+            self.state.offscreen_device = Some(device);
+        }
+        image
+    }
+
+    fn paint_with_device(
+        &mut self,
+        device: &mut Device,
+        size: Size,
+        scale: f64,
+        f: impl FnOnce(&mut PaintCtx),
+    ) -> Result<ImageBuf, PietError> {
+        let width = (size.width * scale).ceil().max(1.0) as usize;
+        let height = (size.height * scale).ceil().max(1.0) as usize;
+        let mut target = device.bitmap_target(width, height, scale)?;
+        {
+            let mut piet = target.render_context();
+            let mut image_ctx = PaintCtx {
+                render_ctx: &mut piet,
+                state: self.state,
+                widget_state: self.widget_state,
+                z_ops: Vec::new(),
+                region: size.to_rect().into(),
+                depth: self.depth,
+            };
+            f(&mut image_ctx);
+
+            // As in the window, the z-ordered operations come last.
+            let mut z_ops = std::mem::take(&mut image_ctx.z_ops);
+            z_ops.sort_by_key(|k| k.z_index);
+            for z_op in z_ops {
+                image_ctx.with_save(|ctx| {
+                    ctx.render_ctx.transform(z_op.transform);
+                    (z_op.paint_func)(ctx);
+                });
+            }
+            piet.finish()?;
+        }
+        target.to_image_buf(ImageFormat::RgbaPremul)
+    }
 }
 
 impl<'a> ContextState<'a> {
@@ -892,6 +1312,7 @@ impl<'a> ContextState<'a> {
         window: &'a WindowHandle,
         window_id: WindowId,
         focus_widget: Option<WidgetId>,
+        content_scale: f64,
     ) -> Self {
         ContextState {
             command_queue,
@@ -901,10 +1322,19 @@ impl<'a> ContextState<'a> {
             focus_widget,
             text: window.text(),
             root_app_data_type: TypeId::of::<T>(),
+            content_scale,
+            raised_widgets: Vec::new(),
+            raised_under_mouse: None,
+            paint_ancestors: Vec::new(),
+            offscreen_depth: 0,
+            offscreen_device: None,
+            record_interactions: false,
+            snap_strokes: false,
+            paint_snap_offset: Vec2::ZERO,
         }
     }
 
-    fn submit_command(&mut self, command: Command) {
+    pub(crate) fn submit_command(&mut self, command: Command) {
         trace!("submit_command");
         self.command_queue
             .push_back(command.default_to(self.window_id.into()));
@@ -913,7 +1343,7 @@ impl<'a> ContextState<'a> {
     fn request_timer(&self, widget_state: &mut WidgetState, deadline: Duration) -> TimerToken {
         trace!("request_timer deadline={:?}", deadline);
         let timer_token = self.window.request_timer(deadline);
-        widget_state.add_timer(timer_token);
+        widget_state.add_timer(timer_token, deadline);
         timer_token
     }
 }
@@ -15,24 +15,99 @@
 //! The fundamental druid types.
 
 use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::time::Duration;
 use tracing::{info_span, trace, warn};
 
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
 use crate::bloom::Bloom;
-use crate::command::sys::{CLOSE_WINDOW, SUB_WINDOW_HOST_TO_PARENT, SUB_WINDOW_PARENT_TO_HOST};
-use crate::contexts::ContextState;
+use crate::command::sys::{
+    CAPTURE_IMAGE, CLOSE_WINDOW, IMAGE_CAPTURED, REQUEST_WIDGET_RECT, SUB_WINDOW_HOST_TO_PARENT,
+    SUB_WINDOW_PARENT_TO_HOST, WIDGET_RECT,
+};
+use crate::contexts::{ContextState, RaisedWidget};
+use crate::dnd::DragSession;
+use crate::interaction::{Interaction, InteractionKind};
 use crate::kurbo::{Affine, Insets, Point, Rect, Shape, Size, Vec2};
+use crate::piet::{Error as PietError, InterpolationMode, PietImage};
+use crate::pixel_snap;
 use crate::sub_window::SubWindowUpdate;
 use crate::text::TextFieldRegistration;
 use crate::util::ExtendDrain;
+use crate::widget::Axis;
 use crate::{
-    ArcStr, BoxConstraints, Color, Command, Cursor, Data, Env, Event, EventCtx, InternalEvent,
-    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Notification, PaintCtx, Region,
-    RenderContext, Target, TextLayout, TimerToken, UpdateCtx, Widget, WidgetId, WindowId,
+    theme, ArcStr, BoxConstraints, Color, Command, Cursor, Data, Env, Event, EventCtx, ImageBuf,
+    InternalEvent, InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Notification, PaintCtx,
+    Region, RenderContext, Scale, Target, TextLayout, TimerToken, UpdateCtx, Widget, WidgetId,
+    WindowId,
 };
 
 /// Our queue type
 pub(crate) type CommandQueue = VecDeque<Command>;
 
+/// The inputs of a widget's last layout, besides the data and env.
+///
+/// These are forgotten when anything the layout may depend on changes, so
+/// they don't hold the data and env themselves.
+struct LayoutInputs {
+    bc: BoxConstraints,
+    scale: Scale,
+    /// The layout rects of the children, which a reused layout keeps.
+    child_rects: Vec<Rect>,
+}
+
+/// An offscreen image of a widget and everything it paints, including its
+/// paint insets.
+pub(crate) struct Layer {
+    /// The pixels, in premultiplied RGBA.
+    pub(crate) buf: ImageBuf,
+    /// The pixels per display point.
+    pub(crate) scale: f64,
+    /// The image uploaded for the window's render context.
+    ///
+    /// Backend images can't always be cloned, so it is shared instead.
+    image: Option<Rc<PietImage>>,
+}
+
+impl Layer {
+    pub(crate) fn new(buf: ImageBuf, scale: f64) -> Layer {
+        Layer {
+            buf,
+            scale,
+            image: None,
+        }
+    }
+
+    /// A copy of the layer with every pixel scaled by `opacity`.
+    pub(crate) fn with_opacity(&self, opacity: f64) -> Layer {
+        // The pixels are premultiplied, so every channel is scaled.
+        let buf = &self.buf;
+        let pixels: Vec<u8> = buf
+            .raw_pixels()
+            .iter()
+            .map(|&c| (c as f64 * opacity).round() as u8)
+            .collect();
+        let buf = ImageBuf::from_raw(pixels, buf.format(), buf.width(), buf.height());
+        Layer::new(buf, self.scale)
+    }
+
+    /// The image to draw with `ctx`.
+    ///
+    /// The image is uploaded once for the window; inside an offscreen image,
+    /// which is painted by another device, it is uploaded every time.
+    pub(crate) fn image(&mut self, ctx: &mut PaintCtx) -> Rc<PietImage> {
+        if ctx.state.offscreen_depth > 0 {
+            return Rc::new(self.buf.to_image(ctx.render_ctx));
+        }
+        let buf = &self.buf;
+        self.image
+            .get_or_insert_with(|| Rc::new(buf.to_image(ctx.render_ctx)))
+            .clone()
+    }
+}
+
 /// A container for one widget in the hierarchy.
 ///
 /// Generally, container widgets don't contain other widgets directly,
@@ -47,14 +122,30 @@ pub(crate) type CommandQueue = VecDeque<Command>;
 /// needs to propagate, and to provide the previous data so that a
 /// widget can process a diff between the old value and the new.
 ///
+/// The `WidgetPod` also remembers the constraints of the widget's last
+/// [`layout`], and skips calling it when they are the same and nothing else
+/// the layout could depend on has changed, returning the previous size
+/// instead. The layout is done again after the data or env change, when the
+/// widget calls [`request_layout`] or [`children_changed`], and when its
+/// parent calls them. A widget whose layout depends on other state, such as
+/// state shared with other widgets, has to request layout when that changes.
+///
 /// [`update`]: trait.Widget.html#tymethod.update
+/// [`layout`]: trait.Widget.html#tymethod.layout
+/// [`request_layout`]: crate::EventCtx::request_layout
+/// [`children_changed`]: crate::EventCtx::children_changed
 pub struct WidgetPod<T, W> {
     state: WidgetState,
     old_data: Option<T>,
     env: Option<Env>,
+    layout_inputs: Option<LayoutInputs>,
     inner: W,
     // stashed layout so we don't recompute this when debugging
     debug_widget_text: TextLayout<ArcStr>,
+    // The widgets that asked for an image of this one, and the scales they asked for.
+    image_captures: Vec<(WidgetId, f64)>,
+    // The offscreen image of the widget, kept while it doesn't change.
+    layer: Option<Layer>,
 }
 
 /// Generic state for all widgets in the hierarchy.
@@ -83,6 +174,12 @@ pub struct WidgetState {
     origin: Point,
     /// The origin of the parent in the window coordinate space;
     pub(crate) parent_window_origin: Point,
+    /// The part of the window, in window coordinates, that the widget can be
+    /// seen through, if an ancestor clips it.
+    pub(crate) clip: Option<Rect>,
+    /// The parent clips this widget to its own bounds, as set with
+    /// [`WidgetPod::set_clip_to_parent`].
+    clip_to_parent: bool,
     /// A flag used to track and debug missing calls to set_origin.
     is_expecting_set_origin_call: bool,
     /// The insets applied to the layout rect to generate the paint rect.
@@ -100,11 +197,36 @@ pub struct WidgetState {
     // The region that needs to be repainted, relative to the widget's bounds.
     pub(crate) invalid: Region,
 
+    /// The region that needs to be repainted, in window coordinates.
+    ///
+    /// This is for raised widgets, which may paint outside of their parent;
+    /// unlike `invalid`, it isn't clipped by the ancestors.
+    pub(crate) window_invalid: Region,
+
+    /// How far this widget is raised above its siblings, as set with
+    /// [`EventCtx::set_z_index`].
+    pub(crate) z_index: u32,
+
+    /// A descendant has been raised above its siblings.
+    pub(crate) has_raised: bool,
+
+    /// The widget's offscreen layer is out of date, because the widget or one
+    /// of its descendants was invalidated or laid out since it was painted.
+    pub(crate) layer_invalid: bool,
+
+    /// The widget or one of its descendants was invalidated, and the parent
+    /// hasn't been told yet.
+    layer_changed: bool,
+
     // The part of this widget that is visible on the screen is offset by this
     // much. This will be non-zero for widgets that are children of `Scroll`, or
     // similar, and it is used for propagating invalid regions.
     pub(crate) viewport_offset: Vec2,
 
+    /// The layout rects of our children, as they were placed by `set_origin`
+    /// during our last layout.
+    pub(crate) child_rects: Vec<Rect>,
+
     // TODO: consider using bitflags for the booleans.
     // `true` if a descendent of this widget changed its disabled state and should receive
     // LifeCycle::DisabledChanged or InternalLifeCycle::RouteDisabledChanged
@@ -123,15 +245,22 @@ pub struct WidgetState {
 
     pub(crate) is_hot: bool,
 
+    /// `true` if a drag is over this widget.
+    pub(crate) is_drag_over: bool,
+
     pub(crate) is_active: bool,
 
     pub(crate) needs_layout: bool,
 
+    /// This widget itself asked for layout, so its children are laid out
+    /// again even if their constraints didn't change.
+    pub(crate) layout_requested: bool,
+
     /// Because of some scrolling or something, `parent_window_origin` needs to be updated.
     pub(crate) needs_window_origin: bool,
 
     /// Any descendant is active.
-    has_active: bool,
+    pub(crate) has_active: bool,
 
     /// In the focused path, starting from window and ending at the focused widget.
     /// Descendants of the focused widget are not in the focused path.
@@ -145,12 +274,21 @@ pub struct WidgetState {
 
     pub(crate) update_focus_chain: bool,
 
-    pub(crate) focus_chain: Vec<WidgetId>,
+    /// A drag started in this subtree, waiting to be picked up by the window.
+    pub(crate) start_drag: Option<DragSession>,
+
+    pub(crate) focus_chain: Vec<FocusEntry>,
     pub(crate) request_focus: Option<FocusChange>,
     pub(crate) children: Bloom<WidgetId>,
     pub(crate) children_changed: bool,
     /// Associate timers with widgets that requested them.
     pub(crate) timers: HashMap<TimerToken, WidgetId>,
+    /// The timers this widget requested that haven't fired or been cancelled,
+    /// with their deadlines.
+    ///
+    /// Unlike `timers`, this isn't merged up: it belongs to this instance of
+    /// the widget, so a timer can't fire into a new widget that reuses the id.
+    pub(crate) owned_timers: HashMap<TimerToken, Instant>,
     /// The cursor that was set using one of the context methods.
     pub(crate) cursor_change: CursorChange,
     /// The result of merging up children cursors. This gets cleared when merging state up (unlike
@@ -161,6 +299,9 @@ pub struct WidgetState {
     pub(crate) sub_window_hosts: Vec<(WindowId, WidgetId)>,
 
     pub(crate) text_registrations: Vec<TextFieldRegistration>,
+
+    /// User interactions recorded in this subtree, waiting to be reported.
+    pub(crate) interactions: Vec<Interaction>,
 }
 
 /// Methods by which a widget can attempt to change focus state.
@@ -174,6 +315,48 @@ pub(crate) enum FocusChange {
     Next,
     /// Focus should pass to the previous focusable widget
     Previous,
+    /// Focus should pass to the first focusable widget in the given widget
+    FirstIn(WidgetId),
+}
+
+/// A focusable widget in a focus chain.
+#[derive(Debug, Clone)]
+pub(crate) struct FocusEntry {
+    pub(crate) id: WidgetId,
+    /// The tab index set with [`WidgetExt::with_tab_index`], if any.
+    ///
+    /// [`WidgetExt::with_tab_index`]: crate::WidgetExt::with_tab_index
+    pub(crate) tab_index: Option<i32>,
+    /// The focus scopes the widget is in, innermost first.
+    pub(crate) scopes: Vec<WidgetId>,
+}
+
+impl FocusEntry {
+    pub(crate) fn new(id: WidgetId) -> Self {
+        FocusEntry {
+            id,
+            tab_index: None,
+            scopes: Vec::new(),
+        }
+    }
+}
+
+/// The entries of a focus chain in the order of keyboard traversal.
+///
+/// Entries with a positive tab index come first, in increasing order of
+/// their index, followed by the others in the order they were registered.
+/// Entries with a negative tab index are left out.
+pub(crate) fn tab_order(chain: &[FocusEntry]) -> Vec<&FocusEntry> {
+    let mut order: Vec<_> = chain
+        .iter()
+        .filter(|entry| !matches!(entry.tab_index, Some(index) if index < 0))
+        .collect();
+    // the sort is stable, so the registration order is kept for equal keys.
+    order.sort_by_key(|entry| match entry.tab_index {
+        Some(index) if index > 0 => index,
+        _ => i32::MAX,
+    });
+    order
 }
 
 /// The possible cursor states for a widget.
@@ -202,8 +385,11 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
             state,
             old_data: None,
             env: None,
+            layout_inputs: None,
             inner,
             debug_widget_text: TextLayout::new(),
+            image_captures: Vec::new(),
+            layer: None,
         }
     }
 
@@ -225,6 +411,15 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         self.state.has_focus
     }
 
+    /// The focusable descendants of this widget, in tab order, as of the
+    /// last time the focus chain was built.
+    pub(crate) fn focus_chain(&self) -> Vec<WidgetId> {
+        tab_order(&self.state.focus_chain)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect()
+    }
+
     /// Query the "active" state of the widget.
     pub fn is_active(&self) -> bool {
         self.state.is_active
@@ -273,18 +468,25 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
     /// [`Size`]: struct.Size.html
     /// [`LifeCycle::Size`]: enum.LifeCycle.html#variant.Size
     pub fn set_origin(&mut self, ctx: &mut LayoutCtx, data: &T, env: &Env, origin: Point) {
-        self.state.origin = origin;
+        self.state.origin = if env.get(theme::PIXEL_SNAPPING).snaps_layout() {
+            pixel_snap::snap_point(origin, ctx.scale())
+        } else {
+            origin
+        };
         self.state.is_expecting_set_origin_call = false;
         let layout_rect = self.layout_rect();
+        ctx.widget_state.child_rects.push(layout_rect);
 
         // if the widget has moved, it may have moved under the mouse, in which
         // case we need to handle that.
+        let is_hot = ctx
+            .mouse_pos
+            .map_or(false, |pos| layout_rect.winding(pos) != 0);
         if WidgetPod::set_hot_state(
             &mut self.inner,
             &mut self.state,
             ctx.state,
-            layout_rect,
-            ctx.mouse_pos,
+            is_hot,
             data,
             env,
         ) {
@@ -321,6 +523,22 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         self.state.viewport_offset = offset;
     }
 
+    /// Set whether the parent clips this widget to the parent's bounds when
+    /// painting it.
+    ///
+    /// Like [`set_viewport_offset`], this is for scroll views and similar
+    /// containers. It limits the [`visible_rect`] of this widget and of its
+    /// descendants.
+    ///
+    /// [`set_viewport_offset`]: WidgetPod::set_viewport_offset
+    /// [`visible_rect`]: crate::EventCtx::visible_rect
+    pub fn set_clip_to_parent(&mut self, clip: bool) {
+        if clip != self.state.clip_to_parent {
+            self.state.needs_window_origin = true;
+        }
+        self.state.clip_to_parent = clip;
+    }
+
     /// The viewport offset.
     ///
     /// This will be the same value as set by [`set_viewport_offset`].
@@ -382,8 +600,8 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         self.state.baseline_offset
     }
 
-    /// Determines if the provided `mouse_pos` is inside `rect`
-    /// and if so updates the hot state and sends `LifeCycle::HotChanged`.
+    /// Updates the hot state to `is_hot`, and sends `LifeCycle::HotChanged`
+    /// if it changed.
     ///
     /// Returns `true` if the hot state changed.
     ///
@@ -392,16 +610,12 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         child: &mut W,
         child_state: &mut WidgetState,
         state: &mut ContextState,
-        rect: Rect,
-        mouse_pos: Option<Point>,
+        is_hot: bool,
         data: &T,
         env: &Env,
     ) -> bool {
         let had_hot = child_state.is_hot;
-        child_state.is_hot = match mouse_pos {
-            Some(pos) => rect.winding(pos) != 0,
-            None => false,
-        };
+        child_state.is_hot = is_hot;
         if had_hot != child_state.is_hot {
             let hot_changed_event = LifeCycle::HotChanged(child_state.is_hot);
             let mut child_ctx = LifeCycleCtx {
@@ -434,6 +648,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
     /// [`Widget::paint`]: trait.Widget.html#tymethod.paint
     /// [`paint`]: #method.paint
     pub fn paint_raw(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if !self.image_captures.is_empty() {
+            self.capture_images(ctx, data, env);
+        }
+
         // we need to do this before we borrow from self
         if env.get(Env::DEBUG_WIDGET_ID) {
             self.make_widget_id_layout_if_needed(self.state.id, ctx, env);
@@ -447,7 +665,13 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             widget_state: &self.state,
             depth: ctx.depth,
         };
+        inner_ctx.state.paint_ancestors.push(self.state.id);
+        let snap_strokes = env.get(theme::PIXEL_SNAPPING).snaps_strokes();
+        let parent_snap_strokes =
+            std::mem::replace(&mut inner_ctx.state.snap_strokes, snap_strokes);
         self.inner.paint(&mut inner_ctx, data, env);
+        inner_ctx.state.snap_strokes = parent_snap_strokes;
+        inner_ctx.state.paint_ancestors.pop();
 
         let debug_ids = inner_ctx.is_hot() && env.get(Env::DEBUG_WIDGET_ID);
         if debug_ids {
@@ -478,7 +702,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
 
     /// Shared implementation that can skip drawing non-visible content.
     fn paint_impl(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env, paint_if_not_visible: bool) {
-        if !paint_if_not_visible && !ctx.region().intersects(self.state.paint_rect()) {
+        // Raised widgets are painted outside of their ancestors, so they and
+        // their ancestors are painted even if they are outside of the region.
+        let may_skip = self.state.z_index == 0 && !self.state.has_raised;
+        if !paint_if_not_visible && may_skip && !ctx.region().intersects(self.state.paint_rect()) {
             return;
         }
 
@@ -490,16 +717,129 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             return;
         }
 
+        if self.state.z_index > 0 {
+            self.paint_raised(ctx, data, env);
+            return;
+        }
+
         ctx.with_save(|ctx| {
-            let layout_origin = self.layout_rect().origin().to_vec2();
+            let mut layout_origin = self.layout_rect().origin().to_vec2();
+            let parent_snap_offset = ctx.state.paint_snap_offset;
+            if env.get(theme::PIXEL_SNAPPING).snaps_layout() {
+                // The origin was snapped relative to the parent, but scroll offsets
+                // and the content scale can still put it between device pixels, so
+                // move it to where it lands in the window.
+                let origin = self.state.window_origin() + parent_snap_offset;
+                let content_scale = ctx.state.content_scale;
+                let scale = ctx.scale();
+                let device_scale = Scale::new(scale.x() * content_scale, scale.y() * content_scale);
+                let nudge = pixel_snap::snap_point(origin, device_scale) - origin;
+                layout_origin += nudge;
+                ctx.state.paint_snap_offset = parent_snap_offset + nudge;
+            }
             ctx.transform(Affine::translate(layout_origin));
             let mut visible = ctx.region().clone();
             visible.intersect_with(self.state.paint_rect());
             visible -= layout_origin;
             ctx.with_child_ctx(visible, |ctx| self.paint_raw(ctx, data, env));
+            ctx.state.paint_snap_offset = parent_snap_offset;
         });
     }
 
+    /// Paint a widget raised above its siblings.
+    ///
+    /// The widget is painted into an image, which is drawn with the z-ordered
+    /// operations after the rest of the window, outside of the ancestors' clips.
+    fn paint_raised(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let paint_rect = self.paint_rect();
+        let image = match self.paint_layer(ctx, data, env) {
+            Ok((layer, _)) => layer.image(ctx),
+            Err(e) => {
+                warn!("failed to paint raised widget {:?}: {}", self.id(), e);
+                return;
+            }
+        };
+        ctx.state.raised_widgets.push(RaisedWidget {
+            id: self.id(),
+            z_index: self.state.z_index,
+            rect: Rect::from_origin_size(self.state.window_origin(), self.state.size),
+            ancestors: ctx.state.paint_ancestors.clone(),
+        });
+        ctx.paint_with_z_index(self.state.z_index, move |ctx| {
+            ctx.draw_image(&image, paint_rect, InterpolationMode::Bilinear);
+        });
+    }
+
+    /// Paint the widget into an offscreen layer covering its paint rect, and
+    /// return the layer.
+    ///
+    /// The layer is kept and returned again until the widget or one of its
+    /// descendants is invalidated or laid out, or the scale changes. The
+    /// `bool` is `true` if the layer was painted by this call.
+    pub(crate) fn paint_layer(
+        &mut self,
+        ctx: &mut PaintCtx,
+        data: &T,
+        env: &Env,
+    ) -> Result<(&mut Layer, bool), PietError> {
+        let scale = ctx.layer_scale();
+        // Raised descendants are registered for hit-testing as they are painted.
+        let is_clean = !self.state.layer_invalid && !self.state.has_raised;
+        let repaint = match &self.layer {
+            Some(layer) => !is_clean || layer.scale != scale,
+            None => true,
+        };
+        if repaint {
+            let paint_rect = self.paint_rect();
+            let insets = self.paint_insets();
+            let buf = ctx.paint_to_image(paint_rect.size(), scale, |ctx| {
+                ctx.transform(Affine::translate((insets.x0, insets.y0)));
+                let visible = self.state.paint_rect() - self.layout_rect().origin().to_vec2();
+                ctx.with_child_ctx(visible, |ctx| self.paint_raw(ctx, data, env));
+            })?;
+            self.state.layer_invalid = false;
+            self.layer = Some(Layer::new(buf, scale));
+        }
+        Ok((self.layer.as_mut().unwrap(), repaint))
+    }
+
+    /// Whether the mouse at `pos` is over this widget, given its layout `rect`.
+    ///
+    /// While the mouse is over a raised widget, only that widget and its
+    /// ancestors are hit, even if the raised widget is outside of them.
+    fn is_hit(&self, state: &ContextState, rect: Rect, pos: Point) -> bool {
+        match &state.raised_under_mouse {
+            Some(raised) if raised.id != self.id() => raised.ancestors.contains(&self.id()),
+            _ => rect.winding(pos) != 0,
+        }
+    }
+
+    /// Paint an image of the widget for each widget that asked for one, and
+    /// send it to them.
+    fn capture_images(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for (requester, scale) in std::mem::take(&mut self.image_captures) {
+            match self.paint_to_image(ctx, data, env, scale) {
+                Ok(image) => {
+                    let cmd = IMAGE_CAPTURED.with((self.id(), image)).to(requester);
+                    ctx.state.submit_command(cmd);
+                }
+                Err(e) => warn!("failed to capture an image of {:?}: {}", self.id(), e),
+            }
+        }
+    }
+
+    /// Paint the widget into an offscreen image, with `scale` pixels per display point.
+    pub(crate) fn paint_to_image(
+        &mut self,
+        ctx: &mut PaintCtx,
+        data: &T,
+        env: &Env,
+        scale: f64,
+    ) -> Result<ImageBuf, PietError> {
+        let size = self.layout_rect().size();
+        ctx.paint_to_image(size, scale, |ctx| self.paint_raw(ctx, data, env))
+    }
+
     fn make_widget_id_layout_if_needed(&mut self, id: WidgetId, ctx: &mut PaintCtx, env: &Env) {
         if self.debug_widget_text.needs_rebuild() {
             // switch text color based on background, this is meh and that's okay
@@ -564,9 +904,21 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             return Size::ZERO;
         }
 
+        let reusable = !self.state.needs_layout && !ctx.widget_state.layout_requested;
+        if let Some(inputs) = self.layout_inputs.as_ref().filter(|_| reusable) {
+            if inputs.bc == *bc && inputs.scale == ctx.scale() {
+                trace!("Reusing the previous layout of {:?}", self.id());
+                self.state.child_rects.clone_from(&inputs.child_rects);
+                self.state.is_expecting_set_origin_call = true;
+                return self.state.size;
+            }
+        }
+
         self.state.needs_layout = false;
         self.state.needs_window_origin = false;
         self.state.is_expecting_set_origin_call = true;
+        self.state.layer_invalid = true;
+        self.state.child_rects.clear();
 
         let child_mouse_pos = ctx
             .mouse_pos
@@ -579,7 +931,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             mouse_pos: child_mouse_pos,
         };
 
-        let new_size = self.inner.layout(&mut child_ctx, bc, data, env);
+        let mut new_size = self.inner.layout(&mut child_ctx, bc, data, env);
+        if env.get(theme::PIXEL_SNAPPING).snaps_layout() {
+            new_size = bc.constrain(pixel_snap::snap_size(new_size, child_ctx.scale()));
+        }
         if new_size != prev_size {
             let mut child_ctx = LifeCycleCtx {
                 widget_state: child_ctx.widget_state,
@@ -593,13 +948,87 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             self.inner.lifecycle(&mut child_ctx, &size_event, data, env);
         }
 
+        let scale = child_ctx.scale();
         ctx.widget_state.merge_up(&mut child_ctx.widget_state);
         self.state.size = new_size;
         self.log_layout_issues(new_size);
+        self.state.layout_requested = false;
+        self.layout_inputs = Some(LayoutInputs {
+            bc: *bc,
+            scale,
+            child_rects: self.state.child_rects.clone(),
+        });
 
         new_size
     }
 
+    /// Compute the child's maximum intrinsic size along `axis`.
+    ///
+    /// Generally called by container widgets as part of their [`layout`]
+    /// method, to measure a child before laying it out. See
+    /// [`Widget::compute_max_intrinsic`] for more information.
+    ///
+    /// [`layout`]: trait.Widget.html#tymethod.layout
+    /// [`Widget::compute_max_intrinsic`]: trait.Widget.html#method.compute_max_intrinsic
+    pub fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.with_intrinsic_ctx(ctx, |inner, ctx| {
+            inner.compute_max_intrinsic(axis, ctx, bc, data, env)
+        })
+    }
+
+    /// Compute the child's minimum intrinsic size along `axis`.
+    ///
+    /// See [`Widget::compute_min_intrinsic`] for more information.
+    ///
+    /// [`Widget::compute_min_intrinsic`]: trait.Widget.html#method.compute_min_intrinsic
+    pub fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.with_intrinsic_ctx(ctx, |inner, ctx| {
+            inner.compute_min_intrinsic(axis, ctx, bc, data, env)
+        })
+    }
+
+    fn with_intrinsic_ctx(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        f: impl FnOnce(&mut W, &mut LayoutCtx) -> f64,
+    ) -> f64 {
+        if !self.is_initialized() {
+            debug_panic!(
+                "{:?}: intrinsic size requested before receiving WidgetAdded.",
+                ctx.widget_id()
+            );
+            return 0.0;
+        }
+        let child_mouse_pos = ctx
+            .mouse_pos
+            .map(|pos| pos - self.layout_rect().origin().to_vec2() + self.viewport_offset());
+        let mut child_ctx = LayoutCtx {
+            widget_state: &mut self.state,
+            state: ctx.state,
+            mouse_pos: child_mouse_pos,
+        };
+        let size = f(&mut self.inner, &mut child_ctx);
+        ctx.widget_state.merge_up(&mut self.state);
+        // measuring may have laid out the descendants with other constraints,
+        // so the previous layout can't be reused.
+        self.layout_inputs = None;
+        size
+    }
+
     fn log_layout_issues(&self, size: Size) {
         if size.width.is_infinite() {
             let name = self.widget().type_name();
@@ -663,6 +1092,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             return;
         }
         let had_active = self.state.has_active;
+        let was_active = self.state.is_active;
         let rect = self.layout_rect();
 
         // If we need to replace either the event or its data.
@@ -675,8 +1105,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                         &mut self.inner,
                         &mut self.state,
                         ctx.state,
-                        rect,
-                        None,
+                        false,
                         data,
                         env,
                     );
@@ -702,8 +1131,15 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 }
                 InternalEvent::RouteTimer(token, widget_id) => {
                     if *widget_id == self.id() {
-                        modified_event = Some(Event::Timer(*token));
-                        true
+                        // the timer may have been cancelled, or requested by a
+                        // removed widget that had the same id.
+                        if self.state.owned_timers.remove(token).is_some() {
+                            modified_event = Some(Event::Timer(*token));
+                            true
+                        } else {
+                            trace!("Dropping timer {:?} not owned by this widget", token);
+                            false
+                        }
                     } else {
                         self.state.children.may_contain(widget_id)
                     }
@@ -717,7 +1153,11 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     }
                 }
             },
-            Event::WindowConnected | Event::WindowCloseRequested => true,
+            Event::WindowConnected
+            | Event::WindowCloseRequested
+            | Event::SystemThemeChanged(_)
+            | Event::MonitorsChanged
+            | Event::WindowMonitorChanged(_) => true,
             Event::WindowDisconnected => {
                 for (window_id, _) in &self.state.sub_window_hosts {
                     ctx.submit_command(CLOSE_WINDOW.to(*window_id))
@@ -729,12 +1169,12 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 ctx.is_root
             }
             Event::MouseDown(mouse_event) => {
+                let is_hit = self.is_hit(ctx.state, rect, mouse_event.pos);
                 WidgetPod::set_hot_state(
                     &mut self.inner,
                     &mut self.state,
                     ctx.state,
-                    rect,
-                    Some(mouse_event.pos),
+                    is_hit,
                     data,
                     env,
                 );
@@ -748,12 +1188,12 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 }
             }
             Event::MouseUp(mouse_event) => {
+                let is_hit = self.is_hit(ctx.state, rect, mouse_event.pos);
                 WidgetPod::set_hot_state(
                     &mut self.inner,
                     &mut self.state,
                     ctx.state,
-                    rect,
-                    Some(mouse_event.pos),
+                    is_hit,
                     data,
                     env,
                 );
@@ -767,12 +1207,12 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 }
             }
             Event::MouseMove(mouse_event) => {
+                let is_hit = self.is_hit(ctx.state, rect, mouse_event.pos);
                 let hot_changed = WidgetPod::set_hot_state(
                     &mut self.inner,
                     &mut self.state,
                     ctx.state,
-                    rect,
-                    Some(mouse_event.pos),
+                    is_hit,
                     data,
                     env,
                 );
@@ -789,12 +1229,12 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 }
             }
             Event::Wheel(mouse_event) => {
+                let is_hit = self.is_hit(ctx.state, rect, mouse_event.pos);
                 WidgetPod::set_hot_state(
                     &mut self.inner,
                     &mut self.state,
                     ctx.state,
-                    rect,
-                    Some(mouse_event.pos),
+                    is_hit,
                     data,
                     env,
                 );
@@ -807,6 +1247,46 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     false
                 }
             }
+            Event::TouchDown(touch) | Event::TouchMove(touch) | Event::TouchUp(touch) => {
+                // touches don't make widgets hot, they go to whatever is under them
+                if had_active || rect.winding(touch.pos) != 0 {
+                    let mut touch = touch.clone();
+                    touch.pos -= rect.origin().to_vec2();
+                    modified_event = Some(event.with_touch(touch));
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::Pen(pen) => {
+                if had_active || rect.winding(pen.pos) != 0 {
+                    let mut pen = pen.clone();
+                    pen.pos -= rect.origin().to_vec2();
+                    modified_event = Some(Event::Pen(pen));
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::DragEnter(drag) | Event::DragOver(drag) | Event::Drop(drag) => {
+                // like hot state, but for the drag: each widget tells whether
+                // the drag entered it, moves within it, or left it.
+                let was_over = self.state.is_drag_over;
+                let is_over = rect.winding(drag.pos) != 0;
+                let is_drop = matches!(event, Event::Drop(_));
+                self.state.is_drag_over = is_over && !is_drop;
+                let mut drag = drag.clone();
+                drag.pos -= rect.origin().to_vec2();
+                modified_event = match (was_over, is_over) {
+                    (_, true) if is_drop => Some(Event::Drop(drag)),
+                    (false, true) => Some(Event::DragEnter(drag)),
+                    (true, true) => Some(Event::DragOver(drag)),
+                    (true, false) => Some(Event::DragLeave),
+                    (false, false) => None,
+                };
+                modified_event.is_some()
+            }
+            Event::DragLeave => std::mem::replace(&mut self.state.is_drag_over, false),
             Event::AnimFrame(_) => {
                 let r = self.state.request_anim;
                 self.state.request_anim = false;
@@ -833,6 +1313,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             };
             let inner_event = modified_event.as_ref().unwrap_or(event);
             inner_ctx.widget_state.has_active = false;
+            inner_ctx.widget_state.has_raised = false;
 
             match inner_event {
                 Event::Command(cmd) if cmd.is(SUB_WINDOW_HOST_TO_PARENT) => {
@@ -844,8 +1325,35 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     }
                     ctx.is_handled = true
                 }
+                Event::Command(cmd) if cmd.is(CAPTURE_IMAGE) => {
+                    self.image_captures.push(*cmd.get_unchecked(CAPTURE_IMAGE));
+                    inner_ctx.request_paint();
+                    ctx.is_handled = true
+                }
+                Event::Command(cmd) if cmd.is(REQUEST_WIDGET_RECT) => {
+                    let requester = *cmd.get_unchecked(REQUEST_WIDGET_RECT);
+                    let rect = Rect::from_origin_size(
+                        inner_ctx.widget_state.window_origin(),
+                        inner_ctx.widget_state.size(),
+                    );
+                    let id = inner_ctx.widget_id();
+                    inner_ctx.submit_command(WIDGET_RECT.with((id, rect)).to(requester));
+                    ctx.is_handled = true
+                }
                 _ => {
+                    // Below a raised widget, its children are hit as usual.
+                    let id = inner_ctx.widget_state.id;
+                    let raised = &inner_ctx.state.raised_under_mouse;
+                    let is_raised = raised.as_ref().map(|raised| raised.id) == Some(id);
+                    let raised_under_mouse = if is_raised {
+                        inner_ctx.state.raised_under_mouse.take()
+                    } else {
+                        None
+                    };
                     self.inner.event(&mut inner_ctx, inner_event, data, env);
+                    if is_raised {
+                        inner_ctx.state.raised_under_mouse = raised_under_mouse;
+                    }
 
                     inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                     ctx.is_handled |= inner_ctx.is_handled;
@@ -854,6 +1362,18 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
 
             // we try to handle the notifications that occured below us in the tree
             self.send_notifications(ctx, &mut notifications, data, env);
+
+            if let Event::MouseDown(mouse) = event {
+                if self.state.is_active && !was_active && ctx.state.record_interactions {
+                    let kind = InteractionKind::Click {
+                        button: mouse.button,
+                        count: mouse.count,
+                    };
+                    let interaction = Interaction::new(Some(ctx.state.window_id), kind)
+                        .with_widget(self.id(), self.inner.type_name());
+                    self.state.interactions.push(interaction);
+                }
+            }
         } else {
             trace!("event wasn't propagated to {:?}", self.state.id);
         }
@@ -963,6 +1483,14 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     if let Some(change) = this_changed {
                         self.state.has_focus = change;
                         extra_event = Some(LifeCycle::FocusChanged(change));
+                        if ctx.state.record_interactions {
+                            let interaction = Interaction::new(
+                                Some(ctx.state.window_id),
+                                InteractionKind::FocusChanged(change),
+                            )
+                            .with_widget(self.id(), self.inner.type_name());
+                            self.state.interactions.push(interaction);
+                        }
                     } else {
                         self.state.has_focus = false;
                     }
@@ -975,8 +1503,30 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                         _ => false,
                     }
                 }
+                InternalLifeCycle::RouteFocusFirstIn(widget) => {
+                    if *widget == self.id() {
+                        if let Some(first) = tab_order(&self.state.focus_chain).first() {
+                            self.state.request_focus = Some(FocusChange::Focus(first.id));
+                        }
+                        false
+                    } else {
+                        // Recurse when the target widget could be our descendant.
+                        // The bloom filter we're checking can return false positives.
+                        self.state.children.may_contain(widget)
+                    }
+                }
                 InternalLifeCycle::ParentWindowOrigin => {
-                    self.state.parent_window_origin = ctx.widget_state.window_origin();
+                    let parent = &ctx.widget_state;
+                    self.state.parent_window_origin = parent.window_origin();
+                    self.state.clip = parent.clip;
+                    if self.state.clip_to_parent {
+                        let bounds = parent.window_layout_rect();
+                        let clip = self
+                            .state
+                            .clip
+                            .map_or(bounds, |clip| clip.intersect(bounds));
+                        self.state.clip = Some(clip);
+                    }
                     self.state.needs_window_origin = false;
                     true
                 }
@@ -1104,7 +1654,9 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 self.state.has_focus = had_focus;
 
                 if !self.state.is_disabled() {
-                    ctx.widget_state.focus_chain.extend(&self.state.focus_chain);
+                    ctx.widget_state
+                        .focus_chain
+                        .extend(self.state.focus_chain.iter().cloned());
                 }
             }
             _ => (),
@@ -1122,9 +1674,20 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
     pub fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
         if !self.state.request_update {
             match (self.old_data.as_ref(), self.env.as_ref()) {
-                (Some(d), Some(e)) if d.same(data) && e.same(env) => {
-                    trace!("data and env are unchanged, returning early.");
-                    return;
+                (Some(d), Some(e)) if e.same(env) => {
+                    let changes = d.changes(data);
+                    if changes.is_empty() {
+                        trace!("data and env are unchanged, returning early.");
+                        return;
+                    }
+                    // sub-windows get all of the data, whatever the widget uses
+                    if !changes.intersects(self.inner.depends_on())
+                        && self.state.sub_window_hosts.is_empty()
+                    {
+                        trace!("data the widget depends on is unchanged, returning early.");
+                        self.old_data = Some(data.clone());
+                        return;
+                    }
                 }
                 (Some(_), None) => self.env = Some(env.clone()),
                 (None, _) => {
@@ -1142,6 +1705,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             self.old_data.is_none() || self.old_data.as_ref().filter(|p| !p.same(data)).is_some();
 
         if ctx.env_changed() || data_changed {
+            self.layout_inputs = None;
             for (_, host) in &self.state.sub_window_hosts {
                 let update = SubWindowUpdate {
                     data: if data_changed {
@@ -1195,9 +1759,21 @@ impl<T, W> WidgetPod<T, W> {
     }
 
     /// Return a mutable reference to the inner widget.
+    ///
+    /// If a change made through this reference affects the widget's layout,
+    /// the parent has to call [`request_layout`], which lays out its children
+    /// again.
+    ///
+    /// [`request_layout`]: crate::EventCtx::request_layout
     pub fn widget_mut(&mut self) -> &mut W {
         &mut self.inner
     }
+
+    /// The layout rects of this widget's children, in its coordinate space,
+    /// as of its last layout.
+    pub(crate) fn child_rects(&self) -> &[Rect] {
+        &self.state.child_rects
+    }
 }
 
 impl WidgetState {
@@ -1206,17 +1782,27 @@ impl WidgetState {
             id,
             origin: Point::ORIGIN,
             parent_window_origin: Point::ORIGIN,
+            clip: None,
+            clip_to_parent: false,
             size: size.unwrap_or_default(),
             is_expecting_set_origin_call: true,
             paint_insets: Insets::ZERO,
             invalid: Region::EMPTY,
+            window_invalid: Region::EMPTY,
+            z_index: 0,
+            has_raised: false,
+            layer_invalid: false,
+            layer_changed: false,
             viewport_offset: Vec2::ZERO,
+            child_rects: Vec::new(),
             children_disabled_changed: false,
             ancestor_disabled: false,
             is_explicitly_disabled: false,
             baseline_offset: 0.0,
             is_hot: false,
+            is_drag_over: false,
             needs_layout: false,
+            layout_requested: false,
             needs_window_origin: false,
             is_active: false,
             has_active: false,
@@ -1228,12 +1814,15 @@ impl WidgetState {
             children: Bloom::new(),
             children_changed: false,
             timers: HashMap::new(),
+            owned_timers: HashMap::new(),
             cursor_change: CursorChange::Default,
             cursor: None,
             sub_window_hosts: Vec::new(),
             is_explicitly_disabled_new: false,
             text_registrations: Vec::new(),
+            interactions: Vec::new(),
             update_focus_chain: false,
+            start_drag: None,
         }
     }
 
@@ -1246,8 +1835,24 @@ impl WidgetState {
             || self.is_explicitly_disabled != self.is_explicitly_disabled_new
     }
 
-    pub(crate) fn add_timer(&mut self, timer_token: TimerToken) {
+    pub(crate) fn add_timer(&mut self, timer_token: TimerToken, deadline: Duration) {
         self.timers.insert(timer_token, self.id);
+        self.owned_timers
+            .insert(timer_token, Instant::now() + deadline);
+    }
+
+    /// Forget a timer, so that it isn't delivered when it fires.
+    ///
+    /// Returns `false` if we don't own a pending timer with this token.
+    pub(crate) fn cancel_timer(&mut self, timer_token: TimerToken) -> bool {
+        self.owned_timers.remove(&timer_token).is_some()
+    }
+
+    /// The time until one of our pending timers fires.
+    pub(crate) fn timer_remaining(&self, timer_token: TimerToken) -> Option<Duration> {
+        self.owned_timers
+            .get(&timer_token)
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
     }
 
     /// Update to incorporate state changes from a child.
@@ -1272,6 +1877,21 @@ impl WidgetState {
                 self.invalid.add_rect(r);
             }
         }
+        if child_state.z_index > 0 {
+            let offset = child_state.window_origin().to_vec2();
+            for &r in child_state.invalid.rects() {
+                self.window_invalid.add_rect(r + offset);
+            }
+        }
+        self.has_raised |= child_state.z_index > 0 || child_state.has_raised;
+        // The layers of this widget and the child include what the child paints.
+        if !child_state.invalid.is_empty() || std::mem::take(&mut child_state.layer_changed) {
+            child_state.layer_invalid = true;
+            self.layer_invalid = true;
+            self.layer_changed = true;
+        }
+        self.window_invalid.union_with(&child_state.window_invalid);
+        child_state.window_invalid.clear();
         // Clearing the invalid rects here is less fragile than doing it while painting. The
         // problem is that widgets (for example, Either) might choose not to paint certain
         // invisible children, and we shouldn't allow these invisible children to accumulate
@@ -1289,9 +1909,11 @@ impl WidgetState {
         self.children_changed |= child_state.children_changed;
         self.request_update |= child_state.request_update;
         self.request_focus = child_state.request_focus.take().or(self.request_focus);
+        self.start_drag = child_state.start_drag.take().or(self.start_drag.take());
         self.timers.extend_drain(&mut child_state.timers);
         self.text_registrations
             .extend(child_state.text_registrations.drain(..));
+        self.interactions.append(&mut child_state.interactions);
         self.update_focus_chain |= child_state.update_focus_chain;
 
         // We reset `child_state.cursor` no matter what, so that on the every pass through the tree,
@@ -1342,6 +1964,16 @@ impl WidgetState {
     pub(crate) fn window_origin(&self) -> Point {
         self.parent_window_origin + self.origin.to_vec2() - self.viewport_offset
     }
+
+    /// The layout rect, in window coordinates.
+    pub(crate) fn window_layout_rect(&self) -> Rect {
+        self.layout_rect() + self.parent_window_origin.to_vec2()
+    }
+
+    /// The paint rect, in window coordinates.
+    pub(crate) fn window_paint_rect(&self) -> Rect {
+        self.paint_rect() - self.origin.to_vec2() + self.window_origin().to_vec2()
+    }
 }
 
 impl CursorChange {
@@ -1404,6 +2036,7 @@ mod tests {
             &window,
             WindowId::next(),
             None,
+            1.0,
         );
 
         let mut ctx = LifeCycleCtx {
@@ -0,0 +1,337 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Crash reports for panics on the UI thread.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::shell::Application;
+use crate::{Command, WindowId};
+
+/// The number of commands kept for the crash report.
+const MAX_RECENT_COMMANDS: usize = 16;
+
+/// The environment variable that tells a new instance of the application to
+/// show the crash dialog for the report at this path.
+const REPORT_ENV_VAR: &str = "DRUID_CRASH_REPORT";
+
+thread_local! {
+    /// What we know about the app, for the report.
+    ///
+    /// This is only `Some` on the UI thread once a [`CrashReporter`] is
+    /// installed, which is also how the panic hook tells the UI thread apart.
+    static BREADCRUMBS: RefCell<Option<Breadcrumbs>> = RefCell::new(None);
+}
+
+/// Writes a crash report when the UI thread panics, and offers to restart the
+/// application.
+///
+/// This is set up with [`AppLauncher::crash_reporter`]. Once installed, a
+/// panic on the UI thread writes a report to the reporter's directory with
+/// the panic message and location, the open windows and their root widgets,
+/// and the commands submitted most recently. The panic then continues as it
+/// would have without the reporter. Panics on other threads are left to the
+/// previous panic hook alone.
+///
+/// The crashed process can't safely show a dialog, so it starts a new
+/// instance of the application with the same arguments. When that instance
+/// reaches [`AppLauncher::launch`], it shows a native dialog that tells the
+/// user what happened and offers to restart the application, instead of
+/// opening its windows. Anything your `main` does before `launch` runs in
+/// that instance too.
+///
+/// Crash reports are not supported on the web.
+///
+/// [`AppLauncher::crash_reporter`]: crate::AppLauncher::crash_reporter
+/// [`AppLauncher::launch`]: crate::AppLauncher::launch
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    dir: PathBuf,
+    app_name: String,
+    offer_restart: bool,
+}
+
+#[derive(Default)]
+struct Breadcrumbs {
+    commands: VecDeque<String>,
+    windows: Vec<(WindowId, String)>,
+}
+
+impl CrashReporter {
+    /// Create a reporter that writes its reports to `dir`.
+    ///
+    /// The directory is created when the first report is written.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        CrashReporter {
+            dir: dir.into(),
+            app_name: String::from("The application"),
+            offer_restart: true,
+        }
+    }
+
+    /// Builder-style method to set the name the crash dialog uses for the
+    /// application.
+    pub fn app_name(mut self, name: impl Into<String>) -> Self {
+        self.app_name = name.into();
+        self
+    }
+
+    /// Builder-style method to set whether to show the dialog that offers to
+    /// restart the application.
+    ///
+    /// The report is written either way. Defaults to `true`.
+    pub fn offer_restart(mut self, offer: bool) -> Self {
+        self.offer_restart = offer;
+        self
+    }
+
+    /// The directory the reports are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Install the panic hook, and start collecting breadcrumbs on this thread.
+    pub(crate) fn install(self) {
+        if cfg!(target_arch = "wasm32") {
+            tracing::warn!("crash reports are not supported on the web");
+            return;
+        }
+        BREADCRUMBS.with(|b| *b.borrow_mut() = Some(Breadcrumbs::default()));
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = payload_message(info.payload());
+            let location = info.location().map(ToString::to_string);
+            let report = BREADCRUMBS.with(|b| match b.try_borrow() {
+                Ok(b) => b.as_ref().map(|b| b.report(message, location.as_deref())),
+                // we panicked while recording; report what we can
+                Err(_) => Some(Breadcrumbs::default().report(message, location.as_deref())),
+            });
+            previous(info);
+            if let Some(report) = report {
+                self.crashed(&report);
+            }
+        }));
+    }
+
+    /// If this instance was started to report a crash, show the crash dialog
+    /// and restart the application if the user asks for it.
+    ///
+    /// Returns `true` if this instance was started to report a crash, in
+    /// which case it shouldn't launch.
+    pub(crate) fn show_pending_report(&self) -> bool {
+        let path = match std::env::var_os(REPORT_ENV_VAR) {
+            Some(path) => PathBuf::from(path),
+            None => return false,
+        };
+        let message = format!(
+            "{} ran into a problem and had to close.\n\nA crash report was saved to {}",
+            self.app_name,
+            path.display()
+        );
+        if Application::show_message_dialog("Crash", &message, "Restart", "Quit") {
+            if let Err(e) = spawn_self(None) {
+                tracing::error!("failed to restart: {}", e);
+            }
+        }
+        true
+    }
+
+    fn crashed(&self, report: &str) {
+        let path = match self.write_report(report) {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::error!("failed to write crash report to {:?}: {}", self.dir, e);
+                return;
+            }
+        };
+        tracing::error!("crash report written to {:?}", path);
+        if self.offer_restart {
+            if let Err(e) = spawn_self(Some(&path)) {
+                tracing::error!("failed to start the crash dialog: {}", e);
+            }
+        }
+    }
+
+    fn write_report(&self, report: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = self
+            .dir
+            .join(format!("crash-{}-{}.txt", secs, process::id()));
+        fs::write(&path, report)?;
+        Ok(path)
+    }
+}
+
+impl Breadcrumbs {
+    fn report(&self, message: &str, location: Option<&str>) -> String {
+        let mut report = format!("panicked: {}\n", message);
+        if let Some(location) = location {
+            let _ = writeln!(report, "at: {}", location);
+        }
+        let _ = writeln!(report, "\nwindows:");
+        for (id, summary) in &self.windows {
+            let _ = writeln!(report, "  {:?}: {}", id, summary);
+        }
+        let _ = writeln!(report, "\nrecent commands, oldest first:");
+        for cmd in &self.commands {
+            let _ = writeln!(report, "  {}", cmd);
+        }
+        report
+    }
+}
+
+/// The message of a panic.
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(s) => s,
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.as_str(),
+            None => "Box<dyn Any>",
+        },
+    }
+}
+
+/// Start a new instance of the application with the same arguments.
+///
+/// If `report` is given, the new instance shows the crash dialog for it.
+fn spawn_self(report: Option<&Path>) -> io::Result<()> {
+    let mut args = std::env::args_os();
+    let arg0 = args.next();
+    let exe = std::env::current_exe()
+        .ok()
+        .or_else(|| arg0.map(PathBuf::from))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no executable path"))?;
+    let mut command = process::Command::new(exe);
+    command.args(args.collect::<Vec<OsString>>());
+    match report {
+        Some(path) => command.env(REPORT_ENV_VAR, path),
+        None => command.env_remove(REPORT_ENV_VAR),
+    };
+    command.spawn().map(drop)
+}
+
+fn with_breadcrumbs(f: impl FnOnce(&mut Breadcrumbs)) {
+    BREADCRUMBS.with(|b| {
+        if let Some(b) = b.borrow_mut().as_mut() {
+            f(b)
+        }
+    })
+}
+
+/// Remember a command for the crash report.
+pub(crate) fn record_command(cmd: &Command) {
+    with_breadcrumbs(|b| {
+        if b.commands.len() == MAX_RECENT_COMMANDS {
+            b.commands.pop_front();
+        }
+        b.commands
+            .push_back(format!("{} -> {:?}", cmd.symbol(), cmd.target()));
+    })
+}
+
+/// Remember an open window for the crash report.
+///
+/// The type name of the root widget also names the widgets it is composed of,
+/// which gives an outline of the widget tree.
+pub(crate) fn record_window(id: WindowId, title: &str, root: &str) {
+    with_breadcrumbs(|b| b.windows.push((id, format!("{:?} {}", title, root))))
+}
+
+/// Forget a window that was closed.
+pub(crate) fn forget_window(id: WindowId) {
+    with_breadcrumbs(|b| b.windows.retain(|(window, _)| *window != id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Selector, Target};
+    use test_env_log::test;
+
+    fn breadcrumbs() -> Option<Breadcrumbs> {
+        BREADCRUMBS.with(|b| b.borrow_mut().take())
+    }
+
+    #[test]
+    fn nothing_is_recorded_without_a_reporter() {
+        record_command(&Command::new(
+            Selector::<()>::new("test.cmd"),
+            (),
+            Target::Global,
+        ));
+        record_window(WindowId::next(), "title", "Label");
+        assert!(breadcrumbs().is_none());
+    }
+
+    #[test]
+    fn recent_commands_and_open_windows() {
+        BREADCRUMBS.with(|b| *b.borrow_mut() = Some(Breadcrumbs::default()));
+        for _ in 0..MAX_RECENT_COMMANDS {
+            record_command(&Command::new(
+                Selector::<()>::new("test.old"),
+                (),
+                Target::Global,
+            ));
+        }
+        record_command(&Command::new(
+            Selector::<()>::new("test.new"),
+            (),
+            Target::Global,
+        ));
+        let (kept, closed) = (WindowId::next(), WindowId::next());
+        record_window(kept, "Main", "Flex<String>");
+        record_window(closed, "Other", "Label<String>");
+        forget_window(closed);
+
+        let crumbs = breadcrumbs().unwrap();
+        assert_eq!(crumbs.commands.len(), MAX_RECENT_COMMANDS);
+        assert!(crumbs.commands.back().unwrap().starts_with("test.new"));
+
+        let report = crumbs.report("boom", Some("src/main.rs:1:1"));
+        assert!(report.starts_with("panicked: boom\nat: src/main.rs:1:1\n"));
+        assert!(report.contains(&format!("{:?}: \"Main\" Flex<String>", kept)));
+        assert!(!report.contains("Other"));
+        assert!(report.contains("test.new -> Global"));
+    }
+
+    #[test]
+    fn panic_messages() {
+        assert_eq!(payload_message(&"static"), "static");
+        assert_eq!(payload_message(&String::from("owned")), "owned");
+        assert_eq!(payload_message(&42), "Box<dyn Any>");
+    }
+
+    #[test]
+    fn report_is_written_to_a_new_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reporter = CrashReporter::new(tmp.path().join("crashes"));
+        let path = reporter.write_report("report").unwrap();
+        assert_eq!(path.parent(), Some(reporter.dir()));
+        assert_eq!(fs::read_to_string(path).unwrap(), "report");
+    }
+}
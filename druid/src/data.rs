@@ -87,9 +87,10 @@ use piet::ImageBuf;
 /// # use druid::Data;
 /// #[derive(Clone, Data)]
 /// struct PathEntry {
-///     // There's no Data impl for PathBuf, but no problem
-///     #[data(eq)]
 ///     path: PathBuf,
+///     // There's no Data impl for Vec, but no problem
+///     #[data(eq)]
+///     tags: Vec<String>,
 ///     priority: usize,
 ///     // This field is not part of our data model.
 ///     #[data(ignore)]
@@ -97,6 +98,14 @@ use piet::ImageBuf;
 /// }
 /// ```
 ///
+/// ## Change sets
+///
+/// Derived implementations for structs also implement [`Data::changes`],
+/// reporting which fields changed. Widgets inside a [`LensWrap`] with a
+/// derived lens are then not updated at all when only other fields of the
+/// struct change. Structs with more than 64 fields share the last bit among
+/// the fields after the 63rd.
+///
 /// ## C-style enums
 ///
 /// In the case of a "c-style" enum (one that only contains unit variants,
@@ -104,6 +113,8 @@ use piet::ImageBuf;
 /// checks for equality. Therefore, such types must also implement `PartialEq`.
 ///
 /// [`Data::same`]: trait.Data.html#tymethod.same
+/// [`Data::changes`]: Data::changes
+/// [`LensWrap`]: crate::widget::LensWrap
 /// [`im` crate]: https://docs.rs/im
 pub trait Data: Clone + 'static {
     //// ANCHOR: same_fn
@@ -119,6 +130,64 @@ pub trait Data: Clone + 'static {
     /// be considered equal when they have the same bit representation.
     fn same(&self, other: &Self) -> bool;
     //// ANCHOR_END: same_fn
+
+    /// Determine which parts of two values differ.
+    ///
+    /// The result must be empty exactly when [`same`] returns `true`. The
+    /// default reports either no change or a change to everything; derived
+    /// implementations for structs report a change to each field that is not
+    /// the same. The parts are the ones [`Lens::depends_on`] refers to.
+    ///
+    /// [`same`]: Data::same
+    /// [`Lens::depends_on`]: crate::Lens::depends_on
+    fn changes(&self, other: &Self) -> ChangeSet {
+        if self.same(other) {
+            ChangeSet::NONE
+        } else {
+            ChangeSet::ALL
+        }
+    }
+}
+
+/// A set of the parts of a value that changed, as reported by
+/// [`Data::changes`].
+///
+/// The parts are the fields of a struct, in the order they are declared.
+/// Each field up to the 63rd has a bit of its own; the fields after share the
+/// last bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChangeSet(u64);
+
+impl ChangeSet {
+    /// No change.
+    pub const NONE: ChangeSet = ChangeSet(0);
+
+    /// A change to every part of the value.
+    pub const ALL: ChangeSet = ChangeSet(u64::MAX);
+
+    /// A change to the field with this index, in declaration order.
+    pub const fn field(index: usize) -> ChangeSet {
+        if index < 63 {
+            ChangeSet(1 << index)
+        } else {
+            ChangeSet(1 << 63)
+        }
+    }
+
+    /// This set, with the changes in `other` added.
+    pub const fn union(self, other: ChangeSet) -> ChangeSet {
+        ChangeSet(self.0 | other.0)
+    }
+
+    /// Whether the two sets have a change in common.
+    pub const fn intersects(self, other: ChangeSet) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Whether there are no changes in this set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
 }
 
 /// An impl of `Data` suitable for simple types.
@@ -174,6 +243,9 @@ impl_data_simple!(std::net::SocketAddrV6);
 impl_data_simple!(std::net::IpAddr);
 impl_data_simple!(std::net::SocketAddr);
 impl_data_simple!(std::ops::RangeFull);
+impl_data_simple!(std::path::PathBuf);
+impl_data_simple!(std::ffi::OsString);
+impl_data_simple!(std::borrow::Cow<'static, str>);
 impl_data_simple!(druid::piet::InterpolationMode);
 #[cfg(feature = "chrono")]
 impl_data_simple!(chrono::Duration);
@@ -185,6 +257,8 @@ impl_data_simple!(chrono::naive::NaiveDate);
 impl_data_simple!(chrono::naive::NaiveDateTime);
 #[cfg(feature = "chrono")]
 impl_data_simple!(chrono::naive::NaiveTime);
+#[cfg(feature = "uuid")]
+impl_data_simple!(uuid::Uuid);
 
 //TODO: remove me!?
 impl_data_simple!(String);
@@ -649,6 +723,43 @@ mod test {
         assert!(!one.same(&two));
     }
 
+    #[test]
+    fn ranges() {
+        assert!((1..4).same(&(1..4)));
+        assert!(!(1..4).same(&(1..5)));
+        assert!(!(1..4).same(&(0..4)));
+        assert!((1..=4).same(&(1..=4)));
+        assert!(!(1..=4).same(&(1..=5)));
+        assert!(!(1.0..2.0).same(&(1.0..2.5)));
+    }
+
+    #[test]
+    fn paths_and_owned_strings() {
+        use std::borrow::Cow;
+        use std::ffi::OsString;
+        use std::path::PathBuf;
+        assert!(PathBuf::from("a/b").same(&PathBuf::from("a/b")));
+        assert!(!PathBuf::from("a/b").same(&PathBuf::from("a/c")));
+        assert!(OsString::from("a").same(&OsString::from("a")));
+        let borrowed: Cow<'static, str> = Cow::Borrowed("a");
+        assert!(borrowed.same(&Cow::Owned("a".to_string())));
+        assert!(!borrowed.same(&Cow::Borrowed("b")));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn chrono_date_times() {
+        use chrono::{FixedOffset, TimeZone, Utc};
+        let utc = Utc.timestamp_opt(1_614_600_000, 0).unwrap();
+        assert!(utc.same(&Utc.timestamp_opt(1_614_600_000, 0).unwrap()));
+        assert!(!utc.same(&Utc.timestamp_opt(1_614_600_001, 0).unwrap()));
+
+        let plus_one = FixedOffset::east_opt(3600).unwrap();
+        let local = plus_one.timestamp_opt(1_614_600_000, 0).unwrap();
+        assert!(local.same(&plus_one.timestamp_opt(1_614_600_000, 0).unwrap()));
+        assert!(!local.same(&plus_one.timestamp_opt(1_614_603_600, 0).unwrap()));
+    }
+
     #[test]
     fn static_strings() {
         let first = "test";
@@ -17,7 +17,9 @@
 //! This is a wrapper around [`druid_shell::FileDialogOptions`] with a few extra druid specifics.
 //! As such, many of the docs are copied from `druid_shell`, and should be kept in sync.
 
-use std::path::PathBuf;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
 use druid_shell::FileDialogOptions as ShellOptions;
 
@@ -138,6 +140,16 @@ impl FileDialogOptions {
         self
     }
 
+    /// Set both files and directories to be selectable.
+    ///
+    /// This is only relevant for open dialogs.
+    ///
+    /// Only macOS supports this; the other platforms fall back to *files mode*.
+    pub fn select_files_and_directories(mut self) -> Self {
+        self.opt = self.opt.select_files_and_directories();
+        self
+    }
+
     /// Set [packages] to be treated as directories instead of files.
     ///
     /// This allows for writing more universal cross-platform code at the cost of user experience.
@@ -225,6 +237,23 @@ impl FileDialogOptions {
         self
     }
 
+    /// Remember the directory of the chosen path under `id`, and start later dialogs
+    /// with the same `id` in that directory.
+    ///
+    /// Use different ids for dialogs that deal with different kinds of files, like
+    /// importing images and opening projects. A directory set with
+    /// [`force_starting_directory`] takes precedence.
+    ///
+    /// The directories are only remembered while the application runs, unless they
+    /// are saved with [`AppLauncher::persist_dialog_directories`].
+    ///
+    /// [`force_starting_directory`]: #method.force_starting_directory
+    /// [`AppLauncher::persist_dialog_directories`]: crate::AppLauncher::persist_dialog_directories
+    pub fn remember_directory(mut self, id: impl Into<String>) -> Self {
+        self.opt = self.opt.remember_directory(id);
+        self
+    }
+
     /// Sets a custom command to use when the file dialog succeeds.
     ///
     /// By default, an "open" dialog sends the [`OPEN_FILE`] command when it succeeds, and a "save"
@@ -251,3 +280,71 @@ impl FileDialogOptions {
         self
     }
 }
+
+/// Restore the directories remembered by dialogs from the file at `path`,
+/// as written by [`save_remembered_directories`].
+pub(crate) fn load_remembered_directories(path: &Path) -> io::Result<()> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for (id, dir) in parse_directories(&text) {
+        ShellOptions::set_remembered_directory(id, dir);
+    }
+    Ok(())
+}
+
+/// Write the directories remembered by dialogs to `path`, creating its
+/// directory if needed.
+pub(crate) fn save_remembered_directories(path: &Path) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(
+        path,
+        format_directories(&ShellOptions::remembered_directories()),
+    )
+}
+
+/// One `id=directory` line per directory.
+///
+/// Ids with a `=` and directories that aren't UTF-8 can't be read back, so
+/// they are left out.
+fn format_directories(dirs: &[(String, PathBuf)]) -> String {
+    dirs.iter()
+        .filter(|(id, _)| !id.contains(&['=', '\n'][..]))
+        .filter_map(|(id, dir)| Some((id, dir.to_str()?)))
+        .filter(|(_, dir)| !dir.contains('\n'))
+        .map(|(id, dir)| format!("{}={}\n", id, dir))
+        .collect()
+}
+
+fn parse_directories(text: &str) -> impl Iterator<Item = (&str, PathBuf)> {
+    text.lines().filter_map(|line| {
+        let (id, dir) = line.split_once('=')?;
+        Some((id, PathBuf::from(dir)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_round_trip() {
+        let dirs = vec![
+            ("images".to_string(), PathBuf::from("/home/me/a=b")),
+            ("projects".to_string(), PathBuf::from("/work")),
+        ];
+        let text = format_directories(&dirs);
+        let parsed: Vec<_> = parse_directories(&text)
+            .map(|(id, dir)| (id.to_string(), dir))
+            .collect();
+        assert_eq!(parsed, dirs);
+
+        // ids that can't be read back are left out
+        let bad = vec![("a=b".to_string(), PathBuf::from("/work"))];
+        assert_eq!(format_directories(&bad), "");
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Drag and drop, between widgets and from other applications.
+
+use std::any::{self, Any};
+use std::fmt;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::kurbo::{Point, Rect, Size, Vec2};
+use crate::{Env, Modifiers, MouseEvent, PaintCtx, WidgetId};
+
+/// What is being dragged.
+///
+/// A drag started by a widget carries a value of any type, which the widgets
+/// it is dragged over can inspect with [`get`] to decide whether they
+/// accept it.
+///
+/// A drag from another application carries [`files`], [`text`] or
+/// [`urls`] instead, depending on what that application offers.
+///
+/// [`get`]: DragContents::get
+/// [`files`]: DragContents::files
+/// [`text`]: DragContents::text
+/// [`urls`]: DragContents::urls
+#[derive(Clone, Default)]
+pub struct DragContents {
+    value: Option<Rc<dyn Any>>,
+    type_name: &'static str,
+    files: Vec<PathBuf>,
+    text: Option<String>,
+    urls: Vec<String>,
+}
+
+/// A drag moving over a widget, or being dropped on it.
+///
+/// Like [`MouseEvent`], the position is given both in the coordinate space
+/// of the receiver and in that of the window.
+///
+/// [`MouseEvent`]: crate::MouseEvent
+#[derive(Debug, Clone)]
+pub struct DragEvent {
+    /// The position of the drag in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the drag in the coordinate space of the window.
+    pub window_pos: Point,
+    /// What is being dragged.
+    pub contents: DragContents,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+}
+
+/// The image that follows the cursor during a drag.
+///
+/// The image is painted on top of the widgets of the window, by a closure
+/// that paints in a space with its origin at the top left of the image.
+#[derive(Clone)]
+pub struct DragImage {
+    size: Size,
+    hotspot: Vec2,
+    paint: Rc<DragPaintFn>,
+}
+
+type DragPaintFn = dyn Fn(&mut PaintCtx, &Env);
+
+/// A drag in progress.
+#[derive(Clone)]
+pub(crate) struct DragSession {
+    /// The widget that started the drag, which is told when it ends.
+    pub(crate) source: WidgetId,
+    pub(crate) contents: DragContents,
+    pub(crate) image: Option<DragImage>,
+}
+
+impl DragContents {
+    /// Create contents that carry `value`.
+    pub fn new<T: Any>(value: T) -> Self {
+        DragContents {
+            value: Some(Rc::new(value)),
+            type_name: any::type_name::<T>(),
+            ..Default::default()
+        }
+    }
+
+    /// The dragged value, if it is a `T`.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.value.as_ref().and_then(|value| value.downcast_ref())
+    }
+
+    /// Returns `true` if the dragged value is a `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    /// The paths of the files dragged from another application.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// The text dragged from another application, if any.
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// The URLs dragged from another application, except for those of local
+    /// files, which are in [`files`].
+    ///
+    /// [`files`]: DragContents::files
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+}
+
+impl From<druid_shell::DropData> for DragContents {
+    fn from(src: druid_shell::DropData) -> DragContents {
+        DragContents {
+            files: src.files,
+            text: src.text,
+            urls: src.urls,
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Debug for DragContents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DragContents")
+            .field("value", &self.type_name)
+            .field("files", &self.files)
+            .field("text", &self.text)
+            .field("urls", &self.urls)
+            .finish()
+    }
+}
+
+impl DragEvent {
+    pub(crate) fn from_mouse(mouse: &MouseEvent, contents: DragContents) -> Self {
+        DragEvent {
+            pos: mouse.pos,
+            window_pos: mouse.window_pos,
+            contents,
+            mods: mouse.mods,
+        }
+    }
+}
+
+impl From<druid_shell::DropEvent> for DragEvent {
+    fn from(src: druid_shell::DropEvent) -> DragEvent {
+        let druid_shell::DropEvent { pos, data, mods } = src;
+        DragEvent {
+            pos,
+            window_pos: pos,
+            contents: data.into(),
+            mods,
+        }
+    }
+}
+
+impl DragImage {
+    /// Create an image of the given size, painted by `paint`.
+    ///
+    /// The center of the image is under the cursor; see [`with_hotspot`].
+    ///
+    /// [`with_hotspot`]: DragImage::with_hotspot
+    pub fn new(size: Size, paint: impl Fn(&mut PaintCtx, &Env) + 'static) -> Self {
+        DragImage {
+            size,
+            hotspot: size.to_vec2() / 2.0,
+            paint: Rc::new(paint),
+        }
+    }
+
+    /// Builder-style method to set the point of the image that is under the
+    /// cursor, relative to the top left of the image.
+    pub fn with_hotspot(mut self, hotspot: impl Into<Point>) -> Self {
+        self.hotspot = hotspot.into().to_vec2();
+        self
+    }
+
+    /// The area covered by the image when the cursor is at `pos`.
+    pub(crate) fn rect_at(&self, pos: Point) -> Rect {
+        Rect::from_origin_size(pos - self.hotspot, self.size)
+    }
+
+    pub(crate) fn paint(&self, ctx: &mut PaintCtx, env: &Env) {
+        (self.paint)(ctx, env)
+    }
+}
+
+impl fmt::Debug for DragImage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DragImage")
+            .field("size", &self.size)
+            .field("hotspot", &self.hotspot)
+            .finish()
+    }
+}
@@ -24,7 +24,10 @@ use std::sync::Arc;
 
 use crate::localization::L10nManager;
 use crate::text::FontDescriptor;
-use crate::{ArcStr, Color, Data, Insets, Point, Rect, Size};
+use crate::{
+    ArcStr, Color, Data, Insets, PixelSnapping, Point, Rect, Size, TextAntialiasing, TextHinting,
+    Vec2,
+};
 
 /// An environment passed down through all widget traversals.
 ///
@@ -101,6 +104,7 @@ pub struct Key<T> {
 // ANCHOR: value_type
 pub enum Value {
     Point(Point),
+    Vec2(Vec2),
     Size(Size),
     Rect(Rect),
     Insets(Insets),
@@ -437,6 +441,7 @@ impl Value {
         matches!(
             (self, other),
             (Point(_), Point(_))
+                | (Vec2(_), Vec2(_))
                 | (Size(_), Size(_))
                 | (Rect(_), Rect(_))
                 | (Insets(_), Insets(_))
@@ -454,6 +459,7 @@ impl Debug for Value {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
             Value::Point(p) => write!(f, "Point {:?}", p),
+            Value::Vec2(v) => write!(f, "Vec2 {:?}", v),
             Value::Size(s) => write!(f, "Size {:?}", s),
             Value::Rect(r) => write!(f, "Rect {:?}", r),
             Value::Insets(i) => write!(f, "Insets {:?}", i),
@@ -601,11 +607,53 @@ impl_value_type!(u64, UnsignedInt);
 impl_value_type!(Color, Color);
 impl_value_type!(Rect, Rect);
 impl_value_type!(Point, Point);
+impl_value_type!(Vec2, Vec2);
 impl_value_type!(Size, Size);
 impl_value_type!(Insets, Insets);
 impl_value_type!(ArcStr, String);
 impl_value_type!(FontDescriptor, Font);
 
+// Small enums without a `Value` variant of their own are stored as integers.
+macro_rules! impl_value_type_for_enum {
+    ($ty:ty, $($variant:path => $n:expr),+ $(,)?) => {
+        impl ValueType for $ty {
+            fn try_from_value(value: &Value) -> Result<Self, ValueTypeError> {
+                match value {
+                    $(Value::UnsignedInt($n) => Ok($variant),)+
+                    other => Err(ValueTypeError::new(any::type_name::<$ty>(), other.clone())),
+                }
+            }
+        }
+
+        impl From<$ty> for Value {
+            fn from(val: $ty) -> Value {
+                match val {
+                    $($variant => Value::UnsignedInt($n),)+
+                }
+            }
+        }
+    };
+}
+
+impl_value_type_for_enum!(TextAntialiasing,
+    TextAntialiasing::Default => 0,
+    TextAntialiasing::None => 1,
+    TextAntialiasing::Grayscale => 2,
+    TextAntialiasing::Subpixel => 3,
+);
+impl_value_type_for_enum!(PixelSnapping,
+    PixelSnapping::Fractional => 0,
+    PixelSnapping::Layout => 1,
+    PixelSnapping::Strokes => 2,
+);
+impl_value_type_for_enum!(TextHinting,
+    TextHinting::Default => 0,
+    TextHinting::None => 1,
+    TextHinting::Slight => 2,
+    TextHinting::Medium => 3,
+    TextHinting::Full => 4,
+);
+
 impl<T: ValueType> KeyOrValue<T> {
     /// Resolve the concrete type `T` from this `KeyOrValue`, using the provided
     /// [`Env`] if required.
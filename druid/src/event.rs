@@ -16,9 +16,12 @@
 
 use crate::kurbo::{Rect, Shape, Size, Vec2};
 
-use druid_shell::{Clipboard, KeyEvent, TimerToken};
+use druid_shell::{Clipboard, KeyEvent, Monitor, SystemTheme, TimerToken};
 
+use crate::dnd::DragEvent;
 use crate::mouse::MouseEvent;
+use crate::pen::PenEvent;
+use crate::touch::TouchEvent;
 use crate::{Command, Notification, WidgetId};
 
 /// An event, propagated downwards during event flow.
@@ -67,7 +70,15 @@ pub enum Event {
     /// that the window *will* close just because this event is received; for instance, you should
     /// avoid destructive side effects such as cleaning up resources.
     ///
+    /// This is also sent to every window when the application is asked to quit.
+    /// To ask the user first, handle the event, show a dialog, and then submit
+    /// [`CONFIRM_CLOSE`] or [`CANCEL_CLOSE`] to the window depending on their
+    /// answer. A quit waits for the answers of all windows that vetoed it, and
+    /// further close requests for these windows are ignored in the meantime.
+    ///
     /// [`set_handled`]: crate::EventCtx::set_handled
+    /// [`CONFIRM_CLOSE`]: crate::commands::CONFIRM_CLOSE
+    /// [`CANCEL_CLOSE`]: crate::commands::CANCEL_CLOSE
     WindowCloseRequested,
     /// Sent to all widgets in a given window when the system is going to close that window.
     ///
@@ -81,6 +92,33 @@ pub enum Event {
     /// in the WindowPod, but after that it might be considered better
     /// to just handle it in `layout`.
     WindowSize(Size),
+    /// Sent to all widgets in every window when the user changes the light or
+    /// dark preference or the accent color in the system settings.
+    ///
+    /// The [`theme::SYSTEM_DARK_MODE`] and [`theme::SYSTEM_ACCENT_COLOR`] keys
+    /// of the [`Env`] already reflect the new theme.
+    ///
+    /// [`theme::SYSTEM_DARK_MODE`]: crate::theme::SYSTEM_DARK_MODE
+    /// [`theme::SYSTEM_ACCENT_COLOR`]: crate::theme::SYSTEM_ACCENT_COLOR
+    /// [`Env`]: crate::Env
+    SystemThemeChanged(SystemTheme),
+    /// Sent to all widgets in every window when a monitor is connected or
+    /// disconnected, or the arrangement, resolution or scale of the monitors
+    /// changes.
+    ///
+    /// Use [`Screen::get_monitors`] to get the new monitors.
+    ///
+    /// [`Screen::get_monitors`]: crate::Screen::get_monitors
+    MonitorsChanged,
+    /// Sent to all widgets in a window when it moves to another monitor.
+    ///
+    /// This is the monitor holding most of the window, as returned by
+    /// [`WindowHandle::get_monitor`]. Popups and palettes opened from the
+    /// window can be kept within its [`virtual_work_rect`].
+    ///
+    /// [`WindowHandle::get_monitor`]: crate::WindowHandle::get_monitor
+    /// [`virtual_work_rect`]: crate::Monitor::virtual_work_rect
+    WindowMonitorChanged(Monitor),
     /// Called when a mouse button is pressed.
     MouseDown(MouseEvent),
     /// Called when a mouse button is released.
@@ -102,6 +140,54 @@ pub enum Event {
     MouseMove(MouseEvent),
     /// Called when the mouse wheel or trackpad is scrolled.
     Wheel(MouseEvent),
+    /// Called when a contact touches down on a touch screen.
+    ///
+    /// Touch events are sent to the widgets under the contact, and to the
+    /// active widget.
+    TouchDown(TouchEvent),
+    /// Called when a contact on a touch screen moves.
+    TouchMove(TouchEvent),
+    /// Called when a contact is lifted from a touch screen, or cancelled by
+    /// the platform.
+    TouchUp(TouchEvent),
+    /// Called when a pen touches down on a tablet, moves, or is lifted.
+    ///
+    /// Pen events are sent to the widgets under the pen, and to the active
+    /// widget. They come with the pressure and tilt of the pen, for
+    /// pressure-sensitive drawing; the platform also turns them into mouse
+    /// events.
+    Pen(PenEvent),
+    /// Called when a drag moves onto a widget.
+    ///
+    /// Drag events are sent to the widgets under the drag; a widget gets
+    /// `DragEnter` when the drag first moves over it, [`DragOver`] while it
+    /// moves within it, and [`DragLeave`] when it moves out of it.
+    ///
+    /// A widget that accepts what is dragged should handle the event, so
+    /// that the widgets around it know that it did. See [`DropTarget`] for a
+    /// convenient way to accept drops.
+    ///
+    /// Drags from other applications, like files dragged from a file
+    /// manager, are delivered the same way; their [`DragContents`] carry
+    /// files, text or URLs. Handling their events is what tells the platform
+    /// that the drop would be taken.
+    ///
+    /// [`DragContents`]: crate::DragContents
+    /// [`DragOver`]: Event::DragOver
+    /// [`DragLeave`]: Event::DragLeave
+    /// [`DropTarget`]: crate::widget::DropTarget
+    DragEnter(DragEvent),
+    /// Called when a drag moves within a widget.
+    DragOver(DragEvent),
+    /// Called when a drag leaves a widget, or is cancelled while it is over
+    /// the widget.
+    DragLeave,
+    /// Called when a drag is dropped on a widget.
+    ///
+    /// A widget that takes what is dropped should handle the event; the
+    /// widget or application that started the drag is then told that the
+    /// drop was accepted.
+    Drop(DragEvent),
     /// Called when a key is pressed.
     KeyDown(KeyEvent),
     /// Called when a key is released.
@@ -331,6 +417,11 @@ pub enum InternalLifeCycle {
     },
     /// Used to route the `DisabledChanged` event to the required widgets.
     RouteDisabledChanged,
+    /// Used to find the first focusable widget in the given widget, for
+    /// [`EventCtx::focus_first_in`].
+    ///
+    /// [`EventCtx::focus_first_in`]: crate::EventCtx::focus_first_in
+    RouteFocusFirstIn(WidgetId),
     /// The parents widget origin in window coordinate space has changed.
     ParentWindowOrigin,
     /// For testing: request the `WidgetState` of a specific widget.
@@ -393,10 +484,62 @@ impl Event {
                     None
                 }
             }
+            Event::TouchDown(touch) | Event::TouchMove(touch) | Event::TouchUp(touch) => {
+                if force || viewport.winding(touch.pos) != 0 {
+                    let mut touch = touch.clone();
+                    touch.pos += offset;
+                    Some(self.with_touch(touch))
+                } else {
+                    None
+                }
+            }
+            Event::Pen(pen) => {
+                if force || viewport.winding(pen.pos) != 0 {
+                    let mut pen = pen.clone();
+                    pen.pos += offset;
+                    Some(Event::Pen(pen))
+                } else {
+                    None
+                }
+            }
+            Event::DragEnter(drag) | Event::DragOver(drag) | Event::Drop(drag) => {
+                if force || viewport.winding(drag.pos) != 0 {
+                    let mut drag = drag.clone();
+                    drag.pos += offset;
+                    Some(self.with_drag(drag))
+                } else {
+                    // the drag left the visible part of the content.
+                    Some(Event::DragLeave)
+                }
+            }
             _ => Some(self.clone()),
         }
     }
 
+    /// The same touch event with `touch` in place of its contact.
+    ///
+    /// Events other than touch events are returned as they are.
+    pub(crate) fn with_touch(&self, touch: TouchEvent) -> Event {
+        match self {
+            Event::TouchDown(_) => Event::TouchDown(touch),
+            Event::TouchMove(_) => Event::TouchMove(touch),
+            Event::TouchUp(_) => Event::TouchUp(touch),
+            other => other.clone(),
+        }
+    }
+
+    /// The same drag event with `drag` in place of its position and contents.
+    ///
+    /// Events other than drag events are returned as they are.
+    pub(crate) fn with_drag(&self, drag: DragEvent) -> Event {
+        match self {
+            Event::DragEnter(_) => Event::DragEnter(drag),
+            Event::DragOver(_) => Event::DragOver(drag),
+            Event::Drop(_) => Event::Drop(drag),
+            other => other.clone(),
+        }
+    }
+
     /// Whether this event should be sent to widgets which are currently not visible and not
     /// accessible.
     ///
@@ -420,15 +563,26 @@ impl Event {
             | Event::WindowCloseRequested
             | Event::WindowDisconnected
             | Event::WindowSize(_)
+            | Event::SystemThemeChanged(_)
+            | Event::MonitorsChanged
+            | Event::WindowMonitorChanged(_)
             | Event::Timer(_)
             | Event::AnimFrame(_)
             | Event::Command(_)
             | Event::Notification(_)
+            | Event::DragLeave
             | Event::Internal(_) => true,
             Event::MouseDown(_)
             | Event::MouseUp(_)
             | Event::MouseMove(_)
             | Event::Wheel(_)
+            | Event::TouchDown(_)
+            | Event::TouchMove(_)
+            | Event::TouchUp(_)
+            | Event::Pen(_)
+            | Event::DragEnter(_)
+            | Event::DragOver(_)
+            | Event::Drop(_)
             | Event::KeyDown(_)
             | Event::KeyUp(_)
             | Event::Paste(_)
@@ -473,7 +627,9 @@ impl InternalLifeCycle {
             InternalLifeCycle::RouteWidgetAdded
             | InternalLifeCycle::RouteFocusChanged { .. }
             | InternalLifeCycle::RouteDisabledChanged => true,
-            InternalLifeCycle::ParentWindowOrigin => false,
+            InternalLifeCycle::ParentWindowOrigin | InternalLifeCycle::RouteFocusFirstIn(_) => {
+                false
+            }
             InternalLifeCycle::DebugRequestState { .. }
             | InternalLifeCycle::DebugInspectState(_) => true,
         }
@@ -19,11 +19,25 @@ use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
 use crate::shell::IdleHandle;
+use crate::widget::mutable::mutation;
 use crate::win_handler::EXT_EVENT_IDLE_TOKEN;
-use crate::{command::SelectorSymbol, Command, Selector, Target, WindowId};
+use crate::{
+    command::SelectorSymbol, commands, Command, Event, EventCtx, KeyEvent, MouseEvent, Selector,
+    Target, WidgetId, WindowId,
+};
 
 pub(crate) type ExtCommand = (SelectorSymbol, Box<dyn Any + Send>, Target);
 
+/// The closure set with [`AppLauncher::spawner`].
+///
+/// [`AppLauncher::spawner`]: crate::AppLauncher::spawner
+#[cfg(feature = "async")]
+pub(crate) type SpawnFn = dyn Fn(crate::SpawnedFuture) + Send + Sync;
+
+/// Where the spawner is kept, shared by the host and all of its sinks.
+#[cfg(feature = "async")]
+type SpawnerSlot = Arc<Mutex<Option<Arc<SpawnFn>>>>;
+
 /// A thing that can move into other threads and be used to submit commands back
 /// to the running application.
 ///
@@ -32,6 +46,31 @@ pub(crate) type ExtCommand = (SelectorSymbol, Box<dyn Any + Send>, Target);
 pub struct ExtEventSink {
     queue: Arc<Mutex<VecDeque<ExtCommand>>>,
     handle: Arc<Mutex<Option<IdleHandle>>>,
+    #[cfg(feature = "async")]
+    spawner: SpawnerSlot,
+}
+
+/// A user input event that is submitted from outside the application with
+/// [`ExtEventSink::submit_event`], for instance by a hardware controller, a
+/// remote control, or a test driver.
+///
+/// Positions of mouse events are in the window's coordinate space.
+#[derive(Debug, Clone)]
+pub enum ExtInputEvent {
+    /// A mouse button was pressed; see [`Event::MouseDown`].
+    MouseDown(MouseEvent),
+    /// A mouse button was released; see [`Event::MouseUp`].
+    MouseUp(MouseEvent),
+    /// The mouse was moved; see [`Event::MouseMove`].
+    MouseMove(MouseEvent),
+    /// The mouse wheel was turned; see [`Event::Wheel`].
+    Wheel(MouseEvent),
+    /// A key was pressed; see [`Event::KeyDown`].
+    KeyDown(KeyEvent),
+    /// A key was released; see [`Event::KeyUp`].
+    KeyUp(KeyEvent),
+    /// A zoom gesture; see [`Event::Zoom`].
+    Zoom(f64),
 }
 
 /// The stuff that we hold onto inside the app that is related to the
@@ -47,6 +86,9 @@ pub(crate) struct ExtEventHost {
     /// The window that the handle belongs to, so we can keep track of when
     /// we need to get a new handle.
     pub(crate) handle_window_id: Option<WindowId>,
+    /// Runs the futures of the app, if it provided a way to.
+    #[cfg(feature = "async")]
+    spawner: SpawnerSlot,
 }
 
 /// An error that occurs if an external event cannot be submitted.
@@ -63,9 +105,16 @@ impl ExtEventHost {
         ExtEventSink {
             queue: self.queue.clone(),
             handle: self.handle.clone(),
+            #[cfg(feature = "async")]
+            spawner: self.spawner.clone(),
         }
     }
 
+    #[cfg(feature = "async")]
+    pub(crate) fn set_spawner(&self, spawner: Arc<SpawnFn>) {
+        self.spawner.lock().unwrap().replace(spawner);
+    }
+
     pub(crate) fn set_idle(&mut self, handle: IdleHandle, window_id: WindowId) {
         self.handle.lock().unwrap().replace(handle);
         self.handle_window_id = Some(window_id);
@@ -85,6 +134,18 @@ impl ExtEventHost {
 }
 
 impl ExtEventSink {
+    /// Run `future` with the app's spawner, or else on a background thread.
+    #[cfg(feature = "async")]
+    pub(crate) fn spawn(&self, future: impl std::future::Future<Output = ()> + Send + 'static) {
+        let spawner = self.spawner.lock().unwrap().clone();
+        match spawner {
+            Some(spawner) => spawner(Box::pin(future)),
+            None => {
+                std::thread::spawn(move || crate::app_delegate::executor::block_on(future));
+            }
+        }
+    }
+
     /// Submit a [`Command`] to the running application.
     ///
     /// [`Command`] is not thread safe, so you cannot submit it directly;
@@ -117,6 +178,52 @@ impl ExtEventSink {
         ));
         Ok(())
     }
+
+    /// Submit a user input event to a window of the running application.
+    ///
+    /// The event is dispatched like one that came from the platform: mouse
+    /// events update the hot state and go to the active widget if there is
+    /// one, key events go to the focused widget, and the [`AppDelegate`] sees
+    /// the event first. A key press that no widget handles edits the focused
+    /// text field, as one typed without an input method would.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    pub fn submit_event(
+        &self,
+        window_id: WindowId,
+        event: ExtInputEvent,
+    ) -> Result<(), ExtEventError> {
+        self.submit_command(crate::command::sys::SUBMIT_EVENT, event, window_id)
+    }
+
+    /// Run `f` on the [`Mutable`] widget with the given id.
+    ///
+    /// This is useful for applying the result of background work to a widget
+    /// directly; see [`EventCtx::mutate_later`] for details.
+    ///
+    /// [`Mutable`]: crate::widget::Mutable
+    /// [`EventCtx::mutate_later`]: crate::EventCtx::mutate_later
+    pub fn mutate_widget<W: 'static>(
+        &self,
+        id: WidgetId,
+        f: impl FnOnce(&mut W, &mut EventCtx) + Send + 'static,
+    ) -> Result<(), ExtEventError> {
+        self.submit_command(commands::MUTATE_WIDGET, mutation(f), id)
+    }
+}
+
+impl From<ExtInputEvent> for Event {
+    fn from(event: ExtInputEvent) -> Event {
+        match event {
+            ExtInputEvent::MouseDown(mouse) => Event::MouseDown(mouse),
+            ExtInputEvent::MouseUp(mouse) => Event::MouseUp(mouse),
+            ExtInputEvent::MouseMove(mouse) => Event::MouseMove(mouse),
+            ExtInputEvent::Wheel(mouse) => Event::Wheel(mouse),
+            ExtInputEvent::KeyDown(key) => Event::KeyDown(key),
+            ExtInputEvent::KeyUp(key) => Event::KeyUp(key),
+            ExtInputEvent::Zoom(delta) => Event::Zoom(delta),
+        }
+    }
 }
 
 impl std::fmt::Display for ExtEventError {
@@ -0,0 +1,213 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Saving and restoring where windows are on screen.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::{Screen, WindowHandle, WindowState};
+
+/// Where a window is on screen, and how big it is.
+///
+/// A `WindowGeometry` is taken from an open window with [`WindowGeometry::of`],
+/// and applied to a new one with [`WindowDesc::geometry`]. It can be written
+/// to and read from a file, so that windows reopen where the user left them;
+/// [`WindowDesc::persist_geometry`] does all of this for a window.
+///
+/// [`WindowDesc::geometry`]: crate::WindowDesc::geometry
+/// [`WindowDesc::persist_geometry`]: crate::WindowDesc::persist_geometry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    /// The position of the window when it is restored, in [display points],
+    /// relative to the origin of the [virtual screen].
+    ///
+    /// [display points]: crate::Scale
+    /// [virtual screen]: crate::Screen
+    pub position: Point,
+    /// The size of the window when it is restored, in [display points].
+    ///
+    /// [display points]: crate::Scale
+    pub size: Size,
+    /// Whether the window is maximized, minimized or neither.
+    pub state: WindowState,
+    /// Whether the window is fullscreen.
+    pub fullscreen: bool,
+    /// The [virtual rect] of the monitor the window was on, if it was on one.
+    ///
+    /// [virtual rect]: crate::Monitor::virtual_rect
+    pub monitor: Option<Rect>,
+}
+
+impl WindowGeometry {
+    /// The current geometry of the window with the given handle.
+    ///
+    /// The position and size are those the window goes back to when it is
+    /// restored, so that a window that was maximized or fullscreen can be
+    /// restored to where it was before.
+    pub fn of(handle: &WindowHandle) -> WindowGeometry {
+        let rect = handle.get_restored_rect();
+        let monitor = Screen::get_monitors()
+            .iter()
+            .map(|monitor| monitor.virtual_rect())
+            .find(|monitor| monitor.contains(rect.center()));
+        WindowGeometry {
+            position: rect.origin(),
+            size: rect.size(),
+            state: handle.get_window_state(),
+            fullscreen: handle.is_fullscreen(),
+            monitor,
+        }
+    }
+
+    /// Returns `true` if the monitor the window was on is still connected,
+    /// with the same bounds.
+    ///
+    /// If it isn't, restoring the position could put the window off screen.
+    pub fn monitor_available(&self) -> bool {
+        match self.monitor {
+            Some(rect) => Screen::get_monitors()
+                .iter()
+                .any(|monitor| monitor.virtual_rect() == rect),
+            None => false,
+        }
+    }
+
+    /// Read a geometry written by [`save`].
+    ///
+    /// Returns `Ok(None)` if there is no file at `path`.
+    ///
+    /// [`save`]: WindowGeometry::save
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Option<WindowGeometry>> {
+        match fs::read_to_string(path) {
+            Ok(text) => parse(&text)
+                .map(Some)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "bad window geometry")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write this geometry to `path`, creating its directory if needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for WindowGeometry {
+    /// One `key=value` line per field, as read by [`WindowGeometry::load`].
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = match self.state {
+            WindowState::Maximized => "maximized",
+            WindowState::Minimized => "minimized",
+            WindowState::Restored => "restored",
+        };
+        writeln!(f, "position={},{}", self.position.x, self.position.y)?;
+        writeln!(f, "size={},{}", self.size.width, self.size.height)?;
+        writeln!(f, "state={}", state)?;
+        if self.fullscreen {
+            writeln!(f, "fullscreen=true")?;
+        }
+        if let Some(rect) = self.monitor {
+            writeln!(f, "monitor={},{},{},{}", rect.x0, rect.y0, rect.x1, rect.y1)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse(text: &str) -> Option<WindowGeometry> {
+    let mut position = None;
+    let mut size = None;
+    let mut state = WindowState::Restored;
+    let mut fullscreen = false;
+    let mut monitor = None;
+    for line in text.lines() {
+        let (key, value) = match line.split_once('=') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let numbers: Vec<f64> = value
+            .split(',')
+            .map(|n| n.trim().parse().ok())
+            .collect::<Option<_>>()
+            .unwrap_or_default();
+        match (key.trim(), numbers.as_slice()) {
+            ("position", &[x, y]) => position = Some(Point::new(x, y)),
+            ("size", &[width, height]) => size = Some(Size::new(width, height)),
+            ("monitor", &[x0, y0, x1, y1]) => monitor = Some(Rect::new(x0, y0, x1, y1)),
+            ("fullscreen", _) => fullscreen = value.trim() == "true",
+            ("state", _) => {
+                state = match value.trim() {
+                    "maximized" => WindowState::Maximized,
+                    "minimized" => WindowState::Minimized,
+                    _ => WindowState::Restored,
+                }
+            }
+            // ignore what we don't understand, it may come from a newer version.
+            _ => (),
+        }
+    }
+    Some(WindowGeometry {
+        position: position?,
+        size: size?,
+        state,
+        fullscreen,
+        monitor,
+    })
+}
+
+/// Write `geometry` to `path`, logging any error.
+pub(crate) fn save_or_warn(geometry: &WindowGeometry, path: &Path) {
+    if let Err(e) = geometry.save(path) {
+        tracing::warn!("failed to save window geometry to {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_round_trip() {
+        let geometry = WindowGeometry {
+            position: Point::new(10.0, -20.5),
+            size: Size::new(800.0, 600.0),
+            state: WindowState::Maximized,
+            fullscreen: false,
+            monitor: Some(Rect::new(0.0, 0.0, 1920.0, 1080.0)),
+        };
+        assert_eq!(parse(&geometry.to_string()), Some(geometry));
+
+        let fullscreen = WindowGeometry {
+            fullscreen: true,
+            ..geometry
+        };
+        assert_eq!(parse(&fullscreen.to_string()), Some(fullscreen));
+
+        let without_monitor = WindowGeometry {
+            monitor: None,
+            ..geometry
+        };
+        assert_eq!(parse(&without_monitor.to_string()), Some(without_monitor));
+
+        assert_eq!(parse("size=1,2\n"), None);
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured records of user interactions, for analytics and UX research.
+
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
+use crate::{MouseButton, WidgetId, WindowId};
+
+/// The closure type used to receive [`Interaction`]s.
+pub(crate) type InteractionSinkFn = dyn FnMut(&Interaction);
+
+/// A single user interaction, as reported to the sink installed with
+/// [`AppLauncher::log_interactions`].
+///
+/// [`AppLauncher::log_interactions`]: crate::AppLauncher::log_interactions
+#[derive(Debug, Clone)]
+pub struct Interaction {
+    /// The window in which the interaction happened, if any.
+    ///
+    /// This is `None` for commands that were not targeted at a window or widget.
+    pub window_id: Option<WindowId>,
+    /// The widget involved, if the interaction is associated with one.
+    pub widget_id: Option<WidgetId>,
+    /// The type name of that widget, as returned by [`Widget::type_name`].
+    ///
+    /// [`Widget::type_name`]: crate::Widget::type_name
+    pub widget_type: Option<&'static str>,
+    /// What happened.
+    pub kind: InteractionKind,
+    /// When the interaction started.
+    pub timestamp: Instant,
+    /// How long the interaction took to handle, if this was measured.
+    pub duration: Option<Duration>,
+}
+
+/// The kind of an [`Interaction`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InteractionKind {
+    /// A widget was pressed with the mouse and became active.
+    Click {
+        /// The button that was pressed.
+        button: MouseButton,
+        /// The click count, as reported by [`MouseEvent::count`].
+        ///
+        /// [`MouseEvent::count`]: crate::MouseEvent::count
+        count: u8,
+    },
+    /// A widget gained (`true`) or lost (`false`) keyboard focus.
+    FocusChanged(bool),
+    /// A command was dispatched by the application.
+    Command {
+        /// The name of the command's [`Selector`].
+        ///
+        /// [`Selector`]: crate::Selector
+        selector: &'static str,
+    },
+}
+
+impl Interaction {
+    pub(crate) fn new(window_id: Option<WindowId>, kind: InteractionKind) -> Self {
+        Interaction {
+            window_id,
+            widget_id: None,
+            widget_type: None,
+            kind,
+            timestamp: Instant::now(),
+            duration: None,
+        }
+    }
+
+    pub(crate) fn with_widget(mut self, id: WidgetId, type_name: &'static str) -> Self {
+        self.widget_id = Some(id);
+        self.widget_type = Some(type_name);
+        self
+    }
+}
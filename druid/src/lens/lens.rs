@@ -16,7 +16,7 @@ use std::marker::PhantomData;
 use std::ops;
 use std::sync::Arc;
 
-use crate::Data;
+use crate::{ChangeSet, Data};
 
 /// A lens is a datatype that gives access to a part of a larger
 /// data structure.
@@ -50,6 +50,17 @@ pub trait Lens<T: ?Sized, U: ?Sized> {
     /// cloning the list, giving the closure mutable access to the clone,
     /// then updating the reference after the closure returns.
     fn with_mut<V, F: FnOnce(&mut U) -> V>(&self, data: &mut T, f: F) -> V;
+
+    /// The parts of the data, as reported by [`Data::changes`], that the
+    /// focused value depends on.
+    ///
+    /// If none of these changed, the focused value must be the same. The
+    /// default is every part; derived lenses name their field.
+    ///
+    /// [`Data::changes`]: crate::Data::changes
+    fn depends_on(&self) -> ChangeSet {
+        ChangeSet::ALL
+    }
 }
 
 /// Helpers for manipulating `Lens`es
@@ -186,6 +197,23 @@ pub trait LensExt<A: ?Sized, B: ?Sized>: Lens<A, B> {
         self.then(Index::new(index))
     }
 
+    /// Access an index in a list, yielding `None` if it is out of bounds
+    ///
+    /// See [`TryIndex`] for details.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// assert_eq!(lens::Identity.try_index(2).get(&vec![0u32, 1, 2, 3]), Some(2));
+    /// assert_eq!(lens::Identity.try_index(4).get(&vec![0u32, 1, 2, 3]), None);
+    /// ```
+    fn try_index<C>(self, index: usize) -> Then<Self, TryIndex, B>
+    where
+        TryIndex: Lens<B, Option<C>>,
+        Self: Sized,
+    {
+        self.then(TryIndex::new(index))
+    }
+
     /// Adapt to operate on the contents of an `Arc` with efficient copy-on-write semantics
     ///
     /// ```
@@ -340,6 +368,10 @@ where
     fn with_mut<V, F: FnOnce(&mut C) -> V>(&self, data: &mut A, f: F) -> V {
         self.left.with_mut(data, |b| self.right.with_mut(b, f))
     }
+
+    fn depends_on(&self) -> ChangeSet {
+        self.left.depends_on()
+    }
 }
 
 impl<T: Clone, U: Clone, B> Clone for Then<T, U, B> {
@@ -457,6 +489,252 @@ where
     }
 }
 
+/// `Lens` for an item of a list, like a `Vec` or an [`im::Vector`], that
+/// yields `None` if the index is out of bounds.
+///
+/// Unlike [`Index`], this doesn't panic when the item goes away, for instance
+/// if it is deleted while a detail view for it is open. What the lens yields
+/// then depends on its [`IndexPolicy`].
+///
+/// Writing `Some` replaces the item, and writing `None` removes it from the
+/// list. Writes are ignored if the index doesn't resolve to an item.
+///
+/// ```
+/// use druid::lens::{self, IndexPolicy, LensExt};
+///
+/// let mut items = vec!["a", "b"];
+/// let second = lens::TryIndex::new(1);
+/// assert_eq!(second.get(&items), Some("b"));
+///
+/// // remove the item through the lens
+/// second.put(&mut items, None);
+/// assert_eq!(second.get(&items), None);
+///
+/// // stick to the last item when the index goes out of bounds
+/// let clamped = second.with_policy(IndexPolicy::Clamp);
+/// assert_eq!(clamped.get(&items), Some("a"));
+/// ```
+///
+/// [`im::Vector`]: crate::im::Vector
+#[derive(Debug, Copy, Clone)]
+pub struct TryIndex {
+    index: usize,
+    policy: IndexPolicy,
+}
+
+/// What a [`TryIndex`] lens yields when its index is out of bounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum IndexPolicy {
+    /// Yield `None`.
+    #[default]
+    Missing,
+    /// Yield the last item, or `None` if the list is empty.
+    Clamp,
+}
+
+impl TryIndex {
+    /// Construct a lens that accesses a particular index, if it is in bounds.
+    ///
+    /// See also `LensExt::try_index`.
+    pub fn new(index: usize) -> Self {
+        TryIndex {
+            index,
+            policy: IndexPolicy::Missing,
+        }
+    }
+
+    /// Builder-style method to set what the lens yields when the index is out
+    /// of bounds.
+    pub fn with_policy(mut self, policy: IndexPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The index of the item that the lens accesses in a list of length `len`.
+    fn resolve(self, len: usize) -> Option<usize> {
+        if self.index < len {
+            return Some(self.index);
+        }
+        match self.policy {
+            IndexPolicy::Missing => None,
+            IndexPolicy::Clamp => len.checked_sub(1),
+        }
+    }
+}
+
+/// Implements the [`TryIndex`] lens for a list type.
+macro_rules! impl_try_index_lens {
+    ($($list:ident)::+) => {
+        impl<T: Data> Lens<$($list)::+<T>, Option<T>> for TryIndex {
+            fn with<V, F: FnOnce(&Option<T>) -> V>(&self, data: &$($list)::+<T>, f: F) -> V {
+                f(&self.resolve(data.len()).map(|index| data[index].clone()))
+            }
+
+            fn with_mut<V, F: FnOnce(&mut Option<T>) -> V>(
+                &self,
+                data: &mut $($list)::+<T>,
+                f: F,
+            ) -> V {
+                let index = self.resolve(data.len());
+                let old = index.map(|index| data[index].clone());
+                let mut value = old.clone();
+                let result = f(&mut value);
+                if let (Some(index), false) = (index, old.same(&value)) {
+                    match value {
+                        Some(value) => data[index] = value,
+                        None => {
+                            data.remove(index);
+                        }
+                    }
+                }
+                result
+            }
+        }
+    };
+}
+
+impl_try_index_lens!(Vec);
+#[cfg(feature = "im")]
+impl_try_index_lens!(im::Vector);
+
+/// `Lens` for the entry of a key in a map, like [`im::HashMap`] or [`im::OrdMap`].
+///
+/// The lens yields `None` if the map has no entry for the key. Writing
+/// `Some` inserts or replaces the entry, and writing `None` removes it. The
+/// map is only modified if the value changed, so that it keeps sharing its
+/// structure with earlier versions.
+///
+/// ```
+/// use druid::{im::HashMap, lens, Lens, LensExt};
+///
+/// let mut scores: HashMap<String, u32> = HashMap::new();
+/// let alice = lens::Key::new("alice");
+/// assert_eq!(alice.get(&scores), None);
+/// alice.put(&mut scores, Some(3));
+/// assert_eq!(scores.get("alice"), Some(&3));
+///
+/// // treat missing entries as zero
+/// let bob = lens::Key::new("bob").with_default();
+/// assert_eq!(bob.get(&scores), 0);
+/// bob.with_mut(&mut scores, |score| *score += 1);
+/// assert_eq!(scores.get("bob"), Some(&1));
+/// ```
+///
+/// [`im::HashMap`]: crate::im::HashMap
+/// [`im::OrdMap`]: crate::im::OrdMap
+#[cfg(feature = "im")]
+#[cfg_attr(docsrs, doc(cfg(feature = "im")))]
+#[derive(Debug, Clone)]
+pub struct Key<K> {
+    key: K,
+}
+
+/// `Lens` for the entry of a key in a map, that yields a default value if
+/// there is no entry.
+///
+/// An entry is inserted once the value is changed. See [`Key::with_default`].
+#[cfg(feature = "im")]
+#[cfg_attr(docsrs, doc(cfg(feature = "im")))]
+#[derive(Debug, Clone)]
+pub struct KeyOrDefault<K> {
+    key: K,
+}
+
+#[cfg(feature = "im")]
+impl<K> Key<K> {
+    /// Construct a lens that accesses the entry of `key`.
+    pub fn new(key: K) -> Self {
+        Key { key }
+    }
+
+    /// Use the default value of the map's values if there is no entry,
+    /// instead of yielding an `Option`.
+    pub fn with_default(self) -> KeyOrDefault<K> {
+        KeyOrDefault { key: self.key }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<K> KeyOrDefault<K> {
+    /// Construct a lens that accesses the entry of `key`, or a default value.
+    pub fn new(key: K) -> Self {
+        KeyOrDefault { key }
+    }
+}
+
+/// Implements the [`Key`] and [`KeyOrDefault`] lenses for a map type.
+#[cfg(feature = "im")]
+macro_rules! impl_key_lens {
+    ($map:ident, $($bounds:tt)+) => {
+        impl<K, Q, V> Lens<im::$map<K, V>, Option<V>> for Key<Q>
+        where
+            K: $($bounds)+,
+            Q: Into<K> + Clone,
+            V: Data,
+        {
+            fn with<R, F: FnOnce(&Option<V>) -> R>(&self, data: &im::$map<K, V>, f: F) -> R {
+                f(&data.get(&self.key.clone().into()).cloned())
+            }
+
+            fn with_mut<R, F: FnOnce(&mut Option<V>) -> R>(
+                &self,
+                data: &mut im::$map<K, V>,
+                f: F,
+            ) -> R {
+                let key = self.key.clone().into();
+                let old = data.get(&key).cloned();
+                let mut value = old.clone();
+                let result = f(&mut value);
+                if !old.same(&value) {
+                    match value {
+                        Some(value) => {
+                            data.insert(key, value);
+                        }
+                        None => {
+                            data.remove(&key);
+                        }
+                    }
+                }
+                result
+            }
+        }
+
+        impl<K, Q, V> Lens<im::$map<K, V>, V> for KeyOrDefault<Q>
+        where
+            K: $($bounds)+,
+            Q: Into<K> + Clone,
+            V: Data + Default,
+        {
+            fn with<R, F: FnOnce(&V) -> R>(&self, data: &im::$map<K, V>, f: F) -> R {
+                match data.get(&self.key.clone().into()) {
+                    Some(value) => f(value),
+                    None => f(&V::default()),
+                }
+            }
+
+            fn with_mut<R, F: FnOnce(&mut V) -> R>(&self, data: &mut im::$map<K, V>, f: F) -> R {
+                let key = self.key.clone().into();
+                let old = data.get(&key).cloned();
+                let mut value = old.clone().unwrap_or_default();
+                let result = f(&mut value);
+                let changed = match old {
+                    Some(old) => !old.same(&value),
+                    None => !V::default().same(&value),
+                };
+                if changed {
+                    data.insert(key, value);
+                }
+                result
+            }
+        }
+    };
+}
+
+#[cfg(feature = "im")]
+impl_key_lens!(HashMap, std::hash::Hash + Eq + Clone + 'static);
+#[cfg(feature = "im")]
+impl_key_lens!(OrdMap, Ord + Clone + 'static);
+
 /// The identity lens: the lens which does nothing, i.e. exposes exactly
 /// the original value.
 ///
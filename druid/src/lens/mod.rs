@@ -49,6 +49,14 @@
 #[allow(clippy::module_inception)]
 #[macro_use]
 mod lens;
-pub use lens::{Constant, Deref, Field, Identity, InArc, Index, Map, Ref, Then, Unit};
+pub use lens::{
+    Constant, Deref, Field, Identity, InArc, Index, IndexPolicy, Map, Ref, Then, TryIndex, Unit,
+};
+#[cfg(feature = "im")]
+pub use lens::{Key, KeyOrDefault};
 #[doc(hidden)]
 pub use lens::{Lens, LensExt};
+
+mod prism;
+#[doc(hidden)]
+pub use prism::Prism;
@@ -0,0 +1,74 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A prism gives access to one variant of a sum type, like an enum.
+///
+/// Where a [`Lens`] focuses on a part of the data that is always there, like
+/// the field of a struct, the part a prism focuses on is only there while the
+/// data is the right variant. The data can be switched to the variant with
+/// [`put`].
+///
+/// Prisms for the variants of an enum can be derived:
+///
+/// ```
+/// use druid::{Data, Prism};
+///
+/// #[derive(Clone, Data, Prism)]
+/// enum Page {
+///     Welcome,
+///     Editor(String),
+/// }
+///
+/// let mut page = Page::Welcome;
+/// assert_eq!(Page::editor.get(&page), None);
+/// Page::editor.put(&mut page, "hello".into());
+/// assert_eq!(Page::editor.get(&page), Some("hello".to_string()));
+/// assert!(!Page::welcome.matches(&page));
+/// ```
+///
+/// The [`PrismWrap`] widget shows a child only while the data is a given
+/// variant.
+///
+/// [`Lens`]: crate::Lens
+/// [`put`]: Prism::put
+/// [`PrismWrap`]: crate::widget::PrismWrap
+pub trait Prism<T: ?Sized, U: ?Sized> {
+    /// Get non-mut access to the variant's data, if `data` is that variant.
+    ///
+    /// Runs the supplied closure with a reference to the variant's data, and
+    /// returns its result, or returns `None` if `data` is another variant.
+    fn with<V, F: FnOnce(&U) -> V>(&self, data: &T, f: F) -> Option<V>;
+
+    /// Get mutable access to the variant's data, if `data` is that variant.
+    fn with_mut<V, F: FnOnce(&mut U) -> V>(&self, data: &mut T, f: F) -> Option<V>;
+
+    /// Replace `data` with the variant, holding `value`.
+    fn put(&self, data: &mut T, value: U)
+    where
+        T: Sized,
+        U: Sized;
+
+    /// Copy the variant's data out of `data`, if it is that variant.
+    fn get(&self, data: &T) -> Option<U>
+    where
+        U: Clone,
+    {
+        self.with(data, |x| x.clone())
+    }
+
+    /// Whether `data` is the variant.
+    fn matches(&self, data: &T) -> bool {
+        self.with(data, |_| ()).is_some()
+    }
+}
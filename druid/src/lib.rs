@@ -131,7 +131,7 @@
 
 // Allows to use macros from druid_derive in this crate
 extern crate self as druid;
-pub use druid_derive::Lens;
+pub use druid_derive::{Lens, Prism};
 
 use druid_shell as shell;
 #[doc(inline)]
@@ -148,30 +148,46 @@ pub mod lens;
 #[macro_use]
 mod util;
 
+mod about;
+pub mod animation;
 mod app;
 mod app_delegate;
 mod bloom;
 mod box_constraints;
+pub mod color;
 mod command;
 mod contexts;
 mod core;
+mod crash;
 mod data;
 mod dialog;
+mod dnd;
 pub mod env;
 mod event;
 mod ext_event;
+mod geometry;
+mod interaction;
 mod localization;
 pub mod menu;
 mod mouse;
+mod pen;
+mod pixel_snap;
 pub mod scroll_component;
+mod session;
+mod shortcut;
 mod sub_window;
+mod task;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tests;
 pub mod text;
 pub mod theme;
+mod toolbar;
+mod touch;
+mod tray;
 pub mod widget;
 mod win_handler;
 mod window;
+mod window_set;
 
 // Types from kurbo & piet that are required by public API.
 pub use kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
@@ -182,9 +198,11 @@ pub use piet::{Color, ImageBuf, LinearGradient, RadialGradient, RenderContext, U
 pub use shell::image;
 pub use shell::keyboard_types;
 pub use shell::{
-    Application, Clipboard, ClipboardFormat, Code, Cursor, CursorDesc, Error as PlatformError,
-    FileInfo, FileSpec, FormatId, HotKey, KbKey, KeyEvent, Location, Modifiers, Monitor,
-    MouseButton, MouseButtons, RawMods, Region, Scalable, Scale, Screen, SysMods, TimerToken,
+    Application, Clipboard, ClipboardFormat, Code, Cursor, CursorDesc, DesktopNotification,
+    Error as PlatformError, FileInfo, FileSpec, FormatId, GlobalHotKeyToken, HotKey, KbKey,
+    KeyEvent, Location, Modifiers, Monitor, MouseButton, MouseButtons, NotificationToken, PenPhase,
+    RawMods, Region, ResizeEdge, Scalable, Scale, Screen, ShellError, ShellOperation, SysMods,
+    SystemTheme, TextAntialiasing, TextHinting, TextRenderingOptions, TimerToken, TouchId,
     WindowHandle, WindowLevel, WindowState,
 };
 
@@ -192,24 +210,40 @@ pub use shell::{
 pub use crate::shell::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 
 pub use crate::core::WidgetPod;
+pub use about::AboutInfo;
 pub use app::{AppLauncher, WindowConfig, WindowDesc, WindowSizePolicy};
 pub use app_delegate::{AppDelegate, DelegateCtx};
+#[cfg(feature = "async")]
+pub use app_delegate::{AsyncUpdate, DelegateFuture, SpawnedFuture};
 pub use box_constraints::BoxConstraints;
 pub use command::{sys as commands, Command, Notification, Selector, SingleUse, Target};
 pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx};
-pub use data::Data;
+pub use crash::CrashReporter;
+pub use data::{ChangeSet, Data};
 pub use dialog::FileDialogOptions;
+pub use dnd::{DragContents, DragEvent, DragImage};
 pub use env::{Env, Key, KeyOrValue, Value, ValueType, ValueTypeError};
 pub use event::{Event, InternalEvent, InternalLifeCycle, LifeCycle};
-pub use ext_event::{ExtEventError, ExtEventSink};
-pub use lens::{Lens, LensExt};
+pub use ext_event::{ExtEventError, ExtEventSink, ExtInputEvent};
+pub use geometry::WindowGeometry;
+pub use interaction::{Interaction, InteractionKind};
+pub use lens::{Lens, LensExt, Prism};
 pub use localization::LocalizedString;
 pub use menu::{sys as platform_menus, Menu, MenuItem};
 pub use mouse::MouseEvent;
+pub use pen::PenEvent;
+pub use pixel_snap::PixelSnapping;
+pub use session::SessionStore;
+pub use shortcut::ShortcutMap;
+pub use task::{ProgressReporter, TaskProgress, TaskStatus};
+pub use toolbar::{AccessoryPosition, ToolbarItem, ToolbarStyle};
+pub use touch::TouchEvent;
+pub use tray::TrayDesc;
 pub use util::Handled;
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
 pub use window::{Window, WindowId};
+pub use window_set::{WindowEntry, WindowSet};
 
 #[cfg(not(target_arch = "wasm32"))]
 pub(crate) use event::{StateCell, StateCheckFn};
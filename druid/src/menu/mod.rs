@@ -63,8 +63,8 @@
 //!     Menu::new(LocalizedString::new("macos-menu-application-menu"))
 //!         .entry(
 //!             MenuItem::new(LocalizedString::new("macos-menu-about-app"))
-//!                 // You need to handle the SHOW_ABOUT command yourself (or else do something
-//!                 // directly to the data here instead of using a command).
+//!                 // This shows the About window set with `AppLauncher::about`, or else
+//!                 // you need to handle the SHOW_ABOUT command yourself.
 //!                 .command(commands::SHOW_ABOUT),
 //!         )
 //!         .separator()
@@ -599,12 +599,8 @@ impl<T: Data> Menu<T> {
 impl<T: Data> MenuItem<T> {
     /// Create a new menu item with a given name.
     pub fn new(title: impl Into<LabelText<T>>) -> MenuItem<T> {
-        let mut id = COUNTER.next() as u32;
-        if id == 0 {
-            id = COUNTER.next() as u32;
-        }
         MenuItem {
-            id: MenuItemId(std::num::NonZeroU32::new(id)),
+            id: MenuItemId::next(),
             title: title.into(),
             callback: None,
             hotkey: None,
@@ -837,4 +833,19 @@ impl MenuItemId {
     pub(crate) fn new(id: u32) -> MenuItemId {
         MenuItemId(NonZeroU32::new(id))
     }
+
+    /// A new id, distinct from every other menu item and toolbar item.
+    pub(crate) fn next() -> MenuItemId {
+        let mut id = COUNTER.next() as u32;
+        if id == 0 {
+            id = COUNTER.next() as u32;
+        }
+        MenuItemId(NonZeroU32::new(id))
+    }
+
+    /// The id as it is represented on the druid-shell side.
+    #[cfg(target_os = "macos")]
+    pub(crate) fn as_u32(self) -> u32 {
+        self.0.map(|x| x.get()).unwrap_or(0)
+    }
 }
@@ -160,7 +160,8 @@ pub mod win {
 
         /// The 'Exit' menu item.
         pub fn exit<T: Data>() -> MenuItem<T> {
-            MenuItem::new(LocalizedString::new("win-menu-file-exit")).command(commands::QUIT_APP)
+            MenuItem::new(LocalizedString::new("win-menu-file-exit"))
+                .command(commands::REQUEST_QUIT)
         }
     }
 }
@@ -242,7 +243,7 @@ pub mod mac {
         /// The 'Quit' menu item.
         pub fn quit<T: Data>() -> MenuItem<T> {
             MenuItem::new(LocalizedString::new("macos-menu-quit-app"))
-                .command(commands::QUIT_APP)
+                .command(commands::REQUEST_QUIT)
                 .hotkey(SysMods::Cmd, "q")
         }
     }
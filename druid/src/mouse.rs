@@ -69,6 +69,11 @@ pub struct MouseEvent {
     ///
     /// [WheelEvent]: https://w3c.github.io/uievents/#event-type-wheel
     pub wheel_delta: Vec2,
+    /// Whether `wheel_delta` comes from a device that scrolls smoothly, such
+    /// as a touchpad, rather than from the notches of a mouse wheel.
+    ///
+    /// This is always `false` for events that aren't wheel events.
+    pub precise_wheel: bool,
 }
 
 impl From<druid_shell::MouseEvent> for MouseEvent {
@@ -81,6 +86,7 @@ impl From<druid_shell::MouseEvent> for MouseEvent {
             focus,
             button,
             wheel_delta,
+            precise_wheel,
         } = src;
         MouseEvent {
             pos,
@@ -91,6 +97,7 @@ impl From<druid_shell::MouseEvent> for MouseEvent {
             focus,
             button,
             wheel_delta,
+            precise_wheel,
         }
     }
 }
@@ -0,0 +1,78 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pen (stylus) events.
+
+use crate::kurbo::{Point, Vec2};
+use crate::{Modifiers, PenPhase};
+
+/// A pen touching down on a tablet, moving, or being lifted.
+///
+/// Like [`MouseEvent`], the position is given both in the coordinate space
+/// of the receiver and in that of the window.
+///
+/// Pen events go to the widgets under the pen, and to the active widget.
+/// The platform also turns the pen into mouse events, which are delivered
+/// right after each pen event, so widgets that don't handle pens can still
+/// be used with one.
+///
+/// [`MouseEvent`]: crate::MouseEvent
+#[derive(Debug, Clone)]
+pub struct PenEvent {
+    /// The position of the pen in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the pen in the coordinate space of the window.
+    pub window_pos: Point,
+    /// Whether the pen touched down, moved, or was lifted.
+    pub phase: PenPhase,
+    /// How hard the pen is pressed, from `0.0` to `1.0`.
+    ///
+    /// This is `0.0` while the pen hovers, and `0.5` for pens that don't
+    /// report pressure while they touch the tablet.
+    pub pressure: f64,
+    /// The tilt of the pen in degrees, from `-90.0` to `90.0`; `x` is
+    /// positive when the pen leans to the right, and `y` is positive when it
+    /// leans towards the user.
+    pub tilt: Vec2,
+    /// Whether the button on the side of the pen is held down.
+    pub barrel_button: bool,
+    /// Whether the eraser end of the pen is used.
+    pub eraser: bool,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+}
+
+impl From<druid_shell::PenEvent> for PenEvent {
+    fn from(src: druid_shell::PenEvent) -> PenEvent {
+        let druid_shell::PenEvent {
+            pos,
+            phase,
+            pressure,
+            tilt,
+            barrel_button,
+            eraser,
+            mods,
+        } = src;
+        PenEvent {
+            pos,
+            window_pos: pos,
+            phase,
+            pressure,
+            tilt,
+            barrel_button,
+            eraser,
+            mods,
+        }
+    }
+}
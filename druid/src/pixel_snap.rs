@@ -0,0 +1,143 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aligning layout and painting to device pixels.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::Scale;
+
+/// How layout and painting are aligned to device pixels.
+///
+/// Druid works in display points, which at a scale factor such as 125% or
+/// 150% do not line up with the pixels of the display. Widgets positioned at
+/// fractional pixels, and thin strokes that straddle two pixels, are drawn
+/// blurry.
+///
+/// The policy is read from [`theme::PIXEL_SNAPPING`]; it can be set for a
+/// whole application with [`AppLauncher::configure_env`], or for part of the
+/// widget tree with [`WidgetExt::env_scope`].
+///
+/// [`theme::PIXEL_SNAPPING`]: crate::theme::PIXEL_SNAPPING
+/// [`AppLauncher::configure_env`]: crate::AppLauncher::configure_env
+/// [`WidgetExt::env_scope`]: crate::WidgetExt::env_scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelSnapping {
+    /// Layout and painting use exact fractional positions.
+    ///
+    /// This is the default.
+    Fractional,
+    /// The origin and size of every widget are rounded to device pixels.
+    ///
+    /// Origins are rounded where the widgets end up in the window when they
+    /// are painted, so a widget stays on device pixels in a scroll view that
+    /// is scrolled by a fraction of a pixel.
+    Layout,
+    /// As with `Layout`, and rects and rounded rects stroked with
+    /// [`PaintCtx::stroke`] are also adjusted so that their strokes cover
+    /// whole device pixels.
+    ///
+    /// See [`PaintCtx::snap_stroke`] for other shapes.
+    ///
+    /// [`PaintCtx::stroke`]: crate::PaintCtx::stroke
+    /// [`PaintCtx::snap_stroke`]: crate::PaintCtx::snap_stroke
+    Strokes,
+}
+
+impl PixelSnapping {
+    /// Returns `true` if widget positions and sizes are rounded to device pixels.
+    pub fn snaps_layout(self) -> bool {
+        self != PixelSnapping::Fractional
+    }
+
+    /// Returns `true` if strokes are aligned to device pixels.
+    pub fn snaps_strokes(self) -> bool {
+        self == PixelSnapping::Strokes
+    }
+}
+
+impl Default for PixelSnapping {
+    fn default() -> Self {
+        PixelSnapping::Fractional
+    }
+}
+
+fn snap(val: f64, scale: f64) -> f64 {
+    if val.is_finite() {
+        (val * scale).round() / scale
+    } else {
+        val
+    }
+}
+
+/// Round a point to the nearest device pixel.
+pub(crate) fn snap_point(point: Point, scale: Scale) -> Point {
+    Point::new(snap(point.x, scale.x()), snap(point.y, scale.y()))
+}
+
+/// Round a size to a whole number of device pixels.
+pub(crate) fn snap_size(size: Size, scale: Scale) -> Size {
+    Size::new(snap(size.width, scale.x()), snap(size.height, scale.y()))
+}
+
+/// Adjust the rect and width of a stroke so that the stroke covers whole
+/// device pixels.
+///
+/// The width is rounded to a whole number of pixels (at least one), and the
+/// edges of the rect are moved to the nearest position at which a stroke of
+/// that width is pixel-aligned.
+pub(crate) fn snap_stroke(rect: Rect, width: f64, scale: Scale) -> (Rect, f64) {
+    // the stroke width is the same on both axes, so round it on the smaller one.
+    let min_scale = scale.x().min(scale.y());
+    let width_px = (width * min_scale).round().max(1.0);
+    let snap_edge = |edge: f64, scale: f64| {
+        let half_px = width_px / 2.0;
+        ((edge * scale - half_px).round() + half_px) / scale
+    };
+    let rect = Rect::new(
+        snap_edge(rect.x0, scale.x()),
+        snap_edge(rect.y0, scale.y()),
+        snap_edge(rect.x1, scale.x()),
+        snap_edge(rect.y1, scale.y()),
+    );
+    (rect, width_px / min_scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn snapping() {
+        let scale = Scale::new(1.5, 1.25);
+        assert_eq!(
+            snap_point(Point::new(10.3, 10.3), scale),
+            Point::new(10.0, 10.4)
+        );
+        assert_eq!(
+            snap_size(Size::new(7.0, 7.0), scale),
+            Size::new(11.0 / 1.5, 7.2)
+        );
+
+        // a 1dp stroke at 150% rounds to 2px, whose edges land on whole pixels.
+        let (rect, width) = snap_stroke(Rect::new(0.5, 0.5, 9.5, 9.5), 1.0, Scale::new(1.5, 1.5));
+        let on_pixel = |dp: f64| ((dp * 1.5) - (dp * 1.5).round()).abs() < 1e-9;
+        assert_eq!(width, 2.0 / 1.5);
+        assert!(on_pixel(rect.x0 - width / 2.0) && on_pixel(rect.x0 + width / 2.0));
+        assert!(on_pixel(rect.y1 - width / 2.0) && on_pixel(rect.y1 + width / 2.0));
+
+        let (rect, width) = snap_stroke(Rect::new(0.5, 0.5, 9.5, 9.5), 1.0, Scale::default());
+        assert_eq!((rect, width), (Rect::new(0.5, 0.5, 9.5, 9.5), 1.0));
+    }
+}
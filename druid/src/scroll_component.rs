@@ -17,10 +17,97 @@
 
 use std::time::Duration;
 
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
 use crate::kurbo::{Point, Rect, Vec2};
 use crate::theme;
 use crate::widget::{Axis, Viewport};
-use crate::{Env, Event, EventCtx, LifeCycle, LifeCycleCtx, PaintCtx, RenderContext, TimerToken};
+use crate::{
+    Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Modifiers, MouseButton, MouseButtons,
+    MouseEvent, PaintCtx, RenderContext, TimerToken, TouchId,
+};
+
+/// The default [`friction`] of inertial scrolling.
+///
+/// [`friction`]: ScrollComponent::friction
+pub const DEFAULT_SCROLL_FRICTION: f64 = 3.0;
+
+// Wheel events further apart than this are not part of the same flick.
+const FLICK_EVENT_GAP: Duration = Duration::from_millis(40);
+// Flicks slower than this, in points per second, don't keep scrolling.
+const MIN_FLICK_SPEED: f64 = 250.0;
+// Inertial scrolling stops once it is slower than this, in points per second.
+const MIN_COAST_SPEED: f64 = 10.0;
+// Wheel events further apart than this start a new gesture, that may lock to another axis.
+const AXIS_LOCK_TIMEOUT: Duration = Duration::from_millis(300);
+// A wheel gesture has ended, and can be snapped, once no wheel event arrived for this long.
+const SNAP_DELAY: Duration = Duration::from_millis(150);
+// How quickly settling on a snap point closes the distance; it shrinks as `exp(-SNAP_SPEED * t)`.
+const SNAP_SPEED: f64 = 12.0;
+// Settling ends once the distance to the snap point is less than this, in points.
+const SNAP_EPSILON: f64 = 0.5;
+// A touch has to move this far, in points, before it drags the content.
+const TOUCH_SLOP: f64 = 8.0;
+
+/// A contact on a touch screen that may drag the content.
+#[derive(Debug, Clone, Copy)]
+struct TouchDrag {
+    id: TouchId,
+    /// Where the contact touched down
+    start: Point,
+    /// Where the contact was last
+    last_pos: Point,
+    /// When the contact last moved
+    last_move: Instant,
+    /// true once the contact moved far enough to drag the content
+    dragging: bool,
+    /// The velocity of the drag, in points per second
+    velocity: Vec2,
+}
+
+/// The mouse move of a drag that is auto scrolling, kept to be sent again
+/// once the content moved under it.
+#[derive(Debug, Clone, Copy)]
+struct DragMove {
+    pos: Point,
+    window_pos: Point,
+    buttons: MouseButtons,
+    mods: Modifiers,
+}
+
+impl DragMove {
+    fn to_event(self) -> MouseEvent {
+        MouseEvent {
+            pos: self.pos,
+            window_pos: self.window_pos,
+            buttons: self.buttons,
+            mods: self.mods,
+            count: 0,
+            focus: false,
+            button: MouseButton::None,
+            wheel_delta: Vec2::ZERO,
+            precise_wheel: false,
+        }
+    }
+}
+
+/// A change of the scroll offset that is animated instead of jumping; see
+/// [`ScrollComponent::animate_to`].
+#[derive(Debug, Clone, Copy)]
+struct ScrollAnimation {
+    from: Point,
+    to: Point,
+    /// The length of the animation, in seconds
+    duration: f64,
+    /// How long the animation has been running, in seconds
+    elapsed: f64,
+}
+
+/// Starts fast and slows down towards the end, for `t` from 0 to 1.
+fn ease_out(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
 
 #[derive(Debug, Copy, Clone)]
 /// Which scroll bars of a scroll area are currently enabled.
@@ -92,6 +179,144 @@ impl Default for ScrollbarsEnabled {
     }
 }
 
+/// What happens to wheel events that a scroll area can't scroll any further
+/// in their direction.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum WheelPropagation {
+    /// The events go on to an enclosing scroll area.
+    #[default]
+    Propagate,
+    /// The events are consumed, so enclosing scroll areas never scroll
+    /// while the pointer is over this one.
+    Consume,
+    /// The events are consumed until this scroll area hasn't moved for the
+    /// given time, and then go on to an enclosing scroll area.
+    ///
+    /// This stops a fast gesture that reaches the end of a nested scroll area
+    /// from carrying on to scroll the area around it.
+    PropagateAfterDelay(Duration),
+}
+
+/// Where a scroll area settles after it was scrolled by the user.
+///
+/// Snapping applies to every axis along which the content can scroll. The end
+/// of the content is always a snap point, so that it can be reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ScrollSnap {
+    /// The scroll area stays wherever it was scrolled to.
+    #[default]
+    None,
+    /// The scroll area settles on a multiple of this offset, for instance
+    /// the height of each row of a list.
+    Interval(f64),
+    /// The scroll area settles on whole pages, the size of the viewport.
+    Page,
+    /// The scroll area settles on the start of one of the children of the
+    /// content, for instance the items of a carousel laid out by a [`Flex`].
+    ///
+    /// [`Flex`]: crate::widget::Flex
+    Children,
+}
+
+impl ScrollSnap {
+    /// The snap point closest to `offset`, along `axis` with the given
+    /// viewport length and largest possible offset.
+    ///
+    /// `children` are the layout rects of the children of the content.
+    fn nearest(
+        self,
+        axis: Axis,
+        offset: f64,
+        view_len: f64,
+        max_offset: f64,
+        children: &[Rect],
+    ) -> f64 {
+        let candidate = match self {
+            ScrollSnap::None => return offset,
+            ScrollSnap::Interval(interval) if interval > 0.0 => {
+                (offset / interval).round() * interval
+            }
+            ScrollSnap::Page if view_len > 0.0 => (offset / view_len).round() * view_len,
+            ScrollSnap::Interval(_) | ScrollSnap::Page => return offset,
+            ScrollSnap::Children => children
+                .iter()
+                .map(|rect| axis.major_span(*rect).0)
+                .min_by(|a, b| (a - offset).abs().total_cmp(&(b - offset).abs()))
+                .unwrap_or(offset),
+        };
+        let candidate = candidate.max(0.0).min(max_offset);
+        if (max_offset - offset).abs() < (candidate - offset).abs() {
+            max_offset
+        } else {
+            candidate
+        }
+    }
+}
+
+/// How a scroll area scrolls by itself while something in it is dragged close
+/// to its edges, like a text selection or an item being dragged to a target
+/// that is out of view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoScroll {
+    /// How close to an edge of the viewport, in points, the pointer has to be
+    /// to start scrolling.
+    pub margin: f64,
+    /// How fast to scroll, in points per second, once the pointer reaches an
+    /// edge or leaves the viewport.
+    pub max_speed: f64,
+    /// How the speed ramps up across the margin; the speed is
+    /// `max_speed * depth.powf(ramp)`, where `depth` goes from 0 at the inner
+    /// side of the margin to 1 at the edge. 1.0 is a linear ramp, larger
+    /// values keep the speed low until the pointer is close to the edge.
+    pub ramp: f64,
+}
+
+impl Default for AutoScroll {
+    fn default() -> Self {
+        AutoScroll {
+            margin: 32.0,
+            max_speed: 1200.0,
+            ramp: 2.0,
+        }
+    }
+}
+
+impl AutoScroll {
+    /// The scroll speed when the pointer is `depth` points into the margin.
+    fn speed(&self, depth: f64) -> f64 {
+        if depth <= 0.0 || self.margin <= 0.0 {
+            0.0
+        } else {
+            self.max_speed * (depth / self.margin).min(1.0).powf(self.ramp)
+        }
+    }
+
+    /// The velocity to scroll with when the pointer is at `pos`, in the
+    /// coordinate space of the `view` rect.
+    fn velocity(&self, view: Rect, pos: Point) -> Vec2 {
+        Vec2::new(
+            self.speed(pos.x - (view.x1 - self.margin)) - self.speed(view.x0 + self.margin - pos.x),
+            self.speed(pos.y - (view.y1 - self.margin)) - self.speed(view.y0 + self.margin - pos.y),
+        )
+    }
+}
+
+/// When the scrollbars of a scroll area are shown.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ScrollbarVisibility {
+    /// Scrollbars are shown while scrolling or hovered, and fade out after
+    /// [`SCROLLBAR_FADE_DELAY`].
+    ///
+    /// [`SCROLLBAR_FADE_DELAY`]: crate::theme::SCROLLBAR_FADE_DELAY
+    #[default]
+    AutoHide,
+    /// Scrollbars and their tracks are always shown when the content
+    /// overflows.
+    Always,
+    /// Scrollbars are never shown, and can not be dragged.
+    Never,
+}
+
 /// Denotes which scrollbar, if any, is currently being hovered over
 /// by the mouse.
 #[derive(Debug, Copy, Clone)]
@@ -145,6 +370,8 @@ pub enum BarHeldState {
 ///   to children.
 /// - Call [`handle_scroll`] with all events after handling / propagating them.
 /// - Call [`draw_bars`] to draw the scrollbars.
+/// - If you use [`ScrollSnap`], call [`snap_to_nearest`] after events for which
+///   [`take_snap_request`] returns true.
 ///
 /// Taking a look at the [`Scroll`] source code can be helpful. You can also do scrolling
 /// without wrapping a child in a [`ClipBox`], but you will need to do certain event and
@@ -157,6 +384,8 @@ pub enum BarHeldState {
 /// [`handle_scroll`]: struct.ScrollComponent.html#method.handle_scroll
 /// [`draw_bars`]: #method.draw_bars
 /// [`lifecycle`]: struct.ScrollComponent.html#method.lifecycle
+/// [`snap_to_nearest`]: ScrollComponent::snap_to_nearest
+/// [`take_snap_request`]: ScrollComponent::take_snap_request
 #[derive(Debug, Copy, Clone)]
 pub struct ScrollComponent {
     /// Current opacity for both scrollbars
@@ -169,6 +398,52 @@ pub struct ScrollComponent {
     pub held: BarHeldState,
     /// Which scrollbars are enabled
     pub enabled: ScrollbarsEnabled,
+    /// When the enabled scrollbars are shown
+    pub visibility: ScrollbarVisibility,
+    /// Whether a flick on a touchpad or touch screen keeps scrolling after it ends.
+    ///
+    /// Only wheel events with [`precise_wheel`] set coast, so a mouse wheel that is spun
+    /// quickly doesn't.
+    ///
+    /// [`precise_wheel`]: crate::MouseEvent::precise_wheel
+    pub inertia: bool,
+    /// How quickly inertial scrolling slows down; the velocity decays as
+    /// `exp(-friction * t)`, with `t` in seconds
+    pub friction: f64,
+    /// The velocity of the current flick, in points per second
+    velocity: Vec2,
+    /// When the last wheel event of the current flick arrived
+    last_wheel: Option<Instant>,
+    /// true once the flick has ended and we are scrolling by inertia
+    coasting: bool,
+    /// Whether each wheel gesture only scrolls along the axis it started on
+    pub axis_lock: bool,
+    /// The axis the current wheel gesture is locked to, and when it last scrolled
+    locked_axis: Option<(Axis, Instant)>,
+    /// What happens to wheel events once the content can't scroll any further
+    pub wheel_propagation: WheelPropagation,
+    /// When a wheel event last scrolled the content
+    last_wheel_scroll: Option<Instant>,
+    /// Where the scroll area settles after the user scrolled it
+    pub snap: ScrollSnap,
+    /// ID for the timer which notices that a wheel gesture has ended
+    snap_timer: TimerToken,
+    /// The origin of the viewport that we are settling towards
+    snap_target: Option<Point>,
+    /// The user let go, so the owner should call `snap_to_nearest`
+    snap_requested: bool,
+    /// Whether to scroll while something is dragged close to the edges
+    pub auto_scroll: Option<AutoScroll>,
+    /// Current velocity of auto scrolling, in points per second
+    auto_scroll_velocity: Vec2,
+    /// The last mouse move of a drag that is auto scrolling
+    last_drag: Option<DragMove>,
+    /// Whether auto scrolling moved the viewport since the last drag event
+    auto_scrolled: bool,
+    /// The programmatic scroll that is being animated
+    animation: Option<ScrollAnimation>,
+    /// The touch screen contact that drags the content, if any
+    touch: Option<TouchDrag>,
 }
 
 impl Default for ScrollComponent {
@@ -179,6 +454,26 @@ impl Default for ScrollComponent {
             hovered: BarHoveredState::None,
             held: BarHeldState::None,
             enabled: ScrollbarsEnabled::Both,
+            visibility: ScrollbarVisibility::AutoHide,
+            inertia: true,
+            friction: DEFAULT_SCROLL_FRICTION,
+            velocity: Vec2::ZERO,
+            last_wheel: None,
+            coasting: false,
+            axis_lock: false,
+            locked_axis: None,
+            wheel_propagation: WheelPropagation::Propagate,
+            last_wheel_scroll: None,
+            snap: ScrollSnap::None,
+            snap_timer: TimerToken::INVALID,
+            snap_target: None,
+            snap_requested: false,
+            auto_scroll: None,
+            auto_scroll_velocity: Vec2::ZERO,
+            last_drag: None,
+            auto_scrolled: false,
+            animation: None,
+            touch: None,
         }
     }
 }
@@ -194,15 +489,207 @@ impl ScrollComponent {
         !matches!(self.held, BarHeldState::None)
     }
 
+    /// true while the content keeps scrolling after a flick
+    pub fn is_coasting(&self) -> bool {
+        self.coasting
+    }
+
+    /// true if the scroll area is moving to settle on a [snap point].
+    ///
+    /// [snap point]: ScrollComponent::snap
+    pub fn is_settling(&self) -> bool {
+        self.snap_target.is_some()
+    }
+
+    /// Returns true once after the user let go of the scroll area, when it
+    /// should settle on a [snap point] with [`snap_to_nearest`].
+    ///
+    /// [snap point]: ScrollComponent::snap
+    /// [`snap_to_nearest`]: ScrollComponent::snap_to_nearest
+    pub fn take_snap_request(&mut self) -> bool {
+        std::mem::take(&mut self.snap_requested)
+    }
+
+    /// Starts settling on the [snap point] closest to the current position.
+    ///
+    /// `children` are the layout rects of the children of the content, which
+    /// are the snap points of [`ScrollSnap::Children`].
+    ///
+    /// Returns true if the scroll area needs to move, in which case the caller
+    /// should request an animation frame.
+    ///
+    /// [snap point]: ScrollComponent::snap
+    pub fn snap_to_nearest(&mut self, port: &Viewport, children: &[Rect]) -> bool {
+        let origin = port.view_origin;
+        let mut target = origin;
+        for axis in [Axis::Horizontal, Axis::Vertical] {
+            let view_len = axis.major(port.view_size);
+            let max_offset = axis.major(port.content_size) - view_len;
+            if max_offset > 0.0 {
+                let offset =
+                    self.snap
+                        .nearest(axis, axis.major_pos(origin), view_len, max_offset, children);
+                let (x, y) = axis.pack(offset, axis.minor_pos(target));
+                target = Point::new(x, y);
+            }
+        }
+        self.snap_target = if target == origin { None } else { Some(target) };
+        self.snap_target.is_some()
+    }
+
+    /// Asks the owner to settle on a snap point, if there are any.
+    fn request_snap(&mut self) {
+        if self.snap != ScrollSnap::None {
+            self.snap_requested = true;
+        }
+    }
+
+    /// Moves towards the snap point for `dt` seconds.
+    ///
+    /// Returns true if the viewport moved.
+    fn settle(&mut self, port: &mut Viewport, dt: f64) -> bool {
+        let target = match self.snap_target {
+            Some(target) => target,
+            None => return false,
+        };
+        let origin = port.view_origin;
+        let next = if (target - origin).hypot() < SNAP_EPSILON {
+            target
+        } else {
+            origin.lerp(target, 1.0 - (-SNAP_SPEED * dt).exp())
+        };
+        if next == target {
+            self.snap_target = None;
+        }
+        port.pan_to(next)
+    }
+
+    /// true while the viewport is moving to the target of [`animate_to`].
+    ///
+    /// [`animate_to`]: ScrollComponent::animate_to
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Scrolls to `origin`, for instance to bring a widget into view.
+    ///
+    /// The offset is animated over [`SCROLL_ANIMATION_DURATION`], unless
+    /// [`ANIMATE_SCROLLING`] is `false`, in which case it changes right away.
+    /// The animation is advanced by [`handle_scroll`], and is cancelled when
+    /// the user scrolls.
+    ///
+    /// Returns true if the viewport is going to move.
+    ///
+    /// [`SCROLL_ANIMATION_DURATION`]: crate::theme::SCROLL_ANIMATION_DURATION
+    /// [`ANIMATE_SCROLLING`]: crate::theme::ANIMATE_SCROLLING
+    /// [`handle_scroll`]: ScrollComponent::handle_scroll
+    pub fn animate_to(
+        &mut self,
+        port: &mut Viewport,
+        ctx: &mut EventCtx,
+        origin: Point,
+        env: &Env,
+    ) -> bool {
+        self.stop_inertia();
+        self.snap_target = None;
+        self.animation = None;
+
+        let target = port.clamp_view_origin(origin);
+        let duration = env.get(theme::SCROLL_ANIMATION_DURATION);
+        if !env.get(theme::ANIMATE_SCROLLING) || duration == 0 {
+            if !port.pan_to(target) {
+                return false;
+            }
+            ctx.request_paint();
+            self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+            return true;
+        }
+        if target == port.view_origin {
+            return false;
+        }
+        self.animation = Some(ScrollAnimation {
+            from: port.view_origin,
+            to: target,
+            duration: duration as f64 * 1e-3,
+            elapsed: 0.0,
+        });
+        ctx.request_anim_frame();
+        true
+    }
+
+    /// Advances the animation of [`animate_to`] by `dt` seconds.
+    ///
+    /// Returns true if the viewport moved.
+    ///
+    /// [`animate_to`]: ScrollComponent::animate_to
+    fn animate(&mut self, port: &mut Viewport, dt: f64) -> bool {
+        let animation = match self.animation.as_mut() {
+            Some(animation) => animation,
+            None => return false,
+        };
+        animation.elapsed += dt;
+        let t = (animation.elapsed / animation.duration).min(1.0);
+        let next = animation.from.lerp(animation.to, ease_out(t));
+        if t >= 1.0 {
+            self.animation = None;
+        }
+        port.pan_to(next)
+    }
+
+    /// Returns the mouse move of the ongoing drag, if auto scrolling moved
+    /// the content since it was handled.
+    ///
+    /// The content has moved under the pointer, so the caller should send
+    /// this to its content again, for instance to extend a text selection.
+    pub fn take_auto_scroll_move(&mut self) -> Option<MouseEvent> {
+        if std::mem::take(&mut self.auto_scrolled) {
+            self.last_drag.map(DragMove::to_event)
+        } else {
+            None
+        }
+    }
+
+    /// Stops any inertial scrolling.
+    pub fn stop_inertia(&mut self) {
+        self.velocity = Vec2::ZERO;
+        self.last_wheel = None;
+        self.coasting = false;
+    }
+
     /// Makes the scrollbars visible, and resets the fade timer.
+    ///
+    /// The timer is only requested if the scrollbars [auto-hide].
+    ///
+    /// [auto-hide]: ScrollbarVisibility::AutoHide
     pub fn reset_scrollbar_fade<F>(&mut self, request_timer: F, env: &Env)
     where
         F: FnOnce(Duration) -> TimerToken,
     {
-        self.opacity = env.get(theme::SCROLLBAR_MAX_OPACITY);
-        let fade_delay = env.get(theme::SCROLLBAR_FADE_DELAY);
-        let deadline = Duration::from_millis(fade_delay);
-        self.timer_id = request_timer(deadline);
+        match self.visibility {
+            ScrollbarVisibility::AutoHide => {
+                self.opacity = env.get(theme::SCROLLBAR_MAX_OPACITY);
+                let fade_delay = env.get(theme::SCROLLBAR_FADE_DELAY);
+                let deadline = Duration::from_millis(fade_delay);
+                self.timer_id = request_timer(deadline);
+            }
+            ScrollbarVisibility::Always => {
+                self.opacity = env.get(theme::SCROLLBAR_MAX_OPACITY);
+                self.timer_id = TimerToken::INVALID;
+            }
+            ScrollbarVisibility::Never => {
+                self.opacity = 0.0;
+                self.timer_id = TimerToken::INVALID;
+            }
+        }
+    }
+
+    /// true if the scrollbars are currently drawn.
+    fn are_bars_visible(&self) -> bool {
+        match self.visibility {
+            ScrollbarVisibility::AutoHide => self.opacity > 0.0,
+            ScrollbarVisibility::Always => true,
+            ScrollbarVisibility::Never => false,
+        }
     }
 
     /// Calculates the paint rect of the vertical scrollbar, or `None` if the vertical scrollbar is
@@ -263,25 +750,65 @@ impl ScrollComponent {
         Some(Rect::new(x0, y0, x1, y1) + scroll_offset)
     }
 
+    /// Calculates the rect of the track the scrollbar on `axis` moves along, or `None` if
+    /// that scrollbar is not visible.
+    pub fn calc_track_bounds(&self, axis: Axis, port: &Viewport, env: &Env) -> Option<Rect> {
+        self.calc_bar_bounds(axis, port, env)?;
+
+        let viewport_size = port.view_size;
+        let bar_width = env.get(theme::SCROLLBAR_WIDTH);
+        let bar_pad = env.get(theme::SCROLLBAR_PAD);
+
+        let major_end = if self.enabled.is_enabled(axis.cross()) {
+            axis.major(viewport_size) - bar_pad - bar_width - bar_pad
+        } else {
+            axis.major(viewport_size) - bar_pad
+        };
+        let minor_end = axis.minor(viewport_size) - bar_pad;
+
+        let (x0, y0) = axis.pack(bar_pad, minor_end - bar_width);
+        let (x1, y1) = axis.pack(major_end, minor_end);
+        Some(Rect::new(x0, y0, x1, y1) + port.view_origin.to_vec2())
+    }
+
     /// Draw scroll bars.
     pub fn draw_bars(&self, ctx: &mut PaintCtx, port: &Viewport, env: &Env) {
         let scroll_offset = port.view_origin.to_vec2();
 
-        if self.enabled.is_none() || self.opacity <= 0.0 {
+        if self.enabled.is_none() || !self.are_bars_visible() {
             return;
         }
 
+        let opacity = match self.visibility {
+            ScrollbarVisibility::Always => env.get(theme::SCROLLBAR_MAX_OPACITY),
+            _ => self.opacity,
+        };
         let brush = ctx
             .render_ctx
-            .solid_brush(env.get(theme::SCROLLBAR_COLOR).with_alpha(self.opacity));
-        let border_brush = ctx.render_ctx.solid_brush(
-            env.get(theme::SCROLLBAR_BORDER_COLOR)
-                .with_alpha(self.opacity),
-        );
+            .solid_brush(env.get(theme::SCROLLBAR_COLOR).with_alpha(opacity));
+        let border_brush = ctx
+            .render_ctx
+            .solid_brush(env.get(theme::SCROLLBAR_BORDER_COLOR).with_alpha(opacity));
 
         let radius = env.get(theme::SCROLLBAR_RADIUS);
         let edge_width = env.get(theme::SCROLLBAR_EDGE_WIDTH);
 
+        // Tracks, which are only drawn when the bars don't fade out
+        if self.visibility == ScrollbarVisibility::Always {
+            let track_brush = ctx
+                .render_ctx
+                .solid_brush(env.get(theme::SCROLLBAR_TRACK_COLOR));
+            for axis in [Axis::Vertical, Axis::Horizontal] {
+                if !self.enabled.is_enabled(axis) {
+                    continue;
+                }
+                if let Some(bounds) = self.calc_track_bounds(axis, port, env) {
+                    let rect = (bounds - scroll_offset).to_rounded_rect(radius);
+                    ctx.render_ctx.fill(rect, &track_brush);
+                }
+            }
+        }
+
         // Vertical bar
         if self.enabled.is_enabled(Axis::Vertical) {
             if let Some(bounds) = self.calc_vertical_bar_bounds(port, env) {
@@ -309,7 +836,8 @@ impl ScrollComponent {
     ///
     /// Returns false if the vertical scrollbar is not visible
     pub fn point_hits_vertical_bar(&self, port: &Viewport, pos: Point, env: &Env) -> bool {
-        if !self.enabled.is_enabled(Axis::Vertical) {
+        if !self.enabled.is_enabled(Axis::Vertical) || self.visibility == ScrollbarVisibility::Never
+        {
             return false;
         }
         let viewport_size = port.view_size;
@@ -328,7 +856,9 @@ impl ScrollComponent {
     ///
     /// Returns false if the horizontal scrollbar is not visible
     pub fn point_hits_horizontal_bar(&self, port: &Viewport, pos: Point, env: &Env) -> bool {
-        if !self.enabled.is_enabled(Axis::Horizontal) {
+        if !self.enabled.is_enabled(Axis::Horizontal)
+            || self.visibility == ScrollbarVisibility::Never
+        {
             return false;
         }
         let viewport_size = port.view_size;
@@ -343,10 +873,62 @@ impl ScrollComponent {
         }
     }
 
+    /// Tests if the specified point is on the track of the scrollbar on `axis`, on either side
+    /// of the bar itself.
+    ///
+    /// Returns false if that scrollbar is not currently shown.
+    pub fn point_hits_track(&self, axis: Axis, port: &Viewport, pos: Point, env: &Env) -> bool {
+        if !self.enabled.is_enabled(axis) || !self.are_bars_visible() {
+            return false;
+        }
+        let bar_hit = match axis {
+            Axis::Vertical => self.point_hits_vertical_bar(port, pos, env),
+            Axis::Horizontal => self.point_hits_horizontal_bar(port, pos, env),
+        };
+        if bar_hit {
+            return false;
+        }
+        let scroll_offset = port.view_origin.to_vec2();
+        if let Some(bounds) = self.calc_track_bounds(axis, port, env) {
+            // Stretch hitbox to edge of widget, like the bar's
+            let (x1, y1) = axis.pack(
+                axis.major_pos(Point::new(bounds.x1, bounds.y1)),
+                axis.minor(port.view_size) + axis.minor_vec(scroll_offset),
+            );
+            Rect::new(bounds.x0, bounds.y0, x1, y1).contains(pos)
+        } else {
+            false
+        }
+    }
+
+    /// Scrolls by one page on `axis`, towards `pos` on the scrollbar's track.
+    ///
+    /// Returns true if the viewport moved.
+    fn page_towards(&self, axis: Axis, port: &mut Viewport, pos: Point, env: &Env) -> bool {
+        let bar = match self.calc_bar_bounds(axis, port, env) {
+            Some(bar) => bar,
+            None => return false,
+        };
+        let page = axis.major(port.view_size);
+        let delta = if axis.major_pos(pos) < axis.major_pos(bar.origin()) {
+            -page
+        } else {
+            page
+        };
+        let (x, y) = axis.pack(delta, 0.0);
+        port.pan_by(Vec2::new(x, y))
+    }
+
     /// Checks if the event applies to the scroll behavior, uses it, and marks it handled
     ///
     /// Make sure to call on every event
     pub fn event(&mut self, port: &mut Viewport, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        if let Event::MouseDown(_) | Event::TouchDown(_) = event {
+            // a click or tap stops a flick
+            self.stop_inertia();
+            self.snap_target = None;
+        }
+
         let viewport_size = port.view_size;
         let content_size = port.content_size;
         let scroll_offset = port.view_origin.to_vec2();
@@ -392,6 +974,7 @@ impl ScrollComponent {
                 Event::MouseUp(_) => {
                     self.held = BarHeldState::None;
                     ctx.set_active(false);
+                    self.request_snap();
 
                     if !scrollbar_is_hovered {
                         self.hovered = BarHoveredState::None;
@@ -423,6 +1006,8 @@ impl ScrollComponent {
                 Event::MouseDown(event) => {
                     let pos = event.pos + scroll_offset;
 
+                    // the user takes over from any programmatic scroll
+                    self.animation = None;
                     if self.point_hits_vertical_bar(port, pos, env) {
                         ctx.set_active(true);
                         self.held = BarHeldState::Vertical(
@@ -447,6 +1032,21 @@ impl ScrollComponent {
             }
         } else {
             match event {
+                Event::MouseDown(event) => {
+                    // clicking on a track scrolls by a page
+                    let pos = event.pos + scroll_offset;
+                    for axis in [Axis::Vertical, Axis::Horizontal] {
+                        if self.point_hits_track(axis, port, pos, env) {
+                            if self.page_towards(axis, port, pos, env) {
+                                ctx.request_paint();
+                            }
+                            self.request_snap();
+                            self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                            ctx.set_handled();
+                            break;
+                        }
+                    }
+                }
                 Event::MouseMove(_) => {
                     // if we have just stopped hovering
                     if self.hovered.is_hovered() && !scrollbar_is_hovered {
@@ -463,7 +1063,9 @@ impl ScrollComponent {
                 Event::AnimFrame(interval) => {
                     // Guard by the timer id being invalid, otherwise the scroll bars would fade
                     // immediately if some other widget started animating.
-                    if self.timer_id == TimerToken::INVALID {
+                    if self.timer_id == TimerToken::INVALID
+                        && self.visibility == ScrollbarVisibility::AutoHide
+                    {
                         // Animate scroll bars opacity
                         let diff = 2.0 * (*interval as f64) * 1e-9;
                         self.opacity -= diff;
@@ -485,7 +1087,7 @@ impl ScrollComponent {
         }
     }
 
-    /// Applies mousewheel scrolling if the event has not already been handled
+    /// Applies mousewheel scrolling and touch dragging if the event has not already been handled
     pub fn handle_scroll(
         &mut self,
         port: &mut Viewport,
@@ -493,15 +1095,267 @@ impl ScrollComponent {
         event: &Event,
         env: &Env,
     ) {
+        self.handle_touch(port, ctx, event, env);
         if !ctx.is_handled() {
             if let Event::Wheel(mouse) = event {
-                if port.pan_by(mouse.wheel_delta) {
+                let now = Instant::now();
+                let delta = self.wheel_delta(port, mouse.wheel_delta, mouse.mods, now);
+                self.snap_target = None;
+                self.animation = None;
+                if port.pan_by(delta) {
                     ctx.request_paint();
                     ctx.set_handled();
+                    self.last_wheel_scroll = Some(now);
+                    if self.snap != ScrollSnap::None {
+                        self.snap_timer = ctx.request_timer(SNAP_DELAY);
+                    }
+                    self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                    if self.inertia && mouse.precise_wheel {
+                        self.track_flick(delta, now);
+                        ctx.request_anim_frame();
+                    }
+                } else {
+                    self.stop_inertia();
+                    if self.consumes_unused_wheel(now) {
+                        ctx.set_handled();
+                    }
+                }
+            }
+        }
+
+        match event {
+            Event::MouseMove(mouse) => {
+                if let Some(auto_scroll) = self.auto_scroll {
+                    let velocity = if ctx.has_active() && !self.are_bars_held() {
+                        auto_scroll.velocity(port.view_size.to_rect(), mouse.pos)
+                    } else {
+                        Vec2::ZERO
+                    };
+                    if velocity != Vec2::ZERO && self.auto_scroll_velocity == Vec2::ZERO {
+                        ctx.request_anim_frame();
+                    }
+                    self.auto_scroll_velocity = velocity;
+                    self.last_drag = if velocity == Vec2::ZERO {
+                        None
+                    } else {
+                        Some(DragMove {
+                            pos: mouse.pos,
+                            window_pos: mouse.window_pos,
+                            buttons: mouse.buttons,
+                            mods: mouse.mods,
+                        })
+                    };
+                }
+            }
+            Event::MouseUp(_) => {
+                self.auto_scroll_velocity = Vec2::ZERO;
+                self.last_drag = None;
+            }
+            Event::Timer(id) if *id == self.snap_timer => {
+                self.snap_timer = TimerToken::INVALID;
+                // a flick snaps once it stops coasting
+                if self.velocity == Vec2::ZERO && !self.are_bars_held() {
+                    self.request_snap();
+                }
+                ctx.set_handled();
+            }
+            Event::AnimFrame(interval) => {
+                let dt = (*interval as f64) * 1e-9;
+                if self.velocity != Vec2::ZERO {
+                    if !self.coasting {
+                        // wheel events stop arriving when the fingers are lifted
+                        let ended = self
+                            .last_wheel
+                            .map(|last| last.elapsed() >= FLICK_EVENT_GAP)
+                            .unwrap_or(true);
+                        if ended {
+                            if self.velocity.hypot() >= MIN_FLICK_SPEED {
+                                self.coasting = true;
+                            } else {
+                                self.stop_inertia();
+                            }
+                        }
+                    }
+                    if self.coasting {
+                        if self.coast(port, dt) {
+                            ctx.request_paint();
+                            self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                        }
+                        if !self.coasting {
+                            self.request_snap();
+                        }
+                    }
+                }
+                if self.settle(port, dt) {
+                    ctx.request_paint();
+                    self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                }
+                if self.animate(port, dt) {
+                    ctx.request_paint();
                     self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
                 }
+                if self.auto_scroll_velocity != Vec2::ZERO {
+                    if port.pan_by(self.auto_scroll_velocity * dt) {
+                        ctx.request_paint();
+                        self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                        self.auto_scrolled = true;
+                    } else {
+                        // at the end of the content; the next drag event starts it again
+                        self.auto_scroll_velocity = Vec2::ZERO;
+                    }
+                }
+                if self.velocity != Vec2::ZERO
+                    || self.snap_target.is_some()
+                    || self.auto_scroll_velocity != Vec2::ZERO
+                    || self.animation.is_some()
+                {
+                    ctx.request_anim_frame();
+                }
             }
+            _ => (),
+        }
+    }
+
+    /// Drags the content with a single contact on a touch screen.
+    ///
+    /// The drag starts with a contact that the content didn't handle, once it has moved a few
+    /// points; other contacts are ignored until it is lifted.
+    fn handle_touch(&mut self, port: &mut Viewport, ctx: &mut EventCtx, event: &Event, env: &Env) {
+        match event {
+            Event::TouchDown(touch) if self.touch.is_none() && !ctx.is_handled() => {
+                self.animation = None;
+                self.snap_target = None;
+                self.touch = Some(TouchDrag {
+                    id: touch.id,
+                    start: touch.pos,
+                    last_pos: touch.pos,
+                    last_move: Instant::now(),
+                    dragging: false,
+                    velocity: Vec2::ZERO,
+                });
+            }
+            Event::TouchMove(touch) => {
+                let drag = match &mut self.touch {
+                    Some(drag) if drag.id == touch.id => drag,
+                    _ => return,
+                };
+                if !drag.dragging {
+                    if (touch.pos - drag.start).hypot() < TOUCH_SLOP {
+                        return;
+                    }
+                    drag.dragging = true;
+                    drag.last_pos = touch.pos;
+                    ctx.set_active(true);
+                }
+                let now = Instant::now();
+                // the content follows the finger, so it scrolls the other way
+                let delta = drag.last_pos - touch.pos;
+                let dt = now.duration_since(drag.last_move).as_secs_f64();
+                if dt > 0.0 {
+                    drag.velocity = (drag.velocity + delta / dt) / 2.0;
+                }
+                drag.last_pos = touch.pos;
+                drag.last_move = now;
+                if port.pan_by(delta) {
+                    ctx.request_paint();
+                    self.reset_scrollbar_fade(|d| ctx.request_timer(d), env);
+                }
+                ctx.set_handled();
+            }
+            Event::TouchUp(touch) => {
+                let drag = match self.touch {
+                    Some(drag) if drag.id == touch.id => drag,
+                    _ => return,
+                };
+                self.touch = None;
+                if !drag.dragging {
+                    return;
+                }
+                ctx.set_active(false);
+                let flicked = drag.last_move.elapsed() < FLICK_EVENT_GAP
+                    && drag.velocity.hypot() >= MIN_FLICK_SPEED;
+                if self.inertia && flicked {
+                    self.velocity = drag.velocity;
+                    self.coasting = true;
+                    ctx.request_anim_frame();
+                } else {
+                    self.request_snap();
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether a wheel event that arrived at `now` and couldn't scroll the content should be
+    /// kept from enclosing scroll areas.
+    fn consumes_unused_wheel(&self, now: Instant) -> bool {
+        match self.wheel_propagation {
+            WheelPropagation::Propagate => false,
+            WheelPropagation::Consume => true,
+            WheelPropagation::PropagateAfterDelay(delay) => self
+                .last_wheel_scroll
+                .map(|last| now.duration_since(last) < delay)
+                .unwrap_or(false),
+        }
+    }
+
+    /// The amount to scroll by for a wheel event that arrived at `now`.
+    ///
+    /// Vertical wheel movement scrolls horizontally if shift is held, or if the content can only
+    /// scroll horizontally. With [`axis_lock`] only the gesture's axis is kept.
+    ///
+    /// [`axis_lock`]: ScrollComponent::axis_lock
+    fn wheel_delta(&mut self, port: &Viewport, delta: Vec2, mods: Modifiers, now: Instant) -> Vec2 {
+        let only_horizontal = port.content_size.height <= port.view_size.height
+            && port.content_size.width > port.view_size.width;
+        let mut delta = delta;
+        if delta.x == 0.0 && (mods.shift() || only_horizontal) {
+            delta = Vec2::new(delta.y, 0.0);
+        }
+
+        if self.axis_lock {
+            let axis = match self.locked_axis {
+                Some((axis, last)) if now.duration_since(last) < AXIS_LOCK_TIMEOUT => axis,
+                _ if delta.x.abs() > delta.y.abs() => Axis::Horizontal,
+                _ => Axis::Vertical,
+            };
+            self.locked_axis = Some((axis, now));
+            let (x, y) = axis.pack(axis.major_vec(delta), 0.0);
+            delta = Vec2::new(x, y);
         }
+        delta
+    }
+
+    /// Updates the velocity of the current flick with a wheel event that arrived at `now`.
+    fn track_flick(&mut self, delta: Vec2, now: Instant) {
+        let sample = self
+            .last_wheel
+            .map(|last| now.duration_since(last))
+            .filter(|dt| *dt > Duration::ZERO && *dt < FLICK_EVENT_GAP)
+            .map(|dt| delta / dt.as_secs_f64());
+        self.velocity = match sample {
+            Some(sample) if self.velocity != Vec2::ZERO => (self.velocity + sample) / 2.0,
+            Some(sample) => sample,
+            None => Vec2::ZERO,
+        };
+        self.last_wheel = Some(now);
+        self.coasting = false;
+    }
+
+    /// Scrolls by the current velocity for `dt` seconds, and slows down.
+    ///
+    /// Returns true if the viewport moved.
+    fn coast(&mut self, port: &mut Viewport, dt: f64) -> bool {
+        if dt <= 0.0 {
+            return false;
+        }
+        let moved = port.pan_by(self.velocity * dt);
+        self.velocity *= (-self.friction * dt).exp();
+        if !moved || self.velocity.hypot() < MIN_COAST_SPEED {
+            self.stop_inertia();
+        }
+        moved
     }
 
     /// Perform any necessary action prompted by a lifecycle event
@@ -743,6 +1597,345 @@ mod tests {
         );
     }
 
+    #[test]
+    fn scrollbar_track_layout() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.enabled = ScrollbarsEnabled::Vertical;
+        let viewport = Viewport {
+            content_size: Size::new(100.0, 100.0),
+            view_origin: (0.0, 25.0).into(),
+            view_size: (100.0, 50.0).into(),
+        };
+
+        let track_rect = scroll_component
+            .calc_track_bounds(Axis::Vertical, &viewport, &test_env())
+            .unwrap();
+        let scrollbar_rect = scroll_component
+            .calc_vertical_bar_bounds(&viewport, &test_env())
+            .unwrap();
+
+        assert!(
+            rect_contains(track_rect, scrollbar_rect),
+            "track should contain the scrollbar"
+        );
+        assert_eq!(track_rect, Rect::new(86.0, 28.0, 97.0, 72.0));
+    }
+
+    #[test]
+    fn scrollbar_track_hit_test() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.enabled = ScrollbarsEnabled::Vertical;
+        scroll_component.visibility = ScrollbarVisibility::Always;
+        let viewport = Viewport {
+            content_size: Size::new(100.0, 100.0),
+            view_origin: (0.0, 25.0).into(),
+            view_size: (100.0, 50.0).into(),
+        };
+        let env = test_env();
+
+        let hits = |component: &ScrollComponent, pos: Point| {
+            component.point_hits_track(Axis::Vertical, &viewport, pos, &env)
+        };
+        assert!(hits(&scroll_component, Point::new(90.0, 30.0)));
+        assert!(hits(&scroll_component, Point::new(99.0, 70.0)));
+        assert!(
+            !hits(&scroll_component, Point::new(90.0, 50.0)),
+            "the scrollbar itself is not part of the track"
+        );
+        assert!(!hits(&scroll_component, Point::new(50.0, 30.0)));
+
+        scroll_component.visibility = ScrollbarVisibility::AutoHide;
+        scroll_component.opacity = 0.0;
+        assert!(
+            !hits(&scroll_component, Point::new(90.0, 30.0)),
+            "hidden tracks should not be hit"
+        );
+    }
+
+    #[test]
+    fn scrollbar_track_paging() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.enabled = ScrollbarsEnabled::Vertical;
+        let mut viewport = Viewport {
+            content_size: Size::new(100.0, 200.0),
+            view_origin: (0.0, 75.0).into(),
+            view_size: (100.0, 50.0).into(),
+        };
+        let env = test_env();
+
+        assert!(scroll_component.page_towards(
+            Axis::Vertical,
+            &mut viewport,
+            Point::new(90.0, 120.0),
+            &env
+        ));
+        assert_eq!(viewport.view_origin, Point::new(0.0, 125.0));
+
+        assert!(scroll_component.page_towards(
+            Axis::Vertical,
+            &mut viewport,
+            Point::new(90.0, 126.0),
+            &env
+        ));
+        assert_eq!(viewport.view_origin, Point::new(0.0, 75.0));
+    }
+
+    #[test]
+    fn scrollbar_never_visible() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.visibility = ScrollbarVisibility::Never;
+        let viewport = Viewport {
+            content_size: Size::new(100.0, 100.0),
+            view_origin: (0.0, 25.0).into(),
+            view_size: (100.0, 50.0).into(),
+        };
+        let env = test_env();
+
+        let bar = scroll_component
+            .calc_vertical_bar_bounds(&viewport, &env)
+            .unwrap();
+        assert!(!scroll_component.point_hits_vertical_bar(&viewport, bar.center(), &env));
+
+        scroll_component.reset_scrollbar_fade(|_| panic!("no fade timer"), &env);
+        assert_eq!(scroll_component.opacity, 0.0);
+    }
+
+    #[test]
+    fn flick_velocity() {
+        let mut scroll_component = ScrollComponent::new();
+        let start = Instant::now();
+
+        scroll_component.track_flick(Vec2::new(0.0, 10.0), start);
+        assert_eq!(scroll_component.velocity, Vec2::ZERO);
+
+        scroll_component.track_flick(Vec2::new(0.0, 10.0), start + Duration::from_millis(10));
+        assert!(approx_eq!(
+            f64,
+            scroll_component.velocity.y,
+            1000.0,
+            epsilon = 1e-9
+        ));
+
+        // a pause ends the flick
+        scroll_component.track_flick(Vec2::new(0.0, 10.0), start + Duration::from_millis(100));
+        assert_eq!(scroll_component.velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn coasting_slows_down() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.velocity = Vec2::new(0.0, 1000.0);
+        scroll_component.coasting = true;
+        let mut viewport = Viewport {
+            content_size: Size::new(100.0, 10000.0),
+            view_origin: (0.0, 0.0).into(),
+            view_size: (100.0, 100.0).into(),
+        };
+
+        assert!(scroll_component.coast(&mut viewport, 0.1));
+        assert!(approx_eq!(
+            f64,
+            viewport.view_origin.y,
+            100.0,
+            epsilon = 1e-9
+        ));
+        assert!(approx_eq!(
+            f64,
+            scroll_component.velocity.y,
+            1000.0 * (-DEFAULT_SCROLL_FRICTION * 0.1).exp(),
+            epsilon = 1e-9
+        ));
+        assert!(scroll_component.is_coasting());
+    }
+
+    #[test]
+    fn coasting_stops_at_edge() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.velocity = Vec2::new(0.0, 1000.0);
+        scroll_component.coasting = true;
+        let mut viewport = Viewport {
+            content_size: Size::new(100.0, 1000.0),
+            view_origin: (0.0, 850.0).into(),
+            view_size: (100.0, 100.0).into(),
+        };
+
+        assert!(scroll_component.coast(&mut viewport, 0.1));
+        assert!(approx_eq!(
+            f64,
+            viewport.view_origin.y,
+            900.0,
+            epsilon = 1e-9
+        ));
+        assert!(!scroll_component.coast(&mut viewport, 0.1));
+        assert!(!scroll_component.is_coasting());
+        assert_eq!(scroll_component.velocity, Vec2::ZERO);
+    }
+
+    #[test]
+    fn shift_wheel_scrolls_horizontally() {
+        let mut scroll_component = ScrollComponent::new();
+        let now = Instant::now();
+        let viewport = Viewport {
+            content_size: Size::new(1000.0, 1000.0),
+            view_origin: (0.0, 0.0).into(),
+            view_size: (100.0, 100.0).into(),
+        };
+        let delta = Vec2::new(0.0, 10.0);
+
+        let no_mods = Modifiers::empty();
+        assert_eq!(
+            scroll_component.wheel_delta(&viewport, delta, no_mods, now),
+            delta
+        );
+        assert_eq!(
+            scroll_component.wheel_delta(&viewport, delta, Modifiers::SHIFT, now),
+            Vec2::new(10.0, 0.0)
+        );
+
+        let wide = Viewport {
+            content_size: Size::new(1000.0, 100.0),
+            ..viewport
+        };
+        assert_eq!(
+            scroll_component.wheel_delta(&wide, delta, no_mods, now),
+            Vec2::new(10.0, 0.0),
+            "content that only scrolls horizontally should take vertical wheel movement"
+        );
+    }
+
+    #[test]
+    fn wheel_axis_lock() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.axis_lock = true;
+        let start = Instant::now();
+        let viewport = Viewport {
+            content_size: Size::new(1000.0, 1000.0),
+            view_origin: (0.0, 0.0).into(),
+            view_size: (100.0, 100.0).into(),
+        };
+        let mods = Modifiers::empty();
+
+        let mut delta_at = |delta: Vec2, millis: u64| {
+            let now = start + Duration::from_millis(millis);
+            scroll_component.wheel_delta(&viewport, delta, mods, now)
+        };
+        assert_eq!(delta_at(Vec2::new(2.0, 10.0), 0), Vec2::new(0.0, 10.0));
+        // the gesture stays vertical, even if it drifts sideways
+        assert_eq!(delta_at(Vec2::new(8.0, 3.0), 10), Vec2::new(0.0, 3.0));
+        // a new gesture can pick another axis
+        assert_eq!(delta_at(Vec2::new(8.0, 3.0), 1000), Vec2::new(8.0, 0.0));
+    }
+
+    #[test]
+    fn wheel_propagation_after_delay() {
+        let mut scroll_component = ScrollComponent::new();
+        let start = Instant::now();
+        let at = |millis: u64| start + Duration::from_millis(millis);
+        assert!(!scroll_component.consumes_unused_wheel(at(0)));
+
+        scroll_component.wheel_propagation = WheelPropagation::Consume;
+        assert!(scroll_component.consumes_unused_wheel(at(0)));
+
+        scroll_component.wheel_propagation =
+            WheelPropagation::PropagateAfterDelay(Duration::from_millis(500));
+        // never scrolled, so there is nothing to wait for
+        assert!(!scroll_component.consumes_unused_wheel(at(0)));
+        scroll_component.last_wheel_scroll = Some(at(100));
+        assert!(scroll_component.consumes_unused_wheel(at(400)));
+        assert!(!scroll_component.consumes_unused_wheel(at(700)));
+    }
+
+    #[test]
+    fn auto_scroll_ramp() {
+        let auto_scroll = AutoScroll {
+            margin: 20.0,
+            max_speed: 100.0,
+            ramp: 1.0,
+        };
+        let view = Rect::new(0.0, 0.0, 200.0, 200.0);
+        assert_eq!(
+            auto_scroll.velocity(view, (100.0, 100.0).into()),
+            Vec2::ZERO
+        );
+        assert_eq!(
+            auto_scroll.velocity(view, (100.0, 190.0).into()),
+            Vec2::new(0.0, 50.0)
+        );
+        // the speed is capped outside the viewport
+        assert_eq!(
+            auto_scroll.velocity(view, (-50.0, 100.0).into()),
+            Vec2::new(-100.0, 0.0)
+        );
+
+        let auto_scroll = AutoScroll {
+            ramp: 2.0,
+            ..auto_scroll
+        };
+        assert_eq!(
+            auto_scroll.velocity(view, (190.0, 5.0).into()),
+            Vec2::new(25.0, -56.25)
+        );
+    }
+
+    #[test]
+    fn snap_points() {
+        let rows = ScrollSnap::Interval(30.0);
+        let nearest_row = |offset| rows.nearest(Axis::Vertical, offset, 100.0, 1000.0, &[]);
+        assert_eq!(nearest_row(40.0), 30.0);
+        assert_eq!(nearest_row(50.0), 60.0);
+        // the end of the content is always reachable
+        assert_eq!(nearest_row(995.0), 1000.0);
+
+        assert_eq!(
+            ScrollSnap::Page.nearest(Axis::Vertical, 160.0, 100.0, 1000.0, &[]),
+            200.0
+        );
+
+        let children = [
+            Rect::new(0.0, 0.0, 100.0, 120.0),
+            Rect::new(0.0, 120.0, 100.0, 300.0),
+            Rect::new(0.0, 300.0, 100.0, 340.0),
+        ];
+        let nearest_child =
+            |offset| ScrollSnap::Children.nearest(Axis::Vertical, offset, 100.0, 1000.0, &children);
+        assert_eq!(nearest_child(200.0), 120.0);
+        assert_eq!(nearest_child(-20.0), 0.0);
+        // without children the scroll area stays where it is
+        assert_eq!(
+            ScrollSnap::Children.nearest(Axis::Vertical, 200.0, 100.0, 1000.0, &[]),
+            200.0
+        );
+
+        assert_eq!(
+            ScrollSnap::None.nearest(Axis::Vertical, 42.0, 100.0, 1000.0, &[]),
+            42.0
+        );
+    }
+
+    #[test]
+    fn snap_settles_on_nearest_point() {
+        let mut scroll_component = ScrollComponent::new();
+        scroll_component.snap = ScrollSnap::Interval(100.0);
+        let mut viewport = Viewport {
+            content_size: Size::new(100.0, 1000.0),
+            view_origin: (0.0, 140.0).into(),
+            view_size: (100.0, 100.0).into(),
+        };
+
+        assert!(scroll_component.snap_to_nearest(&viewport, &[]));
+        assert!(scroll_component.is_settling());
+        let mut frames = 0;
+        while scroll_component.settle(&mut viewport, 1.0 / 60.0) {
+            frames += 1;
+            assert!(frames < 100);
+        }
+        assert!(!scroll_component.is_settling());
+        assert_eq!(viewport.view_origin, Point::new(0.0, 100.0));
+
+        // already on a snap point
+        assert!(!scroll_component.snap_to_nearest(&viewport, &[]));
+    }
+
     fn rect_contains(outer: Rect, inner: Rect) -> bool {
         outer.union(inner) == outer
     }
@@ -752,5 +1945,6 @@ mod tests {
             .adding(theme::SCROLLBAR_WIDTH, TEST_SCROLLBAR_WIDTH)
             .adding(theme::SCROLLBAR_PAD, TEST_SCROLLBAR_PAD)
             .adding(theme::SCROLLBAR_MIN_SIZE, TEST_SCROLLBAR_MIN_SIZE)
+            .adding(theme::SCROLLBAR_MAX_OPACITY, 0.7)
     }
 }
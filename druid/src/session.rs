@@ -0,0 +1,367 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Session autosave and crash recovery storage.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+const STATE_FILE: &str = "autosave.bin";
+const MARKER_PREFIX: &str = "session-";
+const MARKER_SUFFIX: &str = ".lock";
+
+/// How many keep-alive intervals a marker can miss before its session counts
+/// as ended.
+const MISSED_INTERVALS: u32 = 3;
+
+/// On-disk storage for autosaved application state.
+///
+/// This is used by [`AppLauncher::autosave`] to persist the state returned from
+/// [`AppDelegate::save_state`], and to detect whether the previous session
+/// ended unexpectedly. It can also be used directly.
+///
+/// While a session is running, it keeps a marker file next to the saved state,
+/// and refreshes it every keep-alive interval. A marker that is left behind and
+/// stops being refreshed belongs to a session that did not exit cleanly, while
+/// a fresh one belongs to another instance of the app that is still running.
+///
+/// [`AppLauncher::autosave`]: crate::AppLauncher::autosave
+/// [`AppDelegate::save_state`]: crate::AppDelegate::save_state
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    /// Create a store in a directory named `app_id` inside the platform's
+    /// per-user data directory.
+    ///
+    /// This is `%APPDATA%` on Windows, `~/Library/Application Support` on macOS,
+    /// and `$XDG_DATA_HOME` (or `~/.local/share`) elsewhere.
+    ///
+    /// Returns `None` if the data directory cannot be determined.
+    pub fn new(app_id: impl AsRef<str>) -> Option<SessionStore> {
+        platform_data_dir().map(|dir| SessionStore::with_dir(dir.join(app_id.as_ref())))
+    }
+
+    /// Create a store in the provided directory.
+    pub fn with_dir(dir: impl Into<PathBuf>) -> SessionStore {
+        SessionStore { dir: dir.into() }
+    }
+
+    /// The directory this store writes to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Save `state`, replacing any previously saved state.
+    pub fn save(&self, state: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        // write to a temporary file first, so a crash mid-write
+        // doesn't clobber the last good state.
+        let tmp = self.dir.join(format!("{}.tmp", STATE_FILE));
+        fs::write(&tmp, state)?;
+        fs::rename(&tmp, self.dir.join(STATE_FILE))
+    }
+
+    /// Load the most recently saved state, if there is one.
+    pub fn load(&self) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.dir.join(STATE_FILE)) {
+            Ok(state) => Ok(Some(state)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remove any saved state.
+    pub fn clear(&self) -> io::Result<()> {
+        remove_if_exists(&self.dir.join(STATE_FILE))
+    }
+
+    /// Mark the start of a session, which will call [`keep_alive`] at least
+    /// every `interval`.
+    ///
+    /// Returns `true` if a previous session did not call [`end_session`], and
+    /// hasn't been kept alive for a few intervals. Sessions of other instances
+    /// that are still running don't count.
+    ///
+    /// [`keep_alive`]: #method.keep_alive
+    /// [`end_session`]: #method.end_session
+    pub fn begin_session(&self, interval: Duration) -> io::Result<bool> {
+        fs::create_dir_all(&self.dir)?;
+        let now = SystemTime::now();
+        let mut unclean = false;
+        for marker in self.markers()? {
+            let modified = fs::metadata(&marker)?.modified()?;
+            // A marker from the future is as good as fresh.
+            let stale = now
+                .duration_since(modified)
+                .map_or(false, |age| age > interval * MISSED_INTERVALS);
+            if stale {
+                unclean = true;
+                remove_if_exists(&marker)?;
+            }
+        }
+        self.keep_alive()?;
+        Ok(unclean)
+    }
+
+    /// Refresh the marker of the running session.
+    pub fn keep_alive(&self) -> io::Result<()> {
+        fs::write(self.marker(), std::process::id().to_string())
+    }
+
+    /// Mark the clean end of a session, removing its marker.
+    ///
+    /// The saved state is removed too, unless another instance's session is
+    /// still running.
+    pub fn end_session(&self) -> io::Result<()> {
+        remove_if_exists(&self.marker())?;
+        if self.markers()?.is_empty() {
+            self.clear()?;
+        }
+        Ok(())
+    }
+
+    /// The marker of this process's session.
+    fn marker(&self) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}{}",
+            MARKER_PREFIX,
+            std::process::id(),
+            MARKER_SUFFIX
+        ))
+    }
+
+    /// The markers of the other sessions.
+    fn markers(&self) -> io::Result<Vec<PathBuf>> {
+        let ours = self.marker();
+        let mut markers = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_marker = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map_or(false, |n| {
+                    n.starts_with(MARKER_PREFIX) && n.ends_with(MARKER_SUFFIX)
+                });
+            if is_marker && path != ours {
+                markers.push(path);
+            }
+        }
+        Ok(markers)
+    }
+}
+
+/// Writes autosaved state on a background thread, so that the UI doesn't wait
+/// for the disk, and keeps the session alive in the meantime.
+pub(crate) struct SessionWriter {
+    sender: Sender<WriterMessage>,
+    thread: JoinHandle<()>,
+}
+
+enum WriterMessage {
+    Save(Vec<u8>),
+    Finish,
+}
+
+impl SessionWriter {
+    /// Start writing to `store`, whose session has begun with `interval`.
+    pub(crate) fn spawn(store: SessionStore, interval: Duration) -> io::Result<SessionWriter> {
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("druid-autosave".into())
+            .spawn(move || loop {
+                match receiver.recv_timeout(interval) {
+                    Ok(WriterMessage::Save(mut state)) => {
+                        // Only the newest of the pending states matters.
+                        let mut finish = false;
+                        for message in receiver.try_iter() {
+                            match message {
+                                WriterMessage::Save(newer) => state = newer,
+                                WriterMessage::Finish => finish = true,
+                            }
+                        }
+                        if let Err(e) = store.save(&state) {
+                            tracing::error!("failed to autosave to {:?}: {}", store.dir(), e);
+                        }
+                        if finish {
+                            end_session(&store);
+                            return;
+                        }
+                    }
+                    Ok(WriterMessage::Finish) | Err(RecvTimeoutError::Disconnected) => {
+                        end_session(&store);
+                        return;
+                    }
+                    Err(RecvTimeoutError::Timeout) => (),
+                }
+                if let Err(e) = store.keep_alive() {
+                    tracing::error!(
+                        "failed to keep the session in {:?} alive: {}",
+                        store.dir(),
+                        e
+                    );
+                }
+            })?;
+        Ok(SessionWriter { sender, thread })
+    }
+
+    /// A sender for the states to save.
+    pub(crate) fn saver(&self) -> StateSaver {
+        StateSaver(self.sender.clone())
+    }
+
+    /// End the session cleanly, after writing any pending state.
+    pub(crate) fn finish(self) {
+        // If the thread is gone, there's nothing left to finish.
+        let _ = self.sender.send(WriterMessage::Finish);
+        if self.thread.join().is_err() {
+            tracing::error!("the autosave thread panicked");
+        }
+    }
+}
+
+/// Sends states to a [`SessionWriter`].
+#[derive(Clone)]
+pub(crate) struct StateSaver(Sender<WriterMessage>);
+
+impl StateSaver {
+    /// Save `state` in the background.
+    pub(crate) fn save(&self, state: Vec<u8>) {
+        if self.0.send(WriterMessage::Save(state)).is_err() {
+            tracing::warn!("the session has ended; not autosaving");
+        }
+    }
+}
+
+fn end_session(store: &SessionStore) {
+    if let Err(e) = store.end_session() {
+        tracing::error!("failed to end session in {:?}: {}", store.dir(), e);
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_data_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_data_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| {
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn platform_data_dir() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(20);
+
+    /// Leave a marker as if another instance with `pid` were running.
+    fn other_instance(store: &SessionStore, pid: u32) -> PathBuf {
+        let marker = store
+            .dir()
+            .join(format!("{}{}{}", MARKER_PREFIX, pid, MARKER_SUFFIX));
+        fs::write(&marker, pid.to_string()).unwrap();
+        marker
+    }
+
+    #[test]
+    fn session_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::with_dir(dir.path());
+
+        assert!(!store.begin_session(INTERVAL).unwrap());
+        assert_eq!(store.load().unwrap(), None);
+        store.save(b"state").unwrap();
+        assert_eq!(store.load().unwrap().as_deref(), Some(&b"state"[..]));
+
+        // simulate a crash: a marker that isn't kept alive goes stale.
+        let crashed = other_instance(&store, u32::MAX);
+        thread::sleep(INTERVAL * (MISSED_INTERVALS + 1));
+        assert!(store.begin_session(INTERVAL).unwrap());
+        assert!(!crashed.exists());
+        assert_eq!(store.load().unwrap().as_deref(), Some(&b"state"[..]));
+
+        store.end_session().unwrap();
+        assert!(!store.begin_session(INTERVAL).unwrap());
+        assert_eq!(store.load().unwrap(), None);
+    }
+
+    #[test]
+    fn running_instance_is_not_a_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::with_dir(dir.path());
+        fs::create_dir_all(store.dir()).unwrap();
+        let running = other_instance(&store, u32::MAX);
+
+        assert!(!store.begin_session(Duration::from_secs(60)).unwrap());
+        store.save(b"state").unwrap();
+        // the other instance may still want the state.
+        store.end_session().unwrap();
+        assert!(running.exists());
+        assert_eq!(store.load().unwrap().as_deref(), Some(&b"state"[..]));
+    }
+
+    #[test]
+    fn writer_saves_in_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::with_dir(dir.path());
+        store.begin_session(INTERVAL).unwrap();
+        let running = other_instance(&store, u32::MAX);
+
+        let writer = SessionWriter::spawn(store.clone(), INTERVAL).unwrap();
+        let saver = writer.saver();
+        saver.save(b"first".to_vec());
+        saver.save(b"second".to_vec());
+        writer.finish();
+
+        // the state is kept for the other instance, and our marker is gone.
+        assert_eq!(store.load().unwrap().as_deref(), Some(&b"second"[..]));
+        assert_eq!(store.markers().unwrap(), vec![running]);
+        assert!(!store.marker().exists());
+        // saving after the end is harmless.
+        saver.save(b"third".to_vec());
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keyboard shortcuts that are independent of menus.
+
+use crate::{Command, HotKey, KeyEvent};
+
+/// A set of keyboard shortcuts, each of which submits a [`Command`].
+///
+/// Shortcuts work without a menu, so they can be used on platforms and in
+/// windows that have none, and for commands that shouldn't be in a menu.
+///
+/// Maps can be installed at three levels, from outermost to innermost:
+///
+/// - for the whole application, with [`AppLauncher::shortcuts`];
+/// - for one window, with [`WindowDesc::shortcuts`];
+/// - for a part of a window, with a [`ShortcutScope`], whose shortcuts only
+///   apply while the focus is inside it.
+///
+/// A key press is first delivered to the widgets as usual; shortcuts only
+/// apply to key presses that no widget handled, so that a focused text box
+/// keeps its own editing keys. The innermost map that binds the key wins.
+/// A map can also [`disable`] a key, which hides the bindings of that key in
+/// the maps around it.
+///
+/// Commands without a target are sent to the window the key was pressed in.
+///
+/// The maps can be replaced while the application runs with the
+/// [`SET_SHORTCUTS`] command.
+///
+/// [`AppLauncher::shortcuts`]: crate::AppLauncher::shortcuts
+/// [`WindowDesc::shortcuts`]: crate::WindowDesc::shortcuts
+/// [`ShortcutScope`]: crate::widget::ShortcutScope
+/// [`disable`]: ShortcutMap::disable
+/// [`SET_SHORTCUTS`]: crate::commands::SET_SHORTCUTS
+#[derive(Debug, Clone, Default)]
+pub struct ShortcutMap {
+    bindings: Vec<(HotKey, Option<Command>)>,
+}
+
+impl ShortcutMap {
+    /// Create an empty `ShortcutMap`.
+    pub fn new() -> ShortcutMap {
+        ShortcutMap::default()
+    }
+
+    /// Builder-style method to bind `hotkey` to `command`.
+    ///
+    /// This replaces any previous binding of `hotkey`.
+    pub fn bind(mut self, hotkey: HotKey, command: impl Into<Command>) -> Self {
+        self.set(hotkey, command);
+        self
+    }
+
+    /// Builder-style method to disable `hotkey`, so that it doesn't trigger
+    /// the bindings of the maps around this one.
+    pub fn disable(mut self, hotkey: HotKey) -> Self {
+        self.insert(hotkey, None);
+        self
+    }
+
+    /// Bind `hotkey` to `command`, replacing any previous binding of `hotkey`.
+    pub fn set(&mut self, hotkey: HotKey, command: impl Into<Command>) {
+        self.insert(hotkey, Some(command.into()));
+    }
+
+    /// Remove the binding of `hotkey`, if there is one.
+    ///
+    /// This also removes a binding made with [`disable`].
+    ///
+    /// [`disable`]: ShortcutMap::disable
+    pub fn unbind(&mut self, hotkey: &HotKey) {
+        self.bindings.retain(|(key, _)| key != hotkey);
+    }
+
+    /// Returns `true` if there are no bindings.
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// The command that `event` triggers, if any.
+    pub fn command_for(&self, event: &KeyEvent) -> Option<&Command> {
+        self.lookup(event).flatten()
+    }
+
+    /// Find the binding for `event`.
+    ///
+    /// Returns `Some(None)` if the key is disabled in this map.
+    pub(crate) fn lookup(&self, event: &KeyEvent) -> Option<Option<&Command>> {
+        self.bindings
+            .iter()
+            .find(|(hotkey, _)| hotkey.matches(event))
+            .map(|(_, command)| command.as_ref())
+    }
+
+    fn insert(&mut self, hotkey: HotKey, command: Option<Command>) {
+        match self.bindings.iter_mut().find(|(key, _)| *key == hotkey) {
+            Some(binding) => binding.1 = command,
+            None => self.bindings.push((hotkey, command)),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Progress reporting and cancellation for background work.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::commands::UPDATE_TASK_PROGRESS;
+use crate::{ArcStr, Command, Data, ExtEventSink, Target};
+
+/// The state of a background task, as seen by a [`TaskProgress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum TaskStatus {
+    /// The task is running.
+    Running,
+    /// Cancellation was requested, but the task has not yet stopped.
+    Cancelling,
+    /// The task stopped after being cancelled.
+    Cancelled,
+    /// The task finished.
+    Finished,
+}
+
+/// The progress of a background task.
+///
+/// This lives in your application's data, where widgets such as
+/// [`TaskProgressBar`] can display it, while the task itself reports its
+/// progress through the paired [`ProgressReporter`]. Each report is delivered
+/// to the UI as a command, which [`TaskProgressBar`] (or your own widget,
+/// via [`update_from_command`]) applies to the data.
+///
+/// # Examples
+///
+/// ```no_run
+/// use druid::{ExtEventSink, TaskProgress};
+///
+/// fn start_download(sink: ExtEventSink) -> TaskProgress {
+///     let (progress, mut reporter) = TaskProgress::new(sink);
+///     std::thread::spawn(move || {
+///         for i in 0..100 {
+///             if reporter.is_cancelled() {
+///                 return;
+///             }
+///             reporter.report(i as f64 / 100.0, format!("Downloading part {}", i));
+///             // ...
+///         }
+///         reporter.finish();
+///     });
+///     progress
+/// }
+/// ```
+///
+/// [`TaskProgressBar`]: crate::widget::TaskProgressBar
+/// [`update_from_command`]: TaskProgress::update_from_command
+#[derive(Debug, Clone)]
+pub struct TaskProgress {
+    // identifies the task, and is shared with the reporter.
+    cancelled: Arc<AtomicBool>,
+    fraction: Option<f64>,
+    message: ArcStr,
+    status: TaskStatus,
+}
+
+/// The handle a background task uses to report its progress, and to check
+/// whether it has been cancelled.
+///
+/// Dropping the reporter marks the task as [`Finished`], or as [`Cancelled`]
+/// if cancellation was requested.
+///
+/// [`Finished`]: TaskStatus::Finished
+/// [`Cancelled`]: TaskStatus::Cancelled
+pub struct ProgressReporter {
+    progress: TaskProgress,
+    sink: ExtEventSink,
+}
+
+impl TaskProgress {
+    /// Create the progress for a new task, along with the [`ProgressReporter`]
+    /// that the task should use to report to it.
+    ///
+    /// Updates are submitted through `sink`, to [`Target::Global`].
+    pub fn new(sink: ExtEventSink) -> (TaskProgress, ProgressReporter) {
+        let progress = TaskProgress {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            fraction: None,
+            message: "".into(),
+            status: TaskStatus::Running,
+        };
+        let reporter = ProgressReporter {
+            progress: progress.clone(),
+            sink,
+        };
+        (progress, reporter)
+    }
+
+    /// The fraction of the task that is complete, in the range `0.0..=1.0`,
+    /// or `None` if this is not known.
+    pub fn fraction(&self) -> Option<f64> {
+        self.fraction
+    }
+
+    /// The most recent message reported by the task.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The task's [`TaskStatus`].
+    pub fn status(&self) -> TaskStatus {
+        self.status
+    }
+
+    /// Returns `true` if the task is running, and has not been cancelled.
+    pub fn is_running(&self) -> bool {
+        self.status == TaskStatus::Running
+    }
+
+    /// Request that the task be cancelled.
+    ///
+    /// The task is responsible for checking [`ProgressReporter::is_cancelled`]
+    /// and stopping; until it does, the status is [`TaskStatus::Cancelling`].
+    pub fn cancel(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if self.status == TaskStatus::Running {
+            self.status = TaskStatus::Cancelling;
+        }
+    }
+
+    /// If `cmd` is a progress update for this task, apply it.
+    ///
+    /// Returns `true` if the command was an update for this task.
+    pub fn update_from_command(&mut self, cmd: &Command) -> bool {
+        match cmd.get(UPDATE_TASK_PROGRESS) {
+            Some(update) if Arc::ptr_eq(&update.cancelled, &self.cancelled) => {
+                let cancelling = self.status == TaskStatus::Cancelling;
+                *self = update.clone();
+                // an update sent before the task saw the cancellation
+                if cancelling && self.status == TaskStatus::Running {
+                    self.status = TaskStatus::Cancelling;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Data for TaskProgress {
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+            && self.fraction.same(&other.fraction)
+            && self.message.same(&other.message)
+            && self.status == other.status
+    }
+}
+
+impl ProgressReporter {
+    /// Returns `true` if cancellation of the task has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.progress.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Report the fraction of the task that is complete.
+    pub fn set_fraction(&mut self, fraction: impl Into<Option<f64>>) {
+        self.progress.fraction = fraction.into().map(|f| f.clamp(0.0, 1.0));
+        self.send();
+    }
+
+    /// Report a message describing what the task is doing.
+    pub fn set_message(&mut self, message: impl Into<ArcStr>) {
+        self.progress.message = message.into();
+        self.send();
+    }
+
+    /// Report both the fraction complete and a message.
+    pub fn report(&mut self, fraction: impl Into<Option<f64>>, message: impl Into<ArcStr>) {
+        self.progress.fraction = fraction.into().map(|f| f.clamp(0.0, 1.0));
+        self.progress.message = message.into();
+        self.send();
+    }
+
+    /// Mark the task as finished.
+    ///
+    /// This is equivalent to dropping the reporter.
+    pub fn finish(self) {}
+
+    fn send(&mut self) {
+        if self.is_cancelled() && self.progress.status == TaskStatus::Running {
+            self.progress.status = TaskStatus::Cancelling;
+        }
+        // if this fails the application has quit, and there's no one to tell.
+        let _ = self.sink.submit_command(
+            UPDATE_TASK_PROGRESS,
+            Box::new(self.progress.clone()),
+            Target::Global,
+        );
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        self.progress.status = if self.is_cancelled() {
+            TaskStatus::Cancelled
+        } else {
+            TaskStatus::Finished
+        };
+        self.send();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext_event::ExtEventHost;
+
+    #[test]
+    fn report_and_cancel() {
+        let mut host = ExtEventHost::new();
+        let (mut progress, mut reporter) = TaskProgress::new(host.make_sink());
+        let (mut other, _other_reporter) = TaskProgress::new(host.make_sink());
+
+        reporter.report(0.5, "halfway");
+        let cmd = host.recv().unwrap();
+        assert!(!other.update_from_command(&cmd));
+        assert!(progress.update_from_command(&cmd));
+        assert_eq!(progress.fraction(), Some(0.5));
+        assert_eq!(progress.message(), "halfway");
+        assert!(progress.is_running());
+
+        // an update that was in flight when the task was cancelled
+        reporter.set_fraction(0.6);
+        progress.cancel();
+        assert!(reporter.is_cancelled());
+        assert!(progress.update_from_command(&host.recv().unwrap()));
+        assert_eq!(progress.status(), TaskStatus::Cancelling);
+
+        drop(reporter);
+        assert!(progress.update_from_command(&host.recv().unwrap()));
+        assert_eq!(progress.status(), TaskStatus::Cancelled);
+        assert!(!other.update_from_command(&Command::from(crate::commands::QUIT_APP)));
+        other.cancel();
+        assert_eq!(other.status(), TaskStatus::Cancelling);
+    }
+}
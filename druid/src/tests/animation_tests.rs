@@ -0,0 +1,173 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for animated widgets and view transitions.
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use test_env_log::test;
+
+use super::*;
+use crate::animation::{Easing, SlideDirection, Transition};
+
+const SET_F64: Selector<f64> = Selector::new("druid-test.set-f64");
+const SET_BOOL: Selector<bool> = Selector::new("druid-test.set-bool");
+const SET_U32: Selector<u32> = Selector::new("druid-test.set-u32");
+
+const HALF: u64 = 100_000_000;
+
+/// Wrap `child` in a widget that sets the data from the payload of
+/// `selector`, as if it was changed from outside of the child.
+fn data_setter<T: Data>(selector: Selector<T>, child: impl Widget<T> + 'static) -> impl Widget<T> {
+    ModularWidget::new(WidgetPod::new(child))
+        .event_fn(move |child, ctx, event, data, env| match event {
+            Event::Command(cmd) if cmd.is(selector) => *data = cmd.get_unchecked(selector).clone(),
+            _ => child.event(ctx, event, data, env),
+        })
+        .lifecycle_fn(|child, ctx, event, data, env| child.lifecycle(ctx, event, data, env))
+        .update_fn(|child, ctx, _, data, env| child.update(ctx, data, env))
+        .layout_fn(|child, ctx, bc, data, env| {
+            let size = child.layout(ctx, bc, data, env);
+            child.set_origin(ctx, data, env, Point::ORIGIN);
+            size
+        })
+        .paint_fn(|child, ctx, data, env| child.paint(ctx, data, env))
+}
+
+type Painted<T> = Rc<RefCell<Vec<(&'static str, T)>>>;
+
+/// A widget filled with `color`, that records the data it is painted with.
+fn swatch<T: Data>(name: &'static str, color: Color, painted: &Painted<T>) -> impl Widget<T> {
+    let painted = painted.clone();
+    ModularWidget::new(())
+        .layout_fn(|_, _, _, _, _| Size::new(100.0, 100.0))
+        .paint_fn(move |_, ctx, data: &T, _| {
+            painted.borrow_mut().push((name, data.clone()));
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &color);
+        })
+}
+
+#[test]
+fn animated_tweens_outside_changes() {
+    let shown = Rc::new(Cell::new(0.0));
+    let child = {
+        let shown = shown.clone();
+        ModularWidget::new(())
+            .update_fn(move |_, _, _, data: &f64, _| shown.set(*data))
+            .layout_fn(|_, _, _, _, _| Size::new(10.0, 10.0))
+    };
+    let widget = data_setter(
+        SET_F64,
+        Animated::new(child, Duration::from_nanos(2 * HALF)).with_easing(Easing::Linear),
+    );
+
+    Harness::create_simple(0.0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.submit_command(SET_F64.with(10.0));
+        assert_eq!(shown.get(), 0.0);
+
+        harness.event(Event::AnimFrame(0));
+        harness.event(Event::AnimFrame(HALF));
+        assert_eq!(shown.get(), 5.0);
+
+        harness.event(Event::AnimFrame(HALF));
+        assert_eq!(shown.get(), 10.0);
+    });
+}
+
+#[test]
+fn either_crossfades_old_data_into_new() {
+    let painted = Painted::default();
+    let widget = data_setter(
+        SET_BOOL,
+        Either::new(
+            |on: &bool, _| *on,
+            swatch("on", Color::rgb8(0, 0, 255), &painted),
+            swatch("off", Color::rgb8(255, 0, 0), &painted),
+        )
+        .with_transition(Transition::fade(Duration::from_nanos(2 * HALF))),
+    );
+    let mut pixels = None;
+
+    Harness::create_with_render(
+        false,
+        widget,
+        Size::new(100.0, 100.0),
+        |harness| {
+            harness.send_initial_events();
+            harness.paint();
+            painted.borrow_mut().clear();
+
+            harness.submit_command(SET_BOOL.with(true));
+            harness.event(Event::AnimFrame(0));
+            harness.event(Event::AnimFrame(HALF));
+            harness.paint();
+            // The old view is painted with the data it had before the switch.
+            assert_eq!(*painted.borrow(), [("off", false), ("on", true)]);
+
+            // Neither view changed, so their layers are reused.
+            harness.paint();
+            assert_eq!(painted.borrow().len(), 2);
+        },
+        |target| pixels = Some(target.into_raw()),
+    );
+
+    // Halfway, the views are mixed, without the background showing through.
+    let pixels = pixels.unwrap();
+    let i = (50 * 100 + 50) * 4;
+    for (&c, &expected) in pixels[i..i + 4].iter().zip(&[128, 0, 128, 255]) {
+        assert!((c as i32 - expected).abs() <= 1, "{:?}", &pixels[i..i + 4]);
+    }
+}
+
+#[test]
+fn view_switcher_slides_old_view_out() {
+    let painted = Painted::default();
+    let switcher = {
+        let painted = painted.clone();
+        ViewSwitcher::new(
+            |page: &u32, _| *page,
+            move |page, _, _| match page {
+                1 => Box::new(swatch("one", Color::rgb8(255, 0, 0), &painted)),
+                _ => Box::new(swatch("two", Color::rgb8(0, 0, 255), &painted)),
+            },
+        )
+        .with_transition(Transition::slide(
+            Duration::from_nanos(2 * HALF),
+            SlideDirection::Left,
+        ))
+    };
+    let widget = data_setter(SET_U32, switcher);
+
+    Harness::create_simple(1, widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        painted.borrow_mut().clear();
+
+        harness.submit_command(SET_U32.with(2));
+        harness.event(Event::AnimFrame(0));
+        harness.paint();
+        assert_eq!(*painted.borrow(), [("one", 1), ("two", 2)]);
+
+        // Once the transition ends, only the new view is painted.
+        harness.event(Event::AnimFrame(2 * HALF));
+        painted.borrow_mut().clear();
+        harness.paint();
+        assert_eq!(*painted.borrow(), [("two", 2)]);
+    });
+}
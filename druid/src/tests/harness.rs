@@ -14,13 +14,18 @@
 
 //! Tools and infrastructure for testing widgets.
 
+use std::any::Any;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::app::PendingWindow;
+use crate::command::sys::SUBMIT_EVENT;
 use crate::core::{CommandQueue, WidgetState};
 use crate::ext_event::ExtEventHost;
 use crate::piet::{BitmapTarget, Device, Error, ImageFormat, Piet};
+use crate::shell::text::{simulate_input, InputHandler};
+use crate::shell::{TextFieldToken, WinHandler, WindowHandle};
 use crate::*;
 
 pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
@@ -49,6 +54,7 @@ pub struct Harness<'a, T> {
     piet: Piet<'a>,
     inner: Inner<T>,
     window_size: Size,
+    ext_host: ExtEventHost,
 }
 
 /// All of the state except for the `Piet` (render context). We need to pass
@@ -163,6 +169,7 @@ impl<T: Data> Harness<'_, T> {
                 piet,
                 inner,
                 window_size,
+                ext_host,
             };
             harness_closure(&mut harness);
         }
@@ -225,6 +232,45 @@ impl<T: Data> Harness<'_, T> {
         self.event(event);
     }
 
+    /// The host of the window's [`ExtEventSink`]s.
+    #[cfg(feature = "async")]
+    pub(crate) fn ext_host(&mut self) -> &mut ExtEventHost {
+        &mut self.ext_host
+    }
+
+    /// A sink for submitting commands and events to this harness from
+    /// outside; see [`recv_ext_command`].
+    ///
+    /// [`recv_ext_command`]: Harness::recv_ext_command
+    pub fn ext_event_sink(&self) -> ExtEventSink {
+        self.ext_host.make_sink()
+    }
+
+    /// Wait until a command is submitted with an [`ExtEventSink`], and send
+    /// it, as the app would. Returns `false` if none came within `timeout`.
+    pub fn recv_ext_command(&mut self, timeout: Duration) -> bool {
+        let start = Instant::now();
+        loop {
+            if let Some(cmd) = self.ext_host.recv() {
+                match cmd.get(SUBMIT_EVENT) {
+                    Some(ExtInputEvent::KeyDown(key)) => {
+                        let token = self.inner.window.active_text_field();
+                        simulate_input(&mut self.inner, token, key.clone());
+                        self.process_commands();
+                        self.update();
+                    }
+                    Some(event) => self.event(event.clone().into()),
+                    None => self.event(Event::Internal(InternalEvent::TargetedCommand(cmd))),
+                }
+                return true;
+            }
+            if start.elapsed() > timeout {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
     /// Send the events that would normally be sent when the app starts.
     // should we do this automatically? Also these will change regularly?
     pub fn send_initial_events(&mut self) {
@@ -276,14 +322,18 @@ impl<T: Data> Harness<'_, T> {
     pub fn paint_invalid(&mut self) {
         let invalid = std::mem::replace(self.window_mut().invalid_mut(), Region::EMPTY);
         self.inner.paint_region(&mut self.piet, &invalid);
+        self.process_commands();
     }
 
     /// Paints the entire window and resets the invalid region.
+    ///
+    /// Commands submitted during painting are sent afterwards.
     #[allow(dead_code)]
     pub fn paint(&mut self) {
         self.window_mut().invalid_mut().clear();
         self.inner
             .paint_region(&mut self.piet, &self.window_size.to_rect().into());
+        self.process_commands();
     }
 }
 
@@ -314,6 +364,44 @@ impl<T: Data> Inner<T> {
     }
 }
 
+/// Lets [`simulate_input`] handle key presses the way the platform would.
+impl<T: Data> WinHandler for Inner<T> {
+    fn connect(&mut self, _: &WindowHandle) {}
+
+    fn prepare_paint(&mut self) {}
+
+    fn paint(&mut self, _: &mut Piet, _: &Region) {}
+
+    fn key_down(&mut self, event: KeyEvent) -> bool {
+        self.window
+            .event(
+                &mut self.cmds,
+                Event::KeyDown(event),
+                &mut self.data,
+                &self.env,
+            )
+            .is_handled()
+    }
+
+    fn acquire_input_lock(
+        &mut self,
+        token: TextFieldToken,
+        mutable: bool,
+    ) -> Box<dyn InputHandler> {
+        self.window.get_ime_handler(token, mutable)
+    }
+
+    fn release_input_lock(&mut self, token: TextFieldToken) {
+        if let Some(widget) = self.window.release_ime_lock(token) {
+            self.event(Event::Internal(InternalEvent::RouteImeStateChange(widget)));
+        }
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 impl<T> Drop for Harness<'_, T> {
     fn drop(&mut self) {
         // We need to call finish even if a test assert failed
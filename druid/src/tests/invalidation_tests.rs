@@ -14,6 +14,8 @@
 
 //! Tests related to propagation of invalid rects.
 
+use std::sync::Arc;
+
 use float_cmp::approx_eq;
 use test_env_log::test;
 
@@ -123,3 +125,32 @@ fn invalidate_scroll() {
 }
 
 // TODO: one with scroll
+
+#[test]
+fn invalidate_changed_cells() {
+    let grid = BoardGrid::new(3, 3, |_, _, _: &Arc<Vec<bool>>, _| {})
+        .with_cell_size((10., 10.))
+        .with_cell_changed(|old, new, cell| {
+            old[cell.row * 3 + cell.col] != new[cell.row * 3 + cell.col]
+        })
+        .on_cell_click(|_, cell, _, data, _| Arc::make_mut(data)[cell.row * 3 + cell.col] ^= true);
+    let widget = Align::new(UnitPoint::TOP_LEFT, grid);
+
+    Harness::create_simple(Arc::new(vec![false; 9]), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.paint_invalid();
+
+        let mut click = move_mouse((15., 15.));
+        click.button = MouseButton::Left;
+        harness.event(Event::MouseDown(click.clone()));
+        harness.event(Event::MouseUp(click));
+        assert!(harness.data()[4]);
+
+        // only the clicked cell is repainted
+        let cell_rect = Rect::new(10., 10., 20., 20.);
+        let invalid = harness.window().invalid().rects().to_vec();
+        assert!(!invalid.is_empty());
+        assert!(invalid.iter().all(|rect| *rect == cell_rect));
+    });
+}
@@ -273,3 +273,539 @@ fn aspect_ratio() {
         assert_eq!(state.layout_rect().size(), Size::new(1000., 500.));
     });
 }
+
+#[test]
+fn aspect_ratio_fit_and_fill() {
+    let [id1, id2] = widget_ids();
+    let widget = Flex::column()
+        .with_child(
+            AspectRatioBox::new(SizedBox::empty().with_id(id1), 2.0)
+                .fix_width(300.)
+                .fix_height(50.),
+        )
+        .with_child(
+            AspectRatioBox::new(SizedBox::empty().with_id(id2), 2.0)
+                .with_mode(AspectRatioMode::Fill)
+                .fix_width(300.)
+                .fix_height(100.),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // the child is centered in the space it doesn't fill
+        assert_eq!(
+            harness.get_state(id1).layout_rect(),
+            Rect::new(100., 0., 200., 50.)
+        );
+        // or overflows it equally on both sides
+        assert_eq!(
+            harness.get_state(id2).layout_rect(),
+            Rect::new(0., -25., 300., 125.)
+        );
+    });
+}
+
+#[test]
+fn rtl_layout() {
+    let [id1, id2, id3, id4, row] = widget_ids();
+    let widget = Flex::column()
+        .with_child(
+            Flex::row()
+                .must_fill_main_axis(true)
+                .with_child(SizedBox::empty().width(100.).with_id(id1))
+                .with_child(SizedBox::empty().width(50.).with_id(id2))
+                .with_id(row)
+                .fix_width(300.),
+        )
+        .with_child(
+            SizedBox::empty()
+                .width(100.)
+                .with_id(id3)
+                .padding((10., 0., 30., 0.))
+                .fix_width(300.),
+        )
+        .with_child(Align::left(SizedBox::empty().width(100.).with_id(id4)).fix_width(300.))
+        .env_scope(|env, _| env.set(theme::LAYOUT_RTL, true));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(id1).layout_rect().x0, 200.);
+        assert_eq!(harness.get_state(id2).layout_rect().x0, 150.);
+        // each child is placed once, where it ends up
+        let child_rects = &harness.get_state(row).child_rects;
+        assert_eq!(child_rects.len(), 2);
+        assert_eq!(child_rects[0].x0, 200.);
+        assert_eq!(harness.get_state(id3).layout_rect().x0, 30.);
+        assert_eq!(harness.get_state(id4).layout_rect().x0, 200.);
+    });
+}
+
+#[test]
+fn flex_child_spacing() {
+    let [id1, id2, id3] = widget_ids();
+    let widget = Flex::row()
+        .with_child_spacing(10.)
+        .with_child(SizedBox::empty().width(20.).height(20.).with_id(id1))
+        .with_child(SizedBox::empty().width(30.).height(20.).with_id(id2))
+        .with_spacer(5.)
+        .with_child(SizedBox::empty().width(40.).height(20.).with_id(id3));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(id1).layout_rect().x0, 0.);
+        assert_eq!(harness.get_state(id2).layout_rect().x0, 30.);
+        // a spacer adds to the gap
+        assert_eq!(harness.get_state(id3).layout_rect().x0, 75.);
+    });
+
+    let [id1, id2] = widget_ids();
+    let widget = Flex::row()
+        .wrap()
+        .with_child_spacing(10.)
+        .with_child(SizedBox::empty().width(50.).height(20.).with_id(id1))
+        .with_child(SizedBox::empty().width(50.).height(20.).with_id(id2))
+        .fix_width(100.);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // with the gap, the second child doesn't fit on the first line
+        assert_eq!(harness.get_state(id1).layout_rect().origin(), Point::ZERO);
+        assert_eq!(
+            harness.get_state(id2).layout_rect().origin(),
+            Point::new(0., 30.)
+        );
+    });
+
+    // without an explicit gap, the theme's is used
+    let [id1, id2] = widget_ids();
+    let widget = Flex::row()
+        .with_child(SizedBox::empty().width(20.).height(20.).with_id(id1))
+        .with_child(SizedBox::empty().width(20.).height(20.).with_id(id2))
+        .env_scope(|env, _| env.set(theme::FLEX_CHILD_SPACING, 6.));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(id2).layout_rect().x0, 26.);
+    });
+}
+
+#[test]
+fn flex_wrap() {
+    let [id1, id2, id3, id4] = widget_ids();
+    let widget = Flex::row()
+        .wrap()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(SizedBox::empty().width(100.).height(20.).with_id(id1))
+        .with_child(SizedBox::empty().width(100.).height(30.).with_id(id2))
+        .with_child(SizedBox::empty().width(100.).height(20.).with_id(id3))
+        .with_flex_child(
+            SizedBox::empty().expand_width().height(10.).with_id(id4),
+            1.0,
+        )
+        .fix_width(250.);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(
+            harness.get_state(id2).layout_rect(),
+            Rect::new(100., 0., 200., 30.)
+        );
+        // the first line is as tall as its tallest child
+        assert_eq!(
+            harness.get_state(id3).layout_rect().origin(),
+            Point::new(0., 30.)
+        );
+        // an expanding child takes a whole line
+        assert_eq!(
+            harness.get_state(id4).layout_rect(),
+            Rect::new(0., 50., 250., 60.)
+        );
+        assert_eq!(harness.get_state(id1).layout_rect().origin(), Point::ZERO);
+    });
+}
+
+#[test]
+fn z_stack_layout() {
+    let [id1, id2, id3] = widget_ids();
+    let widget = ZStack::new()
+        .with_child(
+            SizedBox::empty().width(100.).height(50.).with_id(id1),
+            UnitPoint::CENTER,
+        )
+        .with_child(
+            SizedBox::empty().width(20.).height(20.).with_id(id2),
+            ZStackParams::new(UnitPoint::TOP_RIGHT).offset(Vec2::new(4., -4.)),
+        )
+        .with_child(
+            SizedBox::empty().width(40.).height(10.).with_id(id3),
+            UnitPoint::BOTTOM,
+        )
+        .center();
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // the stack is as large as its largest child
+        assert_eq!(harness.get_state(id1).layout_rect().origin(), Point::ZERO);
+        assert_eq!(
+            harness.get_state(id2).layout_rect().origin(),
+            Point::new(84., -4.)
+        );
+        assert_eq!(
+            harness.get_state(id3).layout_rect().origin(),
+            Point::new(30., 40.)
+        );
+    });
+}
+
+#[test]
+fn constraint_layout() {
+    let [id1, id2, id3] = widget_ids();
+    let parent = Item::parent();
+    let a = Item::child("a");
+    let b = Item::child("b");
+    let widget = ConstraintLayout::new()
+        .with_child("a", SizedBox::empty().width(50.).height(20.).with_id(id1))
+        .with_child("b", SizedBox::empty().width(30.).height(10.).with_id(id2))
+        .with_constraint(a.left().equals(parent.left() + 10.))
+        .with_constraint(a.top().equals(parent.top() + 10.))
+        .with_constraint(b.left().equals(a.right() + 8.))
+        .with_constraint(b.center_y().equals(a.center_y()))
+        .with_constraint(parent.right().equals(b.right() + 10.))
+        .with_constraint(parent.bottom().equals(a.bottom() + 10.))
+        .with_dynamic_constraints(|width: &f64, _| {
+            vec![Item::child("a")
+                .width()
+                .equals(*width)
+                .with_strength(Strength::Strong)]
+        })
+        .with_id(id3)
+        .center();
+
+    Harness::create_simple(80., widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(
+            harness.get_state(id1).layout_rect(),
+            Rect::new(10., 10., 90., 30.)
+        );
+        assert_eq!(
+            harness.get_state(id2).layout_rect(),
+            Rect::new(98., 15., 128., 25.)
+        );
+        // the layout grows around its children
+        assert_eq!(
+            harness.get_state(id3).layout_rect().size(),
+            Size::new(138., 40.)
+        );
+    });
+}
+
+#[test]
+fn responsive_breakpoints() {
+    let [narrow, wide] = widget_ids();
+    let widget = Responsive::new(move |_: &(), _| SizedBox::empty().expand().with_id(narrow))
+        .with_breakpoint(200., move |_, _| SizedBox::empty().expand().with_id(wide))
+        .fix_width(300.);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // the child is switched after the layout pass that crossed the breakpoint
+        harness.submit_command(Selector::NOOP);
+        harness.just_layout();
+        assert!(harness.try_get_state(narrow).is_none());
+        assert_eq!(harness.get_state(wide).layout_rect().width(), 300.);
+    });
+}
+
+#[test]
+fn grid_layout() {
+    let [id1, id2, id3, id4] = widget_ids();
+    let grid = Grid::new()
+        .with_columns(vec![
+            TrackSize::Fixed(100.),
+            TrackSize::Auto,
+            TrackSize::Flex(1.),
+        ])
+        .with_spacing(10.)
+        .with_child(
+            SizedBox::empty().width(50.).height(20.).with_id(id1),
+            (0, 0),
+        )
+        .with_child(
+            SizedBox::empty().width(80.).height(40.).with_id(id2),
+            GridCell::new(0, 1).align(CrossAxisAlignment::End, CrossAxisAlignment::Start),
+        )
+        .with_child(
+            SizedBox::empty().height(10.).with_id(id3),
+            GridCell::new(1, 0)
+                .span(1, 3)
+                .align(CrossAxisAlignment::Fill, None),
+        )
+        .with_child(
+            SizedBox::empty().width(30.).height(30.).with_id(id4),
+            (0, 2),
+        )
+        .fix_width(400.);
+
+    Harness::create_simple((), grid, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // centered vertically in the 40dp row
+        assert_eq!(
+            harness.get_state(id1).layout_rect(),
+            Rect::new(0., 10., 50., 30.)
+        );
+        assert_eq!(
+            harness.get_state(id2).layout_rect(),
+            Rect::new(110., 0., 190., 40.)
+        );
+        assert_eq!(
+            harness.get_state(id3).layout_rect(),
+            Rect::new(0., 50., 400., 60.)
+        );
+        assert_eq!(
+            harness.get_state(id4).layout_rect().origin(),
+            Point::new(200., 5.)
+        );
+    });
+}
+
+#[test]
+fn layout_skips_unchanged_widgets() {
+    const RELAYOUT: Selector = Selector::new("druid-tests.relayout");
+    const CHANGE_DATA: Selector = Selector::new("druid-tests.change-data");
+
+    let [requester_id] = widget_ids();
+    let layouts = Rc::new(Cell::new(0));
+    let counter = ModularWidget::new(layouts.clone()).layout_fn(|layouts, _, bc, _, _| {
+        layouts.set(layouts.get() + 1);
+        bc.constrain(Size::new(10., 10.))
+    });
+    let requester = ModularWidget::new(())
+        .event_fn(|_, ctx, event, data: &mut u32, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(CHANGE_DATA) {
+                    *data += 1;
+                }
+                ctx.request_layout();
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(10., 10.)))
+        .with_id(requester_id);
+    let widget = Flex::row().with_child(counter).with_child(requester);
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let initial = layouts.get();
+        assert!(initial > 0);
+
+        // a sibling's layout request doesn't lay out the counter again...
+        harness.submit_command(RELAYOUT.to(requester_id));
+        harness.just_layout();
+        assert_eq!(layouts.get(), initial);
+
+        // ...unless the data has changed.
+        harness.submit_command(CHANGE_DATA.to(requester_id));
+        harness.just_layout();
+        assert_eq!(layouts.get(), initial + 1);
+    });
+}
+
+#[test]
+fn layout_runs_when_the_parent_requests_it() {
+    const RELAYOUT: Selector = Selector::new("druid-tests.relayout");
+    const GROW: Selector = Selector::new("druid-tests.grow");
+
+    let [grower_id, row_id, requester_id] = widget_ids();
+    // changes its size on `GROW`, but leaves it to its parent to request layout,
+    // as a parent that changes a child through `widget_mut` would.
+    let grower = ModularWidget::new(10.0)
+        .event_fn(|width, _, event, _, _| {
+            if matches!(event, Event::Command(cmd) if cmd.is(GROW)) {
+                *width += 10.0;
+            }
+        })
+        .layout_fn(|width, _, bc, _, _| bc.constrain(Size::new(*width, 10.)))
+        .with_id(grower_id);
+    let requester = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _: &mut u32, _| {
+            if let Event::Command(_) = event {
+                ctx.request_layout();
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(10., 10.)))
+        .with_id(requester_id);
+    let row = Flex::row()
+        .with_child(grower)
+        .controller(RequestLayoutOn(RELAYOUT))
+        .with_id(row_id);
+    let widget = Flex::column().with_child(row).with_child(requester);
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(grower_id).layout_rect().width(), 10.);
+
+        // a sibling's request reuses the row's layout, and its children's rects
+        harness.submit_command(GROW.to(grower_id));
+        harness.submit_command(RELAYOUT.to(requester_id));
+        harness.just_layout();
+        assert_eq!(harness.get_state(grower_id).layout_rect().width(), 10.);
+        assert_eq!(harness.get_state(row_id).child_rects.len(), 1);
+
+        harness.submit_command(RELAYOUT.to(row_id));
+        harness.just_layout();
+        assert_eq!(harness.get_state(grower_id).layout_rect().width(), 20.);
+    });
+}
+
+/// Requests layout when it gets the command.
+struct RequestLayoutOn(Selector);
+
+impl<T, W: Widget<T>> Controller<T, W> for RequestLayoutOn {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if matches!(event, Event::Command(cmd) if cmd.is(self.0)) {
+            ctx.request_layout();
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+#[test]
+fn grid_min_content_tracks() {
+    const TEXT: &str = "several words of text";
+
+    let [auto, min_content] = widget_ids();
+    let label = || Label::new(TEXT).with_line_break_mode(LineBreaking::WordWrap);
+    let widget = Grid::new()
+        .with_columns(vec![TrackSize::Auto, TrackSize::MinContent])
+        .with_child(label().with_id(auto), (0, 0))
+        .with_child(label().with_id(min_content), (0, 1));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let auto = harness.get_state(auto).layout_rect();
+        let min_content = harness.get_state(min_content).layout_rect();
+        // the min-content column wraps the text at every word
+        assert!(min_content.width() < auto.width());
+        assert!(min_content.height() > auto.height());
+    });
+}
+
+#[test]
+fn split_intrinsic_size() {
+    let [split, second] = widget_ids();
+    let widget = Grid::new().with_columns(vec![TrackSize::Auto]).with_child(
+        Split::columns(
+            SizedBox::empty().width(50.).height(20.),
+            SizedBox::empty().width(30.).height(40.).with_id(second),
+        )
+        .min_size(60., 0.)
+        .bar_size(6.)
+        .with_id(split),
+        (0, 0),
+    );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // the first side grows to its minimum size, then the bar and the second side.
+        assert_eq!(
+            harness.get_state(split).layout_rect(),
+            Rect::new(0., 0., 96., 40.)
+        );
+        assert_eq!(
+            harness.get_state(second).layout_rect(),
+            Rect::new(66., 0., 96., 40.)
+        );
+    });
+}
+
+#[test]
+fn child_rects_follow_layout() {
+    let [column] = widget_ids();
+    let widget = Flex::column()
+        .with_child(SizedBox::empty().width(40.).height(30.))
+        .with_child(SizedBox::empty().width(40.).height(50.))
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_id(column);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(
+            harness.get_state(column).child_rects,
+            vec![Rect::new(0., 0., 40., 30.), Rect::new(0., 30., 40., 80.)]
+        );
+    });
+}
+
+#[test]
+fn content_scale_shrinks_layout() {
+    let id = WidgetId::next();
+    let widget = SizedBox::<()>::empty().expand().with_id(id);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.set_initial_size(Size::new(400., 300.));
+        harness.window_mut().content_scale = 2.0;
+        harness.send_initial_events();
+        harness.just_layout();
+        // the content is laid out at half size, then painted scaled up
+        let state = harness.get_state(id);
+        assert_eq!(state.layout_rect().size(), Size::new(200., 150.));
+    });
+}
+
+#[test]
+fn rotated_label_swaps_size() {
+    let [flat_id, rotated_id] = widget_ids();
+    let widget = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("hello!").with_id(flat_id))
+        .with_child(
+            Label::new("hello!")
+                .with_orientation(TextOrientation::Rotated270)
+                .with_id(rotated_id),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let flat = harness.get_state(flat_id).layout_rect().size();
+        let rotated = harness.get_state(rotated_id).layout_rect().size();
+        assert_eq!(rotated, Size::new(flat.height, flat.width));
+    });
+}
+
+#[test]
+fn upright_label_stacks_characters() {
+    let [flat_id, upright_id] = widget_ids();
+    let widget = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("縦書き").with_id(flat_id))
+        .with_child(
+            Label::new("縦書き")
+                .with_orientation(TextOrientation::Upright)
+                .with_id(upright_id),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let flat = harness.get_state(flat_id).layout_rect().size();
+        let upright = harness.get_state(upright_id).layout_rect().size();
+        // CJK text can be broken between any two characters
+        assert!(upright.height > flat.height * 2.);
+        assert!(upright.width < flat.width);
+    });
+}
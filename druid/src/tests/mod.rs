@@ -19,16 +19,21 @@
 pub mod harness;
 pub mod helpers;
 
+#[cfg(test)]
+mod animation_tests;
 #[cfg(test)]
 mod invalidation_tests;
 #[cfg(test)]
 mod layout_tests;
+#[cfg(test)]
+mod z_order_tests;
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::rc::Rc;
+use std::time::Duration;
 
 use crate::widget::*;
 use crate::*;
@@ -48,6 +53,7 @@ pub fn move_mouse(p: impl Into<Point>) -> MouseEvent {
         focus: false,
         button: MouseButton::None,
         wheel_delta: Vec2::ZERO,
+        precise_wheel: false,
     }
 }
 
@@ -63,6 +69,19 @@ pub fn scroll_mouse(p: impl Into<Point>, delta: impl Into<Vec2>) -> MouseEvent {
         focus: false,
         button: MouseButton::None,
         wheel_delta: delta.into(),
+        precise_wheel: false,
+    }
+}
+
+/// Helper function to construct a touch event for the contact `id`.
+pub fn touch(id: u64, p: impl Into<Point>) -> TouchEvent {
+    let pos = p.into();
+    TouchEvent {
+        id: TouchId::from_raw(id),
+        pos,
+        window_pos: pos,
+        mods: Modifiers::default(),
+        primary: id == 0,
     }
 }
 
@@ -255,6 +274,88 @@ fn take_focus() {
     })
 }
 
+#[test]
+fn mutate_widget() {
+    use crate::widget::mutable::mutation;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let id = WidgetId::next();
+    let widget = Mutable::new(ModularWidget::<(), ()>::new(())).with_id(id);
+    let count = Arc::new(AtomicUsize::new(0));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+
+        let count_2 = count.clone();
+        let mutate = mutation(move |_: &mut ModularWidget<(), ()>, ctx: &mut EventCtx| {
+            count_2.fetch_add(1, Ordering::SeqCst);
+            ctx.request_paint();
+        });
+        harness.submit_command(commands::MUTATE_WIDGET.with(mutate).to(id));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // a mutation for the wrong widget type is not run.
+        let count_2 = count.clone();
+        let mutate = mutation(move |_: &mut Label<()>, _: &mut EventCtx| {
+            count_2.fetch_add(1, Ordering::SeqCst);
+        });
+        harness.submit_command(commands::MUTATE_WIDGET.with(mutate).to(id));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    });
+}
+
+#[test]
+fn record_interactions() {
+    const TAKE_FOCUS: Selector = Selector::new("druid-tests.take-focus");
+
+    let [id_1, id_2] = widget_ids();
+    let clickable = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _data, _env| match event {
+            Event::MouseDown(_) => ctx.set_active(true),
+            Event::MouseUp(_) => ctx.set_active(false),
+            Event::Command(cmd) if cmd.is(TAKE_FOCUS) => ctx.request_focus(),
+            _ => (),
+        })
+        .with_id(id_2);
+    let widget = SizedBox::new(clickable)
+        .width(100.)
+        .height(100.)
+        .with_id(id_1);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let mut mouse = move_mouse((10., 10.));
+        mouse.button = MouseButton::Left;
+        mouse.count = 2;
+        // nothing is recorded without a sink
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseUp(mouse.clone()));
+        assert!(harness.window().interactions.is_empty());
+
+        harness.window_mut().record_interactions = true;
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseUp(mouse));
+        harness.submit_command(TAKE_FOCUS.to(id_2));
+
+        let interactions = &harness.window().interactions;
+        assert_eq!(interactions.len(), 2);
+        assert_eq!(interactions[0].widget_id, Some(id_2));
+        assert_eq!(interactions[0].window_id, Some(harness.window().id));
+        assert_eq!(
+            interactions[0].kind,
+            InteractionKind::Click {
+                button: MouseButton::Left,
+                count: 2
+            }
+        );
+        assert_eq!(interactions[1].widget_id, Some(id_2));
+        assert_eq!(interactions[1].kind, InteractionKind::FocusChanged(true));
+    });
+}
+
 #[test]
 fn focus_changed() {
     const TAKE_FOCUS: Selector = Selector::new("druid-tests.take-focus");
@@ -961,3 +1062,1102 @@ fn notifications() {
         assert!(saw_notification(&grandparent_rec));
     });
 }
+
+#[test]
+/// Ensure that a widget's rect can be requested by its id.
+fn request_widget_rect() {
+    use crate::command::sys::{REQUEST_WIDGET_RECT, WIDGET_RECT};
+
+    let target_id = WidgetId::next();
+    let requester_id = WidgetId::next();
+    let answer = Rc::new(Cell::new(None));
+
+    let requester = ModularWidget::new(answer.clone())
+        .event_fn(|answer, _, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(rect) = cmd.get(WIDGET_RECT) {
+                    answer.set(Some(*rect));
+                }
+            }
+        })
+        .with_id(requester_id);
+
+    let tree = Flex::column()
+        .with_child(SizedBox::empty().fix_size(40.0, 20.0))
+        .with_child(SizedBox::empty().fix_size(30.0, 10.0).with_id(target_id))
+        .with_child(requester)
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .padding(10.0);
+
+    Harness::create_simple((), tree, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(REQUEST_WIDGET_RECT.with(requester_id).to(target_id));
+        assert_eq!(
+            answer.get(),
+            Some((target_id, Rect::new(10.0, 30.0, 40.0, 40.0)))
+        );
+    });
+}
+
+#[test]
+/// Ensure that nested scroll containers bring a widget into view.
+fn scroll_to_view_nested() {
+    const SCROLL: Selector = Selector::new("druid-tests.scroll-to-view");
+
+    let target_id = WidgetId::next();
+    let target = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _, _| {
+            if matches!(event, Event::Command(cmd) if cmd.is(SCROLL)) {
+                ctx.scroll_to_view();
+            }
+        })
+        .layout_fn(|_, _, _, _, _| Size::new(50.0, 50.0))
+        .with_id(target_id);
+
+    let inner = Scroll::new(
+        Flex::column()
+            .with_child(SizedBox::empty().fix_size(50.0, 500.0))
+            .with_child(target),
+    )
+    .vertical()
+    .fix_height(100.0);
+    let outer = Scroll::new(
+        Flex::column()
+            .with_child(SizedBox::empty().fix_size(50.0, 1000.0))
+            .with_child(inner),
+    )
+    .vertical()
+    .fix_height(200.0)
+    .env_scope(|env, _| env.set(theme::ANIMATE_SCROLLING, false));
+
+    Harness::create_simple((), outer, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(SCROLL.to(target_id));
+        harness.just_layout();
+
+        let state = harness.get_state(target_id);
+        let rect = Rect::from_origin_size(state.window_origin(), state.size());
+        assert!(
+            rect.y0 >= 0.0 && rect.y1 <= 200.0,
+            "{:?} is not visible",
+            rect
+        );
+    });
+}
+
+#[test]
+fn scroll_to_view_animates() {
+    const SCROLL: Selector = Selector::new("druid-tests.scroll-to-view-animated");
+
+    let target_id = WidgetId::next();
+    let target = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _, _| {
+            if matches!(event, Event::Command(cmd) if cmd.is(SCROLL)) {
+                ctx.scroll_to_view();
+            }
+        })
+        .layout_fn(|_, _, _, _, _| Size::new(50.0, 50.0))
+        .with_id(target_id);
+    let scroll = Scroll::new(
+        Flex::column()
+            .with_child(SizedBox::empty().fix_size(50.0, 500.0))
+            .with_child(target),
+    )
+    .vertical()
+    .fix_height(100.0);
+
+    Harness::create_simple((), scroll, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(SCROLL.to(target_id));
+        harness.just_layout();
+        assert_eq!(harness.get_state(target_id).window_origin().y, 500.0);
+
+        harness.event(Event::AnimFrame(0));
+        harness.event(Event::AnimFrame(100_000_000));
+        harness.just_layout();
+        let y = harness.get_state(target_id).window_origin().y;
+        assert!(y > 50.0 && y < 500.0, "{} is not on the way", y);
+
+        harness.event(Event::AnimFrame(200_000_000));
+        harness.just_layout();
+        assert_eq!(harness.get_state(target_id).window_origin().y, 50.0);
+    });
+}
+
+#[test]
+fn visible_rect_in_scroll() {
+    const RECORD: Selector = Selector::new("druid-tests.record-visible-rect");
+
+    let visible = Rc::new(Cell::new(Rect::ZERO));
+    let recorded = visible.clone();
+    let target_id = WidgetId::next();
+    let target = ModularWidget::new(())
+        .event_fn(move |_, ctx, event, _, _| {
+            if matches!(event, Event::Command(cmd) if cmd.is(RECORD)) {
+                recorded.set(ctx.visible_rect());
+            }
+        })
+        .layout_fn(|_, _, _, _, _| Size::new(50.0, 50.0))
+        .with_id(target_id);
+    let scroll = Scroll::new(
+        Flex::column()
+            .with_child(SizedBox::empty().fix_size(50.0, 500.0))
+            .with_child(target),
+    )
+    .vertical()
+    .fix_height(100.0);
+
+    Harness::create_simple((), scroll, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.submit_command(RECORD.to(target_id));
+        assert_eq!(visible.get().height(), 0.0);
+
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, 510.0))));
+        harness.just_layout();
+        harness.submit_command(RECORD.to(target_id));
+        assert_eq!(visible.get(), Rect::new(0.0, 10.0, 50.0, 50.0));
+    });
+}
+
+#[test]
+fn scroll_near_end_and_anchor() {
+    let scroll_id = WidgetId::next();
+    let scroll = Scroll::new(SizedBox::empty().fix_size(50.0, 1000.0))
+        .vertical()
+        .on_near_end(100.0, |_, count: &mut u32, _| *count += 1)
+        .with_id(scroll_id)
+        .fix_height(200.0);
+
+    Harness::create_simple(0u32, scroll, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(*harness.data(), 0);
+
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, 650.0))));
+        assert_eq!(*harness.data(), 0);
+        // within 100 of the end; this only fires once
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, 100.0))));
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, 10.0))));
+        assert_eq!(*harness.data(), 1);
+
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, -500.0))));
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, 500.0))));
+        assert_eq!(*harness.data(), 2);
+    });
+
+    let height = Rc::new(Cell::new(1000.0));
+    let content_height = height.clone();
+    let content_id = WidgetId::next();
+    let content = ModularWidget::new(())
+        .layout_fn(move |_, _, _, _, _| Size::new(50.0, content_height.get()))
+        .with_id(content_id);
+    let scroll = Scroll::new(content)
+        .vertical()
+        .with_anchor(ScrollAnchor::End)
+        .fix_height(200.0);
+
+    Harness::create_simple((), scroll, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // starts out scrolled to the end
+        assert_eq!(harness.get_state(content_id).window_origin().y, -800.0);
+
+        // prepending content keeps the visible part in place
+        height.set(1300.0);
+        harness.just_layout();
+        assert_eq!(harness.get_state(content_id).window_origin().y, -1100.0);
+
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, -100.0))));
+        height.set(1400.0);
+        harness.just_layout();
+        assert_eq!(harness.get_state(content_id).window_origin().y, -1100.0);
+    });
+}
+
+#[test]
+fn touch_drags_scroll_and_slider() {
+    let content_id = WidgetId::next();
+    let scroll = Scroll::new(
+        Flex::column()
+            .with_child(Slider::new())
+            .with_child(SizedBox::empty().fix_size(50.0, 1000.0).with_id(content_id)),
+    )
+    .vertical()
+    .fix_height(200.0);
+
+    Harness::create_simple(0.0, scroll, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let top = harness.get_state(content_id).window_origin().y;
+
+        // the slider takes the drag, and the content stays put
+        harness.event(Event::TouchDown(touch(0, (5., 5.))));
+        harness.event(Event::TouchMove(touch(0, (60., 5.))));
+        harness.event(Event::TouchUp(touch(0, (60., 5.))));
+        assert!(*harness.data() > 0.0);
+        harness.just_layout();
+        assert_eq!(harness.get_state(content_id).window_origin().y, top);
+
+        // a short touch doesn't scroll
+        harness.event(Event::TouchDown(touch(1, (10., 150.))));
+        harness.event(Event::TouchMove(touch(1, (10., 145.))));
+        harness.event(Event::TouchUp(touch(1, (10., 145.))));
+        harness.just_layout();
+        assert_eq!(harness.get_state(content_id).window_origin().y, top);
+
+        // past the slop the content follows the finger
+        harness.event(Event::TouchDown(touch(2, (10., 150.))));
+        harness.event(Event::TouchMove(touch(2, (10., 140.))));
+        harness.event(Event::TouchMove(touch(3, (10., 0.))));
+        harness.event(Event::TouchMove(touch(2, (10., 90.))));
+        harness.just_layout();
+        assert_eq!(harness.get_state(content_id).window_origin().y, top - 50.0);
+    });
+}
+
+#[test]
+fn pen_goes_to_widget_under_it() {
+    let left_rec = Recording::default();
+    let right_rec = Recording::default();
+    let widget = Flex::row()
+        .with_child(SizedBox::empty().fix_size(100.0, 100.0).record(&left_rec))
+        .with_child(SizedBox::empty().fix_size(100.0, 100.0).record(&right_rec));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        left_rec.clear();
+        right_rec.clear();
+
+        let pos = Point::new(150.0, 50.0);
+        harness.event(Event::Pen(PenEvent {
+            pos,
+            window_pos: pos,
+            phase: PenPhase::Down,
+            pressure: 0.8,
+            tilt: Vec2::new(10.0, -20.0),
+            barrel_button: false,
+            eraser: false,
+            mods: Modifiers::default(),
+        }));
+        assert!(matches!(left_rec.next(), Record::None));
+        match right_rec.next() {
+            Record::E(Event::Pen(pen)) => {
+                assert_eq!(pen.pos, Point::new(50.0, 50.0));
+                assert_eq!(pen.window_pos, pos);
+                assert_eq!(pen.pressure, 0.8);
+            }
+            other => panic!("expected a pen event, got {:?}", other),
+        }
+    });
+}
+
+#[test]
+fn tab_order_and_focus_scopes() {
+    const FOCUS_SCOPE: Selector = Selector::new("druid-tests.focus-first-in-scope");
+
+    let [id_a, id_b, id_c, id_d, id_e, scope_id] = widget_ids();
+    let focusable = |id| {
+        ModularWidget::new(())
+            .event_fn(move |_, ctx, event, _, _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(FOCUS_SCOPE) {
+                        ctx.focus_first_in(scope_id);
+                    }
+                }
+            })
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .with_id(id)
+    };
+    let scope = Flex::column()
+        .with_child(focusable(id_c))
+        .with_child(focusable(id_d))
+        .focus_scope()
+        .with_id(scope_id);
+    let widget = Flex::row()
+        .with_child(focusable(id_a))
+        .with_child(focusable(id_b).with_tab_index(1))
+        .with_child(scope)
+        .with_child(focusable(id_e).with_tab_index(-1));
+
+    let tab = |shift: bool| {
+        let mods = if shift { RawMods::Shift } else { RawMods::None };
+        Event::KeyDown(KeyEvent::for_test(mods, KbKey::Tab))
+    };
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        assert_eq!(harness.window().focus_chain(), &[id_b, id_a, id_c, id_d]);
+
+        let mut visited = Vec::new();
+        for _ in 0..5 {
+            harness.event(tab(false));
+            visited.push(harness.window().focus.unwrap());
+        }
+        // once inside the scope, traversal stays there.
+        assert_eq!(visited, vec![id_b, id_a, id_c, id_d, id_c]);
+
+        harness.event(tab(true));
+        assert_eq!(harness.window().focus, Some(id_d));
+
+        harness.submit_command(FOCUS_SCOPE.to(id_e));
+        assert_eq!(harness.window().focus, Some(id_c));
+    });
+}
+
+#[test]
+fn shortcut_scope_submits_command_when_focused() {
+    const TAKE_FOCUS: Selector = Selector::new("druid-tests.shortcut-take-focus");
+    const SAVE: Selector = Selector::new("druid-tests.shortcut-save");
+    const CANCEL: Selector = Selector::new("druid-tests.shortcut-cancel");
+
+    let saves = Rc::new(Cell::new(0));
+    let cancels = Rc::new(Cell::new(0));
+    let (saves_2, cancels_2) = (saves.clone(), cancels.clone());
+    let [focus_id, scope_id] = widget_ids();
+    let focusable = ModularWidget::new(())
+        .event_fn(move |_, ctx, event, _, _| match event {
+            Event::Command(cmd) if cmd.is(TAKE_FOCUS) => ctx.request_focus(),
+            Event::Command(cmd) if cmd.is(SAVE) => saves_2.set(saves_2.get() + 1),
+            Event::Command(cmd) if cmd.is(CANCEL) => cancels_2.set(cancels_2.get() + 1),
+            // a key the widget handles itself doesn't trigger a shortcut.
+            Event::KeyDown(key) if key.key == KbKey::Enter => ctx.set_handled(),
+            _ => (),
+        })
+        .with_id(focus_id);
+    let inner = focusable
+        .with_shortcuts(
+            ShortcutMap::new()
+                .bind(HotKey::new(RawMods::Ctrl, "s"), SAVE)
+                .bind(HotKey::new(None, KbKey::Enter), SAVE)
+                .disable(HotKey::new(None, KbKey::Escape)),
+        )
+        .with_id(scope_id);
+    let widget = SizedBox::new(inner)
+        .with_shortcuts(ShortcutMap::new().bind(HotKey::new(None, KbKey::Escape), CANCEL));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+
+        // without focus, the scopes don't see the key.
+        harness.event(Event::KeyDown(KeyEvent::for_test(RawMods::Ctrl, "s")));
+        assert_eq!(saves.get(), 0);
+
+        harness.submit_command(TAKE_FOCUS.to(focus_id));
+        harness.event(Event::KeyDown(KeyEvent::for_test(RawMods::Ctrl, "s")));
+        assert_eq!(saves.get(), 1);
+
+        harness.event(Event::KeyDown(KeyEvent::for_test(
+            RawMods::None,
+            KbKey::Enter,
+        )));
+        assert_eq!(saves.get(), 1);
+
+        // the inner scope disables the key of the outer one.
+        harness.event(Event::KeyDown(KeyEvent::for_test(
+            RawMods::None,
+            KbKey::Escape,
+        )));
+        assert_eq!(cancels.get(), 0);
+
+        harness.submit_command(
+            commands::SET_SHORTCUTS
+                .with(ShortcutMap::new())
+                .to(scope_id),
+        );
+        harness.event(Event::KeyDown(KeyEvent::for_test(RawMods::Ctrl, "s")));
+        assert_eq!(saves.get(), 1);
+        harness.event(Event::KeyDown(KeyEvent::for_test(
+            RawMods::None,
+            KbKey::Escape,
+        )));
+        assert_eq!(cancels.get(), 1);
+    });
+}
+
+#[test]
+fn rubber_band_selects_items() {
+    use std::sync::Arc;
+
+    let item = |index| {
+        SizedBox::empty()
+            .fix_size(50.0, 50.0)
+            .controller(RubberBandItem::new().with_index(move |_| index))
+    };
+    let items = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(item(0))
+        .with_child(item(1));
+    let widget = RubberBand::new(items).on_select(|_, selection, data: &mut Arc<Vec<usize>>, _| {
+        *data = Arc::new(selection.indices.clone());
+    });
+
+    Harness::create_simple(Arc::new(Vec::new()), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let mut mouse = move_mouse((40., 5.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::MouseDown(mouse.clone()));
+        assert!(harness.data().is_empty());
+
+        harness.event(Event::MouseMove(move_mouse((45., 20.))));
+        assert_eq!(**harness.data(), vec![0]);
+        harness.event(Event::MouseMove(move_mouse((45., 60.))));
+        assert_eq!(**harness.data(), vec![0, 1]);
+
+        mouse.pos = (45., 60.).into();
+        harness.event(Event::MouseUp(mouse));
+        harness.event(Event::MouseMove(move_mouse((45., 20.))));
+        assert_eq!(**harness.data(), vec![0, 1]);
+    });
+}
+
+#[test]
+fn scroll_offset_binding_syncs_views() {
+    let left_id = WidgetId::next();
+    let right_id = WidgetId::next();
+    let view = |id| {
+        Scroll::new(SizedBox::empty().fix_size(50.0, 1000.0).with_id(id))
+            .vertical()
+            .bind_offset(lens::Identity)
+            .fix_size(50.0, 200.0)
+    };
+    let widget = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(view(left_id))
+        .with_child(view(right_id));
+
+    Harness::create_simple(Vec2::new(0.0, 300.0), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(left_id).window_origin().y, -300.0);
+        assert_eq!(harness.get_state(right_id).window_origin().y, -300.0);
+
+        harness.event(Event::Wheel(scroll_mouse((10., 10.), (0.0, 100.0))));
+        harness.just_layout();
+        assert_eq!(*harness.data(), Vec2::new(0.0, 400.0));
+        assert_eq!(harness.get_state(right_id).window_origin().y, -400.0);
+    });
+}
+
+#[test]
+fn computed_caches_derived_value() {
+    const SET: Selector<(u32, u32)> = Selector::new("druid-tests.computed-set");
+    let computations: Rc<Cell<usize>> = Default::default();
+    let child_updates: Rc<Cell<usize>> = Default::default();
+
+    let counted = computations.clone();
+    let updated = child_updates.clone();
+    let computed = Computed::new(
+        move |data: &(u32, u32)| {
+            counted.set(counted.get() + 1);
+            data.0 / 10
+        },
+        ModularWidget::new(()).update_fn(move |_, _, _, _: &u32, _| {
+            updated.set(updated.get() + 1);
+        }),
+    );
+    let setter = ModularWidget::new(()).event_fn(|_, _, event, data: &mut (u32, u32), _| {
+        if let Event::Command(cmd) = event {
+            if let Some(value) = cmd.get(SET) {
+                *data = *value;
+            }
+        }
+    });
+    let widget = Flex::column().with_child(setter).with_child(computed);
+
+    Harness::create_simple((1, 0), widget, |harness| {
+        harness.send_initial_events();
+        assert_eq!(computations.get(), 1);
+
+        // the derived value is the same, so the child isn't updated
+        harness.submit_command(SET.with((5, 1)));
+        assert_eq!(computations.get(), 2);
+        assert_eq!(child_updates.get(), 0);
+
+        harness.submit_command(SET.with((15, 1)));
+        assert_eq!(computations.get(), 3);
+        assert_eq!(child_updates.get(), 1);
+    });
+}
+
+#[test]
+fn update_skips_widgets_lensed_to_unchanged_fields() {
+    #[derive(Clone, Data, Lens)]
+    struct Pair {
+        left: u32,
+        right: u32,
+    }
+
+    const SET: Selector<(u32, u32)> = Selector::new("druid-tests.set-pair");
+
+    fn counter(updates: &Rc<Cell<usize>>) -> ModularWidget<(), u32> {
+        let updates = updates.clone();
+        ModularWidget::new(()).update_fn(move |_, _, _, _, _| {
+            updates.set(updates.get() + 1);
+        })
+    }
+
+    let left_updates: Rc<Cell<usize>> = Default::default();
+    let right_updates: Rc<Cell<usize>> = Default::default();
+
+    let setter = ModularWidget::new(()).event_fn(|_, _, event, data: &mut Pair, _| {
+        if let Event::Command(cmd) = event {
+            if let Some((left, right)) = cmd.get(SET) {
+                data.left = *left;
+                data.right = *right;
+            }
+        }
+    });
+    let widget = Flex::column()
+        .with_child(setter)
+        .with_child(counter(&left_updates).lens(Pair::left))
+        .with_child(counter(&right_updates).lens(Pair::right));
+
+    Harness::create_simple(Pair { left: 0, right: 0 }, widget, |harness| {
+        harness.send_initial_events();
+
+        // only `left` changed, so the pod of the `right` lens returns early
+        harness.submit_command(SET.with((1, 0)));
+        assert_eq!(left_updates.get(), 1);
+        assert_eq!(right_updates.get(), 0);
+
+        harness.submit_command(SET.with((1, 1)));
+        assert_eq!(left_updates.get(), 1);
+        assert_eq!(right_updates.get(), 1);
+
+        // the skipped pods kept up with the data, so nothing is stale
+        harness.submit_command(SET.with((1, 1)));
+        assert_eq!(left_updates.get(), 1);
+        assert_eq!(right_updates.get(), 1);
+    });
+}
+
+#[test]
+fn undo_manager_undoes_groups() {
+    const ADD: Selector<u32> = Selector::new("druid-tests.undo-add");
+    let adder = ModularWidget::new(()).event_fn(|_, _, event, data: &mut u32, _| {
+        if let Event::Command(cmd) = event {
+            if let Some(value) = cmd.get(ADD) {
+                *data += value;
+            }
+        }
+    });
+    let widget = adder.controller(UndoManager::new());
+
+    Harness::create_simple(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(ADD.with(1));
+        harness.submit_command(ADD.with(2));
+        assert_eq!(*harness.data(), 3);
+
+        harness.submit_command(commands::UNDO);
+        assert_eq!(*harness.data(), 1);
+        harness.submit_command(commands::REDO);
+        assert_eq!(*harness.data(), 3);
+
+        harness.submit_command(UndoManager::BEGIN_GROUP);
+        harness.submit_command(ADD.with(10));
+        harness.submit_command(ADD.with(20));
+        harness.submit_command(UndoManager::END_GROUP);
+        assert_eq!(*harness.data(), 33);
+        harness.submit_command(commands::UNDO);
+        assert_eq!(*harness.data(), 3);
+
+        // a new change clears what could be redone
+        harness.submit_command(ADD.with(100));
+        harness.submit_command(commands::REDO);
+        assert_eq!(*harness.data(), 103);
+    });
+}
+
+#[test]
+fn validate_reports_errors() {
+    const SET: Selector<String> = Selector::new("druid-tests.validate-set");
+    let valid: Rc<Cell<Option<bool>>> = Default::default();
+
+    let seen = valid.clone();
+    let form = ModularWidget::new(())
+        .event_fn(|_, _, event, data: &mut Validated<String>, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(value) = cmd.get(SET) {
+                    data.value = value.clone();
+                }
+            }
+        })
+        .update_fn(move |_, _, _, data, _| {
+            seen.set(Some(data.is_valid()));
+        });
+    let widget = Validate::new(form).field("name", lens::Identity, |name: &String| {
+        if name.is_empty() {
+            Err("empty".into())
+        } else {
+            Ok(())
+        }
+    });
+
+    Harness::create_simple(String::new(), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(SET.with("druid".into()));
+        assert_eq!(valid.get(), Some(true));
+        assert_eq!(harness.data(), "druid");
+
+        harness.submit_command(SET.with(String::new()));
+        assert_eq!(valid.get(), Some(false));
+    });
+}
+
+#[test]
+fn drag_and_drop_between_widgets() {
+    // (dragged value, dropped value, whether the drop was accepted)
+    let source = SizedBox::empty().width(40.).height(40.);
+    let source = ControllerHost::new(
+        source,
+        Draggable::new(|data: &(u32, u32, bool), _| data.0)
+            .on_drag_end(|_, accepted, data: &mut (u32, u32, bool), _| data.2 = accepted),
+    );
+    let target = SizedBox::empty()
+        .width(40.)
+        .height(40.)
+        .on_drop(|_, value: &u32, _, data: &mut (u32, u32, bool), _| data.1 = *value);
+    let widget = Flex::row().with_child(source).with_child(target);
+
+    Harness::create_simple((7, 0, false), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let mut mouse = move_mouse((10., 10.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseMove(move_mouse((20., 10.))));
+        harness.event(Event::MouseMove(move_mouse((60., 10.))));
+        assert_eq!(*harness.data(), (7, 0, false));
+
+        mouse.pos = (60., 10.).into();
+        harness.event(Event::MouseUp(mouse.clone()));
+        assert_eq!(*harness.data(), (7, 7, true));
+
+        // a drop outside of a target is not accepted.
+        mouse.pos = (10., 10.).into();
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseMove(move_mouse((20., 30.))));
+        mouse.pos = (20., 30.).into();
+        harness.event(Event::MouseUp(mouse));
+        assert_eq!(*harness.data(), (7, 7, false));
+    });
+}
+
+#[test]
+fn file_drop_from_other_application() {
+    let widget = SizedBox::empty().width(40.).height(40.).on_file_drop(
+        |_, files, _, data: &mut String, _| {
+            *data = files[0].to_string_lossy().into();
+        },
+    );
+
+    Harness::create_simple(String::new(), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let drop = |pos: (f64, f64), data: druid_shell::DropData| druid_shell::DropEvent {
+            pos: pos.into(),
+            data,
+            mods: Modifiers::empty(),
+        };
+        let text = druid_shell::DropData {
+            text: Some("text".into()),
+            ..Default::default()
+        };
+        let files = druid_shell::DropData {
+            files: vec!["/tmp/dropped".into()],
+            ..Default::default()
+        };
+
+        // text is not taken, and files are only taken over the target.
+        harness.event(Event::Drop(drop((10., 10.), text).into()));
+        harness.event(Event::Drop(drop((60., 10.), files.clone()).into()));
+        assert!(harness.data().is_empty());
+
+        harness.event(Event::DragOver(drop((10., 10.), files.clone()).into()));
+        harness.event(Event::Drop(drop((10., 10.), files).into()));
+        assert_eq!(harness.data(), "/tmp/dropped");
+    });
+}
+
+#[cfg(test)]
+const REQUEST_TIMER: Selector = Selector::new("druid-tests.request-timer");
+#[cfg(test)]
+const CANCEL_TIMER: Selector = Selector::new("druid-tests.cancel-timer");
+
+#[cfg(test)]
+/// A widget that requests a timer on `REQUEST_TIMER`, cancels it on `CANCEL_TIMER`, and counts
+/// the timer events it receives.
+fn timer_widget(token: Rc<Cell<TimerToken>>, fired: Rc<Cell<usize>>) -> ModularWidget<(), ()> {
+    ModularWidget::new(()).event_fn(move |_, ctx, event, _, _| match event {
+        Event::Command(cmd) if cmd.is(REQUEST_TIMER) => {
+            token.set(ctx.request_timer(Duration::from_secs(10)));
+        }
+        Event::Command(cmd) if cmd.is(CANCEL_TIMER) => {
+            assert!(ctx.timer_remaining(token.get()).is_some());
+            assert!(ctx.cancel_timer(token.get()));
+            assert!(ctx.timer_remaining(token.get()).is_none());
+            assert!(!ctx.cancel_timer(token.get()));
+        }
+        Event::Timer(t) if *t == token.get() => fired.set(fired.get() + 1),
+        _ => (),
+    })
+}
+
+#[test]
+fn timers_are_delivered_once() {
+    let token = Rc::new(Cell::new(TimerToken::INVALID));
+    let fired = Rc::new(Cell::new(0));
+    let widget = timer_widget(token.clone(), fired.clone());
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(REQUEST_TIMER);
+        harness.event(Event::Timer(token.get()));
+        assert_eq!(fired.get(), 1);
+
+        // the window forgets the token once it was routed
+        harness.event(Event::Timer(token.get()));
+        assert_eq!(fired.get(), 1);
+    });
+}
+
+#[test]
+fn cancelled_timers_are_not_delivered() {
+    let token = Rc::new(Cell::new(TimerToken::INVALID));
+    let fired = Rc::new(Cell::new(0));
+    let widget = timer_widget(token.clone(), fired.clone());
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(REQUEST_TIMER);
+        harness.submit_command(CANCEL_TIMER);
+        harness.event(Event::Timer(token.get()));
+        assert_eq!(fired.get(), 0);
+    });
+}
+
+#[test]
+fn timers_of_replaced_widgets_are_not_delivered() {
+    let id = WidgetId::next();
+    let token = Rc::new(Cell::new(TimerToken::INVALID));
+    let old_fired = Rc::new(Cell::new(0));
+    let new_fired = Rc::new(Cell::new(0));
+
+    let new_token = token.clone();
+    let new_fired2 = new_fired.clone();
+    let widget = ReplaceChild::new(
+        timer_widget(token.clone(), old_fired.clone()).with_id(id),
+        move || timer_widget(new_token.clone(), new_fired2.clone()).with_id(id),
+    );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(REQUEST_TIMER);
+
+        // the new widget has the same id, but didn't request the timer
+        harness.submit_command(REPLACE_CHILD);
+        harness.event(Event::Timer(token.get()));
+        assert_eq!(old_fired.get(), 0);
+        assert_eq!(new_fired.get(), 0);
+    });
+}
+
+#[cfg(test)]
+const CAPTURE: Selector<WidgetId> = Selector::new("druid-tests.capture");
+
+#[test]
+fn capture_image_sends_the_widget_painted_offscreen() {
+    let square_id = WidgetId::next();
+    let captured: Rc<RefCell<Vec<(WidgetId, ImageBuf)>>> = Rc::default();
+
+    let square = ModularWidget::new(())
+        .layout_fn(|_, _, _, _, _| Size::new(20.0, 10.0))
+        .paint_fn(|_, ctx, _, _| {
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &Color::rgb8(255, 0, 0));
+        })
+        .with_id(square_id);
+    let captured2 = captured.clone();
+    let widget = ModularWidget::new(WidgetPod::new(square))
+        .event_fn(move |child, ctx, event, data, env| match event {
+            Event::Command(cmd) if cmd.is(CAPTURE) => {
+                ctx.capture_image(*cmd.get_unchecked(CAPTURE), 2.0)
+            }
+            Event::Command(cmd) if cmd.is(commands::IMAGE_CAPTURED) => {
+                let (id, image) = cmd.get_unchecked(commands::IMAGE_CAPTURED);
+                captured2.borrow_mut().push((*id, image.clone()));
+            }
+            _ => child.event(ctx, event, data, env),
+        })
+        .lifecycle_fn(|child, ctx, event, data, env| child.lifecycle(ctx, event, data, env))
+        .update_fn(|child, ctx, _, data, env| child.update(ctx, data, env))
+        .layout_fn(|child, ctx, bc, data, env| {
+            let size = child.layout(ctx, bc, data, env);
+            child.set_origin(ctx, data, env, Point::ORIGIN);
+            size
+        })
+        .paint_fn(|child, ctx, data, env| child.paint(ctx, data, env));
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        assert!(captured.borrow().is_empty());
+
+        // the image is painted, and sent, with the next paint
+        harness.submit_command(CAPTURE.with(square_id));
+        assert!(captured.borrow().is_empty());
+        harness.paint();
+
+        assert_eq!(captured.borrow().len(), 1);
+        let (id, image) = captured.borrow()[0].clone();
+        assert_eq!(id, square_id);
+        assert_eq!((image.width(), image.height()), (40, 20));
+        for row in image.pixel_colors() {
+            for color in row {
+                assert_eq!(color.as_rgba8(), (255, 0, 0, 255));
+            }
+        }
+
+        // only one image is sent per request
+        harness.paint();
+        assert_eq!(captured.borrow().len(), 1);
+    });
+}
+
+#[cfg(test)]
+/// Stroke a 1dp border around (0.3, 0.3, 9.3, 9.3), with the given pixel
+/// snapping policy, and return the alpha of the pixel at (0, 5).
+fn alpha_of_stroke(snapping: PixelSnapping) -> u8 {
+    let widget = ModularWidget::new(())
+        .layout_fn(|_, _, _, _, _| Size::new(10.0, 10.0))
+        .paint_fn(|_, ctx, _, _| {
+            let rect = Rect::new(0.3, 0.3, 9.3, 9.3);
+            ctx.stroke(rect, &Color::BLACK, 1.0);
+        })
+        .env_scope(move |env, _| env.set(theme::PIXEL_SNAPPING, snapping));
+    let mut pixels = None;
+    Harness::create_with_render(
+        (),
+        widget,
+        Size::new(10.0, 10.0),
+        |harness| {
+            harness.send_initial_events();
+            harness.paint();
+        },
+        |target| pixels = Some(target.into_raw()),
+    );
+    pixels.unwrap()[(5 * 10) * 4 + 3]
+}
+
+#[test]
+fn strokes_are_snapped_when_the_policy_asks() {
+    assert_eq!(alpha_of_stroke(PixelSnapping::Strokes), 255);
+    assert!(alpha_of_stroke(PixelSnapping::Fractional) < 255);
+}
+
+#[cfg(test)]
+/// Fill the first column of a 20x10 widget in a horizontal scroll view that is
+/// scrolled by a quarter of a pixel, and return the alpha of the pixel at (0, 5).
+fn alpha_of_scrolled_column(snapping: PixelSnapping) -> u8 {
+    let content = ModularWidget::new(())
+        .layout_fn(|_, _, _, _, _| Size::new(20.0, 10.0))
+        .paint_fn(|_, ctx, _, _| ctx.fill(Rect::new(0.0, 0.0, 1.0, 10.0), &Color::BLACK));
+    let widget = Scroll::new(content)
+        .horizontal()
+        .with_scrollbar_visibility(crate::scroll_component::ScrollbarVisibility::Never)
+        .env_scope(move |env, _| env.set(theme::PIXEL_SNAPPING, snapping));
+    let mut pixels = None;
+    Harness::create_with_render(
+        (),
+        widget,
+        Size::new(10.0, 10.0),
+        |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::Wheel(scroll_mouse((5., 5.), (0.25, 0.0))));
+            harness.paint();
+        },
+        |target| pixels = Some(target.into_raw()),
+    );
+    pixels.unwrap()[(5 * 10) * 4 + 3]
+}
+
+#[test]
+fn layout_is_snapped_in_window_coordinates() {
+    assert_eq!(alpha_of_scrolled_column(PixelSnapping::Layout), 255);
+    assert!(alpha_of_scrolled_column(PixelSnapping::Fractional) < 255);
+}
+
+#[cfg(test)]
+/// A 100x100 widget that counts the long presses on it in its data, and the
+/// mouse-ups it receives in `mouse_ups`.
+fn long_press_widget(mouse_ups: Rc<Cell<usize>>) -> impl Widget<u32> {
+    ModularWidget::new(())
+        .event_fn(move |_, _, event, _, _| {
+            if let Event::MouseUp(_) = event {
+                mouse_ups.set(mouse_ups.get() + 1);
+            }
+        })
+        .layout_fn(|_, _, _, _, _| Size::new(100.0, 100.0))
+        .on_long_press(|_, _, count: &mut u32, _| *count += 1)
+}
+
+#[cfg(test)]
+/// The token of the only timer that the window has pending.
+fn pending_timer<T: Data>(harness: &Harness<'_, T>) -> TimerToken {
+    let timers = &harness.window().timers;
+    assert_eq!(timers.len(), 1);
+    *timers.keys().next().unwrap()
+}
+
+#[test]
+fn long_press_is_recognized_when_the_timer_fires() {
+    let mouse_ups = Rc::new(Cell::new(0));
+    let widget = long_press_widget(mouse_ups.clone());
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let mut mouse = move_mouse((10., 10.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::MouseDown(mouse.clone()));
+        // moving within the slop doesn't cancel the press
+        harness.event(Event::MouseMove(move_mouse((12., 12.))));
+        let timer = pending_timer(harness);
+        harness.event(Event::Timer(timer));
+        assert_eq!(*harness.data(), 1);
+
+        // the child doesn't see the release of a recognized press
+        harness.event(Event::MouseUp(mouse));
+        assert_eq!(mouse_ups.get(), 0);
+    });
+}
+
+#[test]
+fn long_press_is_cancelled_by_moving() {
+    let mouse_ups = Rc::new(Cell::new(0));
+    let widget = long_press_widget(mouse_ups.clone());
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let mut mouse = move_mouse((10., 10.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::MouseDown(mouse.clone()));
+        let timer = pending_timer(harness);
+        harness.event(Event::MouseMove(move_mouse((40., 10.))));
+        harness.event(Event::Timer(timer));
+        assert_eq!(*harness.data(), 0);
+
+        harness.event(Event::MouseUp(mouse));
+        assert_eq!(mouse_ups.get(), 1);
+    });
+}
+
+#[test]
+fn short_press_reaches_the_child() {
+    let mouse_ups = Rc::new(Cell::new(0));
+    let widget = long_press_widget(mouse_ups.clone());
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let mut mouse = move_mouse((10., 10.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::MouseDown(mouse.clone()));
+        let timer = pending_timer(harness);
+        harness.event(Event::MouseUp(mouse));
+        assert_eq!(mouse_ups.get(), 1);
+
+        // a timer that fires after the release is ignored
+        harness.event(Event::Timer(timer));
+        assert_eq!(*harness.data(), 0);
+    });
+}
+
+#[test]
+fn long_press_is_recognized_on_touch() {
+    let mouse_ups = Rc::new(Cell::new(0));
+    let widget = long_press_widget(mouse_ups.clone());
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // the platform also makes mouse events of the primary contact
+        let mut mouse = move_mouse((10., 10.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::TouchDown(touch(0, (10., 10.))));
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::TouchMove(touch(0, (12., 12.))));
+        let timer = pending_timer(harness);
+        harness.event(Event::Timer(timer));
+        assert_eq!(*harness.data(), 1);
+
+        harness.event(Event::TouchUp(touch(0, (12., 12.))));
+        harness.event(Event::MouseUp(mouse));
+        assert_eq!(mouse_ups.get(), 0);
+    });
+}
+
+#[test]
+fn long_press_on_touch_is_cancelled_by_moving() {
+    let mouse_ups = Rc::new(Cell::new(0));
+    let widget = long_press_widget(mouse_ups);
+
+    Harness::create_simple(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.event(Event::TouchDown(touch(1, (10., 10.))));
+        let timer = pending_timer(harness);
+        harness.event(Event::TouchMove(touch(1, (40., 10.))));
+        harness.event(Event::Timer(timer));
+        assert_eq!(*harness.data(), 0);
+    });
+}
+
+#[test]
+fn submitted_key_presses_edit_the_focused_text_box() {
+    let key = |key| ExtInputEvent::KeyDown(KeyEvent::for_test(RawMods::None, key));
+
+    Harness::create_simple(String::new(), TextBox::new(), |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let sink = harness.ext_event_sink();
+        let window_id = harness.window().id;
+
+        // without a focused text field, there's nothing to type into.
+        sink.submit_event(window_id, key("a")).unwrap();
+        assert!(harness.recv_ext_command(Duration::from_secs(1)));
+        assert_eq!(harness.data(), "");
+
+        let mut mouse = move_mouse((5., 5.));
+        mouse.button = MouseButton::Left;
+        harness.event(Event::MouseDown(mouse.clone()));
+        harness.event(Event::MouseUp(mouse));
+
+        sink.submit_event(window_id, key("h")).unwrap();
+        sink.submit_event(window_id, key("i")).unwrap();
+        assert!(harness.recv_ext_command(Duration::from_secs(1)));
+        assert!(harness.recv_ext_command(Duration::from_secs(1)));
+        assert_eq!(harness.data(), "hi");
+    });
+}
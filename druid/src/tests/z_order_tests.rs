@@ -0,0 +1,159 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for widgets raised above their siblings with `set_z_index`.
+
+use test_env_log::test;
+
+use super::*;
+
+const RAISE: Selector<u32> = Selector::new("druid-test.raise");
+const REPAINT: Selector = Selector::new("druid-test.repaint");
+
+/// A red square that counts its paints, and is raised by [`RAISE`].
+fn red_square(paints: Rc<Cell<usize>>) -> impl Widget<()> {
+    ModularWidget::new(paints)
+        .event_fn(|_, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(z_index) = cmd.get(RAISE) {
+                    ctx.set_z_index(*z_index);
+                } else if cmd.is(REPAINT) {
+                    ctx.request_paint();
+                }
+            }
+        })
+        .layout_fn(|_, _, _, _, _| Size::new(100.0, 100.0))
+        .paint_fn(|paints, ctx, _, _| {
+            paints.set(paints.get() + 1);
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &Color::rgb8(255, 0, 0));
+        })
+}
+
+/// A 100x100 widget that places `child` 50 points to the right, so that it
+/// sticks out of the parent into the widget next to it.
+fn offset_parent(child: impl Widget<()> + 'static) -> impl Widget<()> {
+    ModularWidget::new(WidgetPod::new(child))
+        .event_fn(|child, ctx, event, data, env| child.event(ctx, event, data, env))
+        .lifecycle_fn(|child, ctx, event, data, env| child.lifecycle(ctx, event, data, env))
+        .update_fn(|child, ctx, _, data, env| child.update(ctx, data, env))
+        .layout_fn(|child, ctx, bc, data, env| {
+            child.layout(ctx, bc, data, env);
+            child.set_origin(ctx, data, env, Point::new(50.0, 0.0));
+            Size::new(100.0, 100.0)
+        })
+        .paint_fn(|child, ctx, data, env| child.paint(ctx, data, env))
+}
+
+/// The red square in its parent, next to a blue square at x = 100..200.
+fn overlapping(
+    paints: Rc<Cell<usize>>,
+    [square, parent, neighbour]: [WidgetId; 3],
+    neighbour_rec: &Recording,
+) -> impl Widget<()> {
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(offset_parent(red_square(paints).with_id(square)).with_id(parent))
+        .with_child(
+            SizedBox::empty()
+                .fix_size(100.0, 100.0)
+                .background(Color::rgb8(0, 0, 255))
+                .record(neighbour_rec)
+                .with_id(neighbour),
+        )
+}
+
+/// Paint the window, with the square raised to `z_index`, and return the
+/// color at (125, 50), where the square and its neighbour overlap.
+fn color_of_overlap(z_index: u32) -> [u8; 4] {
+    let ids = widget_ids();
+    let widget = overlapping(Rc::default(), ids, &Recording::default());
+    let mut pixels = None;
+    Harness::create_with_render(
+        (),
+        widget,
+        Size::new(200.0, 100.0),
+        |harness| {
+            harness.send_initial_events();
+            harness.submit_command(RAISE.with(z_index).to(ids[0]));
+            harness.paint();
+        },
+        |target| pixels = Some(target.into_raw()),
+    );
+    let pixels = pixels.unwrap();
+    let i = (50 * 200 + 125) * 4;
+    [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+}
+
+#[test]
+fn raised_widget_paints_above_siblings() {
+    assert_eq!(color_of_overlap(0), [0, 0, 255, 255]);
+    assert_eq!(color_of_overlap(1), [255, 0, 0, 255]);
+}
+
+#[test]
+fn raised_widget_gets_the_mouse() {
+    let ids = widget_ids();
+    let [square, parent, neighbour] = ids;
+    let neighbour_rec = Recording::default();
+    let widget = overlapping(Rc::default(), ids, &neighbour_rec);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(RAISE.with(1).to(square));
+        harness.paint();
+        neighbour_rec.clear();
+
+        // The parent is hot as an ancestor, even though the mouse is outside of it.
+        harness.event(Event::MouseMove(move_mouse((125., 50.))));
+        assert!(harness.get_state(square).is_hot);
+        assert!(harness.get_state(parent).is_hot);
+        assert!(!harness.get_state(neighbour).is_hot);
+        assert!(neighbour_rec.is_empty());
+
+        harness.submit_command(RAISE.with(0).to(square));
+        assert!(!harness.get_state(parent).has_raised);
+        harness.paint();
+        neighbour_rec.clear();
+
+        harness.event(Event::MouseMove(move_mouse((125., 50.))));
+        assert!(!harness.get_state(parent).is_hot);
+        assert!(harness.get_state(neighbour).is_hot);
+        assert!(matches!(
+            neighbour_rec.next(),
+            Record::L(LifeCycle::HotChanged(true))
+        ));
+    });
+}
+
+#[test]
+fn raised_widget_reuses_its_layer() {
+    let ids = widget_ids();
+    let paints = Rc::new(Cell::new(0));
+    let widget = overlapping(paints.clone(), ids, &Recording::default());
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(RAISE.with(1).to(ids[0]));
+        harness.paint();
+        assert_eq!(paints.get(), 1);
+
+        harness.paint();
+        assert_eq!(paints.get(), 1);
+
+        harness.submit_command(REPAINT.to(ids[0]));
+        harness.paint();
+        assert_eq!(paints.get(), 2);
+    });
+}
@@ -16,6 +16,7 @@
 
 use std::ops::Range;
 
+use crate::kurbo::Vec2;
 use crate::piet::{Color, FontFamily, FontStyle, FontWeight, TextAttribute as PietAttr};
 use crate::{Command, Env, FontDescriptor, KeyOrValue};
 
@@ -28,6 +29,26 @@ pub struct Link {
     pub command: Command,
 }
 
+/// A shadow drawn beneath text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextShadow {
+    /// The offset of the shadow from the text.
+    pub offset: Vec2,
+    /// The blur radius of the shadow. `0.0` draws a hard shadow.
+    pub blur: f64,
+    /// The color of the shadow.
+    pub color: KeyOrValue<Color>,
+}
+
+/// An outline drawn around each glyph of some text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextOutline {
+    /// The width of the outline.
+    pub width: f64,
+    /// The color of the outline.
+    pub color: KeyOrValue<Color>,
+}
+
 /// A collection of spans of attributes of various kinds.
 #[derive(Debug, Clone, Default)]
 pub struct AttributeSpans {
@@ -38,6 +59,10 @@ pub struct AttributeSpans {
     style: SpanSet<FontStyle>,
     underline: SpanSet<bool>,
     font_descriptor: SpanSet<KeyOrValue<FontDescriptor>>,
+    shadow: SpanSet<TextShadow>,
+    outline: SpanSet<TextOutline>,
+    line_height: SpanSet<KeyOrValue<f64>>,
+    paragraph_spacing: SpanSet<KeyOrValue<f64>>,
 }
 
 /// A set of spans for a given attribute.
@@ -100,6 +125,18 @@ pub enum Attribute {
     Underline(bool),
     /// A [`FontDescriptor`](struct.FontDescriptor.html).
     Descriptor(KeyOrValue<FontDescriptor>),
+    /// A [`TextShadow`].
+    Shadow(TextShadow),
+    /// A [`TextOutline`].
+    Outline(TextOutline),
+    /// The line height, as a multiple of each line's natural height.
+    ///
+    /// A line takes the line height at its start.
+    LineHeight(KeyOrValue<f64>),
+    /// The extra space, in display points, added after a paragraph.
+    ///
+    /// A paragraph takes the spacing at its line break.
+    ParagraphSpacing(KeyOrValue<f64>),
 }
 
 impl Link {
@@ -130,6 +167,130 @@ impl AttributeSpans {
             Attribute::Style(attr) => self.style.add(Span::new(range, attr)),
             Attribute::Underline(attr) => self.underline.add(Span::new(range, attr)),
             Attribute::Descriptor(attr) => self.font_descriptor.add(Span::new(range, attr)),
+            Attribute::Shadow(attr) => self.shadow.add(Span::new(range, attr)),
+            Attribute::Outline(attr) => self.outline.add(Span::new(range, attr)),
+            Attribute::LineHeight(attr) => self.line_height.add(Span::new(range, attr)),
+            Attribute::ParagraphSpacing(attr) => self.paragraph_spacing.add(Span::new(range, attr)),
+        }
+    }
+
+    /// Returns every [`Attribute`] that applies at the byte offset `idx`,
+    /// along with the full range of the span it belongs to.
+    pub fn attributes_at(&self, idx: usize) -> Vec<(Range<usize>, Attribute)> {
+        let mut items = Vec::new();
+        if let Some(s) = self.font_descriptor.span_at(idx) {
+            items.push((s.range.clone(), Attribute::Descriptor(s.attr.clone())));
+        }
+        if let Some(s) = self.family.span_at(idx) {
+            items.push((s.range.clone(), Attribute::FontFamily(s.attr.clone())));
+        }
+        if let Some(s) = self.size.span_at(idx) {
+            items.push((s.range.clone(), Attribute::FontSize(s.attr.clone())));
+        }
+        if let Some(s) = self.weight.span_at(idx) {
+            items.push((s.range.clone(), Attribute::Weight(s.attr)));
+        }
+        if let Some(s) = self.fg_color.span_at(idx) {
+            items.push((s.range.clone(), Attribute::TextColor(s.attr.clone())));
+        }
+        if let Some(s) = self.style.span_at(idx) {
+            items.push((s.range.clone(), Attribute::Style(s.attr)));
+        }
+        if let Some(s) = self.underline.span_at(idx) {
+            items.push((s.range.clone(), Attribute::Underline(s.attr)));
+        }
+        if let Some(s) = self.shadow.span_at(idx) {
+            items.push((s.range.clone(), Attribute::Shadow(s.attr.clone())));
+        }
+        if let Some(s) = self.outline.span_at(idx) {
+            items.push((s.range.clone(), Attribute::Outline(s.attr.clone())));
+        }
+        if let Some(s) = self.line_height.span_at(idx) {
+            items.push((s.range.clone(), Attribute::LineHeight(s.attr.clone())));
+        }
+        if let Some(s) = self.paragraph_spacing.span_at(idx) {
+            items.push((s.range.clone(), Attribute::ParagraphSpacing(s.attr.clone())));
+        }
+        items
+    }
+
+    /// The ranges that have a [`TextShadow`].
+    pub(crate) fn shadows(&self) -> impl Iterator<Item = (Range<usize>, &TextShadow)> {
+        self.shadow.iter().map(|s| (s.range.clone(), &s.attr))
+    }
+
+    /// The ranges that have a [`TextOutline`].
+    pub(crate) fn outlines(&self) -> impl Iterator<Item = (Range<usize>, &TextOutline)> {
+        self.outline.iter().map(|s| (s.range.clone(), &s.attr))
+    }
+
+    /// The line height at the byte offset `idx`, if it has one.
+    pub(crate) fn line_height_at(&self, idx: usize) -> Option<&KeyOrValue<f64>> {
+        self.line_height.span_at(idx).map(|s| &s.attr)
+    }
+
+    /// The paragraph spacing at the byte offset `idx`, if it has one.
+    pub(crate) fn paragraph_spacing_at(&self, idx: usize) -> Option<&KeyOrValue<f64>> {
+        self.paragraph_spacing.span_at(idx).map(|s| &s.attr)
+    }
+
+    /// Returns `true` if all of `range` has a bold (or heavier) [`FontWeight`].
+    ///
+    /// `range` must not be empty.
+    pub(crate) fn is_bold(&self, range: Range<usize>) -> bool {
+        self.weight
+            .covers(range, |w| w.to_raw() >= FontWeight::SEMI_BOLD.to_raw())
+    }
+
+    /// Returns `true` if all of `range` has an italic or oblique [`FontStyle`].
+    ///
+    /// `range` must not be empty.
+    pub(crate) fn is_italic(&self, range: Range<usize>) -> bool {
+        self.style.covers(range, |s| *s != FontStyle::Regular)
+    }
+
+    /// Returns `true` if all of `range` is underlined.
+    ///
+    /// `range` must not be empty.
+    pub(crate) fn is_underlined(&self, range: Range<usize>) -> bool {
+        self.underline.covers(range, |u| *u)
+    }
+
+    /// Returns the text color that applies to all of `range`, if there is one.
+    ///
+    /// `range` must not be empty.
+    pub(crate) fn text_color(&self, range: Range<usize>) -> Option<&KeyOrValue<Color>> {
+        self.fg_color.uniform(range)
+    }
+
+    /// Update the spans to reflect an edit of the underlying text.
+    ///
+    /// `changed` is the range of the text that was replaced, and `new_len` is
+    /// the length of the text that replaced it. Inserted text takes on the
+    /// attributes of the text that precedes it, or if the insertion is at
+    /// the start of the text, of the text that follows it.
+    pub(crate) fn edit(&mut self, changed: Range<usize>, new_len: usize) {
+        let inherited = match changed.start {
+            _ if new_len == 0 => Vec::new(),
+            0 => self.attributes_at(changed.end),
+            start => self.attributes_at(start - 1),
+        };
+
+        self.family.edit(changed.clone(), new_len);
+        self.size.edit(changed.clone(), new_len);
+        self.weight.edit(changed.clone(), new_len);
+        self.fg_color.edit(changed.clone(), new_len);
+        self.style.edit(changed.clone(), new_len);
+        self.underline.edit(changed.clone(), new_len);
+        self.font_descriptor.edit(changed.clone(), new_len);
+        self.shadow.edit(changed.clone(), new_len);
+        self.outline.edit(changed.clone(), new_len);
+        self.line_height.edit(changed.clone(), new_len);
+        self.paragraph_spacing.edit(changed.clone(), new_len);
+
+        let inserted = changed.start..changed.start + new_len;
+        for (_, attr) in inherited {
+            self.add(inserted.clone(), attr);
         }
     }
 
@@ -186,6 +347,11 @@ impl<T: Clone> SpanSet<T> {
         self.spans.iter()
     }
 
+    /// Returns the span containing `idx`, if any.
+    fn span_at(&self, idx: usize) -> Option<&Span<T>> {
+        self.spans.iter().find(|span| span.range.contains(&idx))
+    }
+
     /// Add a `Span` to this `SpanSet`.
     ///
     /// Spans can be added in any order. existing spans will be updated
@@ -239,10 +405,8 @@ impl<T: Clone> SpanSet<T> {
     /// `new_len` is the length of the inserted text.
     //TODO: we could be smarter here about just extending the existing spans
     //as requred for insertions in the interior of a span.
-    //TODO: this isn't currently used; it should be used if we use spans with
-    //some editable type.
     // the branches are much more readable without sharing code
-    #[allow(dead_code, clippy::branches_sharing_code)]
+    #[allow(clippy::branches_sharing_code)]
     fn edit(&mut self, changed: Range<usize>, new_len: usize) {
         let old_len = changed.len();
         let mut to_insert = None;
@@ -290,6 +454,42 @@ impl<T: Clone> SpanSet<T> {
     }
 }
 
+impl<T: Clone + PartialEq> SpanSet<T> {
+    /// Returns the attribute that applies to all of `range`, if there is one.
+    ///
+    /// `range` must not be empty.
+    fn uniform(&self, range: Range<usize>) -> Option<&T> {
+        let first = self.span_at(range.start)?;
+        if self.covers(range, |attr| attr == &first.attr) {
+            Some(&first.attr)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> SpanSet<T> {
+    /// Returns `true` if every position in `range` is covered by a span
+    /// whose attribute satisfies `f`.
+    ///
+    /// `range` must not be empty.
+    fn covers(&self, range: Range<usize>, f: impl Fn(&T) -> bool) -> bool {
+        let mut covered_to = range.start;
+        for span in self
+            .spans
+            .iter()
+            .skip_while(|span| span.range.end <= range.start)
+            .take_while(|span| span.range.start < range.end)
+        {
+            if span.range.start > covered_to || !f(&span.attr) {
+                return false;
+            }
+            covered_to = span.range.end;
+        }
+        covered_to >= range.end
+    }
+}
+
 impl<T> Span<T> {
     fn new(range: Range<usize>, attr: T) -> Self {
         Span { range, attr }
@@ -335,6 +535,47 @@ impl Attribute {
     pub fn font_descriptor(font: impl Into<KeyOrValue<FontDescriptor>>) -> Self {
         Attribute::Descriptor(font.into())
     }
+
+    /// Create a new shadow attribute.
+    pub fn shadow(offset: impl Into<Vec2>, blur: f64, color: impl Into<KeyOrValue<Color>>) -> Self {
+        Attribute::Shadow(TextShadow::new(offset, blur, color))
+    }
+
+    /// Create a new outline attribute.
+    pub fn outline(width: f64, color: impl Into<KeyOrValue<Color>>) -> Self {
+        Attribute::Outline(TextOutline::new(width, color))
+    }
+
+    /// Create a new line height attribute.
+    pub fn line_height(line_height: impl Into<KeyOrValue<f64>>) -> Self {
+        Attribute::LineHeight(line_height.into())
+    }
+
+    /// Create a new paragraph spacing attribute.
+    pub fn paragraph_spacing(spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        Attribute::ParagraphSpacing(spacing.into())
+    }
+}
+
+impl TextShadow {
+    /// Create a new `TextShadow`.
+    pub fn new(offset: impl Into<Vec2>, blur: f64, color: impl Into<KeyOrValue<Color>>) -> Self {
+        TextShadow {
+            offset: offset.into(),
+            blur: blur.max(0.0),
+            color: color.into(),
+        }
+    }
+}
+
+impl TextOutline {
+    /// Create a new `TextOutline`.
+    pub fn new(width: f64, color: impl Into<KeyOrValue<Color>>) -> Self {
+        TextOutline {
+            width: width.max(0.0),
+            color: color.into(),
+        }
+    }
 }
 
 impl<T> Default for SpanSet<T> {
@@ -364,6 +605,37 @@ mod tests {
         assert_eq!(&spans.spans, &vec![Span::new(0..5, 3), Span::new(5..20, 4)]);
     }
 
+    #[test]
+    fn attributes_at() {
+        let mut spans = AttributeSpans::new();
+        spans.add(0..5, Attribute::underline(true));
+        spans.add(3..8, Attribute::weight(FontWeight::BOLD));
+
+        assert_eq!(spans.attributes_at(1).len(), 1);
+        let at_four = spans.attributes_at(4);
+        assert_eq!(at_four.len(), 2);
+        assert!(matches!(at_four[0], (ref r, Attribute::Weight(_)) if *r == (3..8)));
+        assert!(matches!(at_four[1], (ref r, Attribute::Underline(true)) if *r == (0..5)));
+        assert!(spans.attributes_at(8).is_empty());
+    }
+
+    #[test]
+    fn line_height_and_paragraph_spacing() {
+        let mut spans = AttributeSpans::new();
+        spans.add(0..6, Attribute::line_height(1.5));
+        spans.add(4..6, Attribute::paragraph_spacing(8.0));
+
+        assert_eq!(spans.line_height_at(2), Some(&KeyOrValue::Concrete(1.5)));
+        assert_eq!(spans.line_height_at(6), None);
+        assert_eq!(spans.paragraph_spacing_at(3), None);
+        assert_eq!(
+            spans.paragraph_spacing_at(5),
+            Some(&KeyOrValue::Concrete(8.0))
+        );
+        // they aren't piet attributes
+        assert!(spans.to_piet_attrs(&Env::empty()).is_empty());
+    }
+
     #[test]
     fn edit_spans() {
         let mut spans = SpanSet::<u32>::default();
@@ -70,20 +70,24 @@ pub trait EditableText: Sized {
 
     /// Construct an instance of this type from a `&str`.
     fn from_str(s: &str) -> Self;
+
+    /// If this text is the result of a single edit to `previous`, returns
+    /// the range of `previous` that was replaced, and the length of the text
+    /// that replaced it.
+    ///
+    /// This is used to keep the selection of an editor in place when its
+    /// text is changed by something else, such as a collaborator or a plugin.
+    /// The default implementation returns `None`, in which case the selection
+    /// is only clamped to the new text.
+    #[allow(unused_variables)]
+    fn changed_since(&self, previous: &Self) -> Option<(Range<usize>, usize)> {
+        None
+    }
 }
 
 impl EditableText for String {
     fn cursor<'a>(&self, position: usize) -> Option<StringCursor> {
-        let new_cursor = StringCursor {
-            text: self,
-            position,
-        };
-
-        if new_cursor.is_boundary() {
-            Some(new_cursor)
-        } else {
-            None
-        }
+        cursor(self, position)
     }
 
     fn edit(&mut self, range: Range<usize>, new: impl Into<String>) {
@@ -99,57 +103,27 @@ impl EditableText for String {
     }
 
     fn prev_grapheme_offset(&self, from: usize) -> Option<usize> {
-        let mut c = GraphemeCursor::new(from, self.len(), true);
-        c.prev_boundary(self, 0).unwrap()
+        prev_grapheme_offset(self, from)
     }
 
     fn next_grapheme_offset(&self, from: usize) -> Option<usize> {
-        let mut c = GraphemeCursor::new(from, self.len(), true);
-        c.next_boundary(self, 0).unwrap()
+        next_grapheme_offset(self, from)
     }
 
     fn prev_codepoint_offset(&self, from: usize) -> Option<usize> {
-        let mut c = self.cursor(from).unwrap();
-        c.prev()
+        prev_codepoint_offset(self, from)
     }
 
     fn next_codepoint_offset(&self, from: usize) -> Option<usize> {
-        let mut c = self.cursor(from).unwrap();
-        if c.next().is_some() {
-            Some(c.pos())
-        } else {
-            None
-        }
+        next_codepoint_offset(self, from)
     }
 
     fn prev_word_offset(&self, from: usize) -> Option<usize> {
-        let mut offset = from;
-        let mut passed_alphanumeric = false;
-        for prev_grapheme in self.get(0..from)?.graphemes(true).rev() {
-            let is_alphanumeric = prev_grapheme.chars().next()?.is_alphanumeric();
-            if is_alphanumeric {
-                passed_alphanumeric = true;
-            } else if passed_alphanumeric {
-                return Some(offset);
-            }
-            offset -= prev_grapheme.len();
-        }
-        None
+        prev_word_offset(self, from)
     }
 
     fn next_word_offset(&self, from: usize) -> Option<usize> {
-        let mut offset = from;
-        let mut passed_alphanumeric = false;
-        for next_grapheme in self.get(from..)?.graphemes(true) {
-            let is_alphanumeric = next_grapheme.chars().next()?.is_alphanumeric();
-            if is_alphanumeric {
-                passed_alphanumeric = true;
-            } else if passed_alphanumeric {
-                return Some(offset);
-            }
-            offset += next_grapheme.len();
-        }
-        Some(self.len())
+        next_word_offset(self, from)
     }
 
     fn is_empty(&self) -> bool {
@@ -161,30 +135,119 @@ impl EditableText for String {
     }
 
     fn preceding_line_break(&self, from: usize) -> usize {
-        let mut offset = from;
+        preceding_line_break(self, from)
+    }
 
-        for byte in self.get(0..from).unwrap_or("").bytes().rev() {
-            if byte == 0x0a {
-                return offset;
-            }
-            offset -= 1;
+    fn next_line_break(&self, from: usize) -> usize {
+        next_line_break(self, from)
+    }
+}
+
+/// Map an offset in some text to the equivalent offset after `range` was
+/// replaced with text of length `new_len`.
+///
+/// Offsets inside the replaced range are moved to the end of the new text.
+pub(crate) fn transform_offset(offset: usize, range: &Range<usize>, new_len: usize) -> usize {
+    if offset <= range.start {
+        offset
+    } else if offset >= range.end {
+        offset - range.len() + new_len
+    } else {
+        range.start + new_len
+    }
+}
+
+// The implementations of the navigation methods, shared by the `EditableText`
+// impls of the various types backed by a contiguous `str`.
+
+pub(super) fn cursor(text: &str, position: usize) -> Option<StringCursor<'_>> {
+    let new_cursor = StringCursor { text, position };
+
+    if new_cursor.is_boundary() {
+        Some(new_cursor)
+    } else {
+        None
+    }
+}
+
+pub(super) fn prev_grapheme_offset(text: &str, from: usize) -> Option<usize> {
+    let mut c = GraphemeCursor::new(from, text.len(), true);
+    c.prev_boundary(text, 0).unwrap()
+}
+
+pub(super) fn next_grapheme_offset(text: &str, from: usize) -> Option<usize> {
+    let mut c = GraphemeCursor::new(from, text.len(), true);
+    c.next_boundary(text, 0).unwrap()
+}
+
+pub(super) fn prev_codepoint_offset(text: &str, from: usize) -> Option<usize> {
+    let mut c = cursor(text, from).unwrap();
+    c.prev()
+}
+
+pub(super) fn next_codepoint_offset(text: &str, from: usize) -> Option<usize> {
+    let mut c = cursor(text, from).unwrap();
+    if c.next().is_some() {
+        Some(c.pos())
+    } else {
+        None
+    }
+}
+
+pub(super) fn prev_word_offset(text: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+    let mut passed_alphanumeric = false;
+    for prev_grapheme in text.get(0..from)?.graphemes(true).rev() {
+        let is_alphanumeric = prev_grapheme.chars().next()?.is_alphanumeric();
+        if is_alphanumeric {
+            passed_alphanumeric = true;
+        } else if passed_alphanumeric {
+            return Some(offset);
         }
+        offset -= prev_grapheme.len();
+    }
+    None
+}
 
-        0
+pub(super) fn next_word_offset(text: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+    let mut passed_alphanumeric = false;
+    for next_grapheme in text.get(from..)?.graphemes(true) {
+        let is_alphanumeric = next_grapheme.chars().next()?.is_alphanumeric();
+        if is_alphanumeric {
+            passed_alphanumeric = true;
+        } else if passed_alphanumeric {
+            return Some(offset);
+        }
+        offset += next_grapheme.len();
     }
+    Some(text.len())
+}
 
-    fn next_line_break(&self, from: usize) -> usize {
-        let mut offset = from;
+pub(super) fn preceding_line_break(text: &str, from: usize) -> usize {
+    let mut offset = from;
 
-        for char in self.get(from..).unwrap_or("").bytes() {
-            if char == 0x0a {
-                return offset;
-            }
-            offset += 1;
+    for byte in text.get(0..from).unwrap_or("").bytes().rev() {
+        if byte == 0x0a {
+            return offset;
         }
+        offset -= 1;
+    }
 
-        self.len()
+    0
+}
+
+pub(super) fn next_line_break(text: &str, from: usize) -> usize {
+    let mut offset = from;
+
+    for char in text.get(from..).unwrap_or("").bytes() {
+        if char == 0x0a {
+            return offset;
+        }
+        offset += 1;
     }
+
+    text.len()
 }
 
 impl EditableText for Arc<String> {
@@ -462,6 +525,16 @@ mod tests {
         assert_eq!(None, a.next_grapheme_offset(17));
     }
 
+    #[test]
+    fn grapheme_offset_emoji_sequences() {
+        // thumbs up + skin tone modifier, then a ZWJ family sequence
+        let a = String::from("\u{1F44D}\u{1F3FD}\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}");
+        assert_eq!(Some(8), a.next_grapheme_offset(0));
+        assert_eq!(Some(26), a.next_grapheme_offset(8));
+        assert_eq!(Some(8), a.prev_grapheme_offset(26));
+        assert_eq!(Some(0), a.prev_grapheme_offset(8));
+    }
+
     #[test]
     fn prev_word_offset() {
         let a = String::from("Technically a word: ৬藏A\u{030a}\u{110b}\u{1161}");
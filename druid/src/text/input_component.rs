@@ -21,13 +21,13 @@ use std::sync::{Arc, Weak};
 use tracing::instrument;
 
 use super::{
-    EditableText, ImeHandlerRef, ImeInvalidation, InputHandler, Movement, Selection, TextAction,
-    TextLayout, TextStorage,
+    editable_text, ArrowKeyMode, EditableText, ImeHandlerRef, ImeInvalidation, InputHandler,
+    InputHints, Movement, Selection, TextAction, TextLayout, TextStorage, VerticalMovement,
 };
 use crate::kurbo::{Line, Point, Rect, Vec2};
 use crate::piet::TextLayout as _;
 use crate::widget::prelude::*;
-use crate::{text, theme, Cursor, Env, Modifiers, Selector, TextAlignment, UpdateCtx};
+use crate::{text, theme, Cursor, Env, Modifiers, Selector, ShellError, TextAlignment, UpdateCtx};
 
 /// A widget that accepts text input.
 ///
@@ -95,9 +95,13 @@ pub struct EditSession<T> {
     /// notification when the user cancels editing.
     pub send_notification_on_cancel: bool,
     selection: Selection,
+    /// Carets or selections in addition to `selection`, in no particular order.
+    extra_selections: Vec<Selection>,
     accepts_newlines: bool,
     accepts_tabs: bool,
     alignment: TextAlignment,
+    arrow_key_mode: ArrowKeyMode,
+    input_hints: InputHints,
     /// The y-position of the text when it does not fill our width.
     alignment_offset: f64,
     /// The portion of the text that is currently marked by the IME.
@@ -350,6 +354,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                     self.borrow_mut().selection = selection;
                 }
                 ctx.request_update();
+                ctx.request_paint();
             }
             _ => (),
         }
@@ -459,6 +464,12 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
         let selection = self.borrow().selection();
         let composition = self.borrow().composition_range();
         let sel_rects = self.borrow().layout.rects_for_range(selection.range());
+        let extra_rects = self
+            .borrow()
+            .extra_selections
+            .iter()
+            .flat_map(|sel| self.borrow().layout.rects_for_range(sel.range()))
+            .collect::<Vec<_>>();
         if let Some(composition) = composition {
             // I believe selection should always be contained in composition range while composing?
             assert!(composition.start <= selection.anchor && composition.end >= selection.active);
@@ -474,7 +485,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                 ctx.stroke(line, &cursor_color, 2.0);
             }
         } else {
-            for region in sel_rects {
+            for region in sel_rects.into_iter().chain(extra_rects) {
                 let rounded = (region + text_offset).to_rounded_rect(1.0);
                 ctx.fill(rounded, &selection_color);
             }
@@ -489,14 +500,34 @@ impl<T> EditSession<T> {
         self.selection
     }
 
+    /// Any carets or selections in addition to the primary [`selection`].
+    ///
+    /// Edits and cursor movements apply to every selection at once; the
+    /// platform only knows about the primary one.
+    ///
+    /// [`selection`]: EditSession::selection
+    pub fn extra_selections(&self) -> &[Selection] {
+        &self.extra_selections
+    }
+
+    /// Removes all but the primary selection.
+    pub fn clear_extra_selections(&mut self) {
+        self.extra_selections.clear();
+    }
+
     /// Manually set the selection.
     ///
+    /// This also removes any [extra selections].
+    ///
     /// If the new selection is different from the current selection, this
     /// will return an ime event that the controlling widget should use to
     /// invalidte the platform's IME state, by passing it to
     /// [`EventCtx::invalidate_text_input`].
+    ///
+    /// [extra selections]: EditSession::extra_selections
     #[must_use]
     pub fn set_selection(&mut self, selection: Selection) -> Option<ImeInvalidation> {
+        self.extra_selections.clear();
         if selection != self.selection {
             self.selection = selection;
             self.update_pending_invalidation(ImeInvalidation::SelectionChanged);
@@ -519,7 +550,8 @@ impl<T> EditSession<T> {
     /// Set the text alignment.
     ///
     /// This is only meaningful for single-line text that does not fill
-    /// the minimum layout size.
+    /// the minimum layout size. `TextAlignment::Start` and `TextAlignment::End`
+    /// are resolved based on the direction of the text.
     pub fn set_text_alignment(&mut self, alignment: TextAlignment) {
         self.alignment = alignment;
     }
@@ -529,6 +561,30 @@ impl<T> EditSession<T> {
         self.alignment
     }
 
+    /// Set how the left and right arrow keys move the caret in bidirectional text.
+    pub fn set_arrow_key_mode(&mut self, mode: ArrowKeyMode) {
+        self.arrow_key_mode = mode;
+    }
+
+    /// The current [`ArrowKeyMode`].
+    pub fn arrow_key_mode(&self) -> ArrowKeyMode {
+        self.arrow_key_mode
+    }
+
+    /// Set the hints about the expected text that are given to the platform's
+    /// input method.
+    pub fn set_input_hints(&mut self, hints: InputHints) {
+        if hints != self.input_hints {
+            self.input_hints = hints;
+            self.pending_ime_invalidation = Some(ImeInvalidation::Reset);
+        }
+    }
+
+    /// The current [`InputHints`].
+    pub fn input_hints(&self) -> InputHints {
+        self.input_hints
+    }
+
     /// Returns any invalidation action that should be passed to the platform.
     ///
     /// The user of this component *must* check this after calling `update`.
@@ -570,8 +626,11 @@ impl<T> EditSession<T> {
     }
 
     fn update_alignment_offset(&mut self, extra_width: f64) {
+        let text_is_rtl = self.layout.text_is_rtl();
         self.alignment_offset = match self.alignment {
+            TextAlignment::Start | TextAlignment::Justified if text_is_rtl => extra_width,
             TextAlignment::Start | TextAlignment::Justified => 0.0,
+            TextAlignment::End if text_is_rtl => 0.0,
             TextAlignment::End => extra_width,
             TextAlignment::Center => extra_width / 2.0,
         };
@@ -585,29 +644,174 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     /// text state, by calling [`EventCtx::invalidate_text_input`].
     #[must_use]
     pub fn insert_text(&mut self, data: &mut T, new_text: &str) -> ImeInvalidation {
-        let new_cursor_pos = self.selection.min() + new_text.len();
-        data.edit(self.selection.range(), new_text);
-        self.selection = Selection::caret(new_cursor_pos);
+        let ranges = self.selections().map(|sel| sel.range()).collect();
+        let new_selection = self.replace_ranges(data, ranges, new_text);
+        // the selection already reflects this edit, so `update` shouldn't move it.
+        self.layout.set_text(data.clone());
+        self.selection = new_selection;
         self.scroll_to_selection_end(true);
         ImeInvalidation::Reset
     }
 
     /// Sets the clipboard to the contents of the current selection.
     ///
-    /// Returns `true` if the clipboard was set, and `false` if not (indicating)
-    /// that the selection was empty.)
-    pub fn set_clipboard(&self) -> bool {
-        if let Some(text) = self
-            .layout
-            .text()
-            .and_then(|txt| txt.slice(self.selection.range()))
-        {
-            if !text.is_empty() {
-                crate::Application::global().clipboard().put_string(text);
-                return true;
+    /// With [extra selections], their contents are joined by newlines, in
+    /// the order they appear in the text.
+    ///
+    /// Returns `Ok(true)` if the clipboard was set, and `Ok(false)` if not
+    /// (indicating that the selection was empty.)
+    ///
+    /// # Errors
+    ///
+    /// Errors if the system clipboard could not be set.
+    ///
+    /// [extra selections]: EditSession::extra_selections
+    pub fn set_clipboard(&self) -> Result<bool, ShellError> {
+        let txt = match self.layout.text() {
+            Some(txt) => txt,
+            None => return Ok(false),
+        };
+        let mut selections = self
+            .selections()
+            .filter(|sel| !sel.is_caret())
+            .collect::<Vec<_>>();
+        selections.sort_by_key(|sel| sel.min());
+        let text = selections
+            .iter()
+            .filter_map(|sel| txt.slice(sel.range()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() {
+            crate::Application::global().clipboard().put_string(text)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Adds a caret on the line above the topmost caret.
+    ///
+    /// The new caret becomes the primary selection. Returns the invalidation
+    /// to pass to [`EventCtx::invalidate_text_input`], or `None` if there is
+    /// no line above.
+    #[must_use]
+    pub fn add_cursor_above(&mut self) -> Option<ImeInvalidation> {
+        self.add_cursor_vertically(VerticalMovement::LineUp)
+    }
+
+    /// Adds a caret on the line below the bottommost caret.
+    ///
+    /// The new caret becomes the primary selection. Returns the invalidation
+    /// to pass to [`EventCtx::invalidate_text_input`], or `None` if there is
+    /// no line below.
+    #[must_use]
+    pub fn add_cursor_below(&mut self) -> Option<ImeInvalidation> {
+        self.add_cursor_vertically(VerticalMovement::LineDown)
+    }
+
+    /// Selects the word under a caret, or adds a selection for the next
+    /// occurrence of the selected text.
+    ///
+    /// The search starts after the last selection and wraps around to the
+    /// start of the text; the new selection becomes the primary selection.
+    /// Returns the invalidation to pass to [`EventCtx::invalidate_text_input`],
+    /// or `None` if nothing was selected.
+    #[must_use]
+    pub fn select_next_occurrence(&mut self) -> Option<ImeInvalidation> {
+        if self.selection.is_caret() {
+            let word = self.word_for_pos(self.selection.active);
+            if word.is_empty() {
+                return None;
             }
+            self.selection = Selection::new(word.start, word.end);
+            return Some(ImeInvalidation::SelectionChanged);
         }
-        false
+        let taken = self.selections().map(|sel| sel.range()).collect::<Vec<_>>();
+        let from = taken.iter().map(|range| range.end).max()?;
+        let text = self.layout.text()?;
+        let needle = text.slice(self.selection.range())?;
+        let found = find_next_occurrence(text.as_str(), &needle, from, &taken)?;
+        self.extra_selections.push(self.selection);
+        self.selection = Selection::new(found.start, found.end);
+        self.scroll_to_selection_end(false);
+        Some(ImeInvalidation::SelectionChanged)
+    }
+
+    fn add_cursor_vertically(&mut self, movement: VerticalMovement) -> Option<ImeInvalidation> {
+        let layout = self.layout.layout()?;
+        let from = match movement {
+            VerticalMovement::LineDown => self.selections().max_by_key(|sel| sel.active),
+            _ => self.selections().min_by_key(|sel| sel.active),
+        }?;
+        let moved = text::movement_with_mode(
+            Movement::Vertical(movement),
+            Selection::caret(from.active).with_h_pos(from.h_pos),
+            &self.layout,
+            false,
+            self.arrow_key_mode,
+        );
+        let from_line = layout.hit_test_text_position(from.active).line;
+        let to_line = layout.hit_test_text_position(moved.active).line;
+        if from_line == to_line || self.selections().any(|sel| sel.active == moved.active) {
+            return None;
+        }
+        self.extra_selections.push(self.selection);
+        self.selection = moved;
+        self.scroll_to_selection_end(false);
+        Some(ImeInvalidation::SelectionChanged)
+    }
+
+    /// The primary selection, followed by any extra selections.
+    fn selections(&self) -> impl Iterator<Item = Selection> + '_ {
+        std::iter::once(self.selection).chain(self.extra_selections.iter().copied())
+    }
+
+    /// Replaces one range per selection with `text`, leaving a caret after
+    /// each replacement.
+    ///
+    /// `ranges` are in the order of [`selections`]; overlapping ranges are
+    /// merged so no text is replaced twice. The extra selections are updated
+    /// in place, and the new primary selection is returned.
+    ///
+    /// [`selections`]: EditSession::selections
+    fn replace_ranges(
+        &mut self,
+        buffer: &mut T,
+        ranges: Vec<Range<usize>>,
+        text: &str,
+    ) -> Selection {
+        let mut ranges = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(i, range)| (range, i == 0))
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|(range, _)| (range.start, range.end));
+        let mut merged: Vec<(Range<usize>, bool)> = Vec::with_capacity(ranges.len());
+        for (range, is_primary) in ranges {
+            match merged.last_mut() {
+                Some((last, last_is_primary))
+                    if range.start < last.end
+                        || (range.start == last.end
+                            && (range.start == range.end || last.start == last.end)) =>
+                {
+                    last.end = last.end.max(range.end);
+                    *last_is_primary |= is_primary;
+                }
+                _ => merged.push((range, is_primary)),
+            }
+        }
+        let (ranges, is_primary): (Vec<_>, Vec<_>) = merged.into_iter().unzip();
+        let carets = replace_sorted_ranges(buffer, &ranges, text);
+
+        let mut primary = Selection::caret(0);
+        self.extra_selections.clear();
+        for (caret, is_primary) in carets.into_iter().zip(is_primary) {
+            if is_primary {
+                primary = Selection::caret(caret);
+            } else {
+                self.extra_selections.push(Selection::caret(caret));
+            }
+        }
+        primary
     }
 
     fn scroll_to_selection_end(&mut self, after_edit: bool) {
@@ -617,20 +821,22 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     fn do_action(&mut self, buffer: &mut T, action: TextAction) {
         match action {
             TextAction::Move(movement) => {
-                let sel = text::movement(movement, self.selection, &self.layout, false);
+                let sel = self.move_selections(movement, false);
                 self.external_selection_change = Some(sel);
                 self.scroll_to_selection_end(false);
             }
             TextAction::MoveSelecting(movement) => {
-                let sel = text::movement(movement, self.selection, &self.layout, true);
+                let sel = self.move_selections(movement, true);
                 self.external_selection_change = Some(sel);
                 self.scroll_to_selection_end(false);
             }
             TextAction::SelectAll => {
                 let len = buffer.len();
+                self.extra_selections.clear();
                 self.external_selection_change = Some(Selection::new(0, len));
             }
             TextAction::SelectWord => {
+                self.extra_selections.clear();
                 if self.selection.is_caret() {
                     let range =
                         text::movement::word_range_for_pos(buffer.as_str(), self.selection.active);
@@ -647,20 +853,29 @@ impl<T: TextStorage + EditableText> EditSession<T> {
                 if !self.selection.is_caret() || buffer.len() < self.selection.active {
                     return;
                 }
+                self.extra_selections.clear();
                 let prev = buffer.preceding_line_break(self.selection.active);
                 let next = buffer.next_line_break(self.selection.active);
                 self.external_selection_change = Some(Selection::new(prev, next));
             }
-            TextAction::Delete(movement) if self.selection.is_caret() => {
-                if movement == Movement::Grapheme(druid_shell::text::Direction::Upstream) {
-                    self.backspace(buffer);
-                } else {
-                    let to_delete = text::movement(movement, self.selection, &self.layout, true);
-                    self.selection = to_delete;
-                    self.ime_insert_text(buffer, "")
-                }
+            TextAction::Delete(Movement::Grapheme(druid_shell::text::Direction::Upstream)) => {
+                self.backspace(buffer);
+            }
+            TextAction::Delete(movement) => {
+                let ranges = self
+                    .selections()
+                    .map(|sel| {
+                        if sel.is_caret() {
+                            text::movement(movement, sel, &self.layout, true).range()
+                        } else {
+                            sel.range()
+                        }
+                    })
+                    .collect();
+                let sel = self.replace_ranges(buffer, ranges, "");
+                self.external_selection_change = Some(sel);
+                self.scroll_to_selection_end(true);
             }
-            TextAction::Delete(_) => self.ime_insert_text(buffer, ""),
             TextAction::DecomposingBackspace => {
                 tracing::warn!("Decomposing Backspace is not implemented");
                 self.backspace(buffer);
@@ -694,6 +909,9 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             }
             TextAction::InsertSingleQuoteIgnoringSmartQuotes => self.ime_insert_text(buffer, "'"),
             TextAction::InsertDoubleQuoteIgnoringSmartQuotes => self.ime_insert_text(buffer, "\""),
+            TextAction::Cancel if !self.extra_selections.is_empty() => {
+                self.extra_selections.clear();
+            }
             TextAction::Cancel if self.send_notification_on_cancel => {
                 self.external_action = Some(action)
             }
@@ -705,27 +923,62 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     ///
     /// This should only be called from the IME.
     fn ime_insert_text(&mut self, buffer: &mut T, text: &str) {
-        let new_cursor_pos = self.selection.min() + text.len();
-        buffer.edit(self.selection.range(), text);
-        self.external_selection_change = Some(Selection::caret(new_cursor_pos));
+        let ranges = self.selections().map(|sel| sel.range()).collect();
+        let sel = self.replace_ranges(buffer, ranges, text);
+        self.external_selection_change = Some(sel);
         self.scroll_to_selection_end(true);
     }
 
     fn backspace(&mut self, buffer: &mut T) {
-        let to_del = if self.selection.is_caret() {
-            let del_start = text::offset_for_delete_backwards(&self.selection, buffer);
-            del_start..self.selection.anchor
-        } else {
-            self.selection.range()
-        };
-        self.external_selection_change = Some(Selection::caret(to_del.start));
-        buffer.edit(to_del, "");
+        let ranges = self
+            .selections()
+            .map(|sel| {
+                if sel.is_caret() {
+                    text::offset_for_delete_backwards(&sel, buffer)..sel.anchor
+                } else {
+                    sel.range()
+                }
+            })
+            .collect();
+        let sel = self.replace_ranges(buffer, ranges, "");
+        self.external_selection_change = Some(sel);
         self.scroll_to_selection_end(true);
     }
 
+    /// Applies `movement` to every selection, dropping extra selections that
+    /// end up on top of another one, and returns the new primary selection.
+    fn move_selections(&mut self, movement: Movement, modify: bool) -> Selection {
+        let layout = &self.layout;
+        let mode = self.arrow_key_mode;
+        let primary = text::movement_with_mode(movement, self.selection, layout, modify, mode);
+        let mut seen = vec![primary.range()];
+        let mut extras = Vec::with_capacity(self.extra_selections.len());
+        for sel in &self.extra_selections {
+            let sel = text::movement_with_mode(movement, *sel, layout, modify, mode);
+            if !seen.contains(&sel.range()) {
+                seen.push(sel.range());
+                extras.push(sel);
+            }
+        }
+        self.extra_selections = extras;
+        primary
+    }
+
     fn do_mouse_down(&mut self, point: Point, mods: Modifiers, count: u8) {
         let point = point - Vec2::new(self.alignment_offset, 0.0);
         let pos = self.layout.text_position_for_point(point);
+        if mods.alt() && count == 1 {
+            // alt-click adds a caret, or removes the one that was clicked.
+            let len_before = self.extra_selections.len();
+            self.extra_selections.retain(|sel| sel.active != pos);
+            if self.extra_selections.len() == len_before && self.selection.active != pos {
+                self.extra_selections.push(self.selection);
+                self.selection = Selection::caret(pos);
+            }
+            self.drag_granularity = DragGranularity::Grapheme;
+            return;
+        }
+        self.extra_selections.clear();
         if mods.shift() {
             self.selection.active = pos;
         } else {
@@ -819,12 +1072,32 @@ impl<T: TextStorage + EditableText> EditSession<T> {
             .map(|t| !t.same(new_data))
             .unwrap_or(true)
         {
+            // if we know how the text was edited, keep the selection in place.
+            let edit = self
+                .layout
+                .text()
+                .and_then(|old| new_data.changed_since(old));
+            if let Some((range, new_len)) = edit {
+                let anchor =
+                    editable_text::transform_offset(self.selection.anchor, &range, new_len);
+                let active =
+                    editable_text::transform_offset(self.selection.active, &range, new_len);
+                self.selection = Selection::new(anchor, active);
+                for sel in &mut self.extra_selections {
+                    let anchor = editable_text::transform_offset(sel.anchor, &range, new_len);
+                    let active = editable_text::transform_offset(sel.active, &range, new_len);
+                    *sel = Selection::new(anchor, active);
+                }
+            }
             self.update_pending_invalidation(ImeInvalidation::Reset);
             self.layout.set_text(new_data.clone());
         }
         if self.layout.needs_rebuild_after_update(ctx) {
             ctx.request_layout();
         }
+        for sel in &mut self.extra_selections {
+            *sel = sel.constrained(new_data.as_str());
+        }
         let new_sel = self.selection.constrained(new_data.as_str());
         if new_sel != self.selection {
             self.selection = new_sel;
@@ -856,6 +1129,10 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
     }
 
     fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+        // composition only ever happens at the primary caret.
+        if range.is_some() {
+            self.inner.borrow_mut().extra_selections.clear();
+        }
         self.inner.borrow_mut().composition_range = range;
     }
 
@@ -927,6 +1204,10 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
             self.inner.borrow_mut().external_text_change = Some(self.text.clone());
         }
     }
+
+    fn input_hints(&self) -> InputHints {
+        self.inner.borrow().input_hints
+    }
 }
 
 impl<T> Default for TextComponent<T> {
@@ -939,12 +1220,15 @@ impl<T> Default for TextComponent<T> {
             external_action: None,
             pending_ime_invalidation: None,
             selection: Selection::caret(0),
+            extra_selections: Vec::new(),
             composition_range: None,
             send_notification_on_return: false,
             send_notification_on_cancel: false,
             accepts_newlines: false,
             accepts_tabs: false,
             alignment: TextAlignment::Start,
+            arrow_key_mode: ArrowKeyMode::Logical,
+            input_hints: InputHints::default(),
             alignment_offset: 0.0,
             drag_granularity: DragGranularity::Grapheme,
             origin: Point::ZERO,
@@ -957,3 +1241,101 @@ impl<T> Default for TextComponent<T> {
         }
     }
 }
+
+/// Replaces each of `ranges` in `buffer` with `text`.
+///
+/// `ranges` must be sorted and must not overlap. Returns, for each range, the
+/// position just after its replacement in the edited buffer.
+fn replace_sorted_ranges<T: EditableText>(
+    buffer: &mut T,
+    ranges: &[Range<usize>],
+    text: &str,
+) -> Vec<usize> {
+    let mut removed = 0;
+    let carets = ranges
+        .iter()
+        .enumerate()
+        .map(|(i, range)| {
+            let caret = range.start + (i + 1) * text.len() - removed;
+            removed += range.len();
+            caret
+        })
+        .collect();
+    // edit back to front, so earlier ranges stay valid
+    for range in ranges.iter().rev() {
+        buffer.edit(range.clone(), text);
+    }
+    carets
+}
+
+/// Finds the first occurrence of `needle` in `text` at or after `from`,
+/// wrapping around to the start, that doesn't overlap any of `taken`.
+fn find_next_occurrence(
+    text: &str,
+    needle: &str,
+    from: usize,
+    taken: &[Range<usize>],
+) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    let after = text[from..].match_indices(needle).map(|(i, _)| from + i);
+    let before = text[..from].match_indices(needle).map(|(i, _)| i);
+    after
+        .chain(before)
+        .map(|start| start..start + needle.len())
+        .find(|found| {
+            !taken
+                .iter()
+                .any(|range| range.start < found.end && found.start < range.end)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_several_ranges() {
+        let mut text = String::from("abcdefgh");
+        let carets = replace_sorted_ranges(&mut text, &[2..4, 6..6], "x");
+        assert_eq!(text, "abxefxgh");
+        assert_eq!(carets, vec![3, 6]);
+
+        let mut text = String::from("one two three");
+        let carets = replace_sorted_ranges(&mut text, &[0..3, 4..7, 8..13], "");
+        assert_eq!(text, "  ");
+        assert_eq!(carets, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn next_occurrence_wraps_around() {
+        let text = "foo bar foo baz foo";
+        let foos = [0..3, 8..11, 16..19];
+        assert_eq!(
+            find_next_occurrence(text, "foo", 3, &foos[..1]),
+            Some(8..11)
+        );
+        assert_eq!(
+            find_next_occurrence(text, "foo", 11, &foos[1..2]),
+            Some(16..19)
+        );
+        assert_eq!(
+            find_next_occurrence(text, "foo", 19, &foos[1..]),
+            Some(0..3)
+        );
+        assert_eq!(find_next_occurrence(text, "foo", 19, &foos), None);
+    }
+
+    #[test]
+    fn next_occurrence_skips_overlaps() {
+        let taken = [0..2, 2..4];
+        assert_eq!(
+            find_next_occurrence("aaaa", "aa", 2, &taken[..1]),
+            Some(2..4)
+        );
+        assert_eq!(find_next_occurrence("aaaa", "aa", 4, &taken), None);
+        assert_eq!(find_next_occurrence("aaa", "aa", 2, &taken[..1]), None);
+        assert_eq!(find_next_occurrence("abc", "", 0, &[]), None);
+    }
+}
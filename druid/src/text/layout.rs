@@ -14,15 +14,22 @@
 
 //! A type for laying out, drawing, and interacting with text.
 
+use std::cell::RefCell;
 use std::ops::Range;
 use std::rc::Rc;
 
-use super::{Link, TextStorage};
-use crate::kurbo::{Line, Point, Rect, Size};
+use tracing::warn;
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+use super::{Attribute, Link, TextOutline, TextShadow, TextStorage};
+use crate::core::Layer;
+use crate::kurbo::{Affine, BezPath, Insets, Line, Point, Rect, Shape, Size, Vec2};
 use crate::piet::{
-    Color, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
+    Color, Error as PietError, FontFamily, ImageBuf, ImageFormat, InterpolationMode, PietText,
+    PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
     TextLayoutBuilder as _,
 };
+use crate::widget::gaussian_blur;
 use crate::{Env, FontDescriptor, KeyOrValue, PaintCtx, RenderContext, UpdateCtx};
 
 /// A component for displaying text on screen.
@@ -58,6 +65,57 @@ pub struct TextLayout<T> {
     alignment: TextAlignment,
     links: Rc<[(Rect, usize)]>,
     text_is_rtl: bool,
+    line_height: KeyOrValue<f64>,
+    paragraph_spacing: KeyOrValue<f64>,
+    /// The vertical offset applied to each line to account for line height and
+    /// paragraph spacing. Empty when no adjustment is needed.
+    line_offsets: Rc<[f64]>,
+    /// The total height added by `line_offsets`.
+    extra_height: f64,
+    shadow: Option<TextShadow>,
+    outline: Option<TextOutline>,
+    /// The shadows and outlines of the text, beneath which they are drawn.
+    decorations: Rc<[Decoration]>,
+    /// How far the decorations reach out of the layout's size.
+    decoration_insets: Insets,
+    /// The decorations, painted into an image when they are first drawn.
+    decoration_layer: Rc<RefCell<Option<Layer>>>,
+}
+
+/// A shadow or outline of some ranges of the text, with its colors resolved.
+#[derive(Clone)]
+enum Decoration {
+    Shadow {
+        ranges: Vec<Range<usize>>,
+        offset: Vec2,
+        blur: f64,
+        color: Color,
+    },
+    Outline {
+        ranges: Vec<Range<usize>>,
+        width: f64,
+        color: Color,
+    },
+}
+
+/// The result of hit-testing a [`TextLayout`] with [`TextLayout::hit_test`].
+#[derive(Debug, Clone)]
+pub struct TextHit {
+    /// The utf-8 range of the grapheme under the point.
+    ///
+    /// `grapheme.start` is the grapheme's index into the underlying text.
+    pub grapheme: Range<usize>,
+    /// `true` if the point was inside the bounds of the text, and not
+    /// merely closest to `grapheme`.
+    pub is_inside: bool,
+    /// The attributes that apply to `grapheme`, along with the full range
+    /// of each attribute's span.
+    ///
+    /// This is empty unless the text is a type, such as [`RichText`], that
+    /// reports its attributes.
+    ///
+    /// [`RichText`]: super::RichText
+    pub attributes: Vec<(Range<usize>, Attribute)>,
 }
 
 /// Metrics describing the layout text.
@@ -89,6 +147,15 @@ impl<T> TextLayout<T> {
             alignment: Default::default(),
             links: Rc::new([]),
             text_is_rtl: false,
+            line_height: crate::theme::TEXT_LINE_HEIGHT.into(),
+            paragraph_spacing: crate::theme::TEXT_PARAGRAPH_SPACING.into(),
+            line_offsets: Rc::new([]),
+            extra_height: 0.0,
+            shadow: None,
+            outline: None,
+            decorations: Rc::new([]),
+            decoration_insets: Insets::ZERO,
+            decoration_layer: Default::default(),
         }
     }
 
@@ -155,6 +222,58 @@ impl<T> TextLayout<T> {
         }
     }
 
+    /// Set the line height, as a multiple of each line's natural height.
+    ///
+    /// The default is [`theme::TEXT_LINE_HEIGHT`]. The extra space is split
+    /// evenly above and below each line. [`Attribute::LineHeight`] spans in the
+    /// text take precedence over this.
+    ///
+    /// [`theme::TEXT_LINE_HEIGHT`]: crate::theme::TEXT_LINE_HEIGHT
+    pub fn set_line_height(&mut self, line_height: impl Into<KeyOrValue<f64>>) {
+        let line_height = line_height.into();
+        if line_height != self.line_height {
+            self.line_height = line_height;
+            self.layout = None;
+        }
+    }
+
+    /// Set the extra space, in display points, added after each paragraph.
+    ///
+    /// A paragraph ends at each explicit line break in the text. The default
+    /// is [`theme::TEXT_PARAGRAPH_SPACING`]. [`Attribute::ParagraphSpacing`]
+    /// spans in the text take precedence over this.
+    ///
+    /// [`theme::TEXT_PARAGRAPH_SPACING`]: crate::theme::TEXT_PARAGRAPH_SPACING
+    pub fn set_paragraph_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        let spacing = spacing.into();
+        if spacing != self.paragraph_spacing {
+            self.paragraph_spacing = spacing;
+            self.layout = None;
+        }
+    }
+
+    /// Set a shadow to draw beneath all of the text, or `None` for no shadow.
+    ///
+    /// [`Attribute::Shadow`] spans in the text take precedence over this.
+    pub fn set_shadow(&mut self, shadow: impl Into<Option<TextShadow>>) {
+        let shadow = shadow.into();
+        if shadow != self.shadow {
+            self.shadow = shadow;
+            self.layout = None;
+        }
+    }
+
+    /// Set an outline to draw around all of the text, or `None` for no outline.
+    ///
+    /// [`Attribute::Outline`] spans in the text take precedence over this.
+    pub fn set_outline(&mut self, outline: impl Into<Option<TextOutline>>) {
+        let outline = outline.into();
+        if outline != self.outline {
+            self.outline = outline;
+            self.layout = None;
+        }
+    }
+
     /// Returns `true` if this layout's text appears to be right-to-left.
     ///
     /// See [`piet::util::first_strong_rtl`] for more information.
@@ -216,7 +335,7 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn size(&self) -> Size {
         self.layout
             .as_ref()
-            .map(|layout| layout.size())
+            .map(|layout| layout.size() + Size::new(0.0, self.extra_height))
             .unwrap_or_default()
     }
 
@@ -234,8 +353,8 @@ impl<T: TextStorage> TextLayout<T> {
         );
 
         if let Some(layout) = self.layout.as_ref() {
-            let first_baseline = layout.line_metric(0).unwrap().baseline;
-            let size = layout.size();
+            let first_baseline = layout.line_metric(0).unwrap().baseline + self.line_offset(0);
+            let size = self.size();
             LayoutMetrics {
                 size,
                 first_baseline,
@@ -248,11 +367,30 @@ impl<T: TextStorage> TextLayout<T> {
 
     /// For a given `Point` (relative to this object's origin), returns index
     /// into the underlying text of the nearest grapheme boundary.
+    ///
+    /// Some backends may report a position inside a multi-codepoint grapheme
+    /// (such as an emoji ZWJ sequence or an emoji with a skin-tone modifier);
+    /// in this case the position is moved to whichever edge of the grapheme
+    /// is closest to `point`.
     pub fn text_position_for_point(&self, point: Point) -> usize {
-        self.layout
-            .as_ref()
-            .map(|layout| layout.hit_test_point(point).idx)
-            .unwrap_or_default()
+        let (text, layout) = match (self.text.as_ref(), self.layout.as_ref()) {
+            (Some(text), Some(layout)) => (text, layout),
+            _ => return 0,
+        };
+        let point = self.to_layout_space(layout, point);
+        let idx = layout.hit_test_point(point).idx;
+        match enclosing_grapheme(text.as_str(), idx) {
+            Some(range) => {
+                let start_x = layout.hit_test_text_position(range.start).point.x;
+                let end_x = layout.hit_test_text_position(range.end).point.x;
+                if (point.x - start_x).abs() <= (point.x - end_x).abs() {
+                    range.start
+                } else {
+                    range.end
+                }
+            }
+            None => idx,
+        }
     }
 
     /// Given the utf-8 position of a character boundary in the underlying text,
@@ -263,9 +401,13 @@ impl<T: TextStorage> TextLayout<T> {
     ///
     /// Panics if `text_pos` is not a character boundary.
     pub fn point_for_text_position(&self, text_pos: usize) -> Point {
+        let text_pos = self.grapheme_start(text_pos);
         self.layout
             .as_ref()
-            .map(|layout| layout.hit_test_text_position(text_pos).point)
+            .map(|layout| {
+                let pos = layout.hit_test_text_position(text_pos);
+                pos.point + Vec2::new(0.0, self.line_offset(pos.line))
+            })
             .unwrap_or_default()
     }
 
@@ -278,7 +420,16 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
         self.layout
             .as_ref()
-            .map(|layout| layout.rects_for_range(range))
+            .map(|layout| {
+                let mut rects = layout.rects_for_range(range);
+                if !self.line_offsets.is_empty() {
+                    for rect in &mut rects {
+                        let line = line_for_y(layout, rect.center().y);
+                        *rect = *rect + Vec2::new(0.0, self.line_offset(line));
+                    }
+                }
+                rects
+            })
             .unwrap_or_default()
     }
 
@@ -296,7 +447,8 @@ impl<T: TextStorage> TextLayout<T> {
                 let p2 = layout.hit_test_text_position(range.end);
                 let line_metric = layout.line_metric(p1.line).unwrap();
                 // heuristic; 1/5 of height is a rough guess at the descender pos?
-                let y_pos = line_metric.baseline + (line_metric.height / 5.0);
+                let y_pos =
+                    line_metric.baseline + (line_metric.height / 5.0) + self.line_offset(p1.line);
                 Line::new((p1.point.x, y_pos), (p2.point.x, y_pos))
             })
             .unwrap_or_else(|| Line::new(Point::ZERO, Point::ZERO))
@@ -305,18 +457,107 @@ impl<T: TextStorage> TextLayout<T> {
     /// Given the utf-8 position of a character boundary in the underlying text,
     /// return a `Line` suitable for drawing a vertical cursor at that boundary.
     pub fn cursor_line_for_text_position(&self, text_pos: usize) -> Line {
+        let text_pos = self.grapheme_start(text_pos);
         self.layout
             .as_ref()
             .map(|layout| {
                 let pos = layout.hit_test_text_position(text_pos);
                 let line_metrics = layout.line_metric(pos.line).unwrap();
-                let p1 = (pos.point.x, line_metrics.y_offset);
-                let p2 = (pos.point.x, (line_metrics.y_offset + line_metrics.height));
+                let y_offset = line_metrics.y_offset + self.line_offset(pos.line);
+                let p1 = (pos.point.x, y_offset);
+                let p2 = (pos.point.x, (y_offset + line_metrics.height));
                 Line::new(p1, p2)
             })
             .unwrap_or_else(|| Line::new(Point::ZERO, Point::ZERO))
     }
 
+    /// The vertical offset applied to the given line.
+    fn line_offset(&self, line: usize) -> f64 {
+        self.line_offsets.get(line).copied().unwrap_or(0.0)
+    }
+
+    /// Convert a point relative to our origin into the coordinate space of
+    /// the inner piet layout, which knows nothing about line offsets.
+    ///
+    /// A point in the space between two lines is assigned to the nearer line.
+    fn to_layout_space(&self, layout: &PietTextLayout, point: Point) -> Point {
+        let offsets = &self.line_offsets;
+        if offsets.is_empty() {
+            return point;
+        }
+        let bounds = line_bounds(layout, offsets);
+        let line = bounds
+            .iter()
+            .position(|&(_, bottom)| point.y < bottom)
+            .unwrap_or(bounds.len().saturating_sub(1));
+        let metric = match layout.line_metric(line) {
+            Some(metric) => metric,
+            None => return point,
+        };
+        let mut y = point.y - offsets[line];
+        if line > 0 {
+            y = y.max(metric.y_offset);
+        }
+        if line + 1 < offsets.len() {
+            // stay clear of the boundary, which belongs to the next line
+            y = y.min(metric.y_offset + metric.height * 0.99);
+        }
+        Point::new(point.x, y)
+    }
+
+    /// Returns the start of the grapheme containing `text_pos`.
+    fn grapheme_start(&self, text_pos: usize) -> usize {
+        self.text
+            .as_ref()
+            .and_then(|text| enclosing_grapheme(text.as_str(), text_pos))
+            .map(|range| range.start)
+            .unwrap_or(text_pos)
+    }
+
+    /// Returns the grapheme under the provided point (relative to the layout's origin),
+    /// along with any attribute spans that apply to it.
+    ///
+    /// If the point is outside the text, the nearest grapheme is returned and
+    /// [`TextHit::is_inside`] is `false`. Returns `None` if the text is empty, or
+    /// if the layout has not been built.
+    ///
+    /// This can be used to implement things like word-hover or inline popovers
+    /// from a [`Controller`] without reaching into the underlying layout.
+    ///
+    /// [`Controller`]: crate::widget::Controller
+    pub fn hit_test(&self, point: Point) -> Option<TextHit> {
+        let (text, layout) = match (self.text.as_ref(), self.layout.as_ref()) {
+            (Some(text), Some(layout)) => (text, layout),
+            _ => return None,
+        };
+        let is_inside = layout
+            .hit_test_point(self.to_layout_space(layout, point))
+            .is_inside;
+        let pos = self.text_position_for_point(point);
+        let after = grapheme_after(text.as_str(), pos);
+        let before = grapheme_before(text.as_str(), pos);
+        let contains_point = |range: &Range<usize>| {
+            self.rects_for_range(range.clone())
+                .iter()
+                .any(|rect| rect.contains(point))
+        };
+        let grapheme = match (before, after) {
+            (Some(before), _) if contains_point(&before) => before,
+            (_, Some(after)) => after,
+            (Some(before), None) => before,
+            (None, None) => return None,
+        };
+        let attributes = text
+            .attributes()
+            .map(|attrs| attrs.attributes_at(grapheme.start))
+            .unwrap_or_default();
+        Some(TextHit {
+            grapheme,
+            is_inside,
+            attributes,
+        })
+    }
+
     /// Returns the [`Link`] at the provided point (relative to the layout's origin) if one exists.
     ///
     /// This can be used both for hit-testing (deciding whether to change the mouse cursor,
@@ -343,6 +584,18 @@ impl<T: TextStorage> TextLayout<T> {
         if ctx.env_changed() && self.layout.is_some() {
             let rebuild = ctx.env_key_changed(&self.font)
                 || ctx.env_key_changed(&self.text_color)
+                || ctx.env_key_changed(&self.line_height)
+                || ctx.env_key_changed(&self.paragraph_spacing)
+                || self
+                    .shadow
+                    .as_ref()
+                    .map(|s| ctx.env_key_changed(&s.color))
+                    .unwrap_or(false)
+                || self
+                    .outline
+                    .as_ref()
+                    .map(|o| ctx.env_key_changed(&o.color))
+                    .unwrap_or(false)
                 || self
                     .text_size_override
                     .as_ref()
@@ -387,22 +640,61 @@ impl<T: TextStorage> TextLayout<T> {
                     .font(descriptor.family.clone(), descriptor.size)
                     .default_attribute(descriptor.weight)
                     .default_attribute(descriptor.style)
-                    .default_attribute(TextAttribute::TextColor(color));
-                let layout = text.add_attributes(builder, env).build().unwrap();
+                    .default_attribute(TextAttribute::TextColor(color.clone()));
+                let emoji_fonts = self.emoji_fonts(factory, text.as_str(), env);
+                let mut builder = text.add_attributes(builder, env);
+                for (range, family) in &emoji_fonts {
+                    builder = builder
+                        .range_attribute(range.clone(), TextAttribute::FontFamily(family.clone()));
+                }
+                let layout = match builder.build() {
+                    Ok(layout) => layout,
+                    Err(e) => {
+                        warn!("failed to build a text layout: {}", e);
+                        return;
+                    }
+                };
+                let decorations = self.resolve_decorations(text, env);
+
+                let line_height = self.line_height.resolve(env);
+                let paragraph_spacing = self.paragraph_spacing.resolve(env);
+                let spans = text.attributes();
+                let lines = (0..layout.line_count())
+                    .filter_map(|i| layout.line_metric(i))
+                    .map(|metric| {
+                        let line_height = spans
+                            .and_then(|s| s.line_height_at(metric.start_offset))
+                            .map_or(line_height, |h| h.resolve(env));
+                        let ends_paragraph = text.as_str()[metric.range()].ends_with('\n');
+                        let spacing = if ends_paragraph {
+                            spans
+                                .and_then(|s| s.paragraph_spacing_at(metric.end_offset - 1))
+                                .map_or(paragraph_spacing, |s| s.resolve(env))
+                        } else {
+                            0.0
+                        };
+                        (metric.height, line_height, spacing)
+                    })
+                    .collect::<Vec<_>>();
+                let (offsets, extra_height) = compute_line_offsets(&lines);
+                self.line_offsets = offsets.into();
+                self.extra_height = extra_height;
+                self.decoration_insets = decoration_insets(&decorations);
+                self.decorations = decorations.into();
+                self.decoration_layer = Default::default();
+                self.layout = Some(layout);
 
-                self.links = text
+                let links = text
                     .links()
                     .iter()
                     .enumerate()
                     .flat_map(|(i, link)| {
-                        layout
-                            .rects_for_range(link.range())
+                        self.rects_for_range(link.range())
                             .into_iter()
                             .map(move |rect| (rect, i))
                     })
                     .collect();
-
-                self.layout = Some(layout);
+                self.links = links;
             }
         }
     }
@@ -425,9 +717,505 @@ impl<T: TextStorage> TextLayout<T> {
                 .unwrap_or("layout is missing text")
         );
         if let Some(layout) = self.layout.as_ref() {
+            let point = point.into();
+            if !self.decorations.is_empty() {
+                self.draw_decorations(ctx, layout, point);
+            }
+            self.draw_lines(ctx, layout, point);
+        }
+    }
+
+    /// How far the text's shadows and outlines reach out of its [`size`].
+    ///
+    /// Widgets should include this in their [`paint_insets`].
+    ///
+    /// This is not meaningful until [`rebuild_if_needed`] has been called.
+    ///
+    /// [`size`]: #method.size
+    /// [`paint_insets`]: crate::LayoutCtx::set_paint_insets
+    /// [`rebuild_if_needed`]: #method.rebuild_if_needed
+    pub fn decoration_insets(&self) -> Insets {
+        self.layout
+            .as_ref()
+            .map(|layout| self.decoration_rect(layout) - self.size().to_rect())
+            .unwrap_or(Insets::ZERO)
+    }
+
+    fn draw_lines(&self, ctx: &mut PaintCtx, layout: &PietTextLayout, point: Point) {
+        if self.line_offsets.is_empty() {
             ctx.draw_text(layout, point);
+            return;
+        }
+        // piet has no notion of line height, so the layout is drawn once for
+        // each line, at the line's offset and clipped to the line. Each clip
+        // reaches halfway into the space between lines, so that the ink of tall
+        // glyphs isn't cut off.
+        let ink = layout.image_bounds().union(layout.size().to_rect());
+        let bounds = line_bounds(layout, &self.line_offsets);
+        for (i, (top, bottom)) in bounds.into_iter().enumerate() {
+            let offset = self.line_offsets[i];
+            let clip = Rect::new(ink.x0, top, ink.x1, bottom) + point.to_vec2();
+            ctx.with_save(|ctx| {
+                ctx.clip(clip);
+                ctx.draw_text(layout, point + Vec2::new(0.0, offset));
+            });
         }
     }
+
+    /// The ranges of the emoji in `text`, with the family of the [`EMOJI_FONT`]
+    /// to draw them with, if it is installed.
+    ///
+    /// Their attributes come after the text's own attributes, so that emoji
+    /// are in color even within spans of another font.
+    ///
+    /// [`EMOJI_FONT`]: crate::theme::EMOJI_FONT
+    fn emoji_fonts(
+        &self,
+        factory: &mut PietText,
+        text: &str,
+        env: &Env,
+    ) -> Vec<(Range<usize>, FontFamily)> {
+        let ranges = emoji_ranges(text);
+        if ranges.is_empty() {
+            return Vec::new();
+        }
+        let name = env.get(crate::theme::EMOJI_FONT);
+        // otherwise the emoji are left to the platform's font fallback
+        let family = match factory.font_family(&name) {
+            Some(family) if !name.is_empty() => family,
+            _ => return Vec::new(),
+        };
+        ranges
+            .into_iter()
+            .map(|range| (range, family.clone()))
+            .collect()
+    }
+
+    /// Resolve the shadows and outlines that apply to the text.
+    ///
+    /// Shadows come first, so that they are beneath the outlines.
+    fn resolve_decorations(&self, text: &T, env: &Env) -> Vec<Decoration> {
+        let len = text.as_str().len();
+        let spans = text.attributes();
+        let shadows = group_ranges(
+            len,
+            self.shadow.as_ref(),
+            spans.into_iter().flat_map(|s| s.shadows()),
+        );
+        let outlines = group_ranges(
+            len,
+            self.outline.as_ref(),
+            spans.into_iter().flat_map(|s| s.outlines()),
+        );
+
+        let shadows = shadows
+            .into_iter()
+            .map(|(shadow, ranges)| Decoration::Shadow {
+                ranges,
+                offset: shadow.offset,
+                blur: shadow.blur,
+                color: shadow.color.resolve(env),
+            });
+        let outlines = outlines
+            .into_iter()
+            .filter(|(outline, _)| outline.width > 0.0)
+            .map(|(outline, ranges)| Decoration::Outline {
+                ranges,
+                width: outline.width,
+                color: outline.color.resolve(env),
+            });
+        shadows.chain(outlines).collect()
+    }
+
+    /// The area that the decorations are painted in, relative to the layout's origin.
+    fn decoration_rect(&self, layout: &PietTextLayout) -> Rect {
+        if self.decorations.is_empty() {
+            return self.size().to_rect();
+        }
+        let ink = layout.image_bounds().union(layout.size().to_rect());
+        let ink = Rect::new(ink.x0, ink.y0, ink.x1, ink.y1 + self.extra_height);
+        (ink + self.decoration_insets).expand()
+    }
+
+    /// Draw the shadows and outlines, from an image that is painted once for each scale.
+    fn draw_decorations(&self, ctx: &mut PaintCtx, layout: &PietTextLayout, point: Point) {
+        let rect = self.decoration_rect(layout);
+        let scale = ctx.layer_scale();
+        let mut cached = self.decoration_layer.borrow_mut();
+        if cached.as_ref().map_or(true, |layer| layer.scale != scale) {
+            match self.paint_decorations(ctx, layout, rect, scale) {
+                Ok(buf) => *cached = Some(Layer::new(buf, scale)),
+                Err(e) => {
+                    warn!("failed to paint text decorations: {}", e);
+                    return;
+                }
+            }
+        }
+        if let Some(layer) = cached.as_mut() {
+            let image = layer.image(ctx);
+            ctx.draw_image(&image, rect + point.to_vec2(), InterpolationMode::Bilinear);
+        }
+    }
+
+    /// Paint the decorations into a premultiplied RGBA image of `rect`.
+    ///
+    /// piet can't outline or blur text, so the text is painted into an offscreen
+    /// image, and its coverage is dilated for outlines and blurred for shadows.
+    fn paint_decorations(
+        &self,
+        ctx: &mut PaintCtx,
+        layout: &PietTextLayout,
+        rect: Rect,
+        scale: f64,
+    ) -> Result<ImageBuf, PietError> {
+        // The same size as the images from `paint_to_image`.
+        let width = (rect.width() * scale).ceil().max(1.0) as usize;
+        let height = (rect.height() * scale).ceil().max(1.0) as usize;
+        let len = self.text.as_ref().map(|t| t.as_str().len()).unwrap_or(0);
+
+        // The coverage of each set of decorated ranges.
+        let mut masks: Vec<(&[Range<usize>], Vec<u8>)> = Vec::new();
+        let mut pixels = vec![0; width * height * 4];
+        for decoration in self.decorations.iter() {
+            let ranges = decoration.ranges();
+            let mask = match masks.iter().position(|(r, _)| *r == ranges) {
+                Some(i) => &masks[i].1,
+                None => {
+                    let image = ctx.paint_to_image(rect.size(), scale, |ctx| {
+                        ctx.transform(Affine::translate(-rect.origin().to_vec2()));
+                        if ranges != &[0..len][..] {
+                            let mut clip = BezPath::new();
+                            for range in ranges {
+                                for r in self.rects_for_range(range.clone()) {
+                                    r.path_elements(0.1).for_each(|el| clip.push(el));
+                                }
+                            }
+                            ctx.clip(clip);
+                        }
+                        self.draw_lines(ctx, layout, Point::ORIGIN);
+                    })?;
+                    let alpha = image.raw_pixels().chunks_exact(4).map(|p| p[3]).collect();
+                    masks.push((ranges, alpha));
+                    &masks[masks.len() - 1].1
+                }
+            };
+            match decoration {
+                Decoration::Shadow {
+                    offset,
+                    blur,
+                    color,
+                    ..
+                } => {
+                    let offset = *offset * scale;
+                    let shifted = shift_mask(mask, width, height, offset.round());
+                    let mut shadow = colorize(&shifted, color);
+                    gaussian_blur(&mut shadow, width, height, blur / 2.0 * scale);
+                    composite(&mut pixels, &shadow);
+                }
+                Decoration::Outline {
+                    width: w, color, ..
+                } => {
+                    let dilated = dilate_mask(mask, width, height, w * scale);
+                    composite(&mut pixels, &colorize(&dilated, color));
+                }
+            }
+        }
+        Ok(ImageBuf::from_raw(
+            pixels,
+            ImageFormat::RgbaPremul,
+            width,
+            height,
+        ))
+    }
+}
+
+impl Decoration {
+    fn ranges(&self) -> &[Range<usize>] {
+        match self {
+            Decoration::Shadow { ranges, .. } | Decoration::Outline { ranges, .. } => ranges,
+        }
+    }
+}
+
+/// How far the decorations reach out of the text they decorate.
+fn decoration_insets(decorations: &[Decoration]) -> Insets {
+    decorations
+        .iter()
+        .map(|decoration| match decoration {
+            Decoration::Shadow { offset, blur, .. } => {
+                // The blur has a standard deviation of half its radius, and is
+                // visible up to three of those.
+                let margin = blur * 1.5;
+                Insets::new(
+                    margin - offset.x,
+                    margin - offset.y,
+                    margin + offset.x,
+                    margin + offset.y,
+                )
+            }
+            Decoration::Outline { width, .. } => Insets::uniform(*width),
+        })
+        .fold(Insets::ZERO, |a, b| {
+            // and a pixel for antialiasing
+            Insets::new(
+                a.x0.max(b.x0 + 1.0),
+                a.y0.max(b.y0 + 1.0),
+                a.x1.max(b.x1 + 1.0),
+                a.y1.max(b.y1 + 1.0),
+            )
+        })
+}
+
+/// Move a coverage mask of `width` by `height` pixels by a whole number of pixels.
+fn shift_mask(mask: &[u8], width: usize, height: usize, offset: Vec2) -> Vec<u8> {
+    let (dx, dy) = (offset.x as isize, offset.y as isize);
+    let mut shifted = vec![0; mask.len()];
+    for y in 0..height as isize {
+        let src_y = y - dy;
+        if src_y < 0 || src_y >= height as isize {
+            continue;
+        }
+        for x in 0..width as isize {
+            let src_x = x - dx;
+            if src_x >= 0 && src_x < width as isize {
+                shifted[(y as usize) * width + x as usize] =
+                    mask[(src_y as usize) * width + src_x as usize];
+            }
+        }
+    }
+    shifted
+}
+
+/// Grow a coverage mask of `width` by `height` pixels by `radius` pixels in every
+/// direction, with an antialiased edge.
+fn dilate_mask(mask: &[u8], width: usize, height: usize, radius: f64) -> Vec<u8> {
+    let reach = radius.ceil() as isize;
+    // Each pixel within reach, and how much it counts at its distance.
+    let kernel = (-reach..=reach)
+        .flat_map(|dy| (-reach..=reach).map(move |dx| (dx, dy)))
+        .filter_map(|(dx, dy)| {
+            let distance = ((dx * dx + dy * dy) as f64).sqrt();
+            let weight = (radius + 0.5 - distance).min(1.0);
+            if weight > 0.0 {
+                Some((dx, dy, weight))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut dilated = vec![0; mask.len()];
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let mut value = 0.0f64;
+            for &(dx, dy, weight) in &kernel {
+                let (sx, sy) = (x + dx, y + dy);
+                if sx >= 0 && sy >= 0 && sx < width as isize && sy < height as isize {
+                    let covered = mask[sy as usize * width + sx as usize] as f64 * weight;
+                    value = value.max(covered);
+                }
+            }
+            dilated[y as usize * width + x as usize] = value.round() as u8;
+        }
+    }
+    dilated
+}
+
+/// Premultiplied RGBA pixels of `color`, with the coverage of `mask`.
+fn colorize(mask: &[u8], color: &Color) -> Vec<u8> {
+    let (r, g, b, a) = color.as_rgba8();
+    let mul = |c: u8, m: u8| ((c as u32 * m as u32 + 127) / 255) as u8;
+    let mut pixels = Vec::with_capacity(mask.len() * 4);
+    for &coverage in mask {
+        let alpha = mul(a, coverage);
+        pixels.extend_from_slice(&[mul(r, alpha), mul(g, alpha), mul(b, alpha), alpha]);
+    }
+    pixels
+}
+
+/// Draw the premultiplied RGBA pixels of `src` over those of `dst`.
+fn composite(dst: &mut [u8], src: &[u8]) {
+    for (dst, src) in dst.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+        let remaining = 255 - src[3] as u32;
+        for i in 0..4 {
+            dst[i] = (src[i] as u32 + (dst[i] as u32 * remaining + 127) / 255).min(255) as u8;
+        }
+    }
+}
+
+/// Group the ranges of some text of length `len` by the decoration that
+/// applies to them.
+///
+/// `spans` must be sorted and non-overlapping; `default` applies to any text
+/// they don't cover.
+fn group_ranges<'a, D: PartialEq>(
+    len: usize,
+    default: Option<&'a D>,
+    spans: impl Iterator<Item = (Range<usize>, &'a D)>,
+) -> Vec<(&'a D, Vec<Range<usize>>)> {
+    fn add<'a, D: PartialEq>(
+        groups: &mut Vec<(&'a D, Vec<Range<usize>>)>,
+        item: Option<&'a D>,
+        range: Range<usize>,
+    ) {
+        let item = match item {
+            Some(item) if !range.is_empty() => item,
+            _ => return,
+        };
+        match groups.iter_mut().find(|(d, _)| *d == item) {
+            Some((_, ranges)) => ranges.push(range),
+            None => groups.push((item, std::iter::once(range).collect())),
+        }
+    }
+
+    let mut groups = Vec::new();
+    let mut pos = 0;
+    for (range, item) in spans {
+        add(&mut groups, default, pos..range.start);
+        pos = range.end;
+        add(&mut groups, Some(item), range);
+    }
+    add(&mut groups, default, pos..len);
+    groups
+}
+
+/// The index of the line at `y`, in the coordinate space of `layout`.
+fn line_for_y(layout: &PietTextLayout, y: f64) -> usize {
+    (0..layout.line_count())
+        .rev()
+        .find(|i| {
+            layout
+                .line_metric(*i)
+                .map(|metric| metric.y_offset <= y)
+                .unwrap_or(false)
+        })
+        .unwrap_or(0)
+}
+
+/// The top and bottom of each line of `layout` moved by its offset, extended
+/// halfway into the space between lines.
+///
+/// The first and last lines also reach out to the ink of the whole layout.
+fn line_bounds(layout: &PietTextLayout, offsets: &[f64]) -> Vec<(f64, f64)> {
+    let ink = layout.image_bounds();
+    let lines: Vec<_> = (0..offsets.len())
+        .map_while(|i| layout.line_metric(i))
+        .zip(offsets)
+        .map(|(metric, offset)| {
+            let top = metric.y_offset + offset;
+            (top, top + metric.height)
+        })
+        .collect();
+    let mut bounds = Vec::with_capacity(lines.len());
+    for (i, &(top, bottom)) in lines.iter().enumerate() {
+        let top = match i.checked_sub(1).map(|prev| lines[prev]) {
+            Some((_, prev_bottom)) => (prev_bottom + top) / 2.0,
+            None => top.min(ink.y0),
+        };
+        let bottom = match lines.get(i + 1) {
+            Some(&(next_top, _)) => (bottom + next_top) / 2.0,
+            None => bottom.max(ink.y1 + offsets[i]),
+        };
+        bounds.push((top, bottom));
+    }
+    bounds
+}
+
+/// Given the height, the line height, and the spacing after each line (which is
+/// zero unless it ends a paragraph), compute the vertical offset of each line
+/// and the total height added.
+///
+/// Returns an empty list if no adjustment is needed.
+fn compute_line_offsets(lines: &[(f64, f64, f64)]) -> (Vec<f64>, f64) {
+    if lines.iter().all(|&(_, line_height, spacing)| {
+        (line_height - 1.0).abs() < f64::EPSILON && spacing == 0.0
+    }) {
+        return (Vec::new(), 0.0);
+    }
+    let mut offsets = Vec::with_capacity(lines.len());
+    let mut total = 0.0;
+    for (i, &(height, line_height, spacing)) in lines.iter().enumerate() {
+        let leading = height * (line_height - 1.0);
+        offsets.push(total + leading / 2.0);
+        total += leading;
+        if i + 1 < lines.len() {
+            total += spacing;
+        }
+    }
+    (offsets, total)
+}
+
+/// The ranges of `text` that are presented as emoji, with adjacent ranges merged.
+fn emoji_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (start, grapheme) in text.grapheme_indices(true) {
+        if !is_emoji(grapheme) {
+            continue;
+        }
+        let end = start + grapheme.len();
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
+}
+
+/// Whether a grapheme is presented as an emoji: it asks for emoji presentation
+/// or is a keycap, or it contains a pictograph from the emoji blocks, unless
+/// it asks for text presentation.
+fn is_emoji(grapheme: &str) -> bool {
+    let mut emoji = false;
+    for c in grapheme.chars() {
+        match c as u32 {
+            // VARIATION SELECTOR-15, text presentation
+            0xFE0E => return false,
+            // VARIATION SELECTOR-16 and COMBINING ENCLOSING KEYCAP
+            0xFE0F | 0x20E3 => emoji = true,
+            // from Mahjong Tiles to Symbols and Pictographs Extended-A,
+            // including the regional indicators of flags
+            0x1F000..=0x1FAFF => emoji = true,
+            _ => (),
+        }
+    }
+    emoji
+}
+
+/// If `pos` falls inside a grapheme cluster, returns the range of that cluster.
+///
+/// Returns `None` if `pos` is already on a grapheme boundary, or is not
+/// a valid position in `text`.
+fn enclosing_grapheme(text: &str, pos: usize) -> Option<Range<usize>> {
+    if pos == 0 || pos >= text.len() || !text.is_char_boundary(pos) {
+        return None;
+    }
+    let mut cursor = GraphemeCursor::new(pos, text.len(), true);
+    if cursor.is_boundary(text, 0).ok()? {
+        return None;
+    }
+    let start = cursor.prev_boundary(text, 0).ok()??;
+    cursor.set_cursor(pos);
+    let end = cursor.next_boundary(text, 0).ok()??;
+    Some(start..end)
+}
+
+/// The grapheme that begins at `pos`, which must be a grapheme boundary.
+fn grapheme_after(text: &str, pos: usize) -> Option<Range<usize>> {
+    if pos >= text.len() {
+        return None;
+    }
+    let mut cursor = GraphemeCursor::new(pos, text.len(), true);
+    let end = cursor.next_boundary(text, 0).ok()??;
+    Some(pos..end)
+}
+
+/// The grapheme that ends at `pos`, which must be a grapheme boundary.
+fn grapheme_before(text: &str, pos: usize) -> Option<Range<usize>> {
+    if pos == 0 || pos > text.len() {
+        return None;
+    }
+    let mut cursor = GraphemeCursor::new(pos, text.len(), true);
+    let start = cursor.prev_boundary(text, 0).ok()??;
+    Some(start..pos)
 }
 
 impl<T> std::fmt::Debug for TextLayout<T> {
@@ -453,3 +1241,150 @@ impl<T: TextStorage> Default for TextLayout<T> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emoji_ranges_cover_sequences() {
+        // thumbs up with a skin tone, then a ZWJ family and a flag
+        let text = "a\u{1F44D}\u{1F3FD} \u{1F469}\u{200D}\u{1F467}\u{1F1EB}\u{1F1F7}b";
+        assert_eq!(emoji_ranges(text), vec![1..9, 10..29]);
+        // keycaps and emoji presentation of text symbols
+        assert_eq!(
+            emoji_ranges("1\u{FE0F}\u{20E3}\u{2764}\u{FE0F}"),
+            vec![0..13]
+        );
+        // text presentation, and symbols outside the emoji blocks
+        assert!(emoji_ranges("\u{1F600}\u{FE0E}\u{2764}\u{263A}").is_empty());
+        assert!(emoji_ranges("plain text").is_empty());
+    }
+
+    #[test]
+    fn enclosing_grapheme_emoji() {
+        // woman, zwj, woman, zwj, girl
+        let family = "a\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}b";
+        assert_eq!(enclosing_grapheme(family, 0), None);
+        assert_eq!(enclosing_grapheme(family, 1), None);
+        assert_eq!(enclosing_grapheme(family, 5), Some(1..19));
+        assert_eq!(enclosing_grapheme(family, 8), Some(1..19));
+        assert_eq!(enclosing_grapheme(family, 15), Some(1..19));
+        assert_eq!(enclosing_grapheme(family, 19), None);
+
+        // thumbs up, medium skin tone
+        let thumbs = "\u{1F44D}\u{1F3FD}";
+        assert_eq!(enclosing_grapheme(thumbs, 4), Some(0..8));
+        assert_eq!(enclosing_grapheme(thumbs, 8), None);
+    }
+
+    #[test]
+    fn graphemes_around_boundary() {
+        let family = "a\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}b";
+        assert_eq!(grapheme_after(family, 0), Some(0..1));
+        assert_eq!(grapheme_after(family, 1), Some(1..19));
+        assert_eq!(grapheme_before(family, 19), Some(1..19));
+        assert_eq!(grapheme_after(family, 20), None);
+        assert_eq!(grapheme_before(family, 0), None);
+        assert_eq!(grapheme_before(family, 20), Some(19..20));
+    }
+
+    #[test]
+    fn decoration_ranges() {
+        let spans = [(2..4, &'b'), (6..8, &'a')];
+        let groups = group_ranges(10, Some(&'a'), spans.iter().cloned());
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], (&'a', vec![0..2, 4..6, 6..8, 8..10]));
+        assert_eq!(groups[1].0, &'b');
+        assert_eq!(groups[1].1.first(), Some(&(2..4)));
+
+        let groups = group_ranges(10, None, spans.iter().cloned());
+        assert_eq!(groups.iter().map(|g| *g.0).collect::<String>(), "ba");
+        assert!(groups.iter().all(|g| g.1.len() == 1));
+        assert!(group_ranges::<char>(10, None, std::iter::empty()).is_empty());
+    }
+
+    #[test]
+    fn dilate_grows_by_the_radius() {
+        let mut mask = vec![0; 7 * 7];
+        mask[3 * 7 + 3] = 255;
+        let dilated = dilate_mask(&mask, 7, 7, 2.0);
+        let at = |x: usize, y: usize| dilated[y * 7 + x];
+        assert_eq!(at(3, 3), 255);
+        assert_eq!(at(4, 3), 255);
+        assert_eq!(at(4, 4), 255);
+        // the edge is antialiased
+        assert_eq!(at(5, 3), 128);
+        assert_eq!(at(3, 1), 128);
+        // the corners are further away than the radius
+        assert_eq!(at(5, 5), 0);
+        assert_eq!(at(6, 3), 0);
+        // partial coverage stays partial
+        mask[3 * 7 + 3] = 100;
+        assert_eq!(dilate_mask(&mask, 7, 7, 2.0)[3 * 7 + 4], 100);
+    }
+
+    #[test]
+    fn shift_moves_and_drops_pixels() {
+        let mask = [1, 2, 3, 4, 5, 6];
+        assert_eq!(
+            shift_mask(&mask, 3, 2, Vec2::new(1.0, 0.0)),
+            [0, 1, 2, 0, 4, 5]
+        );
+        assert_eq!(
+            shift_mask(&mask, 3, 2, Vec2::new(-1.0, 1.0)),
+            [0, 0, 0, 2, 3, 0]
+        );
+    }
+
+    #[test]
+    fn colorize_and_composite() {
+        let red = colorize(&[255, 0, 128], &Color::rgba8(255, 0, 0, 255));
+        assert_eq!(red, [255, 0, 0, 255, 0, 0, 0, 0, 128, 0, 0, 128]);
+
+        let mut dst = colorize(&[255, 255, 255], &Color::rgba8(0, 0, 255, 255));
+        composite(&mut dst, &red);
+        assert_eq!(&dst[..4], &[255, 0, 0, 255]);
+        assert_eq!(&dst[4..8], &[0, 0, 255, 255]);
+        assert_eq!(&dst[8..], &[128, 0, 127, 255]);
+    }
+
+    #[test]
+    fn insets_cover_every_decoration() {
+        let shadow = Decoration::Shadow {
+            ranges: vec![0..1],
+            offset: Vec2::new(2.0, -1.0),
+            blur: 2.0,
+            color: Color::BLACK,
+        };
+        let outline = Decoration::Outline {
+            ranges: vec![0..1],
+            width: 2.5,
+            color: Color::BLACK,
+        };
+        let insets = decoration_insets(&[shadow, outline]);
+        assert_eq!(insets, Insets::new(3.5, 5.0, 6.0, 3.5));
+        assert_eq!(decoration_insets(&[]), Insets::ZERO);
+    }
+
+    #[test]
+    fn line_offsets() {
+        let lines = [(10.0, 1.0, 0.0); 3];
+        assert_eq!(compute_line_offsets(&lines), (vec![], 0.0));
+        let lines = [(10.0, 1.5, 0.0); 3];
+        assert_eq!(compute_line_offsets(&lines), (vec![2.5, 7.5, 12.5], 15.0));
+        // spacing is not added after the last paragraph
+        let lines = [(10.0, 1.0, 0.0), (10.0, 1.0, 4.0), (10.0, 1.0, 4.0)];
+        assert_eq!(compute_line_offsets(&lines), (vec![0.0, 0.0, 4.0], 4.0));
+        // only the lines with a line height get its leading
+        let lines = [(10.0, 1.0, 0.0), (20.0, 2.0, 0.0), (10.0, 1.0, 0.0)];
+        assert_eq!(compute_line_offsets(&lines), (vec![0.0, 10.0, 20.0], 20.0));
+    }
+
+    #[test]
+    fn enclosing_grapheme_invalid() {
+        let thumbs = "\u{1F44D}\u{1F3FD}";
+        assert_eq!(enclosing_grapheme(thumbs, 2), None);
+        assert_eq!(enclosing_grapheme(thumbs, 42), None);
+    }
+}
@@ -31,24 +31,26 @@ mod input_methods;
 mod layout;
 mod movement;
 mod rich_text;
+mod rope;
 mod storage;
 
 pub use crate::piet::{FontFamily, FontStyle, FontWeight, TextAlignment};
 pub use druid_shell::text::{
-    Action as TextAction, Affinity, Direction, Event as ImeInvalidation, InputHandler, Movement,
-    Selection, VerticalMovement, WritingDirection,
+    Action as TextAction, Affinity, Direction, Event as ImeInvalidation, InputHandler, InputHints,
+    InputPurpose, Movement, Selection, VerticalMovement, WritingDirection,
 };
 
-pub use self::attribute::{Attribute, AttributeSpans, Link};
+pub use self::attribute::{Attribute, AttributeSpans, Link, TextOutline, TextShadow};
 pub use self::backspace::offset_for_delete_backwards;
 pub use self::editable_text::{EditableText, EditableTextCursor, StringCursor};
 pub use self::font_descriptor::FontDescriptor;
 pub use self::format_priv::{Formatter, ParseFormatter, Validation, ValidationError};
-pub use self::layout::{LayoutMetrics, TextLayout};
-pub use self::movement::movement;
+pub use self::layout::{LayoutMetrics, TextHit, TextLayout};
+pub use self::movement::{movement, movement_with_mode, ArrowKeyMode};
 pub use input_component::{EditSession, TextComponent};
 pub use input_methods::ImeHandlerRef;
-pub use rich_text::{AttributesAdder, RichText, RichTextBuilder};
+pub use rich_text::{AttributesAdder, RichText, RichTextBuilder, RichTextDelta, SelectionStyle};
+pub use rope::{Chunks, Rope, RopeDelta};
 pub use storage::{ArcStr, TextStorage};
 
 pub(crate) use input_methods::TextFieldRegistration;
@@ -19,11 +19,27 @@ use std::ops::Range;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::kurbo::Point;
-use crate::piet::TextLayout as _;
+use crate::piet::{PietTextLayout, TextLayout as _};
 use crate::text::{
-    EditableText, Movement, Selection, TextLayout, TextStorage, VerticalMovement, WritingDirection,
+    Direction, EditableText, Movement, Selection, TextLayout, TextStorage, VerticalMovement,
+    WritingDirection,
 };
 
+/// How left and right arrow keys move the caret in bidirectional text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowKeyMode {
+    /// The arrow keys move backwards and forwards through the text
+    /// in storage order, based on the direction of the paragraph.
+    ///
+    /// In a right-to-left paragraph, the left arrow moves forwards; within
+    /// an embedded run of opposite-direction text the caret may appear to
+    /// move against the arrow.
+    Logical,
+    /// The arrow keys move the caret in the direction of the arrow on screen,
+    /// even across runs of text with a different direction.
+    Visual,
+}
+
 /// Compute the result of a [`Movement`] on a [`Selection`].
 ///
 /// returns a new selection representing the state after the movement.
@@ -31,11 +47,28 @@ use crate::text::{
 /// If `modify` is true, only the 'active' edge (the `end`) of the selection
 /// should be changed; this is the case when the user moves with the shift
 /// key pressed.
+///
+/// Horizontal movements use [`ArrowKeyMode::Logical`]; see [`movement_with_mode`]
+/// to choose a different behaviour.
 pub fn movement<T: EditableText + TextStorage>(
     m: Movement,
     s: Selection,
     layout: &TextLayout<T>,
     modify: bool,
+) -> Selection {
+    movement_with_mode(m, s, layout, modify, ArrowKeyMode::Logical)
+}
+
+/// Compute the result of a [`Movement`] on a [`Selection`], using the provided
+/// [`ArrowKeyMode`] for movements to the left or right.
+///
+/// See [`movement`] for more information.
+pub fn movement_with_mode<T: EditableText + TextStorage>(
+    m: Movement,
+    s: Selection,
+    layout: &TextLayout<T>,
+    modify: bool,
+    mode: ArrowKeyMode,
 ) -> Selection {
     let (text, layout) = match (layout.text(), layout.layout()) {
         (Some(text), Some(layout)) => (text, layout),
@@ -51,7 +84,17 @@ pub fn movement<T: EditableText + TextStorage>(
         WritingDirection::LeftToRight
     };
 
+    let visual_offset = match m {
+        Movement::Grapheme(d @ Direction::Left) | Movement::Grapheme(d @ Direction::Right)
+            if mode == ArrowKeyMode::Visual && (s.is_caret() || modify) =>
+        {
+            visual_grapheme_offset(text, layout, s.active, d == Direction::Left)
+        }
+        _ => None,
+    };
+
     let (offset, h_pos) = match m {
+        Movement::Grapheme(_) if visual_offset.is_some() => (visual_offset.unwrap(), None),
         Movement::Grapheme(d) if d.is_upstream_for_direction(writing_direction) => {
             if s.is_caret() || modify {
                 text.prev_grapheme_offset(s.active)
@@ -153,6 +196,35 @@ pub fn movement<T: EditableText + TextStorage>(
     Selection::new(start, offset).with_h_pos(h_pos)
 }
 
+/// Returns the grapheme boundary adjacent to `pos` that is visually to the
+/// left (or right) of it on the same line, if one exists.
+///
+/// When both neighbouring boundaries lie in the requested direction (which can
+/// happen at the boundary between runs of different direction) the closer one
+/// is chosen. If neither does, this returns `None`, and the caller should fall
+/// back to moving based on the paragraph direction.
+fn visual_grapheme_offset<T: EditableText>(
+    text: &T,
+    layout: &PietTextLayout,
+    pos: usize,
+    leftwards: bool,
+) -> Option<usize> {
+    let current = layout.hit_test_text_position(pos);
+    let candidates = [
+        text.prev_grapheme_offset(pos),
+        text.next_grapheme_offset(pos),
+    ];
+    candidates
+        .iter()
+        .flatten()
+        .map(|&offset| (offset, layout.hit_test_text_position(offset)))
+        .filter(|(_, hit)| hit.line == current.line)
+        .map(|(offset, hit)| (offset, hit.point.x - current.point.x))
+        .filter(|(_, dx)| if leftwards { *dx < 0.0 } else { *dx > 0.0 })
+        .min_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+        .map(|(offset, _)| offset)
+}
+
 /// Given a position in some text, return the containing word boundaries.
 ///
 /// The returned range may not necessary be a 'word'; for instance it could be
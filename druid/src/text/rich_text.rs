@@ -14,11 +14,14 @@
 
 //! Rich text with style spans.
 
+use std::borrow::Cow;
 use std::ops::{Range, RangeBounds};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 
 use super::attribute::Link;
-use super::{Attribute, AttributeSpans, TextStorage};
+use super::editable_text;
+use super::{Attribute, AttributeSpans, EditableText, StringCursor, TextStorage};
+use crate::kurbo::Vec2;
 use crate::piet::{
     util, Color, FontFamily, FontStyle, FontWeight, PietTextLayoutBuilder, TextLayoutBuilder,
     TextStorage as PietTextStorage,
@@ -26,11 +29,57 @@ use crate::piet::{
 use crate::{ArcStr, Command, Data, Env, FontDescriptor, KeyOrValue};
 
 /// Text with optional style spans.
+///
+/// `RichText` can be edited in a [`RichTextBox`], or by applying a
+/// [`RichTextDelta`]. It remembers the most recent edit made to its text,
+/// which is available through [`edit_since`]; editors use this to keep their
+/// selection in place when the text is changed by someone else.
+///
+/// [`RichTextBox`]: crate::widget::RichTextBox
+/// [`edit_since`]: RichText::edit_since
 #[derive(Clone, Debug, Data)]
 pub struct RichText {
     buffer: ArcStr,
     attrs: Arc<AttributeSpans>,
     links: Arc<[Link]>,
+    #[data(ignore)]
+    last_edit: Option<Arc<LastEdit>>,
+}
+
+/// An edit to a [`RichText`]: the replacement of a range of the text with
+/// new, optionally styled, text.
+///
+/// # Examples
+///
+/// ```
+/// use druid::text::{Attribute, RichText, RichTextDelta};
+/// use druid::FontWeight;
+///
+/// let mut text = RichText::new("hello world".into());
+/// let delta = RichTextDelta::insert(6, "bold ")
+///     .with_attribute(0..4, Attribute::weight(FontWeight::BOLD));
+/// text.apply(&delta);
+/// assert!(text.style_for_range(6..10).bold);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RichTextDelta {
+    /// The range of the text that is replaced, before the edit.
+    pub range: Range<usize>,
+    /// The text that replaces it.
+    pub text: ArcStr,
+    /// Attributes for the new text, with ranges relative to the start of `text`.
+    ///
+    /// These are added to the attributes the new text takes on from the
+    /// text that precedes it.
+    pub attributes: Vec<(Range<usize>, Attribute)>,
+}
+
+#[derive(Debug)]
+struct LastEdit {
+    // a weak reference keeps the allocation, and so the address, alive,
+    // without keeping the previous text alive.
+    previous: Weak<str>,
+    delta: RichTextDelta,
 }
 
 impl RichText {
@@ -45,6 +94,7 @@ impl RichText {
             buffer,
             attrs: Arc::new(attributes),
             links: Arc::new([]),
+            last_edit: None,
         }
     }
 
@@ -73,6 +123,153 @@ impl RichText {
         let range = util::resolve_range(range, self.buffer.len());
         Arc::make_mut(&mut self.attrs).add(range, attr);
     }
+
+    /// Apply a [`RichTextDelta`] to the text.
+    ///
+    /// Attributes are moved with the text around them, and links that
+    /// overlap the replaced range are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or end of the delta's range is out of bounds,
+    /// or is not on a `char` boundary.
+    pub fn apply(&mut self, delta: &RichTextDelta) {
+        let range = delta.range.clone();
+        let new_len = delta.text.len();
+        if range.is_empty() && new_len == 0 {
+            return;
+        }
+        let mut buffer = String::with_capacity(self.buffer.len() - range.len() + new_len);
+        buffer.push_str(&self.buffer[..range.start]);
+        buffer.push_str(&delta.text);
+        buffer.push_str(&self.buffer[range.end..]);
+        let previous = std::mem::replace(&mut self.buffer, buffer.into());
+
+        let attrs = Arc::make_mut(&mut self.attrs);
+        attrs.edit(range.clone(), new_len);
+        for (attr_range, attr) in &delta.attributes {
+            let start = range.start + attr_range.start.min(new_len);
+            let end = range.start + attr_range.end.min(new_len);
+            attrs.add(start..end, attr.clone());
+        }
+
+        if !self.links.is_empty() {
+            // links that were edited are removed, and links after the edit are moved.
+            self.links = self
+                .links
+                .iter()
+                .filter_map(|link| {
+                    if link.range.end <= range.start {
+                        Some(link.clone())
+                    } else if link.range.start >= range.end {
+                        let start = link.range.start - range.len() + new_len;
+                        let end = link.range.end - range.len() + new_len;
+                        Some(Link::new(start..end, link.command.clone()))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+        }
+
+        self.last_edit = Some(Arc::new(LastEdit {
+            previous: Arc::downgrade(&previous),
+            delta: delta.clone(),
+        }));
+    }
+
+    /// If this text is the result of a single edit to `previous`, returns
+    /// that edit.
+    ///
+    /// Changes to attributes alone are not edits.
+    pub fn edit_since(&self, previous: &RichText) -> Option<&RichTextDelta> {
+        self.last_edit
+            .as_ref()
+            .filter(|edit| Weak::ptr_eq(&edit.previous, &Arc::downgrade(&previous.buffer)))
+            .map(|edit| &edit.delta)
+    }
+
+    /// Returns the [`SelectionStyle`] of the provided range of text.
+    ///
+    /// If the range is empty, this is the style that text inserted at that
+    /// position will have; that is, the style of the preceding character.
+    pub fn style_for_range(&self, range: Range<usize>) -> SelectionStyle {
+        let range = if !range.is_empty() {
+            range
+        } else if let Some(prev) = self.prev_codepoint_offset(range.start) {
+            prev..range.start
+        } else if let Some(next) = self.next_codepoint_offset(range.start) {
+            range.start..next
+        } else {
+            return SelectionStyle::default();
+        };
+        SelectionStyle {
+            bold: self.attrs.is_bold(range.clone()),
+            italic: self.attrs.is_italic(range.clone()),
+            underline: self.attrs.is_underlined(range.clone()),
+            text_color: self.attrs.text_color(range).cloned(),
+        }
+    }
+
+    /// Make the provided range bold, or if it is already entirely bold,
+    /// make it regular weight.
+    pub fn toggle_bold(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            let weight = if self.attrs.is_bold(range.clone()) {
+                FontWeight::REGULAR
+            } else {
+                FontWeight::BOLD
+            };
+            self.add_attribute(range, Attribute::weight(weight));
+        }
+    }
+
+    /// Make the provided range italic, or if it is already entirely italic,
+    /// make it regular.
+    pub fn toggle_italic(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            let style = if self.attrs.is_italic(range.clone()) {
+                FontStyle::Regular
+            } else {
+                FontStyle::Italic
+            };
+            self.add_attribute(range, Attribute::style(style));
+        }
+    }
+
+    /// Underline the provided range, or if it is already entirely underlined,
+    /// remove the underline.
+    pub fn toggle_underline(&mut self, range: Range<usize>) {
+        if !range.is_empty() {
+            let underline = !self.attrs.is_underlined(range.clone());
+            self.add_attribute(range, Attribute::underline(underline));
+        }
+    }
+
+    /// Set the color of the provided range of text.
+    pub fn set_text_color(&mut self, range: Range<usize>, color: impl Into<KeyOrValue<Color>>) {
+        if !range.is_empty() {
+            self.add_attribute(range, Attribute::text_color(color));
+        }
+    }
+}
+
+/// The style of a range of [`RichText`], such as the selection in a
+/// [`RichTextBox`].
+///
+/// A style is only reported if it applies to the entire range.
+///
+/// [`RichTextBox`]: crate::widget::RichTextBox
+#[derive(Debug, Clone, Default, PartialEq, Data)]
+pub struct SelectionStyle {
+    /// `true` if the text is bold.
+    pub bold: bool,
+    /// `true` if the text is italic.
+    pub italic: bool,
+    /// `true` if the text is underlined.
+    pub underline: bool,
+    /// The color of the text, if it has been set explicitly.
+    pub text_color: Option<KeyOrValue<Color>>,
 }
 
 impl PietTextStorage for RichText {
@@ -96,6 +293,114 @@ impl TextStorage for RichText {
     fn links(&self) -> &[Link] {
         &self.links
     }
+
+    fn attributes(&self) -> Option<&AttributeSpans> {
+        Some(&self.attrs)
+    }
+}
+
+impl EditableText for RichText {
+    fn cursor(&self, position: usize) -> Option<StringCursor> {
+        editable_text::cursor(&self.buffer, position)
+    }
+
+    fn edit(&mut self, range: Range<usize>, new: impl Into<String>) {
+        self.apply(&RichTextDelta::replace(range, new.into()));
+    }
+
+    fn slice(&self, range: Range<usize>) -> Option<Cow<str>> {
+        self.buffer.get(range).map(Cow::from)
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn prev_word_offset(&self, offset: usize) -> Option<usize> {
+        editable_text::prev_word_offset(&self.buffer, offset)
+    }
+
+    fn next_word_offset(&self, offset: usize) -> Option<usize> {
+        editable_text::next_word_offset(&self.buffer, offset)
+    }
+
+    fn prev_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        editable_text::prev_grapheme_offset(&self.buffer, offset)
+    }
+
+    fn next_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        editable_text::next_grapheme_offset(&self.buffer, offset)
+    }
+
+    fn prev_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        editable_text::prev_codepoint_offset(&self.buffer, offset)
+    }
+
+    fn next_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        editable_text::next_codepoint_offset(&self.buffer, offset)
+    }
+
+    fn preceding_line_break(&self, offset: usize) -> usize {
+        editable_text::preceding_line_break(&self.buffer, offset)
+    }
+
+    fn next_line_break(&self, offset: usize) -> usize {
+        editable_text::next_line_break(&self.buffer, offset)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    fn from_str(s: &str) -> Self {
+        RichText::new(s.into())
+    }
+
+    fn changed_since(&self, previous: &Self) -> Option<(Range<usize>, usize)> {
+        self.edit_since(previous)
+            .map(|delta| (delta.range.clone(), delta.text.len()))
+    }
+}
+
+impl RichTextDelta {
+    /// Replace `range` with `text`.
+    pub fn replace(range: Range<usize>, text: impl Into<ArcStr>) -> Self {
+        RichTextDelta {
+            range,
+            text: text.into(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Insert `text` at `offset`.
+    pub fn insert(offset: usize, text: impl Into<ArcStr>) -> Self {
+        RichTextDelta::replace(offset..offset, text)
+    }
+
+    /// Delete `range`.
+    pub fn delete(range: Range<usize>) -> Self {
+        RichTextDelta::replace(range, "")
+    }
+
+    /// Builder-style method to add an [`Attribute`] to a range of the new
+    /// text, relative to its start.
+    pub fn with_attribute(mut self, range: Range<usize>, attr: Attribute) -> Self {
+        self.attributes.push((range, attr));
+        self
+    }
+
+    /// The range of the new text, after the edit.
+    pub fn new_range(&self) -> Range<usize> {
+        self.range.start..self.range.start + self.text.len()
+    }
+
+    /// Map an offset in the text before the edit to the equivalent offset
+    /// after it.
+    ///
+    /// Offsets inside the replaced range are moved to the end of the new text.
+    pub fn transform_offset(&self, offset: usize) -> usize {
+        editable_text::transform_offset(offset, &self.range, self.text.len())
+    }
 }
 
 /// A builder for creating [`RichText`] objects.
@@ -173,6 +478,7 @@ impl RichTextBuilder {
             buffer: self.buffer.into(),
             attrs: self.attrs.into(),
             links: self.links.into(),
+            last_edit: None,
         }
     }
 }
@@ -234,6 +540,35 @@ impl AttributesAdder<'_> {
         self
     }
 
+    /// Add a shadow attribute.
+    pub fn shadow(
+        &mut self,
+        offset: impl Into<Vec2>,
+        blur: f64,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> &mut Self {
+        self.add_attr(Attribute::shadow(offset, blur, color));
+        self
+    }
+
+    /// Add an outline attribute.
+    pub fn outline(&mut self, width: f64, color: impl Into<KeyOrValue<Color>>) -> &mut Self {
+        self.add_attr(Attribute::outline(width, color));
+        self
+    }
+
+    /// Add a line height attribute.
+    pub fn line_height(&mut self, line_height: impl Into<KeyOrValue<f64>>) -> &mut Self {
+        self.add_attr(Attribute::line_height(line_height));
+        self
+    }
+
+    /// Add a paragraph spacing attribute.
+    pub fn paragraph_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) -> &mut Self {
+        self.add_attr(Attribute::paragraph_spacing(spacing));
+        self
+    }
+
     /// Add a [`Link`] attribute.
     ///
     /// [`Link`]: super::attribute::Link
@@ -244,3 +579,79 @@ impl AttributesAdder<'_> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn edit_and_toggle_style() {
+        let mut builder = RichTextBuilder::new();
+        builder.push("hello ");
+        builder.push("bold").weight(FontWeight::BOLD);
+        builder.push(" world").link(crate::commands::QUIT_APP);
+        let mut text = builder.build();
+
+        assert!(text.style_for_range(6..10).bold);
+        assert!(!text.style_for_range(4..8).bold);
+        // a caret takes the style of the preceding text
+        assert!(text.style_for_range(10..10).bold);
+        assert!(!text.style_for_range(6..6).bold);
+
+        // typed text takes on the style of the preceding text
+        text.edit(10..10, "er");
+        assert_eq!(text.slice(0..text.len()).unwrap(), "hello bolder world");
+        assert!(text.style_for_range(6..12).bold);
+        assert_eq!(text.links()[0].range(), 12..18);
+
+        text.toggle_bold(4..8);
+        assert!(text.style_for_range(4..12).bold);
+        text.toggle_bold(4..8);
+        assert!(!text.style_for_range(4..8).bold);
+        assert!(text.style_for_range(8..12).bold);
+
+        text.toggle_italic(0..5);
+        text.set_text_color(0..3, Color::RED);
+        let style = text.style_for_range(0..3);
+        assert!(style.italic && !style.underline);
+        assert_eq!(style.text_color, Some(Color::RED.into()));
+        assert_eq!(text.style_for_range(0..4).text_color, None);
+
+        // editing a link removes it
+        text.edit(13..15, "");
+        assert!(text.links().is_empty());
+    }
+
+    #[test]
+    fn apply_delta() {
+        let mut text = RichText::new("hello world".into());
+        let original = text.clone();
+        let delta = RichTextDelta::insert(6, "big ")
+            .with_attribute(0..3, Attribute::weight(FontWeight::BOLD));
+        text.apply(&delta);
+        assert_eq!(text.as_str(), "hello big world");
+        assert!(text.style_for_range(6..9).bold);
+        assert!(!text.style_for_range(9..15).bold);
+
+        let edit = text.edit_since(&original).unwrap();
+        assert_eq!(edit.new_range(), 6..10);
+        // a selection after the edit moves with the text
+        assert_eq!(edit.transform_offset(8), 12);
+        assert_eq!(edit.transform_offset(3), 3);
+        assert_eq!(text.changed_since(&original), Some((6..6, 4)));
+
+        let intermediate = text.clone();
+        text.apply(&RichTextDelta::delete(0..6));
+        assert_eq!(text.as_str(), "big world");
+        assert!(text.edit_since(&original).is_none());
+        assert_eq!(text.edit_since(&intermediate).unwrap().range, 0..6);
+        // restyling isn't an edit
+        let restyled = {
+            let mut t = text.clone();
+            t.toggle_italic(0..3);
+            t
+        };
+        assert!(restyled.edit_since(&text).is_none());
+    }
+}
@@ -0,0 +1,770 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A rope, for editing large amounts of text.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock, Weak};
+
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+
+use super::editable_text;
+use super::{ArcStr, EditableText, StringCursor, TextStorage};
+use crate::piet::TextStorage as PietTextStorage;
+use crate::Data;
+
+/// The maximum size of a leaf, in bytes.
+const MAX_LEAF: usize = 1024;
+
+/// An immutable string, stored as a balanced tree of chunks.
+///
+/// A `Rope` is cheap to clone, and an edit only copies the parts of the tree
+/// that change, with the rest shared with the previous version. This makes it
+/// suitable for editing large documents, where storing the text as a `String`
+/// would mean copying the whole document on every keystroke.
+///
+/// The text is made contiguous when something needs it as a `&str`, such as
+/// when it is laid out for display; this happens at most once per version.
+/// Moving through the text by characters, words or lines works on the
+/// chunks, and so does [`slice`]; use [`chunks`] to read the text without
+/// making it contiguous.
+///
+/// A `Rope` can be edited in a [`TextBox`], and remembers the most recent
+/// edit made to it, which is available through [`edit_since`].
+///
+/// [`chunks`]: Rope::chunks
+/// [`slice`]: Rope::slice
+/// [`edit_since`]: Rope::edit_since
+/// [`TextBox`]: crate::widget::TextBox
+#[derive(Clone)]
+pub struct Rope {
+    root: Arc<Node>,
+    flat: Arc<OnceLock<String>>,
+    last_edit: Option<Arc<LastEdit>>,
+}
+
+/// An edit to a [`Rope`]: the replacement of a range of its text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RopeDelta {
+    /// The range of the text that was replaced, before the edit.
+    pub range: Range<usize>,
+    /// The text that replaced it.
+    pub text: ArcStr,
+}
+
+struct LastEdit {
+    // a weak reference keeps the allocation, and so the address, alive,
+    // without keeping the previous version's text alive.
+    previous: Weak<Node>,
+    delta: RopeDelta,
+}
+
+#[derive(Debug)]
+enum Node {
+    Leaf(String),
+    Branch {
+        len: usize,
+        height: usize,
+        left: Arc<Node>,
+        right: Arc<Node>,
+    },
+}
+
+impl Rope {
+    /// Create a new, empty `Rope`.
+    pub fn new() -> Self {
+        Rope::from_root(Arc::new(Node::Leaf(String::new())))
+    }
+
+    fn from_root(root: Arc<Node>) -> Self {
+        Rope {
+            root,
+            flat: Default::default(),
+            last_edit: None,
+        }
+    }
+
+    /// The length of the text, in bytes.
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    /// Returns `true` if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replace a range of the text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start or end of `range` is out of bounds, or is not on
+    /// a `char` boundary.
+    pub fn edit(&mut self, range: Range<usize>, new: &str) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "edit range {:?} out of bounds of rope of length {}",
+            range,
+            self.len()
+        );
+        if range.is_empty() && new.is_empty() {
+            return;
+        }
+        let (before, rest) = Node::split(&self.root, range.start);
+        let (_, after) = Node::split(&rest, range.end - range.start);
+        let root = Node::join(Node::join(before, Node::from_str(new)), after);
+
+        let last_edit = LastEdit {
+            previous: Arc::downgrade(&self.root),
+            delta: RopeDelta {
+                range,
+                text: new.into(),
+            },
+        };
+        *self = Rope::from_root(root);
+        self.last_edit = Some(Arc::new(last_edit));
+    }
+
+    /// Apply a [`RopeDelta`] to the text.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`edit`](Rope::edit).
+    pub fn apply(&mut self, delta: &RopeDelta) {
+        self.edit(delta.range.clone(), &delta.text);
+    }
+
+    /// If this rope is the result of a single edit to `previous`, returns
+    /// that edit.
+    ///
+    /// This lets a widget update incrementally when its data changes, in
+    /// the common case that it changes one edit at a time.
+    pub fn edit_since(&self, previous: &Rope) -> Option<&RopeDelta> {
+        self.last_edit
+            .as_ref()
+            .filter(|edit| Weak::as_ptr(&edit.previous) == Arc::as_ptr(&previous.root))
+            .map(|edit| &edit.delta)
+    }
+
+    /// Returns an iterator over the chunks of the text, in order.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            stack: vec![&self.root],
+        }
+    }
+
+    /// Returns a range of the text, or `None` if the range is out of bounds
+    /// or is not on `char` boundaries.
+    ///
+    /// This does not make the text contiguous; the slice is only copied if
+    /// it spans more than one chunk.
+    pub fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        if let Some(flat) = self.flat.get() {
+            return flat.get(range).map(Cow::from);
+        }
+
+        let mut result: Option<Cow<str>> = None;
+        let mut chunk_start = 0;
+        for chunk in self.chunks() {
+            let chunk_end = chunk_start + chunk.len();
+            if chunk_end > range.start || chunk_end == range.end {
+                let start = range.start.saturating_sub(chunk_start);
+                let end = range.end.min(chunk_end) - chunk_start;
+                let part = chunk.get(start..end)?;
+                result = match result {
+                    None => Some(Cow::from(part)),
+                    Some(prev) => Some(Cow::from(prev.into_owned() + part)),
+                };
+                if chunk_end >= range.end {
+                    break;
+                }
+            }
+            chunk_start = chunk_end;
+        }
+        Some(result.unwrap_or_default())
+    }
+
+    /// The chunk that contains the byte at `offset`, and the offset it
+    /// starts at. The end of the text is in the last chunk.
+    fn chunk_at(&self, mut offset: usize) -> (&str, usize) {
+        let mut node = &self.root;
+        let mut start = 0;
+        loop {
+            match &**node {
+                Node::Leaf(text) => return (text, start),
+                Node::Branch { left, right, .. } => {
+                    if offset < left.len() {
+                        node = left;
+                    } else {
+                        offset -= left.len();
+                        start += left.len();
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The `char` that starts at `offset`, if any.
+    fn char_at(&self, offset: usize) -> Option<char> {
+        let (chunk, start) = self.chunk_at(offset);
+        chunk.get(offset - start..)?.chars().next()
+    }
+
+    /// The grapheme boundary after (if `next`) or before `from`, feeding
+    /// the chunks around it to the cursor as it asks for them.
+    fn grapheme_boundary(&self, from: usize, next: bool) -> Option<usize> {
+        if from > self.len() {
+            return None;
+        }
+        let mut cursor = GraphemeCursor::new(from, self.len(), true);
+        let (mut chunk, mut start) = self.chunk_at(from);
+        loop {
+            let result = if next {
+                cursor.next_boundary(chunk, start)
+            } else {
+                cursor.prev_boundary(chunk, start)
+            };
+            match result {
+                Ok(offset) => return offset,
+                Err(GraphemeIncomplete::PreContext(end)) => {
+                    let (context, context_start) = self.chunk_at(end - 1);
+                    cursor.provide_context(&context[..end - context_start], context_start);
+                }
+                Err(GraphemeIncomplete::NextChunk) => {
+                    let (c, s) = self.chunk_at(start + chunk.len());
+                    chunk = c;
+                    start = s;
+                }
+                Err(GraphemeIncomplete::PrevChunk) => {
+                    let (c, s) = self.chunk_at(start - 1);
+                    chunk = c;
+                    start = s;
+                }
+                Err(GraphemeIncomplete::InvalidOffset) => return None,
+            }
+        }
+    }
+}
+
+impl Node {
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf(s) => s.len(),
+            Node::Branch { len, .. } => *len,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    fn children(&self) -> (Arc<Node>, Arc<Node>) {
+        match self {
+            Node::Leaf(_) => unreachable!("leaves have no children"),
+            Node::Branch { left, right, .. } => (left.clone(), right.clone()),
+        }
+    }
+
+    fn branch(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        Arc::new(Node::Branch {
+            len: left.len() + right.len(),
+            height: left.height().max(right.height()) + 1,
+            left,
+            right,
+        })
+    }
+
+    /// Build a balanced tree for `s`.
+    fn from_str(s: &str) -> Arc<Node> {
+        let mut leaves = Vec::new();
+        let mut rest = s;
+        while rest.len() > MAX_LEAF {
+            let mut split = MAX_LEAF;
+            while !rest.is_char_boundary(split) {
+                split -= 1;
+            }
+            leaves.push(Arc::new(Node::Leaf(rest[..split].to_owned())));
+            rest = &rest[split..];
+        }
+        leaves.push(Arc::new(Node::Leaf(rest.to_owned())));
+        Node::build(&leaves)
+    }
+
+    fn build(nodes: &[Arc<Node>]) -> Arc<Node> {
+        match nodes.len() {
+            1 => nodes[0].clone(),
+            n => Node::branch(Node::build(&nodes[..n / 2]), Node::build(&nodes[n / 2..])),
+        }
+    }
+
+    /// Split a tree at `offset`; either side may be an empty leaf.
+    fn split(node: &Arc<Node>, offset: usize) -> (Arc<Node>, Arc<Node>) {
+        let empty = || Arc::new(Node::Leaf(String::new()));
+        if offset == 0 {
+            return (empty(), node.clone());
+        }
+        if offset >= node.len() {
+            return (node.clone(), empty());
+        }
+        match &**node {
+            Node::Leaf(s) => (
+                Arc::new(Node::Leaf(s[..offset].to_owned())),
+                Arc::new(Node::Leaf(s[offset..].to_owned())),
+            ),
+            Node::Branch { left, right, .. } if offset <= left.len() => {
+                let (a, b) = Node::split(left, offset);
+                (a, Node::join(b, right.clone()))
+            }
+            Node::Branch { left, right, .. } => {
+                let (a, b) = Node::split(right, offset - left.len());
+                (Node::join(left.clone(), a), b)
+            }
+        }
+    }
+
+    /// Concatenate two trees, keeping the result balanced.
+    ///
+    /// This is the join operation of an AVL tree; leaves are merged where
+    /// they are small enough.
+    fn join(a: Arc<Node>, b: Arc<Node>) -> Arc<Node> {
+        if a.len() == 0 {
+            return b;
+        }
+        if b.len() == 0 {
+            return a;
+        }
+        if let (Node::Leaf(a), Node::Leaf(b)) = (&*a, &*b) {
+            if a.len() + b.len() <= MAX_LEAF {
+                return Arc::new(Node::Leaf(format!("{}{}", a, b)));
+            }
+        }
+        if let Some(joined) = Node::merge_edge_leaf(&a, &b) {
+            return joined;
+        }
+
+        let (ha, hb) = (a.height(), b.height());
+        if ha > hb + 1 {
+            let (left, right) = a.children();
+            Node::rebalance(left, Node::join(right, b))
+        } else if hb > ha + 1 {
+            let (left, right) = b.children();
+            Node::rebalance(Node::join(a, left), right)
+        } else {
+            Node::branch(a, b)
+        }
+    }
+
+    /// If one of the trees is a small leaf, merge it into the adjacent leaf
+    /// of the other, which leaves the shape of the tree unchanged.
+    fn merge_edge_leaf(a: &Arc<Node>, b: &Arc<Node>) -> Option<Arc<Node>> {
+        match (&**a, &**b) {
+            (Node::Branch { .. }, Node::Leaf(text)) => Node::map_edge_leaf(a, false, text),
+            (Node::Leaf(text), Node::Branch { .. }) => Node::map_edge_leaf(b, true, text),
+            _ => None,
+        }
+    }
+
+    /// Add `text` to the first (if `first`) or last leaf of `node`, if it fits.
+    fn map_edge_leaf(node: &Arc<Node>, first: bool, text: &str) -> Option<Arc<Node>> {
+        match &**node {
+            Node::Leaf(leaf) if leaf.len() + text.len() <= MAX_LEAF => {
+                let merged = if first {
+                    format!("{}{}", text, leaf)
+                } else {
+                    format!("{}{}", leaf, text)
+                };
+                Some(Arc::new(Node::Leaf(merged)))
+            }
+            Node::Leaf(_) => None,
+            Node::Branch { left, right, .. } if first => {
+                let left = Node::map_edge_leaf(left, first, text)?;
+                Some(Node::branch(left, right.clone()))
+            }
+            Node::Branch { left, right, .. } => {
+                let right = Node::map_edge_leaf(right, first, text)?;
+                Some(Node::branch(left.clone(), right))
+            }
+        }
+    }
+
+    /// Combine two trees whose heights differ by at most two.
+    fn rebalance(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        let (hl, hr) = (left.height(), right.height());
+        if hr > hl + 1 {
+            let (rl, rr) = right.children();
+            if rl.height() > rr.height() {
+                let (x, y) = rl.children();
+                Node::branch(Node::branch(left, x), Node::branch(y, rr))
+            } else {
+                Node::branch(Node::branch(left, rl), rr)
+            }
+        } else if hl > hr + 1 {
+            let (ll, lr) = left.children();
+            if lr.height() > ll.height() {
+                let (x, y) = lr.children();
+                Node::branch(Node::branch(ll, x), Node::branch(y, right))
+            } else {
+                Node::branch(ll, Node::branch(lr, right))
+            }
+        } else {
+            Node::branch(left, right)
+        }
+    }
+}
+
+/// An iterator over the chunks of a [`Rope`].
+///
+/// This is created by [`Rope::chunks`].
+pub struct Chunks<'a> {
+    stack: Vec<&'a Arc<Node>>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while let Some(node) = self.stack.pop() {
+            match &**node {
+                Node::Leaf(text) if text.is_empty() => (),
+                Node::Leaf(text) => return Some(text),
+                Node::Branch { left, right, .. } => {
+                    self.stack.push(right);
+                    self.stack.push(left);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl RopeDelta {
+    /// The range of the new text, after the edit.
+    pub fn new_range(&self) -> Range<usize> {
+        self.range.start..self.range.start + self.text.len()
+    }
+
+    /// Map an offset in the text before the edit to the equivalent offset
+    /// after it.
+    ///
+    /// Offsets inside the replaced range are moved to the end of the new text.
+    pub fn transform_offset(&self, offset: usize) -> usize {
+        editable_text::transform_offset(offset, &self.range, self.text.len())
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Rope::new()
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(s: &str) -> Rope {
+        Rope::from_root(Node::from_str(s))
+    }
+}
+
+impl From<String> for Rope {
+    fn from(s: String) -> Rope {
+        if s.len() <= MAX_LEAF {
+            Rope::from_root(Arc::new(Node::Leaf(s)))
+        } else {
+            Rope::from(s.as_str())
+        }
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rope").field(&self.to_string()).finish()
+    }
+}
+
+impl Data for Rope {
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
+}
+
+impl PietTextStorage for Rope {
+    fn as_str(&self) -> &str {
+        match &*self.root {
+            Node::Leaf(text) => text,
+            Node::Branch { .. } => self.flat.get_or_init(|| self.to_string()),
+        }
+    }
+}
+
+impl TextStorage for Rope {}
+
+impl EditableText for Rope {
+    fn cursor(&self, position: usize) -> Option<StringCursor<'_>> {
+        editable_text::cursor(self.as_str(), position)
+    }
+
+    fn edit(&mut self, range: Range<usize>, new: impl Into<String>) {
+        Rope::edit(self, range, &new.into())
+    }
+
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        Rope::slice(self, range)
+    }
+
+    fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    fn prev_word_offset(&self, from: usize) -> Option<usize> {
+        let mut offset = from;
+        let mut passed_alphanumeric = false;
+        while let Some(prev) = self.prev_grapheme_offset(offset) {
+            if self.char_at(prev)?.is_alphanumeric() {
+                passed_alphanumeric = true;
+            } else if passed_alphanumeric {
+                return Some(offset);
+            }
+            offset = prev;
+        }
+        None
+    }
+
+    fn next_word_offset(&self, from: usize) -> Option<usize> {
+        if from > self.len() {
+            return None;
+        }
+        let mut offset = from;
+        let mut passed_alphanumeric = false;
+        while let Some(next) = self.next_grapheme_offset(offset) {
+            if self.char_at(offset)?.is_alphanumeric() {
+                passed_alphanumeric = true;
+            } else if passed_alphanumeric {
+                return Some(offset);
+            }
+            offset = next;
+        }
+        Some(self.len())
+    }
+
+    fn prev_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        self.grapheme_boundary(offset, false)
+    }
+
+    fn next_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        self.grapheme_boundary(offset, true)
+    }
+
+    fn prev_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        if offset == 0 || offset > self.len() {
+            return None;
+        }
+        // chunks end on char boundaries, so the char is in one chunk.
+        let (chunk, start) = self.chunk_at(offset - 1);
+        let c = chunk.get(..offset - start)?.chars().next_back()?;
+        Some(offset - c.len_utf8())
+    }
+
+    fn next_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        if offset >= self.len() {
+            return None;
+        }
+        let c = self.char_at(offset)?;
+        Some(offset + c.len_utf8())
+    }
+
+    fn preceding_line_break(&self, from: usize) -> usize {
+        let mut end = from.min(self.len());
+        while end > 0 {
+            let (chunk, start) = self.chunk_at(end - 1);
+            let bytes = &chunk.as_bytes()[..end - start];
+            if let Some(i) = bytes.iter().rposition(|&b| b == b'\n') {
+                return start + i + 1;
+            }
+            end = start;
+        }
+        0
+    }
+
+    fn next_line_break(&self, from: usize) -> usize {
+        let mut offset = from;
+        while offset < self.len() {
+            let (chunk, start) = self.chunk_at(offset);
+            let bytes = &chunk.as_bytes()[offset - start..];
+            if let Some(i) = bytes.iter().position(|&b| b == b'\n') {
+                return offset + i;
+            }
+            offset = start + chunk.len();
+        }
+        self.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.root.len() == 0
+    }
+
+    fn from_str(s: &str) -> Self {
+        Rope::from(s)
+    }
+
+    fn changed_since(&self, previous: &Self) -> Option<(Range<usize>, usize)> {
+        self.edit_since(previous)
+            .map(|delta| (delta.range.clone(), delta.text.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    fn check_balanced(node: &Node) -> usize {
+        match node {
+            Node::Leaf(text) => {
+                assert!(text.len() <= MAX_LEAF);
+                0
+            }
+            Node::Branch {
+                len,
+                height,
+                left,
+                right,
+            } => {
+                let (hl, hr) = (check_balanced(left), check_balanced(right));
+                assert!((hl as isize - hr as isize).abs() <= 1, "unbalanced");
+                assert_eq!(*height, hl.max(hr) + 1);
+                assert_eq!(*len, left.len() + right.len());
+                *height
+            }
+        }
+    }
+
+    #[test]
+    fn edits_match_string() {
+        let base = "the quick brown fox jumps over the lazy dog\n".repeat(200);
+        let mut rope = Rope::from(base.as_str());
+        let mut string = base;
+
+        // a simple deterministic sequence of edits
+        let mut seed = 7usize;
+        for i in 0..500 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345) % (1 << 31);
+            let start = seed % (string.len() + 1);
+            let end = (start + seed % 20).min(string.len());
+            let new = match i % 3 {
+                0 => "",
+                1 => "x",
+                _ => "inserted text ",
+            };
+            rope.edit(start..end, new);
+            string.replace_range(start..end, new);
+            check_balanced(&rope.root);
+        }
+        assert_eq!(rope.len(), string.len());
+        assert_eq!(rope.to_string(), string);
+        assert_eq!(rope.as_str(), string);
+        let (quarter, half) = (string.len() / 4, string.len() / 2);
+        assert_eq!(rope.slice(quarter..half).unwrap(), &string[quarter..half]);
+        assert_eq!(rope.slice(5..5).unwrap(), "");
+        assert!(rope.slice(10..string.len() + 1).is_none());
+    }
+
+    #[test]
+    fn navigation_matches_string_across_chunks() {
+        // words, newlines and multi-byte graphemes that straddle leaves
+        let string = "héllo wörld, e\u{301}\u{301} 🇫🇷🇩🇪\r\nnext line\n".repeat(200);
+        let rope = Rope::from(string.as_str());
+        assert!(rope.root.height() > 1);
+
+        let mut offset = 0;
+        while offset <= string.len() {
+            assert_eq!(
+                rope.prev_grapheme_offset(offset),
+                string.prev_grapheme_offset(offset),
+                "at {}",
+                offset
+            );
+            assert_eq!(
+                rope.next_grapheme_offset(offset),
+                string.next_grapheme_offset(offset)
+            );
+            assert_eq!(
+                rope.prev_codepoint_offset(offset),
+                string.prev_codepoint_offset(offset)
+            );
+            assert_eq!(
+                rope.next_codepoint_offset(offset),
+                string.next_codepoint_offset(offset)
+            );
+            assert_eq!(
+                rope.prev_word_offset(offset),
+                string.prev_word_offset(offset)
+            );
+            assert_eq!(
+                rope.next_word_offset(offset),
+                string.next_word_offset(offset)
+            );
+            assert_eq!(
+                rope.preceding_line_break(offset),
+                string.preceding_line_break(offset)
+            );
+            assert_eq!(rope.next_line_break(offset), string.next_line_break(offset));
+            offset = string
+                .next_codepoint_offset(offset)
+                .unwrap_or(string.len() + 1);
+        }
+        // none of this needed the text to be contiguous
+        assert!(rope.flat.get().is_none());
+    }
+
+    #[test]
+    fn edit_since() {
+        let original = Rope::from("hello world");
+        let mut edited = original.clone();
+        edited.edit(5..11, ", rope");
+        assert!(!edited.same(&original));
+        assert_eq!(edited.to_string(), "hello, rope");
+
+        let delta = edited.edit_since(&original).unwrap().clone();
+        assert_eq!(delta.range, 5..11);
+        assert_eq!(delta.new_range(), 5..11);
+        assert_eq!(delta.transform_offset(2), 2);
+        assert_eq!(delta.transform_offset(8), 11);
+
+        let mut twice = edited.clone();
+        twice.edit(0..0, "oh, ");
+        assert!(twice.edit_since(&original).is_none());
+        assert!(twice.edit_since(&edited).is_some());
+
+        let mut replayed = original.clone();
+        replayed.apply(&delta);
+        assert_eq!(replayed.to_string(), edited.to_string());
+    }
+}
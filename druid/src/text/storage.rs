@@ -19,7 +19,7 @@ use std::sync::Arc;
 use crate::piet::{PietTextLayoutBuilder, TextStorage as PietTextStorage};
 use crate::{Data, Env};
 
-use super::attribute::Link;
+use super::attribute::{AttributeSpans, Link};
 
 /// A type that represents text that can be displayed.
 pub trait TextStorage: PietTextStorage + Data {
@@ -43,6 +43,20 @@ pub trait TextStorage: PietTextStorage + Data {
     fn links(&self) -> &[Link] {
         &[]
     }
+
+    /// The style spans applied to this text, if any.
+    ///
+    /// This is used for hit-testing, via [`TextLayout::hit_test`], and for the
+    /// attributes that piet doesn't know about, like shadows and line heights.
+    /// The other attributes are drawn by [`add_attributes`], except that lines
+    /// that are moved by a line height or paragraph spacing are laid out again
+    /// with these spans.
+    ///
+    /// [`TextLayout::hit_test`]: super::TextLayout::hit_test
+    /// [`add_attributes`]: TextStorage::add_attributes
+    fn attributes(&self) -> Option<&AttributeSpans> {
+        None
+    }
 }
 
 /// A reference counted string slice.
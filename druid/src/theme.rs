@@ -16,9 +16,13 @@
 
 #![allow(missing_docs)]
 
+use crate::color::ColorExt;
 use crate::piet::Color;
 
-use crate::{Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key};
+use crate::{
+    ArcStr, Data, Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key,
+    PixelSnapping, SystemTheme, TextAntialiasing, TextHinting, TextRenderingOptions, Vec2,
+};
 
 pub const WINDOW_BACKGROUND_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.window_background_color");
@@ -62,9 +66,44 @@ pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Key<Color> =
 pub const SELECTION_TEXT_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.selection_text_color");
 pub const CURSOR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.cursor_color");
+/// The width, in display points, of the text caret.
+pub const CURSOR_WIDTH: Key<f64> = Key::new("org.linebender.druid.theme.cursor_width");
+/// How long, in milliseconds, the text caret stays on or off while blinking.
+///
+/// A value of `0` keeps the caret on.
+pub const CURSOR_BLINK_INTERVAL: Key<u64> =
+    Key::new("org.linebender.druid.theme.cursor_blink_interval");
 
 pub const TEXT_SIZE_NORMAL: Key<f64> = Key::new("org.linebender.druid.theme.text_size_normal");
 pub const TEXT_SIZE_LARGE: Key<f64> = Key::new("org.linebender.druid.theme.text_size_large");
+/// The default line height for text, as a multiple of each line's natural height.
+///
+/// Values above `1.0` add leading between lines, and values below `1.0` tighten it.
+pub const TEXT_LINE_HEIGHT: Key<f64> = Key::new("org.linebender.druid.theme.text_line_height");
+/// The default extra space, in display points, added after each paragraph of text.
+pub const TEXT_PARAGRAPH_SPACING: Key<f64> =
+    Key::new("org.linebender.druid.theme.text_paragraph_spacing");
+/// The antialiasing mode used to draw text.
+///
+/// This and the other text rendering keys are read from the root [`Env`] when
+/// a window is created, and again whenever the root `Env` changes with the
+/// system theme, and passed to [`WindowHandle::set_text_rendering`].
+///
+/// [`WindowHandle::set_text_rendering`]: crate::WindowHandle::set_text_rendering
+pub const TEXT_ANTIALIASING: Key<TextAntialiasing> =
+    Key::new("org.linebender.druid.theme.text_antialiasing");
+/// The hinting mode used to draw text.
+pub const TEXT_HINTING: Key<TextHinting> = Key::new("org.linebender.druid.theme.text_hinting");
+/// The gamma used when drawing text; `0.0` uses the platform default.
+///
+/// This is only respected on Windows. Cairo, which draws text on GTK and X11,
+/// and Core Text on macOS have no gamma setting, so it is ignored there.
+pub const TEXT_GAMMA: Key<f64> = Key::new("org.linebender.druid.theme.text_gamma");
+/// How layout and painting are aligned to device pixels.
+///
+/// See [`PixelSnapping`] for the available policies.
+pub const PIXEL_SNAPPING: Key<PixelSnapping> =
+    Key::new("org.linebender.druid.theme.pixel_snapping");
 pub const BASIC_WIDGET_HEIGHT: Key<f64> =
     Key::new("org.linebender.druid.theme.basic_widget_height");
 
@@ -78,6 +117,26 @@ pub const UI_FONT_BOLD: Key<FontDescriptor> = Key::new("org.linebender.druid.the
 pub const UI_FONT_ITALIC: Key<FontDescriptor> =
     Key::new("org.linebender.druid.theme.ui-font-italic");
 
+/// The font that emoji are drawn with, so that they come out in color instead
+/// of as monochrome outlines or missing glyphs when the text's own font lacks
+/// them.
+///
+/// An empty name, or a font that isn't installed, leaves emoji to the
+/// platform's font fallback.
+pub const EMOJI_FONT: Key<ArcStr> = Key::new("org.linebender.druid.theme.emoji-font");
+
+/// The color emoji font that comes with the platform.
+const DEFAULT_EMOJI_FONT: &str = if cfg!(target_os = "windows") {
+    "Segoe UI Emoji"
+} else if cfg!(target_os = "macos") {
+    "Apple Color Emoji"
+} else if cfg!(target_arch = "wasm32") {
+    // browsers fall back to a color emoji font on their own
+    ""
+} else {
+    "Noto Color Emoji"
+};
+
 /// The default minimum width for a 'wide' widget; a textbox, slider, progress bar, etc.
 pub const WIDE_WIDGET_WIDTH: Key<f64> = Key::new("org.linebender.druid.theme.long-widget-width");
 pub const BORDERED_WIDGET_HEIGHT: Key<f64> =
@@ -95,6 +154,12 @@ pub const WIDGET_PADDING_HORIZONTAL: Key<f64> =
 /// The default vertical spacing between widgets.
 pub const WIDGET_PADDING_VERTICAL: Key<f64> =
     Key::new("org.linebender.druid.theme.widget-padding-v");
+/// The gap that a [`Flex`] places between its children, unless it was given
+/// one with [`with_child_spacing`]. It is `0.0` by default.
+///
+/// [`Flex`]: crate::widget::Flex
+/// [`with_child_spacing`]: crate::widget::Flex::with_child_spacing
+pub const FLEX_CHILD_SPACING: Key<f64> = Key::new("org.linebender.druid.theme.flex-child-spacing");
 /// The default internal (horizontal) padding for visually distinct components
 /// of a widget; for instance between a checkbox and its label.
 pub const WIDGET_CONTROL_COMPONENT_PADDING: Key<f64> =
@@ -115,6 +180,282 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> =
 /// Minimum length for any scrollbar to be when measured on that
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
+/// The color of the track behind a scrollbar, drawn when scrollbars are
+/// [always visible].
+///
+/// [always visible]: crate::scroll_component::ScrollbarVisibility::Always
+pub const SCROLLBAR_TRACK_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.scrollbar_track_color");
+
+/// How long, in milliseconds, a press has to be held to be a long press.
+pub const LONG_PRESS_DURATION: Key<u64> =
+    Key::new("org.linebender.druid.theme.long_press_duration");
+/// How far, in display points, a long press can move before it is cancelled.
+pub const LONG_PRESS_SLOP: Key<f64> = Key::new("org.linebender.druid.theme.long_press_slop");
+/// How far, in display points, the mouse has to move with the button held
+/// down for a [`Draggable`] to start a drag.
+///
+/// [`Draggable`]: crate::widget::Draggable
+pub const DRAG_THRESHOLD: Key<f64> = Key::new("org.linebender.druid.theme.drag_threshold");
+
+/// Whether programmatic changes of the scroll offset, like bringing a widget
+/// into view, are animated instead of jumping to the new offset.
+pub const ANIMATE_SCROLLING: Key<bool> = Key::new("org.linebender.druid.theme.animate_scrolling");
+/// How long, in milliseconds, an animated change of the scroll offset takes.
+pub const SCROLL_ANIMATION_DURATION: Key<u64> =
+    Key::new("org.linebender.druid.theme.scroll_animation_duration");
+
+/// The color of the drop shadow of a [`Container`] with a shadow.
+///
+/// [`Container`]: crate::widget::Container
+pub const SHADOW_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.shadow_color");
+/// How far, in display points, a drop shadow is blurred.
+pub const SHADOW_BLUR_RADIUS: Key<f64> = Key::new("org.linebender.druid.theme.shadow_blur_radius");
+/// How far a drop shadow is offset from the widget casting it.
+pub const SHADOW_OFFSET: Key<Vec2> = Key::new("org.linebender.druid.theme.shadow_offset");
+
+/// Whether the layout should be mirrored for right-to-left locales.
+///
+/// When this is `true`, horizontal [`Flex`] containers lay out their children
+/// starting from the right, and [`Align`] and [`Padding`] swap their left
+/// and right edges.
+///
+/// [`Flex`]: crate::widget::Flex
+/// [`Align`]: crate::widget::Align
+/// [`Padding`]: crate::widget::Padding
+pub const LAYOUT_RTL: Key<bool> = Key::new("org.linebender.druid.theme.layout_rtl");
+
+/// Whether the user prefers dark colors, as set in the system settings.
+///
+/// Druid keeps this up to date; it changes along with
+/// [`Event::SystemThemeChanged`]. To switch between a light and a dark theme
+/// with it, use [`AppLauncher::configure_env_for_theme`].
+///
+/// [`Event::SystemThemeChanged`]: crate::Event::SystemThemeChanged
+/// [`AppLauncher::configure_env_for_theme`]: crate::AppLauncher::configure_env_for_theme
+pub const SYSTEM_DARK_MODE: Key<bool> = Key::new("org.linebender.druid.theme.system_dark_mode");
+/// The accent color chosen in the system settings, or [`PRIMARY_DARK`] on
+/// platforms without one.
+pub const SYSTEM_ACCENT_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.system_accent_color");
+
+/// A built-in set of theme values.
+///
+/// Apart from [`Druid`], these approximate the look of a native platform,
+/// including colors, control metrics, and fonts, so that an application
+/// blends in with its surroundings.
+///
+/// A preset can be applied at launch with [`AppLauncher::configure_env`],
+/// or at runtime by storing it in your app's data and applying it in an
+/// [`EnvScope`]:
+///
+/// ```no_run
+/// # use druid::{theme::ThemePreset, widget::{EnvScope, Label}, AppLauncher, WindowDesc};
+/// // at launch:
+/// let window = WindowDesc::new(Label::new("Hello"));
+/// AppLauncher::with_window(window)
+///     .configure_env(|env, _| ThemePreset::platform().apply(env))
+///     .launch(())
+///     .unwrap();
+///
+/// // at runtime:
+/// let root = EnvScope::new(|env, preset: &ThemePreset| preset.apply(env), Label::new("Hello"));
+/// ```
+///
+/// [`Druid`]: ThemePreset::Druid
+/// [`AppLauncher::configure_env`]: crate::AppLauncher::configure_env
+/// [`EnvScope`]: crate::widget::EnvScope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum ThemePreset {
+    /// Druid's own dark theme; this is the default.
+    Druid,
+    /// Approximates the Windows 11 light theme.
+    Windows,
+    /// Approximates the macOS light appearance.
+    MacOs,
+    /// Approximates the GNOME Adwaita light theme.
+    Adwaita,
+}
+
+impl ThemePreset {
+    /// The preset that best matches the platform we are running on.
+    ///
+    /// This is [`ThemePreset::Druid`] on platforms without a matching preset.
+    pub fn platform() -> ThemePreset {
+        if cfg!(target_os = "windows") {
+            ThemePreset::Windows
+        } else if cfg!(target_os = "macos") {
+            ThemePreset::MacOs
+        } else if cfg!(all(unix, not(target_arch = "wasm32"))) {
+            ThemePreset::Adwaita
+        } else {
+            ThemePreset::Druid
+        }
+    }
+
+    /// Set the values of this preset in `env`, replacing those of any other preset.
+    ///
+    /// Only the colors, control metrics, and fonts that presets style are changed;
+    /// every other key, including ones you customized, is left untouched.
+    pub fn apply(self, env: &mut Env) {
+        reset_preset_keys(env);
+        match self {
+            ThemePreset::Druid => (),
+            ThemePreset::Windows => {
+                add_light_palette(env, Color::rgb8(0x00, 0x67, 0xc0));
+                env.set(WINDOW_BACKGROUND_COLOR, Color::rgb8(0xf3, 0xf3, 0xf3));
+                env.set(BUTTON_BORDER_RADIUS, 4.);
+                env.set(BUTTON_BORDER_WIDTH, 1.);
+                env.set(TEXTBOX_BORDER_RADIUS, 4.);
+                env.set(BORDERED_WIDGET_HEIGHT, 32.);
+                env.set(TEXTBOX_INSETS, Insets::new(10.0, 6.0, 10.0, 6.0));
+                env.set(SCROLLBAR_WIDTH, 6.);
+                env.set(SCROLLBAR_RADIUS, 3.);
+                add_fonts(env, FontFamily::new_unchecked("Segoe UI"), 14.0);
+            }
+            ThemePreset::MacOs => {
+                add_light_palette(env, Color::rgb8(0x00, 0x7a, 0xff));
+                env.set(WINDOW_BACKGROUND_COLOR, Color::rgb8(0xec, 0xec, 0xec));
+                env.set(BUTTON_BORDER_RADIUS, 5.);
+                env.set(BUTTON_BORDER_WIDTH, 1.);
+                env.set(TEXTBOX_BORDER_RADIUS, 0.);
+                env.set(BORDERED_WIDGET_HEIGHT, 22.);
+                env.set(TEXTBOX_INSETS, Insets::new(3.0, 3.0, 3.0, 3.0));
+                env.set(WIDGET_PADDING_VERTICAL, 8.0);
+                env.set(SCROLLBAR_WIDTH, 7.);
+                env.set(SCROLLBAR_RADIUS, 3.5);
+                add_fonts(env, FontFamily::SYSTEM_UI, 13.0);
+            }
+            ThemePreset::Adwaita => {
+                add_light_palette(env, Color::rgb8(0x35, 0x84, 0xe4));
+                env.set(WINDOW_BACKGROUND_COLOR, Color::rgb8(0xfa, 0xfa, 0xfa));
+                env.set(TEXT_COLOR, Color::rgb8(0x2e, 0x34, 0x36));
+                env.set(BUTTON_BORDER_RADIUS, 6.);
+                env.set(BUTTON_BORDER_WIDTH, 1.);
+                env.set(TEXTBOX_BORDER_RADIUS, 6.);
+                env.set(BORDERED_WIDGET_HEIGHT, 34.);
+                env.set(TEXTBOX_INSETS, Insets::new(9.0, 8.0, 9.0, 8.0));
+                env.set(WIDGET_PADDING_VERTICAL, 12.0);
+                env.set(WIDGET_PADDING_HORIZONTAL, 12.0);
+                env.set(WIDGET_CONTROL_COMPONENT_PADDING, 6.0);
+                add_fonts(env, FontFamily::new_unchecked("Cantarell"), 14.5);
+            }
+        }
+    }
+}
+
+/// Set the keys that any preset changes back to their values in the [`Druid`] preset.
+///
+/// [`Druid`]: ThemePreset::Druid
+fn reset_preset_keys(env: &mut Env) {
+    let defaults = add_to_env(Env::empty());
+    macro_rules! reset {
+        ($($key:expr),* $(,)?) => {
+            $(env.set($key, defaults.get($key));)*
+        };
+    }
+    reset!(
+        WINDOW_BACKGROUND_COLOR,
+        TEXT_COLOR,
+        DISABLED_TEXT_COLOR,
+        PLACEHOLDER_COLOR,
+        PRIMARY_LIGHT,
+        PRIMARY_DARK,
+        BACKGROUND_LIGHT,
+        BACKGROUND_DARK,
+        FOREGROUND_LIGHT,
+        FOREGROUND_DARK,
+        DISABLED_FOREGROUND_LIGHT,
+        DISABLED_FOREGROUND_DARK,
+        BUTTON_DARK,
+        BUTTON_LIGHT,
+        DISABLED_BUTTON_DARK,
+        DISABLED_BUTTON_LIGHT,
+        BORDER_DARK,
+        BORDER_LIGHT,
+        SELECTED_TEXT_BACKGROUND_COLOR,
+        SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR,
+        SELECTION_TEXT_COLOR,
+        CURSOR_COLOR,
+        SCROLLBAR_COLOR,
+        SCROLLBAR_BORDER_COLOR,
+        SCROLLBAR_TRACK_COLOR,
+        BUTTON_BORDER_RADIUS,
+        BUTTON_BORDER_WIDTH,
+        TEXTBOX_BORDER_RADIUS,
+        BORDERED_WIDGET_HEIGHT,
+        TEXTBOX_INSETS,
+        SCROLLBAR_WIDTH,
+        SCROLLBAR_RADIUS,
+        WIDGET_PADDING_VERTICAL,
+        WIDGET_PADDING_HORIZONTAL,
+        WIDGET_CONTROL_COMPONENT_PADDING,
+        TEXT_SIZE_NORMAL,
+        UI_FONT,
+        UI_FONT_BOLD,
+        UI_FONT_ITALIC,
+    );
+}
+
+/// The colors shared by the light presets, using `accent` for primary colors.
+fn add_light_palette(env: &mut Env, accent: Color) {
+    env.set(TEXT_COLOR, Color::rgb8(0x1b, 0x1b, 0x1b));
+    env.set(DISABLED_TEXT_COLOR, Color::rgb8(0xa0, 0xa0, 0xa0));
+    env.set(PLACEHOLDER_COLOR, Color::rgb8(0x8a, 0x8a, 0x8a));
+    env.set(PRIMARY_LIGHT, accent.lighten(0.08));
+    env.set(PRIMARY_DARK, accent.clone());
+    env.set(BACKGROUND_LIGHT, Color::WHITE);
+    env.set(BACKGROUND_DARK, Color::rgb8(0xf6, 0xf6, 0xf6));
+    env.set(FOREGROUND_LIGHT, Color::WHITE);
+    env.set(FOREGROUND_DARK, Color::rgb8(0xe1, 0xe1, 0xe1));
+    env.set(DISABLED_FOREGROUND_LIGHT, Color::rgb8(0xf0, 0xf0, 0xf0));
+    env.set(DISABLED_FOREGROUND_DARK, Color::rgb8(0xd6, 0xd6, 0xd6));
+    env.set(BUTTON_DARK, Color::rgb8(0xf3, 0xf3, 0xf3));
+    env.set(BUTTON_LIGHT, Color::rgb8(0xfd, 0xfd, 0xfd));
+    env.set(DISABLED_BUTTON_DARK, Color::rgb8(0xf0, 0xf0, 0xf0));
+    env.set(DISABLED_BUTTON_LIGHT, Color::rgb8(0xf5, 0xf5, 0xf5));
+    env.set(BORDER_DARK, Color::rgb8(0xc8, 0xc8, 0xc8));
+    env.set(BORDER_LIGHT, accent.clone());
+    env.set(SELECTED_TEXT_BACKGROUND_COLOR, accent.with_alpha(0.35));
+    env.set(
+        SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR,
+        Color::rgb8(0xdc, 0xdc, 0xdc),
+    );
+    env.set(SELECTION_TEXT_COLOR, Color::BLACK);
+    env.set(CURSOR_COLOR, Color::BLACK);
+    env.set(SCROLLBAR_COLOR, Color::rgb8(0x80, 0x80, 0x80));
+    env.set(SCROLLBAR_BORDER_COLOR, Color::rgb8(0xf0, 0xf0, 0xf0));
+    env.set(SCROLLBAR_TRACK_COLOR, Color::rgba8(0x00, 0x00, 0x00, 0x10));
+}
+
+/// Set the UI fonts to `family` at `size`.
+fn add_fonts(env: &mut Env, family: FontFamily, size: f64) {
+    let font = FontDescriptor::new(family).with_size(size);
+    env.set(TEXT_SIZE_NORMAL, size);
+    env.set(UI_FONT_BOLD, font.clone().with_weight(FontWeight::BOLD));
+    env.set(UI_FONT_ITALIC, font.clone().with_style(FontStyle::Italic));
+    env.set(UI_FONT, font);
+}
+
+/// Set the system theme keys in `env` to match `system_theme`.
+pub(crate) fn set_system_theme(env: &mut Env, system_theme: &SystemTheme) {
+    let accent = match system_theme.accent_color() {
+        Some(color) => color.clone(),
+        None => env.get(PRIMARY_DARK),
+    };
+    env.set(SYSTEM_DARK_MODE, system_theme.is_dark());
+    env.set(SYSTEM_ACCENT_COLOR, accent);
+}
+
+/// The text rendering options described by the text rendering keys in `env`.
+pub(crate) fn text_rendering_options(env: &Env) -> TextRenderingOptions {
+    let gamma = env.get(TEXT_GAMMA);
+    TextRenderingOptions {
+        antialiasing: env.get(TEXT_ANTIALIASING),
+        hinting: env.get(TEXT_HINTING),
+        gamma: if gamma > 0.0 { Some(gamma) } else { None },
+    }
+}
 
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
@@ -146,8 +487,17 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0x74))
         .adding(SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00))
         .adding(CURSOR_COLOR, Color::WHITE)
+        .adding(CURSOR_WIDTH, 1.0)
+        .adding(CURSOR_BLINK_INTERVAL, 500u64)
         .adding(TEXT_SIZE_NORMAL, 15.0)
         .adding(TEXT_SIZE_LARGE, 24.0)
+        .adding(TEXT_LINE_HEIGHT, 1.0)
+        .adding(EMOJI_FONT, DEFAULT_EMOJI_FONT)
+        .adding(TEXT_PARAGRAPH_SPACING, 0.0)
+        .adding(TEXT_ANTIALIASING, TextAntialiasing::Default)
+        .adding(TEXT_HINTING, TextHinting::Default)
+        .adding(TEXT_GAMMA, 0.0)
+        .adding(PIXEL_SNAPPING, PixelSnapping::Fractional)
         .adding(BASIC_WIDGET_HEIGHT, 18.0)
         .adding(WIDE_WIDGET_WIDTH, 100.)
         .adding(BORDERED_WIDGET_HEIGHT, 24.0)
@@ -163,9 +513,22 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(SCROLLBAR_MIN_SIZE, 45.)
         .adding(SCROLLBAR_RADIUS, 5.)
         .adding(SCROLLBAR_EDGE_WIDTH, 1.)
+        .adding(SCROLLBAR_TRACK_COLOR, Color::rgba8(0xff, 0xff, 0xff, 0x18))
+        .adding(LONG_PRESS_DURATION, 500u64)
+        .adding(LONG_PRESS_SLOP, 8.)
+        .adding(DRAG_THRESHOLD, 4.)
+        .adding(ANIMATE_SCROLLING, true)
+        .adding(SCROLL_ANIMATION_DURATION, 250u64)
+        .adding(SHADOW_COLOR, Color::rgba8(0x00, 0x00, 0x00, 0x60))
+        .adding(SHADOW_BLUR_RADIUS, 6.0)
+        .adding(SHADOW_OFFSET, Vec2::new(0.0, 2.0))
         .adding(WIDGET_PADDING_VERTICAL, 10.0)
         .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
         .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
+        .adding(FLEX_CHILD_SPACING, 0.0)
+        .adding(LAYOUT_RTL, false)
+        .adding(SYSTEM_DARK_MODE, false)
+        .adding(SYSTEM_ACCENT_COLOR, Color::rgb8(0x00, 0x8d, 0xdd))
         .adding(
             UI_FONT,
             FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(15.0),
@@ -183,3 +546,52 @@ pub(crate) fn add_to_env(env: Env) -> Env {
                 .with_size(15.0),
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_replace_each_other() {
+        let mut env = Env::empty();
+        ThemePreset::MacOs.apply(&mut env);
+        assert_eq!(env.get(TEXT_SIZE_NORMAL), 13.0);
+        assert_eq!(env.get(BORDERED_WIDGET_HEIGHT), 22.0);
+
+        ThemePreset::Druid.apply(&mut env);
+        assert_eq!(env.get(TEXT_SIZE_NORMAL), 15.0);
+        assert_eq!(env.get(BORDERED_WIDGET_HEIGHT), 24.0);
+    }
+
+    #[test]
+    fn presets_keep_other_keys() {
+        const CUSTOM: Key<f64> = Key::new("org.linebender.druid.test.custom");
+        let mut env = add_to_env(Env::empty())
+            .adding(CURSOR_BLINK_INTERVAL, 1000u64)
+            .adding(TEXT_HINTING, TextHinting::Full)
+            .adding(CUSTOM, 42.0);
+        ThemePreset::Adwaita.apply(&mut env);
+        assert_eq!(env.get(CURSOR_BLINK_INTERVAL), 1000);
+        assert_eq!(env.get(TEXT_HINTING), TextHinting::Full);
+        assert_eq!(env.get(CUSTOM), 42.0);
+        assert_eq!(env.get(BORDERED_WIDGET_HEIGHT), 34.0);
+    }
+
+    #[test]
+    fn text_rendering_from_env() {
+        let env = add_to_env(Env::empty());
+        assert_eq!(
+            text_rendering_options(&env),
+            TextRenderingOptions::default()
+        );
+
+        let env = env
+            .adding(TEXT_ANTIALIASING, TextAntialiasing::Grayscale)
+            .adding(TEXT_HINTING, TextHinting::Slight)
+            .adding(TEXT_GAMMA, 1.8);
+        let options = text_rendering_options(&env);
+        assert_eq!(options.antialiasing, TextAntialiasing::Grayscale);
+        assert_eq!(options.hinting, TextHinting::Slight);
+        assert_eq!(options.gamma, Some(1.8));
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Window toolbars and titlebar accessories, on macOS.
+//!
+//! These are configured by submitting the [`SET_TOOLBAR`],
+//! [`SET_TOOLBAR_STYLE`], [`SET_TITLEBAR_ACCESSORY`] and
+//! [`SET_TITLEBAR_TRANSPARENT`] commands to a window. On other platforms
+//! these commands have no effect.
+//!
+//! [`SET_TOOLBAR`]: crate::commands::SET_TOOLBAR
+//! [`SET_TOOLBAR_STYLE`]: crate::commands::SET_TOOLBAR_STYLE
+//! [`SET_TITLEBAR_ACCESSORY`]: crate::commands::SET_TITLEBAR_ACCESSORY
+//! [`SET_TITLEBAR_TRANSPARENT`]: crate::commands::SET_TITLEBAR_TRANSPARENT
+
+use crate::menu::MenuItemId;
+use crate::Command;
+
+/// An item in a window's toolbar, or a button in a titlebar accessory.
+///
+/// When the item is clicked its command is submitted. If the command's target
+/// is [`Target::Auto`], it is sent to the item's window.
+///
+/// [`Target::Auto`]: crate::Target::Auto
+#[derive(Debug, Clone)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct ToolbarItem {
+    label: String,
+    symbol: Option<String>,
+    tooltip: Option<String>,
+    command: Command,
+}
+
+/// The style of a window's toolbar, and how it is combined with the titlebar.
+///
+/// This requires macOS 11 or later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarStyle {
+    /// Let the system choose the style.
+    Automatic,
+    /// The toolbar is shown below the title.
+    Expanded,
+    /// Like `Expanded`, with the items centered, as in preference windows.
+    Preference,
+    /// The toolbar and the title share a single unified bar.
+    Unified,
+    /// Like `Unified`, with less space around the items.
+    UnifiedCompact,
+}
+
+/// Where a titlebar accessory is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessoryPosition {
+    /// At the trailing edge of the titlebar.
+    Right,
+    /// Below the titlebar, and the toolbar if there is one.
+    Bottom,
+}
+
+impl ToolbarItem {
+    /// Create a new item with the given label, that submits `command` when
+    /// it is clicked.
+    pub fn new(label: impl Into<String>, command: impl Into<Command>) -> Self {
+        ToolbarItem {
+            label: label.into(),
+            symbol: None,
+            tooltip: None,
+            command: command.into(),
+        }
+    }
+
+    /// Builder-style method to set the name of an SF Symbol to use as the
+    /// item's image.
+    ///
+    /// Symbols require macOS 11 or later; on earlier versions only the label
+    /// is shown.
+    pub fn symbol(mut self, name: impl Into<String>) -> Self {
+        self.symbol = Some(name.into());
+        self
+    }
+
+    /// Builder-style method to set the item's tooltip.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    #[cfg(target_os = "macos")]
+    fn to_platform(&self, id: MenuItemId) -> crate::shell::platform::mac::ToolbarItem {
+        let mut item = crate::shell::platform::mac::ToolbarItem::new(id.as_u32(), &self.label);
+        if let Some(symbol) = &self.symbol {
+            item = item.symbol(symbol);
+        }
+        if let Some(tooltip) = &self.tooltip {
+            item = item.tooltip(tooltip);
+        }
+        item
+    }
+}
+
+/// The commands of a window's toolbar items or accessory buttons, by id.
+#[derive(Default)]
+pub(crate) struct ToolbarCommands(Vec<(MenuItemId, Command)>);
+
+impl ToolbarCommands {
+    /// Assign ids to `items`, replacing any previous ones.
+    fn replace(&mut self, items: &[ToolbarItem]) {
+        self.0 = items
+            .iter()
+            .map(|item| (MenuItemId::next(), item.command.clone()))
+            .collect();
+    }
+
+    /// The command of the item with `id`.
+    pub(crate) fn get(&self, id: MenuItemId) -> Option<&Command> {
+        self.0
+            .iter()
+            .find(|(item_id, _)| *item_id == id)
+            .map(|(_, cmd)| cmd)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn platform_items(
+        &self,
+        items: &[ToolbarItem],
+    ) -> Vec<crate::shell::platform::mac::ToolbarItem> {
+        items
+            .iter()
+            .zip(&self.0)
+            .map(|(item, (id, _))| item.to_platform(*id))
+            .collect()
+    }
+}
+
+/// The toolbar state of a window.
+#[derive(Default)]
+pub(crate) struct WindowToolbar {
+    toolbar: ToolbarCommands,
+    accessory: ToolbarCommands,
+}
+
+impl WindowToolbar {
+    /// The command of the toolbar item or accessory button with `id`.
+    pub(crate) fn command(&self, id: MenuItemId) -> Option<&Command> {
+        self.toolbar.get(id).or_else(|| self.accessory.get(id))
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn set_toolbar(
+        &mut self,
+        handle: &crate::shell::WindowHandle,
+        items: &[ToolbarItem],
+    ) {
+        self.toolbar.replace(items);
+        #[cfg(target_os = "macos")]
+        {
+            use crate::shell::platform::mac::WindowHandleExt;
+            handle.set_toolbar(self.toolbar.platform_items(items));
+        }
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn set_accessory(
+        &mut self,
+        handle: &crate::shell::WindowHandle,
+        items: &[ToolbarItem],
+        position: AccessoryPosition,
+    ) {
+        self.accessory.replace(items);
+        #[cfg(target_os = "macos")]
+        {
+            use crate::shell::platform::mac::{self, WindowHandleExt};
+            let position = match position {
+                AccessoryPosition::Right => mac::AccessoryPosition::Right,
+                AccessoryPosition::Bottom => mac::AccessoryPosition::Bottom,
+            };
+            handle.set_titlebar_accessory(self.accessory.platform_items(items), position);
+        }
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn set_style(handle: &crate::shell::WindowHandle, style: ToolbarStyle) {
+        #[cfg(target_os = "macos")]
+        {
+            use crate::shell::platform::mac::{self, WindowHandleExt};
+            let style = match style {
+                ToolbarStyle::Automatic => mac::ToolbarStyle::Automatic,
+                ToolbarStyle::Expanded => mac::ToolbarStyle::Expanded,
+                ToolbarStyle::Preference => mac::ToolbarStyle::Preference,
+                ToolbarStyle::Unified => mac::ToolbarStyle::Unified,
+                ToolbarStyle::UnifiedCompact => mac::ToolbarStyle::UnifiedCompact,
+            };
+            handle.set_toolbar_style(style);
+        }
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn set_transparent(handle: &crate::shell::WindowHandle, transparent: bool) {
+        #[cfg(target_os = "macos")]
+        {
+            use crate::shell::platform::mac::WindowHandleExt;
+            handle.set_titlebar_transparent(transparent);
+        }
+    }
+}
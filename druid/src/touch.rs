@@ -0,0 +1,73 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Touch screen events.
+
+use crate::kurbo::Point;
+use crate::{Modifiers, TouchId};
+
+/// A contact with a touch screen touching down, moving, or being lifted.
+///
+/// Like [`MouseEvent`], the position is given both in the coordinate space
+/// of the receiver and in that of the window.
+///
+/// Touch events go to the widgets under the contact, and to the active
+/// widget. A widget that wants to follow a contact, for a drag, should
+/// remember its [`id`] on [`Event::TouchDown`] and call [`set_active`], and
+/// ignore the events of other contacts.
+///
+/// The platform also turns the [`primary`] contact into mouse events, so
+/// widgets that don't handle touch can still be used with a touch screen.
+/// It doesn't if a widget calls [`set_handled`] on the contact's
+/// [`Event::TouchDown`], so a widget that handles touch doesn't get the same
+/// gesture twice.
+///
+/// [`MouseEvent`]: crate::MouseEvent
+/// [`id`]: TouchEvent::id
+/// [`primary`]: TouchEvent::primary
+/// [`Event::TouchDown`]: crate::Event::TouchDown
+/// [`set_active`]: crate::EventCtx::set_active
+/// [`set_handled`]: crate::EventCtx::set_handled
+#[derive(Debug, Clone)]
+pub struct TouchEvent {
+    /// The contact this event belongs to.
+    pub id: TouchId,
+    /// The position of the contact in the coordinate space of the receiver.
+    pub pos: Point,
+    /// The position of the contact in the coordinate space of the window.
+    pub window_pos: Point,
+    /// Keyboard modifiers at the time of the event.
+    pub mods: Modifiers,
+    /// Whether this is the first contact of the gesture, which the platform
+    /// also turns into mouse events.
+    pub primary: bool,
+}
+
+impl From<druid_shell::TouchEvent> for TouchEvent {
+    fn from(src: druid_shell::TouchEvent) -> TouchEvent {
+        let druid_shell::TouchEvent {
+            id,
+            pos,
+            mods,
+            primary,
+        } = src;
+        TouchEvent {
+            id,
+            pos,
+            window_pos: pos,
+            mods,
+            primary,
+        }
+    }
+}
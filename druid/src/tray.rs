@@ -0,0 +1,112 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The application's icon in the system tray.
+
+use crate::core::CommandQueue;
+use crate::menu::{MenuItemId, MenuManager};
+use crate::shell::TrayIcon;
+use crate::{Data, Env, ImageBuf, Menu, PlatformError};
+
+/// An icon in the system tray, for [`AppLauncher::tray`].
+///
+/// Clicking the icon submits [`TRAY_ACTIVATED`] to the application.
+///
+/// [`AppLauncher::tray`]: crate::AppLauncher::tray
+/// [`TRAY_ACTIVATED`]: crate::commands::TRAY_ACTIVATED
+pub struct TrayDesc<T> {
+    icon: ImageBuf,
+    tooltip: Option<String>,
+    menu: Option<Menu<T>>,
+}
+
+/// The tray icon of a running application, and the menu it shows.
+pub(crate) struct Tray<T> {
+    icon: TrayIcon,
+    menu: Option<MenuManager<T>>,
+}
+
+impl<T: Data> TrayDesc<T> {
+    /// Create a tray icon showing `icon`.
+    pub fn new(icon: ImageBuf) -> Self {
+        TrayDesc {
+            icon,
+            tooltip: None,
+            menu: None,
+        }
+    }
+
+    /// Builder-style method to set the text shown when hovering over the icon.
+    pub fn tooltip(mut self, tooltip: impl Into<String>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Builder-style method to set the menu of the icon.
+    ///
+    /// The menu is shown when the icon is right-clicked, or on any click on
+    /// macOS. It is updated when the data changes, like a context menu.
+    ///
+    /// Tray menus are not supported on X11; there the icon is shown without
+    /// its menu, and a warning is logged.
+    pub fn menu(mut self, menu: Menu<T>) -> Self {
+        self.menu = Some(menu);
+        self
+    }
+
+    /// Add the icon to the system tray.
+    pub(crate) fn build(self, data: &T, env: &Env) -> Result<Tray<T>, PlatformError> {
+        let mut icon = TrayIcon::new(&self.icon)?;
+        if let Some(tooltip) = &self.tooltip {
+            icon.set_tooltip(tooltip);
+        }
+        let menu = self.menu.and_then(|menu| {
+            let mut menu = MenuManager::new_for_popup(menu);
+            match icon.set_menu(menu.initialize(None, data, env)) {
+                Ok(()) => Some(menu),
+                Err(e) => {
+                    tracing::warn!("The tray icon can't have a menu: {}", e);
+                    None
+                }
+            }
+        });
+        Ok(Tray { icon, menu })
+    }
+}
+
+impl<T: Data> Tray<T> {
+    /// Called when a menu item is selected; ignores items of other menus.
+    pub(crate) fn menu_cmd(
+        &mut self,
+        queue: &mut CommandQueue,
+        cmd_id: MenuItemId,
+        data: &mut T,
+        env: &Env,
+    ) {
+        if let Some(menu) = &mut self.menu {
+            menu.event(queue, None, cmd_id, data, env);
+        }
+    }
+
+    /// Rebuild the menu if the data changed anything about it.
+    pub(crate) fn update(&mut self, data: &T, env: &Env) {
+        if let Some(menu) = &mut self.menu {
+            if let Some(new_menu) = menu.update(None, data, env) {
+                if let Err(e) = self.icon.set_menu(new_menu) {
+                    tracing::error!("Failed to update the tray menu: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -15,7 +15,7 @@
 //! A widget that aligns its child (for example, centering it).
 
 use crate::widget::prelude::*;
-use crate::{Data, Rect, Size, UnitPoint, WidgetPod};
+use crate::{theme, Data, Rect, Size, UnitPoint, WidgetPod};
 use tracing::{instrument, trace};
 
 /// A widget that aligns its child.
@@ -30,8 +30,11 @@ impl<T> Align<T> {
     /// Create widget with alignment.
     ///
     /// Note that the `align` parameter is specified as a `UnitPoint` in
-    /// terms of left and right. This is inadequate for bidi-aware layout
-    /// and thus the API will change when druid gains bidi capability.
+    /// terms of left and right. If [`theme::LAYOUT_RTL`] is set, the
+    /// horizontal component is mirrored, so that (for instance) `Align::left`
+    /// aligns its child to the leading edge in a right-to-left layout.
+    ///
+    /// [`theme::LAYOUT_RTL`]: crate::theme::LAYOUT_RTL
     pub fn new(align: UnitPoint, child: impl Widget<T> + 'static) -> Align<T> {
         Align {
             align,
@@ -90,6 +93,9 @@ impl<T: Data> Widget<T> for Align<T> {
 
     #[instrument(name = "Align", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&theme::LAYOUT_RTL) {
+            ctx.request_layout();
+        }
         self.child.update(ctx, data, env);
     }
 
@@ -120,10 +126,13 @@ impl<T: Data> Widget<T> for Align<T> {
         my_size = bc.constrain(my_size);
         let extra_width = (my_size.width - size.width).max(0.);
         let extra_height = (my_size.height - size.height).max(0.);
-        let origin = self
+        let mut origin = self
             .align
-            .resolve(Rect::new(0., 0., extra_width, extra_height))
-            .expand();
+            .resolve(Rect::new(0., 0., extra_width, extra_height));
+        if env.get(theme::LAYOUT_RTL) {
+            origin.x = extra_width - origin.x;
+        }
+        let origin = origin.expand();
         self.child.set_origin(ctx, data, env, origin);
 
         let my_insets = self.child.compute_parent_paint_insets(my_size);
@@ -0,0 +1,151 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that tweens its child's data.
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+use crate::animation::{Animation, Easing, Interpolate};
+use crate::widget::prelude::*;
+use crate::{Data, Point, WidgetPod};
+
+/// A widget that animates changes of its child's data.
+///
+/// When the data changes, the child is shown the values between the old and
+/// the new data over the given duration, instead of jumping to the new data.
+/// Changes the child makes itself, for instance while the user drags a
+/// slider, aren't animated.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{Animated, ProgressBar};
+/// use druid::Widget;
+///
+/// fn smooth_progress() -> impl Widget<f64> {
+///     Animated::new(ProgressBar::new(), Duration::from_millis(300))
+/// }
+/// ```
+pub struct Animated<T, W> {
+    child: WidgetPod<T, W>,
+    duration: Duration,
+    easing: Easing,
+    /// The data the child is shown.
+    shown: Option<T>,
+    /// The data shown when the animation started.
+    from: Option<T>,
+    animation: Option<Animation>,
+    /// Set when the child changed the data, so that the change isn't animated.
+    child_changed: bool,
+}
+
+impl<T: Data + Interpolate, W: Widget<T>> Animated<T, W> {
+    /// Create a widget that animates changes of `child`'s data over `duration`.
+    pub fn new(child: W, duration: Duration) -> Self {
+        Animated {
+            child: WidgetPod::new(child),
+            duration,
+            easing: Easing::default(),
+            shown: None,
+            from: None,
+            animation: None,
+            child_changed: false,
+        }
+    }
+
+    /// Builder-style method to set the easing of the animations.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Whether the child's data is still being animated.
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+}
+
+impl<T: Data + Interpolate, W: Widget<T>> Widget<T> for Animated<T, W> {
+    #[instrument(name = "Animated", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let (Event::AnimFrame(interval), Some(animation), Some(from)) =
+            (event, self.animation.as_mut(), self.from.as_ref())
+        {
+            if animation.advance(*interval) {
+                self.shown = Some(from.interpolate(data, animation.progress()));
+                ctx.request_anim_frame();
+            } else {
+                self.shown = Some(data.clone());
+                self.animation = None;
+                self.from = None;
+            }
+            ctx.request_update();
+        }
+
+        let old = data.clone();
+        self.child.event(ctx, event, data, env);
+        if !old.same(data) {
+            self.child_changed = true;
+        }
+    }
+
+    #[instrument(name = "Animated", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.shown = Some(data.clone());
+        }
+        let shown = self.shown.as_ref().unwrap_or(data);
+        self.child.lifecycle(ctx, event, shown, env)
+    }
+
+    #[instrument(
+        name = "Animated",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            if std::mem::take(&mut self.child_changed) {
+                self.shown = Some(data.clone());
+                self.animation = None;
+                self.from = None;
+            } else {
+                // Start from what is shown, in case an animation was running.
+                self.from = self.shown.clone();
+                self.animation = Some(Animation::new(self.duration, self.easing));
+                ctx.request_anim_frame();
+            }
+        }
+        let shown = self.shown.as_ref().unwrap_or(data);
+        self.child.update(ctx, shown, env);
+    }
+
+    #[instrument(name = "Animated", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let shown = self.shown.as_ref().unwrap_or(data);
+        let size = self.child.layout(ctx, bc, shown, env);
+        self.child.set_origin(ctx, shown, env, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.paint_insets());
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Animated", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let shown = self.shown.as_ref().unwrap_or(data);
+        self.child.paint(ctx, shown, env);
+    }
+}
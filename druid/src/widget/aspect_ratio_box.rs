@@ -13,19 +13,47 @@
 // limitations under the License.
 
 use druid::widget::prelude::*;
-use druid::Data;
+use druid::{Data, Point, WidgetPod};
 use tracing::{instrument, warn};
 
 /// A widget that preserves the aspect ratio given to it.
 ///
 /// If given a child, this widget forces the child to have a width and height that preserves
-/// the aspect ratio.
+/// the aspect ratio. The child is centered within the space given to the box.
 ///
 /// If not given a child, The box will try to size itself  as large or small as possible
 /// to preserve the aspect ratio.
+///
+/// The [`AspectRatioMode`] determines whether the child is the largest size
+/// that fits the available space, or the smallest size that fills it.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{AspectRatioBox, AspectRatioMode, Image};
+/// use druid::ImageBuf;
+///
+/// // a 16:9 thumbnail that covers its space, cropping the edges.
+/// let thumbnail = AspectRatioBox::<()>::new(Image::new(ImageBuf::empty()), 16.0 / 9.0)
+///     .with_mode(AspectRatioMode::Fill);
+/// ```
 pub struct AspectRatioBox<T> {
-    inner: Box<dyn Widget<T>>,
+    inner: WidgetPod<T, Box<dyn Widget<T>>>,
     ratio: f64,
+    mode: AspectRatioMode,
+}
+
+/// How an [`AspectRatioBox`] sizes its child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum AspectRatioMode {
+    /// The child is the largest size with the aspect ratio that fits within
+    /// the maximum constraints, and is centered in any leftover space.
+    ///
+    /// This is the default.
+    Fit,
+    /// The child is the smallest size with the aspect ratio that covers the
+    /// maximum constraints; the overflow is centered, and clipped.
+    Fill,
 }
 
 impl<T> AspectRatioBox<T> {
@@ -36,11 +64,23 @@ impl<T> AspectRatioBox<T> {
     /// If aspect ratio <= 0.0, the ratio will be set to 1.0
     pub fn new(inner: impl Widget<T> + 'static, ratio: f64) -> Self {
         Self {
-            inner: Box::new(inner),
+            inner: WidgetPod::new(Box::new(inner)),
             ratio: clamp_ratio(ratio),
+            mode: AspectRatioMode::Fit,
         }
     }
 
+    /// Builder-style method to set the [`AspectRatioMode`].
+    pub fn with_mode(mut self, mode: AspectRatioMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the [`AspectRatioMode`].
+    pub fn set_mode(&mut self, mode: AspectRatioMode) {
+        self.mode = mode;
+    }
+
     /// Set the ratio of the box.
     ///
     /// The ratio has to be a value between 0 and f64::MAX, excluding 0. It will be clamped
@@ -50,40 +90,24 @@ impl<T> AspectRatioBox<T> {
         self.ratio = clamp_ratio(ratio);
     }
 
-    /// Generate `BoxConstraints` that fit within the provided `BoxConstraints`.
+    /// Compute the size of the child within the provided `BoxConstraints`.
     ///
-    /// If the generated constraints do not fit then they are constrained to the
-    /// provided `BoxConstraints`.
-    fn generate_constraints(&self, bc: &BoxConstraints) -> BoxConstraints {
-        let (mut new_width, mut new_height) = (bc.max().width, bc.max().height);
-
-        if new_width == f64::INFINITY {
-            new_width = new_height * self.ratio;
+    /// At least one of the maximum width and height must be finite. The minimum
+    /// constraints are ignored; the box itself is made large enough to satisfy
+    /// them, and the child is centered within it.
+    fn child_size(&self, bc: &BoxConstraints) -> Size {
+        let max = bc.max();
+        let fit_width = max.width.min(max.height * self.ratio);
+        let fill_width = if max.width.is_finite() && max.height.is_finite() {
+            max.width.max(max.height * self.ratio)
         } else {
-            new_height = new_width / self.ratio;
-        }
-
-        if new_width > bc.max().width {
-            new_width = bc.max().width;
-            new_height = new_width / self.ratio;
-        }
-
-        if new_height > bc.max().height {
-            new_height = bc.max().height;
-            new_width = new_height * self.ratio;
-        }
-
-        if new_width < bc.min().width {
-            new_width = bc.min().width;
-            new_height = new_width / self.ratio;
-        }
-
-        if new_height < bc.min().height {
-            new_height = bc.min().height;
-            new_width = new_height * self.ratio;
-        }
-
-        BoxConstraints::tight(bc.constrain(Size::new(new_width, new_height)))
+            fit_width
+        };
+        let width = match self.mode {
+            AspectRatioMode::Fit => fit_width,
+            AspectRatioMode::Fill => fill_width,
+        };
+        Size::new(width, width / self.ratio)
     }
 }
 
@@ -122,10 +146,10 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
     #[instrument(
         name = "AspectRatioBox",
         level = "trace",
-        skip(self, ctx, old_data, data, env)
+        skip(self, ctx, _old_data, data, env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
-        self.inner.update(ctx, old_data, data, env);
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
     }
 
     #[instrument(
@@ -136,29 +160,30 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("AspectRatioBox");
 
-        if bc.max() == bc.min() {
-            warn!("Box constraints are tight. Aspect ratio box will not be able to preserve aspect ratio.");
-
-            return self.inner.layout(ctx, bc, data, env);
-        }
-
         if bc.max().width == f64::INFINITY && bc.max().height == f64::INFINITY {
             warn!("Box constraints are INFINITE. Aspect ratio box won't be able to choose a size because the constraints given by the parent widget are INFINITE.");
 
-            return self.inner.layout(ctx, bc, data, env);
+            let size = self.inner.layout(ctx, bc, data, env);
+            self.inner.set_origin(ctx, data, env, Point::ORIGIN);
+            return size;
         }
 
-        let bc = self.generate_constraints(bc);
+        let child_size = self.child_size(bc);
+        self.inner
+            .layout(ctx, &BoxConstraints::tight(child_size), data, env);
 
-        self.inner.layout(ctx, &bc, data, env)
+        let my_size = bc.constrain(child_size);
+        let origin = ((my_size.to_vec2() - child_size.to_vec2()) / 2.0).to_point();
+        self.inner.set_origin(ctx, data, env, origin);
+        my_size
     }
 
     #[instrument(name = "AspectRatioBox", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.mode == AspectRatioMode::Fill {
+            let clip = ctx.size().to_rect();
+            ctx.clip(clip);
+        }
         self.inner.paint(ctx, data, env);
     }
-
-    fn id(&self) -> Option<WidgetId> {
-        self.inner.id()
-    }
 }
@@ -0,0 +1,326 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that blurs its child.
+
+use tracing::{instrument, warn};
+
+use crate::core::Layer;
+use crate::piet::{ImageBuf, ImageFormat, InterpolationMode};
+use crate::widget::prelude::*;
+use crate::{Data, Insets, KeyOrValue, Point, WidgetPod};
+
+enum BlurRadius<T> {
+    Fixed(KeyOrValue<f64>),
+    Dynamic(Box<dyn Fn(&T, &Env) -> f64>),
+}
+
+/// A widget that paints its child with a Gaussian blur.
+///
+/// This is meant for content behind a modal layer, such as a dialog in a
+/// [`ZStack`]: blurring the content makes it clear that it can't be used
+/// right now, while keeping it recognizable.
+///
+/// The child is painted into an offscreen layer, which is blurred and then
+/// drawn in its place, spreading out by three times the radius around the
+/// child. The blurred layer is kept while the child and the radius don't
+/// change, and a radius of `0.0` paints the child directly.
+///
+/// # Examples
+/// ```
+/// use druid::widget::{Blur, Label};
+/// use druid::Widget;
+///
+/// // Blur the page while a dialog is open.
+/// fn page() -> impl Widget<bool> {
+///     Blur::dynamic(Label::new("The page"), |dialog_open: &bool, _| {
+///         if *dialog_open { 8.0 } else { 0.0 }
+///     })
+/// }
+/// ```
+///
+/// [`ZStack`]: crate::widget::ZStack
+pub struct Blur<T, W> {
+    child: WidgetPod<T, W>,
+    radius: BlurRadius<T>,
+    /// The radius the child is currently painted with.
+    current: f64,
+    /// The child's blurred layer, and the radius it was blurred with.
+    blurred: Option<(f64, Layer)>,
+}
+
+impl<T: Data, W: Widget<T>> Blur<T, W> {
+    /// Create a widget that blurs `child` with the given radius, in display
+    /// points.
+    ///
+    /// The radius can be a value, or a [`Key`] of one in the [`Env`].
+    ///
+    /// [`Key`]: crate::Key
+    pub fn new(child: W, radius: impl Into<KeyOrValue<f64>>) -> Self {
+        Blur::with_radius(child, BlurRadius::Fixed(radius.into()))
+    }
+
+    /// Create a widget that blurs `child` with a radius computed from the data.
+    pub fn dynamic(child: W, radius: impl Fn(&T, &Env) -> f64 + 'static) -> Self {
+        Blur::with_radius(child, BlurRadius::Dynamic(Box::new(radius)))
+    }
+
+    fn with_radius(child: W, radius: BlurRadius<T>) -> Self {
+        Blur {
+            child: WidgetPod::new(child),
+            radius,
+            current: 0.0,
+            blurred: None,
+        }
+    }
+
+    fn resolve(&self, data: &T, env: &Env) -> f64 {
+        let radius = match &self.radius {
+            BlurRadius::Fixed(radius) => radius.resolve(env),
+            BlurRadius::Dynamic(f) => f(data, env),
+        };
+        radius.max(0.0)
+    }
+
+    /// Paint the child into a layer, and draw it blurred.
+    fn paint_blurred(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let layer = match self.child.paint_layer(ctx, data, env) {
+            Ok((layer, repainted)) => {
+                if repainted {
+                    self.blurred = None;
+                }
+                layer
+            }
+            Err(e) => {
+                warn!("failed to paint the layer of {:?}: {}", ctx.widget_id(), e);
+                self.child.paint(ctx, data, env);
+                return;
+            }
+        };
+        let radius = self.current;
+        let blurred = match &mut self.blurred {
+            Some((blurred_radius, blurred)) if *blurred_radius == radius => blurred,
+            blurred => {
+                let buf = blur_layer(&layer.buf, layer.scale, radius);
+                &mut blurred.insert((radius, Layer::new(buf, layer.scale))).1
+            }
+        };
+        let image = blurred.image(ctx);
+        let spread = blur_spread(&blurred.buf, &layer.buf, layer.scale);
+        let rect = self.child.paint_rect().inflate(spread, spread);
+        ctx.draw_image(&image, rect, InterpolationMode::Bilinear);
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Blur<T, W> {
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.current = self.resolve(data, env);
+        }
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let radius = self.resolve(data, env);
+        if radius != self.current {
+            self.current = radius;
+            // The blur spreads out of the child, by an amount that depends on the radius.
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        let margin = blur_margin(self.current);
+        let insets = self.child.paint_insets();
+        ctx.set_paint_insets(Insets::new(
+            insets.x0 + margin,
+            insets.y0 + margin,
+            insets.x1 + margin,
+            insets.y1 + margin,
+        ));
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Blur", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.current > 0.0 {
+            self.paint_blurred(ctx, data, env);
+        } else {
+            self.child.paint(ctx, data, env);
+        }
+    }
+}
+
+/// How far a blur with the given radius spreads out of what is blurred.
+pub(crate) fn blur_margin(radius: f64) -> f64 {
+    3.0 * radius
+}
+
+/// Blur a premultiplied RGBA layer with `scale` pixels per display point.
+///
+/// The blurred image is larger than the layer by the [`blur_margin`] on each
+/// side, so that the blur can spread out of it.
+pub(crate) fn blur_layer(layer: &ImageBuf, scale: f64, radius: f64) -> ImageBuf {
+    let pad = (blur_margin(radius) * scale).ceil() as usize;
+    let width = layer.width() + 2 * pad;
+    let height = layer.height() + 2 * pad;
+    let mut pixels = vec![0; width * height * 4];
+    let row_len = layer.width() * 4;
+    for (y, row) in layer.raw_pixels().chunks_exact(row_len).enumerate() {
+        let start = ((y + pad) * width + pad) * 4;
+        pixels[start..start + row_len].copy_from_slice(row);
+    }
+    gaussian_blur(&mut pixels, width, height, radius * scale);
+    ImageBuf::from_raw(pixels, ImageFormat::RgbaPremul, width, height)
+}
+
+/// How far, in display points, a layer blurred by [`blur_layer`] extends past
+/// the original one.
+pub(crate) fn blur_spread(blurred: &ImageBuf, layer: &ImageBuf, scale: f64) -> f64 {
+    (blurred.width() - layer.width()) as f64 / 2.0 / scale
+}
+
+/// Blur premultiplied RGBA `pixels` in place, with a standard deviation of
+/// `sigma` pixels.
+///
+/// The Gaussian is approximated by three box blurs, each of which is split
+/// into a horizontal and a vertical pass; this takes the same time for any
+/// radius.
+pub(crate) fn gaussian_blur(pixels: &mut [u8], width: usize, height: usize, sigma: f64) {
+    let mut scratch = vec![0; pixels.len()];
+    for size in box_sizes(sigma) {
+        let radius = size / 2;
+        box_blur(pixels, &mut scratch, height, width, width * 4, 4, radius);
+        box_blur(&scratch, pixels, width, height, 4, width * 4, radius);
+    }
+}
+
+/// The widths of three box blurs that together approximate a Gaussian blur.
+fn box_sizes(sigma: f64) -> [usize; 3] {
+    let ideal = (12.0 * sigma * sigma / 3.0 + 1.0).sqrt();
+    let mut lower = ideal.floor() as usize;
+    if lower % 2 == 0 {
+        lower = lower.saturating_sub(1).max(1);
+    }
+    let lower_f = lower as f64;
+    let lower_count = ((12.0 * sigma * sigma - 3.0 * lower_f * lower_f - 12.0 * lower_f - 9.0)
+        / (-4.0 * lower_f - 4.0))
+        .round()
+        .max(0.0) as usize;
+    let mut sizes = [lower + 2; 3];
+    for size in sizes.iter_mut().take(lower_count) {
+        *size = lower;
+    }
+    sizes
+}
+
+/// Box blur `lines` lines of `len` pixels each from `src` into `dst`.
+///
+/// Lines start `line_stride` bytes apart, and the pixels within a line are
+/// `pixel_stride` bytes apart, so that the same pass works for both rows and
+/// columns. Pixels past the ends of a line repeat the pixel at the end.
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    lines: usize,
+    len: usize,
+    line_stride: usize,
+    pixel_stride: usize,
+    radius: usize,
+) {
+    if len == 0 {
+        return;
+    }
+    let window = 2 * radius as u32 + 1;
+    let radius = radius as isize;
+    for line in 0..lines {
+        let start = line * line_stride;
+        for channel in 0..4 {
+            let at =
+                |i: isize| start + i.clamp(0, len as isize - 1) as usize * pixel_stride + channel;
+            let mut sum: u32 = (-radius..=radius).map(|i| src[at(i)] as u32).sum();
+            for i in 0..len as isize {
+                dst[at(i)] = ((sum + window / 2) / window) as u8;
+                sum += src[at(i + radius + 1)] as u32;
+                sum -= src[at(i - radius)] as u32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn box_sizes_approximate_the_gaussian() {
+        assert_eq!(box_sizes(0.0), [1, 1, 1]);
+        for &sigma in &[1.0, 2.0, 5.0, 10.0, 25.0] {
+            let sizes = box_sizes(sigma);
+            assert!(sizes.iter().all(|size| size % 2 == 1), "{:?}", sizes);
+            // The variances of the boxes add up to about the Gaussian's.
+            let variance: f64 = sizes.iter().map(|&w| (w * w - 1) as f64 / 12.0).sum();
+            assert!(
+                (variance.sqrt() - sigma).abs() < 0.25,
+                "{}: {:?}",
+                sigma,
+                sizes
+            );
+        }
+    }
+
+    #[test]
+    fn box_blur_spreads_and_repeats_edges() {
+        let mut src = vec![0; 5 * 4];
+        src[8..12].copy_from_slice(&[255; 4]);
+        let mut dst = vec![0; 5 * 4];
+        box_blur(&src, &mut dst, 1, 5, 0, 4, 1);
+        let alpha: Vec<u8> = dst.chunks(4).map(|pixel| pixel[3]).collect();
+        assert_eq!(alpha, [0, 85, 85, 85, 0]);
+
+        let src = vec![200; 5 * 4];
+        box_blur(&src, &mut dst, 1, 5, 0, 4, 2);
+        assert_eq!(dst, src);
+    }
+
+    #[test]
+    fn blurred_layer_spreads_into_its_margin() {
+        let layer = ImageBuf::from_raw(vec![255; 4], ImageFormat::RgbaPremul, 1, 1);
+        let blurred = blur_layer(&layer, 2.0, 1.0);
+        // Three radii at two pixels per point, on each side.
+        assert_eq!((blurred.width(), blurred.height()), (13, 13));
+        assert_eq!(blur_spread(&blurred, &layer, 2.0), 3.0);
+
+        let alpha = |x: usize, y: usize| blurred.raw_pixels()[(y * 13 + x) * 4 + 3];
+        assert!(alpha(6, 6) > 0 && alpha(6, 6) < 255);
+        assert!(alpha(6, 6) > alpha(4, 6));
+        assert_eq!(alpha(4, 6), alpha(8, 6));
+        assert_eq!(alpha(6, 4), alpha(6, 8));
+        assert_eq!(alpha(0, 0), 0);
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A grid of cells that are painted and hit-tested directly.
+
+use crate::widget::prelude::*;
+use crate::{Data, MouseButton, MouseEvent, Point, Rect};
+use tracing::{instrument, trace};
+
+/// The default size of a cell, in display points.
+const DEFAULT_CELL_SIZE: f64 = 24.0;
+
+/// The position of a cell in a [`BoardGrid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Data)]
+pub struct BoardCell {
+    /// The row, counting from the top.
+    pub row: usize,
+    /// The column, counting from the left.
+    pub col: usize,
+}
+
+/// A cell being painted by a [`BoardGrid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardCellState {
+    /// The cell's position.
+    pub cell: BoardCell,
+    /// The cell's rect, in the coordinate space of the grid.
+    pub rect: Rect,
+    /// Whether the mouse is over the cell.
+    pub is_hot: bool,
+    /// Whether the cell is being clicked.
+    pub is_pressed: bool,
+}
+
+type PaintCellFn<T> = Box<dyn Fn(&mut PaintCtx, &BoardCellState, &T, &Env)>;
+type ClickCellFn<T> = Box<dyn Fn(&mut EventCtx, BoardCell, &MouseEvent, &mut T, &Env)>;
+type CellChangedFn<T> = Box<dyn Fn(&T, &T, BoardCell) -> bool>;
+
+/// A fixed-size grid of cells, such as a game board.
+///
+/// A `BoardGrid` is a single widget: its cells are painted by a callback,
+/// and clicks are hit-tested against the cells directly. This scales to large
+/// boards far better than a widget per cell.
+///
+/// By default the grid prefers cells of 24x24 display points, and otherwise
+/// divides the space it is given evenly between the cells. To keep the cells
+/// square, place the grid in an [`AspectRatioBox`].
+///
+/// By default the whole grid is repainted when its data changes. If you
+/// provide a way to tell which cells changed, with [`with_cell_changed`],
+/// only those cells are repainted.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::BoardGrid;
+/// use druid::{Color, RenderContext};
+/// use std::sync::Arc;
+///
+/// const SIZE: usize = 8;
+///
+/// let board = BoardGrid::new(SIZE, SIZE, |ctx, cell, data: &Arc<Vec<bool>>, _env| {
+///     let color = if data[cell.cell.row * SIZE + cell.cell.col] {
+///         Color::BLACK
+///     } else {
+///         Color::WHITE
+///     };
+///     ctx.fill(cell.rect, &color);
+/// })
+/// .with_spacing(1.0)
+/// .on_cell_click(|_ctx, cell, _mouse, data, _env| {
+///     let idx = cell.row * SIZE + cell.col;
+///     Arc::make_mut(data)[idx] ^= true;
+/// });
+/// ```
+///
+/// [`AspectRatioBox`]: super::AspectRatioBox
+/// [`with_cell_changed`]: BoardGrid::with_cell_changed
+pub struct BoardGrid<T> {
+    rows: usize,
+    columns: usize,
+    cell_size: Size,
+    spacing: f64,
+    paint_cell: PaintCellFn<T>,
+    on_click: Option<ClickCellFn<T>>,
+    cell_changed: Option<CellChangedFn<T>>,
+    hot_cell: Option<BoardCell>,
+    pressed: Option<(BoardCell, MouseButton)>,
+    // the computed size of a cell
+    actual_cell_size: Size,
+}
+
+impl<T: Data> BoardGrid<T> {
+    /// Create a grid of `rows` by `columns` cells, each painted by `paint_cell`.
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        paint_cell: impl Fn(&mut PaintCtx, &BoardCellState, &T, &Env) + 'static,
+    ) -> Self {
+        BoardGrid {
+            rows,
+            columns,
+            cell_size: Size::new(DEFAULT_CELL_SIZE, DEFAULT_CELL_SIZE),
+            spacing: 0.0,
+            paint_cell: Box::new(paint_cell),
+            on_click: None,
+            cell_changed: None,
+            hot_cell: None,
+            pressed: None,
+            actual_cell_size: Size::ZERO,
+        }
+    }
+
+    /// Builder-style method to set the preferred size of a cell.
+    ///
+    /// This is the size used when the grid's constraints allow it.
+    pub fn with_cell_size(mut self, size: impl Into<Size>) -> Self {
+        self.cell_size = size.into();
+        self
+    }
+
+    /// Builder-style method to set the space between cells.
+    pub fn with_spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing.max(0.0);
+        self
+    }
+
+    /// Builder-style method to set a callback for when a cell is clicked.
+    ///
+    /// A click is a mouse down and up with the same button on the same cell;
+    /// the callback receives the mouse up event, so it can check which button
+    /// was used.
+    pub fn on_cell_click(
+        mut self,
+        f: impl Fn(&mut EventCtx, BoardCell, &MouseEvent, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_click = Some(Box::new(f));
+        self
+    }
+
+    /// Builder-style method to set a function that determines whether a
+    /// cell looks different in the new data than in the old.
+    ///
+    /// When the data changes, only the cells for which this returns `true`
+    /// are repainted. The function is called with the old data, the new data,
+    /// and the cell, for every cell in the grid, so it should be cheap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::BoardGrid;
+    /// use druid::{Color, RenderContext};
+    /// use std::sync::Arc;
+    ///
+    /// const SIZE: usize = 100;
+    ///
+    /// let board = BoardGrid::new(SIZE, SIZE, |ctx, cell, data: &Arc<Vec<Color>>, _env| {
+    ///     ctx.fill(cell.rect, &data[cell.cell.row * SIZE + cell.cell.col]);
+    /// })
+    /// .with_cell_changed(|old, new, cell| {
+    ///     let idx = cell.row * SIZE + cell.col;
+    ///     old[idx] != new[idx]
+    /// });
+    /// ```
+    pub fn with_cell_changed(mut self, f: impl Fn(&T, &T, BoardCell) -> bool + 'static) -> Self {
+        self.cell_changed = Some(Box::new(f));
+        self
+    }
+
+    /// Set the preferred size of a cell.
+    pub fn set_cell_size(&mut self, size: impl Into<Size>) {
+        self.cell_size = size.into();
+    }
+
+    /// Set the space between cells.
+    pub fn set_spacing(&mut self, spacing: f64) {
+        self.spacing = spacing.max(0.0);
+    }
+
+    /// The number of rows in the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns in the grid.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The rect of a cell, as of the most recent layout.
+    pub fn cell_rect(&self, cell: BoardCell) -> Rect {
+        let step = self.actual_cell_size + Size::new(self.spacing, self.spacing);
+        let origin = Point::new(cell.col as f64 * step.width, cell.row as f64 * step.height);
+        Rect::from_origin_size(origin, self.actual_cell_size)
+    }
+
+    /// The cell at `pos`, if any.
+    ///
+    /// Points in the space between cells are not in any cell.
+    pub fn cell_at(&self, pos: Point) -> Option<BoardCell> {
+        let step = self.actual_cell_size + Size::new(self.spacing, self.spacing);
+        if pos.x < 0.0 || pos.y < 0.0 || step.width <= 0.0 || step.height <= 0.0 {
+            return None;
+        }
+        let cell = BoardCell {
+            row: (pos.y / step.height) as usize,
+            col: (pos.x / step.width) as usize,
+        };
+        if cell.row < self.rows && cell.col < self.columns && self.cell_rect(cell).contains(pos) {
+            Some(cell)
+        } else {
+            None
+        }
+    }
+
+    /// The cell under the mouse, if any.
+    pub fn hot_cell(&self) -> Option<BoardCell> {
+        self.hot_cell
+    }
+
+    fn set_hot_cell(&mut self, ctx: &mut EventCtx, cell: Option<BoardCell>) {
+        if cell != self.hot_cell {
+            for cell in self.hot_cell.iter().chain(cell.iter()) {
+                ctx.request_paint_rect(self.cell_rect(*cell));
+            }
+            self.hot_cell = cell;
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for BoardGrid<T> {
+    #[instrument(name = "BoardGrid", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) => {
+                let cell = self.cell_at(mouse.pos).filter(|_| ctx.is_hot());
+                self.set_hot_cell(ctx, cell);
+            }
+            Event::MouseDown(mouse) if !ctx.is_disabled() => {
+                if let Some(cell) = self.cell_at(mouse.pos) {
+                    ctx.set_active(true);
+                    self.pressed = Some((cell, mouse.button));
+                    ctx.request_paint_rect(self.cell_rect(cell));
+                }
+            }
+            Event::MouseUp(mouse) => {
+                if let Some((cell, button)) = self.pressed.take() {
+                    ctx.set_active(false);
+                    ctx.request_paint_rect(self.cell_rect(cell));
+                    if button == mouse.button && self.cell_at(mouse.pos) == Some(cell) {
+                        if let Some(on_click) = &self.on_click {
+                            on_click(ctx, cell, mouse, data, env);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "BoardGrid",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        if let LifeCycle::HotChanged(false) = event {
+            if let Some(cell) = self.hot_cell.take() {
+                ctx.request_paint_rect(self.cell_rect(cell));
+            }
+        }
+    }
+
+    #[instrument(
+        name = "BoardGrid",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if old_data.same(data) {
+            return;
+        }
+        match &self.cell_changed {
+            Some(cell_changed) => {
+                let columns = self.columns;
+                let changed = (0..self.rows)
+                    .flat_map(|row| (0..columns).map(move |col| BoardCell { row, col }))
+                    .filter(|cell| cell_changed(old_data, data, *cell))
+                    .map(|cell| self.cell_rect(cell));
+                ctx.request_paint_rects(changed);
+            }
+            None => ctx.request_paint(),
+        }
+    }
+
+    #[instrument(name = "BoardGrid", level = "trace", skip(self, _ctx, bc, _data, _env))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.debug_check("BoardGrid");
+        let columns = self.columns as f64;
+        let rows = self.rows as f64;
+        let total_spacing = Size::new(
+            self.spacing * (columns - 1.0).max(0.0),
+            self.spacing * (rows - 1.0).max(0.0),
+        );
+        let preferred =
+            Size::new(self.cell_size.width * columns, self.cell_size.height * rows) + total_spacing;
+        let size = bc.constrain(preferred);
+
+        self.actual_cell_size = Size::new(
+            ((size.width - total_spacing.width) / columns.max(1.0)).max(0.0),
+            ((size.height - total_spacing.height) / rows.max(1.0)).max(0.0),
+        );
+        trace!("Computed cell size: {}", self.actual_cell_size);
+        size
+    }
+
+    #[instrument(name = "BoardGrid", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let region = ctx.region().clone();
+        for row in 0..self.rows {
+            for col in 0..self.columns {
+                let cell = BoardCell { row, col };
+                let rect = self.cell_rect(cell);
+                // only paint the cells that need it
+                if !region.intersects(rect) {
+                    continue;
+                }
+                let state = BoardCellState {
+                    cell,
+                    rect,
+                    is_hot: self.hot_cell == Some(cell),
+                    is_pressed: self.pressed.map(|(pressed, _)| pressed) == Some(cell),
+                };
+                ctx.with_save(|ctx| {
+                    ctx.clip(rect);
+                    (self.paint_cell)(ctx, &state, data, env);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn hit_testing() {
+        let mut grid = BoardGrid::<()>::new(3, 4, |_, _, _, _| {}).with_spacing(2.0);
+        grid.actual_cell_size = Size::new(10.0, 10.0);
+
+        let cell = BoardCell { row: 1, col: 2 };
+        assert_eq!(grid.cell_rect(cell), Rect::new(24.0, 12.0, 34.0, 22.0));
+        assert_eq!(grid.cell_at(Point::new(25.0, 21.0)), Some(cell));
+        // between cells, and outside the grid
+        assert_eq!(grid.cell_at(Point::new(35.0, 15.0)), None);
+        assert_eq!(grid.cell_at(Point::new(15.0, 40.0)), None);
+        assert_eq!(grid.cell_at(Point::new(-1.0, 5.0)), None);
+    }
+}
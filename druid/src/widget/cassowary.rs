@@ -0,0 +1,767 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A solver for systems of linear constraints, used by [`ConstraintLayout`].
+//!
+//! This is an implementation of the Cassowary algorithm, following the
+//! structure of the [Kiwi] solver. The system is kept between layout passes:
+//! constraints can be added and removed, and edit variables take values that
+//! change often, like the natural sizes of children, without rebuilding it.
+//!
+//! [`ConstraintLayout`]: super::ConstraintLayout
+//! [Kiwi]: https://github.com/nucleic/kiwi
+
+use std::collections::BTreeMap;
+
+use tracing::warn;
+
+/// The index of a variable in a system.
+pub(crate) type Variable = usize;
+
+/// Identifies a constraint that was added to a [`Solver`], to remove it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct ConstraintId(usize);
+
+/// A linear relation between variables that is enforced, or preferred, by
+/// the [`Solver`].
+///
+/// The constraint is `expression <op> 0`, where the expression is the sum of
+/// the `terms` and the `constant`.
+#[derive(Debug, Clone)]
+pub(crate) struct SolverConstraint {
+    pub terms: Vec<(Variable, f64)>,
+    pub constant: f64,
+    pub relation: Relation,
+    pub strength: f64,
+}
+
+/// The relation between a constraint's expression and zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Relation {
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+}
+
+/// The strength of a constraint that must be satisfied.
+pub(crate) const REQUIRED: f64 = 1_001_001_000.0;
+
+/// Returned when a required constraint conflicts with those already added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Unsatisfiable;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolKind {
+    External,
+    Slack,
+    Error,
+    Dummy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Symbol {
+    id: usize,
+    kind: SymbolKind,
+}
+
+/// The symbols a constraint added to the system.
+#[derive(Debug, Clone, Copy)]
+struct Tag {
+    marker: Option<Symbol>,
+    other: Option<Symbol>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Row {
+    cells: BTreeMap<Symbol, f64>,
+    constant: f64,
+}
+
+fn near_zero(val: f64) -> bool {
+    val.abs() < 1e-8
+}
+
+impl Row {
+    fn new(constant: f64) -> Row {
+        Row {
+            cells: BTreeMap::new(),
+            constant,
+        }
+    }
+
+    fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        self.cells.get(&symbol).copied().unwrap_or(0.0)
+    }
+
+    fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64) {
+        let value = self.cells.entry(symbol).or_insert(0.0);
+        *value += coefficient;
+        if near_zero(*value) {
+            self.cells.remove(&symbol);
+        }
+    }
+
+    fn insert_row(&mut self, other: &Row, coefficient: f64) {
+        self.constant += other.constant * coefficient;
+        for (symbol, value) in &other.cells {
+            self.insert_symbol(*symbol, value * coefficient);
+        }
+    }
+
+    /// Add `value` to the constant, returning the new constant.
+    fn add(&mut self, value: f64) -> f64 {
+        self.constant += value;
+        self.constant
+    }
+
+    fn remove(&mut self, symbol: Symbol) {
+        self.cells.remove(&symbol);
+    }
+
+    fn reverse_sign(&mut self) {
+        self.constant = -self.constant;
+        for value in self.cells.values_mut() {
+            *value = -*value;
+        }
+    }
+
+    /// Solve the row for `symbol`, which must be in the row.
+    ///
+    /// The row becomes the expression that `symbol` is equal to.
+    fn solve_for_symbol(&mut self, symbol: Symbol) {
+        let coefficient = -1.0 / self.cells.remove(&symbol).unwrap_or(1.0);
+        self.constant *= coefficient;
+        for value in self.cells.values_mut() {
+            *value *= coefficient;
+        }
+    }
+
+    /// Solve a row that is equal to `lhs` for `rhs`.
+    fn solve_for_symbols(&mut self, lhs: Symbol, rhs: Symbol) {
+        self.insert_symbol(lhs, -1.0);
+        self.solve_for_symbol(rhs);
+    }
+
+    /// Replace `symbol` with the expression in `row`.
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        if let Some(coefficient) = self.cells.remove(&symbol) {
+            self.insert_row(row, coefficient);
+        }
+    }
+}
+
+/// A constraint in the system, and the symbols it added.
+#[derive(Debug, Clone, Copy)]
+struct ConstraintInfo {
+    tag: Tag,
+    strength: f64,
+}
+
+/// An edit variable, and the value that was last suggested for it.
+#[derive(Debug, Clone, Copy)]
+struct EditInfo {
+    constraint: ConstraintId,
+    tag: Tag,
+    value: f64,
+}
+
+/// An incremental solver for a system of linear constraints.
+#[derive(Default)]
+pub(crate) struct Solver {
+    rows: BTreeMap<Symbol, Row>,
+    vars: BTreeMap<Variable, Symbol>,
+    constraints: BTreeMap<ConstraintId, ConstraintInfo>,
+    edits: BTreeMap<Variable, EditInfo>,
+    objective: Row,
+    artificial: Option<Row>,
+    /// Rows whose constants went negative, for `dual_optimize` to fix.
+    infeasible: Vec<Symbol>,
+    next_id: usize,
+    next_constraint: usize,
+}
+
+impl Solver {
+    /// Create an empty system.
+    pub fn new() -> Solver {
+        Solver::default()
+    }
+
+    /// Add a constraint to the system, and re-solve it.
+    ///
+    /// If the constraint is required and conflicts with the constraints
+    /// already in the system, it is not added.
+    pub fn add_constraint(
+        &mut self,
+        constraint: &SolverConstraint,
+    ) -> Result<ConstraintId, Unsatisfiable> {
+        let tag = self.try_add_constraint(constraint)?;
+        self.next_constraint += 1;
+        let id = ConstraintId(self.next_constraint);
+        let info = ConstraintInfo {
+            tag,
+            strength: constraint.strength,
+        };
+        self.constraints.insert(id, info);
+        Ok(id)
+    }
+
+    /// Remove a constraint from the system, and re-solve it.
+    pub fn remove_constraint(&mut self, id: ConstraintId) {
+        let ConstraintInfo { tag, strength } = match self.constraints.remove(&id) {
+            Some(info) => info,
+            None => return,
+        };
+        for symbol in tag.marker.iter().chain(tag.other.iter()) {
+            if symbol.kind == SymbolKind::Error {
+                match self.rows.get(symbol) {
+                    Some(row) => self.objective.insert_row(row, -strength),
+                    None => self.objective.insert_symbol(*symbol, -strength),
+                }
+            }
+        }
+
+        if let Some(marker) = tag.marker {
+            if self.rows.remove(&marker).is_none() {
+                // pivot the marker into the basis, so that its row can go.
+                if let Some(leaving) = self.marker_leaving_symbol(marker) {
+                    let mut row = self.rows.remove(&leaving).unwrap();
+                    row.solve_for_symbols(leaving, marker);
+                    self.substitute(marker, &row);
+                }
+            }
+        }
+        self.optimize(false);
+    }
+
+    /// Make `var` an edit variable, whose value is suggested with
+    /// [`suggest_value`], and held with the non-required `strength`.
+    ///
+    /// [`suggest_value`]: Solver::suggest_value
+    pub fn add_edit_variable(&mut self, var: Variable, strength: f64) {
+        debug_assert!(strength < REQUIRED, "edit variables can't be required");
+        if self.edits.contains_key(&var) {
+            return;
+        }
+        let constraint = SolverConstraint {
+            terms: vec![(var, 1.0)],
+            constant: 0.0,
+            relation: Relation::Equal,
+            strength,
+        };
+        // a constraint with error variables is always satisfiable.
+        if let Ok(id) = self.add_constraint(&constraint) {
+            let tag = self.constraints[&id].tag;
+            let info = EditInfo {
+                constraint: id,
+                tag,
+                value: 0.0,
+            };
+            self.edits.insert(var, info);
+        }
+    }
+
+    /// Stop `var` from being an edit variable.
+    pub fn remove_edit_variable(&mut self, var: Variable) {
+        if let Some(info) = self.edits.remove(&var) {
+            self.remove_constraint(info.constraint);
+        }
+    }
+
+    /// Returns `true` if `var` is an edit variable.
+    pub fn has_edit_variable(&self, var: Variable) -> bool {
+        self.edits.contains_key(&var)
+    }
+
+    /// Suggest a value for the edit variable `var`, and re-solve the system.
+    pub fn suggest_value(&mut self, var: Variable, value: f64) {
+        let info = match self.edits.get_mut(&var) {
+            Some(info) => info,
+            None => return,
+        };
+        let delta = value - info.value;
+        info.value = value;
+        let EditInfo { tag, .. } = *info;
+
+        if let Some(plus) = tag.marker {
+            if let Some(row) = self.rows.get_mut(&plus) {
+                if row.add(-delta) < 0.0 {
+                    self.infeasible.push(plus);
+                }
+                self.dual_optimize();
+                return;
+            }
+        }
+        if let Some(minus) = tag.other {
+            if let Some(row) = self.rows.get_mut(&minus) {
+                if row.add(delta) < 0.0 {
+                    self.infeasible.push(minus);
+                }
+                self.dual_optimize();
+                return;
+            }
+        }
+        if let Some(plus) = tag.marker {
+            for (symbol, row) in self.rows.iter_mut() {
+                let coefficient = row.coefficient_for(plus);
+                if coefficient != 0.0
+                    && row.add(delta * coefficient) < 0.0
+                    && symbol.kind != SymbolKind::External
+                {
+                    self.infeasible.push(*symbol);
+                }
+            }
+        }
+        self.dual_optimize();
+    }
+
+    /// The value of `var` in the current solution.
+    pub fn value(&self, var: Variable) -> f64 {
+        self.vars
+            .get(&var)
+            .and_then(|symbol| self.rows.get(symbol))
+            .map(|row| row.constant)
+            .unwrap_or(0.0)
+    }
+
+    fn try_add_constraint(&mut self, constraint: &SolverConstraint) -> Result<Tag, Unsatisfiable> {
+        let (mut row, tag) = self.create_row(constraint);
+        let mut subject = choose_subject(&row, &tag);
+
+        if subject.is_none() && row.cells.keys().all(|s| s.kind == SymbolKind::Dummy) {
+            if !near_zero(row.constant) {
+                return Err(Unsatisfiable);
+            }
+            subject = tag.marker;
+        }
+
+        match subject {
+            None => {
+                // pivoting for the artificial variable changes the whole
+                // system, so it is restored if the constraint can't be met.
+                // Only constraints whose variables are all solved for
+                // already get here.
+                let saved = (self.rows.clone(), self.objective.clone());
+                if !self.add_with_artificial_variable(row) {
+                    self.rows = saved.0;
+                    self.objective = saved.1;
+                    self.infeasible.clear();
+                    return Err(Unsatisfiable);
+                }
+            }
+            Some(subject) => {
+                row.solve_for_symbol(subject);
+                self.substitute(subject, &row);
+                self.rows.insert(subject, row);
+            }
+        }
+
+        self.optimize(false);
+        Ok(tag)
+    }
+
+    fn new_symbol(&mut self, kind: SymbolKind) -> Symbol {
+        self.next_id += 1;
+        Symbol {
+            id: self.next_id,
+            kind,
+        }
+    }
+
+    fn var_symbol(&mut self, var: Variable) -> Symbol {
+        if let Some(symbol) = self.vars.get(&var) {
+            return *symbol;
+        }
+        let symbol = self.new_symbol(SymbolKind::External);
+        self.vars.insert(var, symbol);
+        symbol
+    }
+
+    /// Create a row for the constraint, with all basic variables substituted.
+    fn create_row(&mut self, constraint: &SolverConstraint) -> (Row, Tag) {
+        let mut row = Row::new(constraint.constant);
+        for (var, coefficient) in &constraint.terms {
+            if near_zero(*coefficient) {
+                continue;
+            }
+            let symbol = self.var_symbol(*var);
+            match self.rows.get(&symbol) {
+                Some(basic) => row.insert_row(basic, *coefficient),
+                None => row.insert_symbol(symbol, *coefficient),
+            }
+        }
+
+        let mut tag = Tag {
+            marker: None,
+            other: None,
+        };
+        let strength = constraint.strength;
+        match constraint.relation {
+            Relation::LessOrEqual | Relation::GreaterOrEqual => {
+                let coefficient = if constraint.relation == Relation::LessOrEqual {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let slack = self.new_symbol(SymbolKind::Slack);
+                tag.marker = Some(slack);
+                row.insert_symbol(slack, coefficient);
+                if strength < REQUIRED {
+                    let error = self.new_symbol(SymbolKind::Error);
+                    tag.other = Some(error);
+                    row.insert_symbol(error, -coefficient);
+                    self.objective.insert_symbol(error, strength);
+                }
+            }
+            Relation::Equal => {
+                if strength < REQUIRED {
+                    let plus = self.new_symbol(SymbolKind::Error);
+                    let minus = self.new_symbol(SymbolKind::Error);
+                    tag.marker = Some(plus);
+                    tag.other = Some(minus);
+                    row.insert_symbol(plus, -1.0);
+                    row.insert_symbol(minus, 1.0);
+                    self.objective.insert_symbol(plus, strength);
+                    self.objective.insert_symbol(minus, strength);
+                } else {
+                    let dummy = self.new_symbol(SymbolKind::Dummy);
+                    tag.marker = Some(dummy);
+                    row.insert_symbol(dummy, 1.0);
+                }
+            }
+        }
+
+        if row.constant < 0.0 {
+            row.reverse_sign();
+        }
+        (row, tag)
+    }
+
+    /// Add a row using an artificial variable, returning `false` if the
+    /// constraint it represents cannot be satisfied.
+    fn add_with_artificial_variable(&mut self, row: Row) -> bool {
+        let art = self.new_symbol(SymbolKind::Slack);
+        self.rows.insert(art, row.clone());
+        self.artificial = Some(row);
+
+        self.optimize(true);
+        let success = self
+            .artificial
+            .take()
+            .map(|row| near_zero(row.constant))
+            .unwrap_or(false);
+
+        if let Some(mut row) = self.rows.remove(&art) {
+            if row.cells.is_empty() {
+                return success;
+            }
+            let entering = row
+                .cells
+                .keys()
+                .find(|s| matches!(s.kind, SymbolKind::Slack | SymbolKind::Error))
+                .copied();
+            let entering = match entering {
+                Some(entering) => entering,
+                None => return false,
+            };
+            row.solve_for_symbols(art, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+
+        for row in self.rows.values_mut() {
+            row.remove(art);
+        }
+        self.objective.remove(art);
+        success
+    }
+
+    /// Replace `symbol` with `row` throughout the system.
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        for (basic_symbol, basic) in self.rows.iter_mut() {
+            basic.substitute(symbol, row);
+            if basic_symbol.kind != SymbolKind::External && basic.constant < 0.0 {
+                self.infeasible.push(*basic_symbol);
+            }
+        }
+        self.objective.substitute(symbol, row);
+        if let Some(artificial) = self.artificial.as_mut() {
+            artificial.substitute(symbol, row);
+        }
+    }
+
+    /// Optimize the system for the objective, or for the artificial objective.
+    fn optimize(&mut self, artificial: bool) {
+        loop {
+            let objective = if artificial {
+                match &self.artificial {
+                    Some(row) => row,
+                    None => return,
+                }
+            } else {
+                &self.objective
+            };
+            let entering = objective
+                .cells
+                .iter()
+                .find(|(s, value)| s.kind != SymbolKind::Dummy && **value < 0.0)
+                .map(|(s, _)| *s);
+            let entering = match entering {
+                Some(entering) => entering,
+                None => return,
+            };
+
+            // the row that most restricts the entering symbol.
+            let mut leaving = None;
+            let mut min_ratio = f64::INFINITY;
+            for (symbol, row) in &self.rows {
+                if symbol.kind == SymbolKind::External {
+                    continue;
+                }
+                let coefficient = row.coefficient_for(entering);
+                if coefficient < 0.0 {
+                    let ratio = -row.constant / coefficient;
+                    if ratio < min_ratio {
+                        min_ratio = ratio;
+                        leaving = Some(*symbol);
+                    }
+                }
+            }
+            // the objective is unbounded; with error variables as the only
+            // terms of the objective this can't happen.
+            let leaving = match leaving {
+                Some(leaving) => leaving,
+                None => return,
+            };
+
+            let mut row = self.rows.remove(&leaving).unwrap();
+            row.solve_for_symbols(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+}
+
+impl Solver {
+    /// Restore feasibility after edit variables changed, keeping the
+    /// solution optimal.
+    fn dual_optimize(&mut self) {
+        while let Some(leaving) = self.infeasible.pop() {
+            let feasible = self
+                .rows
+                .get(&leaving)
+                .map_or(true, |row| near_zero(row.constant) || row.constant >= 0.0);
+            if feasible {
+                continue;
+            }
+            let entering = match self.dual_entering_symbol(&self.rows[&leaving]) {
+                Some(entering) => entering,
+                None => {
+                    warn!("ConstraintLayout: the dual optimization failed");
+                    self.infeasible.clear();
+                    return;
+                }
+            };
+            let mut row = self.rows.remove(&leaving).unwrap();
+            row.solve_for_symbols(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+
+    /// The symbol that enters the basis when `row` leaves it in the dual
+    /// simplex, if there is one.
+    fn dual_entering_symbol(&self, row: &Row) -> Option<Symbol> {
+        let mut entering = None;
+        let mut min_ratio = f64::INFINITY;
+        for (symbol, coefficient) in &row.cells {
+            if *coefficient > 0.0 && symbol.kind != SymbolKind::Dummy {
+                let ratio = self.objective.coefficient_for(*symbol) / coefficient;
+                if ratio < min_ratio {
+                    min_ratio = ratio;
+                    entering = Some(*symbol);
+                }
+            }
+        }
+        entering
+    }
+
+    /// The row to pivot a constraint's marker into, when removing the constraint.
+    fn marker_leaving_symbol(&self, marker: Symbol) -> Option<Symbol> {
+        let mut min_negative = (f64::INFINITY, None);
+        let mut min_positive = (f64::INFINITY, None);
+        let mut external = None;
+        for (symbol, row) in &self.rows {
+            let coefficient = row.coefficient_for(marker);
+            if coefficient == 0.0 {
+                continue;
+            }
+            if symbol.kind == SymbolKind::External {
+                external = Some(*symbol);
+            } else if coefficient < 0.0 {
+                let ratio = -row.constant / coefficient;
+                if ratio < min_negative.0 {
+                    min_negative = (ratio, Some(*symbol));
+                }
+            } else {
+                let ratio = row.constant / coefficient;
+                if ratio < min_positive.0 {
+                    min_positive = (ratio, Some(*symbol));
+                }
+            }
+        }
+        min_negative.1.or(min_positive.1).or(external)
+    }
+}
+
+/// Choose the symbol to solve a new row for, if there is a suitable one.
+fn choose_subject(row: &Row, tag: &Tag) -> Option<Symbol> {
+    if let Some(symbol) = row.cells.keys().find(|s| s.kind == SymbolKind::External) {
+        return Some(*symbol);
+    }
+    for symbol in tag.marker.iter().chain(tag.other.iter()) {
+        if matches!(symbol.kind, SymbolKind::Slack | SymbolKind::Error)
+            && row.coefficient_for(*symbol) < 0.0
+        {
+            return Some(*symbol);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    fn constraint(
+        terms: &[(Variable, f64)],
+        constant: f64,
+        relation: Relation,
+        strength: f64,
+    ) -> SolverConstraint {
+        SolverConstraint {
+            terms: terms.to_vec(),
+            constant,
+            relation,
+            strength,
+        }
+    }
+
+    #[test]
+    fn solves_constraints() {
+        use Relation::*;
+        let (left, width, right) = (0, 1, 2);
+        let mut solver = Solver::new();
+        // right == left + width
+        solver
+            .add_constraint(&constraint(
+                &[(right, 1.0), (left, -1.0), (width, -1.0)],
+                0.0,
+                Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        // left >= 10
+        solver
+            .add_constraint(&constraint(&[(left, 1.0)], -10.0, GreaterOrEqual, REQUIRED))
+            .unwrap();
+        // right <= 100
+        solver
+            .add_constraint(&constraint(&[(right, 1.0)], -100.0, LessOrEqual, REQUIRED))
+            .unwrap();
+        // width == 50, weakly; width == 200, strongly
+        solver
+            .add_constraint(&constraint(&[(width, 1.0)], -50.0, Equal, 1.0))
+            .unwrap();
+        solver
+            .add_constraint(&constraint(&[(width, 1.0)], -200.0, Equal, 1000.0))
+            .unwrap();
+
+        // the strong preference wins, as far as the required constraints allow
+        assert!((solver.value(left) - 10.0).abs() < 1e-6);
+        assert!((solver.value(width) - 90.0).abs() < 1e-6);
+        assert!((solver.value(right) - 100.0).abs() < 1e-6);
+
+        // left <= 5 conflicts with left >= 10, and is ignored
+        assert_eq!(
+            solver.add_constraint(&constraint(&[(left, 1.0)], -5.0, LessOrEqual, REQUIRED)),
+            Err(Unsatisfiable)
+        );
+        assert!((solver.value(left) - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn removes_constraints() {
+        use Relation::*;
+        let width = 0;
+        let mut solver = Solver::new();
+        solver
+            .add_constraint(&constraint(&[(width, 1.0)], -50.0, Equal, 1.0))
+            .unwrap();
+        let at_least = solver
+            .add_constraint(&constraint(
+                &[(width, 1.0)],
+                -80.0,
+                GreaterOrEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        assert!((solver.value(width) - 80.0).abs() < 1e-6);
+
+        solver.remove_constraint(at_least);
+        assert!((solver.value(width) - 50.0).abs() < 1e-6);
+
+        // a constraint that conflicted with the removed one fits now
+        solver
+            .add_constraint(&constraint(&[(width, 1.0)], -20.0, LessOrEqual, REQUIRED))
+            .unwrap();
+        assert!((solver.value(width) - 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn edit_variables() {
+        use Relation::*;
+        let (left, width, right) = (0, 1, 2);
+        let mut solver = Solver::new();
+        solver
+            .add_constraint(&constraint(
+                &[(right, 1.0), (left, -1.0), (width, -1.0)],
+                0.0,
+                Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(&constraint(&[(left, 1.0)], -10.0, Equal, REQUIRED))
+            .unwrap();
+        solver
+            .add_constraint(&constraint(&[(right, 1.0)], -100.0, LessOrEqual, REQUIRED))
+            .unwrap();
+
+        solver.add_edit_variable(width, 1000.0);
+        assert!(solver.has_edit_variable(width));
+        solver.suggest_value(width, 40.0);
+        assert!((solver.value(right) - 50.0).abs() < 1e-6);
+        solver.suggest_value(width, 60.0);
+        assert!((solver.value(right) - 70.0).abs() < 1e-6);
+        // the required constraints win over the suggestion
+        solver.suggest_value(width, 500.0);
+        assert!((solver.value(width) - 90.0).abs() < 1e-6);
+        solver.suggest_value(width, 30.0);
+        assert!((solver.value(right) - 40.0).abs() < 1e-6);
+
+        solver.remove_edit_variable(width);
+        assert!(!solver.has_edit_variable(width));
+    }
+}
@@ -18,6 +18,7 @@ use crate::kurbo::{BezPath, Size};
 use crate::piet::{LineCap, LineJoin, LinearGradient, RenderContext, StrokeStyle, UnitPoint};
 use crate::theme;
 use crate::widget::{prelude::*, Label, LabelText};
+use crate::KbKey;
 use tracing::{instrument, trace};
 
 /// A checkbox that toggles a `bool`.
@@ -65,6 +66,16 @@ impl Widget<bool> for Checkbox {
                 }
                 ctx.set_active(false);
             }
+            Event::KeyDown(key)
+                if key.key == KbKey::Character(" ".into())
+                    && ctx.is_focused()
+                    && !ctx.is_disabled() =>
+            {
+                *data = !*data;
+                trace!("Checkbox {:?} toggled from the keyboard", ctx.widget_id());
+                ctx.request_paint();
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
@@ -72,8 +83,12 @@ impl Widget<bool> for Checkbox {
     #[instrument(name = "CheckBox", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &bool, env: &Env) {
         self.child_label.lifecycle(ctx, event, data, env);
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_)
+            | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -111,10 +126,9 @@ impl Widget<bool> for Checkbox {
         let x_padding = env.get(theme::WIDGET_CONTROL_COMPONENT_PADDING);
         let border_width = 1.;
 
-        let rect = Size::new(size, size)
-            .to_rect()
-            .inset(-border_width / 2.)
-            .to_rounded_rect(2.);
+        let rect = Size::new(size, size).to_rect().inset(-border_width / 2.);
+        let (rect, border_width) = ctx.snap_stroke(rect, border_width, env);
+        let rect = rect.to_rounded_rect(2.);
 
         //Paint the background
         let background_gradient = LinearGradient::new(
@@ -128,7 +142,9 @@ impl Widget<bool> for Checkbox {
 
         ctx.fill(rect, &background_gradient);
 
-        let border_color = if ctx.is_hot() && !ctx.is_disabled() {
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else if ctx.is_hot() && !ctx.is_disabled() {
             env.get(theme::BORDER_LIGHT)
         } else {
             env.get(theme::BORDER_DARK)
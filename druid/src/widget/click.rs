@@ -17,7 +17,7 @@
 //! [`Controller`]: struct.Controller.html
 
 use crate::widget::Controller;
-use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, MouseButton, Widget};
+use crate::{Data, Env, Event, EventCtx, KbKey, LifeCycle, LifeCycleCtx, MouseButton, Widget};
 use tracing::{instrument, trace};
 
 /// A clickable [`Controller`] widget. Pass this and a child widget to a
@@ -31,6 +31,9 @@ use tracing::{instrument, trace};
 /// mouse down, which can be useful for painting based on `ctx.is_active()`
 /// and `ctx.is_hot()`.
 ///
+/// When the child has keyboard focus, pressing Enter or Space also counts
+/// as a click, unless the child handled the key.
+///
 /// [`Controller`]: struct.Controller.html
 /// [`ControllerHost`]: struct.ControllerHost.html
 /// [`WidgetExt`]: ../trait.WidgetExt.html
@@ -79,6 +82,15 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
         }
 
         child.event(ctx, event, data, env);
+
+        if let Event::KeyDown(key) = event {
+            let is_activation = key.key == KbKey::Enter || key.key == KbKey::Character(" ".into());
+            if is_activation && ctx.is_focused() && !ctx.is_handled() && !ctx.is_disabled() {
+                trace!("Widget {:?} activated from the keyboard", ctx.widget_id());
+                (self.action)(ctx, data, env);
+                ctx.set_handled();
+            }
+        }
     }
 
     #[instrument(
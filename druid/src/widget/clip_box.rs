@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::commands::SCROLL_TO_VIEW;
 use crate::kurbo::{Affine, Point, Rect, Size, Vec2};
 use crate::widget::prelude::*;
 use crate::widget::Axis;
@@ -181,6 +182,12 @@ impl<T, W> ClipBox<T, W> {
         self.child.widget_mut()
     }
 
+    /// The layout rects of the children of the child widget, in the
+    /// coordinate space of the content.
+    pub(crate) fn child_rects(&self) -> &[Rect] {
+        self.child.child_rects()
+    }
+
     /// Returns a the viewport describing this `ClipBox`'s position.
     pub fn viewport(&self) -> Viewport {
         self.port
@@ -236,8 +243,10 @@ impl<T, W> ClipBox<T, W> {
 impl<T, W: Widget<T>> ClipBox<T, W> {
     /// Creates a new `ClipBox` wrapping `child`.
     pub fn new(child: W) -> Self {
+        let mut child = WidgetPod::new(child);
+        child.set_clip_to_parent(true);
         ClipBox {
-            child: WidgetPod::new(child),
+            child,
             port: Default::default(),
             constrain_horizontal: false,
             constrain_vertical: false,
@@ -298,6 +307,28 @@ impl<T, W: Widget<T>> ClipBox<T, W> {
         }
     }
 
+    /// Scroll so that `area`, in window coordinates, is visible, as asked for
+    /// by a [`SCROLL_TO_VIEW`] notification.
+    ///
+    /// The part of `area` that is visible afterwards is submitted as a new
+    /// [`SCROLL_TO_VIEW`] notification, so that enclosing scroll containers
+    /// bring it into view too. Returns `true` if the viewport changed.
+    ///
+    /// [`SCROLL_TO_VIEW`]: crate::commands::SCROLL_TO_VIEW
+    pub fn scroll_to_view(&mut self, ctx: &mut EventCtx, area: Rect) -> bool {
+        let content_origin = ctx.window_origin() - self.viewport_origin().to_vec2();
+        let content_area = area - content_origin.to_vec2();
+        let changed = self.pan_to_visible(content_area);
+        if changed {
+            ctx.request_paint();
+        }
+
+        let content_origin = ctx.window_origin() - self.viewport_origin().to_vec2();
+        let visible = content_area.intersect(self.port.view_rect());
+        ctx.submit_notification(SCROLL_TO_VIEW.with(visible + content_origin.to_vec2()));
+        changed
+    }
+
     /// Modify the `ClipBox`'s viewport rectangle with a closure.
     ///
     /// The provided callback function can modify its argument, and when it is
@@ -312,6 +343,14 @@ impl<T, W: Widget<T>> ClipBox<T, W> {
 impl<T: Data, W: Widget<T>> Widget<T> for ClipBox<T, W> {
     #[instrument(name = "ClipBox", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Notification(note) = event {
+            if let Some(area) = note.get(SCROLL_TO_VIEW) {
+                self.scroll_to_view(ctx, *area);
+                ctx.set_handled();
+                return;
+            }
+        }
+
         let viewport = ctx.size().to_rect();
         let force_event = self.child.is_hot() || self.child.has_active();
         if let Some(child_event) =
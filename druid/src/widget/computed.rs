@@ -0,0 +1,143 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows a value derived from its data.
+
+use crate::widget::prelude::*;
+use crate::widget::{Axis, WidgetWrapper};
+use crate::Data;
+
+use tracing::{instrument, trace, warn};
+
+type ComputeFn<T, U> = Box<dyn Fn(&T) -> U>;
+
+/// A wrapper that gives its child a value derived from the data, through a
+/// pure function.
+///
+/// The derived value is cached: the function only runs again when the data
+/// changes, and the child is only updated when the derived value changes.
+/// This is useful for expensive derivations, like filtering a list or
+/// computing an aggregate, that would otherwise run on every update pass.
+///
+/// The derived value is read-only; changes the child makes to it are
+/// discarded.
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{Computed, Label};
+///
+/// // show the number of completed tasks
+/// let done = Computed::new(
+///     |tasks: &Arc<Vec<bool>>| tasks.iter().filter(|done| **done).count(),
+///     Label::dynamic(|count: &usize, _| format!("{} done", count)),
+/// );
+/// ```
+pub struct Computed<T, U, W> {
+    compute: ComputeFn<T, U>,
+    inner: W,
+    /// The derived value, once we have seen the data.
+    value: Option<U>,
+}
+
+impl<T, U: Data, W: Widget<U>> Computed<T, U, W> {
+    /// Create a widget that shows the value that `compute` derives from the
+    /// data with `inner`.
+    pub fn new(compute: impl Fn(&T) -> U + 'static, inner: W) -> Self {
+        Computed {
+            compute: Box::new(compute),
+            inner,
+            value: None,
+        }
+    }
+
+    /// The child and the derived value, computing it if we haven't yet.
+    fn split(&mut self, data: &T) -> (&mut W, &U) {
+        let compute = &self.compute;
+        let value = self.value.get_or_insert_with(|| compute(data));
+        (&mut self.inner, value)
+    }
+}
+
+impl<T, U, W> WidgetWrapper for Computed<T, U, W> {
+    widget_wrapper_body!(W, inner);
+}
+
+impl<T: Data, U: Data, W: Widget<U>> Widget<T> for Computed<T, U, W> {
+    #[instrument(name = "Computed", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let (inner, value) = self.split(data);
+        let mut new_value = value.clone();
+        inner.event(ctx, event, &mut new_value, env);
+        if !value.same(&new_value) {
+            warn!("Computed: the child changed the derived value; the change is discarded");
+        }
+    }
+
+    #[instrument(name = "Computed", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let (inner, value) = self.split(data);
+        inner.lifecycle(ctx, event, value, env);
+    }
+
+    #[instrument(
+        name = "Computed",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let (_, old_value) = self.split(old_data);
+        let old_value = old_value.clone();
+        let value = if old_data.same(data) {
+            old_value.clone()
+        } else {
+            trace!("recomputing derived value");
+            (self.compute)(data)
+        };
+        if ctx.has_requested_update() || !old_value.same(&value) || ctx.env_changed() {
+            self.inner.update(ctx, &old_value, &value, env);
+        } else {
+            trace!("skipping child update");
+        }
+        self.value = Some(value);
+    }
+
+    #[instrument(name = "Computed", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let (inner, value) = self.split(data);
+        inner.layout(ctx, bc, value, env)
+    }
+
+    #[instrument(name = "Computed", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let (inner, value) = self.split(data);
+        inner.compute_max_intrinsic(axis, ctx, bc, value, env)
+    }
+
+    #[instrument(name = "Computed", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let (inner, value) = self.split(data);
+        inner.paint(ctx, value, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.inner.id()
+    }
+}
@@ -0,0 +1,625 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that positions its children with linear constraints.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::cassowary::{self, ConstraintId, Relation, Solver, SolverConstraint};
+use crate::widget::prelude::*;
+use crate::{ArcStr, Data, Point, Rect, WidgetPod};
+use tracing::{instrument, trace, warn};
+
+/// A container that positions its children with linear constraints between
+/// their edges.
+///
+/// Each child is added with a name, and [`Constraint`]s relate the edges and
+/// sizes of named children to each other and to the layout itself, such as
+/// "the left edge of `ok` is 8 past the right edge of `cancel`". The
+/// constraints are solved with the [Cassowary] algorithm.
+///
+/// Constraints have a [`Strength`]. Required constraints are always
+/// satisfied; weaker ones are satisfied as well as they can be, with stronger
+/// constraints taking precedence. Every child prefers, with
+/// [`Strength::Medium`], to be its natural size, and the layout prefers, with
+/// [`Strength::Weak`], to be as small as its constraints allow, so the
+/// layout's right and bottom edges should be constrained to the children's
+/// for it to grow around them.
+///
+/// Constraints can be fixed when the layout is built, or computed from the
+/// data with [`with_dynamic_constraints`]; these are recomputed on each
+/// layout pass, and the layout is requested again whenever the data changes.
+///
+/// The system of constraints is kept between layout passes. Only the
+/// constraints that changed, and the children's natural sizes, are updated.
+///
+/// A required constraint that conflicts with the constraints before it is
+/// ignored, and a warning is logged.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, ConstraintLayout, Item, Strength, TextBox};
+///
+/// let parent = Item::parent();
+/// let name = Item::child("name");
+/// let ok = Item::child("ok");
+///
+/// let layout = ConstraintLayout::new()
+///     .with_child("name", TextBox::new())
+///     .with_child("ok", Button::new("OK"))
+///     .with_constraint(name.left().equals(parent.left() + 10.0))
+///     .with_constraint(name.top().equals(parent.top() + 10.0))
+///     .with_constraint(ok.left().equals(name.right() + 8.0))
+///     .with_constraint(ok.center_y().equals(name.center_y()))
+///     .with_constraint(parent.right().equals(ok.right() + 10.0))
+///     .with_constraint(parent.bottom().equals(name.bottom() + 10.0))
+///     // the text box takes any extra width.
+///     .with_constraint(name.width().at_least(200.0).with_strength(Strength::Strong));
+/// # let _: ConstraintLayout<String> = layout;
+/// ```
+///
+/// [Cassowary]: https://constraints.cs.washington.edu/cassowary/
+/// [`with_dynamic_constraints`]: ConstraintLayout::with_dynamic_constraints
+pub struct ConstraintLayout<T> {
+    children: Vec<(ArcStr, ChildPod<T>)>,
+    constraints: Vec<Constraint>,
+    dynamic_constraints: Option<ConstraintsFn<T>>,
+    /// The system from the last layout pass, until a child or a fixed
+    /// constraint is added.
+    system: Option<System>,
+}
+
+/// A solver, and the constraints in it that can change between layout passes.
+struct System {
+    solver: Solver,
+    /// The box constraints that the layout's size is constrained to.
+    bc: BoxConstraints,
+    bc_constraints: Vec<ConstraintId>,
+    /// The dynamic constraints, as of the last layout pass.
+    dynamic: Vec<Constraint>,
+    dynamic_constraints: Vec<ConstraintId>,
+}
+
+type ChildPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+type ConstraintsFn<T> = Box<dyn Fn(&T, &Env) -> Vec<Constraint>>;
+
+/// The layout itself, or one of its children, in a [`ConstraintLayout`].
+///
+/// The methods of an `Item` return an [`Expression`] for one of its edges
+/// or dimensions, to build [`Constraint`]s with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item(Option<ArcStr>);
+
+/// A linear combination of the edges and dimensions of [`Item`]s, plus a
+/// constant.
+///
+/// Expressions are built from the methods of [`Item`] and arithmetic with
+/// other expressions and with `f64`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    terms: Vec<(Item, Field, f64)>,
+    constant: f64,
+}
+
+/// A linear relation between two [`Expression`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constraint {
+    expression: Expression,
+    relation: Relation,
+    strength: Strength,
+}
+
+/// How strongly a [`Constraint`] is enforced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Data)]
+pub enum Strength {
+    /// A preference that yields to every stronger constraint.
+    Weak,
+    /// A preference that yields to strong and required constraints.
+    Medium,
+    /// A preference that yields only to required constraints.
+    Strong,
+    /// The constraint must be satisfied.
+    Required,
+}
+
+/// The variables the solver knows about for each item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Left,
+    Top,
+    Width,
+    Height,
+}
+
+const FIELDS: usize = 4;
+
+impl Item {
+    /// The layout itself.
+    ///
+    /// The layout's left and top edges are always zero.
+    pub fn parent() -> Item {
+        Item(None)
+    }
+
+    /// The child added with `name`.
+    pub fn child(name: impl Into<ArcStr>) -> Item {
+        Item(Some(name.into()))
+    }
+
+    /// The left edge of the item.
+    pub fn left(&self) -> Expression {
+        self.field(Field::Left, 1.0)
+    }
+
+    /// The right edge of the item.
+    pub fn right(&self) -> Expression {
+        self.left() + self.width()
+    }
+
+    /// The top edge of the item.
+    pub fn top(&self) -> Expression {
+        self.field(Field::Top, 1.0)
+    }
+
+    /// The bottom edge of the item.
+    pub fn bottom(&self) -> Expression {
+        self.top() + self.height()
+    }
+
+    /// The width of the item.
+    pub fn width(&self) -> Expression {
+        self.field(Field::Width, 1.0)
+    }
+
+    /// The height of the item.
+    pub fn height(&self) -> Expression {
+        self.field(Field::Height, 1.0)
+    }
+
+    /// The horizontal center of the item.
+    pub fn center_x(&self) -> Expression {
+        self.left() + self.field(Field::Width, 0.5)
+    }
+
+    /// The vertical center of the item.
+    pub fn center_y(&self) -> Expression {
+        self.top() + self.field(Field::Height, 0.5)
+    }
+
+    fn field(&self, field: Field, coefficient: f64) -> Expression {
+        Expression {
+            terms: vec![(self.clone(), field, coefficient)],
+            constant: 0.0,
+        }
+    }
+}
+
+impl Expression {
+    /// An expression with only a constant.
+    pub fn constant(constant: f64) -> Expression {
+        Expression {
+            terms: Vec::new(),
+            constant,
+        }
+    }
+
+    /// A required constraint that this expression equals `other`.
+    pub fn equals(self, other: impl Into<Expression>) -> Constraint {
+        Constraint::new(self - other.into(), Relation::Equal)
+    }
+
+    /// A required constraint that this expression is at least `other`.
+    pub fn at_least(self, other: impl Into<Expression>) -> Constraint {
+        Constraint::new(self - other.into(), Relation::GreaterOrEqual)
+    }
+
+    /// A required constraint that this expression is at most `other`.
+    pub fn at_most(self, other: impl Into<Expression>) -> Constraint {
+        Constraint::new(self - other.into(), Relation::LessOrEqual)
+    }
+}
+
+impl From<f64> for Expression {
+    fn from(constant: f64) -> Expression {
+        Expression::constant(constant)
+    }
+}
+
+impl Add for Expression {
+    type Output = Expression;
+
+    fn add(mut self, other: Expression) -> Expression {
+        self.terms.extend(other.terms);
+        self.constant += other.constant;
+        self
+    }
+}
+
+impl Add<f64> for Expression {
+    type Output = Expression;
+
+    fn add(mut self, other: f64) -> Expression {
+        self.constant += other;
+        self
+    }
+}
+
+impl Sub for Expression {
+    type Output = Expression;
+
+    fn sub(self, other: Expression) -> Expression {
+        self + -other
+    }
+}
+
+impl Sub<f64> for Expression {
+    type Output = Expression;
+
+    fn sub(self, other: f64) -> Expression {
+        self + -other
+    }
+}
+
+impl Mul<f64> for Expression {
+    type Output = Expression;
+
+    fn mul(mut self, other: f64) -> Expression {
+        for term in &mut self.terms {
+            term.2 *= other;
+        }
+        self.constant *= other;
+        self
+    }
+}
+
+impl Neg for Expression {
+    type Output = Expression;
+
+    fn neg(self) -> Expression {
+        self * -1.0
+    }
+}
+
+impl Constraint {
+    fn new(expression: Expression, relation: Relation) -> Constraint {
+        Constraint {
+            expression,
+            relation,
+            strength: Strength::Required,
+        }
+    }
+
+    /// Builder-style method to set the strength of the constraint.
+    ///
+    /// The default is [`Strength::Required`].
+    pub fn with_strength(mut self, strength: Strength) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+impl Strength {
+    fn weight(self) -> f64 {
+        match self {
+            Strength::Weak => 1.0,
+            Strength::Medium => 1_000.0,
+            Strength::Strong => 1_000_000.0,
+            Strength::Required => cassowary::REQUIRED,
+        }
+    }
+}
+
+impl<T: Data> ConstraintLayout<T> {
+    /// Create a new, empty `ConstraintLayout`.
+    pub fn new() -> Self {
+        ConstraintLayout {
+            children: Vec::new(),
+            constraints: Vec::new(),
+            dynamic_constraints: None,
+            system: None,
+        }
+    }
+
+    /// Builder-style method to add a child, referred to by [`Item::child`]
+    /// with `name`.
+    ///
+    /// See also [`add_child`].
+    ///
+    /// [`add_child`]: ConstraintLayout::add_child
+    pub fn with_child(mut self, name: impl Into<ArcStr>, child: impl Widget<T> + 'static) -> Self {
+        self.add_child(name, child);
+        self
+    }
+
+    /// Add a child, referred to by [`Item::child`] with `name`.
+    ///
+    /// See also [`with_child`].
+    ///
+    /// [`with_child`]: ConstraintLayout::with_child
+    pub fn add_child(&mut self, name: impl Into<ArcStr>, child: impl Widget<T> + 'static) {
+        self.children
+            .push((name.into(), WidgetPod::new(Box::new(child))));
+        self.system = None;
+    }
+
+    /// Builder-style method to add a constraint.
+    ///
+    /// See also [`add_constraint`].
+    ///
+    /// [`add_constraint`]: ConstraintLayout::add_constraint
+    pub fn with_constraint(mut self, constraint: Constraint) -> Self {
+        self.add_constraint(constraint);
+        self
+    }
+
+    /// Add a constraint.
+    ///
+    /// The layout is not requested again; this is meant to be used while
+    /// building the widget.
+    pub fn add_constraint(&mut self, constraint: Constraint) {
+        self.constraints.push(constraint);
+        self.system = None;
+    }
+
+    /// Builder-style method to add constraints that are computed from the data.
+    ///
+    /// The closure is called on each layout pass, and its constraints are
+    /// added after the fixed ones. The layout is requested again whenever the
+    /// data changes.
+    pub fn with_dynamic_constraints(
+        mut self,
+        constraints: impl Fn(&T, &Env) -> Vec<Constraint> + 'static,
+    ) -> Self {
+        self.dynamic_constraints = Some(Box::new(constraints));
+        self
+    }
+
+    /// The index of the first solver variable of `item`, if it exists.
+    fn item_index(&self, item: &Item) -> Option<usize> {
+        match &item.0 {
+            None => Some(0),
+            Some(name) => self
+                .children
+                .iter()
+                .position(|(child, _)| child == name)
+                .map(|idx| (idx + 1) * FIELDS),
+        }
+    }
+
+    fn solver_constraint(
+        &self,
+        expression: &Expression,
+        relation: Relation,
+        strength: Strength,
+    ) -> Option<SolverConstraint> {
+        let mut terms = Vec::with_capacity(expression.terms.len());
+        for (item, field, coefficient) in &expression.terms {
+            match self.item_index(item) {
+                Some(idx) => terms.push((idx + *field as usize, *coefficient)),
+                None => {
+                    warn!("ConstraintLayout has no child named {:?}", item.0);
+                    return None;
+                }
+            }
+        }
+        Some(SolverConstraint {
+            terms,
+            constant: expression.constant,
+            relation,
+            strength: strength.weight(),
+        })
+    }
+
+    /// Add `constraints` to the solver, returning the ids of those that fit.
+    fn add_all<'a>(
+        &self,
+        solver: &mut Solver,
+        constraints: impl IntoIterator<Item = &'a Constraint>,
+    ) -> Vec<ConstraintId> {
+        // required constraints are added first, so that a preference can
+        // never cause a requirement to be rejected.
+        let mut constraints: Vec<&Constraint> = constraints.into_iter().collect();
+        constraints.sort_by_key(|constraint| std::cmp::Reverse(constraint.strength));
+
+        let mut ids = Vec::with_capacity(constraints.len());
+        for constraint in constraints {
+            let solver_constraint = self.solver_constraint(
+                &constraint.expression,
+                constraint.relation,
+                constraint.strength,
+            );
+            if let Some(solver_constraint) = solver_constraint {
+                match solver.add_constraint(&solver_constraint) {
+                    Ok(id) => ids.push(id),
+                    Err(_) => warn!("ignoring unsatisfiable constraint {:?}", constraint),
+                }
+            }
+        }
+        ids
+    }
+
+    /// The system with the fixed constraints, which hold on every layout pass.
+    fn new_system(&self, bc: &BoxConstraints) -> System {
+        let parent = Item::parent();
+        let mut fixed = vec![
+            parent.left().equals(0.0),
+            parent.top().equals(0.0),
+            parent.width().equals(0.0).with_strength(Strength::Weak),
+            parent.height().equals(0.0).with_strength(Strength::Weak),
+        ];
+        for (name, _) in &self.children {
+            let item = Item::child(name.clone());
+            fixed.push(item.width().at_least(0.0));
+            fixed.push(item.height().at_least(0.0));
+        }
+        let mut solver = Solver::new();
+        let bc_constraints = self.add_all(&mut solver, &box_constraints(bc));
+        self.add_all(&mut solver, fixed.iter().chain(&self.constraints));
+        System {
+            solver,
+            bc: *bc,
+            bc_constraints,
+            dynamic: Vec::new(),
+            dynamic_constraints: Vec::new(),
+        }
+    }
+}
+
+/// The constraints that keep the layout's size within `bc`.
+fn box_constraints(bc: &BoxConstraints) -> Vec<Constraint> {
+    let parent = Item::parent();
+    let mut constraints = vec![
+        parent.width().at_least(bc.min().width),
+        parent.height().at_least(bc.min().height),
+    ];
+    if bc.max().width.is_finite() {
+        constraints.push(parent.width().at_most(bc.max().width));
+    }
+    if bc.max().height.is_finite() {
+        constraints.push(parent.height().at_most(bc.max().height));
+    }
+    constraints
+}
+
+impl<T: Data> Default for ConstraintLayout<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for ConstraintLayout<T> {
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for (_, child) in &mut self.children {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for (_, child) in &mut self.children {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if self.dynamic_constraints.is_some() && !old_data.same(data) {
+            ctx.request_layout();
+        }
+        for (_, child) in &mut self.children {
+            child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("ConstraintLayout");
+
+        let mut system = match self.system.take() {
+            Some(system) => system,
+            None => self.new_system(bc),
+        };
+        if system.bc != *bc {
+            for id in system.bc_constraints.drain(..) {
+                system.solver.remove_constraint(id);
+            }
+            system.bc_constraints = self.add_all(&mut system.solver, &box_constraints(bc));
+            system.bc = *bc;
+        }
+
+        // measure the children, so they can prefer their natural size.
+        let child_bc = bc.loosen();
+        for (idx, (_, child)) in self.children.iter_mut().enumerate() {
+            let size = child.layout(ctx, &child_bc, data, env);
+            let base = (idx + 1) * FIELDS;
+            for &(field, value) in &[(Field::Width, size.width), (Field::Height, size.height)] {
+                let var = base + field as usize;
+                let solver = &mut system.solver;
+                if value.is_finite() {
+                    if !solver.has_edit_variable(var) {
+                        solver.add_edit_variable(var, Strength::Medium.weight());
+                    }
+                    solver.suggest_value(var, value);
+                } else {
+                    solver.remove_edit_variable(var);
+                }
+            }
+        }
+
+        let dynamic = self
+            .dynamic_constraints
+            .as_ref()
+            .map(|constraints| constraints(data, env))
+            .unwrap_or_default();
+        if dynamic != system.dynamic {
+            for id in system.dynamic_constraints.drain(..) {
+                system.solver.remove_constraint(id);
+            }
+            system.dynamic_constraints = self.add_all(&mut system.solver, &dynamic);
+            system.dynamic = dynamic;
+        }
+        let solver = &system.solver;
+
+        let mut child_paint_rect = Rect::ZERO;
+        for (idx, (_, child)) in self.children.iter_mut().enumerate() {
+            let base = (idx + 1) * FIELDS;
+            let origin = Point::new(
+                solver.value(base + Field::Left as usize),
+                solver.value(base + Field::Top as usize),
+            );
+            let size = Size::new(
+                solver.value(base + Field::Width as usize).max(0.0),
+                solver.value(base + Field::Height as usize).max(0.0),
+            );
+            child.layout(ctx, &BoxConstraints::tight(size), data, env);
+            child.set_origin(ctx, data, env, origin);
+            child_paint_rect = child_paint_rect.union(child.paint_rect());
+        }
+
+        let my_size = bc.constrain(Size::new(
+            solver.value(Field::Width as usize),
+            solver.value(Field::Height as usize),
+        ));
+        self.system = Some(system);
+        let insets = child_paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed layout: size={}, insets={:?}", my_size, insets);
+        my_size
+    }
+
+    #[instrument(name = "ConstraintLayout", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for (_, child) in &mut self.children {
+            child.paint(ctx, data, env);
+        }
+    }
+}
@@ -14,20 +14,104 @@
 
 //! A widget that provides simple visual styling options to a child.
 
+use super::blur::{blur_layer, blur_margin, blur_spread};
 use super::BackgroundBrush;
+use crate::core::Layer;
+use crate::piet::InterpolationMode;
 use crate::widget::prelude::*;
-use crate::{Color, Data, KeyOrValue, Point, WidgetPod};
-use tracing::{instrument, trace, trace_span};
+use crate::{Color, Data, Insets, KeyOrValue, Point, Vec2, WidgetPod};
+use tracing::{instrument, trace, trace_span, warn};
 
 struct BorderStyle {
     width: KeyOrValue<f64>,
     color: KeyOrValue<Color>,
 }
 
+struct ShadowStyle {
+    color: KeyOrValue<Color>,
+    blur_radius: KeyOrValue<f64>,
+    offset: KeyOrValue<Vec2>,
+    /// The blurred image of a rounded shadow, what it was painted for, and
+    /// how far it extends past the container.
+    rounded: Option<(RoundedShadow, Layer, f64)>,
+}
+
+/// The inputs of a rounded shadow's image.
+#[derive(PartialEq)]
+struct RoundedShadow {
+    size: Size,
+    corner_radius: f64,
+    blur_radius: f64,
+    color: Color,
+    scale: f64,
+}
+
+impl ShadowStyle {
+    /// How far the shadow reaches past each edge of the container.
+    fn insets(&self, env: &Env) -> Insets {
+        let reach = blur_margin(self.blur_radius.resolve(env));
+        let offset = self.offset.resolve(env);
+        Insets::new(
+            (reach - offset.x).max(0.0),
+            (reach - offset.y).max(0.0),
+            (reach + offset.x).max(0.0),
+            (reach + offset.y).max(0.0),
+        )
+    }
+
+    /// Paint the shadow of a container with the given corner radius.
+    fn paint(&mut self, ctx: &mut PaintCtx, corner_radius: f64, env: &Env) {
+        let rect = ctx.size().to_rect() + self.offset.resolve(env);
+        let blur_radius = self.blur_radius.resolve(env);
+        let color = self.color.resolve(env);
+        if corner_radius <= 0.0 {
+            ctx.blurred_rect(rect, blur_radius, &color);
+            return;
+        }
+
+        // `blurred_rect` only has square corners, so the rounded shadow is
+        // blurred offscreen, and kept until its size or style changes.
+        let key = RoundedShadow {
+            size: ctx.size(),
+            corner_radius,
+            blur_radius,
+            color,
+            scale: ctx.layer_scale(),
+        };
+        let (image, spread) = match &mut self.rounded {
+            Some((painted, layer, spread)) if *painted == key => (layer.image(ctx), *spread),
+            rounded => {
+                let shape = key.size.to_rounded_rect(corner_radius);
+                let color = key.color.clone();
+                let buf =
+                    match ctx.paint_to_image(key.size, key.scale, |ctx| ctx.fill(shape, &color)) {
+                        Ok(buf) => buf,
+                        Err(e) => {
+                            warn!("failed to paint a rounded shadow: {}", e);
+                            ctx.blurred_rect(rect, blur_radius, &key.color);
+                            return;
+                        }
+                    };
+                let blurred = blur_layer(&buf, key.scale, blur_radius);
+                let spread = blur_spread(&blurred, &buf, key.scale);
+                let layer = Layer::new(blurred, key.scale);
+                let (_, layer, _) = rounded.insert((key, layer, spread));
+                (layer.image(ctx), spread)
+            }
+        };
+        ctx.draw_image(
+            &image,
+            rect.inflate(spread, spread),
+            InterpolationMode::Bilinear,
+        );
+    }
+}
+
 /// A widget that provides simple visual styling options to a child.
 pub struct Container<T> {
     background: Option<BackgroundBrush<T>>,
     border: Option<BorderStyle>,
+    shadow: Option<ShadowStyle>,
     corner_radius: KeyOrValue<f64>,
 
     inner: WidgetPod<T, Box<dyn Widget<T>>>,
@@ -39,6 +123,7 @@ impl<T: Data> Container<T> {
         Self {
             background: None,
             border: None,
+            shadow: None,
             corner_radius: 0.0.into(),
             inner: WidgetPod::new(inner).boxed(),
         }
@@ -117,6 +202,61 @@ impl<T: Data> Container<T> {
         self.border = None;
     }
 
+    /// Builder-style method for painting a drop shadow below the widget.
+    ///
+    /// Arguments can be either concrete values, or a [`Key`] of the respective
+    /// type; the theme provides [`SHADOW_COLOR`], [`SHADOW_BLUR_RADIUS`] and
+    /// [`SHADOW_OFFSET`]:
+    ///
+    /// ```
+    /// use druid::widget::{Container, Label};
+    /// use druid::theme;
+    ///
+    /// let card = Container::new(Label::<()>::new("A card"))
+    ///     .background(theme::BACKGROUND_LIGHT)
+    ///     .rounded(4.0)
+    ///     .shadow(theme::SHADOW_COLOR, theme::SHADOW_BLUR_RADIUS, theme::SHADOW_OFFSET);
+    /// ```
+    ///
+    /// [`Key`]: crate::Key
+    /// [`SHADOW_COLOR`]: crate::theme::SHADOW_COLOR
+    /// [`SHADOW_BLUR_RADIUS`]: crate::theme::SHADOW_BLUR_RADIUS
+    /// [`SHADOW_OFFSET`]: crate::theme::SHADOW_OFFSET
+    pub fn shadow(
+        mut self,
+        color: impl Into<KeyOrValue<Color>>,
+        blur_radius: impl Into<KeyOrValue<f64>>,
+        offset: impl Into<KeyOrValue<Vec2>>,
+    ) -> Self {
+        self.set_shadow(color, blur_radius, offset);
+        self
+    }
+
+    /// Paint a drop shadow below the widget.
+    ///
+    /// Arguments can be either concrete values, or a [`Key`] of the respective
+    /// type.
+    ///
+    /// [`Key`]: crate::Key
+    pub fn set_shadow(
+        &mut self,
+        color: impl Into<KeyOrValue<Color>>,
+        blur_radius: impl Into<KeyOrValue<f64>>,
+        offset: impl Into<KeyOrValue<Vec2>>,
+    ) {
+        self.shadow = Some(ShadowStyle {
+            color: color.into(),
+            blur_radius: blur_radius.into(),
+            offset: offset.into(),
+            rounded: None,
+        });
+    }
+
+    /// Clears the drop shadow.
+    pub fn clear_shadow(&mut self) {
+        self.shadow = None;
+    }
+
     /// Builder style method for rounding off corners of this container by setting a corner radius
     pub fn rounded(mut self, radius: impl Into<KeyOrValue<f64>>) -> Self {
         self.set_rounded(radius);
@@ -183,7 +323,16 @@ impl<T: Data> Widget<T> for Container<T> {
             size.height + 2.0 * border_width,
         );
 
-        let my_insets = self.inner.compute_parent_paint_insets(my_size);
+        let mut my_insets = self.inner.compute_parent_paint_insets(my_size);
+        if let Some(shadow) = &self.shadow {
+            let shadow_insets = shadow.insets(env);
+            my_insets = Insets::new(
+                my_insets.x0.max(shadow_insets.x0),
+                my_insets.y0.max(shadow_insets.y0),
+                my_insets.x1.max(shadow_insets.x1),
+                my_insets.y1.max(shadow_insets.y1),
+            );
+        }
         ctx.set_paint_insets(my_insets);
         trace!("Computed layout: size={}, insets={:?}", my_size, my_insets);
         my_size
@@ -193,6 +342,10 @@ impl<T: Data> Widget<T> for Container<T> {
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         let corner_radius = self.corner_radius.resolve(env);
 
+        if let Some(shadow) = &mut self.shadow {
+            shadow.paint(ctx, corner_radius, env);
+        }
+
         if let Some(background) = self.background.as_mut() {
             let panel = ctx.size().to_rounded_rect(corner_radius);
 
@@ -206,11 +359,8 @@ impl<T: Data> Widget<T> for Container<T> {
 
         if let Some(border) = &self.border {
             let border_width = border.width.resolve(env);
-            let border_rect = ctx
-                .size()
-                .to_rect()
-                .inset(border_width / -2.0)
-                .to_rounded_rect(corner_radius);
+            let border_rect = ctx.size().to_rect().inset(border_width / -2.0);
+            let border_rect = border_rect.to_rounded_rect(corner_radius);
             ctx.stroke(border_rect, &border.color.resolve(env), border_width);
         };
 
@@ -0,0 +1,263 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [`Controller`]s for dragging values between widgets.
+
+use std::any::Any;
+use std::path::PathBuf;
+
+use crate::commands::DRAG_ENDED;
+use crate::kurbo::{Point, Size};
+use crate::piet::RenderContext;
+use crate::widget::Controller;
+use crate::{theme, Data, DragContents, DragImage, Env, Event, EventCtx, MouseButton, Widget};
+use tracing::{instrument, trace};
+
+type PayloadFn<T> = dyn Fn(&T, &Env) -> DragContents;
+type ImageFn<T> = dyn Fn(&T, Size, &Env) -> DragImage;
+type DragEndFn<T> = dyn Fn(&mut EventCtx, bool, &mut T, &Env);
+type DropFn<T> = dyn Fn(&mut EventCtx, &DragContents, Point, &mut T, &Env);
+type HoverFn<T> = dyn Fn(&mut EventCtx, Option<Point>, &mut T, &Env);
+
+/// A [`Controller`] that lets its child be dragged.
+///
+/// When the left mouse button is pressed on the child and the mouse then
+/// moves further than [`theme::DRAG_THRESHOLD`], a drag starts, carrying the
+/// value produced by the closure passed to [`new`]. An image follows the
+/// cursor until the button is released over a [`DropTarget`] that accepts
+/// the value, or anywhere else to cancel the drag; Escape also cancels it.
+///
+/// By default the image is a translucent rectangle of the size of the child;
+/// use [`with_image`] to paint something else.
+///
+/// This is available as a `draggable` method via [`WidgetExt`].
+///
+/// [`new`]: Draggable::new
+/// [`with_image`]: Draggable::with_image
+/// [`theme::DRAG_THRESHOLD`]: crate::theme::DRAG_THRESHOLD
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct Draggable<T> {
+    payload: Box<PayloadFn<T>>,
+    image: Option<Box<ImageFn<T>>>,
+    on_end: Option<Box<DragEndFn<T>>>,
+    /// Where the button went down, while it is held.
+    press: Option<Point>,
+    dragging: bool,
+}
+
+/// A [`Controller`] that accepts values of one type dropped on its child.
+///
+/// The closure passed to [`new`] is called with the value and the position
+/// of the drop. While a drag carrying a value of the right type is over the
+/// child, the closure passed to [`on_hover`] is called with the position of
+/// the drag, and with `None` when the drag leaves; this is the place to
+/// update the data so that the child can highlight itself.
+///
+/// Use [`files`] for a target that takes files dragged from other
+/// applications.
+///
+/// The child gets the drag events first, and can handle them itself.
+///
+/// This is available as `on_drop` and `on_file_drop` methods via
+/// [`WidgetExt`].
+///
+/// [`new`]: DropTarget::new
+/// [`on_hover`]: DropTarget::on_hover
+/// [`files`]: DropTarget::files
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct DropTarget<T> {
+    accepts: fn(&DragContents) -> bool,
+    on_drop: Box<DropFn<T>>,
+    on_hover: Option<Box<HoverFn<T>>>,
+    hovering: bool,
+}
+
+impl<T: Data> Draggable<T> {
+    /// Create a new `Draggable` that drags the value returned by `payload`.
+    pub fn new<P: Any>(payload: impl Fn(&T, &Env) -> P + 'static) -> Self {
+        Draggable {
+            payload: Box::new(move |data, env| DragContents::new(payload(data, env))),
+            image: None,
+            on_end: None,
+            press: None,
+            dragging: false,
+        }
+    }
+
+    /// Builder-style method to set the image that follows the cursor.
+    ///
+    /// The closure is called with the data and the size of the child when
+    /// the drag starts.
+    pub fn with_image(mut self, image: impl Fn(&T, Size, &Env) -> DragImage + 'static) -> Self {
+        self.image = Some(Box::new(image));
+        self
+    }
+
+    /// Builder-style method to set a closure that is called when the drag
+    /// ends, with `true` if the value was dropped on a widget that took it.
+    ///
+    /// This is where a value that was moved rather than copied is removed.
+    pub fn on_drag_end(
+        mut self,
+        on_end: impl Fn(&mut EventCtx, bool, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_end = Some(Box::new(on_end));
+        self
+    }
+
+    fn image(&self, data: &T, size: Size, press: Point, env: &Env) -> DragImage {
+        match &self.image {
+            Some(image) => image(data, size, env),
+            None => DragImage::new(size, move |ctx, env| {
+                let rect = size
+                    .to_rect()
+                    .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+                let color = env.get(theme::PRIMARY_LIGHT).with_alpha(0.5);
+                ctx.fill(rect, &color);
+            })
+            .with_hotspot(press),
+        }
+    }
+}
+
+impl<T: Data> DropTarget<T> {
+    /// Create a new `DropTarget` that accepts values of type `P`.
+    pub fn new<P: Any>(on_drop: impl Fn(&mut EventCtx, &P, Point, &mut T, &Env) + 'static) -> Self {
+        DropTarget {
+            accepts: DragContents::is::<P>,
+            on_drop: Box::new(move |ctx, contents, pos, data, env| {
+                if let Some(value) = contents.get() {
+                    on_drop(ctx, value, pos, data, env);
+                }
+            }),
+            on_hover: None,
+            hovering: false,
+        }
+    }
+
+    /// Create a new `DropTarget` that accepts files dragged from other
+    /// applications.
+    pub fn files(
+        on_drop: impl Fn(&mut EventCtx, &[PathBuf], Point, &mut T, &Env) + 'static,
+    ) -> Self {
+        DropTarget {
+            accepts: |contents| !contents.files().is_empty(),
+            on_drop: Box::new(move |ctx, contents, pos, data, env| {
+                on_drop(ctx, contents.files(), pos, data, env)
+            }),
+            on_hover: None,
+            hovering: false,
+        }
+    }
+
+    /// Builder-style method to set a closure that is called while an
+    /// acceptable drag is over the child.
+    pub fn on_hover(
+        mut self,
+        on_hover: impl Fn(&mut EventCtx, Option<Point>, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
+    fn hover(&mut self, ctx: &mut EventCtx, pos: Option<Point>, data: &mut T, env: &Env) {
+        if pos.is_none() && !self.hovering {
+            return;
+        }
+        self.hovering = pos.is_some();
+        if let Some(on_hover) = &self.on_hover {
+            on_hover(ctx, pos, data, env);
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Draggable<T> {
+    #[instrument(
+        name = "Draggable",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left && !ctx.is_disabled() => {
+                self.press = Some(mouse.pos);
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) if !self.dragging => {
+                if let Some(press) = self.press {
+                    if (mouse.pos - press).hypot() > env.get(theme::DRAG_THRESHOLD) {
+                        trace!("Widget {:?} starts a drag", ctx.widget_id());
+                        let contents = (self.payload)(data, env);
+                        let image = self.image(data, ctx.size(), press, env);
+                        ctx.start_drag(contents, image);
+                        self.dragging = true;
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && self.press.is_some() => {
+                self.press = None;
+                ctx.set_active(false);
+            }
+            Event::Command(cmd) if cmd.is(DRAG_ENDED) => {
+                let accepted = *cmd.get_unchecked(DRAG_ENDED);
+                trace!(
+                    "Drag from {:?} ended, accepted: {}",
+                    ctx.widget_id(),
+                    accepted
+                );
+                self.dragging = false;
+                if let Some(on_end) = &self.on_end {
+                    on_end(ctx, accepted, data, env);
+                }
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for DropTarget<T> {
+    #[instrument(
+        name = "DropTarget",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        child.event(ctx, event, data, env);
+
+        match event {
+            Event::DragEnter(drag) | Event::DragOver(drag) => {
+                if !ctx.is_handled() && (self.accepts)(&drag.contents) {
+                    self.hover(ctx, Some(drag.pos), data, env);
+                    ctx.set_handled();
+                } else {
+                    self.hover(ctx, None, data, env);
+                }
+            }
+            Event::Drop(drag) => {
+                if !ctx.is_handled() && (self.accepts)(&drag.contents) {
+                    trace!("Widget {:?} took a drop", ctx.widget_id());
+                    (self.on_drop)(ctx, &drag.contents, drag.pos, data, env);
+                    ctx.set_handled();
+                }
+                self.hover(ctx, None, data, env);
+            }
+            Event::DragLeave => self.hover(ctx, None, data, env),
+            _ => (),
+        }
+    }
+}
@@ -14,6 +14,7 @@
 
 //! A widget that switches dynamically between two child views.
 
+use crate::animation::{Animation, Transition};
 use crate::widget::prelude::*;
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
@@ -24,6 +25,10 @@ pub struct Either<T> {
     true_branch: WidgetPod<T, Box<dyn Widget<T>>>,
     false_branch: WidgetPod<T, Box<dyn Widget<T>>>,
     current: bool,
+    transition: Option<Transition>,
+    /// The running transition from the other branch, and the data that
+    /// branch was last shown.
+    animation: Option<(Animation, T)>,
 }
 
 impl<T> Either<T> {
@@ -41,13 +46,31 @@ impl<T> Either<T> {
             true_branch: WidgetPod::new(true_branch).boxed(),
             false_branch: WidgetPod::new(false_branch).boxed(),
             current: false,
+            transition: None,
+            animation: None,
         }
     }
+
+    /// Builder-style method to animate switching between the views.
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
 }
 
 impl<T: Data> Widget<T> for Either<T> {
     #[instrument(name = "Either", level = "trace", skip(self, ctx, event, data, env), fields(branch = self.current))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let (Event::AnimFrame(interval), Some((animation, _))) = (event, self.animation.as_mut())
+        {
+            if animation.advance(*interval) {
+                ctx.request_anim_frame();
+            } else {
+                self.animation = None;
+            }
+            ctx.request_paint();
+        }
+
         if event.should_propagate_to_hidden() {
             self.true_branch.event(ctx, event, data, env);
             self.false_branch.event(ctx, event, data, env);
@@ -70,12 +93,16 @@ impl<T: Data> Widget<T> for Either<T> {
         }
     }
 
-    #[instrument(name = "Either", level = "trace", skip(self, ctx, _old_data, data, env), fields(branch = self.current))]
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+    #[instrument(name = "Either", level = "trace", skip(self, ctx, old_data, data, env), fields(branch = self.current))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         let current = (self.closure)(data, env);
         if current != self.current {
             self.current = current;
             ctx.children_changed();
+            if let Some(transition) = &self.transition {
+                self.animation = Some((transition.start(), old_data.clone()));
+                ctx.request_anim_frame();
+            }
         }
         self.current_widget().update(ctx, data, env)
     }
@@ -91,7 +118,18 @@ impl<T: Data> Widget<T> for Either<T> {
 
     #[instrument(name = "Either", level = "trace", skip(self, ctx, data, env), fields(branch = self.current))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        self.current_widget().paint(ctx, data, env)
+        if let (Some(transition), Some((animation, old_data))) = (&self.transition, &self.animation)
+        {
+            let (old, new) = if self.current {
+                (&mut self.false_branch, &mut self.true_branch)
+            } else {
+                (&mut self.true_branch, &mut self.false_branch)
+            };
+            let progress = animation.progress();
+            transition.paint(ctx, progress, (old, old_data), new, data, env);
+        } else {
+            self.current_widget().paint(ctx, data, env)
+        }
     }
 }
 
@@ -0,0 +1,276 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A controller that keeps part of the data in sync with an outside source.
+
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{Lens, Selector, SingleUse, Target};
+
+use tracing::{trace, warn};
+
+type PushFn<U> = Box<dyn Fn(&U)>;
+
+/// How often the forwarding thread checks whether the controller is still around,
+/// while it waits for a value.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Delivers a value from the outside source to the controller.
+const RECEIVED: Selector<SingleUse<Box<dyn Any + Send>>> =
+    Selector::new("druid-builtin.external-sync-received");
+
+/// A [`Controller`] that keeps the part of the data that a lens focuses on in
+/// sync with a source outside of druid, such as a thread that owns the real
+/// state.
+///
+/// Values from the source come in through the receiving end of a
+/// [`std::sync::mpsc`] channel. They are applied to the data on the UI thread,
+/// through an [`ExtEventSink`]. Changes made in the UI are handed to the `push`
+/// closure, which can send them back to the source.
+///
+/// Values that came from the source are not pushed back to it, so the two
+/// sides don't echo each other's changes forever. The source should likewise
+/// not send back a value it was pushed.
+///
+/// The values are received until the sending end is dropped, or until the
+/// widget is removed.
+///
+/// ```
+/// use std::sync::mpsc;
+/// use druid::widget::{ExternalSync, Slider};
+/// use druid::{lens, WidgetExt};
+///
+/// // the engine thread keeps `to_ui` and `from_ui`
+/// let (to_ui, from_engine) = mpsc::channel::<f64>();
+/// let (to_engine, from_ui) = mpsc::channel::<f64>();
+///
+/// let volume = Slider::new().controller(ExternalSync::new(
+///     lens::Identity,
+///     from_engine,
+///     move |volume: &f64| {
+///         let _ = to_engine.send(*volume);
+///     },
+/// ));
+/// ```
+///
+/// [`ExtEventSink`]: crate::ExtEventSink
+pub struct ExternalSync<U, L> {
+    lens: L,
+    /// Taken by the thread that forwards the values, once we are added.
+    incoming: Option<Receiver<U>>,
+    push: PushFn<U>,
+    /// A value from the source that changed the data, which is not pushed back
+    /// by the update that follows.
+    received: Option<U>,
+    /// Tells the forwarding thread to stop when we are dropped.
+    alive: Arc<AtomicBool>,
+}
+
+impl<U: Data + Send, L> ExternalSync<U, L> {
+    /// Create a controller that applies the values from `incoming` to the
+    /// part of the data that `lens` focuses on, and calls `push` with that
+    /// part whenever it changes in the UI.
+    pub fn new(lens: L, incoming: Receiver<U>, push: impl Fn(&U) + 'static) -> Self {
+        ExternalSync {
+            lens,
+            incoming: Some(incoming),
+            push: Box::new(push),
+            received: None,
+            alive: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    fn start(&mut self, ctx: &mut LifeCycleCtx) {
+        let incoming = match self.incoming.take() {
+            Some(incoming) => incoming,
+            None => return,
+        };
+        let sink = ctx.get_external_handle();
+        let target = Target::Widget(ctx.widget_id());
+        let alive = self.alive.clone();
+        std::thread::spawn(move || {
+            // Waking up now and then lets the thread notice that we were dropped, even if the
+            // source never sends again.
+            while alive.load(Ordering::SeqCst) {
+                let value = match incoming.recv_timeout(POLL_INTERVAL) {
+                    Ok(value) => value,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                let value: Box<dyn Any + Send> = Box::new(value);
+                if sink
+                    .submit_command(RECEIVED, SingleUse::new(value), target)
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            trace!("ExternalSync: stopped receiving");
+        });
+    }
+}
+
+impl<U, L> Drop for ExternalSync<U, L> {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::SeqCst);
+    }
+}
+
+impl<T: Data, U: Data + Send, L: Lens<T, U>, W: Widget<T>> Controller<T, W> for ExternalSync<U, L> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(value) = cmd.get(RECEIVED).and_then(SingleUse::take) {
+                match value.downcast::<U>() {
+                    Ok(value) => {
+                        let changed = self.lens.with_mut(data, |current| {
+                            if current.same(&value) {
+                                return false;
+                            }
+                            *current = (*value).clone();
+                            true
+                        });
+                        // A value that didn't change anything doesn't cause an update, so
+                        // it mustn't be left for a later one.
+                        if changed {
+                            self.received = Some(*value);
+                        }
+                    }
+                    Err(_) => warn!("ExternalSync: received a value of the wrong type"),
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.start(ctx);
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let lens = &self.lens;
+        let received = self.received.take();
+        let push = &self.push;
+        lens.with(old_data, |old| {
+            lens.with(data, |new| {
+                let from_source = matches!(&received, Some(received) if received.same(new));
+                if !old.same(new) && !from_source {
+                    push(new);
+                }
+            })
+        });
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::{lens, WidgetExt};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+    use std::time::Instant;
+    use test_env_log::test;
+
+    const SET: Selector<f64> = Selector::new("druid-test.external-sync-set");
+
+    /// A widget that sets the data to the value of a `SET` command, like a UI change.
+    fn setter() -> impl Widget<f64> {
+        ModularWidget::new(()).event_fn(|_, ctx, event, data: &mut f64, _| {
+            if let Event::Command(cmd) = event {
+                if let Some(value) = cmd.get(SET) {
+                    *data = *value;
+                    ctx.set_handled();
+                }
+            }
+        })
+    }
+
+    fn receive(harness: &mut Harness<f64>, id: WidgetId, value: f64) {
+        let value: Box<dyn Any + Send> = Box::new(value);
+        harness.submit_command(RECEIVED.with(SingleUse::new(value)).to(id));
+    }
+
+    #[test]
+    fn received_values_are_not_pushed_back() {
+        let id = WidgetId::next();
+        let pushed = Rc::new(RefCell::new(Vec::new()));
+        let (_to_ui, from_source) = mpsc::channel();
+        let widget = setter()
+            .controller(ExternalSync::new(lens::Identity, from_source, {
+                let pushed = pushed.clone();
+                move |value: &f64| pushed.borrow_mut().push(*value)
+            }))
+            .with_id(id);
+
+        Harness::create_simple(0.0, widget, |harness| {
+            harness.send_initial_events();
+
+            receive(harness, id, 1.0);
+            assert_eq!(*harness.data(), 1.0);
+            assert!(pushed.borrow().is_empty());
+
+            harness.submit_command(SET.with(2.0).to(id));
+            // returning to the value that came from the source is a change of its own
+            harness.submit_command(SET.with(1.0).to(id));
+            assert_eq!(*pushed.borrow(), vec![2.0, 1.0]);
+
+            // a value that is already there doesn't hold back a later change
+            receive(harness, id, 1.0);
+            harness.submit_command(SET.with(3.0).to(id));
+            harness.submit_command(SET.with(1.0).to(id));
+            assert_eq!(*pushed.borrow(), vec![2.0, 1.0, 3.0, 1.0]);
+        });
+    }
+
+    #[test]
+    fn thread_stops_when_dropped() {
+        let (to_ui, from_source) = mpsc::channel();
+        let widget = setter().controller(ExternalSync::new(lens::Identity, from_source, |_| ()));
+
+        Harness::create_simple(0.0, widget, |harness| {
+            harness.send_initial_events();
+        });
+
+        // the thread drops the receiver once it notices, without anything being sent
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while to_ui.send(0.0).is_ok() {
+            assert!(
+                Instant::now() < deadline,
+                "the forwarding thread didn't stop"
+            );
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
@@ -16,7 +16,7 @@
 
 use crate::kurbo::{common::FloatExt, Vec2};
 use crate::widget::prelude::*;
-use crate::{Data, KeyOrValue, Point, Rect, WidgetPod};
+use crate::{theme, Data, KeyOrValue, Point, Rect, WidgetPod};
 use tracing::{instrument, trace};
 
 /// A container with either horizontal or vertical layout.
@@ -95,6 +95,11 @@ use tracing::{instrument, trace};
 /// If this is `true`, then the container must fill the available space on that
 /// axis; otherwise it may be smaller if its children are smaller.
 ///
+/// - [`wrap`] lets children that don't fit on the main axis flow onto
+/// additional lines, like CSS's `flex-wrap`. Each line is laid out on its own,
+/// and the [`line_alignment`] determines how the lines are positioned on the
+/// cross axis.
+///
 /// Additional options can be set (or overridden) in the [`FlexParams`].
 ///
 /// # Examples
@@ -129,6 +134,8 @@ use tracing::{instrument, trace};
 /// [`MainAxisAlignment`]: enum.MainAxisAlignment.html
 /// [`CrossAxisAlignment`]: enum.CrossAxisAlignment.html
 /// [`must_fill_main_axis`]: struct.Flex.html#method.must_fill_main_axis
+/// [`wrap`]: Flex::wrap
+/// [`line_alignment`]: Flex::line_alignment
 /// [`FlexParams`]: struct.FlexParams.html
 /// [`WidgetExt`]: ../trait.WidgetExt.html
 /// [`expand_height`]: ../trait.WidgetExt.html#method.expand_height
@@ -140,6 +147,9 @@ pub struct Flex<T> {
     cross_alignment: CrossAxisAlignment,
     main_alignment: MainAxisAlignment,
     fill_major_axis: bool,
+    wrap: bool,
+    line_alignment: MainAxisAlignment,
+    child_spacing: KeyOrValue<f64>,
     children: Vec<Child<T>>,
 }
 
@@ -374,13 +384,18 @@ impl<T: Data> Flex<T> {
             cross_alignment: CrossAxisAlignment::Center,
             main_alignment: MainAxisAlignment::Start,
             fill_major_axis: false,
+            wrap: false,
+            line_alignment: MainAxisAlignment::Start,
+            child_spacing: theme::FLEX_CHILD_SPACING.into(),
         }
     }
 
     /// Create a new horizontal stack.
     ///
-    /// The child widgets are laid out horizontally, from left to right.
+    /// The child widgets are laid out horizontally, from left to right;
+    /// or from right to left if [`theme::LAYOUT_RTL`] is set.
     ///
+    /// [`theme::LAYOUT_RTL`]: crate::theme::LAYOUT_RTL
     pub fn row() -> Self {
         Self::for_axis(Axis::Horizontal)
     }
@@ -427,6 +442,94 @@ impl<T: Data> Flex<T> {
         self
     }
 
+    /// Builder-style method to make children that overflow the main axis
+    /// wrap onto additional lines.
+    ///
+    /// Children are placed on a line until the next one would not fit in the
+    /// container's maximum size on the main axis, and then a new line is
+    /// started. Each line is laid out like an unwrapped `Flex`: flex children
+    /// share the space left over on their own line, and the
+    /// [`MainAxisAlignment`] and [`CrossAxisAlignment`] are applied within it.
+    ///
+    /// In a wrapping container, no child is larger than a line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Button, Flex, MainAxisAlignment};
+    ///
+    /// let mut tags = Flex::<()>::row()
+    ///     .wrap()
+    ///     .line_alignment(MainAxisAlignment::Center);
+    /// for tag in &["rust", "gui", "druid", "data-oriented"] {
+    ///     tags.add_child(Button::new(*tag));
+    ///     tags.add_default_spacer();
+    /// }
+    /// ```
+    pub fn wrap(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    /// Builder-style method for specifying how lines are positioned on the
+    /// cross axis, when the container [wraps] and there is more space on that
+    /// axis than the lines need.
+    ///
+    /// The variants of [`MainAxisAlignment`] have the same meaning here, applied
+    /// to lines instead of children. The default is `MainAxisAlignment::Start`.
+    ///
+    /// [wraps]: Flex::wrap
+    pub fn line_alignment(mut self, alignment: MainAxisAlignment) -> Self {
+        self.line_alignment = alignment;
+        self
+    }
+
+    /// Builder-style method to put a gap of `spacing` between every pair of
+    /// adjacent children.
+    ///
+    /// The gap is only placed between children, not before the first or
+    /// after the last one; a spacer adds to the gap between the children on
+    /// either side of it. In a container that [wraps], the same gap is also
+    /// placed between lines.
+    ///
+    /// Without a call to this method, the gap is [`theme::FLEX_CHILD_SPACING`],
+    /// which is `0.0` unless the theme changes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Button, Flex};
+    ///
+    /// let buttons = Flex::<()>::row()
+    ///     .with_child_spacing(8.0)
+    ///     .with_child(Button::new("Back"))
+    ///     .with_child(Button::new("Next"))
+    ///     .with_child(Button::new("Finish"));
+    /// ```
+    ///
+    /// [wraps]: Flex::wrap
+    /// [`theme::FLEX_CHILD_SPACING`]: crate::theme::FLEX_CHILD_SPACING
+    pub fn with_child_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_child_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method to put a gap of the standard size between every
+    /// pair of adjacent children.
+    ///
+    /// The size of the gap is the same as a [default spacer], which depends on
+    /// whether this container is a row or column, and on the theme.
+    ///
+    /// [default spacer]: Flex::add_default_spacer
+    pub fn with_default_child_spacing(mut self) -> Self {
+        let key = match self.direction {
+            Axis::Vertical => crate::theme::WIDGET_PADDING_VERTICAL,
+            Axis::Horizontal => crate::theme::WIDGET_PADDING_HORIZONTAL,
+        };
+        self.set_child_spacing(key);
+        self
+    }
+
     /// Builder-style variant of `add_child`.
     ///
     /// Convenient for assembling a group of widgets in a single expression.
@@ -516,6 +619,31 @@ impl<T: Data> Flex<T> {
         self.fill_major_axis = fill;
     }
 
+    /// Set whether children that overflow the main axis wrap onto additional
+    /// lines.
+    ///
+    /// See [`wrap`] for details.
+    ///
+    /// [`wrap`]: Flex::wrap
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Set how lines are positioned on the cross axis, when the container
+    /// wraps.
+    pub fn set_line_alignment(&mut self, alignment: MainAxisAlignment) {
+        self.line_alignment = alignment;
+    }
+
+    /// Set the gap between every pair of adjacent children.
+    ///
+    /// See [`with_child_spacing`] for details.
+    ///
+    /// [`with_child_spacing`]: Flex::with_child_spacing
+    pub fn set_child_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.child_spacing = spacing.into();
+    }
+
     /// Add a non-flex child widget.
     ///
     /// See also [`with_child`].
@@ -624,6 +752,263 @@ impl<T: Data> Flex<T> {
     }
 }
 
+impl<T: Data> Flex<T> {
+    /// Layout when children wrap onto multiple lines.
+    ///
+    /// Children are measured, then broken into lines; each line then
+    /// distributes its leftover space to its flex children and is aligned
+    /// like an unwrapped container.
+    fn layout_wrapped(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let axis = self.direction;
+        let loosened_bc = bc.loosen();
+        let max_major = axis.major(bc.max());
+        // no child is larger than a line.
+        let child_bc = axis.constraints(&loosened_bc, 0.0, max_major);
+
+        // Measure all children, and break them into lines.
+        let gap = self.resolve_child_spacing(env);
+        let mut lines: Vec<WrapLine> = Vec::new();
+        let mut line = WrapLine::starting_at(0);
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let is_widget = child.widget().is_some();
+            let (child_major, child_flex) = match child {
+                Child::Fixed { widget, .. } => {
+                    let child_size = widget.layout(ctx, &child_bc, data, env);
+                    (axis.major(child_size).expand(), 0.0)
+                }
+                Child::Flex { widget, flex, .. } => {
+                    let child_size = widget.layout(ctx, &child_bc, data, env);
+                    (axis.major(child_size).expand(), *flex)
+                }
+                Child::FixedSpacer(kv, calculated_size) => {
+                    *calculated_size = kv.resolve(env).max(0.0);
+                    (*calculated_size, 0.0)
+                }
+                Child::FlexedSpacer(flex, calculated_size) => {
+                    *calculated_size = 0.0;
+                    (0.0, *flex)
+                }
+            };
+            let gap_before = if is_widget && line.has_widget {
+                gap
+            } else {
+                0.0
+            };
+            if !line.range.is_empty() && line.major + gap_before + child_major > max_major {
+                lines.push(line);
+                line = WrapLine::starting_at(i);
+            } else {
+                line.major += gap_before;
+            }
+            line.range.end = i + 1;
+            line.major += child_major;
+            line.flex_sum += child_flex;
+            line.has_widget |= is_widget;
+        }
+        if !line.range.is_empty() {
+            lines.push(line);
+        }
+
+        let any_flex = lines.iter().any(|line| line.flex_sum > 0.0);
+        let container_major = if (self.fill_major_axis || any_flex) && max_major.is_finite() {
+            max_major
+        } else {
+            lines
+                .iter()
+                .map(|line| line.major)
+                .fold(axis.major(bc.min()), f64::max)
+        };
+
+        // Give each line's leftover space to its flex children, and measure
+        // the line on the cross axis.
+        for line in &mut lines {
+            if line.flex_sum > 0.0 {
+                let px_per_flex = (container_major - line.major).max(0.0) / line.flex_sum;
+                let mut remainder: f64 = 0.0;
+                for child in &mut self.children[line.range.clone()] {
+                    match child {
+                        Child::Flex { widget, flex, .. } => {
+                            let old_major = axis.major(widget.layout_rect().size()).expand();
+                            let desired_major = old_major + (*flex) * px_per_flex + remainder;
+                            let actual_major = desired_major.round();
+                            remainder = desired_major - actual_major;
+
+                            let flex_bc = axis.constraints(&loosened_bc, 0.0, actual_major);
+                            let child_size = widget.layout(ctx, &flex_bc, data, env);
+                            line.major += axis.major(child_size).expand() - old_major;
+                        }
+                        Child::FlexedSpacer(flex, calculated_size) => {
+                            let desired_major = (*flex) * px_per_flex + remainder;
+                            *calculated_size = desired_major.round();
+                            remainder = desired_major - *calculated_size;
+                            line.major += *calculated_size;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let mut max_below_baseline = 0f64;
+            let mut any_use_baseline = false;
+            for child in &self.children[line.range.clone()] {
+                if let Child::Fixed { widget, alignment }
+                | Child::Flex {
+                    widget, alignment, ..
+                } = child
+                {
+                    let child_size = widget.layout_rect().size();
+                    let baseline_offset = widget.baseline_offset();
+                    any_use_baseline |=
+                        alignment.unwrap_or(self.cross_alignment) == CrossAxisAlignment::Baseline;
+                    line.minor = line.minor.max(axis.minor(child_size).expand());
+                    line.above_baseline =
+                        line.above_baseline.max(child_size.height - baseline_offset);
+                    max_below_baseline = max_below_baseline.max(baseline_offset);
+                }
+            }
+            if axis == Axis::Horizontal && any_use_baseline {
+                line.minor = line.minor.max(line.above_baseline + max_below_baseline);
+            }
+        }
+
+        // Position the lines, and the children within them.
+        let line_gaps = gap * lines.len().saturating_sub(1) as f64;
+        let lines_minor: f64 = lines.iter().map(|line| line.minor).sum::<f64>() + line_gaps;
+        let extra_minor = (axis.minor(bc.min()) - lines_minor).max(0.0);
+        let mut line_spacing = Spacing::new(self.line_alignment, extra_minor, lines.len());
+        let mut line_minor_offset = line_spacing.next().unwrap_or(0.);
+        let mut origins = Vec::new();
+        let mut last_line_baseline = 0.0;
+
+        for line in &lines {
+            let extra = (container_major - line.major).max(0.0);
+            let mut spacing = Spacing::new(self.main_alignment, extra, line.range.len());
+            let mut major = spacing.next().unwrap_or(0.);
+            let mut after_widget = false;
+
+            for child in &mut self.children[line.range.clone()] {
+                match child {
+                    Child::Fixed { widget, alignment }
+                    | Child::Flex {
+                        widget, alignment, ..
+                    } => {
+                        if after_widget {
+                            major += gap;
+                        }
+                        after_widget = true;
+                        let child_size = widget.layout_rect().size();
+                        let alignment = alignment.unwrap_or(self.cross_alignment);
+                        let child_minor_offset = match alignment {
+                            CrossAxisAlignment::Baseline if axis == Axis::Horizontal => {
+                                let child_baseline = widget.baseline_offset();
+                                line.above_baseline - (child_size.height - child_baseline)
+                            }
+                            CrossAxisAlignment::Fill => {
+                                let fill_size: Size =
+                                    axis.pack(axis.major(child_size), line.minor).into();
+                                widget.layout(ctx, &BoxConstraints::tight(fill_size), data, env);
+                                0.0
+                            }
+                            _ => alignment.align(line.minor - axis.minor(child_size)),
+                        };
+
+                        let child_pos: Point = axis
+                            .pack(major, line_minor_offset + child_minor_offset)
+                            .into();
+                        origins.push(child_pos);
+                        major += axis.major(child_size).expand();
+                        major += spacing.next().unwrap_or(0.);
+                    }
+                    Child::FlexedSpacer(_, calculated_size)
+                    | Child::FixedSpacer(_, calculated_size) => {
+                        major += *calculated_size;
+                    }
+                }
+            }
+            last_line_baseline = line_minor_offset + line.above_baseline;
+            line_minor_offset += line.minor + gap + line_spacing.next().unwrap_or(0.);
+        }
+
+        let my_size = bc.constrain(Size::from(axis.pack(container_major, lines_minor)));
+        self.finish_layout(ctx, data, env, my_size, &origins);
+
+        let baseline_offset = match axis {
+            Axis::Horizontal if !lines.is_empty() => my_size.height - last_line_baseline,
+            Axis::Horizontal => 0.0,
+            Axis::Vertical => self.last_child_baseline(my_size),
+        };
+        ctx.set_baseline_offset(baseline_offset);
+        trace!(
+            "Computed layout: size={}, baseline_offset={}, lines={}",
+            my_size,
+            baseline_offset,
+            lines.len()
+        );
+        my_size
+    }
+
+    /// Place the children at `origins`, which are in the order of the children,
+    /// mirrored in a right-to-left layout, and set the paint insets.
+    fn finish_layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        data: &T,
+        env: &Env,
+        my_size: Size,
+        origins: &[Point],
+    ) {
+        // in a right-to-left layout, mirror the children along the main axis
+        let rtl = self.direction == Axis::Horizontal && env.get(theme::LAYOUT_RTL);
+        let mut child_paint_rect = Rect::ZERO;
+        let widgets = self.children.iter_mut().filter_map(|x| x.widget_mut());
+        for (widget, origin) in widgets.zip(origins) {
+            let origin = if rtl {
+                Point::new(
+                    my_size.width - origin.x - widget.layout_rect().width(),
+                    origin.y,
+                )
+            } else {
+                *origin
+            };
+            widget.set_origin(ctx, data, env, origin);
+            child_paint_rect = child_paint_rect.union(widget.paint_rect());
+        }
+
+        let my_bounds = Rect::ZERO.with_size(my_size);
+        let insets = child_paint_rect - my_bounds;
+        ctx.set_paint_insets(insets);
+    }
+
+    /// The gap between adjacent children, resolved in `env`.
+    fn resolve_child_spacing(&self, env: &Env) -> f64 {
+        self.child_spacing.resolve(env).max(0.0)
+    }
+
+    /// The baseline of a vertical container, which is that of its last child.
+    fn last_child_baseline(&self, my_size: Size) -> f64 {
+        (&self.children)
+            .last()
+            .map(|last| {
+                let child = last.widget();
+                if let Some(widget) = child {
+                    let child_bl = widget.baseline_offset();
+                    let child_max_y = widget.layout_rect().max_y();
+                    let extra_bottom_padding = my_size.height - child_max_y;
+                    child_bl + extra_bottom_padding
+                } else {
+                    0.0
+                }
+            })
+            .unwrap_or(0.0)
+    }
+}
+
 impl<T: Data> Widget<T> for Flex<T> {
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
@@ -641,6 +1026,9 @@ impl<T: Data> Widget<T> for Flex<T> {
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if self.direction == Axis::Horizontal && ctx.env_key_changed(&theme::LAYOUT_RTL) {
+            ctx.request_layout();
+        }
         for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
             child.update(ctx, data, env);
         }
@@ -649,6 +1037,9 @@ impl<T: Data> Widget<T> for Flex<T> {
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, bc, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Flex");
+        if self.wrap {
+            return self.layout_wrapped(ctx, bc, data, env);
+        }
         // we loosen our constraints when passing to children.
         let loosened_bc = bc.loosen();
 
@@ -660,7 +1051,14 @@ impl<T: Data> Widget<T> for Flex<T> {
         let mut any_use_baseline = self.cross_alignment == CrossAxisAlignment::Baseline;
 
         // Measure non-flex children.
-        let mut major_non_flex = 0.0;
+        let gap = self.resolve_child_spacing(env);
+        let gap_count = self
+            .children
+            .iter()
+            .filter(|child| child.widget().is_some())
+            .count()
+            .saturating_sub(1);
+        let mut major_non_flex = gap * gap_count as f64;
         let mut flex_sum = 0.0;
         for child in &mut self.children {
             match child {
@@ -754,7 +1152,8 @@ impl<T: Data> Widget<T> for Flex<T> {
         let extra_height = minor - minor_dim.min(minor);
 
         let mut major = spacing.next().unwrap_or(0.);
-        let mut child_paint_rect = Rect::ZERO;
+        let mut origins = Vec::new();
+        let mut after_widget = false;
 
         for child in &mut self.children {
             match child {
@@ -762,6 +1161,10 @@ impl<T: Data> Widget<T> for Flex<T> {
                 | Child::Flex {
                     widget, alignment, ..
                 } => {
+                    if after_widget {
+                        major += gap;
+                    }
+                    after_widget = true;
                     let child_size = widget.layout_rect().size();
                     let alignment = alignment.unwrap_or(self.cross_alignment);
                     let child_minor_offset = match alignment {
@@ -790,8 +1193,7 @@ impl<T: Data> Widget<T> for Flex<T> {
                     };
 
                     let child_pos: Point = self.direction.pack(major, child_minor_offset).into();
-                    widget.set_origin(ctx, data, env, child_pos);
-                    child_paint_rect = child_paint_rect.union(widget.paint_rect());
+                    origins.push(child_pos);
                     major += self.direction.major(child_size).expand();
                     major += spacing.next().unwrap_or(0.);
                 }
@@ -822,26 +1224,11 @@ impl<T: Data> Widget<T> for Flex<T> {
             bc.constrain(my_size)
         };
 
-        let my_bounds = Rect::ZERO.with_size(my_size);
-        let insets = child_paint_rect - my_bounds;
-        ctx.set_paint_insets(insets);
+        self.finish_layout(ctx, data, env, my_size, &origins);
 
         let baseline_offset = match self.direction {
             Axis::Horizontal => max_below_baseline,
-            Axis::Vertical => (&self.children)
-                .last()
-                .map(|last| {
-                    let child = last.widget();
-                    if let Some(widget) = child {
-                        let child_bl = widget.baseline_offset();
-                        let child_max_y = widget.layout_rect().max_y();
-                        let extra_bottom_padding = my_size.height - child_max_y;
-                        child_bl + extra_bottom_padding
-                    } else {
-                        0.0
-                    }
-                })
-                .unwrap_or(0.0),
+            Axis::Vertical => self.last_child_baseline(my_size),
         };
 
         ctx.set_baseline_offset(baseline_offset);
@@ -874,7 +1261,7 @@ impl CrossAxisAlignment {
     /// Given the difference between the size of the container and the size
     /// of the child (on their minor axis) return the necessary offset for
     /// this alignment.
-    fn align(self, val: f64) -> f64 {
+    pub(crate) fn align(self, val: f64) -> f64 {
         match self {
             CrossAxisAlignment::Start => 0.0,
             // in vertical layout, baseline is equivalent to center
@@ -980,6 +1367,31 @@ impl Iterator for Spacing {
     }
 }
 
+/// A line of children in a wrapping [`Flex`].
+struct WrapLine {
+    /// The indices of the children on this line.
+    range: std::ops::Range<usize>,
+    major: f64,
+    minor: f64,
+    flex_sum: f64,
+    above_baseline: f64,
+    /// Whether the line has any children that aren't spacers.
+    has_widget: bool,
+}
+
+impl WrapLine {
+    fn starting_at(index: usize) -> Self {
+        WrapLine {
+            range: index..index,
+            major: 0.0,
+            minor: 0.0,
+            flex_sum: 0.0,
+            above_baseline: 0.0,
+            has_widget: false,
+        }
+    }
+}
+
 impl From<f64> for FlexParams {
     fn from(flex: f64) -> FlexParams {
         FlexParams::new(flex, None)
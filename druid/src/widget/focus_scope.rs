@@ -0,0 +1,110 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [`Controller`]s that change the order of keyboard focus traversal.
+
+use crate::widget::Controller;
+use crate::{Env, LifeCycle, LifeCycleCtx, Widget};
+
+/// A [`Controller`] that sets the position of the focusable widgets in its
+/// child in the tab order.
+///
+/// Widgets with a positive tab index are visited first, in increasing order
+/// of their index, followed by the widgets without one (or with a tab index
+/// of `0`) in the order of the widget tree. Widgets with a negative tab
+/// index are skipped when traversing with the keyboard, but can still be
+/// focused otherwise.
+///
+/// A tab index set closer to a widget takes precedence over one set further
+/// up the tree.
+///
+/// This is available as a `with_tab_index` method via [`WidgetExt`].
+///
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct TabIndex {
+    index: i32,
+}
+
+/// A [`Controller`] that keeps keyboard focus traversal inside its child.
+///
+/// While the focus is on a widget inside the scope, moving the focus to the
+/// next or previous widget cycles among the focusable widgets in the scope,
+/// which is what a dialog wants. Focus can still leave the scope when it is
+/// moved to a particular widget, for instance by a click.
+///
+/// Use [`EventCtx::focus_first_in`] to move the focus into the scope.
+///
+/// This is available as a `focus_scope` method via [`WidgetExt`].
+///
+/// [`EventCtx::focus_first_in`]: crate::EventCtx::focus_first_in
+/// [`WidgetExt`]: crate::WidgetExt
+#[derive(Debug, Default)]
+pub struct FocusScope;
+
+impl TabIndex {
+    /// Create a new `TabIndex` controller with the given index.
+    pub fn new(index: i32) -> Self {
+        TabIndex { index }
+    }
+}
+
+impl FocusScope {
+    /// Create a new `FocusScope` controller.
+    pub fn new() -> Self {
+        FocusScope
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for TabIndex {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::BuildFocusChain = event {
+            let start = ctx.widget_state.focus_chain.len();
+            child.lifecycle(ctx, event, data, env);
+            for entry in &mut ctx.widget_state.focus_chain[start..] {
+                entry.tab_index.get_or_insert(self.index);
+            }
+        } else {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for FocusScope {
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::BuildFocusChain = event {
+            let start = ctx.widget_state.focus_chain.len();
+            child.lifecycle(ctx, event, data, env);
+            let id = ctx.widget_id();
+            for entry in &mut ctx.widget_state.focus_chain[start..] {
+                entry.scopes.push(id);
+            }
+        } else {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows the result of a future once it resolves.
+
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::widget::prelude::*;
+use crate::{Point, Selector, SingleUse, Target, WidgetPod};
+
+use tracing::{instrument, warn};
+
+/// The future a [`FutureWidget`] waits for.
+type BoxFuture<U> = Pin<Box<dyn Future<Output = U> + Send>>;
+type FutureFn<T, U> = Box<dyn Fn(&T, &Env) -> BoxFuture<U>>;
+type BuildFn<T, U> = Box<dyn Fn(U, &T, &Env) -> Box<dyn Widget<T>>>;
+
+/// Delivers the output of the future to the widget that started it.
+const RESOLVED: Selector<SingleUse<Box<dyn Any + Send>>> =
+    Selector::new("druid-builtin.future-widget-resolved");
+
+/// A widget that starts a future when it is added, shows a placeholder
+/// while the future is pending, and then replaces it with a widget built
+/// from the future's output.
+///
+/// The future is run by the spawner set with [`AppLauncher::spawner`], or
+/// else on a background thread by a minimal executor that doesn't run the
+/// reactor of any async runtime; see there for running futures that need
+/// one, like tokio's. The output is delivered to the UI thread with an
+/// [`ExtEventSink`]. If the widget is removed before the future resolves,
+/// the future is cancelled: it is dropped without being polled again.
+///
+/// This is only available with the `async` feature.
+///
+/// ```
+/// use druid::widget::{FutureWidget, Label, Spinner};
+/// use druid::WidgetExt;
+///
+/// let greeting = FutureWidget::new(
+///     |name: &String, _env| {
+///         let name = name.clone();
+///         async move { format!("Hello, {}!", name) }
+///     },
+///     Spinner::new().center(),
+///     |greeting, _data, _env| Box::new(Label::new(greeting)),
+/// );
+/// ```
+///
+/// [`AppLauncher::spawner`]: crate::AppLauncher::spawner
+/// [`ExtEventSink`]: crate::ExtEventSink
+pub struct FutureWidget<T, U> {
+    future: FutureFn<T, U>,
+    build: BuildFn<T, U>,
+    pending: WidgetPod<T, Box<dyn Widget<T>>>,
+    resolved: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    /// Cancels the future that is running, if any.
+    cancel: Option<Arc<CancelHandle>>,
+}
+
+/// Shared between a [`FutureWidget`] and the future it is waiting for.
+#[derive(Default)]
+struct CancelHandle {
+    cancelled: AtomicBool,
+    /// The waker of the task, so that we can drop the future right away.
+    waker: Mutex<Option<Waker>>,
+}
+
+/// A future that resolves to `None` once it is cancelled.
+struct Cancellable<U> {
+    future: BoxFuture<U>,
+    handle: Arc<CancelHandle>,
+}
+
+impl<T: Data, U: Send + 'static> FutureWidget<T, U> {
+    /// Create a widget that waits for the future that `future` returns,
+    /// showing `pending` until it resolves, and then the widget that `build`
+    /// makes from its output.
+    ///
+    /// `future` is called with the data when the widget is added.
+    pub fn new<F: Future<Output = U> + Send + 'static>(
+        future: impl Fn(&T, &Env) -> F + 'static,
+        pending: impl Widget<T> + 'static,
+        build: impl Fn(U, &T, &Env) -> Box<dyn Widget<T>> + 'static,
+    ) -> Self {
+        FutureWidget {
+            future: Box::new(move |data, env| Box::pin(future(data, env))),
+            build: Box::new(build),
+            pending: WidgetPod::new(Box::new(pending)),
+            resolved: None,
+            cancel: None,
+        }
+    }
+
+    fn start(&mut self, ctx: &mut LifeCycleCtx, data: &T, env: &Env) {
+        let handle = Arc::new(CancelHandle::default());
+        let future = Cancellable {
+            future: (self.future)(data, env),
+            handle: handle.clone(),
+        };
+        let sink = ctx.get_external_handle();
+        let target = Target::Widget(ctx.widget_id());
+        ctx.get_external_handle().spawn(async move {
+            let output = match future.await {
+                Some(output) => output,
+                None => return,
+            };
+            let output: Box<dyn Any + Send> = Box::new(output);
+            if sink
+                .submit_command(RESOLVED, SingleUse::new(output), target)
+                .is_err()
+            {
+                warn!("Application exited before a future of a FutureWidget resolved.");
+            }
+        });
+        self.cancel = Some(handle);
+    }
+
+    fn child(&mut self) -> &mut WidgetPod<T, Box<dyn Widget<T>>> {
+        self.resolved.as_mut().unwrap_or(&mut self.pending)
+    }
+}
+
+impl CancelHandle {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<U> Future for Cancellable<U> {
+    type Output = Option<U>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<U>> {
+        *self.handle.waker.lock().unwrap() = Some(cx.waker().clone());
+        if self.handle.cancelled.load(Ordering::SeqCst) {
+            return Poll::Ready(None);
+        }
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+impl<T, U> Drop for FutureWidget<T, U> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.cancel.take() {
+            handle.cancel();
+        }
+    }
+}
+
+impl<T: Data, U: Send + 'static> Widget<T> for FutureWidget<T, U> {
+    #[instrument(
+        name = "FutureWidget",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(output) = cmd.get(RESOLVED).and_then(SingleUse::take) {
+                match output.downcast::<U>() {
+                    Ok(output) => {
+                        self.cancel = None;
+                        let widget = (self.build)(*output, data, env);
+                        self.resolved = Some(WidgetPod::new(widget));
+                        ctx.children_changed();
+                    }
+                    Err(_) => warn!("FutureWidget: the output has the wrong type"),
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.child().event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "FutureWidget",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            if self.resolved.is_none() && self.cancel.is_none() {
+                self.start(ctx, data, env);
+            }
+        }
+        self.child().lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "FutureWidget",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child().update(ctx, data, env);
+    }
+
+    #[instrument(name = "FutureWidget", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let child = self.child();
+        let size = child.layout(ctx, bc, data, env);
+        child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_baseline_offset(child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "FutureWidget", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child().paint(ctx, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::SizedBox;
+    use crate::WidgetExt;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+    use test_env_log::test;
+
+    /// A widget that shows the future's output as its width.
+    fn answer(id: WidgetId) -> FutureWidget<(), f64> {
+        FutureWidget::new(
+            |_: &(), _| async { 21.0 * 2.0 },
+            SizedBox::empty(),
+            move |width, _, _| Box::new(SizedBox::empty().width(width).with_id(id)),
+        )
+    }
+
+    #[test]
+    fn shows_the_output() {
+        let id = WidgetId::next();
+        Harness::create_simple((), answer(id), |harness| {
+            harness.send_initial_events();
+            assert!(harness.recv_ext_command(Duration::from_secs(5)));
+            harness.just_layout();
+            assert_eq!(harness.get_state(id).size().width, 42.0);
+        });
+    }
+
+    #[test]
+    fn runs_on_the_spawner() {
+        let id = WidgetId::next();
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let count = spawned.clone();
+        Harness::create_simple((), answer(id), |harness| {
+            let count = count.clone();
+            harness.ext_host().set_spawner(Arc::new(move |future| {
+                count.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || crate::app_delegate::executor::block_on(future));
+            }));
+            harness.send_initial_events();
+            assert!(harness.recv_ext_command(Duration::from_secs(5)));
+            harness.just_layout();
+            assert_eq!(harness.get_state(id).size().width, 42.0);
+        });
+        assert_eq!(spawned.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cancelled_when_dropped() {
+        let handle = Arc::new(CancelHandle::default());
+        let mut future = Cancellable {
+            future: Box::pin(std::future::pending::<()>()),
+            handle: handle.clone(),
+        };
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Pending);
+        handle.cancel();
+        assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(None));
+    }
+
+    /// A waker that does nothing.
+    fn noop_waker() -> Waker {
+        struct NoopWaker;
+        impl std::task::Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+        Waker::from(Arc::new(NoopWaker))
+    }
+}
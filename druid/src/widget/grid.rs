@@ -0,0 +1,591 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that arranges its children in a two-dimensional grid.
+
+use std::ops::Range;
+
+use crate::kurbo::common::FloatExt;
+use crate::widget::prelude::*;
+use crate::widget::{Axis, CrossAxisAlignment};
+use crate::{theme, Data, Point, Rect, WidgetPod};
+use tracing::{instrument, trace, warn};
+
+/// The size of a row or column of a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackSize {
+    /// A fixed size, in display points.
+    Fixed(f64),
+    /// The size of the largest child in the track, when each child has as
+    /// much space as it wants.
+    ///
+    /// Children are measured with [`Widget::compute_max_intrinsic`].
+    Auto,
+    /// The size of the largest child in the track, when each child is as
+    /// small as it can be, for example with a label's text wrapped at every
+    /// word.
+    ///
+    /// Children are measured with [`Widget::compute_min_intrinsic`].
+    MinContent,
+    /// A share of the space left over once the fixed and auto tracks have
+    /// been sized, in proportion to this flex factor.
+    ///
+    /// If the grid is unbounded on this axis, the track is sized as if it
+    /// were [`TrackSize::Auto`].
+    Flex(f64),
+}
+
+/// The cell (or cells) a child of a [`Grid`] occupies, and how it is
+/// aligned within them.
+///
+/// A `GridCell` can also be created from a `(row, column)` tuple.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridCell {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    horizontal: Option<CrossAxisAlignment>,
+    vertical: Option<CrossAxisAlignment>,
+}
+
+/// A container that arranges its children in rows and columns.
+///
+/// The size of each row and column is described by a [`TrackSize`]; rows
+/// and columns that are used by a child but not explicitly added are
+/// [`TrackSize::Auto`]. A child can span several rows or columns, and can be
+/// aligned within its cell using [`CrossAxisAlignment`] on each axis, where
+/// `Baseline` is equivalent to `Center`.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{CrossAxisAlignment, Grid, GridCell, Label, TextBox, TrackSize};
+///
+/// let form = Grid::<String>::new()
+///     .with_columns(vec![TrackSize::Auto, TrackSize::Flex(1.0)])
+///     .with_spacing(8.0)
+///     .with_child(Label::new("Name"), (0, 0))
+///     .with_child(TextBox::new(), GridCell::new(0, 1).align(CrossAxisAlignment::Fill, None))
+///     .with_child(Label::new("Notes"), (1, 0))
+///     .with_child(TextBox::multiline(), GridCell::new(1, 1))
+///     .with_child(Label::new("Spans both columns"), GridCell::new(2, 0).span(1, 2));
+/// ```
+pub struct Grid<T> {
+    rows: Vec<TrackSize>,
+    columns: Vec<TrackSize>,
+    row_spacing: f64,
+    column_spacing: f64,
+    horizontal_alignment: CrossAxisAlignment,
+    vertical_alignment: CrossAxisAlignment,
+    children: Vec<GridChild<T>>,
+}
+
+struct GridChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    cell: GridCell,
+}
+
+impl GridCell {
+    /// The cell at the given row and column.
+    pub fn new(row: usize, column: usize) -> Self {
+        GridCell {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            horizontal: None,
+            vertical: None,
+        }
+    }
+
+    /// Builder-style method to span several rows and columns, starting
+    /// from this cell.
+    ///
+    /// Spans of `0` are treated as `1`.
+    pub fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.row_span = rows.max(1);
+        self.column_span = columns.max(1);
+        self
+    }
+
+    /// Builder-style method to set the alignment of the child within its
+    /// cell, overriding that of the [`Grid`].
+    ///
+    /// An alignment of `None` uses the [`Grid`]'s alignment on that axis.
+    pub fn align(
+        mut self,
+        horizontal: impl Into<Option<CrossAxisAlignment>>,
+        vertical: impl Into<Option<CrossAxisAlignment>>,
+    ) -> Self {
+        self.horizontal = horizontal.into();
+        self.vertical = vertical.into();
+        self
+    }
+
+    fn rows(&self) -> Range<usize> {
+        self.row..self.row + self.row_span
+    }
+
+    fn columns(&self) -> Range<usize> {
+        self.column..self.column + self.column_span
+    }
+}
+
+impl From<(usize, usize)> for GridCell {
+    fn from((row, column): (usize, usize)) -> GridCell {
+        GridCell::new(row, column)
+    }
+}
+
+impl<T: Data> Grid<T> {
+    /// Create a new, empty `Grid`.
+    ///
+    /// Children are aligned to the start of their cells horizontally, and
+    /// centered vertically.
+    pub fn new() -> Self {
+        Grid {
+            rows: Vec::new(),
+            columns: Vec::new(),
+            row_spacing: 0.0,
+            column_spacing: 0.0,
+            horizontal_alignment: CrossAxisAlignment::Start,
+            vertical_alignment: CrossAxisAlignment::Center,
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a row.
+    pub fn with_row(mut self, size: TrackSize) -> Self {
+        self.add_row(size);
+        self
+    }
+
+    /// Builder-style method to add a column.
+    pub fn with_column(mut self, size: TrackSize) -> Self {
+        self.add_column(size);
+        self
+    }
+
+    /// Builder-style method to add several rows.
+    pub fn with_rows(mut self, sizes: impl IntoIterator<Item = TrackSize>) -> Self {
+        self.rows.extend(sizes);
+        self
+    }
+
+    /// Builder-style method to add several columns.
+    pub fn with_columns(mut self, sizes: impl IntoIterator<Item = TrackSize>) -> Self {
+        self.columns.extend(sizes);
+        self
+    }
+
+    /// Builder-style method to set the space between both rows and columns.
+    pub fn with_spacing(mut self, spacing: f64) -> Self {
+        self.set_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method to set the space between rows.
+    pub fn with_row_spacing(mut self, spacing: f64) -> Self {
+        self.set_row_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method to set the space between columns.
+    pub fn with_column_spacing(mut self, spacing: f64) -> Self {
+        self.set_column_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method to set the default alignment of children
+    /// within their cells.
+    pub fn with_cell_alignment(
+        mut self,
+        horizontal: CrossAxisAlignment,
+        vertical: CrossAxisAlignment,
+    ) -> Self {
+        self.set_cell_alignment(horizontal, vertical);
+        self
+    }
+
+    /// Builder-style method to add a child in the given cell.
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        cell: impl Into<GridCell>,
+    ) -> Self {
+        self.add_child(child, cell);
+        self
+    }
+
+    /// Add a row.
+    pub fn add_row(&mut self, size: TrackSize) {
+        self.rows.push(size);
+    }
+
+    /// Add a column.
+    pub fn add_column(&mut self, size: TrackSize) {
+        self.columns.push(size);
+    }
+
+    /// Set the space between both rows and columns.
+    pub fn set_spacing(&mut self, spacing: f64) {
+        self.set_row_spacing(spacing);
+        self.set_column_spacing(spacing);
+    }
+
+    /// Set the space between rows.
+    pub fn set_row_spacing(&mut self, spacing: f64) {
+        self.row_spacing = spacing.max(0.0);
+    }
+
+    /// Set the space between columns.
+    pub fn set_column_spacing(&mut self, spacing: f64) {
+        self.column_spacing = spacing.max(0.0);
+    }
+
+    /// Set the default alignment of children within their cells.
+    pub fn set_cell_alignment(
+        &mut self,
+        horizontal: CrossAxisAlignment,
+        vertical: CrossAxisAlignment,
+    ) {
+        self.horizontal_alignment = horizontal;
+        self.vertical_alignment = vertical;
+    }
+
+    /// Add a child in the given cell.
+    ///
+    /// See also [`with_child`].
+    ///
+    /// [`with_child`]: Grid::with_child
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, cell: impl Into<GridCell>) {
+        self.children.push(GridChild {
+            widget: WidgetPod::new(Box::new(child)),
+            cell: cell.into(),
+        });
+    }
+
+    /// The rows and columns, including those implied by the children.
+    fn tracks(&self) -> (Vec<TrackSize>, Vec<TrackSize>) {
+        let mut rows = self.rows.clone();
+        let mut columns = self.columns.clone();
+        for child in &self.children {
+            if child.cell.rows().end > rows.len() {
+                rows.resize(child.cell.rows().end, TrackSize::Auto);
+            }
+            if child.cell.columns().end > columns.len() {
+                columns.resize(child.cell.columns().end, TrackSize::Auto);
+            }
+        }
+        (rows, columns)
+    }
+}
+
+impl<T: Data> GridChild<T> {
+    /// Measure the child along `axis`, at its minimum intrinsic size if all
+    /// the tracks it spans are [`TrackSize::MinContent`].
+    fn measure(
+        &mut self,
+        axis: Axis,
+        tracks: &[TrackSize],
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let span = match axis {
+            Axis::Horizontal => self.cell.columns(),
+            Axis::Vertical => self.cell.rows(),
+        };
+        if tracks[span]
+            .iter()
+            .all(|track| *track == TrackSize::MinContent)
+        {
+            self.widget.compute_min_intrinsic(axis, ctx, bc, data, env)
+        } else {
+            self.widget.compute_max_intrinsic(axis, ctx, bc, data, env)
+        }
+    }
+}
+
+impl<T: Data> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Grid<T> {
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&theme::LAYOUT_RTL) {
+            ctx.request_layout();
+        }
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Grid");
+        let (rows, columns) = self.tracks();
+        let unbounded = Size::new(f64::INFINITY, f64::INFINITY);
+
+        // Measure the children in non-fixed columns, with unbounded space.
+        let mut measured = Vec::new();
+        for child in &mut self.children {
+            let span = child.cell.columns();
+            if columns[span.clone()]
+                .iter()
+                .any(|track| !matches!(track, TrackSize::Fixed(_)))
+            {
+                let child_bc = BoxConstraints::new(Size::ZERO, unbounded);
+                let width = child.measure(Axis::Horizontal, &columns, ctx, &child_bc, data, env);
+                measured.push((span, width.expand()));
+            }
+        }
+        let column_widths = resolve_tracks(
+            &columns,
+            &auto_sizes(&columns, self.column_spacing, &measured),
+            self.column_spacing,
+            bc.max().width,
+        );
+        let column_offsets = offsets(&column_widths, self.column_spacing);
+
+        // Measure the children in non-fixed rows, at the width of their cells.
+        measured.clear();
+        for child in &mut self.children {
+            let span = child.cell.rows();
+            if rows[span.clone()]
+                .iter()
+                .any(|track| !matches!(track, TrackSize::Fixed(_)))
+            {
+                let width = span_size(&column_widths, &column_offsets, child.cell.columns());
+                let child_bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+                let height = child.measure(Axis::Vertical, &rows, ctx, &child_bc, data, env);
+                measured.push((span, height.expand()));
+            }
+        }
+        let row_heights = resolve_tracks(
+            &rows,
+            &auto_sizes(&rows, self.row_spacing, &measured),
+            self.row_spacing,
+            bc.max().height,
+        );
+        let row_offsets = offsets(&row_heights, self.row_spacing);
+
+        let my_size = bc.constrain(Size::new(
+            total_size(&column_widths, self.column_spacing),
+            total_size(&row_heights, self.row_spacing),
+        ));
+        let rtl = env.get(theme::LAYOUT_RTL);
+
+        // Lay out each child in its cell.
+        let mut child_paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let x0 = column_offsets[child.cell.column];
+            let y0 = row_offsets[child.cell.row];
+            let width = span_size(&column_widths, &column_offsets, child.cell.columns());
+            let height = span_size(&row_heights, &row_offsets, child.cell.rows());
+            let horizontal = child.cell.horizontal.unwrap_or(self.horizontal_alignment);
+            let vertical = child.cell.vertical.unwrap_or(self.vertical_alignment);
+
+            let min_width = if horizontal == CrossAxisAlignment::Fill {
+                width
+            } else {
+                0.0
+            };
+            let min_height = if vertical == CrossAxisAlignment::Fill {
+                height
+            } else {
+                0.0
+            };
+            let child_bc =
+                BoxConstraints::new(Size::new(min_width, min_height), Size::new(width, height));
+            let child_size = child.widget.layout(ctx, &child_bc, data, env);
+
+            let mut x = x0 + horizontal.align(width - child_size.width);
+            let y = y0 + vertical.align(height - child_size.height);
+            // in a right-to-left layout, mirror the columns
+            if rtl {
+                x = my_size.width - x - child_size.width;
+            }
+            child.widget.set_origin(ctx, data, env, Point::new(x, y));
+            child_paint_rect = child_paint_rect.union(child.widget.paint_rect());
+        }
+
+        let insets = child_paint_rect - Rect::ZERO.with_size(my_size);
+        ctx.set_paint_insets(insets);
+        trace!("Computed layout: size={}", my_size);
+        my_size
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+}
+
+/// The size needed by each non-fixed track to fit the children measured in it.
+///
+/// Children spanning several tracks are fit after the others, by growing
+/// the auto tracks they span, unless they also span a flex track.
+fn auto_sizes(tracks: &[TrackSize], spacing: f64, measured: &[(Range<usize>, f64)]) -> Vec<f64> {
+    let mut sizes = vec![0.0f64; tracks.len()];
+    for (span, size) in measured.iter().filter(|(span, _)| span.len() == 1) {
+        sizes[span.start] = sizes[span.start].max(*size);
+    }
+
+    for (span, size) in measured.iter().filter(|(span, _)| span.len() > 1) {
+        if tracks[span.clone()]
+            .iter()
+            .any(|track| matches!(track, TrackSize::Flex(_)))
+        {
+            continue;
+        }
+        let auto_tracks: Vec<usize> = span
+            .clone()
+            .filter(|&i| matches!(tracks[i], TrackSize::Auto | TrackSize::MinContent))
+            .collect();
+        if auto_tracks.is_empty() {
+            continue;
+        }
+        let current: f64 = span
+            .clone()
+            .map(|i| match tracks[i] {
+                TrackSize::Fixed(fixed) => fixed.max(0.0),
+                _ => sizes[i],
+            })
+            .sum::<f64>()
+            + spacing * (span.len() - 1) as f64;
+        let extra = (size - current) / auto_tracks.len() as f64;
+        if extra > 0.0 {
+            for i in auto_tracks {
+                sizes[i] += extra;
+            }
+        }
+    }
+    sizes
+}
+
+/// Resolve the size of each track, given the size needed by the auto tracks
+/// and the space available.
+fn resolve_tracks(tracks: &[TrackSize], auto: &[f64], spacing: f64, available: f64) -> Vec<f64> {
+    let flex_sum: f64 = tracks
+        .iter()
+        .map(|track| match track {
+            TrackSize::Flex(flex) => flex.max(0.0),
+            _ => 0.0,
+        })
+        .sum();
+    let flex_is_auto = flex_sum > 0.0 && !available.is_finite();
+    if flex_is_auto {
+        warn!("A track of Grid is flex, but Grid is unbounded.");
+    }
+
+    let mut sizes: Vec<f64> = tracks
+        .iter()
+        .zip(auto)
+        .map(|(track, auto)| match track {
+            TrackSize::Fixed(fixed) => fixed.max(0.0),
+            TrackSize::Flex(_) if !flex_is_auto => 0.0,
+            _ => *auto,
+        })
+        .collect();
+
+    if flex_sum > 0.0 && !flex_is_auto {
+        let remaining = (available - total_size(&sizes, spacing)).max(0.0);
+        let px_per_flex = remaining / flex_sum;
+        let mut remainder = 0.0;
+        for (size, track) in sizes.iter_mut().zip(tracks) {
+            if let TrackSize::Flex(flex) = track {
+                let desired = flex.max(0.0) * px_per_flex + remainder;
+                *size = desired.round();
+                remainder = desired - *size;
+            }
+        }
+    }
+    sizes
+}
+
+/// The offset of the start of each track.
+fn offsets(sizes: &[f64], spacing: f64) -> Vec<f64> {
+    let mut offset = 0.0;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = offset;
+            offset += size + spacing;
+            start
+        })
+        .collect()
+}
+
+/// The size of a span of tracks, including the spacing between them.
+fn span_size(sizes: &[f64], offsets: &[f64], span: Range<usize>) -> f64 {
+    let last = span.end - 1;
+    offsets[last] + sizes[last] - offsets[span.start]
+}
+
+/// The size of all the tracks, including the spacing between them.
+fn total_size(sizes: &[f64], spacing: f64) -> f64 {
+    sizes.iter().sum::<f64>() + spacing * sizes.len().saturating_sub(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn track_sizes() {
+        let tracks = [
+            TrackSize::Fixed(20.0),
+            TrackSize::Auto,
+            TrackSize::Flex(1.0),
+            TrackSize::Flex(2.0),
+        ];
+        let measured = [(1..2, 30.0), (2..3, 500.0), (0..2, 70.0)];
+        let auto = auto_sizes(&tracks, 10.0, &measured);
+        assert_eq!(auto[1], 40.0);
+
+        let sizes = resolve_tracks(&tracks, &auto, 10.0, 220.0);
+        assert_eq!(sizes, vec![20.0, 40.0, 43.0, 87.0]);
+        assert_eq!(total_size(&sizes, 10.0), 220.0);
+        let offsets = offsets(&sizes, 10.0);
+        assert_eq!(offsets, vec![0.0, 30.0, 80.0, 133.0]);
+        assert_eq!(span_size(&sizes, &offsets, 1..3), 93.0);
+
+        // unbounded, flex tracks fit their children
+        let sizes = resolve_tracks(&tracks, &auto, 10.0, f64::INFINITY);
+        assert_eq!(sizes, vec![20.0, 40.0, 500.0, 0.0]);
+    }
+}
@@ -16,7 +16,7 @@
 
 use crate::kurbo::Size;
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
+use crate::widget::{Axis, WidgetWrapper};
 use crate::Data;
 use tracing::instrument;
 
@@ -70,6 +70,38 @@ impl<T: Data, W: Widget<T>> Widget<T> for IdentityWrapper<W> {
         self.inner.layout(ctx, bc, data, env)
     }
 
+    #[instrument(
+        name = "IdentityWrapper",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner.compute_max_intrinsic(axis, ctx, bc, data, env)
+    }
+
+    #[instrument(
+        name = "IdentityWrapper",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner.compute_min_intrinsic(axis, ctx, bc, data, env)
+    }
+
     #[instrument(name = "IdentityWrapper", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.inner.paint(ctx, data, env);
@@ -18,9 +18,10 @@ use std::ops::{Deref, DerefMut};
 
 use druid_shell::Cursor;
 
-use crate::kurbo::Vec2;
-use crate::text::TextStorage;
+use crate::kurbo::{Affine, Vec2};
+use crate::text::{TextHit, TextOutline, TextShadow, TextStorage};
 use crate::widget::prelude::*;
+use crate::widget::Axis;
 use crate::{
     ArcStr, Color, Data, FontDescriptor, KeyOrValue, LocalizedString, Point, TextAlignment,
     TextLayout,
@@ -94,6 +95,9 @@ pub struct Label<T> {
 pub struct RawLabel<T> {
     layout: TextLayout<T>,
     line_break_mode: LineBreaking,
+    orientation: TextOrientation,
+    /// The size from the last layout, to map points into the rotated text.
+    size: Size,
 
     disabled: bool,
     default_text_color: KeyOrValue<Color>,
@@ -110,6 +114,66 @@ pub enum LineBreaking {
     Overflow,
 }
 
+/// The direction in which a label's text runs.
+///
+/// Rotated text is useful for axis labels of charts and for compact vertical
+/// tabs. The size of a rotated label is the size of its text, rotated; line
+/// wrapping applies to the label's height.
+///
+/// Upright text is the vertical layout of Chinese, Japanese and Korean: the
+/// characters keep their orientation, and are stacked from top to bottom in a
+/// single column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data, Default)]
+pub enum TextOrientation {
+    /// The text runs from left to right.
+    #[default]
+    Horizontal,
+    /// The text is rotated by 90° clockwise, and runs from top to bottom.
+    Rotated90,
+    /// The text is rotated by 270° clockwise, and runs from bottom to top.
+    Rotated270,
+    /// The characters are upright, and stacked from top to bottom.
+    ///
+    /// The text is broken into lines at every opportunity, which is between
+    /// every character of CJK text; words of other scripts stay together on
+    /// a line. The label's [`LineBreaking`] doesn't apply, and the lines are
+    /// aligned with the label's [`TextAlignment`], so `Center` is usually
+    /// what you want.
+    ///
+    /// [`TextAlignment`]: crate::TextAlignment
+    Upright,
+}
+
+impl TextOrientation {
+    /// Whether the text runs along the vertical axis.
+    pub fn is_vertical(self) -> bool {
+        self != TextOrientation::Horizontal
+    }
+
+    /// Whether the text is drawn rotated, so that the label's width and
+    /// height are swapped.
+    fn is_rotated(self) -> bool {
+        matches!(
+            self,
+            TextOrientation::Rotated90 | TextOrientation::Rotated270
+        )
+    }
+
+    /// The transform from the coordinate space of the text to that of a
+    /// label of the given size.
+    fn transform(self, size: Size) -> Affine {
+        match self {
+            TextOrientation::Horizontal | TextOrientation::Upright => Affine::IDENTITY,
+            TextOrientation::Rotated90 => {
+                Affine::translate((size.width, 0.0)) * Affine::rotate(std::f64::consts::FRAC_PI_2)
+            }
+            TextOrientation::Rotated270 => {
+                Affine::translate((0.0, size.height)) * Affine::rotate(-std::f64::consts::FRAC_PI_2)
+            }
+        }
+    }
+}
+
 /// The text for a [`Label`].
 ///
 /// This can be one of three things; either an [`ArcStr`], a [`LocalizedString`],
@@ -153,6 +217,8 @@ impl<T: TextStorage> RawLabel<T> {
         Self {
             layout: TextLayout::new(),
             line_break_mode: LineBreaking::Overflow,
+            orientation: TextOrientation::Horizontal,
+            size: Size::ZERO,
             disabled: false,
             default_text_color: crate::theme::TEXT_COLOR.into(),
         }
@@ -178,6 +244,46 @@ impl<T: TextStorage> RawLabel<T> {
         self
     }
 
+    /// Builder-style method for setting the line height.
+    ///
+    /// See [`set_line_height`] for details.
+    ///
+    /// [`set_line_height`]: #method.set_line_height
+    pub fn with_line_height(mut self, line_height: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_line_height(line_height);
+        self
+    }
+
+    /// Builder-style method for setting the paragraph spacing.
+    ///
+    /// See [`set_paragraph_spacing`] for details.
+    ///
+    /// [`set_paragraph_spacing`]: #method.set_paragraph_spacing
+    pub fn with_paragraph_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_paragraph_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method for setting a shadow beneath the text.
+    ///
+    /// See [`set_shadow`] for details.
+    ///
+    /// [`set_shadow`]: #method.set_shadow
+    pub fn with_shadow(mut self, shadow: TextShadow) -> Self {
+        self.set_shadow(shadow);
+        self
+    }
+
+    /// Builder-style method for setting an outline around the text.
+    ///
+    /// See [`set_outline`] for details.
+    ///
+    /// [`set_outline`]: #method.set_outline
+    pub fn with_outline(mut self, outline: TextOutline) -> Self {
+        self.set_outline(outline);
+        self
+    }
+
     /// Builder-style method for setting the font.
     ///
     /// The argument can be a [`FontDescriptor`] or a [`Key<FontDescriptor>`]
@@ -237,6 +343,60 @@ impl<T: TextStorage> RawLabel<T> {
         self.layout.set_text_size(size);
     }
 
+    /// Set the line height, as a multiple of each line's natural height.
+    ///
+    /// The argument can be either an `f64` or a [`Key<f64>`]; the default
+    /// is [`theme::TEXT_LINE_HEIGHT`].
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label is updated.
+    ///
+    /// [`request_layout`]: ../struct.EventCtx.html#method.request_layout
+    /// [`Key<f64>`]: ../struct.Key.html
+    /// [`theme::TEXT_LINE_HEIGHT`]: crate::theme::TEXT_LINE_HEIGHT
+    pub fn set_line_height(&mut self, line_height: impl Into<KeyOrValue<f64>>) {
+        self.layout.set_line_height(line_height);
+    }
+
+    /// Set the extra space added after each paragraph.
+    ///
+    /// The argument can be either an `f64` or a [`Key<f64>`]; the default
+    /// is [`theme::TEXT_PARAGRAPH_SPACING`].
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label is updated.
+    ///
+    /// [`request_layout`]: ../struct.EventCtx.html#method.request_layout
+    /// [`Key<f64>`]: ../struct.Key.html
+    /// [`theme::TEXT_PARAGRAPH_SPACING`]: crate::theme::TEXT_PARAGRAPH_SPACING
+    pub fn set_paragraph_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.layout.set_paragraph_spacing(spacing);
+    }
+
+    /// Set a shadow to draw beneath the text, or `None` to remove it.
+    ///
+    /// The shadow is not included in the label's size, but in its paint insets.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label is updated.
+    ///
+    /// [`request_layout`]: ../struct.EventCtx.html#method.request_layout
+    pub fn set_shadow(&mut self, shadow: impl Into<Option<TextShadow>>) {
+        self.layout.set_shadow(shadow);
+    }
+
+    /// Set an outline to draw around the text, or `None` to remove it.
+    ///
+    /// The outline is not included in the label's size, but in its paint insets.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label is updated.
+    ///
+    /// [`request_layout`]: ../struct.EventCtx.html#method.request_layout
+    pub fn set_outline(&mut self, outline: impl Into<Option<TextOutline>>) {
+        self.layout.set_outline(outline);
+    }
+
     /// Set the font.
     ///
     /// The argument can be a [`FontDescriptor`] or a [`Key<FontDescriptor>`]
@@ -271,6 +431,27 @@ impl<T: TextStorage> RawLabel<T> {
         self.layout.set_text_alignment(alignment);
     }
 
+    /// Builder-style method to set the [`TextOrientation`].
+    pub fn with_orientation(mut self, orientation: TextOrientation) -> Self {
+        self.set_orientation(orientation);
+        self
+    }
+
+    /// Set the [`TextOrientation`].
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label updates.
+    ///
+    /// [`request_layout`]: ../struct.EventCtx.html#method.request_layout
+    pub fn set_orientation(&mut self, orientation: TextOrientation) {
+        self.orientation = orientation;
+    }
+
+    /// Map a point in the label's coordinate space to the text layout's.
+    fn text_point(&self, point: Point) -> Point {
+        self.orientation.transform(self.size).inverse() * point - Vec2::new(LABEL_X_PADDING, 0.0)
+    }
+
     /// Draw this label's text at the provided `Point`, without internal padding.
     ///
     /// This is a convenience for widgets that want to use Label as a way
@@ -280,11 +461,47 @@ impl<T: TextStorage> RawLabel<T> {
         self.layout.draw(ctx, origin)
     }
 
+    /// The width of the text, including padding, when wrapped at `wrap_width`.
+    ///
+    /// This measures a copy of the layout, so that the one that is painted
+    /// keeps the wrap width of the last [`layout`].
+    ///
+    /// [`layout`]: Widget::layout
+    fn text_width(&self, ctx: &mut LayoutCtx, wrap_width: f64, env: &Env) -> f64 {
+        let mut layout = self.layout.clone();
+        layout.set_wrap_width(wrap_width);
+        layout.rebuild_if_needed(ctx.text(), env);
+        layout.layout_metrics().size.width + 2.0 * LABEL_X_PADDING
+    }
+
     /// Return the offset of the first baseline relative to the bottom of the widget.
     pub fn baseline_offset(&self) -> f64 {
         let text_metrics = self.layout.layout_metrics();
         text_metrics.size.height - text_metrics.first_baseline
     }
+
+    /// The [`TextLayout`] used to display this label's text.
+    ///
+    /// This is only valid after the label has been laid out. Note that the layout's
+    /// coordinates do not include the label's internal padding; to hit-test with
+    /// a point in the label's own coordinate space, use [`hit_test`].
+    ///
+    /// [`hit_test`]: #method.hit_test
+    pub fn text_layout(&self) -> &TextLayout<T> {
+        &self.layout
+    }
+
+    /// Returns the grapheme and attribute spans under `point`, which is
+    /// relative to the origin of this label.
+    ///
+    /// This is intended for [`Controller`]s that want to react to specific
+    /// parts of the text, for instance to show a popover when hovering a word.
+    /// See [`TextLayout::hit_test`] for details.
+    ///
+    /// [`Controller`]: super::Controller
+    pub fn hit_test(&self, point: impl Into<Point>) -> Option<TextHit> {
+        self.layout.hit_test(self.text_point(point.into()))
+    }
 }
 
 impl<T: TextStorage> Label<T> {
@@ -389,6 +606,38 @@ impl<T: Data> Label<T> {
         self
     }
 
+    /// Builder-style method for setting the line height.
+    ///
+    /// See [`RawLabel::set_line_height`] for details.
+    pub fn with_line_height(mut self, line_height: impl Into<KeyOrValue<f64>>) -> Self {
+        self.label.set_line_height(line_height);
+        self
+    }
+
+    /// Builder-style method for setting the paragraph spacing.
+    ///
+    /// See [`RawLabel::set_paragraph_spacing`] for details.
+    pub fn with_paragraph_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.label.set_paragraph_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method for setting a shadow beneath the text.
+    ///
+    /// See [`RawLabel::set_shadow`] for details.
+    pub fn with_shadow(mut self, shadow: TextShadow) -> Self {
+        self.label.set_shadow(shadow);
+        self
+    }
+
+    /// Builder-style method for setting an outline around the text.
+    ///
+    /// See [`RawLabel::set_outline`] for details.
+    pub fn with_outline(mut self, outline: TextOutline) -> Self {
+        self.label.set_outline(outline);
+        self
+    }
+
     /// Builder-style method for setting the font.
     ///
     /// The argument can be a [`FontDescriptor`] or a [`Key<FontDescriptor>`]
@@ -418,6 +667,13 @@ impl<T: Data> Label<T> {
         self
     }
 
+    /// Builder-style method to set the [`TextOrientation`], to draw the text
+    /// rotated.
+    pub fn with_orientation(mut self, orientation: TextOrientation) -> Self {
+        self.label.set_orientation(orientation);
+        self
+    }
+
     /// Draw this label's text at the provided `Point`, without internal padding.
     ///
     /// This is a convenience for widgets that want to use Label as a way
@@ -517,6 +773,32 @@ impl<T: Data> Widget<T> for Label<T> {
         self.label.layout(ctx, bc, &self.current_text, env)
     }
 
+    #[instrument(name = "Label", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.label
+            .compute_max_intrinsic(axis, ctx, bc, &self.current_text, env)
+    }
+
+    #[instrument(name = "Label", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.label
+            .compute_min_intrinsic(axis, ctx, bc, &self.current_text, env)
+    }
+
     #[instrument(name = "Label", level = "trace", skip(self, ctx, _data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         if self.text_should_be_updated {
@@ -535,15 +817,13 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
         match event {
             Event::MouseUp(event) => {
-                // Account for the padding
-                let pos = event.pos - Vec2::new(LABEL_X_PADDING, 0.0);
+                let pos = self.text_point(event.pos);
                 if let Some(link) = self.layout.link_for_pos(pos) {
                     ctx.submit_command(link.command.clone());
                 }
             }
             Event::MouseMove(event) => {
-                // Account for the padding
-                let pos = event.pos - Vec2::new(LABEL_X_PADDING, 0.0);
+                let pos = self.text_point(event.pos);
 
                 if self.layout.link_for_pos(pos).is_some() {
                     ctx.set_cursor(&Cursor::Pointer);
@@ -593,8 +873,16 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
         bc.debug_check("Label");
 
+        let rotated = self.orientation.is_rotated();
+        let max_length = if rotated {
+            bc.max().height
+        } else {
+            bc.max().width
+        };
         let width = match self.line_break_mode {
-            LineBreaking::WordWrap => bc.max().width - LABEL_X_PADDING * 2.0,
+            // one character (or word) per line
+            _ if self.orientation == TextOrientation::Upright => 0.0,
+            LineBreaking::WordWrap => max_length - LABEL_X_PADDING * 2.0,
             _ => f64::INFINITY,
         };
 
@@ -602,15 +890,84 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         self.layout.rebuild_if_needed(ctx.text(), env);
 
         let text_metrics = self.layout.layout_metrics();
-        ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
-        let size = bc.constrain(Size::new(
+        let text_size = Size::new(
             text_metrics.size.width + 2. * LABEL_X_PADDING,
             text_metrics.size.height,
-        ));
+        );
+        let size = if rotated {
+            // rotated text has no horizontal baseline to align with
+            ctx.set_baseline_offset(0.0);
+            bc.constrain(Size::new(text_size.height, text_size.width))
+        } else if self.orientation == TextOrientation::Upright {
+            // nor does a column of upright characters
+            ctx.set_baseline_offset(0.0);
+            bc.constrain(text_size)
+        } else {
+            ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
+            bc.constrain(text_size)
+        };
+        let insets = self.layout.decoration_insets();
+        if rotated {
+            // the text is rotated, so each side may reach out of any edge
+            let reach = insets.x0.max(insets.y0).max(insets.x1).max(insets.y1);
+            ctx.set_paint_insets(reach);
+        } else {
+            ctx.set_paint_insets(insets);
+        }
+        self.size = size;
         trace!("Computed size: {}", size);
         size
     }
 
+    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        if self.orientation == TextOrientation::Upright {
+            // the lines don't depend on the constraints.
+            let bc = axis.constraints(bc, 0.0, f64::INFINITY);
+            return axis.major(self.layout(ctx, &bc, data, env));
+        }
+        // the axis along which the text runs
+        let along_text = if self.orientation.is_vertical() {
+            Axis::Vertical
+        } else {
+            Axis::Horizontal
+        };
+        if axis == along_text {
+            self.text_width(ctx, f64::INFINITY, env)
+        } else {
+            let bc = axis.constraints(bc, 0.0, f64::INFINITY);
+            axis.major(self.layout(ctx, &bc, data, env))
+        }
+    }
+
+    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let along_text = (axis == Axis::Vertical) == self.orientation.is_rotated();
+        if along_text
+            && self.line_break_mode == LineBreaking::WordWrap
+            && self.orientation != TextOrientation::Upright
+        {
+            // wrapping at every opportunity leaves the longest word.
+            self.text_width(ctx, 0.0, env)
+        } else {
+            self.compute_max_intrinsic(axis, ctx, bc, data, env)
+        }
+    }
+
     #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, _data, _env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
         let origin = Point::new(LABEL_X_PADDING, 0.0);
@@ -619,7 +976,15 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         if self.line_break_mode == LineBreaking::Clip {
             ctx.clip(label_size.to_rect());
         }
-        self.draw_at(ctx, origin)
+        if self.orientation.is_rotated() {
+            let transform = self.orientation.transform(label_size);
+            ctx.with_save(|ctx| {
+                ctx.transform(transform);
+                self.draw_at(ctx, origin)
+            })
+        } else {
+            self.draw_at(ctx, origin)
+        }
     }
 }
 
@@ -21,8 +21,8 @@
 use std::marker::PhantomData;
 
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
-use crate::{Data, Lens};
+use crate::widget::{Axis, WidgetWrapper};
+use crate::{ChangeSet, Data, Lens};
 
 use tracing::{instrument, trace};
 
@@ -128,6 +128,36 @@ where
             .with(data, |data| inner.layout(ctx, bc, data, env))
     }
 
+    #[instrument(name = "LensWrap", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let inner = &mut self.inner;
+        self.lens.with(data, |data| {
+            inner.compute_max_intrinsic(axis, ctx, bc, data, env)
+        })
+    }
+
+    #[instrument(name = "LensWrap", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let inner = &mut self.inner;
+        self.lens.with(data, |data| {
+            inner.compute_min_intrinsic(axis, ctx, bc, data, env)
+        })
+    }
+
     #[instrument(name = "LensWrap", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         let inner = &mut self.inner;
@@ -137,6 +167,10 @@ where
     fn id(&self) -> Option<WidgetId> {
         self.inner.id()
     }
+
+    fn depends_on(&self) -> ChangeSet {
+        self.lens.depends_on()
+    }
 }
 
 impl<T, U, L, W> WidgetWrapper for LensWrap<T, U, L, W> {
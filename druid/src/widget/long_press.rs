@@ -0,0 +1,210 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that recognizes long presses.
+
+use std::time::Duration;
+
+use crate::widget::Controller;
+use crate::{
+    theme, Data, Env, Event, EventCtx, MouseButton, Point, Selector, TimerToken, TouchId, Widget,
+};
+use tracing::{instrument, trace};
+
+/// A [`Controller`] that recognizes a press that is held in place, such as
+/// a touch-and-hold on a touch screen.
+///
+/// A long press starts when the left mouse button goes down or a touch screen
+/// is touched, and is recognized when it is held for [`LONG_PRESS_DURATION`]
+/// without moving more than [`LONG_PRESS_SLOP`] from where it went down. The
+/// duration and the distance can also be set with [`with_duration`] and
+/// [`with_slop`].
+///
+/// When a long press is recognized the action is called with the position of
+/// the press, and a [`LONG_PRESS`] notification is submitted, so an ancestor
+/// can handle long presses of its descendants. The mouse up or touch up that
+/// ends a long press is not delivered to the child, so that the press is not
+/// also handled as a click.
+///
+/// This is available as an `on_long_press` method via [`WidgetExt`].
+///
+/// [`LONG_PRESS_DURATION`]: crate::theme::LONG_PRESS_DURATION
+/// [`LONG_PRESS_SLOP`]: crate::theme::LONG_PRESS_SLOP
+/// [`with_duration`]: LongPress::with_duration
+/// [`with_slop`]: LongPress::with_slop
+/// [`LONG_PRESS`]: LongPress::LONG_PRESS
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct LongPress<T> {
+    action: LongPressFn<T>,
+    duration: Option<Duration>,
+    slop: Option<f64>,
+    state: PressState,
+    /// The contact that started the current press, if it is a touch.
+    touch: Option<TouchId>,
+}
+
+type LongPressFn<T> = Box<dyn Fn(&mut EventCtx, Point, &mut T, &Env)>;
+
+enum PressState {
+    Idle,
+    /// The button or touch is down, and the timer is running.
+    Pending {
+        timer: TimerToken,
+        origin: Point,
+    },
+    /// The press was recognized, and the button or touch is still down.
+    Recognized,
+}
+
+impl LongPress<()> {
+    /// A notification submitted when a long press is recognized, with the
+    /// position of the press in the coordinate space of the widget.
+    pub const LONG_PRESS: Selector<Point> = Selector::new("druid-builtin.long-press");
+}
+
+impl<T: Data> LongPress<T> {
+    /// Create a new `LongPress` controller, that calls `action` with the
+    /// position of each long press.
+    pub fn new(action: impl Fn(&mut EventCtx, Point, &mut T, &Env) + 'static) -> Self {
+        LongPress {
+            action: Box::new(action),
+            duration: None,
+            slop: None,
+            state: PressState::Idle,
+            touch: None,
+        }
+    }
+
+    /// Builder-style method to set how long a press has to be held.
+    ///
+    /// The default is [`LONG_PRESS_DURATION`] from the [`Env`].
+    ///
+    /// [`LONG_PRESS_DURATION`]: crate::theme::LONG_PRESS_DURATION
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Builder-style method to set how far the mouse can move before a
+    /// press is no longer a long press.
+    ///
+    /// The default is [`LONG_PRESS_SLOP`] from the [`Env`].
+    ///
+    /// [`LONG_PRESS_SLOP`]: crate::theme::LONG_PRESS_SLOP
+    pub fn with_slop(mut self, slop: f64) -> Self {
+        self.slop = Some(slop);
+        self
+    }
+
+    fn start(&mut self, ctx: &mut EventCtx, pos: Point, env: &Env) {
+        // keep getting events if the press leaves the widget.
+        ctx.set_active(true);
+        let duration = self
+            .duration
+            .unwrap_or_else(|| Duration::from_millis(env.get(theme::LONG_PRESS_DURATION)));
+        self.state = PressState::Pending {
+            timer: ctx.request_timer(duration),
+            origin: pos,
+        };
+    }
+
+    fn check_slop(&mut self, ctx: &mut EventCtx, pos: Point, env: &Env) {
+        if let PressState::Pending { origin, .. } = self.state {
+            let slop = self.slop.unwrap_or_else(|| env.get(theme::LONG_PRESS_SLOP));
+            if pos.distance(origin) > slop {
+                trace!("Long press on {:?} cancelled", ctx.widget_id());
+                self.state = PressState::Idle;
+            }
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for LongPress<T> {
+    #[instrument(
+        name = "LongPress",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            // the mouse events the platform makes of a touch belong to the touch's press.
+            Event::MouseDown(mouse)
+                if mouse.button == MouseButton::Left
+                    && !ctx.is_disabled()
+                    && self.touch.is_none() =>
+            {
+                self.start(ctx, mouse.pos, env);
+            }
+            Event::MouseMove(mouse) if self.touch.is_none() => {
+                self.check_slop(ctx, mouse.pos, env);
+            }
+            Event::TouchDown(touch)
+                if matches!(self.state, PressState::Idle) && !ctx.is_disabled() =>
+            {
+                self.touch = Some(touch.id);
+                self.start(ctx, touch.pos, env);
+            }
+            Event::TouchMove(touch) if self.touch == Some(touch.id) => {
+                self.check_slop(ctx, touch.pos, env);
+            }
+            Event::TouchUp(touch) if self.touch == Some(touch.id) => {
+                self.touch = None;
+                if matches!(self.state, PressState::Recognized) {
+                    // the platform still sends the mouse up of a primary touch; swallow
+                    // that one too.
+                    if !touch.primary {
+                        self.state = PressState::Idle;
+                    }
+                    ctx.set_active(false);
+                    ctx.set_handled();
+                    ctx.request_paint();
+                } else {
+                    self.state = PressState::Idle;
+                    child.event(ctx, event, data, env);
+                    ctx.set_active(false);
+                }
+                return;
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left => {
+                let recognized = matches!(self.state, PressState::Recognized);
+                self.state = PressState::Idle;
+                if recognized {
+                    // the press was handled; don't let the child see a click.
+                    ctx.set_active(false);
+                    ctx.set_handled();
+                    ctx.request_paint();
+                } else {
+                    child.event(ctx, event, data, env);
+                    ctx.set_active(false);
+                }
+                return;
+            }
+            Event::Timer(token) => {
+                if let PressState::Pending { timer, origin } = self.state {
+                    if *token == timer {
+                        self.state = PressState::Recognized;
+                        trace!("Long press on {:?} recognized", ctx.widget_id());
+                        (self.action)(ctx, origin, data, env);
+                        ctx.submit_notification(LongPress::LONG_PRESS.with(origin));
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
@@ -20,86 +20,141 @@ mod widget_wrapper;
 
 mod added;
 mod align;
+mod animated;
 mod aspect_ratio_box;
+mod blur;
+mod board_grid;
 mod button;
+mod cassowary;
 mod checkbox;
 mod click;
 mod clip_box;
 mod common;
+mod computed;
+mod constraint_layout;
 mod container;
 mod controller;
 mod disable_if;
+mod drag;
 mod either;
 mod env_scope;
+mod external_sync;
 mod flex;
+mod focus_scope;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+mod future_widget;
+mod grid;
 mod identity_wrapper;
 mod image;
 mod invalidation;
 mod label;
 mod lens_wrap;
 mod list;
+mod long_press;
 mod maybe;
+pub(crate) mod mutable;
+mod opacity;
 mod padding;
 mod painter;
 mod parse;
+mod popover;
+mod prism_wrap;
 mod progress_bar;
 mod radio;
+mod responsive;
+mod rich_text_box;
+mod rubber_band;
 mod scope;
 mod scroll;
+mod shortcut_scope;
 mod sized_box;
 mod slider;
 mod spinner;
 mod split;
 mod stepper;
+mod sticky_header;
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 mod svg;
 mod switch;
 mod tabs;
+mod task_progress_bar;
 mod textbox;
+mod tour;
+mod undo_manager;
+mod validate;
 mod value_textbox;
 mod view_switcher;
 #[allow(clippy::module_inception)]
 mod widget;
 mod widget_ext;
+mod window_chrome;
+mod z_stack;
 
 pub use self::image::Image;
 pub use added::Added;
 pub use align::Align;
-pub use aspect_ratio_box::AspectRatioBox;
+pub use animated::Animated;
+pub use aspect_ratio_box::{AspectRatioBox, AspectRatioMode};
+pub(crate) use blur::gaussian_blur;
+pub use blur::Blur;
+pub use board_grid::{BoardCell, BoardCellState, BoardGrid};
 pub use button::Button;
 pub use checkbox::Checkbox;
 pub use click::Click;
 pub use clip_box::{ClipBox, Viewport};
 pub use common::FillStrat;
+pub use computed::Computed;
+pub use constraint_layout::{Constraint, ConstraintLayout, Expression, Item, Strength};
 pub use container::Container;
 pub use controller::{Controller, ControllerHost};
 pub use disable_if::DisabledIf;
+pub use drag::{Draggable, DropTarget};
 pub use either::Either;
 pub use env_scope::EnvScope;
+pub use external_sync::ExternalSync;
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use focus_scope::{FocusScope, TabIndex};
+#[cfg(feature = "async")]
+pub use future_widget::FutureWidget;
+pub use grid::{Grid, GridCell, TrackSize};
 pub use identity_wrapper::IdentityWrapper;
-pub use label::{Label, LabelText, LineBreaking, RawLabel};
+pub use label::{Label, LabelText, LineBreaking, RawLabel, TextOrientation};
 pub use lens_wrap::LensWrap;
 pub use list::{List, ListIter};
+pub use long_press::LongPress;
 pub use maybe::Maybe;
+pub use mutable::Mutable;
+pub use opacity::Opacity;
 pub use padding::Padding;
 pub use painter::{BackgroundBrush, Painter};
 pub use parse::Parse;
+pub use popover::{Popover, PopoverSide};
+pub use prism_wrap::PrismWrap;
 pub use progress_bar::ProgressBar;
 pub use radio::{Radio, RadioGroup};
+pub use responsive::Responsive;
+pub use rich_text_box::RichTextBox;
+pub use rubber_band::{RubberBand, RubberBandItem, RubberBandSelection};
 pub use scope::{DefaultScopePolicy, LensScopeTransfer, Scope, ScopePolicy, ScopeTransfer};
-pub use scroll::Scroll;
+pub use scroll::{Scroll, ScrollAnchor};
+pub use shortcut_scope::ShortcutScope;
 pub use sized_box::SizedBox;
 pub use slider::Slider;
 pub use spinner::Spinner;
 pub use split::Split;
 pub use stepper::Stepper;
+pub use sticky_header::StickyHeader;
 #[cfg(feature = "svg")]
 pub use svg::{Svg, SvgData};
 pub use switch::Switch;
 pub use tabs::{TabInfo, Tabs, TabsEdge, TabsPolicy, TabsState, TabsTransition};
+pub use task_progress_bar::TaskProgressBar;
 pub use textbox::TextBox;
+pub use tour::{Tour, TourStep};
+pub use undo_manager::UndoManager;
+pub use validate::{Validate, Validated, ValidationErrors};
 pub use value_textbox::{TextBoxEvent, ValidationDelegate, ValueTextBox};
 pub use view_switcher::ViewSwitcher;
 #[doc(hidden)]
@@ -107,6 +162,8 @@ pub use widget::{Widget, WidgetId};
 #[doc(hidden)]
 pub use widget_ext::WidgetExt;
 pub use widget_wrapper::WidgetWrapper;
+pub use window_chrome::{MaximizeButton, TitleBar, WindowFrame};
+pub use z_stack::{ZStack, ZStackParams};
 
 /// The types required to implement a `Widget`.
 ///
@@ -0,0 +1,134 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that can be mutated imperatively.
+
+use std::any::Any;
+
+use crate::commands::MUTATE_WIDGET;
+use crate::kurbo::Size;
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{Data, SingleUse};
+use tracing::{instrument, warn};
+
+/// The closure that mutates a widget of type `W`.
+type MutationFn<W> = Box<dyn FnOnce(&mut W, &mut EventCtx) + Send>;
+
+/// A wrapper that allows its child to be mutated directly, from outside
+/// the widget's own methods.
+///
+/// This is an escape hatch, for the rare state that can't reasonably be
+/// expressed through [`Data`]. A mutation is requested with
+/// [`EventCtx::mutate_later`], [`DelegateCtx::mutate_later`] or
+/// [`ExtEventSink::mutate_widget`], targeting the id of this widget; the
+/// closure is then run during the handling of a command, with mutable access
+/// to the child and an [`EventCtx`].
+///
+/// The closure is responsible for requesting a layout or paint if the
+/// mutation requires one.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, Mutable};
+/// use druid::{WidgetExt, WidgetId};
+///
+/// let id = WidgetId::next();
+/// let label = Mutable::new(Label::<()>::new("Working...")).with_id(id);
+///
+/// // later, for instance in response to an async completion:
+/// # fn mutate(ctx: &mut druid::DelegateCtx, id: WidgetId) {
+/// ctx.mutate_later(id, |label: &mut Label<()>, ctx| {
+///     label.set_text("Done");
+///     ctx.request_layout();
+/// });
+/// # }
+/// ```
+///
+/// [`EventCtx::mutate_later`]: crate::EventCtx::mutate_later
+/// [`DelegateCtx::mutate_later`]: crate::DelegateCtx::mutate_later
+/// [`ExtEventSink::mutate_widget`]: crate::ExtEventSink::mutate_widget
+pub struct Mutable<W> {
+    inner: W,
+}
+
+impl<W> Mutable<W> {
+    /// Wrap a widget so that it can be mutated.
+    pub fn new(inner: W) -> Mutable<W> {
+        Mutable { inner }
+    }
+}
+
+/// Create the payload for a [`MUTATE_WIDGET`] command.
+pub(crate) fn mutation<W: 'static>(
+    f: impl FnOnce(&mut W, &mut EventCtx) + Send + 'static,
+) -> SingleUse<Box<dyn Any + Send>> {
+    let f: MutationFn<W> = Box::new(f);
+    SingleUse::new(Box::new(f))
+}
+
+impl<T: Data, W: Widget<T> + 'static> Widget<T> for Mutable<W> {
+    #[instrument(name = "Mutable", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(payload) = cmd.get(MUTATE_WIDGET) {
+                ctx.set_handled();
+                match payload.take().map(|f| f.downcast::<MutationFn<W>>()) {
+                    Some(Ok(f)) => f(&mut self.inner, ctx),
+                    Some(Err(_)) => warn!(
+                        "mutation of widget {:?} expected a different widget type than {}",
+                        ctx.widget_id(),
+                        std::any::type_name::<W>()
+                    ),
+                    None => warn!("mutation of widget {:?} already run", ctx.widget_id()),
+                }
+                return;
+            }
+        }
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Mutable", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "Mutable",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "Mutable", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "Mutable", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.inner.id()
+    }
+}
+
+impl<W> WidgetWrapper for Mutable<W> {
+    widget_wrapper_body!(W, inner);
+}
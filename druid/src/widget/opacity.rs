@@ -0,0 +1,211 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that paints its child translucently.
+
+use std::time::Duration;
+
+use tracing::{instrument, warn};
+
+use crate::animation::{Animation, Easing, Interpolate};
+use crate::core::Layer;
+use crate::piet::InterpolationMode;
+use crate::widget::prelude::*;
+use crate::{Data, KeyOrValue, Point, WidgetPod};
+
+enum OpacitySource<T> {
+    Fixed(KeyOrValue<f64>),
+    Dynamic(Box<dyn Fn(&T, &Env) -> f64>),
+}
+
+/// A widget that paints its child with the given opacity.
+///
+/// The child is painted into an offscreen layer as a whole, and the layer is
+/// then blended with what is below it. Overlapping parts of the child, such
+/// as a label on a button, don't show through each other, as they would if
+/// each of them was painted with a translucent color.
+///
+/// The layer covers the child's paint rect, so it includes what the child
+/// paints outside of its layout rect, such as a shadow. It is kept while the
+/// child doesn't change, so an opacity animation doesn't repaint the child on
+/// every frame. An opacity of `1.0` paints the child directly, and an opacity
+/// of `0.0` doesn't paint it at all; the child still gets events either way.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{Label, Opacity};
+/// use druid::{Widget, WidgetExt};
+///
+/// // A label that fades in and out when `visible` changes.
+/// fn fading_label() -> impl Widget<bool> {
+///     Label::new("Now you see me")
+///         .opacity_with(|visible: &bool, _| if *visible { 1.0 } else { 0.0 })
+///         .animate_changes(Duration::from_millis(250))
+/// }
+/// ```
+pub struct Opacity<T, W> {
+    child: WidgetPod<T, W>,
+    opacity: OpacitySource<T>,
+    /// The opacity the child is painted with.
+    shown: f64,
+    /// The opacity changes are animated to, and their animation, if any.
+    target: f64,
+    animation: Option<(Duration, Easing)>,
+    running: Option<(f64, Animation)>,
+    /// The child's layer with the `shown` opacity applied, and that opacity.
+    faded: Option<(f64, Layer)>,
+}
+
+impl<T: Data, W: Widget<T>> Opacity<T, W> {
+    /// Create a widget that paints `child` with the given opacity.
+    ///
+    /// The opacity can be a value from `0.0` to `1.0`, or a [`Key`] of one
+    /// in the [`Env`].
+    ///
+    /// [`Key`]: crate::Key
+    pub fn new(child: W, opacity: impl Into<KeyOrValue<f64>>) -> Self {
+        Opacity::with_source(child, OpacitySource::Fixed(opacity.into()))
+    }
+
+    /// Create a widget that paints `child` with an opacity computed from
+    /// the data.
+    pub fn dynamic(child: W, opacity: impl Fn(&T, &Env) -> f64 + 'static) -> Self {
+        Opacity::with_source(child, OpacitySource::Dynamic(Box::new(opacity)))
+    }
+
+    fn with_source(child: W, opacity: OpacitySource<T>) -> Self {
+        Opacity {
+            child: WidgetPod::new(child),
+            opacity,
+            shown: 1.0,
+            target: 1.0,
+            animation: None,
+            running: None,
+            faded: None,
+        }
+    }
+
+    /// Builder-style method to animate changes of the opacity over `duration`.
+    pub fn animate_changes(self, duration: Duration) -> Self {
+        self.animate_changes_with_easing(duration, Easing::default())
+    }
+
+    /// Builder-style method to animate changes of the opacity over
+    /// `duration`, with the given easing.
+    pub fn animate_changes_with_easing(mut self, duration: Duration, easing: Easing) -> Self {
+        self.animation = Some((duration, easing));
+        self
+    }
+
+    fn resolve(&self, data: &T, env: &Env) -> f64 {
+        let opacity = match &self.opacity {
+            OpacitySource::Fixed(opacity) => opacity.resolve(env),
+            OpacitySource::Dynamic(f) => f(data, env),
+        };
+        opacity.clamp(0.0, 1.0)
+    }
+
+    /// Paint the child into a layer, and draw it with the `shown` opacity.
+    fn paint_layer(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let layer = match self.child.paint_layer(ctx, data, env) {
+            Ok((layer, repainted)) => {
+                if repainted {
+                    self.faded = None;
+                }
+                layer
+            }
+            Err(e) => {
+                warn!("failed to paint the layer of {:?}: {}", ctx.widget_id(), e);
+                self.child.paint(ctx, data, env);
+                return;
+            }
+        };
+        let shown = self.shown;
+        let faded = match &mut self.faded {
+            Some((opacity, faded)) if *opacity == shown => faded,
+            faded => &mut faded.insert((shown, layer.with_opacity(shown))).1,
+        };
+        let image = faded.image(ctx);
+        let rect = self.child.paint_rect();
+        ctx.draw_image(&image, rect, InterpolationMode::Bilinear);
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Opacity<T, W> {
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let (Event::AnimFrame(interval), Some((from, animation))) = (event, &mut self.running) {
+            if animation.advance(*interval) {
+                self.shown = from.interpolate(&self.target, animation.progress());
+                ctx.request_anim_frame();
+            } else {
+                self.shown = self.target;
+                self.running = None;
+            }
+            ctx.request_paint();
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.target = self.resolve(data, env);
+            self.shown = self.target;
+        }
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "Opacity",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let opacity = self.resolve(data, env);
+        if opacity != self.target {
+            self.target = opacity;
+            match self.animation {
+                Some((duration, easing)) => {
+                    self.running = Some((self.shown, Animation::new(duration, easing)));
+                    ctx.request_anim_frame();
+                }
+                None => {
+                    self.shown = opacity;
+                    ctx.request_paint();
+                }
+            }
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.paint_insets());
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Opacity", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.shown >= 1.0 {
+            self.child.paint(ctx, data, env);
+        } else if self.shown > 0.0 {
+            self.paint_layer(ctx, data, env);
+        }
+    }
+}
@@ -14,8 +14,8 @@
 
 //! A widget that just adds padding during layout.
 
-use crate::widget::{prelude::*, WidgetWrapper};
-use crate::{Data, Insets, KeyOrValue, Point, WidgetPod};
+use crate::widget::{prelude::*, Axis, WidgetWrapper};
+use crate::{theme, Data, Insets, KeyOrValue, Point, WidgetPod};
 
 use tracing::{instrument, trace};
 
@@ -33,6 +33,8 @@ impl<T, W: Widget<T>> Padding<T, W> {
     /// an `f64` for uniform padding, an `(f64, f64)` for axis-uniform padding,
     /// or `(f64, f64, f64, f64)` (left, top, right, bottom) values.
     ///
+    /// If [`theme::LAYOUT_RTL`] is set, the left and right insets are swapped.
+    ///
     /// # Examples
     ///
     /// Uniform padding:
@@ -58,12 +60,26 @@ impl<T, W: Widget<T>> Padding<T, W> {
     /// ```
     ///
     /// [`Key`]: crate::Key
+    /// [`theme::LAYOUT_RTL`]: crate::theme::LAYOUT_RTL
     pub fn new(insets: impl Into<KeyOrValue<Insets>>, child: W) -> Padding<T, W> {
         Padding {
             insets: insets.into(),
             child: WidgetPod::new(child),
         }
     }
+
+    /// The constraints of the child when measuring it along `axis`, and the
+    /// padding along that axis.
+    fn child_intrinsic_constraints(
+        &self,
+        axis: Axis,
+        bc: &BoxConstraints,
+        env: &Env,
+    ) -> (BoxConstraints, f64) {
+        let insets = self.insets.resolve(env);
+        let padding = Size::new(insets.x0 + insets.x1, insets.y0 + insets.y1);
+        (bc.shrink(padding), axis.major(padding))
+    }
 }
 
 impl<T, W> WidgetWrapper for Padding<T, W> {
@@ -83,7 +99,7 @@ impl<T: Data, W: Widget<T>> Widget<T> for Padding<T, W> {
 
     #[instrument(name = "Padding", level = "trace", skip(self, ctx, _old, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
-        if ctx.env_key_changed(&self.insets) {
+        if ctx.env_key_changed(&self.insets) || ctx.env_key_changed(&theme::LAYOUT_RTL) {
             ctx.request_layout();
         }
         self.child.update(ctx, data, env);
@@ -92,7 +108,10 @@ impl<T: Data, W: Widget<T>> Widget<T> for Padding<T, W> {
     #[instrument(name = "Padding", level = "trace", skip(self, ctx, bc, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Padding");
-        let insets = self.insets.resolve(env);
+        let mut insets = self.insets.resolve(env);
+        if env.get(theme::LAYOUT_RTL) {
+            std::mem::swap(&mut insets.x0, &mut insets.x1);
+        }
 
         let hpad = insets.x0 + insets.x1;
         let vpad = insets.y0 + insets.y1;
@@ -109,6 +128,36 @@ impl<T: Data, W: Widget<T>> Widget<T> for Padding<T, W> {
         my_size
     }
 
+    #[instrument(name = "Padding", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let (child_bc, padding) = self.child_intrinsic_constraints(axis, bc, env);
+        self.child
+            .compute_max_intrinsic(axis, ctx, &child_bc, data, env)
+            + padding
+    }
+
+    #[instrument(name = "Padding", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let (child_bc, padding) = self.child_intrinsic_constraints(axis, bc, env);
+        self.child
+            .compute_min_intrinsic(axis, ctx, &child_bc, data, env)
+            + padding
+    }
+
     #[instrument(name = "Padding", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.child.paint(ctx, data, env);
@@ -72,10 +72,56 @@ use tracing::instrument;
 /// });
 /// ```
 ///
+/// **Exporting what was painted**
+///
+/// Give the `Painter` an id, and ask for an image of it with
+/// [`capture_image`]. It is painted into the image with the same closure that
+/// paints it on screen, and the image is sent back as an [`IMAGE_CAPTURED`]
+/// command:
+///
+/// ```
+/// use druid::commands::IMAGE_CAPTURED;
+/// use druid::widget::{Controller, Painter};
+/// use druid::{Color, Env, Event, EventCtx, RenderContext, Selector, Widget, WidgetExt, WidgetId};
+///
+/// const EXPORT_CHART: Selector = Selector::new("my-app.export-chart");
+///
+/// struct ChartExport(WidgetId);
+///
+/// impl<W: Widget<f64>> Controller<f64, W> for ChartExport {
+///     fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
+///         match event {
+///             Event::Command(cmd) if cmd.is(EXPORT_CHART) => ctx.capture_image(self.0, 2.0),
+///             Event::Command(cmd) if cmd.is(IMAGE_CAPTURED) => {
+///                 let (_, image) = cmd.get_unchecked(IMAGE_CAPTURED);
+///                 // write `image` to a PNG file
+///             }
+///             _ => child.event(ctx, event, data, env),
+///         }
+///     }
+/// }
+///
+/// fn chart() -> impl Widget<f64> {
+///     let chart_id = WidgetId::next();
+///     Painter::new(|ctx, value: &f64, _env| {
+///         let bar = ctx.size().to_rect().with_size((ctx.size().width * value, 20.0));
+///         ctx.fill(bar, &Color::rgb8(0x00, 0x8d, 0xdd));
+///     })
+///     .with_id(chart_id)
+///     .controller(ChartExport(chart_id))
+/// }
+/// ```
+///
+/// A widget that draws itself can also use [`PaintCtx::paint_to_image`]
+/// directly, to paint into an image while it paints.
+///
 /// [`paint`]: ../trait.Widget.html#tymethod.paint
 /// [`Data`]: ../trait.Data.html
 /// [`request_paint`]: ../EventCtx.html#method.request_paint
 /// [`Controller`]: trait.Controller.html
+/// [`capture_image`]: crate::EventCtx::capture_image
+/// [`IMAGE_CAPTURED`]: crate::commands::IMAGE_CAPTURED
+/// [`PaintCtx::paint_to_image`]: crate::PaintCtx::paint_to_image
 pub struct Painter<T>(Box<dyn FnMut(&mut PaintCtx, &T, &Env)>);
 
 /// Something that can be used as the background for a widget.
@@ -0,0 +1,527 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows floating content next to a target widget.
+
+use crate::kurbo::BezPath;
+use crate::widget::prelude::*;
+use crate::{theme, Data, KbKey, Point, Rect, Selector, Vec2, WidgetPod};
+use tracing::{instrument, trace};
+
+/// The size of the arrow that points from a popover to its target.
+pub(super) const ARROW_SIZE: f64 = 8.0;
+const CORNER_RADIUS: f64 = 4.0;
+
+// Sent to a Popover by itself once the focus chain includes its content.
+const FOCUS_CONTENT: Selector = Selector::new("druid-builtin.popover-focus-content");
+
+/// The side of its target that a [`Popover`] is shown on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopoverSide {
+    /// Above the target.
+    Above,
+    /// Below the target.
+    Below,
+    /// To the left of the target.
+    Left,
+    /// To the right of the target.
+    Right,
+}
+
+/// A widget that shows floating content next to a target widget, such as a
+/// picker, a menu or a tip.
+///
+/// The popover wraps its target, and is laid out with the target's size. When
+/// it is shown, its content is drawn on a bubble on the preferred
+/// [`side`] of the target, with an arrow pointing at the target. If the
+/// content does not fit between the target and the edge of the window on
+/// that side, but does fit on the opposite side, it is shown there instead;
+/// the bubble is also moved along the target's edge to stay inside the
+/// window.
+///
+/// The popover is shown when it receives a [`SHOW`] notification from its
+/// target or its content, or a [`SHOW`] command sent to its id, and is
+/// dismissed in the same way with [`DISMISS`]. It is also dismissed when
+/// the mouse is pressed outside of the content, or escape is pressed; that
+/// mouse press is not delivered to any other widget. A [`DISMISSED`]
+/// notification is submitted whenever the popover is dismissed.
+///
+/// If the popover [traps focus], its first focusable descendant is focused
+/// when it is shown, and tab and shift-tab move focus between the focusable
+/// widgets of the content only.
+///
+/// The content is painted with the popover, so widgets that are painted
+/// after it, and any clipping ancestor such as a [`Scroll`], can cover it.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Label, Popover, PopoverSide};
+/// use druid::WidgetExt;
+///
+/// let info = Popover::new(
+///     Button::new("Info").on_click(|ctx, _data, _env| {
+///         ctx.submit_notification(Popover::SHOW);
+///     }),
+///     Label::new("Druid is a data-first Rust-native UI toolkit.").padding(8.0),
+/// )
+/// .with_side(PopoverSide::Right);
+/// # let _: Popover<()> = info;
+/// ```
+///
+/// [`side`]: Popover::with_side
+/// [`SHOW`]: Popover::SHOW
+/// [`DISMISS`]: Popover::DISMISS
+/// [`DISMISSED`]: Popover::DISMISSED
+/// [traps focus]: Popover::with_focus_trap
+/// [`Scroll`]: crate::widget::Scroll
+pub struct Popover<T> {
+    target: WidgetPod<T, Box<dyn Widget<T>>>,
+    content: WidgetPod<T, Box<dyn Widget<T>>>,
+    side: PopoverSide,
+    trap_focus: bool,
+    is_open: bool,
+    /// The side the content was placed on in the last layout.
+    placed_side: PopoverSide,
+    /// The origin of the popover in window coordinates, used to keep the
+    /// content inside the window.
+    window_origin: Point,
+}
+
+impl Popover<()> {
+    /// Show the popover.
+    ///
+    /// This can be submitted as a notification from within the popover, or
+    /// as a command to the popover's id.
+    pub const SHOW: Selector = Selector::new("druid-builtin.popover-show");
+
+    /// Dismiss the popover.
+    ///
+    /// This can be submitted as a notification from within the popover, or
+    /// as a command to the popover's id.
+    pub const DISMISS: Selector = Selector::new("druid-builtin.popover-dismiss");
+
+    /// A notification submitted when the popover is dismissed.
+    pub const DISMISSED: Selector = Selector::new("druid-builtin.popover-dismissed");
+}
+
+impl<T: Data> Popover<T> {
+    /// Create a new `Popover`, that shows `content` next to `target`.
+    ///
+    /// The popover is shown below the target by default.
+    pub fn new(target: impl Widget<T> + 'static, content: impl Widget<T> + 'static) -> Self {
+        Popover {
+            target: WidgetPod::new(target).boxed(),
+            content: WidgetPod::new(content).boxed(),
+            side: PopoverSide::Below,
+            trap_focus: false,
+            is_open: false,
+            placed_side: PopoverSide::Below,
+            window_origin: Point::ORIGIN,
+        }
+    }
+
+    /// Builder-style method to set the side of the target the content is
+    /// shown on, when it fits.
+    pub fn with_side(mut self, side: PopoverSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Builder-style method to set whether keyboard focus is kept within the
+    /// content while the popover is shown.
+    pub fn with_focus_trap(mut self, trap_focus: bool) -> Self {
+        self.trap_focus = trap_focus;
+        self
+    }
+
+    /// Set the side of the target the content is shown on, when it fits.
+    pub fn set_side(&mut self, side: PopoverSide) {
+        self.side = side;
+    }
+
+    /// Whether the popover is currently shown.
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    fn set_open(&mut self, ctx: &mut EventCtx, open: bool) {
+        if open == self.is_open {
+            return;
+        }
+        trace!("Popover {:?} open: {}", ctx.widget_id(), open);
+        self.is_open = open;
+        // the content is hidden while the popover is dismissed.
+        ctx.children_changed();
+        // capture the mouse, to see presses outside of the content.
+        ctx.set_active(open);
+        if open {
+            self.window_origin = ctx.window_origin();
+            if self.trap_focus {
+                ctx.submit_command(FOCUS_CONTENT.to(ctx.widget_id()));
+            }
+        } else {
+            if self.content.has_focus() {
+                ctx.resign_focus();
+            }
+            ctx.submit_notification(Popover::DISMISSED);
+        }
+        ctx.request_layout();
+    }
+
+    /// Move focus to the next, or previous, focusable widget of the content.
+    fn cycle_focus(&self, ctx: &mut EventCtx, forward: bool) -> bool {
+        let chain = self.content.focus_chain();
+        if chain.is_empty() {
+            return false;
+        }
+        let current = ctx
+            .state
+            .focus_widget
+            .and_then(|focus| chain.iter().position(|id| *id == focus));
+        let len = chain.len();
+        let next = match current {
+            Some(idx) if forward => (idx + 1) % len,
+            Some(idx) => (idx + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        ctx.set_focus(chain[next]);
+        true
+    }
+
+    /// The content area of the window, in window coordinates, if it is known.
+    fn window_bounds(ctx: &LayoutCtx) -> Option<Rect> {
+        let window = ctx.window();
+        let size = window.get_size() - window.content_insets().size();
+        if size.width > 0. && size.height > 0. {
+            Some(size.to_rect())
+        } else {
+            None
+        }
+    }
+}
+
+impl PopoverSide {
+    fn opposite(self) -> PopoverSide {
+        match self {
+            PopoverSide::Above => PopoverSide::Below,
+            PopoverSide::Below => PopoverSide::Above,
+            PopoverSide::Left => PopoverSide::Right,
+            PopoverSide::Right => PopoverSide::Left,
+        }
+    }
+
+    /// The rect of content with `size` on this side of `target`, centered
+    /// on it, `gap` away from it.
+    fn place(self, target: Rect, size: Size, gap: f64) -> Rect {
+        let center = target.center();
+        let origin = match self {
+            PopoverSide::Above => {
+                Point::new(center.x - size.width / 2., target.y0 - gap - size.height)
+            }
+            PopoverSide::Below => Point::new(center.x - size.width / 2., target.y1 + gap),
+            PopoverSide::Left => {
+                Point::new(target.x0 - gap - size.width, center.y - size.height / 2.)
+            }
+            PopoverSide::Right => Point::new(target.x1 + gap, center.y - size.height / 2.),
+        };
+        Rect::from_origin_size(origin, size)
+    }
+
+    /// Whether `rect`, placed on this side, stays inside `bounds` on that side.
+    fn fits(self, rect: Rect, bounds: Rect) -> bool {
+        match self {
+            PopoverSide::Above => rect.y0 >= bounds.y0,
+            PopoverSide::Below => rect.y1 <= bounds.y1,
+            PopoverSide::Left => rect.x0 >= bounds.x0,
+            PopoverSide::Right => rect.x1 <= bounds.x1,
+        }
+    }
+}
+
+/// Place content with `size` next to `target`, preferably on `side`, keeping
+/// it inside `bounds` when possible.
+pub(super) fn place_content(
+    side: PopoverSide,
+    target: Rect,
+    size: Size,
+    gap: f64,
+    bounds: Option<Rect>,
+) -> (PopoverSide, Rect) {
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => return (side, side.place(target, size, gap)),
+    };
+
+    let mut rect = side.place(target, size, gap);
+    let mut side = side;
+    if !side.fits(rect, bounds) {
+        let flipped = side.opposite().place(target, size, gap);
+        if side.opposite().fits(flipped, bounds) {
+            side = side.opposite();
+            rect = flipped;
+        }
+    }
+
+    // slide along the target's edge to stay inside the window.
+    let shift_into = |start: f64, end: f64, min: f64, max: f64| {
+        if end > max {
+            (max - end).max(min - start)
+        } else if start < min {
+            min - start
+        } else {
+            0.
+        }
+    };
+    let shift = match side {
+        PopoverSide::Above | PopoverSide::Below => {
+            Vec2::new(shift_into(rect.x0, rect.x1, bounds.x0, bounds.x1), 0.)
+        }
+        PopoverSide::Left | PopoverSide::Right => {
+            Vec2::new(0., shift_into(rect.y0, rect.y1, bounds.y0, bounds.y1))
+        }
+    };
+    (side, rect + shift)
+}
+
+/// The outline of a bubble covering `rect`, with an arrow on the edge facing
+/// a target on `side`, pointing at `tip`.
+pub(super) fn bubble_path(rect: Rect, side: PopoverSide, tip: Point) -> BezPath {
+    let r = CORNER_RADIUS.min(rect.width() / 2.).min(rect.height() / 2.);
+    let s = ARROW_SIZE;
+    // keep the arrow clear of the corners.
+    let ax = tip.x.min(rect.x1 - r - s).max(rect.x0 + r + s);
+    let ay = tip.y.min(rect.y1 - r - s).max(rect.y0 + r + s);
+
+    let mut path = BezPath::new();
+    path.move_to((rect.x0 + r, rect.y0));
+    // the content is below its target, so the arrow is on its top edge.
+    if side == PopoverSide::Below {
+        path.line_to((ax - s, rect.y0));
+        path.line_to((ax, rect.y0 - s));
+        path.line_to((ax + s, rect.y0));
+    }
+    path.line_to((rect.x1 - r, rect.y0));
+    path.quad_to((rect.x1, rect.y0), (rect.x1, rect.y0 + r));
+    if side == PopoverSide::Left {
+        path.line_to((rect.x1, ay - s));
+        path.line_to((rect.x1 + s, ay));
+        path.line_to((rect.x1, ay + s));
+    }
+    path.line_to((rect.x1, rect.y1 - r));
+    path.quad_to((rect.x1, rect.y1), (rect.x1 - r, rect.y1));
+    if side == PopoverSide::Above {
+        path.line_to((ax + s, rect.y1));
+        path.line_to((ax, rect.y1 + s));
+        path.line_to((ax - s, rect.y1));
+    }
+    path.line_to((rect.x0 + r, rect.y1));
+    path.quad_to((rect.x0, rect.y1), (rect.x0, rect.y1 - r));
+    if side == PopoverSide::Right {
+        path.line_to((rect.x0, ay + s));
+        path.line_to((rect.x0 - s, ay));
+        path.line_to((rect.x0, ay - s));
+    }
+    path.line_to((rect.x0, rect.y0 + r));
+    path.quad_to((rect.x0, rect.y0), (rect.x0 + r, rect.y0));
+    path.close_path();
+    path
+}
+
+impl<T: Data> Widget<T> for Popover<T> {
+    #[instrument(name = "Popover", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Notification(note) if note.is(Popover::SHOW) => {
+                self.set_open(ctx, true);
+                ctx.set_handled();
+                return;
+            }
+            Event::Notification(note) if note.is(Popover::DISMISS) => {
+                self.set_open(ctx, false);
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(Popover::SHOW) => {
+                self.set_open(ctx, true);
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(Popover::DISMISS) => {
+                self.set_open(ctx, false);
+                ctx.set_handled();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(FOCUS_CONTENT) => {
+                if self.is_open {
+                    self.cycle_focus(ctx, true);
+                }
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+
+        if !self.is_open {
+            self.target.event(ctx, event, data, env);
+            if event.should_propagate_to_hidden() {
+                self.content.event(ctx, event, data, env);
+            }
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) if !self.content.layout_rect().contains(mouse.pos) => {
+                self.set_open(ctx, false);
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key) if key.key == KbKey::Escape => {
+                self.set_open(ctx, false);
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key)
+                if key.key == KbKey::Tab
+                    && self.trap_focus
+                    && self.cycle_focus(ctx, !key.mods.shift()) =>
+            {
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+
+        // the mouse belongs to the content while the popover is shown.
+        if !matches!(
+            event,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+        ) {
+            self.target.event(ctx, event, data, env);
+        }
+        self.content.event(ctx, event, data, env);
+
+        if self.is_open && ctx.window_origin() != self.window_origin {
+            self.window_origin = ctx.window_origin();
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Popover", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.target.lifecycle(ctx, event, data, env);
+        if self.is_open || event.should_propagate_to_hidden() {
+            self.content.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Popover",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.target.update(ctx, data, env);
+        self.content.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Popover", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Popover");
+
+        let size = self.target.layout(ctx, bc, data, env);
+        self.target.set_origin(ctx, data, env, Point::ORIGIN);
+        if !self.is_open {
+            ctx.set_paint_insets(self.target.paint_insets());
+            return size;
+        }
+
+        let bounds = Self::window_bounds(ctx);
+        let content_bc = match bounds {
+            Some(bounds) => BoxConstraints::new(Size::ZERO, bounds.size()),
+            None => BoxConstraints::UNBOUNDED,
+        };
+        let content_size = self.content.layout(ctx, &content_bc, data, env);
+
+        let target = Rect::from_origin_size(self.window_origin, size);
+        let (side, rect) = place_content(self.side, target, content_size, ARROW_SIZE, bounds);
+        self.placed_side = side;
+        let origin = rect.origin() - self.window_origin.to_vec2();
+        self.content.set_origin(ctx, data, env, origin);
+
+        let own_rect = size.to_rect();
+        let covered = own_rect
+            .union(self.target.paint_rect())
+            .union(self.content.paint_rect())
+            .union(self.content.layout_rect().inflate(ARROW_SIZE, ARROW_SIZE));
+        ctx.set_paint_insets(covered - own_rect);
+        size
+    }
+
+    #[instrument(name = "Popover", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.target.paint(ctx, data, env);
+        if !self.is_open {
+            return;
+        }
+
+        let rect = self.content.layout_rect();
+        let path = bubble_path(rect, self.placed_side, ctx.size().to_rect().center());
+        ctx.fill(&path, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(&path, &env.get(theme::BORDER_DARK), 1.0);
+        self.content.paint(ctx, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    const TARGET: Rect = Rect::new(100., 100., 140., 120.);
+    const SIZE: Size = Size::new(60., 30.);
+
+    #[test]
+    fn placed_on_preferred_side() {
+        let bounds = Some(Rect::new(0., 0., 400., 400.));
+        let (side, rect) = place_content(PopoverSide::Below, TARGET, SIZE, 8., bounds);
+        assert_eq!(side, PopoverSide::Below);
+        assert_eq!(rect, Rect::new(90., 128., 150., 158.));
+    }
+
+    #[test]
+    fn flips_near_edge() {
+        let bounds = Some(Rect::new(0., 0., 400., 140.));
+        let (side, rect) = place_content(PopoverSide::Below, TARGET, SIZE, 8., bounds);
+        assert_eq!(side, PopoverSide::Above);
+        assert_eq!(rect, Rect::new(90., 62., 150., 92.));
+
+        // no room on either side; keep the preferred one.
+        let bounds = Some(Rect::new(0., 80., 400., 140.));
+        let (side, _) = place_content(PopoverSide::Below, TARGET, SIZE, 8., bounds);
+        assert_eq!(side, PopoverSide::Below);
+    }
+
+    #[test]
+    fn shifted_into_bounds() {
+        let bounds = Some(Rect::new(95., 0., 400., 400.));
+        let (_, rect) = place_content(PopoverSide::Below, TARGET, SIZE, 8., bounds);
+        assert_eq!(rect.x0, 95.);
+
+        let bounds = Some(Rect::new(0., 0., 145., 400.));
+        let (_, rect) = place_content(PopoverSide::Below, TARGET, SIZE, 8., bounds);
+        assert_eq!(rect.x1, 145.);
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows a child for one variant of its data.
+
+use std::marker::PhantomData;
+
+use crate::widget::prelude::*;
+use crate::{Point, Prism, WidgetPod};
+use tracing::{instrument, trace};
+
+/// A widget that shows a child only while its data is one variant of a sum
+/// type, like an enum, as picked by a [`Prism`].
+///
+/// The child gets the data of the variant, and its changes are written back
+/// into the variant. While the data is another variant, nothing is shown,
+/// and the `PrismWrap` takes up no more space than its constraints require.
+///
+/// The child is created with the provided closure each time the data becomes
+/// the variant, so it starts out fresh.
+///
+/// Stack several of these, for instance in a [`ZStack`], to show a different
+/// view for each variant.
+///
+/// ```
+/// use druid::widget::{Flex, Label, PrismWrap, TextBox};
+/// use druid::{Data, Prism, Widget};
+///
+/// #[derive(Clone, Data, Prism)]
+/// enum Page {
+///     Loading,
+///     Editor(String),
+/// }
+///
+/// fn page() -> impl Widget<Page> {
+///     Flex::column()
+///         .with_child(PrismWrap::new(Page::loading, || Label::new("Loading…")))
+///         .with_child(PrismWrap::new(Page::editor, TextBox::new))
+/// }
+/// ```
+///
+/// [`ZStack`]: crate::widget::ZStack
+pub struct PrismWrap<T, U, P> {
+    prism: P,
+    maker: Box<dyn Fn() -> Box<dyn Widget<U>>>,
+    child: Option<WidgetPod<U, Box<dyn Widget<U>>>>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Data, U: Data, P: Prism<T, U>> PrismWrap<T, U, P> {
+    /// Create a new `PrismWrap`, that shows the widget created by `maker`
+    /// while the data is the variant that `prism` focuses on.
+    pub fn new<W: Widget<U> + 'static>(prism: P, maker: impl Fn() -> W + 'static) -> Self {
+        PrismWrap {
+            prism,
+            maker: Box::new(move || Box::new(maker())),
+            child: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create or drop the child, to follow whether the data is the variant.
+    fn rebuild_child(&mut self, data: &T) {
+        self.child = if self.prism.matches(data) {
+            Some(WidgetPod::new((self.maker)()))
+        } else {
+            None
+        };
+    }
+}
+
+impl<T: Data, U: Data, P: Prism<T, U>> Widget<T> for PrismWrap<T, U, P> {
+    #[instrument(name = "PrismWrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some(child) = self.child.as_mut() {
+            self.prism
+                .with_mut(data, |data| child.event(ctx, event, data, env));
+        }
+    }
+
+    #[instrument(name = "PrismWrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rebuild_child(data);
+        }
+        // until the next update, the data may be another variant than the child's.
+        if let Some(child) = self.child.as_mut() {
+            self.prism
+                .with(data, |data| child.lifecycle(ctx, event, data, env));
+        }
+    }
+
+    #[instrument(
+        name = "PrismWrap",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let matches = self.prism.matches(data);
+        if self.prism.matches(old_data) != matches || self.child.is_some() != matches {
+            self.rebuild_child(data);
+            ctx.children_changed();
+        } else if let Some(child) = self.child.as_mut() {
+            self.prism.with(data, |data| child.update(ctx, data, env));
+        }
+    }
+
+    #[instrument(name = "PrismWrap", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let child = match self.child.as_mut() {
+            Some(child) => child,
+            None => return bc.min(),
+        };
+        let size = self
+            .prism
+            .with(data, |data| {
+                let size = child.layout(ctx, bc, data, env);
+                child.set_origin(ctx, data, env, Point::ORIGIN);
+                size
+            })
+            .unwrap_or_else(|| bc.min());
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PrismWrap", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if let Some(child) = self.child.as_mut() {
+            self.prism.with(data, |data| child.paint(ctx, data, env));
+        }
+    }
+}
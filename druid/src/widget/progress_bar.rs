@@ -82,10 +82,9 @@ impl Widget<f64> for ProgressBar {
         let stroke_width = 2.0;
         let inset = -stroke_width / 2.0;
         let size = ctx.size();
-        let rounded_rect = Size::new(size.width, height)
-            .to_rect()
-            .inset(inset)
-            .to_rounded_rect(corner_radius);
+        let rect = Size::new(size.width, height).to_rect().inset(inset);
+        let (rect, stroke_width) = ctx.snap_stroke(rect, stroke_width, env);
+        let rounded_rect = rect.to_rounded_rect(corner_radius);
 
         // Paint the border
         ctx.stroke(rounded_rect, &env.get(theme::BORDER_DARK), stroke_width);
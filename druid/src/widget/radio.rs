@@ -17,7 +17,7 @@
 use crate::kurbo::Circle;
 use crate::widget::prelude::*;
 use crate::widget::{CrossAxisAlignment, Flex, Label, LabelText};
-use crate::{theme, Data, LinearGradient, UnitPoint};
+use crate::{theme, Data, KbKey, LinearGradient, UnitPoint};
 use tracing::{instrument, trace};
 
 const DEFAULT_RADIO_RADIUS: f64 = 7.0;
@@ -82,6 +82,15 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
                 }
                 ctx.set_active(false);
             }
+            Event::KeyDown(key)
+                if key.key == KbKey::Character(" ".into())
+                    && ctx.is_focused()
+                    && !ctx.is_disabled() =>
+            {
+                *data = self.variant.clone();
+                ctx.request_paint();
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
@@ -89,8 +98,12 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
     #[instrument(name = "Radio", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
         self.child_label.lifecycle(ctx, event, data, env);
-        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_)
+            | LifeCycle::DisabledChanged(_)
+            | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
@@ -138,7 +151,9 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
 
         ctx.fill(circle, &background_gradient);
 
-        let border_color = if ctx.is_hot() && !ctx.is_disabled() {
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else if ctx.is_hot() && !ctx.is_disabled() {
             env.get(theme::BORDER_LIGHT)
         } else {
             env.get(theme::BORDER_DARK)
@@ -0,0 +1,195 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that switches between children depending on the available width.
+
+use crate::widget::prelude::*;
+use crate::{Data, Point, Selector, WidgetPod};
+use tracing::{instrument, trace};
+
+type ChildBuilder<T> = Box<dyn Fn(&T, &Env) -> Box<dyn Widget<T>>>;
+
+// Sent to a Responsive from its own layout, to switch to another child.
+const SWITCH_CHILD: Selector = Selector::new("druid-builtin.responsive-switch-child");
+
+/// A widget that builds a different child depending on the width it is
+/// given, for interfaces that have to work in both narrow and wide windows.
+///
+/// A `Responsive` has a default child builder, used for the narrowest
+/// widths, and any number of breakpoints. Each breakpoint has a minimum
+/// width and a child builder, which is used when the maximum width of the
+/// incoming [`BoxConstraints`] is at least that width, and less than the
+/// width of the next breakpoint.
+///
+/// The child is only rebuilt when the width crosses a breakpoint. Children
+/// can't be added during layout, so the new child is built after the layout
+/// pass in which the breakpoint was crossed, and the widget is laid out
+/// again; the previous child is shown in the meantime.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, Label, Responsive};
+///
+/// let layout = Responsive::new(|_data: &(), _env| {
+///     Flex::column()
+///         .with_child(Label::new("Sidebar"))
+///         .with_child(Label::new("Content"))
+/// })
+/// .with_breakpoint(600., |_data, _env| {
+///     Flex::row()
+///         .with_child(Label::new("Sidebar"))
+///         .with_child(Label::new("Content"))
+/// });
+/// ```
+pub struct Responsive<T> {
+    default_builder: ChildBuilder<T>,
+    breakpoints: Vec<(f64, ChildBuilder<T>)>,
+    child: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    /// The index of the child's builder: 0 for the default, and `n + 1` for
+    /// the `n`th breakpoint.
+    active: usize,
+    /// The builder to switch to, once the switch command arrives.
+    pending: Option<usize>,
+}
+
+impl<T: Data> Responsive<T> {
+    /// Create a new `Responsive` widget, with the builder for its child when
+    /// the width is less than all of its breakpoints.
+    pub fn new<W: Widget<T> + 'static>(builder: impl Fn(&T, &Env) -> W + 'static) -> Self {
+        Responsive {
+            default_builder: Box::new(move |data, env| Box::new(builder(data, env))),
+            breakpoints: Vec::new(),
+            child: None,
+            active: 0,
+            pending: None,
+        }
+    }
+
+    /// Builder-style method to add a breakpoint, with the builder for the
+    /// child when the width is at least `min_width`.
+    ///
+    /// Breakpoints can be added in any order.
+    pub fn with_breakpoint<W: Widget<T> + 'static>(
+        mut self,
+        min_width: f64,
+        builder: impl Fn(&T, &Env) -> W + 'static,
+    ) -> Self {
+        let idx = self
+            .breakpoints
+            .iter()
+            .position(|(width, _)| *width > min_width)
+            .unwrap_or(self.breakpoints.len());
+        let builder: ChildBuilder<T> = Box::new(move |data, env| Box::new(builder(data, env)));
+        self.breakpoints.insert(idx, (min_width, builder));
+        self
+    }
+
+    /// The index of the builder to use for `width`.
+    fn builder_for_width(&self, width: f64) -> usize {
+        self.breakpoints
+            .iter()
+            .take_while(|(min_width, _)| width >= *min_width)
+            .count()
+    }
+
+    fn build_child(&self, idx: usize, data: &T, env: &Env) -> WidgetPod<T, Box<dyn Widget<T>>> {
+        let builder = match idx {
+            0 => &self.default_builder,
+            _ => &self.breakpoints[idx - 1].1,
+        };
+        WidgetPod::new(builder(data, env))
+    }
+}
+
+impl<T: Data> Widget<T> for Responsive<T> {
+    #[instrument(
+        name = "Responsive",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(SWITCH_CHILD) {
+                if let Some(idx) = self.pending.take() {
+                    trace!("Switching to child {}", idx);
+                    self.child = Some(self.build_child(idx, data, env));
+                    self.active = idx;
+                    ctx.children_changed();
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        if let Some(child) = self.child.as_mut() {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Responsive",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.child = Some(self.build_child(self.active, data, env));
+        }
+        if let Some(child) = self.child.as_mut() {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Responsive",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(child) = self.child.as_mut() {
+            child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Responsive", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Responsive");
+
+        let idx = self.builder_for_width(bc.max().width);
+        if idx != self.active && self.pending != Some(idx) {
+            self.pending = Some(idx);
+            ctx.submit_command(SWITCH_CHILD.to(ctx.widget_id()));
+        } else if idx == self.active {
+            // the width came back before the switch happened.
+            self.pending = None;
+        }
+
+        match self.child.as_mut() {
+            Some(child) => {
+                let size = child.layout(ctx, bc, data, env);
+                child.set_origin(ctx, data, env, Point::ORIGIN);
+                size
+            }
+            None => bc.min(),
+        }
+    }
+
+    #[instrument(name = "Responsive", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if let Some(child) = self.child.as_mut() {
+            child.paint(ctx, data, env);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A textbox for editing styled text.
+
+use crate::text::{RichText, SelectionStyle};
+use crate::widget::prelude::*;
+use crate::widget::{TextBox, WidgetWrapper};
+use crate::{Color, Command, KeyOrValue, Selector, Target};
+use tracing::instrument;
+
+/// A multi-line [`TextBox`] for editing [`RichText`].
+///
+/// The style of the selected text can be changed with the commands defined
+/// on this type, such as [`RichTextBox::TOGGLE_BOLD`]. These are applied by
+/// the focused `RichTextBox`, or by the `RichTextBox` they are targeted at,
+/// which makes them suitable for use from menus and toolbars.
+///
+/// When the style of the selection changes, the textbox submits a
+/// [`RichTextBox::SELECTION_STYLE_CHANGED`] notification, which a toolbar
+/// can use to reflect the current style. Text that is typed takes on the
+/// style of the text preceding it.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Flex, RichTextBox};
+/// use druid::text::RichText;
+///
+/// let toolbar = Flex::<RichText>::row()
+///     .with_child(Button::new("Bold").on_click(|ctx, _, _| {
+///         ctx.submit_command(RichTextBox::TOGGLE_BOLD)
+///     }))
+///     .with_child(Button::new("Italic").on_click(|ctx, _, _| {
+///         ctx.submit_command(RichTextBox::TOGGLE_ITALIC)
+///     }));
+/// let editor = Flex::column()
+///     .with_child(toolbar)
+///     .with_flex_child(RichTextBox::new(), 1.0);
+/// ```
+pub struct RichTextBox {
+    inner: TextBox<RichText>,
+    style: SelectionStyle,
+}
+
+impl RichTextBox {
+    /// Toggle bold on the selected text.
+    pub const TOGGLE_BOLD: Selector = Selector::new("druid-builtin.rich-textbox-toggle-bold");
+
+    /// Toggle italic on the selected text.
+    pub const TOGGLE_ITALIC: Selector = Selector::new("druid-builtin.rich-textbox-toggle-italic");
+
+    /// Toggle underline on the selected text.
+    pub const TOGGLE_UNDERLINE: Selector =
+        Selector::new("druid-builtin.rich-textbox-toggle-underline");
+
+    /// Set the color of the selected text.
+    pub const SET_TEXT_COLOR: Selector<KeyOrValue<Color>> =
+        Selector::new("druid-builtin.rich-textbox-set-text-color");
+
+    /// A notification sent when the [`SelectionStyle`] of the selection changes,
+    /// either because the selection moved or because its style was changed.
+    pub const SELECTION_STYLE_CHANGED: Selector<SelectionStyle> =
+        Selector::new("druid-builtin.rich-textbox-selection-style-changed");
+
+    /// Create a new `RichTextBox`.
+    pub fn new() -> Self {
+        RichTextBox {
+            inner: TextBox::multiline(),
+            style: SelectionStyle::default(),
+        }
+    }
+
+    /// The [`SelectionStyle`] of the current selection.
+    pub fn selection_style(&self) -> &SelectionStyle {
+        &self.style
+    }
+
+    /// Apply a style command to the selection; returns `true` if `cmd` was
+    /// a style command.
+    fn apply_style_command(&self, cmd: &Command, data: &mut RichText) -> bool {
+        let range = self.inner.text().borrow().selection().range();
+        if cmd.is(Self::TOGGLE_BOLD) {
+            data.toggle_bold(range);
+        } else if cmd.is(Self::TOGGLE_ITALIC) {
+            data.toggle_italic(range);
+        } else if cmd.is(Self::TOGGLE_UNDERLINE) {
+            data.toggle_underline(range);
+        } else if let Some(color) = cmd.get(Self::SET_TEXT_COLOR) {
+            data.set_text_color(range, color.clone());
+        } else {
+            return false;
+        }
+        true
+    }
+
+    fn update_selection_style(&mut self, ctx: &mut EventCtx, data: &RichText) {
+        if !self.inner.text().can_read() {
+            return;
+        }
+        let range = self.inner.text().borrow().selection().range();
+        let style = data.style_for_range(range);
+        if style != self.style {
+            self.style = style.clone();
+            ctx.submit_notification(Self::SELECTION_STYLE_CHANGED.with(style));
+        }
+    }
+}
+
+impl Default for RichTextBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<RichText> for RichTextBox {
+    #[instrument(
+        name = "RichTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut RichText, env: &Env) {
+        match event {
+            Event::Command(cmd)
+                if self.inner.text().can_write()
+                    && !self.inner.text().is_composing()
+                    && (ctx.has_focus() || cmd.target() == Target::Widget(ctx.widget_id())) =>
+            {
+                if self.apply_style_command(cmd, data) {
+                    ctx.set_handled();
+                } else {
+                    self.inner.event(ctx, event, data, env);
+                }
+            }
+            _ => self.inner.event(ctx, event, data, env),
+        }
+        self.update_selection_style(ctx, data);
+    }
+
+    #[instrument(
+        name = "RichTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &RichText, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "RichTextBox",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &RichText, data: &RichText, env: &Env) {
+        self.inner.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "RichTextBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &RichText,
+        env: &Env,
+    ) -> Size {
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "RichTextBox", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &RichText, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+}
+
+impl WidgetWrapper for RichTextBox {
+    widget_wrapper_body!(TextBox<RichText>, inner);
+}
@@ -0,0 +1,269 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Selecting several items by dragging a rectangle over them.
+
+use std::cell::RefCell;
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{theme, Command, Data, MouseButton, Selector, Target, WidgetPod};
+use tracing::{instrument, trace};
+
+/// Sent through the content of a [`RubberBand`] to collect the items that can
+/// be selected, with their rects in window coordinates.
+const COLLECT_ITEMS: Selector<RefCell<Vec<SelectableItem>>> =
+    Selector::new("druid-builtin.rubber-band-collect-items");
+
+type SelectFn<T> = Box<dyn Fn(&mut EventCtx, &RubberBandSelection, &mut T, &Env)>;
+type IndexFn<T> = Box<dyn Fn(&T) -> usize>;
+
+/// A container that lets the user select several items by dragging a
+/// rectangle, a "rubber band", over them, like the icons in a file manager or
+/// the shapes on a canvas.
+///
+/// The items are the descendants that are controlled by a [`RubberBandItem`].
+/// A drag starts when the mouse goes down on a part of the content that
+/// doesn't make itself [active], like the background. While the rectangle
+/// changes, the callback is called with the items it covers.
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{Flex, Label, RubberBand, RubberBandItem};
+/// use druid::WidgetExt;
+///
+/// let items = Flex::column()
+///     .with_child(Label::new("one").controller(RubberBandItem::new().with_index(|_| 0)))
+///     .with_child(Label::new("two").controller(RubberBandItem::new().with_index(|_| 1)));
+/// let selectable = RubberBand::new(items).on_select(
+///     |_ctx, selection, data: &mut Arc<Vec<usize>>, _env| {
+///         *data = Arc::new(selection.indices.clone());
+///     },
+/// );
+/// ```
+///
+/// [active]: crate::EventCtx::is_active
+pub struct RubberBand<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    on_select: Option<SelectFn<T>>,
+    /// Where the drag started, in our coordinate space.
+    origin: Option<Point>,
+    /// The current selection rectangle, in our coordinate space.
+    band: Option<Rect>,
+    /// The ids of the covered items, to notice when they change.
+    covered: Vec<WidgetId>,
+}
+
+/// The items covered by the rectangle of a [`RubberBand`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RubberBandSelection {
+    /// The selection rectangle, in the coordinate space of the `RubberBand`.
+    pub rect: Rect,
+    /// The ids of the covered items.
+    pub ids: Vec<WidgetId>,
+    /// The indices of the covered items that have one; see
+    /// [`RubberBandItem::with_index`].
+    pub indices: Vec<usize>,
+}
+
+/// An item found in the content of a [`RubberBand`].
+#[derive(Debug, Clone, Copy)]
+struct SelectableItem {
+    id: WidgetId,
+    rect: Rect,
+    index: Option<usize>,
+}
+
+/// A [`Controller`] that marks a widget as an item that can be selected with
+/// an enclosing [`RubberBand`].
+pub struct RubberBandItem<T> {
+    index: Option<IndexFn<T>>,
+}
+
+impl<T> RubberBand<T> {
+    /// Create a new `RubberBand` around `child`.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        RubberBand {
+            child: WidgetPod::new(child).boxed(),
+            on_select: None,
+            origin: None,
+            band: None,
+            covered: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the callback that is called whenever the
+    /// items covered by the rectangle change.
+    pub fn on_select(
+        mut self,
+        f: impl Fn(&mut EventCtx, &RubberBandSelection, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+}
+
+impl<T: Data> RubberBand<T> {
+    /// Find the items that the current rectangle covers, and report them if
+    /// they changed.
+    fn select(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        let band = match self.band {
+            Some(band) => band,
+            None => return,
+        };
+        let collect = Command::new(COLLECT_ITEMS, RefCell::default(), Target::Global);
+        self.child
+            .event(ctx, &Event::Command(collect.clone()), data, env);
+        let items = collect.get_unchecked(COLLECT_ITEMS).take();
+
+        let window_band = band + ctx.window_origin().to_vec2();
+        let covered = items
+            .into_iter()
+            .filter(|item| item.rect.intersect(window_band).area() > 0.0)
+            .collect::<Vec<_>>();
+        let ids = covered.iter().map(|item| item.id).collect::<Vec<_>>();
+        if ids == self.covered {
+            return;
+        }
+        self.covered = ids.clone();
+        trace!("Rubber band covers {} items", ids.len());
+
+        let selection = RubberBandSelection {
+            rect: band,
+            ids,
+            indices: covered.iter().filter_map(|item| item.index).collect(),
+        };
+        if let Some(on_select) = &self.on_select {
+            on_select(ctx, &selection, data, env);
+        }
+    }
+}
+
+impl<T> RubberBandItem<T> {
+    /// Create a new `RubberBandItem`.
+    pub fn new() -> Self {
+        RubberBandItem { index: None }
+    }
+
+    /// Builder-style method to report the item with an index, computed from
+    /// its data, for instance its position in a list.
+    pub fn with_index(mut self, index: impl Fn(&T) -> usize + 'static) -> Self {
+        self.index = Some(Box::new(index));
+        self
+    }
+}
+
+impl<T> Default for RubberBandItem<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for RubberBand<T> {
+    #[instrument(
+        name = "RubberBand",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) if self.origin.is_some() => {
+                self.band = self
+                    .origin
+                    .map(|origin| Rect::from_points(origin, mouse.pos));
+                self.select(ctx, data, env);
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(mouse) if self.origin.is_some() && mouse.button == MouseButton::Left => {
+                ctx.set_active(false);
+                self.origin = None;
+                self.band = None;
+                self.covered.clear();
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        self.child.event(ctx, event, data, env);
+
+        if let Event::MouseDown(mouse) = event {
+            // start a drag unless the mouse went down on something interactive
+            if mouse.button == MouseButton::Left && !ctx.is_handled() && !ctx.has_active() {
+                ctx.set_active(true);
+                self.origin = Some(mouse.pos);
+                self.band = Some(Rect::from_points(mouse.pos, mouse.pos));
+                self.covered.clear();
+                self.select(ctx, data, env);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    #[instrument(
+        name = "RubberBand",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "RubberBand",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "RubberBand", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("RubberBand");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "RubberBand", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if let Some(band) = self.band {
+            let color = env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR);
+            ctx.fill(band, &color.clone().with_alpha(0.25));
+            ctx.stroke(band.inset(-0.5), &color, 1.0);
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for RubberBandItem<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(items) = cmd.get(COLLECT_ITEMS) {
+                items.borrow_mut().push(SelectableItem {
+                    id: ctx.widget_id(),
+                    rect: Rect::from_origin_size(ctx.window_origin(), ctx.size()),
+                    index: self.index.as_ref().map(|index| index(data)),
+                });
+            }
+        }
+        child.event(ctx, event, data, env)
+    }
+}
@@ -14,11 +14,18 @@
 
 //! A container that scrolls its contents.
 
+use std::rc::Rc;
+
+use crate::commands::SCROLL_TO_VIEW;
 use crate::widget::prelude::*;
 use crate::widget::{Axis, ClipBox};
-use crate::{scroll_component::*, Data, Rect, Vec2};
+use crate::{scroll_component::*, Data, Lens, LensExt, Point, Rect, Selector, Vec2};
 use tracing::{instrument, trace};
 
+/// Sent to itself by a `Scroll` whose content changed size, to check whether
+/// it is still close to the end.
+const CHECK_NEAR_END: Selector = Selector::new("druid-builtin.scroll-check-near-end");
+
 /// A container that scrolls its contents.
 ///
 /// This container holds a single child, and uses the wheel to scroll it
@@ -29,11 +36,69 @@ use tracing::{instrument, trace};
 /// When restricted to scrolling on a specific axis the child's size is
 /// locked on the opposite axis.
 ///
+/// Scrolling to bring a widget into view, and scrolling with
+/// [`Scroll::SET_OFFSET`], is animated; see [`theme::ANIMATE_SCROLLING`].
+///
 /// [`vertical`]: struct.Scroll.html#method.vertical
 /// [`horizontal`]: struct.Scroll.html#method.horizontal
+/// [`theme::ANIMATE_SCROLLING`]: crate::theme::ANIMATE_SCROLLING
 pub struct Scroll<T, W> {
     clip: ClipBox<T, W>,
     scroll_component: ScrollComponent,
+    near_end: Option<NearEnd<T>>,
+    anchor: ScrollAnchor,
+    offset_binding: Option<OffsetBinding<T>>,
+    /// An offset to scroll to once the content has been laid out.
+    pending_offset: Option<Vec2>,
+    /// The offset when it was last reported with [`Scroll::OFFSET_CHANGED`].
+    reported_offset: Vec2,
+}
+
+/// Keeps the scroll offset in sync with a value in the data; see
+/// [`Scroll::bind_offset`].
+struct OffsetBinding<T> {
+    get: Box<dyn Fn(&T) -> Vec2>,
+    put: PutOffsetFn<T>,
+}
+
+type PutOffsetFn<T> = Box<dyn Fn(&mut T, Vec2)>;
+
+/// Which end of the content keeps its place on screen when the size of the
+/// content of a [`Scroll`] changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScrollAnchor {
+    /// The distance to the start of the content is kept, so items added at
+    /// the end don't move what is visible.
+    #[default]
+    Start,
+    /// The distance to the end of the content is kept, so items prepended
+    /// to the content, like older messages in a chat, don't move what is
+    /// visible. The content starts out scrolled to the end.
+    End,
+}
+
+type NearEndFn<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+
+/// The callback run when the viewport gets close to the end of the content.
+struct NearEnd<T> {
+    threshold: f64,
+    callback: NearEndFn<T>,
+    /// Whether the callback ran since the viewport got close to the end,
+    /// or since the content last changed size.
+    fired: bool,
+}
+
+impl Scroll<(), ()> {
+    /// Scroll to the offset in the payload.
+    ///
+    /// This can be submitted as a command to the `Scroll`'s id, for instance
+    /// by a controller that pages through the content with the keyboard.
+    /// The change is animated like other programmatic scrolls.
+    pub const SET_OFFSET: Selector<Vec2> = Selector::new("druid-builtin.scroll-set-offset");
+
+    /// A notification submitted when the scroll offset changed, with the new
+    /// offset.
+    pub const OFFSET_CHANGED: Selector<Vec2> = Selector::new("druid-builtin.scroll-offset-changed");
 }
 
 impl<T, W: Widget<T>> Scroll<T, W> {
@@ -46,6 +111,11 @@ impl<T, W: Widget<T>> Scroll<T, W> {
         Scroll {
             clip: ClipBox::new(child),
             scroll_component: ScrollComponent::new(),
+            near_end: None,
+            anchor: ScrollAnchor::Start,
+            offset_binding: None,
+            pending_offset: None,
+            reported_offset: Vec2::ZERO,
         }
     }
 
@@ -70,6 +140,33 @@ impl<T, W: Widget<T>> Scroll<T, W> {
     pub fn scroll_to_on_axis(&mut self, axis: Axis, position: f64) -> bool {
         self.clip.pan_to_on_axis(axis, position)
     }
+
+    /// Scroll to `origin`, animating the change unless animations are
+    /// disabled in the `Env`.
+    fn animate_to(&mut self, ctx: &mut EventCtx, origin: Point, env: &Env) {
+        let scroll_component = &mut self.scroll_component;
+        self.clip.with_port(|port| {
+            scroll_component.animate_to(port, ctx, origin, env);
+        });
+    }
+
+    /// Scroll so that `area`, in window coordinates, comes into view, as asked
+    /// for by a [`SCROLL_TO_VIEW`] notification.
+    ///
+    /// Like [`ClipBox::scroll_to_view`], but animated.
+    fn animate_to_view(&mut self, ctx: &mut EventCtx, area: Rect, env: &Env) {
+        let content_origin = ctx.window_origin() - self.offset();
+        let content_area = area - content_origin.to_vec2();
+        let mut target = self.clip.viewport();
+        target.pan_to_visible(content_area);
+        self.animate_to(ctx, target.view_origin, env);
+
+        // enclosing scroll areas bring the part into view that will be
+        // visible here once the animation ends
+        let content_origin = ctx.window_origin() - target.view_origin.to_vec2();
+        let visible = content_area.intersect(target.view_rect());
+        ctx.submit_notification(SCROLL_TO_VIEW.with(visible + content_origin.to_vec2()));
+    }
 }
 
 impl<T, W> Scroll<T, W> {
@@ -105,6 +202,130 @@ impl<T, W> Scroll<T, W> {
         self
     }
 
+    /// Builder-style method to set when the scrollbars are shown.
+    ///
+    /// The default is [`ScrollbarVisibility::AutoHide`].
+    pub fn with_scrollbar_visibility(mut self, visibility: ScrollbarVisibility) -> Self {
+        self.set_scrollbar_visibility(visibility);
+        self
+    }
+
+    /// Builder-style method to set whether a flick on a touchpad or touch
+    /// screen keeps scrolling after it ends.
+    ///
+    /// This is enabled by default. A mouse wheel never coasts; see
+    /// [`MouseEvent::precise_wheel`].
+    ///
+    /// [`MouseEvent::precise_wheel`]: crate::MouseEvent::precise_wheel
+    pub fn with_inertia(mut self, inertia: bool) -> Self {
+        self.set_inertia(inertia);
+        self
+    }
+
+    /// Builder-style method to set how quickly inertial scrolling slows down.
+    ///
+    /// The velocity decays as `exp(-friction * t)`, with `t` in seconds; the
+    /// default is [`DEFAULT_SCROLL_FRICTION`].
+    pub fn with_friction(mut self, friction: f64) -> Self {
+        self.set_friction(friction);
+        self
+    }
+
+    /// Builder-style method to set whether each wheel or touchpad gesture
+    /// only scrolls along the axis it started on.
+    ///
+    /// This keeps diagonal touchpad gestures from wobbling sideways in views,
+    /// like lists, that mostly scroll along one axis. It is disabled by default.
+    pub fn with_axis_lock(mut self, axis_lock: bool) -> Self {
+        self.set_axis_lock(axis_lock);
+        self
+    }
+
+    /// Builder-style method to set what happens to wheel events once the
+    /// content can't scroll any further in their direction.
+    ///
+    /// By default they go on to an enclosing `Scroll`; see [`WheelPropagation`]
+    /// for the alternatives.
+    pub fn with_wheel_propagation(mut self, propagation: WheelPropagation) -> Self {
+        self.set_wheel_propagation(propagation);
+        self
+    }
+
+    /// Builder-style method to set where the `Scroll` settles after the user
+    /// scrolled it.
+    ///
+    /// After a wheel gesture, a flick or a scrollbar drag, the `Scroll`
+    /// animates to the closest snap point. See [`ScrollSnap`] for the options;
+    /// [`ScrollSnap::Children`] settles on the items of the child, for
+    /// instance those of a [`Flex`], as they are laid out.
+    ///
+    /// [`Flex`]: super::Flex
+    pub fn with_snap(mut self, snap: ScrollSnap) -> Self {
+        self.set_snap(snap);
+        self
+    }
+
+    /// Builder-style method to run `f` when the viewport gets within
+    /// `threshold` of the end of the content, for instance to fetch more items.
+    ///
+    /// The end is the bottom of the content, or its right edge if the
+    /// `Scroll` is [`horizontal`]. `f` runs once when the viewport gets close
+    /// to the end, and again whenever the content changed size while it stays
+    /// there, so that a list keeps loading until it fills the viewport.
+    ///
+    /// [`horizontal`]: Scroll::horizontal
+    pub fn on_near_end(
+        mut self,
+        threshold: f64,
+        f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.near_end = Some(NearEnd {
+            threshold,
+            callback: Box::new(f),
+            fired: false,
+        });
+        self
+    }
+
+    /// Builder-style method to keep the scroll offset in sync with the value
+    /// that `lens` focuses on.
+    ///
+    /// The offset is restored from the data when the `Scroll` is added and
+    /// whenever the value changes, and the data is updated when the user
+    /// scrolls. This can keep a scroll position across view switches or data
+    /// reloads, or keep two `Scroll`s bound to the same value in sync.
+    pub fn bind_offset(mut self, lens: impl Lens<T, Vec2> + 'static) -> Self {
+        let lens = Rc::new(lens);
+        let put_lens = lens.clone();
+        self.offset_binding = Some(OffsetBinding {
+            get: Box::new(move |data| lens.get(data)),
+            put: Box::new(move |data, offset| put_lens.put(data, offset)),
+        });
+        self
+    }
+
+    /// Builder-style method to set which end of the content keeps its place
+    /// on screen when the content changes size.
+    ///
+    /// The default is [`ScrollAnchor::Start`].
+    pub fn with_anchor(mut self, anchor: ScrollAnchor) -> Self {
+        self.set_anchor(anchor);
+        self
+    }
+
+    /// Builder-style method to scroll while something in the content is
+    /// dragged close to the edges of the `Scroll`.
+    ///
+    /// This applies while a descendant is [active], like a text box extending
+    /// its selection or an item being dragged; see [`AutoScroll`] for how the
+    /// speed ramps up. It is disabled by default.
+    ///
+    /// [active]: crate::EventCtx::is_active
+    pub fn with_auto_scroll(mut self, auto_scroll: AutoScroll) -> Self {
+        self.set_auto_scroll(Some(auto_scroll));
+        self
+    }
+
     /// Set whether the child's size must be greater than or equal the size of
     /// the `Scroll` widget.
     ///
@@ -123,6 +344,61 @@ impl<T, W> Scroll<T, W> {
         self.scroll_component.enabled = enabled;
     }
 
+    /// Set when the scrollbars are shown.
+    ///
+    /// With [`ScrollbarVisibility::Never`] the content can still be scrolled
+    /// with a trackpad or scroll wheel.
+    pub fn set_scrollbar_visibility(&mut self, visibility: ScrollbarVisibility) {
+        self.scroll_component.visibility = visibility;
+    }
+
+    /// Set whether a flick on a touchpad or touch screen keeps scrolling
+    /// after it ends.
+    pub fn set_inertia(&mut self, inertia: bool) {
+        self.scroll_component.inertia = inertia;
+        if !inertia {
+            self.scroll_component.stop_inertia();
+        }
+    }
+
+    /// Set how quickly inertial scrolling slows down.
+    ///
+    /// See [`with_friction`] for details.
+    ///
+    /// [`with_friction`]: Scroll::with_friction
+    pub fn set_friction(&mut self, friction: f64) {
+        self.scroll_component.friction = friction;
+    }
+
+    /// Set whether each wheel or touchpad gesture only scrolls along the axis
+    /// it started on.
+    pub fn set_axis_lock(&mut self, axis_lock: bool) {
+        self.scroll_component.axis_lock = axis_lock;
+    }
+
+    /// Set what happens to wheel events once the content can't scroll any
+    /// further in their direction.
+    pub fn set_wheel_propagation(&mut self, propagation: WheelPropagation) {
+        self.scroll_component.wheel_propagation = propagation;
+    }
+
+    /// Set where the `Scroll` settles after the user scrolled it.
+    pub fn set_snap(&mut self, snap: ScrollSnap) {
+        self.scroll_component.snap = snap;
+    }
+
+    /// Set whether, and how, to scroll while something in the content is
+    /// dragged close to the edges of the `Scroll`.
+    pub fn set_auto_scroll(&mut self, auto_scroll: Option<AutoScroll>) {
+        self.scroll_component.auto_scroll = auto_scroll;
+    }
+
+    /// Set which end of the content keeps its place on screen when the
+    /// content changes size.
+    pub fn set_anchor(&mut self, anchor: ScrollAnchor) {
+        self.anchor = anchor;
+    }
+
     /// Set whether the content can be scrolled in the vertical direction.
     pub fn set_vertical_scroll_enabled(&mut self, enabled: bool) {
         self.clip.set_constrain_vertical(!enabled);
@@ -170,11 +446,71 @@ impl<T, W> Scroll<T, W> {
     pub fn offset_for_axis(&self, axis: Axis) -> f64 {
         axis.major_pos(self.clip.viewport_origin())
     }
+
+    /// The axis along which the content grows, for anchoring and for
+    /// [`on_near_end`](Scroll::on_near_end).
+    fn main_axis(&self) -> Axis {
+        match self.scroll_component.enabled {
+            ScrollbarsEnabled::Horizontal => Axis::Horizontal,
+            _ => Axis::Vertical,
+        }
+    }
+
+    /// Report the offset, if it changed since it was last reported.
+    fn sync_offset(&mut self, ctx: &mut EventCtx, data: &mut T) {
+        let offset = self.offset();
+        if offset == self.reported_offset {
+            return;
+        }
+        self.reported_offset = offset;
+        if let Some(binding) = &self.offset_binding {
+            (binding.put)(data, offset);
+        }
+        ctx.submit_notification(Scroll::OFFSET_CHANGED.with(offset));
+    }
+
+    fn check_near_end(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        let axis = self.main_axis();
+        let port = self.clip.viewport();
+        let near_end = match self.near_end.as_mut() {
+            Some(near_end) => near_end,
+            None => return,
+        };
+        let remaining = axis.major(port.content_size)
+            - axis.major_pos(port.view_origin)
+            - axis.major(port.view_size);
+        if remaining > near_end.threshold {
+            near_end.fired = false;
+        } else if !near_end.fired {
+            near_end.fired = true;
+            (near_end.callback)(ctx, data, env);
+        }
+    }
 }
 
 impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(CHECK_NEAR_END) {
+                self.check_near_end(ctx, data, env);
+                ctx.set_handled();
+                return;
+            }
+            if let Some(offset) = cmd.get(Scroll::SET_OFFSET) {
+                self.animate_to(ctx, offset.to_point(), env);
+                self.sync_offset(ctx, data);
+                ctx.set_handled();
+                return;
+            }
+        }
+        if let Event::Notification(note) = event {
+            if let Some(area) = note.get(SCROLL_TO_VIEW) {
+                self.animate_to_view(ctx, *area, env);
+                ctx.set_handled();
+                return;
+            }
+        }
         let scroll_component = &mut self.scroll_component;
         self.clip.with_port(|port| {
             scroll_component.event(port, ctx, event, env);
@@ -186,16 +522,42 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         self.clip.with_port(|port| {
             scroll_component.handle_scroll(port, ctx, event, env);
         });
+        if self.scroll_component.take_snap_request() {
+            let port = self.clip.viewport();
+            if self
+                .scroll_component
+                .snap_to_nearest(&port, self.clip.child_rects())
+            {
+                ctx.request_anim_frame();
+            }
+        }
+        // the content moved under a drag, so let it follow the pointer
+        if let Some(mouse) = self.scroll_component.take_auto_scroll_move() {
+            self.clip.event(ctx, &Event::MouseMove(mouse), data, env);
+        }
+        self.sync_offset(ctx, data);
+        self.check_near_end(ctx, data, env);
     }
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let (LifeCycle::WidgetAdded, Some(binding)) = (event, &self.offset_binding) {
+            self.pending_offset = Some((binding.get)(data));
+        }
         self.scroll_component.lifecycle(ctx, event, env);
         self.clip.lifecycle(ctx, event, data, env);
     }
 
     #[instrument(name = "Scroll", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if let Some(binding) = &self.offset_binding {
+            let offset = (binding.get)(data);
+            if offset != self.offset() {
+                // the content may change too, so wait for it to be laid out
+                self.pending_offset = Some(offset);
+                ctx.request_layout();
+            }
+        }
         self.clip.update(ctx, old_data, data, env);
     }
 
@@ -204,10 +566,29 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
         bc.debug_check("Scroll");
 
         let old_size = self.clip.viewport().view_size;
+        let old_content_size = self.clip.content_size();
         let child_size = self.clip.layout(ctx, bc, data, env);
         log_size_warnings(child_size);
 
+        let axis = self.main_axis();
+        let growth = axis.major(self.clip.content_size()) - axis.major(old_content_size);
+        if growth != 0.0 {
+            if let Some(near_end) = self.near_end.as_mut() {
+                near_end.fired = false;
+                let id = ctx.widget_id();
+                ctx.submit_command(CHECK_NEAR_END.to(id));
+            }
+            if self.anchor == ScrollAnchor::End {
+                let (x, y) = axis.pack(growth, 0.0);
+                let _ = self.scroll_by(Vec2::new(x, y));
+            }
+        }
+
         let self_size = bc.constrain(child_size);
+        if let Some(offset) = self.pending_offset.take() {
+            let _ = self.clip.pan_to(offset.to_point());
+            self.reported_offset = self.offset();
+        }
         // The new size might have made the current scroll offset invalid. This makes it valid
         // again.
         let _ = self.scroll_by(Vec2::ZERO);
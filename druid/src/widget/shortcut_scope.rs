@@ -0,0 +1,81 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] with keyboard shortcuts for a part of a window.
+
+use crate::commands::SET_SHORTCUTS;
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, ShortcutMap, Widget};
+use tracing::{instrument, trace};
+
+/// A [`Controller`] with keyboard shortcuts that apply while the focus is
+/// inside its child.
+///
+/// These shortcuts take precedence over those of the window and of the
+/// application, and over those of enclosing scopes. As with the other
+/// shortcuts, a key press only triggers a shortcut if no widget handled it.
+///
+/// The shortcuts can be replaced by sending [`SET_SHORTCUTS`] to this
+/// widget. See [`ShortcutMap`] for details.
+///
+/// This is available as a `with_shortcuts` method via [`WidgetExt`].
+///
+/// [`SET_SHORTCUTS`]: crate::commands::SET_SHORTCUTS
+/// [`ShortcutMap`]: crate::ShortcutMap
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct ShortcutScope {
+    shortcuts: ShortcutMap,
+}
+
+impl ShortcutScope {
+    /// Create a new `ShortcutScope` with the given shortcuts.
+    pub fn new(shortcuts: ShortcutMap) -> Self {
+        ShortcutScope { shortcuts }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for ShortcutScope {
+    #[instrument(
+        name = "ShortcutScope",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(shortcuts) = cmd.get(SET_SHORTCUTS) {
+                self.shortcuts = shortcuts.clone();
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env);
+
+        if let Event::KeyDown(key) = event {
+            if ctx.is_handled() || !ctx.has_focus() {
+                return;
+            }
+            match self.shortcuts.lookup(key) {
+                Some(Some(command)) => {
+                    trace!("Shortcut {:?} in {:?}", command, ctx.widget_id());
+                    ctx.submit_command(command.clone());
+                    ctx.set_handled();
+                }
+                // the key is disabled here, which hides the outer shortcuts.
+                Some(None) => ctx.set_handled(),
+                None => (),
+            }
+        }
+    }
+}
@@ -18,6 +18,7 @@ use std::f64::INFINITY;
 use tracing::{instrument, trace, warn};
 
 use crate::widget::prelude::*;
+use crate::widget::Axis;
 use crate::Data;
 
 /// A widget with predefined size.
@@ -29,10 +30,18 @@ use crate::Data;
 /// If not given a child, SizedBox will try to size itself as close to the specified height
 /// and width as possible given the parent's constraints. If height or width is not set,
 /// it will be treated as zero.
+///
+/// The width and height can also be set as a fraction of the maximum size
+/// allowed by the parent, with [`width_fraction`] and [`height_fraction`].
+///
+/// [`width_fraction`]: SizedBox::width_fraction
+/// [`height_fraction`]: SizedBox::height_fraction
 pub struct SizedBox<T> {
     inner: Option<Box<dyn Widget<T>>>,
     width: Option<f64>,
     height: Option<f64>,
+    width_fraction: Option<f64>,
+    height_fraction: Option<f64>,
 }
 
 impl<T> SizedBox<T> {
@@ -42,6 +51,8 @@ impl<T> SizedBox<T> {
             inner: Some(Box::new(inner)),
             width: None,
             height: None,
+            width_fraction: None,
+            height_fraction: None,
         }
     }
 
@@ -56,18 +67,44 @@ impl<T> SizedBox<T> {
             inner: None,
             width: None,
             height: None,
+            width_fraction: None,
+            height_fraction: None,
         }
     }
 
     /// Set container's width.
     pub fn width(mut self, width: f64) -> Self {
         self.width = Some(width);
+        self.width_fraction = None;
         self
     }
 
     /// Set container's height.
     pub fn height(mut self, height: f64) -> Self {
         self.height = Some(height);
+        self.height_fraction = None;
+        self
+    }
+
+    /// Set container's width as a fraction of the maximum width allowed by
+    /// the parent; `0.5` is half of the available width.
+    ///
+    /// If the parent allows an unbounded width, the width is treated as not
+    /// set.
+    pub fn width_fraction(mut self, fraction: f64) -> Self {
+        self.width_fraction = Some(fraction);
+        self.width = None;
+        self
+    }
+
+    /// Set container's height as a fraction of the maximum height allowed by
+    /// the parent; `0.5` is half of the available height.
+    ///
+    /// If the parent allows an unbounded height, the height is treated as not
+    /// set.
+    pub fn height_fraction(mut self, fraction: f64) -> Self {
+        self.height_fraction = Some(fraction);
+        self.height = None;
         self
     }
 
@@ -82,6 +119,8 @@ impl<T> SizedBox<T> {
     pub fn expand(mut self) -> Self {
         self.width = Some(INFINITY);
         self.height = Some(INFINITY);
+        self.width_fraction = None;
+        self.height_fraction = None;
         self
     }
 
@@ -90,6 +129,7 @@ impl<T> SizedBox<T> {
     /// This will force the child to have maximum width.
     pub fn expand_width(mut self) -> Self {
         self.width = Some(INFINITY);
+        self.width_fraction = None;
         self
     }
 
@@ -98,13 +138,58 @@ impl<T> SizedBox<T> {
     /// This will force the child to have maximum height.
     pub fn expand_height(mut self) -> Self {
         self.height = Some(INFINITY);
+        self.height_fraction = None;
         self
     }
 
+    /// The width and height for these constraints, resolving fractions.
+    fn resolved_size(&self, bc: &BoxConstraints) -> (Option<f64>, Option<f64>) {
+        let resolve = |fraction: Option<f64>, max: f64| {
+            fraction
+                .filter(|_| max.is_finite())
+                .map(|fraction| fraction * max)
+        };
+        (
+            self.width
+                .or_else(|| resolve(self.width_fraction, bc.max().width)),
+            self.height
+                .or_else(|| resolve(self.height_fraction, bc.max().height)),
+        )
+    }
+
+    /// The minimum or maximum intrinsic size along `axis`.
+    fn intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+        min: bool,
+    ) -> f64 {
+        // fractions of an unbounded axis are ignored, like in layout.
+        let bc = axis.constraints(bc, 0.0, f64::INFINITY);
+        let (width, height) = self.resolved_size(&bc);
+        let fixed = match axis {
+            Axis::Horizontal => width,
+            Axis::Vertical => height,
+        };
+        let child_bc = self.child_constraints(&bc);
+        match (fixed, self.inner.as_mut()) {
+            (Some(size), _) => size,
+            (None, Some(inner)) if min => {
+                inner.compute_min_intrinsic(axis, ctx, &child_bc, data, env)
+            }
+            (None, Some(inner)) => inner.compute_max_intrinsic(axis, ctx, &child_bc, data, env),
+            (None, None) => 0.0,
+        }
+    }
+
     fn child_constraints(&self, bc: &BoxConstraints) -> BoxConstraints {
         // if we don't have a width/height, we don't change that axis.
         // if we have a width/height, we clamp it on that axis.
-        let (min_width, max_width) = match self.width {
+        let (width, height) = self.resolved_size(bc);
+        let (min_width, max_width) = match width {
             Some(width) => {
                 let w = width.max(bc.min().width).min(bc.max().width);
                 (w, w)
@@ -112,7 +197,7 @@ impl<T> SizedBox<T> {
             None => (bc.min().width, bc.max().width),
         };
 
-        let (min_height, max_height) = match self.height {
+        let (min_height, max_height) = match height {
             Some(height) => {
                 let h = height.max(bc.min().height).min(bc.max().height);
                 (h, h)
@@ -165,7 +250,10 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         let child_bc = self.child_constraints(bc);
         let size = match self.inner.as_mut() {
             Some(inner) => inner.layout(ctx, &child_bc, data, env),
-            None => bc.constrain((self.width.unwrap_or(0.0), self.height.unwrap_or(0.0))),
+            None => {
+                let (width, height) = self.resolved_size(bc);
+                bc.constrain((width.unwrap_or(0.0), height.unwrap_or(0.0)))
+            }
         };
 
         trace!("Computed size: {}", size);
@@ -180,6 +268,30 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         size
     }
 
+    #[instrument(name = "SizedBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(axis, ctx, bc, data, env, false)
+    }
+
+    #[instrument(name = "SizedBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(axis, ctx, bc, data, env, true)
+    }
+
     #[instrument(name = "SizedBox", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         if let Some(ref mut inner) = self.inner {
@@ -214,4 +326,21 @@ mod tests {
         assert_eq!(child_bc.min(), Size::new(0., 200.,));
         assert_eq!(child_bc.max(), Size::new(400., 200.,));
     }
+
+    #[test]
+    fn fractions() {
+        let half = SizedBox::<()>::new(Label::new("hello!"))
+            .width_fraction(0.5)
+            .height_fraction(0.3);
+        let bc = BoxConstraints::tight(Size::new(400., 400.)).loosen();
+        let child_bc = half.child_constraints(&bc);
+        assert_eq!(child_bc.min(), Size::new(200., 120.,));
+        assert_eq!(child_bc.max(), Size::new(200., 120.,));
+
+        // an unbounded axis is left alone
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(400., f64::INFINITY));
+        let child_bc = half.child_constraints(&bc);
+        assert_eq!(child_bc.min(), Size::new(200., 0.,));
+        assert_eq!(child_bc.max(), Size::new(200., f64::INFINITY,));
+    }
 }
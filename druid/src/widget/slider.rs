@@ -16,7 +16,7 @@
 
 use crate::kurbo::{Circle, Shape};
 use crate::widget::prelude::*;
-use crate::{theme, LinearGradient, Point, Rect, UnitPoint};
+use crate::{theme, KbKey, LinearGradient, Point, Rect, TouchId, UnitPoint};
 use tracing::{instrument, trace, warn};
 
 const TRACK_THICKNESS: f64 = 4.0;
@@ -27,6 +27,9 @@ const KNOB_STROKE_WIDTH: f64 = 2.0;
 ///
 /// This slider implements `Widget<f64>`, and works on values clamped
 /// in the range `min..max`.
+///
+/// The knob can be dragged with the mouse or with a finger on a touch
+/// screen.
 #[derive(Debug, Clone, Default)]
 pub struct Slider {
     min: f64,
@@ -35,6 +38,8 @@ pub struct Slider {
     knob_pos: Point,
     knob_hovered: bool,
     x_offset: f64,
+    /// The touch screen contact that is dragging the knob.
+    touch: Option<TouchId>,
 }
 
 impl Slider {
@@ -47,6 +52,7 @@ impl Slider {
             knob_pos: Default::default(),
             knob_hovered: Default::default(),
             x_offset: Default::default(),
+            touch: None,
         }
     }
 
@@ -119,6 +125,24 @@ impl Slider {
         value
     }
 
+    /// Start a drag at `pos`, keeping the knob under it if it is on the knob.
+    fn start_drag(&mut self, pos: Point, knob_size: f64, slider_width: f64, data: &mut f64) {
+        if self.knob_hit_test(knob_size, pos) {
+            self.x_offset = self.knob_pos.x - pos.x
+        } else {
+            self.x_offset = 0.;
+            *data = self.calculate_value(pos.x, knob_size, slider_width);
+        }
+    }
+
+    /// The value `steps` keyboard steps away from `data`.
+    ///
+    /// Without a step, the keyboard moves the knob by a hundredth of the range.
+    fn step_value(&self, data: f64, steps: f64) -> f64 {
+        let step = self.step.unwrap_or((self.max - self.min) / 100.);
+        (data + steps * step).clamp(self.min, self.max)
+    }
+
     fn normalize(&self, data: f64) -> f64 {
         (data.max(self.min).min(self.max) - self.min) / (self.max - self.min)
     }
@@ -134,12 +158,7 @@ impl Widget<f64> for Slider {
             Event::MouseDown(mouse) => {
                 if !ctx.is_disabled() {
                     ctx.set_active(true);
-                    if self.knob_hit_test(knob_size, mouse.pos) {
-                        self.x_offset = self.knob_pos.x - mouse.pos.x
-                    } else {
-                        self.x_offset = 0.;
-                        *data = self.calculate_value(mouse.pos.x, knob_size, slider_width);
-                    }
+                    self.start_drag(mouse.pos, knob_size, slider_width, data);
                     ctx.request_paint();
                 }
             }
@@ -167,6 +186,45 @@ impl Widget<f64> for Slider {
                     ctx.set_active(false);
                 }
             }
+            Event::TouchDown(touch) if !ctx.is_disabled() && self.touch.is_none() => {
+                ctx.set_active(true);
+                self.touch = Some(touch.id);
+                self.start_drag(touch.pos, knob_size, slider_width, data);
+                ctx.request_paint();
+                // keep an enclosing scroll area from taking the drag
+                ctx.set_handled();
+            }
+            Event::TouchMove(touch) if self.touch == Some(touch.id) => {
+                if !ctx.is_disabled() {
+                    *data = self.calculate_value(touch.pos.x, knob_size, slider_width);
+                    ctx.request_paint();
+                    ctx.set_handled();
+                } else {
+                    self.touch = None;
+                    ctx.set_active(false);
+                }
+            }
+            Event::TouchUp(touch) if self.touch == Some(touch.id) => {
+                if !ctx.is_disabled() {
+                    *data = self.calculate_value(touch.pos.x, knob_size, slider_width);
+                    ctx.request_paint();
+                }
+                self.touch = None;
+                ctx.set_active(false);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_disabled() => {
+                let steps = match key.key {
+                    KbKey::ArrowLeft | KbKey::ArrowDown => -1.,
+                    KbKey::ArrowRight | KbKey::ArrowUp => 1.,
+                    KbKey::PageDown => -10.,
+                    KbKey::PageUp => 10.,
+                    _ => return,
+                };
+                *data = self.step_value(*data, steps);
+                ctx.request_paint();
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
@@ -176,7 +234,8 @@ impl Widget<f64> for Slider {
         match event {
             // checked in LifeCycle::WidgetAdded because logging may not be setup in with_range
             LifeCycle::WidgetAdded => self.check_range(),
-            LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
             _ => (),
         }
     }
@@ -229,7 +288,12 @@ impl Widget<f64> for Slider {
             ),
         );
 
-        ctx.stroke(background_rect, &env.get(theme::BORDER_DARK), BORDER_WIDTH);
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(background_rect, &border_color, BORDER_WIDTH);
 
         ctx.fill(background_rect, &background_gradient);
 
@@ -221,6 +221,40 @@ impl<T> Split<T> {
         (min_limit, max_limit)
     }
 
+    /// Measure both children along `axis`, without our constraint on the split axis.
+    ///
+    /// Along the split axis the sides add up, each at least its minimum size, with the
+    /// bar in between. Across it, the larger child is the size.
+    fn intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+        min: bool,
+    ) -> f64
+    where
+        T: Data,
+    {
+        let child_bc = self.split_axis.constraints(bc, 0.0, f64::INFINITY);
+        let mut measure = |child: &mut WidgetPod<T, Box<dyn Widget<T>>>| {
+            if min {
+                child.compute_min_intrinsic(axis, ctx, &child_bc, data, env)
+            } else {
+                child.compute_max_intrinsic(axis, ctx, &child_bc, data, env)
+            }
+        };
+        let first = measure(&mut self.child1);
+        let second = measure(&mut self.child2);
+        if axis == self.split_axis {
+            let (min_first, min_second) = self.min_size;
+            first.max(min_first) + second.max(min_second) + self.bar_area()
+        } else {
+            first.max(second)
+        }
+    }
+
     /// Set a new chosen split point.
     fn update_split_point(&mut self, size: Size, mouse_pos: Point) {
         let (min_limit, max_limit) = self.split_side_limits(size);
@@ -491,6 +525,30 @@ impl<T: Data> Widget<T> for Split<T> {
         my_size
     }
 
+    #[instrument(name = "Split", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(axis, ctx, bc, data, env, false)
+    }
+
+    #[instrument(name = "Split", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(axis, ctx, bc, data, env, true)
+    }
+
     #[instrument(name = "Split", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         if self.solid {
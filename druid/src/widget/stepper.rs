@@ -21,7 +21,7 @@ use tracing::{instrument, trace};
 use crate::kurbo::BezPath;
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
 use crate::widget::prelude::*;
-use crate::{theme, Point, Rect, TimerToken};
+use crate::{theme, KbKey, Point, Rect, TimerToken};
 
 // Delay until stepper starts automatically changing valued when one of the button is held down.
 const STEPPER_REPEAT_DELAY: Duration = Duration::from_millis(500);
@@ -119,17 +119,20 @@ impl Widget<f64> for Stepper {
     #[instrument(name = "Stepper", level = "trace", skip(self, ctx, _data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &f64, env: &Env) {
         let stroke_width = 2.0;
-        let rounded_rect = ctx
-            .size()
-            .to_rect()
-            .inset(-stroke_width / 2.0)
-            .to_rounded_rect(4.0);
+        let rect = ctx.size().to_rect().inset(-stroke_width / 2.0);
+        let (rect, stroke_width) = ctx.snap_stroke(rect, stroke_width, env);
+        let rounded_rect = rect.to_rounded_rect(4.0);
 
         let height = ctx.size().height;
         let width = env.get(theme::BASIC_WIDGET_HEIGHT);
         let button_size = Size::new(width, height / 2.);
 
-        ctx.stroke(rounded_rect, &env.get(theme::BORDER_DARK), stroke_width);
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(rounded_rect, &border_color, stroke_width);
         ctx.clip(rounded_rect);
 
         // draw buttons for increase/decrease
@@ -262,13 +265,23 @@ impl Widget<f64> for Stepper {
                     ctx.set_active(false);
                 }
             }
+            Event::KeyDown(key) if ctx.is_focused() && !ctx.is_disabled() => {
+                match key.key {
+                    KbKey::ArrowUp => self.increment(data),
+                    KbKey::ArrowDown => self.decrement(data),
+                    _ => return,
+                }
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &f64, _env: &Env) {
-        if let LifeCycle::DisabledChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::DisabledChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
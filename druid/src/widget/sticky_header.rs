@@ -0,0 +1,151 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A section whose header stays in view while the section is scrolled.
+
+use crate::kurbo::{Affine, Point, Rect, Size, Vec2};
+use crate::widget::prelude::*;
+use crate::{Data, WidgetPod};
+use tracing::{instrument, trace};
+
+/// A section of scrolled content, made of a header above a body, whose header
+/// stays pinned to the top of the enclosing [`Scroll`] while any part of the
+/// section is in view.
+///
+/// Put a `StickyHeader` for each section in a column, for instance the groups
+/// of a contact list; as the next section scrolls up, its header pushes the
+/// pinned header out of view.
+///
+/// The pinned header is painted over the body, so it should have an opaque
+/// background.
+///
+/// [`Scroll`]: crate::widget::Scroll
+pub struct StickyHeader<T> {
+    header: WidgetPod<T, Box<dyn Widget<T>>>,
+    body: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+impl<T> StickyHeader<T> {
+    /// Create a new section with the given header and body.
+    pub fn new(header: impl Widget<T> + 'static, body: impl Widget<T> + 'static) -> Self {
+        StickyHeader {
+            header: WidgetPod::new(header).boxed(),
+            body: WidgetPod::new(body).boxed(),
+        }
+    }
+
+    /// How far the header is moved down from the top of the section, to stay
+    /// in view, given the visible part of the section.
+    fn pin(&self, visible: Rect, size: Size) -> f64 {
+        pin_offset(visible.y0, size.height, self.header.layout_rect().height())
+    }
+}
+
+impl<T: Data> Widget<T> for StickyHeader<T> {
+    #[instrument(
+        name = "StickyHeader",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let pin = self.pin(ctx.visible_rect(), ctx.size());
+        let header_rect = self.header.layout_rect() + Vec2::new(0.0, pin);
+        let force = self.header.is_active() || self.header.is_hot();
+        if let Some(event) = event.transform_scroll(Vec2::new(0.0, -pin), header_rect, force) {
+            self.header.event(ctx, &event, data, env);
+        }
+        // a click on the pinned header is not meant for the body underneath
+        let on_header = matches!(event, Event::MouseDown(mouse) if header_rect.contains(mouse.pos));
+        if !on_header {
+            self.body.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "StickyHeader",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.header.lifecycle(ctx, event, data, env);
+        self.body.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "StickyHeader",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.header.update(ctx, data, env);
+        self.body.update(ctx, data, env);
+    }
+
+    #[instrument(name = "StickyHeader", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("StickyHeader");
+
+        let header_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, f64::INFINITY),
+        );
+        let header_size = self.header.layout(ctx, &header_bc, data, env);
+        self.header.set_origin(ctx, data, env, Point::ORIGIN);
+
+        let body_bc = bc.shrink((0.0, header_size.height));
+        let body_size = self.body.layout(ctx, &body_bc, data, env);
+        self.body
+            .set_origin(ctx, data, env, Point::new(0.0, header_size.height));
+
+        let size = bc.constrain(Size::new(
+            header_size.width.max(body_size.width),
+            header_size.height + body_size.height,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "StickyHeader", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let pin = self.pin(ctx.visible_rect(), ctx.size());
+        self.body.paint(ctx, data, env);
+        ctx.with_save(|ctx| {
+            ctx.transform(Affine::translate((0.0, pin)));
+            self.header.paint_always(ctx, data, env);
+        });
+    }
+}
+
+/// How far to move a header down from the top of its section so that it stays
+/// in view, given the top of the visible area relative to the section.
+fn pin_offset(visible_top: f64, section_height: f64, header_height: f64) -> f64 {
+    visible_top.min(section_height - header_height).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_is_pushed_out() {
+        // the section is below the top of the viewport
+        assert_eq!(pin_offset(-50.0, 300.0, 20.0), 0.0);
+        // the section is partly scrolled out of view
+        assert_eq!(pin_offset(100.0, 300.0, 20.0), 100.0);
+        // the next section pushes the header up
+        assert_eq!(pin_offset(290.0, 300.0, 20.0), 280.0);
+        // the section is out of view
+        assert_eq!(pin_offset(400.0, 300.0, 20.0), 280.0);
+    }
+}
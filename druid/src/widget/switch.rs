@@ -20,7 +20,7 @@ use tracing::{instrument, trace};
 use crate::kurbo::{Circle, Shape};
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
 use crate::widget::prelude::*;
-use crate::{theme, ArcStr, Point, TextLayout};
+use crate::{theme, ArcStr, KbKey, Point, TextLayout};
 
 const SWITCH_CHANGE_TIME: f64 = 0.2;
 const SWITCH_PADDING: f64 = 3.;
@@ -164,6 +164,16 @@ impl Widget<bool> for Switch {
                     ctx.request_paint();
                 }
             }
+            Event::KeyDown(key)
+                if key.key == KbKey::Character(" ".into())
+                    && ctx.is_focused()
+                    && !ctx.is_disabled() =>
+            {
+                *data = !*data;
+                self.animation_in_progress = true;
+                ctx.request_anim_frame();
+                ctx.set_handled();
+            }
             _ => (),
         }
     }
@@ -180,6 +190,8 @@ impl Widget<bool> for Switch {
                 self.animation_in_progress = true;
                 ctx.request_anim_frame();
             }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
             LifeCycle::DisabledChanged(disabled) => {
                 ctx.request_paint();
                 let color = if *disabled {
@@ -242,8 +254,9 @@ impl Widget<bool> for Switch {
 
         let background_rect = Size::new(switch_width, switch_height)
             .to_rect()
-            .inset(-stroke_width / 2.0)
-            .to_rounded_rect(switch_height / 2.);
+            .inset(-stroke_width / 2.0);
+        let (background_rect, stroke_width) = ctx.snap_stroke(background_rect, stroke_width, env);
+        let background_rect = background_rect.to_rounded_rect(switch_height / 2.);
 
         // position knob
         if !self.animation_in_progress && !self.knob_dragged {
@@ -283,7 +296,12 @@ impl Widget<bool> for Switch {
             ),
         );
 
-        ctx.stroke(background_rect, &env.get(theme::BORDER_DARK), stroke_width);
+        let border_color = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(background_rect, &border_color, stroke_width);
         ctx.fill(background_rect, &background_gradient_on_state);
         ctx.fill(background_rect, &background_gradient_off_state);
         ctx.clip(background_rect);
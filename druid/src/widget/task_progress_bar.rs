@@ -0,0 +1,133 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget displaying the progress of a background task.
+
+use crate::lens;
+use crate::widget::prelude::*;
+use crate::widget::{Button, Flex, Label, ProgressBar};
+use crate::{LocalizedString, Point, TaskProgress, WidgetExt, WidgetPod};
+use tracing::instrument;
+
+/// A widget that displays a [`TaskProgress`]: its message, a [`ProgressBar`],
+/// and a button to cancel the task.
+///
+/// This widget applies the updates sent by the task's [`ProgressReporter`]
+/// to its data.
+///
+/// [`ProgressReporter`]: crate::ProgressReporter
+pub struct TaskProgressBar {
+    inner: WidgetPod<TaskProgress, Flex<TaskProgress>>,
+}
+
+impl TaskProgressBar {
+    /// Create a new `TaskProgressBar`.
+    pub fn new() -> TaskProgressBar {
+        let fraction = lens::Map::new(
+            |task: &TaskProgress| task.fraction().unwrap_or(0.0),
+            |_, _| (),
+        );
+        let inner = Flex::row()
+            .with_flex_child(
+                Label::dynamic(|task: &TaskProgress, _| task.message().to_string()),
+                1.0,
+            )
+            .with_default_spacer()
+            .with_child(ProgressBar::new().lens(fraction))
+            .with_default_spacer()
+            .with_child(
+                Button::new(LocalizedString::new("task-progress-cancel"))
+                    .on_click(|_, task: &mut TaskProgress, _| task.cancel())
+                    .disabled_if(|task, _| !task.is_running()),
+            );
+        TaskProgressBar {
+            inner: WidgetPod::new(inner),
+        }
+    }
+}
+
+impl Default for TaskProgressBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<TaskProgress> for TaskProgressBar {
+    #[instrument(
+        name = "TaskProgressBar",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut TaskProgress, env: &Env) {
+        if let Event::Command(cmd) = event {
+            // not handled; other widgets may be showing the same task.
+            if data.update_from_command(cmd) {
+                return;
+            }
+        }
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "TaskProgressBar",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &TaskProgress,
+        env: &Env,
+    ) {
+        self.inner.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "TaskProgressBar",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        _old_data: &TaskProgress,
+        data: &TaskProgress,
+        env: &Env,
+    ) {
+        self.inner.update(ctx, data, env);
+    }
+
+    #[instrument(
+        name = "TaskProgressBar",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &TaskProgress,
+        env: &Env,
+    ) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, data, env, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "TaskProgressBar", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &TaskProgress, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+}
@@ -20,16 +20,16 @@ use tracing::{instrument, trace};
 use crate::kurbo::Insets;
 use crate::piet::TextLayout as _;
 use crate::text::{
-    EditableText, ImeInvalidation, Selection, TextComponent, TextLayout, TextStorage,
+    ArrowKeyMode, EditableText, ImeInvalidation, InputHints, Selection, TextComponent, TextLayout,
+    TextStorage,
 };
 use crate::widget::prelude::*;
 use crate::widget::{Padding, Scroll, WidgetWrapper};
 use crate::{
-    theme, Color, Command, FontDescriptor, HotKey, KeyEvent, KeyOrValue, Point, Rect, SysMods,
-    TextAlignment, TimerToken, Vec2,
+    theme, Color, Command, FontDescriptor, HotKey, KbKey, KeyEvent, KeyOrValue, Point, Rect,
+    SysMods, TextAlignment, TimerToken, Vec2,
 };
 
-const CURSOR_BLINK_DURATION: Duration = Duration::from_millis(500);
 const MAC_OR_LINUX: bool = cfg!(any(target_os = "macos", target_os = "linux"));
 
 /// When we scroll after editing or movement, we show a little extra of the document.
@@ -138,15 +138,9 @@ impl<T> TextBox<T> {
     /// in which case it determines how the text is positioned inside the
     /// `TextBox` when it does not fill the available space.
     ///
-    /// # Note:
-    ///
-    /// This does not behave exactly like [`TextAlignment`] does when used
-    /// with label; in particular this does not account for reading direction.
-    /// This means that `TextAlignment::Start` (the default) always means
-    /// *left aligned*, and `TextAlignment::End` always means *right aligned*.
-    ///
-    /// This should be considered a bug, but it will not be fixed until proper
-    /// BiDi support is implemented.
+    /// `TextAlignment::Start` (the default) and `TextAlignment::End` account
+    /// for reading direction: text that begins with a right-to-left script is
+    /// right aligned by `TextAlignment::Start`.
     ///
     /// [`TextAlignment`]: enum.TextAlignment.html
     /// [`multiline`]: #method.multiline
@@ -226,15 +220,9 @@ impl<T> TextBox<T> {
     /// in which case it determines how the text is positioned inside the
     /// `TextBox` when it does not fill the available space.
     ///
-    /// # Note:
-    ///
-    /// This does not behave exactly like [`TextAlignment`] does when used
-    /// with label; in particular this does not account for reading direction.
-    /// This means that `TextAlignment::Start` (the default) always means
-    /// *left aligned*, and `TextAlignment::End` always means *right aligned*.
-    ///
-    /// This should be considered a bug, but it will not be fixed until proper
-    /// BiDi support is implemented.
+    /// `TextAlignment::Start` (the default) and `TextAlignment::End` account
+    /// for reading direction: text that begins with a right-to-left script is
+    /// right aligned by `TextAlignment::Start`.
     ///
     /// [`TextAlignment`]: enum.TextAlignment.html
     /// [`multiline`]: #method.multiline
@@ -246,6 +234,52 @@ impl<T> TextBox<T> {
         self.text_mut().borrow_mut().set_text_alignment(alignment);
     }
 
+    /// Builder-style method to set how the left and right arrow keys move
+    /// the caret in bidirectional text.
+    ///
+    /// The default is [`ArrowKeyMode::Logical`].
+    pub fn with_arrow_key_mode(mut self, mode: ArrowKeyMode) -> Self {
+        self.set_arrow_key_mode(mode);
+        self
+    }
+
+    /// Set how the left and right arrow keys move the caret in bidirectional text.
+    pub fn set_arrow_key_mode(&mut self, mode: ArrowKeyMode) {
+        if !self.text().can_write() {
+            tracing::warn!("set_arrow_key_mode called with IME lock held.");
+            return;
+        }
+        self.text_mut().borrow_mut().set_arrow_key_mode(mode);
+    }
+
+    /// Builder-style method to set hints about the expected text, such as
+    /// whether it is a number or an email address, for the platform's input
+    /// method and on-screen keyboard.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::text::{InputHints, InputPurpose};
+    /// use druid::widget::TextBox;
+    ///
+    /// let email = TextBox::<String>::new()
+    ///     .with_input_hints(InputHints::new(InputPurpose::Email).autocorrect(false));
+    /// ```
+    pub fn with_input_hints(mut self, hints: InputHints) -> Self {
+        self.set_input_hints(hints);
+        self
+    }
+
+    /// Set hints about the expected text, for the platform's input method
+    /// and on-screen keyboard.
+    pub fn set_input_hints(&mut self, hints: InputHints) {
+        if !self.text().can_write() {
+            tracing::warn!("set_input_hints called with IME lock held.");
+            return;
+        }
+        self.text_mut().borrow_mut().set_input_hints(hints);
+    }
+
     /// Set the text color.
     ///
     /// The argument can be either a `Color` or a [`Key<Color>`].
@@ -292,17 +326,10 @@ impl<T> TextBox<T> {
         self.inner.child_mut().wrapped_mut()
     }
 
-    fn reset_cursor_blink(&mut self, token: TimerToken) {
+    /// Turns the cursor on, restarting the blink with `token` if the cursor blinks.
+    fn reset_cursor_blink(&mut self, token: Option<TimerToken>) {
         self.cursor_on = true;
-        self.cursor_timer = token;
-    }
-
-    fn should_draw_cursor(&self) -> bool {
-        if cfg!(target_os = "macos") && self.text().can_read() {
-            self.cursor_on && self.text().borrow().selection().is_caret()
-        } else {
-            self.cursor_on
-        }
+        self.cursor_timer = token.unwrap_or(TimerToken::INVALID);
     }
 }
 
@@ -355,6 +382,15 @@ impl<T: TextStorage + EditableText> TextBox<T> {
                 Some(sys::REDO.to(our_id))
             }
             key if HotKey::new(SysMods::Cmd, "a").matches(key) => Some(sys::SELECT_ALL.to(our_id)),
+            key if HotKey::new(SysMods::Cmd, "d").matches(key) => {
+                Some(sys::SELECT_NEXT_OCCURRENCE.to(our_id))
+            }
+            key if HotKey::new(SysMods::AltCmd, KbKey::ArrowUp).matches(key) => {
+                Some(sys::ADD_CURSOR_ABOVE.to(our_id))
+            }
+            key if HotKey::new(SysMods::AltCmd, KbKey::ArrowDown).matches(key) => {
+                Some(sys::ADD_CURSOR_BELOW.to(our_id))
+            }
             _ => None,
         }
     }
@@ -404,7 +440,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                     if !mouse.focus {
                         ctx.request_focus();
                         self.was_focused_from_click = true;
-                        self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                        self.reset_cursor_blink(
+                            cursor_blink_interval(env).map(|d| ctx.request_timer(d)),
+                        );
                     } else {
                         ctx.set_handled();
                     }
@@ -413,9 +451,14 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             Event::Timer(id) => {
                 if !ctx.is_disabled() {
                     if *id == self.cursor_timer && ctx.has_focus() {
-                        self.cursor_on = !self.cursor_on;
+                        match cursor_blink_interval(env) {
+                            Some(interval) => {
+                                self.cursor_on = !self.cursor_on;
+                                self.cursor_timer = ctx.request_timer(interval);
+                            }
+                            None => self.reset_cursor_blink(None),
+                        }
                         ctx.request_paint();
-                        self.cursor_timer = ctx.request_timer(CURSOR_BLINK_DURATION);
                     }
                 } else if self.cursor_on {
                     self.cursor_on = false;
@@ -423,14 +466,16 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 }
             }
             Event::ImeStateChange => {
-                self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                self.reset_cursor_blink(cursor_blink_interval(env).map(|d| ctx.request_timer(d)));
             }
             Event::Command(ref cmd)
                 if !self.text().is_composing()
                     && ctx.is_focused()
                     && cmd.is(crate::commands::COPY) =>
             {
-                self.text().borrow().set_clipboard();
+                if let Err(e) = self.text().borrow().set_clipboard() {
+                    ctx.submit_command(crate::commands::SHELL_ERROR.with(e));
+                }
                 ctx.set_handled();
             }
             Event::Command(cmd)
@@ -438,9 +483,38 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                     && ctx.is_focused()
                     && cmd.is(crate::commands::CUT) =>
             {
-                if self.text().borrow().set_clipboard() {
-                    let inval = self.text_mut().borrow_mut().insert_text(data, "");
+                let copied = self.text().borrow().set_clipboard();
+                match copied {
+                    Ok(true) => {
+                        let inval = self.text_mut().borrow_mut().insert_text(data, "");
+                        ctx.invalidate_text_input(inval);
+                    }
+                    Ok(false) => (),
+                    Err(e) => ctx.submit_command(crate::commands::SHELL_ERROR.with(e)),
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if !self.text().is_composing()
+                    && ctx.is_focused()
+                    && (cmd.is(crate::commands::ADD_CURSOR_ABOVE)
+                        || cmd.is(crate::commands::ADD_CURSOR_BELOW)
+                        || cmd.is(crate::commands::SELECT_NEXT_OCCURRENCE)) =>
+            {
+                let inval = if cmd.is(crate::commands::ADD_CURSOR_ABOVE) {
+                    self.text_mut().borrow_mut().add_cursor_above()
+                } else if cmd.is(crate::commands::ADD_CURSOR_BELOW) {
+                    self.text_mut().borrow_mut().add_cursor_below()
+                } else {
+                    self.text_mut().borrow_mut().select_next_occurrence()
+                };
+                if let Some(inval) = inval {
                     ctx.invalidate_text_input(inval);
+                    self.reset_cursor_blink(
+                        cursor_blink_interval(env).map(|d| ctx.request_timer(d)),
+                    );
+                    self.scroll_to_selection_end();
+                    ctx.request_paint();
                 }
                 ctx.set_handled();
             }
@@ -479,7 +553,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                     ctx.invalidate_text_input(ImeInvalidation::SelectionChanged);
                 }
                 self.text_mut().has_focus = true;
-                self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                self.reset_cursor_blink(cursor_blink_interval(env).map(|d| ctx.request_timer(d)));
                 self.was_focused_from_click = false;
                 ctx.request_paint();
             }
@@ -564,6 +638,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         let size = ctx.size();
         let background_color = env.get(theme::BACKGROUND_LIGHT);
         let cursor_color = env.get(theme::CURSOR_COLOR);
+        let cursor_width = env.get(theme::CURSOR_WIDTH);
         let border_width = env.get(theme::TEXTBOX_BORDER_WIDTH);
         let textbox_insets = env.get(theme::TEXTBOX_INSETS);
 
@@ -576,10 +651,9 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         };
 
         // Paint the background
-        let clip_rect = size
-            .to_rect()
-            .inset(-border_width / 2.0)
-            .to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
+        let clip_rect = size.to_rect().inset(-border_width / 2.0);
+        let (clip_rect, border_width) = ctx.snap_stroke(clip_rect, border_width, env);
+        let clip_rect = clip_rect.to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
 
         ctx.fill(clip_rect, &background_color);
 
@@ -593,7 +667,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             let x_offset = if self.multiline {
                 0.0
             } else {
-                x_offset_for_extra_width(alignment, extra_width)
+                x_offset_for_extra_width(alignment, extra_width, self.placeholder.text_is_rtl())
             };
 
             // clip when we draw the placeholder, since it isn't in a clipbox
@@ -604,31 +678,37 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             })
         }
 
-        // Paint the cursor if focused and there's no selection
-        if is_focused && self.should_draw_cursor() {
-            // if there's no data, we always draw the cursor based on
-            // our alignment.
-            let cursor_pos = self.text().borrow().selection().active;
-            let cursor_line = self
-                .text()
-                .borrow()
-                .cursor_line_for_text_position(cursor_pos);
-
+        // Paint a cursor for each selection if focused; on macOS, only for
+        // selections that are carets.
+        if is_focused && self.cursor_on {
+            let text = self.text().borrow();
             let padding_offset = Vec2::new(textbox_insets.x0, textbox_insets.y0);
-
-            let mut cursor = if data.is_empty() {
-                cursor_line + padding_offset
-            } else {
-                cursor_line + padding_offset - self.inner.offset()
-            };
-
             // Snap the cursor to the pixel grid so it stays sharp.
-            cursor.p0.x = cursor.p0.x.trunc() + 0.5;
-            cursor.p1.x = cursor.p0.x;
+            let snap_offset = (cursor_width.round() / 2.0).fract();
+            let cursors = std::iter::once(text.selection())
+                .chain(text.extra_selections().iter().copied())
+                .filter(|sel| !cfg!(target_os = "macos") || sel.is_caret())
+                .map(|sel| {
+                    // if there's no data, we always draw the cursor based on
+                    // our alignment.
+                    let cursor_line = text.cursor_line_for_text_position(sel.active);
+                    let mut cursor = if data.is_empty() {
+                        cursor_line + padding_offset
+                    } else {
+                        cursor_line + padding_offset - self.inner.offset()
+                    };
+                    cursor.p0.x = cursor.p0.x.trunc() + snap_offset;
+                    cursor.p1.x = cursor.p0.x;
+                    cursor
+                })
+                .collect::<Vec<_>>();
+            drop(text);
 
             ctx.with_save(|ctx| {
                 ctx.clip(clip_rect);
-                ctx.stroke(cursor, &cursor_color, 1.);
+                for cursor in cursors {
+                    ctx.stroke(cursor, &cursor_color, cursor_width);
+                }
             })
         }
 
@@ -643,9 +723,19 @@ impl<T: TextStorage + EditableText> Default for TextBox<T> {
     }
 }
 
-fn x_offset_for_extra_width(alignment: TextAlignment, extra_width: f64) -> f64 {
+/// How long the cursor stays on or off, or `None` if it shouldn't blink.
+fn cursor_blink_interval(env: &Env) -> Option<Duration> {
+    match env.get(theme::CURSOR_BLINK_INTERVAL) {
+        0 => None,
+        millis => Some(Duration::from_millis(millis)),
+    }
+}
+
+fn x_offset_for_extra_width(alignment: TextAlignment, extra_width: f64, is_rtl: bool) -> f64 {
     match alignment {
+        TextAlignment::Start | TextAlignment::Justified if is_rtl => extra_width,
         TextAlignment::Start | TextAlignment::Justified => 0.0,
+        TextAlignment::End if is_rtl => 0.0,
         TextAlignment::End => extra_width,
         TextAlignment::Center => extra_width / 2.0,
     }
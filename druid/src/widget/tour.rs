@@ -0,0 +1,364 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A guided tour, that highlights a sequence of widgets.
+
+use crate::command::sys::{REQUEST_WIDGET_RECT, WIDGET_RECT};
+use crate::kurbo::{RoundedRect, Shape};
+use crate::widget::popover::{bubble_path, place_content, ARROW_SIZE};
+use crate::widget::prelude::*;
+use crate::widget::{
+    Button, CrossAxisAlignment, Flex, Label, LineBreaking, PopoverSide, WidgetExt,
+};
+use crate::{theme, Color, Data, KbKey, Point, Rect, Selector, WidgetPod};
+use tracing::{instrument, trace};
+
+/// The color that everything but the highlighted widget is dimmed with.
+const DIM_COLOR: Color = Color::rgba8(0, 0, 0, 0x80);
+/// The space between a highlighted widget and the edge of its cutout.
+const CUTOUT_PADDING: f64 = 4.0;
+const CARD_WIDTH: f64 = 280.0;
+
+/// One step of a [`Tour`]: a widget to highlight, and an explanation of it.
+#[derive(Debug, Clone)]
+pub struct TourStep {
+    target: WidgetId,
+    title: String,
+    text: String,
+    side: PopoverSide,
+}
+
+/// A guided tour, for onboarding new users: a sequence of steps that each
+/// highlight a widget and explain it.
+///
+/// A `Tour` wraps a part of the interface, usually the whole window. While a
+/// step is shown, everything but the step's target widget is dimmed, and an
+/// explanation card is shown next to the target, with buttons to go to the
+/// next or the previous step. The targets are found by their [`WidgetId`],
+/// and have to be descendants of the tour; a step whose target can't be
+/// found is shown in the middle, without a highlight.
+///
+/// The tour is started by a [`START`] notification from its child, or a
+/// [`START`] command sent to its id; [`NEXT`], [`BACK`] and [`STOP`] can be
+/// sent in the same way. It is stopped when escape is pressed. While the tour
+/// is shown the rest of the interface doesn't receive mouse or keyboard
+/// input. When the tour ends, either after its last step or when it is
+/// stopped, a [`FINISHED`] notification is submitted.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Flex, Tour, TourStep};
+/// use druid::{WidgetExt, WidgetId};
+///
+/// let new_id = WidgetId::next();
+/// let open_id = WidgetId::next();
+/// let toolbar = Flex::row()
+///     .with_child(Button::new("New").with_id(new_id))
+///     .with_child(Button::new("Open").with_id(open_id))
+///     .with_child(Button::new("Help").on_click(|ctx, _data, _env| {
+///         ctx.submit_notification(Tour::START);
+///     }));
+///
+/// let tour = Tour::new(toolbar)
+///     .with_step(TourStep::new(new_id, "New", "Start a new document."))
+///     .with_step(TourStep::new(open_id, "Open", "Open an existing document."));
+/// # let _: Tour<()> = tour;
+/// ```
+///
+/// [`START`]: Tour::START
+/// [`NEXT`]: Tour::NEXT
+/// [`BACK`]: Tour::BACK
+/// [`STOP`]: Tour::STOP
+/// [`FINISHED`]: Tour::FINISHED
+pub struct Tour<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    steps: Vec<TourStep>,
+    /// The index of the step that is shown, if the tour is running.
+    current: Option<usize>,
+    card: Option<WidgetPod<(), Box<dyn Widget<()>>>>,
+    /// The rect of the current step's target, in our coordinate space.
+    target_rect: Option<Rect>,
+    /// The side the card was placed on in the last layout.
+    placed_side: PopoverSide,
+}
+
+impl TourStep {
+    /// Create a new step, that highlights the widget with the id `target`
+    /// and explains it with a `title` and a `text`.
+    pub fn new(target: WidgetId, title: impl Into<String>, text: impl Into<String>) -> Self {
+        TourStep {
+            target,
+            title: title.into(),
+            text: text.into(),
+            side: PopoverSide::Below,
+        }
+    }
+
+    /// Builder-style method to set the side of the target the explanation
+    /// is shown on, when it fits. The default is below the target.
+    pub fn side(mut self, side: PopoverSide) -> Self {
+        self.side = side;
+        self
+    }
+}
+
+impl Tour<()> {
+    /// Start the tour from its first step.
+    pub const START: Selector = Selector::new("druid-builtin.tour-start");
+
+    /// Go to the next step, or end the tour after its last step.
+    pub const NEXT: Selector = Selector::new("druid-builtin.tour-next");
+
+    /// Go to the previous step.
+    pub const BACK: Selector = Selector::new("druid-builtin.tour-back");
+
+    /// Stop the tour.
+    pub const STOP: Selector = Selector::new("druid-builtin.tour-stop");
+
+    /// A notification submitted when the tour ends. The payload is `true`
+    /// if the tour was completed, and `false` if it was stopped early.
+    pub const FINISHED: Selector<bool> = Selector::new("druid-builtin.tour-finished");
+}
+
+impl<T: Data> Tour<T> {
+    /// Create a new `Tour` over `child`, with no steps.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        Tour {
+            child: WidgetPod::new(child).boxed(),
+            steps: Vec::new(),
+            current: None,
+            card: None,
+            target_rect: None,
+            placed_side: PopoverSide::Below,
+        }
+    }
+
+    /// Builder-style method to add a step to the tour.
+    pub fn with_step(mut self, step: TourStep) -> Self {
+        self.add_step(step);
+        self
+    }
+
+    /// Add a step to the tour.
+    pub fn add_step(&mut self, step: TourStep) {
+        self.steps.push(step);
+    }
+
+    /// Whether the tour is running.
+    pub fn is_running(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Show the step at `idx`, or end the tour if there is no such step.
+    fn show_step(&mut self, ctx: &mut EventCtx, idx: Option<usize>, completed: bool) {
+        let idx = idx.filter(|idx| *idx < self.steps.len());
+        let was_running = self.current.is_some();
+        trace!("Tour {:?} showing step {:?}", ctx.widget_id(), idx);
+        self.current = idx;
+        self.target_rect = None;
+        self.card = idx.map(|idx| WidgetPod::new(self.build_card(idx)));
+        ctx.children_changed();
+        ctx.request_layout();
+        // capture the mouse, so the rest of the interface doesn't get it.
+        ctx.set_active(idx.is_some());
+        match idx {
+            Some(idx) => {
+                let request = REQUEST_WIDGET_RECT.with(ctx.widget_id());
+                ctx.submit_command(request.to(self.steps[idx].target));
+            }
+            None if was_running => ctx.submit_notification(Tour::FINISHED.with(completed)),
+            None => {}
+        }
+    }
+
+    /// Build the explanation card of the step at `idx`.
+    fn build_card(&self, idx: usize) -> Box<dyn Widget<()>> {
+        let step = &self.steps[idx];
+        let is_last = idx + 1 == self.steps.len();
+
+        let mut buttons = Flex::row()
+            .with_child(Label::new(format!("{} of {}", idx + 1, self.steps.len())))
+            .with_flex_spacer(1.0);
+        if idx > 0 {
+            buttons.add_child(
+                Button::new("Back").on_click(|ctx, _, _| ctx.submit_notification(Tour::BACK)),
+            );
+            buttons.add_default_spacer();
+        }
+        let next_label = if is_last { "Done" } else { "Next" };
+        buttons.add_child(
+            Button::new(next_label).on_click(|ctx, _, _| ctx.submit_notification(Tour::NEXT)),
+        );
+
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Label::new(step.title.clone()).with_font(theme::UI_FONT_BOLD))
+            .with_default_spacer()
+            .with_child(Label::new(step.text.clone()).with_line_break_mode(LineBreaking::WordWrap))
+            .with_default_spacer()
+            .with_child(buttons)
+            .padding(12.0)
+            .fix_width(CARD_WIDTH)
+            .boxed()
+    }
+
+    fn handle_selector(&mut self, ctx: &mut EventCtx, event: &Event) -> bool {
+        let selector_is = |selector| match event {
+            Event::Notification(note) => note.is(selector),
+            Event::Command(cmd) => cmd.is(selector),
+            _ => false,
+        };
+        if selector_is(Tour::START) {
+            self.show_step(ctx, Some(0), false);
+        } else if selector_is(Tour::NEXT) {
+            if let Some(idx) = self.current {
+                self.show_step(ctx, Some(idx + 1), true);
+            }
+        } else if selector_is(Tour::BACK) {
+            if let Some(idx) = self.current {
+                self.show_step(ctx, Some(idx.saturating_sub(1)), false);
+            }
+        } else if selector_is(Tour::STOP) {
+            self.show_step(ctx, None, false);
+        } else {
+            return false;
+        }
+        true
+    }
+}
+
+impl<T: Data> Widget<T> for Tour<T> {
+    #[instrument(name = "Tour", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if self.handle_selector(ctx, event) {
+            ctx.set_handled();
+            return;
+        }
+        if let Event::Command(cmd) = event {
+            if let Some((id, rect)) = cmd.get(WIDGET_RECT) {
+                let target = self.current.map(|idx| self.steps[idx].target);
+                if target == Some(*id) {
+                    let rect = *rect - ctx.window_origin().to_vec2();
+                    if self.target_rect != Some(rect) {
+                        self.target_rect = Some(rect);
+                        ctx.request_layout();
+                    }
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        let card = match self.card.as_mut() {
+            Some(card) => card,
+            None => {
+                self.child.event(ctx, event, data, env);
+                return;
+            }
+        };
+
+        if let Event::KeyDown(key) = event {
+            if key.key == KbKey::Escape {
+                self.show_step(ctx, None, false);
+                ctx.set_handled();
+                return;
+            }
+        }
+        card.event(ctx, event, &mut (), env);
+        if event.should_propagate_to_hidden() {
+            self.child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Tour", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+        if let Some(card) = self.card.as_mut() {
+            card.lifecycle(ctx, event, &(), env);
+        }
+    }
+
+    #[instrument(name = "Tour", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        if let Some(card) = self.card.as_mut() {
+            card.update(ctx, &(), env);
+        }
+    }
+
+    #[instrument(name = "Tour", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Tour");
+
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+
+        if let (Some(idx), Some(card)) = (self.current, self.card.as_mut()) {
+            let step = &self.steps[idx];
+            // the target may have moved; this only causes another layout
+            // if its rect changed.
+            let request = REQUEST_WIDGET_RECT.with(ctx.widget_id());
+            ctx.submit_command(request.to(step.target));
+
+            let card_bc = BoxConstraints::new(Size::ZERO, size);
+            let card_size = card.layout(ctx, &card_bc, &(), env);
+            let bounds = Some(size.to_rect());
+            let origin = match self.target_rect {
+                Some(target) => {
+                    let target = target.inflate(CUTOUT_PADDING, CUTOUT_PADDING);
+                    let (side, rect) =
+                        place_content(step.side, target, card_size, ARROW_SIZE, bounds);
+                    self.placed_side = side;
+                    rect.origin()
+                }
+                None => Rect::from_center_size(size.to_rect().center(), card_size).origin(),
+            };
+            card.set_origin(ctx, &(), env, origin);
+        }
+        size
+    }
+
+    #[instrument(name = "Tour", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+
+        let card = match self.card.as_mut() {
+            Some(card) => card,
+            None => return,
+        };
+
+        let mut veil = ctx.size().to_rect().to_path(0.1);
+        if let Some(target) = self.target_rect {
+            let cutout = RoundedRect::from_rect(target.inflate(CUTOUT_PADDING, CUTOUT_PADDING), 4.);
+            veil.extend(cutout.path_elements(0.1));
+        }
+        ctx.fill_even_odd(veil, &DIM_COLOR);
+
+        let rect = card.layout_rect();
+        match self.target_rect {
+            Some(target) => {
+                let path = bubble_path(rect, self.placed_side, target.center());
+                ctx.fill(&path, &env.get(theme::BACKGROUND_LIGHT));
+                ctx.stroke(&path, &env.get(theme::BORDER_DARK), 1.0);
+            }
+            None => {
+                let rect = rect.to_rounded_rect(4.);
+                ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+                ctx.stroke(rect, &env.get(theme::BORDER_DARK), 1.0);
+            }
+        }
+        card.paint(ctx, &(), env);
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A controller that keeps an undo history of its data.
+
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::commands::{REDO, UNDO};
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{Lens, Selector};
+
+/// Copies the excluded part of the first value into the second.
+type ExcludeFn<T> = Box<dyn Fn(&T, &mut T)>;
+
+/// A [`Controller`] that records changes to its data, and undoes and redoes
+/// them.
+///
+/// Every change to the data is an entry in the history; [`UNDO`] restores the
+/// data from before the last entry, and [`REDO`] reapplies an undone entry.
+/// These are the commands of the standard Undo and Redo menu items, so an
+/// `UndoManager` around the root widget of a window makes those items work
+/// for the whole window. Changes that are not undos or redos clear the redo
+/// history.
+///
+/// Several changes can be made into a single entry with the
+/// [`BEGIN_GROUP`] and [`END_GROUP`] commands, or by coalescing changes made
+/// in quick succession, like the keystrokes when typing, with
+/// [`coalesce_within`]. Parts of the data that are not worth undoing, like
+/// a scroll position or a selection, can be left out of the history with
+/// [`exclude`].
+///
+/// Undo commands that a widget submits to itself, like those of a [`TextBox`]
+/// with focus, are not seen by the `UndoManager`.
+///
+/// ```
+/// use druid::widget::{TextBox, UndoManager};
+/// use druid::{Data, Lens, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Note {
+///     text: String,
+///     scroll_offset: f64,
+/// }
+///
+/// let editor = TextBox::multiline().lens(Note::text).controller(
+///     UndoManager::new()
+///         .coalesce_within(std::time::Duration::from_secs(1))
+///         .exclude(Note::scroll_offset),
+/// );
+/// ```
+///
+/// [`UNDO`]: crate::commands::UNDO
+/// [`REDO`]: crate::commands::REDO
+/// [`BEGIN_GROUP`]: UndoManager::BEGIN_GROUP
+/// [`END_GROUP`]: UndoManager::END_GROUP
+/// [`coalesce_within`]: UndoManager::coalesce_within
+/// [`exclude`]: UndoManager::exclude
+/// [`TextBox`]: crate::widget::TextBox
+pub struct UndoManager<T> {
+    /// The data from before each entry, the most recent last.
+    undo: Vec<T>,
+    /// The data from before each undo, the most recent last.
+    redo: Vec<T>,
+    exclusions: Vec<ExcludeFn<T>>,
+    limit: usize,
+    coalesce: Duration,
+    /// When the last entry was last changed, while it can take more changes.
+    last_change: Option<Instant>,
+    /// How many groups are open.
+    group_depth: usize,
+    /// Whether the open group has an entry yet.
+    group_recorded: bool,
+    /// Whether the next data change is our own undo or redo.
+    restoring: bool,
+}
+
+impl UndoManager<()> {
+    /// Start a group of changes that are undone together.
+    ///
+    /// Groups can be nested; the changes belong to a single entry until the
+    /// outermost group is ended.
+    pub const BEGIN_GROUP: Selector = Selector::new("druid-builtin.undo-begin-group");
+
+    /// End a group started with [`BEGIN_GROUP`].
+    ///
+    /// [`BEGIN_GROUP`]: UndoManager::BEGIN_GROUP
+    pub const END_GROUP: Selector = Selector::new("druid-builtin.undo-end-group");
+
+    /// Forget the whole history, for instance after opening another document.
+    pub const CLEAR: Selector = Selector::new("druid-builtin.undo-clear");
+}
+
+impl<T: Data> UndoManager<T> {
+    /// Create an `UndoManager` with an empty history, that keeps the last
+    /// 100 entries.
+    pub fn new() -> Self {
+        UndoManager {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            exclusions: Vec::new(),
+            limit: 100,
+            coalesce: Duration::ZERO,
+            last_change: None,
+            group_depth: 0,
+            group_recorded: false,
+            restoring: false,
+        }
+    }
+
+    /// Builder-style method to set how many entries are kept.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Builder-style method to combine changes into one entry as long as each
+    /// comes within `interval` of the last.
+    pub fn coalesce_within(mut self, interval: Duration) -> Self {
+        self.coalesce = interval;
+        self
+    }
+
+    /// Builder-style method to leave the part of the data that `lens` focuses
+    /// on out of the history.
+    ///
+    /// Changes to only that part are not recorded, and undo and redo leave it
+    /// as it is.
+    pub fn exclude<U: Data>(mut self, lens: impl Lens<T, U> + 'static) -> Self {
+        self.exclusions.push(Box::new(move |from, to| {
+            let value = lens.with(from, |value| value.clone());
+            lens.with_mut(to, |target| {
+                if !target.same(&value) {
+                    *target = value;
+                }
+            });
+        }));
+        self
+    }
+
+    /// Whether there is an entry to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether there is an undone entry to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Take the excluded parts of `state` from `current`.
+    fn keep_excluded(&self, current: &T, mut state: T) -> T {
+        for exclude in &self.exclusions {
+            exclude(current, &mut state);
+        }
+        state
+    }
+
+    fn record(&mut self, old_data: &T, data: &T) {
+        if self.keep_excluded(data, old_data.clone()).same(data) {
+            return;
+        }
+        self.redo.clear();
+        let now = Instant::now();
+        let coalesced = match self.last_change {
+            Some(last) => now.duration_since(last) <= self.coalesce,
+            None => false,
+        };
+        if self.group_depth > 0 {
+            if self.group_recorded {
+                return;
+            }
+            self.group_recorded = true;
+        } else if coalesced {
+            self.last_change = Some(now);
+            return;
+        }
+        self.undo.push(old_data.clone());
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+        if self.coalesce > Duration::ZERO && self.group_depth == 0 {
+            self.last_change = Some(now);
+        }
+    }
+
+    /// Replace the data with `state` from one stack, saving it on the other.
+    fn restore(&mut self, state: T, data: &mut T, undo: bool) {
+        let current = data.clone();
+        *data = self.keep_excluded(&current, state);
+        // we only get an update if the data changed
+        self.restoring = !data.same(&current);
+        if undo {
+            self.redo.push(current);
+        } else {
+            self.undo.push(current);
+        }
+        self.last_change = None;
+    }
+}
+
+impl<T: Data> Default for UndoManager<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for UndoManager<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(UNDO) && self.can_undo() => {
+                let state = self.undo.pop().unwrap();
+                self.restore(state, data, true);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(REDO) && self.can_redo() => {
+                let state = self.redo.pop().unwrap();
+                self.restore(state, data, false);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(UndoManager::BEGIN_GROUP) => {
+                if self.group_depth == 0 {
+                    self.group_recorded = false;
+                    self.last_change = None;
+                }
+                self.group_depth += 1;
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(UndoManager::END_GROUP) => {
+                self.group_depth = self.group_depth.saturating_sub(1);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(UndoManager::CLEAR) => {
+                self.undo.clear();
+                self.redo.clear();
+                self.last_change = None;
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if self.restoring {
+            self.restoring = false;
+        } else if !old_data.same(data) {
+            self.record(old_data, data);
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
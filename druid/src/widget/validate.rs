@@ -0,0 +1,300 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that validates its data, for forms.
+
+use std::sync::Arc;
+
+use crate::lens;
+use crate::text::ArcStr;
+use crate::widget::prelude::*;
+use crate::widget::{Axis, WidgetWrapper};
+use crate::{Data, Lens};
+
+use tracing::{instrument, trace};
+
+/// Checks the data, returning an error message if it is invalid.
+type CheckFn<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+
+/// The errors found by a [`Validate`] widget, keyed by the name of the field
+/// they belong to.
+///
+/// Errors about the data as a whole are under [`ValidationErrors::FORM`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationErrors(Arc<Vec<(&'static str, ArcStr)>>);
+
+/// Data that has been checked by a [`Validate`] widget, with the errors that
+/// were found.
+///
+/// This is the data of the child of a [`Validate`]; use the [`value`] lens
+/// to get to the data itself, and [`field_error`] to show the error of a
+/// field.
+///
+/// [`value`]: Validated::value
+/// [`field_error`]: Validated::field_error
+#[derive(Debug, Clone, Data, Lens)]
+pub struct Validated<T> {
+    /// The data.
+    pub value: T,
+    /// The errors in the data.
+    pub errors: ValidationErrors,
+}
+
+/// A widget that checks its data whenever it changes, and gives its child
+/// the data along with the errors it found.
+///
+/// Each check belongs to a field, named by a string; [`ValidationErrors`]
+/// collects the errors by field, and [`Validated::field_error`] is a lens to
+/// the first error of a field, to show next to it. Checks of the form as
+/// a whole, like two fields that have to agree, are added with [`form`].
+///
+/// A submit button can be disabled until the form is valid with
+/// [`disabled_if`] and [`Validated::is_valid`].
+///
+/// The errors are read-only; changes the child makes to them are discarded.
+///
+/// ```
+/// use druid::widget::{Button, Flex, Label, TextBox, Validate, Validated};
+/// use druid::{Data, Lens, LensExt, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct SignUp {
+///     email: String,
+///     password: String,
+///     repeated: String,
+/// }
+///
+/// let form = Flex::column()
+///     .with_child(TextBox::new().lens(Validated::value.then(SignUp::email)))
+///     .with_child(Label::dynamic(|error: &Option<_>, _| {
+///         error.as_deref().unwrap_or_default().to_string()
+///     }).lens(Validated::field_error("email")))
+///     .with_child(TextBox::new().lens(Validated::value.then(SignUp::password)))
+///     .with_child(TextBox::new().lens(Validated::value.then(SignUp::repeated)))
+///     .with_child(
+///         Button::new("Sign up")
+///             .disabled_if(|form: &Validated<SignUp>, _| !form.is_valid()),
+///     );
+///
+/// let form = Validate::new(form)
+///     .field("email", SignUp::email, |email: &String| {
+///         if email.contains('@') {
+///             Ok(())
+///         } else {
+///             Err("not an email address".into())
+///         }
+///     })
+///     .form(|form: &SignUp| {
+///         if form.password == form.repeated {
+///             Ok(())
+///         } else {
+///             Err("the passwords differ".into())
+///         }
+///     });
+/// ```
+///
+/// [`form`]: Validate::form
+/// [`disabled_if`]: crate::WidgetExt::disabled_if
+pub struct Validate<T, W> {
+    inner: W,
+    checks: Vec<(&'static str, CheckFn<T>)>,
+    /// The data the child last saw, once we have seen it.
+    state: Option<Validated<T>>,
+}
+
+impl ValidationErrors {
+    /// The field that errors about the whole form belong to.
+    pub const FORM: &'static str = "";
+
+    /// Whether there are no errors.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The first error of `field`, if it has one.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.get_arc(field).map(|error| &**error)
+    }
+
+    /// Every error, along with the field it belongs to.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> + '_ {
+        self.0.iter().map(|(field, error)| (*field, &**error))
+    }
+
+    fn get_arc(&self, field: &str) -> Option<&ArcStr> {
+        self.0.iter().find(|(f, _)| *f == field).map(|(_, e)| e)
+    }
+}
+
+impl Data for ValidationErrors {
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl<T: Data> Validated<T> {
+    /// Whether the data has no errors.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// A lens to the first error of `field`, if it has one.
+    ///
+    /// The lens is read-only.
+    pub fn field_error(field: &'static str) -> impl Lens<Validated<T>, Option<ArcStr>> {
+        lens::Map::new(
+            move |validated: &Validated<T>| validated.errors.get_arc(field).cloned(),
+            |_: &mut Validated<T>, _| {},
+        )
+    }
+}
+
+impl<T: Data, W: Widget<Validated<T>>> Validate<T, W> {
+    /// Create a widget that gives `inner` its data along with the errors the
+    /// checks find in it.
+    ///
+    /// There are no checks at first; add them with [`field`] and [`form`].
+    ///
+    /// [`field`]: Validate::field
+    /// [`form`]: Validate::form
+    pub fn new(inner: W) -> Self {
+        Validate {
+            inner,
+            checks: Vec::new(),
+            state: None,
+        }
+    }
+
+    /// Builder-style method to add a check of the field `name`, that `lens`
+    /// focuses on.
+    ///
+    /// A field can have several checks; they run in the order they were added.
+    pub fn field<U>(
+        mut self,
+        name: &'static str,
+        lens: impl Lens<T, U> + 'static,
+        check: impl Fn(&U) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.checks
+            .push((name, Box::new(move |data| lens.with(data, &check))));
+        self
+    }
+
+    /// Builder-style method to add a check of the whole form.
+    ///
+    /// Its errors belong to [`ValidationErrors::FORM`].
+    pub fn form(mut self, check: impl Fn(&T) -> Result<(), String> + 'static) -> Self {
+        self.checks.push((ValidationErrors::FORM, Box::new(check)));
+        self
+    }
+
+    /// Run the checks.
+    fn validate(&self, data: &T) -> Validated<T> {
+        let errors = self
+            .checks
+            .iter()
+            .filter_map(|(field, check)| check(data).err().map(|e| (*field, e.into())))
+            .collect();
+        Validated {
+            value: data.clone(),
+            errors: ValidationErrors(Arc::new(errors)),
+        }
+    }
+
+    /// The data along with its errors, reusing the last errors if the data
+    /// is the same.
+    fn validated(&self, data: &T) -> Validated<T> {
+        match &self.state {
+            Some(state) if state.value.same(data) => state.clone(),
+            _ => self.validate(data),
+        }
+    }
+
+    /// The child and the data it last saw.
+    fn split(&mut self, data: &T) -> (&mut W, &Validated<T>) {
+        if self.state.is_none() {
+            self.state = Some(self.validate(data));
+        }
+        (&mut self.inner, self.state.as_ref().unwrap())
+    }
+}
+
+impl<T, W> WidgetWrapper for Validate<T, W> {
+    widget_wrapper_body!(W, inner);
+}
+
+impl<T: Data, W: Widget<Validated<T>>> Widget<T> for Validate<T, W> {
+    #[instrument(name = "Validate", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let mut validated = self.validated(data);
+        self.inner.event(ctx, event, &mut validated, env);
+        if !validated.value.same(data) {
+            *data = validated.value;
+        }
+    }
+
+    #[instrument(name = "Validate", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.state = Some(self.validate(data));
+        }
+        let (inner, validated) = self.split(data);
+        inner.lifecycle(ctx, event, validated, env);
+    }
+
+    #[instrument(
+        name = "Validate",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let old = self.split(old_data).1.clone();
+        let new = self.validated(data);
+        if ctx.has_requested_update() || !old.same(&new) || ctx.env_changed() {
+            self.inner.update(ctx, &old, &new, env);
+        } else {
+            trace!("skipping child update");
+        }
+        self.state = Some(new);
+    }
+
+    #[instrument(name = "Validate", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let (inner, validated) = self.split(data);
+        inner.layout(ctx, bc, validated, env)
+    }
+
+    #[instrument(name = "Validate", level = "trace", skip(self, ctx, bc, data, env))]
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let (inner, validated) = self.split(data);
+        inner.compute_max_intrinsic(axis, ctx, bc, validated, env)
+    }
+
+    #[instrument(name = "Validate", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let (inner, validated) = self.split(data);
+        inner.paint(ctx, validated, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.inner.id()
+    }
+}
@@ -14,6 +14,7 @@
 
 //! A widget that can dynamically switch between one of many views.
 
+use crate::animation::{Animation, Transition};
 use crate::widget::prelude::*;
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
@@ -27,6 +28,10 @@ pub struct ViewSwitcher<T, U> {
     child_builder: Box<ChildBuilder<T, U>>,
     active_child: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
     active_child_id: Option<U>,
+    transition: Option<Transition>,
+    /// The child being switched away from, the data it was last shown, and
+    /// the transition, while it runs.
+    outgoing_child: Option<(WidgetPod<T, Box<dyn Widget<T>>>, T, Animation)>,
 }
 
 impl<T: Data, U: Data> ViewSwitcher<T, U> {
@@ -73,8 +78,19 @@ impl<T: Data, U: Data> ViewSwitcher<T, U> {
             child_builder: Box::new(child_builder),
             active_child: None,
             active_child_id: None,
+            transition: None,
+            outgoing_child: None,
         }
     }
+
+    /// Builder-style method to animate switching between the views.
+    ///
+    /// The old view is only painted during the transition, with the data it
+    /// had before the switch; it doesn't get any events or updates.
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
 }
 
 impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
@@ -84,6 +100,16 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
         skip(self, ctx, event, data, env)
     )]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let (Event::AnimFrame(interval), Some((_, _, animation))) =
+            (event, self.outgoing_child.as_mut())
+        {
+            if animation.advance(*interval) {
+                ctx.request_anim_frame();
+            } else {
+                self.outgoing_child = None;
+            }
+            ctx.request_paint();
+        }
         if let Some(child) = self.active_child.as_mut() {
             child.event(ctx, event, data, env);
         }
@@ -108,12 +134,16 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
     #[instrument(
         name = "ViewSwitcher",
         level = "trace",
-        skip(self, ctx, _old_data, data, env)
+        skip(self, ctx, old_data, data, env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         let child_id = (self.child_picker)(data, env);
         // Safe to unwrap because self.active_child_id should not be empty
         if !child_id.same(self.active_child_id.as_ref().unwrap()) {
+            if let (Some(transition), Some(child)) = (&self.transition, self.active_child.take()) {
+                self.outgoing_child = Some((child, old_data.clone(), transition.start()));
+                ctx.request_anim_frame();
+            }
             self.active_child = Some(WidgetPod::new((self.child_builder)(&child_id, data, env)));
             self.active_child_id = Some(child_id);
             ctx.children_changed();
@@ -137,8 +167,17 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
 
     #[instrument(name = "ViewSwitcher", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        if let Some(ref mut child) = self.active_child {
-            child.paint_raw(ctx, data, env);
+        match (
+            &self.transition,
+            &mut self.outgoing_child,
+            &mut self.active_child,
+        ) {
+            (Some(transition), Some((old, old_data, animation)), Some(new)) => {
+                let progress = animation.progress();
+                transition.paint(ctx, progress, (old, old_data), new, data, env)
+            }
+            (_, _, Some(child)) => child.paint_raw(ctx, data, env),
+            _ => (),
         }
     }
 }
@@ -16,6 +16,8 @@ use std::num::NonZeroU64;
 use std::ops::{Deref, DerefMut};
 
 use super::prelude::*;
+use super::Axis;
+use crate::ChangeSet;
 
 /// A unique identifier for a single [`Widget`].
 ///
@@ -169,6 +171,61 @@ pub trait Widget<T> {
     /// [`set_origin`]: struct.WidgetPod.html#method.set_origin
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size;
 
+    /// Compute the size this widget would like to have along `axis`, if it
+    /// were given as much space as it wants on that axis.
+    ///
+    /// This is the natural size of the widget's content, such as the width of
+    /// a label's text on a single line. Containers that size their children
+    /// to their content, such as a [`Grid`] with [`TrackSize::Auto`] columns,
+    /// call this (through [`WidgetPod::compute_max_intrinsic`]) to measure
+    /// their children before laying them out.
+    ///
+    /// The constraints along `axis` are ignored; those on the other axis are
+    /// the ones the widget would be laid out with.
+    ///
+    /// The default implementation lays out the widget with unbounded space
+    /// along `axis`, and returns its size on that axis.
+    ///
+    /// [`Grid`]: widget/struct.Grid.html
+    /// [`TrackSize::Auto`]: widget/enum.TrackSize.html#variant.Auto
+    /// [`WidgetPod::compute_max_intrinsic`]: struct.WidgetPod.html#method.compute_max_intrinsic
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let bc = axis.constraints(bc, 0.0, f64::INFINITY);
+        axis.major(self.layout(ctx, &bc, data, env))
+    }
+
+    /// Compute the smallest size this widget can have along `axis` without
+    /// its content overflowing.
+    ///
+    /// For example, this is the width of the longest word of a label that
+    /// wraps its text. Containers can use this to shrink their children as
+    /// far as possible before clipping them.
+    ///
+    /// The constraints along `axis` are ignored; those on the other axis are
+    /// the ones the widget would be laid out with.
+    ///
+    /// The default implementation assumes the widget can't shrink, and
+    /// returns the [`compute_max_intrinsic`] size.
+    ///
+    /// [`compute_max_intrinsic`]: #method.compute_max_intrinsic
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.compute_max_intrinsic(axis, ctx, bc, data, env)
+    }
+
     /// Paint the widget appearance.
     ///
     /// The [`PaintCtx`] derefs to something that implements the [`RenderContext`]
@@ -197,6 +254,17 @@ pub trait Widget<T> {
     fn type_name(&self) -> &'static str {
         std::any::type_name::<Self>()
     }
+
+    #[doc(hidden)]
+    /// The parts of the data, as reported by [`Data::changes`], that this
+    /// widget depends on; its [`WidgetPod`] skips `update` when none of them
+    /// changed. This is basically only implemented by `LensWrap`.
+    ///
+    /// [`Data::changes`]: crate::Data::changes
+    /// [`WidgetPod`]: crate::WidgetPod
+    fn depends_on(&self) -> ChangeSet {
+        ChangeSet::ALL
+    }
 }
 
 impl WidgetId {
@@ -251,6 +319,30 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
         self.deref_mut().layout(ctx, bc, data, env)
     }
 
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut()
+            .compute_max_intrinsic(axis, ctx, bc, data, env)
+    }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut()
+            .compute_min_intrinsic(axis, ctx, bc, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.deref_mut().paint(ctx, data, env);
     }
@@ -262,4 +354,8 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
     fn type_name(&self) -> &'static str {
         self.deref().type_name()
     }
+
+    fn depends_on(&self) -> ChangeSet {
+        self.deref().depends_on()
+    }
 }
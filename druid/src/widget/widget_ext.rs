@@ -14,14 +14,19 @@
 
 //! Convenience methods for widgets.
 
+use std::any::Any;
+use std::path::PathBuf;
+
 use super::invalidation::DebugInvalidation;
 use super::{
-    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, LensWrap, Padding, Parse, SizedBox, WidgetId,
+    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, Draggable,
+    DropTarget, EnvScope, FocusScope, IdentityWrapper, LensWrap, LongPress, Padding, Parse,
+    ShortcutScope, SizedBox, TabIndex, WidgetId,
 };
-use crate::widget::{DisabledIf, Scroll};
+use crate::widget::{Blur, DisabledIf, Opacity, Scroll};
 use crate::{
-    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, Widget,
+    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, Point, ShortcutMap,
+    UnitPoint, Vec2, Widget,
 };
 
 /// A trait that provides extra methods for combining `Widget`s.
@@ -149,6 +154,21 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         Container::new(self).border(color, width)
     }
 
+    /// Wrap this widget in a [`Container`] with the given drop shadow.
+    ///
+    /// See [`Container::shadow`] for more information.
+    ///
+    /// [`Container`]: crate::widget::Container
+    /// [`Container::shadow`]: crate::widget::Container::shadow
+    fn shadow(
+        self,
+        color: impl Into<KeyOrValue<Color>>,
+        blur_radius: impl Into<KeyOrValue<f64>>,
+        offset: impl Into<KeyOrValue<Vec2>>,
+    ) -> Container<T> {
+        Container::new(self).shadow(color, blur_radius, offset)
+    }
+
     /// Wrap this widget in a [`EnvScope`] widget, modifying the parent
     /// [`Env`] with the provided closure.
     ///
@@ -197,6 +217,84 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
+    /// Control the events of this widget with a [`LongPress`] controller. The
+    /// closure provided will be called with the position of the press when
+    /// the left mouse button is held down without moving, for the duration
+    /// in [`theme::LONG_PRESS_DURATION`].
+    ///
+    /// A press that is recognized as a long press is not also a click.
+    ///
+    /// [`LongPress`]: widget/struct.LongPress.html
+    /// [`theme::LONG_PRESS_DURATION`]: crate::theme::LONG_PRESS_DURATION
+    fn on_long_press(
+        self,
+        f: impl Fn(&mut EventCtx, Point, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, LongPress<T>> {
+        ControllerHost::new(self, LongPress::new(f))
+    }
+
+    /// Set the position of this widget, or of the focusable widgets in it,
+    /// in the tab order, with a [`TabIndex`] controller.
+    ///
+    /// Widgets with a positive index are visited first, in increasing order;
+    /// widgets with a negative index are skipped by keyboard traversal.
+    ///
+    /// [`TabIndex`]: widget/struct.TabIndex.html
+    fn with_tab_index(self, index: i32) -> ControllerHost<Self, TabIndex> {
+        ControllerHost::new(self, TabIndex::new(index))
+    }
+
+    /// Keep keyboard focus traversal inside this widget while it has focus,
+    /// with a [`FocusScope`] controller.
+    ///
+    /// [`FocusScope`]: widget/struct.FocusScope.html
+    fn focus_scope(self) -> ControllerHost<Self, FocusScope> {
+        ControllerHost::new(self, FocusScope::new())
+    }
+
+    /// Let this widget be dragged, carrying the value returned by the
+    /// closure, with a [`Draggable`] controller.
+    ///
+    /// [`Draggable`]: widget/struct.Draggable.html
+    fn draggable<P: Any>(
+        self,
+        payload: impl Fn(&T, &Env) -> P + 'static,
+    ) -> ControllerHost<Self, Draggable<T>> {
+        ControllerHost::new(self, Draggable::new(payload))
+    }
+
+    /// Accept values of type `P` dropped on this widget, with a
+    /// [`DropTarget`] controller. The closure is called with the value and
+    /// the position of the drop.
+    ///
+    /// [`DropTarget`]: widget/struct.DropTarget.html
+    fn on_drop<P: Any>(
+        self,
+        f: impl Fn(&mut EventCtx, &P, Point, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, DropTarget<T>> {
+        ControllerHost::new(self, DropTarget::new(f))
+    }
+
+    /// Accept files dragged onto this widget from other applications, with a
+    /// [`DropTarget`] controller. The closure is called with the paths of the
+    /// files and the position of the drop.
+    ///
+    /// [`DropTarget`]: widget/struct.DropTarget.html
+    fn on_file_drop(
+        self,
+        f: impl Fn(&mut EventCtx, &[PathBuf], Point, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, DropTarget<T>> {
+        ControllerHost::new(self, DropTarget::files(f))
+    }
+
+    /// Give this widget keyboard shortcuts that apply while the focus is
+    /// inside it, with a [`ShortcutScope`].
+    ///
+    /// [`ShortcutScope`]: widget/struct.ShortcutScope.html
+    fn with_shortcuts(self, shortcuts: ShortcutMap) -> ControllerHost<Self, ShortcutScope> {
+        ControllerHost::new(self, ShortcutScope::new(shortcuts))
+    }
+
     /// Draw the [`layout`] `Rect`s of  this widget and its children.
     ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
@@ -285,6 +383,33 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
     fn disabled_if(self, disabled_if: impl Fn(&T, &Env) -> bool + 'static) -> DisabledIf<T, Self> {
         DisabledIf::new(self, disabled_if)
     }
+
+    /// Wrap this widget in an [`Opacity`] widget, painting it with the given
+    /// opacity.
+    ///
+    /// The opacity can be a value from `0.0` to `1.0`, or a [`Key`] of one
+    /// in the [`Env`].
+    ///
+    /// [`Key`]: crate::Key
+    /// [`Opacity`]: crate::widget::Opacity
+    fn opacity(self, opacity: impl Into<KeyOrValue<f64>>) -> Opacity<T, Self> {
+        Opacity::new(self, opacity)
+    }
+
+    /// Wrap this widget in an [`Opacity`] widget, painting it with an
+    /// opacity computed from the data.
+    ///
+    /// [`Opacity`]: crate::widget::Opacity
+    fn opacity_with(self, opacity: impl Fn(&T, &Env) -> f64 + 'static) -> Opacity<T, Self> {
+        Opacity::dynamic(self, opacity)
+    }
+
+    /// Wrap this widget in a [`Blur`] widget, blurring it with the given radius.
+    ///
+    /// [`Blur`]: crate::widget::Blur
+    fn blur(self, radius: impl Into<KeyOrValue<f64>>) -> Blur<T, Self> {
+        Blur::new(self, radius)
+    }
 }
 
 impl<T: Data, W: Widget<T> + 'static> WidgetExt<T> for W {}
@@ -0,0 +1,304 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Widgets for windows that draw their own titlebar.
+
+use tracing::instrument;
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::prelude::*;
+use crate::{Cursor, Data, MouseButton, ResizeEdge, WidgetPod, WindowHandle, WindowState};
+
+/// The default width of the resizable border of a [`WindowFrame`].
+const DEFAULT_BORDER_WIDTH: f64 = 6.0;
+
+/// A widget that acts as the titlebar of a window without system decorations.
+///
+/// Dragging the titlebar moves the window, and double-clicking it maximizes
+/// or restores the window. Presses that the child handles, such as clicks on
+/// buttons inside the titlebar, are left alone.
+///
+/// Moves get the platform's snapping behavior. Use it in windows created with
+/// [`WindowDesc::show_titlebar(false)`], along with a [`WindowFrame`] for the
+/// resizable border and a [`MaximizeButton`] for the maximize button.
+///
+/// [`WindowDesc::show_titlebar(false)`]: crate::WindowDesc::show_titlebar
+pub struct TitleBar<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+/// A widget that lets the user resize a window without system decorations,
+/// by dragging the edges of the child.
+///
+/// The child should fill the window. The border lies on top of the child's
+/// edges; it is disabled while the window is maximized.
+///
+/// On Windows, the system already handles the border of such windows, and
+/// on macOS their edges are resizable anyway, so this mostly matters on
+/// Linux.
+pub struct WindowFrame<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    border_width: f64,
+}
+
+/// A wrapper that marks its child as the maximize button of a custom titlebar.
+///
+/// On Windows 11 this shows the snap layouts flyout when the mouse hovers the
+/// button. Clicks on it are then handled by the system, which maximizes or
+/// restores the window, so the child shouldn't handle clicks of its own; it
+/// does still get mouse moves, to show a hover state. On other platforms
+/// this does nothing, and the child has to maximize the window itself.
+pub struct MaximizeButton<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    /// The rect last given to the window, in display points.
+    rect: Option<Rect>,
+}
+
+impl<T> TitleBar<T> {
+    /// Create a titlebar showing the given widget.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        TitleBar {
+            child: WidgetPod::new(child).boxed(),
+        }
+    }
+}
+
+impl<T> WindowFrame<T> {
+    /// Create a resizable border around the given widget.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        WindowFrame {
+            child: WidgetPod::new(child).boxed(),
+            border_width: DEFAULT_BORDER_WIDTH,
+        }
+    }
+
+    /// Builder-style method to set the width of the border.
+    pub fn border_width(mut self, width: f64) -> Self {
+        self.border_width = width;
+        self
+    }
+
+    /// The edge under `pos`, if it is on the border.
+    fn edge_at(&self, pos: Point, size: Size) -> Option<ResizeEdge> {
+        let width = self.border_width;
+        let top = pos.y < width;
+        let bottom = pos.y > size.height - width;
+        let left = pos.x < width;
+        let right = pos.x > size.width - width;
+        match (top, bottom, left, right) {
+            (true, _, true, _) => Some(ResizeEdge::TopLeft),
+            (true, _, _, true) => Some(ResizeEdge::TopRight),
+            (_, true, true, _) => Some(ResizeEdge::BottomLeft),
+            (_, true, _, true) => Some(ResizeEdge::BottomRight),
+            (true, ..) => Some(ResizeEdge::Top),
+            (_, true, ..) => Some(ResizeEdge::Bottom),
+            (_, _, true, _) => Some(ResizeEdge::Left),
+            (_, _, _, true) => Some(ResizeEdge::Right),
+            _ => None,
+        }
+    }
+}
+
+impl<T> MaximizeButton<T> {
+    /// Mark the given widget as a maximize button.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        MaximizeButton {
+            child: WidgetPod::new(child).boxed(),
+            rect: None,
+        }
+    }
+}
+
+fn toggle_maximized(window: &WindowHandle) {
+    let mut window = window.clone();
+    let state = match window.get_window_state() {
+        WindowState::Maximized => WindowState::Restored,
+        _ => WindowState::Maximized,
+    };
+    window.set_window_state(state);
+}
+
+fn resize_cursor(edge: ResizeEdge) -> Cursor {
+    match edge {
+        ResizeEdge::Top | ResizeEdge::Bottom => Cursor::ResizeUpDown,
+        ResizeEdge::Left | ResizeEdge::Right => Cursor::ResizeLeftRight,
+        ResizeEdge::TopLeft | ResizeEdge::BottomRight => Cursor::ResizeNwSe,
+        ResizeEdge::TopRight | ResizeEdge::BottomLeft => Cursor::ResizeNeSw,
+    }
+}
+
+impl<T: Data> Widget<T> for TitleBar<T> {
+    #[instrument(name = "TitleBar", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+        if let Event::MouseDown(mouse) = event {
+            if mouse.button == MouseButton::Left && !ctx.is_handled() && !self.child.has_active() {
+                if mouse.count == 2 {
+                    toggle_maximized(ctx.window());
+                } else {
+                    ctx.window().begin_move_drag();
+                }
+                ctx.set_handled();
+            }
+        }
+    }
+
+    #[instrument(name = "TitleBar", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "TitleBar",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "TitleBar", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("TitleBar");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    #[instrument(name = "TitleBar", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+}
+
+impl<T: Data> Widget<T> for WindowFrame<T> {
+    #[instrument(
+        name = "WindowFrame",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let edge = match event {
+            Event::MouseMove(mouse) | Event::MouseDown(mouse)
+                if !self.child.has_active()
+                    && ctx.window().get_window_state() != WindowState::Maximized =>
+            {
+                self.edge_at(mouse.pos, ctx.size())
+            }
+            _ => None,
+        };
+        match (event, edge) {
+            (Event::MouseDown(mouse), Some(edge)) if mouse.button == MouseButton::Left => {
+                ctx.window().begin_resize_drag(edge);
+                ctx.set_handled();
+                return;
+            }
+            (Event::MouseMove(_), Some(edge)) => ctx.override_cursor(&resize_cursor(edge)),
+            (Event::MouseMove(_), None) => ctx.clear_cursor(),
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "WindowFrame",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "WindowFrame",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "WindowFrame", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("WindowFrame");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    #[instrument(name = "WindowFrame", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+}
+
+impl<T: Data> Widget<T> for MaximizeButton<T> {
+    #[instrument(
+        name = "MaximizeButton",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "MaximizeButton",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "MaximizeButton",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(
+        name = "MaximizeButton",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("MaximizeButton");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, data, env, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    #[instrument(name = "MaximizeButton", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        // Our position in the window is only final once we're painted.
+        let size = ctx.size();
+        let rect = Rect::from_points(
+            ctx.to_window(Point::ORIGIN),
+            ctx.to_window(Point::new(size.width, size.height)),
+        );
+        if self.rect != Some(rect) {
+            self.rect = Some(rect);
+            #[cfg(target_os = "windows")]
+            ctx.window().set_maximize_button(Some(rect));
+        }
+        self.child.paint(ctx, data, env);
+    }
+}
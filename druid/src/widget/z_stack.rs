@@ -0,0 +1,259 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that layers its children on top of each other.
+
+use crate::kurbo::common::FloatExt;
+use crate::widget::prelude::*;
+use crate::{theme, Data, InternalEvent, Point, Rect, UnitPoint, Vec2, WidgetPod};
+use tracing::{instrument, trace};
+
+/// A container that layers its children on top of each other.
+///
+/// Children are painted in the order they were added, so later children are
+/// drawn on top of earlier ones. Each child is positioned within the stack
+/// by its alignment, and then moved by an optional offset.
+///
+/// The stack is as large as its largest child. Children are given loose
+/// constraints; a child that should cover the whole stack, such as a
+/// background or a veil, can be [expanded].
+///
+/// Mouse events are delivered to the topmost child first. A child that is
+/// under the mouse stops the event from reaching the children below it,
+/// unless it is [transparent] to hit-testing; this is useful for badges and
+/// decorations that should not block the widgets they are drawn over.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Label, ZStack, ZStackParams};
+/// use druid::{UnitPoint, Vec2, WidgetExt};
+///
+/// let stack = ZStack::new()
+///     .with_child(Button::new("Inbox"), UnitPoint::CENTER)
+///     .with_child(
+///         Label::new("3"),
+///         ZStackParams::new(UnitPoint::TOP_RIGHT)
+///             .offset(Vec2::new(4.0, -4.0))
+///             .transparent(true),
+///     );
+/// # let _: ZStack<()> = stack;
+/// ```
+///
+/// [expanded]: crate::WidgetExt::expand
+/// [transparent]: ZStackParams::transparent
+pub struct ZStack<T> {
+    children: Vec<ZChild<T>>,
+}
+
+/// The position of a child in a [`ZStack`], and how it takes part in
+/// hit-testing.
+///
+/// A `UnitPoint` can be used wherever a `ZStackParams` is expected, for a
+/// child with no offset that is not transparent.
+#[derive(Debug, Clone, Copy)]
+pub struct ZStackParams {
+    alignment: UnitPoint,
+    offset: Vec2,
+    transparent: bool,
+}
+
+struct ZChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    params: ZStackParams,
+}
+
+impl ZStackParams {
+    /// Create `ZStackParams` that position a child at `alignment`.
+    pub fn new(alignment: UnitPoint) -> Self {
+        ZStackParams {
+            alignment,
+            offset: Vec2::ZERO,
+            transparent: false,
+        }
+    }
+
+    /// Builder-style method to move the child by `offset`, after it is aligned.
+    pub fn offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Builder-style method to set whether the child is transparent to
+    /// hit-testing.
+    ///
+    /// Mouse events over a transparent child are also delivered to the
+    /// children below it. The default is `false`.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+}
+
+impl From<UnitPoint> for ZStackParams {
+    fn from(alignment: UnitPoint) -> ZStackParams {
+        ZStackParams::new(alignment)
+    }
+}
+
+impl<T: Data> ZStack<T> {
+    /// Create a new, empty `ZStack`.
+    pub fn new() -> Self {
+        ZStack {
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a child on top of the existing children.
+    ///
+    /// See also [`add_child`].
+    ///
+    /// [`add_child`]: ZStack::add_child
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        params: impl Into<ZStackParams>,
+    ) -> Self {
+        self.add_child(child, params);
+        self
+    }
+
+    /// Add a child on top of the existing children.
+    ///
+    /// See also [`with_child`].
+    ///
+    /// [`with_child`]: ZStack::with_child
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, params: impl Into<ZStackParams>) {
+        self.children.push(ZChild {
+            widget: WidgetPod::new(Box::new(child)),
+            params: params.into(),
+        });
+    }
+
+    /// The number of children in the stack.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if the stack has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl<T: Data> Default for ZStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for ZStack<T> {
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let mouse_pos = match event {
+            Event::MouseDown(mouse) | Event::MouseUp(mouse) | Event::MouseMove(mouse) => {
+                Some(mouse.pos)
+            }
+            Event::Wheel(mouse) => Some(mouse.pos),
+            _ => None,
+        };
+
+        // the topmost child gets the first chance at every event.
+        let mut covered = false;
+        for child in self.children.iter_mut().rev() {
+            match mouse_pos {
+                // a child beneath an opaque sibling doesn't see the mouse,
+                // unless it is in the middle of handling a click or drag.
+                Some(_) if covered && !child.widget.has_active() => {
+                    let leave = Event::Internal(InternalEvent::MouseLeave);
+                    child.widget.event(ctx, &leave, data, env);
+                }
+                Some(pos) => {
+                    child.widget.event(ctx, event, data, env);
+                    covered |=
+                        !child.params.transparent && child.widget.layout_rect().contains(pos);
+                }
+                None => child.widget.event(ctx, event, data, env),
+            }
+        }
+    }
+
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ZStack",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&theme::LAYOUT_RTL) {
+            ctx.request_layout();
+        }
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("ZStack");
+        let child_bc = bc.loosen();
+
+        let mut my_size = Size::ZERO;
+        for child in &mut self.children {
+            let size = child.widget.layout(ctx, &child_bc, data, env);
+            my_size.width = my_size.width.max(size.width);
+            my_size.height = my_size.height.max(size.height);
+        }
+        let my_size = bc.constrain(my_size);
+
+        let rtl = env.get(theme::LAYOUT_RTL);
+        let mut child_paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let size = child.widget.layout_rect().size();
+            let extra_width = (my_size.width - size.width).max(0.);
+            let extra_height = (my_size.height - size.height).max(0.);
+            let mut origin =
+                child
+                    .params
+                    .alignment
+                    .resolve(Rect::new(0., 0., extra_width, extra_height));
+            let mut offset = child.params.offset;
+            if rtl {
+                origin.x = extra_width - origin.x;
+                offset.x = -offset.x;
+            }
+            let origin = Point::new(origin.x.expand(), origin.y.expand()) + offset;
+            child.widget.set_origin(ctx, data, env, origin);
+            child_paint_rect = child_paint_rect.union(child.widget.paint_rect());
+        }
+
+        let insets = child_paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed layout: size={}, insets={:?}", my_size, insets);
+        my_size
+    }
+
+    #[instrument(name = "ZStack", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+}
@@ -16,27 +16,41 @@
 
 use std::any::{Any, TypeId};
 use std::cell::RefCell;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::Rc;
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
 
 use crate::kurbo::Size;
 use crate::piet::Piet;
 use crate::shell::{
-    text::InputHandler, Application, FileDialogToken, FileInfo, IdleToken, MouseEvent, Region,
-    Scale, TextFieldToken, WinHandler, WindowHandle,
+    text::InputHandler, Application, DropEvent, FileDialogToken, FileInfo, GlobalHotKeyToken,
+    HotKey, IdleToken, Monitor, MouseEvent, NotificationToken, PenEvent, Region, Scale,
+    SystemTheme, TextFieldToken, TouchEvent, WinHandler, WindowHandle,
 };
 
+#[cfg(feature = "async")]
+use crate::app_delegate::AsyncUpdate;
 use crate::app_delegate::{AppDelegate, DelegateCtx};
 use crate::core::CommandQueue;
-use crate::ext_event::{ExtEventHost, ExtEventSink};
+use crate::crash;
+use crate::ext_event::{ExtEventHost, ExtEventSink, ExtInputEvent};
+use crate::interaction::{Interaction, InteractionKind, InteractionSinkFn};
 use crate::menu::{ContextMenu, MenuItemId, MenuManager};
+use crate::session::StateSaver;
+use crate::toolbar::WindowToolbar;
+use crate::tray::{Tray, TrayDesc};
 use crate::window::{ImeUpdateFn, Window};
+use crate::window_set::WindowSet;
 use crate::{
-    Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, PlatformError, Selector, Target,
-    TimerToken, WidgetId, WindowDesc, WindowId,
+    theme, AboutInfo, Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, PlatformError,
+    Selector, ShellError, ShellOperation, ShortcutMap, SingleUse, Target, TimerToken, WidgetId,
+    WindowDesc, WindowId,
 };
 
-use crate::app::{PendingWindow, WindowConfig};
+use crate::app::{PendingWindow, ThemeEnvs, WindowConfig};
 use crate::command::sys as sys_cmd;
 use druid_shell::WindowBuilder;
 
@@ -84,6 +98,138 @@ struct DialogInfo {
     cancel_cmd: Selector<()>,
 }
 
+/// Tracks user inactivity, for [`AppLauncher::idle_timeout`].
+///
+/// [`AppLauncher::idle_timeout`]: crate::AppLauncher::idle_timeout
+pub(crate) struct IdleMonitor {
+    /// How long the user must be inactive before `command` is submitted.
+    threshold: Duration,
+    command: Command,
+    /// The pending timer, and the window it was requested on.
+    timer: Option<(WindowId, TimerToken)>,
+    /// Whether `command` was submitted since the last user input.
+    fired: bool,
+}
+
+impl IdleMonitor {
+    /// A command with [`Target::Auto`] is submitted to [`Target::Global`].
+    pub(crate) fn new(threshold: Duration, command: Command) -> Self {
+        IdleMonitor {
+            threshold,
+            command: command.default_to(Target::Global),
+            timer: None,
+            fired: false,
+        }
+    }
+
+    /// How long until the threshold is reached, after `idle` without input.
+    fn remaining(&self, idle: Duration) -> Duration {
+        self.threshold.saturating_sub(idle)
+    }
+
+    /// The command to submit after `idle` without input, unless the
+    /// threshold wasn't reached or it was already submitted in this idle period.
+    fn take_command(&mut self, idle: Duration) -> Option<Command> {
+        if idle >= self.threshold && !self.fired {
+            self.fired = true;
+            Some(self.command.clone())
+        } else {
+            None
+        }
+    }
+
+    /// The user did something, so a new idle period starts.
+    fn reset(&mut self) {
+        self.fired = false;
+    }
+}
+
+/// Periodic state saving, for [`AppLauncher::autosave`].
+///
+/// [`AppLauncher::autosave`]: crate::AppLauncher::autosave
+pub(crate) struct Autosave {
+    saver: StateSaver,
+    interval: Duration,
+    /// The pending timer, and the window it was requested on.
+    timer: Option<(WindowId, TimerToken)>,
+    /// State left behind by a session that ended unexpectedly, waiting to be
+    /// offered to the delegate.
+    recovered: Option<Vec<u8>>,
+}
+
+impl Autosave {
+    pub(crate) fn new(saver: StateSaver, interval: Duration, recovered: Option<Vec<u8>>) -> Self {
+        Autosave {
+            saver,
+            interval,
+            timer: None,
+            recovered,
+        }
+    }
+}
+
+/// A quit that waits for the windows that vetoed it to confirm or cancel
+/// closing, for [`REQUEST_QUIT`].
+///
+/// [`REQUEST_QUIT`]: crate::commands::REQUEST_QUIT
+#[derive(Debug, Default)]
+struct PendingQuit {
+    waiting_for: Option<HashSet<WindowId>>,
+}
+
+impl PendingQuit {
+    /// Start a quit that the `vetoed` windows have to agree to. Returns
+    /// `true` if none of them vetoed, so the app can quit right away.
+    fn start(&mut self, vetoed: HashSet<WindowId>) -> bool {
+        if vetoed.is_empty() {
+            self.waiting_for = None;
+            return true;
+        }
+        self.waiting_for = Some(vetoed);
+        false
+    }
+
+    fn is_waiting(&self) -> bool {
+        self.waiting_for.is_some()
+    }
+
+    fn is_waiting_for(&self, window_id: WindowId) -> bool {
+        self.waiting_for
+            .as_ref()
+            .map_or(false, |windows| windows.contains(&window_id))
+    }
+
+    /// Stop waiting for a window that agreed to close, or went away.
+    ///
+    /// Returns `None` if the quit wasn't waiting for the window, and
+    /// otherwise whether it was the last one, so the app can quit now.
+    fn stop_waiting_for(&mut self, window_id: WindowId) -> Option<bool> {
+        let windows = self.waiting_for.as_mut()?;
+        if !windows.remove(&window_id) {
+            return None;
+        }
+        if windows.is_empty() {
+            self.waiting_for = None;
+            return Some(true);
+        }
+        Some(false)
+    }
+
+    /// Abandon the quit if it is waiting for this window. Returns whether
+    /// it was abandoned.
+    fn cancel(&mut self, window_id: WindowId) -> bool {
+        if self.is_waiting_for(window_id) {
+            self.waiting_for = None;
+            return true;
+        }
+        false
+    }
+
+    fn clear(&mut self) {
+        self.waiting_for = None;
+    }
+}
+
 struct Inner<T> {
     app: Application,
     delegate: Option<Box<dyn AppDelegate<T>>>,
@@ -101,12 +247,53 @@ struct Inner<T> {
     pub(crate) env: Env,
     pub(crate) data: T,
     ime_focus_change: Option<Box<dyn Fn()>>,
+    /// Receives user interactions, if the app opted in.
+    interaction_sink: Option<Box<InteractionSinkFn>>,
+    /// The time of the most recent user input in any window.
+    last_input: Instant,
+    idle_monitor: Option<IdleMonitor>,
+    autosave: Option<Autosave>,
+    /// The windows described by the app data, if the app uses them.
+    window_set: Option<WindowSet<T>>,
+    /// What to show in the About window, if the app has one.
+    about: Option<AboutInfo>,
+    /// The About window drawn by druid, while it is open.
+    about_window: Option<WindowId>,
+    /// Keyboard shortcuts that apply in every window.
+    shortcuts: ShortcutMap,
+    /// The icon in the system tray, if the app has one.
+    tray: Option<Tray<T>>,
+    /// The registered system-wide hotkeys, and the commands they submit.
+    global_hotkeys: Vec<(HotKey, GlobalHotKeyToken, Command)>,
+    /// The windows that vetoed closing while the app was quitting, if the
+    /// quit is waiting for them to confirm or cancel.
+    pending_quit: PendingQuit,
+    /// The system theme, as last reported by the platform.
+    system_theme: SystemTheme,
+    /// The environments for the light and dark themes, if the app has them.
+    theme_envs: Option<ThemeEnvs<T>>,
+}
+
+/// The optional parts of the app that the [`AppLauncher`] configured.
+///
+/// [`AppLauncher`]: crate::AppLauncher
+pub(crate) struct AppConfig<T> {
+    pub interaction_sink: Option<Box<InteractionSinkFn>>,
+    pub idle_monitor: Option<IdleMonitor>,
+    pub autosave: Option<Autosave>,
+    pub window_set: Option<WindowSet<T>>,
+    pub about: Option<AboutInfo>,
+    pub shortcuts: ShortcutMap,
+    pub system_theme: SystemTheme,
+    pub theme_envs: Option<ThemeEnvs<T>>,
 }
 
 /// All active windows.
 struct Windows<T> {
     pending: HashMap<WindowId, PendingWindow<T>>,
     windows: HashMap<WindowId, Window<T>>,
+    /// The ids of all windows, open or pending, in the order they were created.
+    ids: Vec<WindowId>,
 }
 
 impl<T> Windows<T> {
@@ -121,9 +308,13 @@ impl<T> Windows<T> {
 
     fn add(&mut self, id: WindowId, win: PendingWindow<T>) {
         assert!(self.pending.insert(id, win).is_none(), "duplicate pending");
+        self.ids.push(id);
     }
 
+    /// Forget a window, whether it is open or still pending.
     fn remove(&mut self, id: WindowId) -> Option<Window<T>> {
+        self.ids.retain(|&other| other != id);
+        self.pending.remove(&id);
         self.windows.remove(&id)
     }
 
@@ -157,7 +348,18 @@ impl<T> AppState<T> {
         env: Env,
         delegate: Option<Box<dyn AppDelegate<T>>>,
         ext_event_host: ExtEventHost,
+        config: AppConfig<T>,
     ) -> Self {
+        let AppConfig {
+            interaction_sink,
+            idle_monitor,
+            autosave,
+            window_set,
+            about,
+            shortcuts,
+            system_theme,
+            theme_envs,
+        } = config;
         let inner = Rc::new(RefCell::new(Inner {
             app,
             delegate,
@@ -170,6 +372,19 @@ impl<T> AppState<T> {
             env,
             windows: Windows::default(),
             ime_focus_change: None,
+            interaction_sink,
+            last_input: Instant::now(),
+            idle_monitor,
+            autosave,
+            window_set,
+            about,
+            about_window: None,
+            shortcuts,
+            tray: None,
+            global_hotkeys: Vec::new(),
+            pending_quit: PendingQuit::default(),
+            system_theme,
+            theme_envs,
         }));
 
         AppState { inner }
@@ -195,6 +410,10 @@ impl<T: Data> Inner<T> {
                 .as_mut()
                 .map(|m| m.event(queue, None, cmd_id, data, env)),
         };
+        // the tray menu may be activated while any window has focus.
+        if let Some(tray) = &mut self.tray {
+            tray.menu_cmd(queue, cmd_id, data, env);
+        }
     }
 
     fn append_command(&mut self, cmd: Command) {
@@ -214,12 +433,16 @@ impl<T: Data> Inner<T> {
             ref mut data,
             ref ext_event_host,
             ref env,
+            ref windows,
+            last_input,
             ..
-        } = self;
+        } = *self;
         let mut ctx = DelegateCtx {
             command_queue,
             app_data_type: TypeId::of::<T>(),
             ext_event_host,
+            last_input,
+            window_ids: &windows.ids,
         };
         delegate
             .as_deref_mut()
@@ -236,13 +459,39 @@ impl<T: Data> Inner<T> {
     }
 
     fn delegate_cmd(&mut self, cmd: &Command) -> Handled {
+        #[cfg(feature = "async")]
+        {
+            if let Some(update) = cmd.get(sys_cmd::APPLY_ASYNC_UPDATE) {
+                if let Some(update) = update
+                    .take()
+                    .and_then(|update| update.downcast::<AsyncUpdate<T>>().ok())
+                {
+                    self.with_delegate(|_, data, env, ctx| update(ctx, data, env));
+                }
+                return Handled::Yes;
+            }
+            let spawned = self
+                .with_delegate(|del, data, env, ctx| {
+                    del.command_async(ctx, cmd.target(), cmd, data, env)
+                        .map(|future| ctx.spawn(future))
+                })
+                .flatten();
+            if spawned.is_some() {
+                return Handled::Yes;
+            }
+        }
         self.with_delegate(|del, data, env, ctx| del.command(ctx, cmd.target(), cmd, data, env))
             .unwrap_or(Handled::No)
     }
 
     fn connect(&mut self, id: WindowId, handle: WindowHandle) {
+        handle.set_text_rendering(theme::text_rendering_options(&self.env));
         self.windows
             .connect(id, handle, self.ext_event_host.make_sink());
+        if let Some(win) = self.windows.get_mut(id) {
+            win.record_interactions = self.interaction_sink.is_some();
+            crash::record_window(id, &win.title.display_text(), win.root.widget().type_name());
+        }
 
         // If the external event host has no handle, it cannot wake us
         // when an event arrives.
@@ -250,7 +499,19 @@ impl<T: Data> Inner<T> {
             self.set_ext_event_idle_handler(id);
         }
 
+        if matches!(&self.idle_monitor, Some(monitor) if monitor.timer.is_none()) {
+            self.schedule_idle_timer(id);
+        }
+        if matches!(&self.autosave, Some(autosave) if autosave.timer.is_none()) {
+            self.schedule_autosave_timer(id);
+        }
+
         self.with_delegate(|del, data, env, ctx| del.window_added(id, data, env, ctx));
+
+        // offer any state from a crashed session once there is a window to show it in.
+        if let Some(state) = self.autosave.as_mut().and_then(|a| a.recovered.take()) {
+            self.with_delegate(|del, data, env, ctx| del.on_unexpected_exit(ctx, state, data, env));
+        }
     }
 
     /// Called after this window has been closed by the platform.
@@ -258,13 +519,25 @@ impl<T: Data> Inner<T> {
     /// We clean up resources and notifiy the delegate, if necessary.
     fn remove_window(&mut self, window_id: WindowId) {
         self.with_delegate(|del, data, env, ctx| del.window_removed(window_id, data, env, ctx));
+        // the data describes the windows of a window set, so it has to forget this one
+        let declared = match self.window_set.as_mut() {
+            Some(window_set) => window_set.window_closed(&mut self.data, window_id),
+            None => false,
+        };
+        if self.about_window == Some(window_id) {
+            self.about_window = None;
+        }
+        crash::forget_window(window_id);
+        // a window the quit was waiting for can't veto it anymore.
+        self.stop_waiting_for(window_id);
         // when closing the last window:
         if let Some(mut win) = self.windows.remove(window_id) {
             if self.windows.windows.is_empty() {
                 // on mac we need to keep the menu around
                 self.root_menu = win.menu.take();
-                // If there are even no pending windows, we quit the run loop.
-                if self.windows.count() == 0 {
+                // If there are even no pending windows, we quit the run loop,
+                // unless the app lives on in the system tray.
+                if self.windows.count() == 0 && self.tray.is_none() {
                     #[cfg(any(target_os = "windows", feature = "x11"))]
                     self.app.quit();
                 }
@@ -282,6 +555,113 @@ impl<T: Data> Inner<T> {
                 self.set_ext_event_idle_handler(any_other_window);
             }
         }
+
+        // likewise, the idle timer dies with its window.
+        let timer_window = self
+            .idle_monitor
+            .as_ref()
+            .and_then(|m| m.timer)
+            .map(|t| t.0);
+        if timer_window == Some(window_id) {
+            if let Some(monitor) = self.idle_monitor.as_mut() {
+                monitor.timer = None;
+            }
+            let win_id = self.windows.windows.keys().next().copied();
+            if let Some(any_other_window) = win_id {
+                self.schedule_idle_timer(any_other_window);
+            }
+        }
+        let timer_window = self.autosave.as_ref().and_then(|a| a.timer).map(|t| t.0);
+        if timer_window == Some(window_id) {
+            if let Some(autosave) = self.autosave.as_mut() {
+                autosave.timer = None;
+            }
+            let win_id = self.windows.windows.keys().next().copied();
+            if let Some(any_other_window) = win_id {
+                self.schedule_autosave_timer(any_other_window);
+            }
+        }
+
+        if declared {
+            self.do_update();
+        }
+    }
+
+    /// Request a timer on the given window for the next autosave.
+    fn schedule_autosave_timer(&mut self, window_id: WindowId) {
+        let Inner {
+            autosave, windows, ..
+        } = self;
+        if let (Some(autosave), Some(win)) = (autosave.as_mut(), windows.get(window_id)) {
+            let token = win.handle.request_timer(autosave.interval);
+            autosave.timer = Some((window_id, token));
+        }
+    }
+
+    /// Handle a timer event if it belongs to autosave, asking the delegate
+    /// for the current state and handing it to the thread that writes it to disk.
+    ///
+    /// Returns `true` if the timer was ours.
+    fn autosave_timer_fired(&mut self, window_id: WindowId, token: TimerToken) -> bool {
+        match self.autosave.as_mut() {
+            Some(autosave) if autosave.timer.map(|t| t.1) == Some(token) => autosave.timer = None,
+            _ => return false,
+        }
+        let state = self
+            .with_delegate(|del, data, env, _ctx| del.save_state(data, env))
+            .flatten();
+        if let (Some(state), Some(autosave)) = (state, self.autosave.as_ref()) {
+            autosave.saver.save(state);
+        }
+        self.schedule_autosave_timer(window_id);
+        true
+    }
+
+    /// Request a timer on the given window that fires when the idle threshold
+    /// would be reached, if no further input arrives.
+    fn schedule_idle_timer(&mut self, window_id: WindowId) {
+        let last_input = self.last_input;
+        let Inner {
+            idle_monitor,
+            windows,
+            ..
+        } = self;
+        if let (Some(monitor), Some(win)) = (idle_monitor.as_mut(), windows.get(window_id)) {
+            let token = win
+                .handle
+                .request_timer(monitor.remaining(last_input.elapsed()));
+            monitor.timer = Some((window_id, token));
+        }
+    }
+
+    /// Record user input, resetting the idle clock.
+    fn note_user_input(&mut self, window_id: WindowId) {
+        self.last_input = Instant::now();
+        if let Some(monitor) = self.idle_monitor.as_mut() {
+            monitor.reset();
+            if monitor.timer.is_none() {
+                self.schedule_idle_timer(window_id);
+            }
+        }
+    }
+
+    /// Handle a timer event if it belongs to the idle monitor.
+    ///
+    /// Returns `true` if the timer was ours.
+    fn idle_timer_fired(&mut self, window_id: WindowId, token: TimerToken) -> bool {
+        let monitor = match self.idle_monitor.as_mut() {
+            Some(monitor) if monitor.timer.map(|t| t.1) == Some(token) => monitor,
+            _ => return false,
+        };
+        monitor.timer = None;
+        let idle = self.last_input.elapsed();
+        if idle < monitor.threshold {
+            // there was input since the timer was requested; wait for the rest.
+            self.schedule_idle_timer(window_id);
+        } else if let Some(cmd) = monitor.take_command(idle) {
+            self.append_command(cmd);
+        }
+        true
     }
 
     /// Set the idle handle that will be used to wake us when external events arrive.
@@ -305,13 +685,80 @@ impl<T: Data> Inner<T> {
     /// our handlers `destroy()` method, at which point we can do our cleanup.
     fn request_close_window(&mut self, window_id: WindowId) {
         if let Some(win) = self.windows.get_mut(window_id) {
+            win.save_geometry();
             win.handle.close();
         }
     }
 
+    /// Update the environment for a new system theme.
+    fn set_system_theme(&mut self, system_theme: &SystemTheme) {
+        if self.system_theme == *system_theme {
+            return;
+        }
+        self.system_theme = system_theme.clone();
+        let text_rendering = theme::text_rendering_options(&self.env);
+        match &self.theme_envs {
+            Some(theme_envs) => self.env = theme_envs.env_for(system_theme, &self.data),
+            None => theme::set_system_theme(&mut self.env, system_theme),
+        }
+        let new_text_rendering = theme::text_rendering_options(&self.env);
+        if new_text_rendering != text_rendering {
+            for win in self.windows.iter_mut() {
+                win.handle.set_text_rendering(new_text_rendering);
+            }
+        }
+    }
+
+    /// Stop a pending quit from waiting for a window, and quit if it was the
+    /// last one. Returns `false` if the quit wasn't waiting for the window.
+    fn stop_waiting_for(&mut self, window_id: WindowId) -> bool {
+        match self.pending_quit.stop_waiting_for(window_id) {
+            Some(last) => {
+                if last {
+                    self.app.quit();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Close a window that agreed to close, after it vetoed the request.
+    fn close_window(&mut self, window_id: WindowId) {
+        if let Some(w) = self.windows.get_mut(window_id) {
+            w.event(
+                &mut self.command_queue,
+                Event::WindowDisconnected,
+                &mut self.data,
+                &self.env,
+            );
+        }
+        self.request_close_window(window_id);
+    }
+
+    /// Ask the delegate and the widgets of a window whether it may close,
+    /// without closing it.
+    fn ask_to_close(&mut self, window_id: WindowId) -> Handled {
+        let handled = self.delegate_cmd(&sys_cmd::CLOSE_WINDOW.to(window_id));
+        self.do_update();
+        if handled.is_handled() {
+            return handled;
+        }
+        match self.windows.get_mut(window_id) {
+            Some(w) => w.event(
+                &mut self.command_queue,
+                Event::WindowCloseRequested,
+                &mut self.data,
+                &self.env,
+            ),
+            None => Handled::No,
+        }
+    }
+
     /// Requests the platform to close all windows.
     fn request_close_all_windows(&mut self) {
         for win in self.windows.iter_mut() {
+            win.save_geometry();
             win.handle.close();
         }
     }
@@ -328,6 +775,32 @@ impl<T: Data> Inner<T> {
         }
     }
 
+    fn toggle_fullscreen(&mut self, id: WindowId) {
+        if let Some(win) = self.windows.get_mut(id) {
+            win.handle.set_fullscreen(!win.handle.is_fullscreen());
+        }
+    }
+
+    fn set_content_scale(&mut self, scale: f64, id: WindowId) {
+        if let Some(win) = self.windows.get_mut(id) {
+            win.set_content_scale(&mut self.command_queue, scale, &mut self.data, &self.env);
+        }
+    }
+
+    fn configure_toolbar(&mut self, cmd: &Command, id: WindowId) {
+        if let Some(win) = self.windows.get_mut(id) {
+            if let Some(items) = cmd.get(sys_cmd::SET_TOOLBAR) {
+                win.toolbar.set_toolbar(&win.handle, items);
+            } else if let Some((items, position)) = cmd.get(sys_cmd::SET_TITLEBAR_ACCESSORY) {
+                win.toolbar.set_accessory(&win.handle, items, *position);
+            } else if let Some(style) = cmd.get(sys_cmd::SET_TOOLBAR_STYLE) {
+                WindowToolbar::set_style(&win.handle, *style);
+            } else if let Some(transparent) = cmd.get(sys_cmd::SET_TITLEBAR_TRANSPARENT) {
+                WindowToolbar::set_transparent(&win.handle, *transparent);
+            }
+        }
+    }
+
     fn prepare_paint(&mut self, window_id: WindowId) {
         if let Some(win) = self.windows.get_mut(window_id) {
             win.prepare_paint(&mut self.command_queue, &mut self.data, &self.env);
@@ -348,6 +821,37 @@ impl<T: Data> Inner<T> {
     }
 
     fn dispatch_cmd(&mut self, cmd: Command) -> Handled {
+        crash::record_command(&cmd);
+        if self.interaction_sink.is_none() {
+            return self.route_cmd(cmd);
+        }
+
+        let kind = InteractionKind::Command {
+            selector: cmd.symbol(),
+        };
+        let mut interaction = match cmd.target() {
+            Target::Window(id) => Interaction::new(Some(id), kind),
+            Target::Widget(id) => {
+                let window_id = self
+                    .windows
+                    .iter_mut()
+                    .find(|w| w.may_contain_widget(id))
+                    .map(|w| w.id);
+                let mut interaction = Interaction::new(window_id, kind);
+                interaction.widget_id = Some(id);
+                interaction
+            }
+            Target::Global | Target::Auto => Interaction::new(None, kind),
+        };
+        let handled = self.route_cmd(cmd);
+        interaction.duration = Some(interaction.timestamp.elapsed());
+        if let Some(sink) = self.interaction_sink.as_mut() {
+            sink(&interaction);
+        }
+        handled
+    }
+
+    fn route_cmd(&mut self, cmd: Command) -> Handled {
         let handled = self.delegate_cmd(&cmd);
         self.do_update();
         if handled.is_handled() {
@@ -424,16 +928,87 @@ impl<T: Data> Inner<T> {
             _ => (),
         }
 
+        match event {
+            Event::Timer(token) if self.idle_timer_fired(source_id, token) => {
+                return Handled::Yes;
+            }
+            Event::Timer(token) if self.autosave_timer_fired(source_id, token) => {
+                return Handled::Yes;
+            }
+            Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_)
+            | Event::TouchDown(_)
+            | Event::TouchMove(_)
+            | Event::TouchUp(_)
+            | Event::Pen(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::Zoom(_) => self.note_user_input(source_id),
+            _ => (),
+        }
+
         // if the event was swallowed by the delegate we consider it handled?
         let event = match self.delegate_event(source_id, event) {
             Some(event) => event,
             None => return Handled::Yes,
         };
 
-        if let Some(win) = self.windows.get_mut(source_id) {
-            win.event(&mut self.command_queue, event, &mut self.data, &self.env)
-        } else {
-            Handled::No
+        let key = match &event {
+            Event::KeyDown(key) => Some(key.clone()),
+            _ => None,
+        };
+        let win = match self.windows.get_mut(source_id) {
+            Some(win) => win,
+            None => return Handled::No,
+        };
+        if win
+            .event(&mut self.command_queue, event, &mut self.data, &self.env)
+            .is_handled()
+        {
+            return Handled::Yes;
+        }
+
+        // Shortcuts only apply to the keys that no widget handled.
+        let key = match key {
+            Some(key) => key,
+            None => return Handled::No,
+        };
+        let app_shortcuts = &self.shortcuts;
+        let command = win
+            .shortcuts
+            .lookup(&key)
+            .or_else(|| app_shortcuts.lookup(&key))
+            .flatten();
+        match command {
+            Some(command) => {
+                let command = command.clone().default_to(Target::Window(source_id));
+                self.command_queue.push_back(command);
+                Handled::Yes
+            }
+            // with raw key input, keys don't go on to the text input system.
+            None if win.raw_key_input => Handled::Yes,
+            None => Handled::No,
+        }
+    }
+
+    fn set_raw_key_input(&mut self, window_id: WindowId, raw: bool) {
+        if let Some(win) = self.windows.get_mut(window_id) {
+            win.raw_key_input = raw;
+        }
+    }
+
+    fn set_shortcuts(&mut self, cmd: &Command) {
+        let shortcuts = cmd.get_unchecked(sys_cmd::SET_SHORTCUTS).clone();
+        match cmd.target() {
+            Target::Global => self.shortcuts = shortcuts,
+            Target::Window(id) => {
+                if let Some(win) = self.windows.get_mut(id) {
+                    win.shortcuts = shortcuts;
+                }
+            }
+            _ => (),
         }
     }
 
@@ -455,7 +1030,41 @@ impl<T: Data> Inner<T> {
         }
     }
 
+    /// Open, close and reconfigure the windows of the window set to match the
+    /// app data.
+    fn reconcile_windows(&mut self) {
+        let changes = match self.window_set.as_mut() {
+            Some(window_set) => window_set.reconcile(&self.data),
+            None => return,
+        };
+        for id in changes.close {
+            self.request_close_window(id);
+        }
+        for (id, config) in changes.configure {
+            self.configure_window(&config, id);
+        }
+        if changes.open.is_empty() {
+            return;
+        }
+        for desc in changes.open {
+            self.append_command(
+                sys_cmd::NEW_WINDOW
+                    .with(SingleUse::new(Box::new(desc)))
+                    .to(Target::Global),
+            );
+        }
+        // we are past the command processing of this pass, so ask for another one
+        let idle = self
+            .windows
+            .iter_mut()
+            .find_map(|win| win.handle.get_idle_handle());
+        if let Some(mut idle) = idle {
+            idle.schedule_idle(RUN_COMMANDS_TOKEN);
+        }
+    }
+
     fn do_update(&mut self) {
+        self.reconcile_windows();
         // we send `update` to all windows, not just the active one:
         for window in self.windows.iter_mut() {
             window.update(&mut self.command_queue, &self.data, &self.env);
@@ -486,6 +1095,9 @@ impl<T: Data> Inner<T> {
                 }
             }
         }
+        if let Some(tray) = &mut self.tray {
+            tray.update(&self.data, &self.env);
+        }
         self.invalidate_and_finalize();
     }
 
@@ -497,6 +1109,17 @@ impl<T: Data> Inner<T> {
         for win in self.windows.iter_mut() {
             win.invalidate_and_finalize();
         }
+        self.report_interactions();
+    }
+
+    /// Hand any interactions recorded by the windows to the sink, if there is one.
+    fn report_interactions(&mut self) {
+        for win in self.windows.iter_mut() {
+            match self.interaction_sink.as_mut() {
+                Some(sink) => win.interactions.drain(..).for_each(|i| sink(&i)),
+                None => win.interactions.clear(),
+            }
+        }
     }
 
     fn ime_update_fn(&self, window_id: WindowId, widget_id: WidgetId) -> Option<Box<ImeUpdateFn>> {
@@ -561,6 +1184,17 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().windows.add(id, window);
     }
 
+    /// The windows of the window set that the initial data describes.
+    pub(crate) fn declared_windows(&mut self) -> Vec<WindowDesc<T>> {
+        let Inner {
+            window_set, data, ..
+        } = &mut *self.inner.borrow_mut();
+        match window_set.as_mut() {
+            Some(window_set) => window_set.reconcile(data).open,
+            None => Vec::new(),
+        }
+    }
+
     fn connect_window(&mut self, window_id: WindowId, handle: WindowHandle) {
         self.inner.borrow_mut().connect(window_id, handle)
     }
@@ -647,6 +1281,54 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().do_update();
     }
 
+    fn handle_tray_activated(&mut self) {
+        self.inner
+            .borrow_mut()
+            .append_command(sys_cmd::TRAY_ACTIVATED.to(Target::Global));
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
+    fn handle_notification_activated(&mut self, token: NotificationToken, action: Option<usize>) {
+        let cmd = sys_cmd::NOTIFICATION_ACTIVATED.with((token, action));
+        self.inner
+            .borrow_mut()
+            .append_command(cmd.to(Target::Global));
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
+    fn handle_quit_request(&mut self) {
+        self.inner
+            .borrow_mut()
+            .append_command(sys_cmd::REQUEST_QUIT.to(Target::Global));
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
+    fn handle_global_hotkey(&mut self, token: GlobalHotKeyToken) {
+        let cmd = self
+            .inner
+            .borrow()
+            .global_hotkeys
+            .iter()
+            .find(|(_, t, _)| *t == token)
+            .map(|(_, _, cmd)| cmd.clone());
+        if let Some(cmd) = cmd {
+            self.inner.borrow_mut().append_command(cmd);
+            self.process_commands();
+            self.inner.borrow_mut().do_update();
+        }
+    }
+
+    /// Add the app's icon to the system tray.
+    pub(crate) fn add_tray(&mut self, desc: TrayDesc<T>) -> Result<(), PlatformError> {
+        let mut inner = self.inner.borrow_mut();
+        let tray = desc.build(&inner.data, &inner.env)?;
+        inner.tray = Some(tray);
+        Ok(())
+    }
+
     /// Handle a command. Top level commands (e.g. for creating and destroying
     /// windows) have their logic here; other commands are passed to the window.
     fn handle_cmd(&mut self, cmd: Command) {
@@ -654,6 +1336,7 @@ impl<T: Data> AppState<T> {
         match cmd.target() {
             // these are handled the same no matter where they come from
             _ if cmd.is(sys_cmd::QUIT_APP) => self.quit(),
+            _ if cmd.is(sys_cmd::REQUEST_QUIT) => self.request_quit(),
             #[cfg(target_os = "macos")]
             _ if cmd.is(sys_cmd::HIDE_APPLICATION) => self.hide_app(),
             #[cfg(target_os = "macos")]
@@ -661,33 +1344,92 @@ impl<T: Data> AppState<T> {
             _ if cmd.is(sys_cmd::NEW_WINDOW) => {
                 if let Err(e) = self.new_window(cmd) {
                     tracing::error!("failed to create window: '{}'", e);
+                    self.shell_error(ShellError::new(ShellOperation::CreateWindow, e), None);
                 }
             }
             _ if cmd.is(sys_cmd::NEW_SUB_WINDOW) => {
                 if let Err(e) = self.new_sub_window(cmd) {
                     tracing::error!("failed to create sub window: '{}'", e);
+                    self.shell_error(ShellError::new(ShellOperation::CreateWindow, e), None);
                 }
             }
             _ if cmd.is(sys_cmd::CLOSE_ALL_WINDOWS) => self.request_close_all_windows(),
+            _ if cmd.is(sys_cmd::REGISTER_GLOBAL_HOTKEY) => {
+                let (hotkey, hotkey_cmd) = cmd.get_unchecked(sys_cmd::REGISTER_GLOBAL_HOTKEY);
+                if let Err(e) = self.register_global_hotkey(hotkey.clone(), hotkey_cmd.clone()) {
+                    tracing::warn!("failed to register global hotkey: '{}'", e);
+                    self.shell_error(ShellError::new(ShellOperation::GlobalHotKey, e), None);
+                }
+            }
+            _ if cmd.is(sys_cmd::UNREGISTER_GLOBAL_HOTKEY) => {
+                let hotkey = cmd.get_unchecked(sys_cmd::UNREGISTER_GLOBAL_HOTKEY);
+                self.unregister_global_hotkey(hotkey);
+            }
+            _ if cmd.is(sys_cmd::OPEN_URL) => self.open_url(&cmd),
+            _ if cmd.is(sys_cmd::REVEAL_PATH) => self.reveal_path(&cmd),
+            T::Global | T::Window(_) if cmd.is(sys_cmd::SET_SHORTCUTS) => {
+                self.inner.borrow_mut().set_shortcuts(&cmd)
+            }
+            T::Window(id) if cmd.is(sys_cmd::SET_RAW_KEY_INPUT) => {
+                let raw = *cmd.get_unchecked(sys_cmd::SET_RAW_KEY_INPUT);
+                self.inner.borrow_mut().set_raw_key_input(id, raw)
+            }
+            _ if cmd.is(sys_cmd::SHOW_ABOUT) => {
+                if !self.inner.borrow_mut().dispatch_cmd(cmd).is_handled() {
+                    self.show_about();
+                }
+            }
             T::Window(id) if cmd.is(sys_cmd::INVALIDATE_IME) => self.invalidate_ime(cmd, id),
             // these should come from a window
             // FIXME: we need to be able to open a file without a window handle
             T::Window(id) if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => self.show_open_panel(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::SHOW_SAVE_PANEL) => self.show_save_panel(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::CONFIGURE_WINDOW) => self.configure_window(cmd, id),
+            T::Window(id) if cmd.is(sys_cmd::SET_CONTENT_SCALE) => self.set_content_scale(cmd, id),
+            T::Window(id)
+                if cmd.is(sys_cmd::SET_TOOLBAR)
+                    || cmd.is(sys_cmd::SET_TOOLBAR_STYLE)
+                    || cmd.is(sys_cmd::SET_TITLEBAR_ACCESSORY)
+                    || cmd.is(sys_cmd::SET_TITLEBAR_TRANSPARENT) =>
+            {
+                self.configure_toolbar(cmd, id)
+            }
+            // don't ask again while the user is answering for the quit.
+            T::Window(id) if cmd.is(sys_cmd::CLOSE_WINDOW) && self.is_pending_quit(id) => (),
             T::Window(id) if cmd.is(sys_cmd::CLOSE_WINDOW) => {
                 if !self.inner.borrow_mut().dispatch_cmd(cmd).is_handled() {
                     self.request_close_window(id);
                 }
             }
+            T::Window(id) if cmd.is(sys_cmd::CONFIRM_CLOSE) => self.confirm_close(id),
+            T::Window(id) if cmd.is(sys_cmd::CANCEL_CLOSE) => {
+                let mut inner = self.inner.borrow_mut();
+                if inner.pending_quit.cancel(id) {
+                    inner.app.cancel_quit();
+                }
+            }
             T::Window(id) if cmd.is(sys_cmd::SHOW_WINDOW) => self.show_window(id),
+            T::Window(id) if cmd.is(sys_cmd::TOGGLE_FULLSCREEN) => {
+                self.inner.borrow_mut().toggle_fullscreen(id)
+            }
             T::Window(id) if cmd.is(sys_cmd::PASTE) => self.do_paste(id),
+            T::Window(id) if cmd.is(sys_cmd::SUBMIT_EVENT) => {
+                match cmd.get_unchecked(sys_cmd::SUBMIT_EVENT).clone() {
+                    ExtInputEvent::KeyDown(key) => self.simulate_key_down(key, id),
+                    event => {
+                        self.do_window_event(event.into(), id);
+                    }
+                }
+            }
             _ if cmd.is(sys_cmd::CLOSE_WINDOW) => {
                 tracing::warn!("CLOSE_WINDOW command must target a window.")
             }
             _ if cmd.is(sys_cmd::SHOW_WINDOW) => {
                 tracing::warn!("SHOW_WINDOW command must target a window.")
             }
+            _ if cmd.is(sys_cmd::TOGGLE_FULLSCREEN) => {
+                tracing::warn!("TOGGLE_FULLSCREEN command must target a window.")
+            }
             _ if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => {
                 tracing::warn!("SHOW_OPEN_PANEL command must target a window.")
             }
@@ -710,8 +1452,11 @@ impl<T: Data> AppState<T> {
         let cancel_cmd = options
             .cancel_cmd
             .unwrap_or(crate::commands::OPEN_PANEL_CANCELLED);
-        let token = handle.and_then(|mut handle| handle.open_file(options.opt));
-        if let Some(token) = token {
+        let mut handle = match handle {
+            Some(handle) => handle,
+            None => return,
+        };
+        if let Some(token) = handle.open_file(options.opt) {
             self.inner.borrow_mut().file_dialogs.insert(
                 token,
                 DialogInfo {
@@ -720,6 +1465,12 @@ impl<T: Data> AppState<T> {
                     cancel_cmd,
                 },
             );
+        } else {
+            let error = ShellError::new(ShellOperation::FileDialog, PlatformError::Unsupported);
+            self.shell_error(error, Some(window_id));
+            self.inner
+                .borrow_mut()
+                .append_command(cancel_cmd.to(window_id));
         }
     }
 
@@ -735,8 +1486,11 @@ impl<T: Data> AppState<T> {
         let cancel_cmd = options
             .cancel_cmd
             .unwrap_or(crate::commands::SAVE_PANEL_CANCELLED);
-        let token = handle.and_then(|mut handle| handle.save_as(options.opt));
-        if let Some(token) = token {
+        let mut handle = match handle {
+            Some(handle) => handle,
+            None => return,
+        };
+        if let Some(token) = handle.save_as(options.opt) {
             self.inner.borrow_mut().file_dialogs.insert(
                 token,
                 DialogInfo {
@@ -745,6 +1499,12 @@ impl<T: Data> AppState<T> {
                     cancel_cmd,
                 },
             );
+        } else {
+            let error = ShellError::new(ShellOperation::FileDialog, PlatformError::Unsupported);
+            self.shell_error(error, Some(window_id));
+            self.inner
+                .borrow_mut()
+                .append_command(cancel_cmd.to(window_id));
         }
     }
 
@@ -766,7 +1526,29 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().do_update();
     }
 
-    fn new_window(&mut self, cmd: Command) -> Result<(), Box<dyn std::error::Error>> {
+    fn system_theme_changed(&mut self, system_theme: SystemTheme, window_id: WindowId) {
+        self.inner.borrow_mut().set_system_theme(&system_theme);
+        self.do_window_event(Event::SystemThemeChanged(system_theme), window_id);
+    }
+
+    fn clipboard_changed(&mut self, window_id: WindowId) {
+        self.inner
+            .borrow_mut()
+            .append_command(sys_cmd::CLIPBOARD_CHANGED.to(window_id));
+        self.process_commands();
+        self.inner.borrow_mut().do_update();
+    }
+
+    /// Reports a failed shell operation to `window_id`, or globally if the
+    /// failure doesn't belong to a window.
+    fn shell_error(&mut self, error: ShellError, window_id: Option<WindowId>) {
+        let target = window_id.map(Target::Window).unwrap_or(Target::Global);
+        self.inner
+            .borrow_mut()
+            .append_command(sys_cmd::SHELL_ERROR.with(error).to(target));
+    }
+
+    fn new_window(&mut self, cmd: Command) -> Result<(), PlatformError> {
         let desc = cmd.get_unchecked(sys_cmd::NEW_WINDOW);
         // The NEW_WINDOW command is private and only druid can receive it by normal means,
         // thus unwrapping can be considered safe and deserves a panic.
@@ -776,7 +1558,7 @@ impl<T: Data> AppState<T> {
         Ok(())
     }
 
-    fn new_sub_window(&mut self, cmd: Command) -> Result<(), Box<dyn std::error::Error>> {
+    fn new_sub_window(&mut self, cmd: Command) -> Result<(), PlatformError> {
         if let Some(transfer) = cmd.get(sys_cmd::NEW_SUB_WINDOW) {
             if let Some(sub_window_desc) = transfer.take() {
                 let window = sub_window_desc.make_sub_window(self)?;
@@ -808,12 +1590,59 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().show_window(id);
     }
 
+    /// Show the About window, if the app has one.
+    fn show_about(&mut self) {
+        let inner = self.inner.borrow();
+        let info = match &inner.about {
+            Some(info) => info.clone(),
+            None => return,
+        };
+        let open = inner.about_window;
+        drop(inner);
+        if let Some(id) = open {
+            self.show_window(id);
+            return;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use druid_shell::platform::mac::ApplicationExt as _;
+            let credits = info.credits.join("\n");
+            self.app()
+                .show_about_panel(&info.name, &info.version, &credits);
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let desc = info.window::<T>();
+            let id = desc.id;
+            match desc.build_native(self) {
+                Ok(window) => {
+                    window.show();
+                    self.inner.borrow_mut().about_window = Some(id);
+                }
+                Err(e) => {
+                    tracing::error!("failed to create About window: '{}'", e);
+                    self.shell_error(ShellError::new(ShellOperation::CreateWindow, e), None);
+                }
+            }
+        }
+    }
+
     fn configure_window(&mut self, cmd: Command, id: WindowId) {
         if let Some(config) = cmd.get(sys_cmd::CONFIGURE_WINDOW) {
             self.inner.borrow_mut().configure_window(config, id);
         }
     }
 
+    fn configure_toolbar(&mut self, cmd: Command, id: WindowId) {
+        self.inner.borrow_mut().configure_toolbar(&cmd, id);
+    }
+
+    fn set_content_scale(&mut self, cmd: Command, id: WindowId) {
+        let scale = *cmd.get_unchecked(sys_cmd::SET_CONTENT_SCALE);
+        self.inner.borrow_mut().set_content_scale(scale, id);
+    }
+
     fn do_paste(&mut self, window_id: WindowId) {
         let event = Event::Paste(self.inner.borrow().app.clipboard());
         self.inner.borrow_mut().do_window_event(window_id, event);
@@ -827,6 +1656,20 @@ impl<T: Data> AppState<T> {
         }
     }
 
+    /// Handle a key press that was not made on the keyboard the way the
+    /// platform handles one without an IME: if no widget handles it, it edits
+    /// the focused text field.
+    fn simulate_key_down(&mut self, event: KeyEvent, window_id: WindowId) {
+        let token = self
+            .inner
+            .borrow()
+            .windows
+            .get(window_id)
+            .and_then(|win| win.active_text_field());
+        let mut handler = DruidHandler::new_shared(self.clone(), window_id);
+        druid_shell::text::simulate_input(&mut handler, token, event);
+    }
+
     fn release_ime_lock(&mut self, window_id: WindowId, token: TextFieldToken) {
         let needs_update = self.inner.borrow_mut().release_ime_lock(window_id, token);
         if let Some(widget) = needs_update {
@@ -836,7 +1679,92 @@ impl<T: Data> AppState<T> {
     }
 
     fn quit(&self) {
-        self.inner.borrow().app.quit()
+        let mut inner = self.inner.borrow_mut();
+        inner.pending_quit.clear();
+        inner.app.quit()
+    }
+
+    /// Quit once every window agreed to close.
+    fn request_quit(&mut self) {
+        if self.inner.borrow().pending_quit.is_waiting() {
+            return;
+        }
+        let ids = self.inner.borrow().windows.ids.clone();
+        let vetoed = ids
+            .into_iter()
+            .filter(|&id| self.inner.borrow_mut().ask_to_close(id).is_handled())
+            .collect::<HashSet<_>>();
+        if self.inner.borrow_mut().pending_quit.start(vetoed) {
+            self.quit();
+        }
+    }
+
+    /// Whether a quit is waiting for this window to confirm or cancel.
+    fn is_pending_quit(&self, id: WindowId) -> bool {
+        self.inner.borrow().pending_quit.is_waiting_for(id)
+    }
+
+    fn confirm_close(&mut self, id: WindowId) {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.stop_waiting_for(id) {
+            inner.close_window(id);
+        }
+    }
+
+    fn register_global_hotkey(
+        &mut self,
+        hotkey: HotKey,
+        cmd: Command,
+    ) -> Result<(), PlatformError> {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(entry) = inner
+            .global_hotkeys
+            .iter_mut()
+            .find(|(h, _, _)| *h == hotkey)
+        {
+            entry.2 = cmd;
+            return Ok(());
+        }
+        let token = inner.app.register_global_hotkey(&hotkey)?;
+        inner.global_hotkeys.push((hotkey, token, cmd));
+        Ok(())
+    }
+
+    fn open_url(&mut self, cmd: &Command) {
+        let url = cmd.get_unchecked(sys_cmd::OPEN_URL);
+        // Other URLs, like `file:` ones or paths, could start a program.
+        let result = if is_web_url(url) {
+            self.inner.borrow().app.open_url(url)
+        } else {
+            Err(PlatformError::Unsupported)
+        };
+        if let Err(e) = result {
+            tracing::warn!("failed to open '{}': '{}'", url, e);
+            let error = ShellError::new(ShellOperation::OpenUrl, e);
+            self.shell_error(error, cmd.target().window_id());
+        }
+    }
+
+    fn reveal_path(&mut self, cmd: &Command) {
+        let path = cmd.get_unchecked(sys_cmd::REVEAL_PATH);
+        let result = self.inner.borrow().app.reveal_path(path);
+        if let Err(e) = result {
+            tracing::warn!("failed to reveal {:?}: '{}'", path, e);
+            let error = ShellError::new(ShellOperation::RevealPath, e);
+            self.shell_error(error, cmd.target().window_id());
+        }
+    }
+
+    fn unregister_global_hotkey(&mut self, hotkey: &HotKey) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(idx) = inner
+            .global_hotkeys
+            .iter()
+            .position(|(h, _, _)| h == hotkey)
+        {
+            let (_, token, _) = inner.global_hotkeys.remove(idx);
+            inner.app.unregister_global_hotkey(token);
+        }
     }
 
     #[cfg(target_os = "macos")]
@@ -879,7 +1807,20 @@ impl<T: Data> AppState<T> {
         builder.set_handler(Box::new(handler));
 
         self.add_window(id, pending);
-        builder.build()
+        let handle = match builder.build() {
+            Ok(handle) => handle,
+            Err(e) => {
+                // the window will never connect.
+                self.inner.borrow_mut().windows.remove(id);
+                return Err(e);
+            }
+        };
+        match (config.fullscreen, &config.fullscreen_monitor) {
+            (Some(true), Some(monitor)) => handle.set_fullscreen_on(monitor),
+            (Some(true), None) => handle.set_fullscreen(true),
+            _ => (),
+        }
+        Ok(handle)
     }
 }
 
@@ -887,6 +1828,22 @@ impl<T: Data> crate::shell::AppHandler for AppHandler<T> {
     fn command(&mut self, id: u32) {
         self.app_state.handle_system_cmd(id, None)
     }
+
+    fn tray_activated(&mut self) {
+        self.app_state.handle_tray_activated()
+    }
+
+    fn notification_activated(&mut self, token: NotificationToken, action: Option<usize>) {
+        self.app_state.handle_notification_activated(token, action)
+    }
+
+    fn global_hotkey(&mut self, token: GlobalHotKeyToken) {
+        self.app_state.handle_global_hotkey(token)
+    }
+
+    fn request_quit(&mut self) {
+        self.app_state.handle_quit_request()
+    }
 }
 
 impl<T: Data> WinHandler for DruidHandler<T> {
@@ -948,6 +1905,51 @@ impl<T: Data> WinHandler for DruidHandler<T> {
             .do_window_event(Event::Internal(InternalEvent::MouseLeave), self.window_id);
     }
 
+    fn touch_down(&mut self, event: &TouchEvent) -> bool {
+        let event = Event::TouchDown(event.clone().into());
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
+    fn touch_move(&mut self, event: &TouchEvent) -> bool {
+        let event = Event::TouchMove(event.clone().into());
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
+    fn touch_up(&mut self, event: &TouchEvent) -> bool {
+        let event = Event::TouchUp(event.clone().into());
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
+    fn pen(&mut self, event: &PenEvent) {
+        let event = Event::Pen(event.clone().into());
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn drag_over(&mut self, event: &DropEvent) -> bool {
+        let event = Event::DragOver(event.clone().into());
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
+    fn drag_leave(&mut self) {
+        self.app_state
+            .do_window_event(Event::DragLeave, self.window_id);
+    }
+
+    fn drag_drop(&mut self, event: &DropEvent) -> bool {
+        let event = Event::Drop(event.clone().into());
+        self.app_state
+            .do_window_event(event, self.window_id)
+            .is_handled()
+    }
+
     fn key_down(&mut self, event: KeyEvent) -> bool {
         self.app_state
             .do_window_event(Event::KeyDown(event), self.window_id)
@@ -973,6 +1975,31 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         self.app_state.window_got_focus(self.window_id);
     }
 
+    fn clipboard_changed(&mut self) {
+        self.app_state.clipboard_changed(self.window_id);
+    }
+
+    fn system_theme_changed(&mut self, system_theme: &SystemTheme) {
+        self.app_state
+            .system_theme_changed(system_theme.clone(), self.window_id);
+    }
+
+    fn monitors_changed(&mut self) {
+        self.app_state
+            .do_window_event(Event::MonitorsChanged, self.window_id);
+    }
+
+    fn monitor_changed(&mut self, monitor: &Monitor) {
+        let event = Event::WindowMonitorChanged(monitor.clone());
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
+    fn shell_error(&mut self, error: ShellError) {
+        self.app_state.shell_error(error, Some(self.window_id));
+        self.app_state.process_commands();
+        self.app_state.inner.borrow_mut().do_update();
+    }
+
     fn timer(&mut self, token: TimerToken) {
         self.app_state
             .do_window_event(Event::Timer(token), self.window_id);
@@ -1018,6 +2045,128 @@ impl<T> Default for Windows<T> {
         Windows {
             windows: HashMap::new(),
             pending: HashMap::new(),
+            ids: Vec::new(),
         }
     }
 }
+
+/// Whether `url` is an `http`, `https` or `mailto` URL, which are the ones
+/// [`OPEN_URL`] opens.
+///
+/// [`OPEN_URL`]: crate::commands::OPEN_URL
+fn is_web_url(url: &str) -> bool {
+    let (scheme, rest) = match url.split_once(':') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") {
+        // Without an authority, some platforms treat the rest as a path.
+        rest.starts_with("//") && rest.len() > 2
+    } else {
+        scheme.eq_ignore_ascii_case("mailto")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_urls() {
+        assert!(is_web_url("https://linebender.org/druid/"));
+        assert!(is_web_url("HTTP://example.com"));
+        assert!(is_web_url("mailto:someone@example.com"));
+
+        assert!(!is_web_url("file:///C:/Windows/System32/calc.exe"));
+        assert!(!is_web_url("C:\\Windows\\System32\\calc.exe"));
+        assert!(!is_web_url("\\\\host\\share\\x.exe"));
+        assert!(!is_web_url("/usr/share/applications/x.desktop"));
+        assert!(!is_web_url("x.desktop"));
+        assert!(!is_web_url(" https://example.com"));
+        assert!(!is_web_url("https:example.com"));
+        assert!(!is_web_url("https://"));
+        assert!(!is_web_url("javascript:alert(1)"));
+        assert!(!is_web_url("smb://host/share/x.exe"));
+    }
+
+    #[test]
+    fn quit_without_vetoes_is_immediate() {
+        let mut pending = PendingQuit::default();
+        assert!(pending.start(HashSet::new()));
+        assert!(!pending.is_waiting());
+    }
+
+    #[test]
+    fn quit_waits_until_every_veto_is_confirmed() {
+        let (a, b, other) = (WindowId::next(), WindowId::next(), WindowId::next());
+        let mut pending = PendingQuit::default();
+        assert!(!pending.start([a, b].iter().copied().collect()));
+        assert!(pending.is_waiting_for(a));
+        assert!(!pending.is_waiting_for(other));
+
+        // CONFIRM_CLOSE for a window the quit isn't waiting for just closes it.
+        assert_eq!(pending.stop_waiting_for(other), None);
+        assert_eq!(pending.stop_waiting_for(a), Some(false));
+        assert_eq!(pending.stop_waiting_for(a), None);
+        assert!(pending.is_waiting());
+
+        // a vetoing window that goes away counts as agreeing.
+        assert_eq!(pending.stop_waiting_for(b), Some(true));
+        assert!(!pending.is_waiting());
+    }
+
+    #[test]
+    fn cancelled_close_abandons_the_quit() {
+        let (a, b, other) = (WindowId::next(), WindowId::next(), WindowId::next());
+        let mut pending = PendingQuit::default();
+        pending.start([a, b].iter().copied().collect());
+
+        // CANCEL_CLOSE from a window that didn't veto has no effect.
+        assert!(!pending.cancel(other));
+        assert!(pending.is_waiting());
+
+        assert!(pending.cancel(b));
+        assert!(!pending.is_waiting());
+        // the quit is over, so confirming the other window only closes it.
+        assert_eq!(pending.stop_waiting_for(a), None);
+    }
+
+    #[test]
+    fn idle_command_is_submitted_once_per_idle_period() {
+        const LOCK: Selector = Selector::new("druid-tests.lock");
+        let secs = Duration::from_secs;
+        let mut monitor = IdleMonitor::new(secs(60), LOCK.into());
+        assert_eq!(monitor.remaining(secs(20)), secs(40));
+        assert!(monitor.take_command(secs(20)).is_none());
+
+        let cmd = monitor.take_command(secs(60)).unwrap();
+        assert!(cmd.is(LOCK));
+        assert_eq!(cmd.target(), Target::Global);
+        assert_eq!(monitor.remaining(secs(90)), Duration::ZERO);
+        assert!(monitor.take_command(secs(90)).is_none());
+
+        // input starts a new idle period.
+        monitor.reset();
+        assert!(monitor.take_command(secs(60)).is_some());
+    }
+
+    #[test]
+    fn idle_command_keeps_its_target() {
+        const LOCK: Selector = Selector::new("druid-tests.lock");
+        let window = WindowId::next();
+        let mut monitor = IdleMonitor::new(Duration::from_secs(1), LOCK.to(window));
+        let cmd = monitor.take_command(Duration::from_secs(1)).unwrap();
+        assert_eq!(cmd.target(), Target::Window(window));
+    }
+
+    #[test]
+    fn forced_quit_clears_the_pending_quit() {
+        let a = WindowId::next();
+        let mut pending = PendingQuit::default();
+        pending.start([a].iter().copied().collect());
+        pending.clear();
+        assert!(!pending.is_waiting_for(a));
+        // a new request asks again.
+        assert!(!pending.start([a].iter().copied().collect()));
+    }
+}
@@ -14,27 +14,36 @@
 
 //! Management of multiple windows.
 
+use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::mem;
-use tracing::{error, info, info_span};
+use std::ops::Range;
+use std::path::PathBuf;
+use tracing::{error, info, info_span, warn};
 
 // Automatically defaults to std::time::Instant on non Wasm platforms
 use instant::Instant;
 
-use crate::piet::{Color, Piet, RenderContext};
-use crate::shell::{text::InputHandler, Counter, Cursor, Region, TextFieldToken, WindowHandle};
+use crate::piet::{Color, Device, HitTestPoint, Piet, RenderContext};
+use crate::shell::text::{Action, Affinity, InputHandler, InputHints, Selection};
+use crate::shell::{Counter, Cursor, Region, TextFieldToken, WindowHandle};
 
 use crate::app::{PendingWindow, WindowSizePolicy};
-use crate::contexts::ContextState;
-use crate::core::{CommandQueue, FocusChange, WidgetState};
+use crate::command::sys::DRAG_ENDED;
+use crate::contexts::{ContextState, RaisedWidget};
+use crate::core::{tab_order, CommandQueue, FocusChange, WidgetState};
+use crate::dnd::{DragEvent, DragSession};
+use crate::geometry::{self, WindowGeometry};
 use crate::menu::{MenuItemId, MenuManager};
 use crate::text::TextFieldRegistration;
+use crate::toolbar::WindowToolbar;
 use crate::util::ExtendDrain;
 use crate::widget::LabelText;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
-    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Size, TimerToken,
+    Affine, BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, Interaction,
+    InternalEvent, InternalLifeCycle, KbKey, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, MouseButton,
+    MouseEvent, PaintCtx, Point, Rect, ShortcutMap, Size, Target, TimerToken, TouchEvent,
     UpdateCtx, Widget, WidgetId, WidgetPod,
 };
 
@@ -51,9 +60,18 @@ pub struct Window<T> {
     pub(crate) title: LabelText<T>,
     size_policy: WindowSizePolicy,
     size: Size,
+    /// The factor by which the content is scaled up to fill the window.
+    pub(crate) content_scale: f64,
+    /// Keyboard shortcuts that only apply to this window.
+    pub(crate) shortcuts: ShortcutMap,
+    /// Whether keys that no widget handled are kept from the text input system.
+    pub(crate) raw_key_input: bool,
+    /// Where to save the window's geometry when it closes.
+    geometry_path: Option<PathBuf>,
     invalid: Region,
     pub(crate) menu: Option<MenuManager<T>>,
     pub(crate) context_menu: Option<(MenuManager<T>, Point)>,
+    pub(crate) toolbar: WindowToolbar,
     // This will be `Some` whenever the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<Point>,
@@ -64,6 +82,17 @@ pub struct Window<T> {
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     ext_handle: ExtEventSink,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
+    /// Whether the widgets record interactions, because the app has a sink
+    /// for them.
+    pub(crate) record_interactions: bool,
+    /// Interactions recorded since they were last handed to the app's sink.
+    pub(crate) interactions: Vec<Interaction>,
+    /// The drag started by one of the widgets, while it is in progress.
+    drag: Option<DragSession>,
+    /// The widgets raised above their siblings, as of the last paint.
+    raised_widgets: Vec<RaisedWidget>,
+    /// The device for offscreen images, shared by all paint passes.
+    offscreen_device: Option<Device>,
 }
 
 impl<T> Window<T> {
@@ -78,11 +107,16 @@ impl<T> Window<T> {
             root: WidgetPod::new(pending.root),
             size_policy: pending.size_policy,
             size: Size::ZERO,
+            content_scale: pending.content_scale,
+            shortcuts: pending.shortcuts,
+            raw_key_input: pending.raw_key_input,
+            geometry_path: pending.geometry_path,
             invalid: Region::EMPTY,
             title: pending.title,
             transparent: pending.transparent,
             menu: pending.menu,
             context_menu: None,
+            toolbar: WindowToolbar::default(),
             last_anim: None,
             last_mouse_pos: None,
             focus: None,
@@ -91,18 +125,31 @@ impl<T> Window<T> {
             ext_handle,
             ime_handlers: Vec::new(),
             ime_focus_change: None,
+            record_interactions: false,
+            interactions: Vec::new(),
+            drag: None,
+            raised_widgets: Vec::new(),
+            offscreen_device: None,
         }
     }
 }
 
 impl<T: Data> Window<T> {
+    /// Save the window's geometry, if it was asked to.
+    pub(crate) fn save_geometry(&self) {
+        if let Some(path) = &self.geometry_path {
+            geometry::save_or_warn(&WindowGeometry::of(&self.handle), path);
+        }
+    }
+
     /// `true` iff any child requested an animation frame since the last `AnimFrame` event.
     pub(crate) fn wants_animation_frame(&self) -> bool {
         self.root.state().request_anim
     }
 
-    pub(crate) fn focus_chain(&self) -> &[WidgetId] {
-        &self.root.state().focus_chain
+    #[cfg(test)]
+    pub(crate) fn focus_chain(&self) -> Vec<WidgetId> {
+        self.root.focus_chain()
     }
 
     /// Returns `true` if the provided widget may be in this window,
@@ -126,6 +173,9 @@ impl<T: Data> Window<T> {
         if let Some((menu, _)) = &mut self.context_menu {
             menu.event(queue, Some(self.id), cmd_id, data, env);
         }
+        if let Some(cmd) = self.toolbar.command(cmd_id) {
+            queue.push_back(cmd.clone().default_to(Target::Window(self.id)));
+        }
     }
 
     pub(crate) fn show_context_menu(&mut self, menu: Menu<T>, point: Point, data: &T, env: &Env) {
@@ -178,6 +228,11 @@ impl<T: Data> Window<T> {
                 env,
                 false,
             );
+
+            // Forget the timers of widgets that were removed from the tree.
+            let root = self.root.state();
+            self.timers
+                .retain(|_, id| *id == root.id || root.children.may_contain(id));
         }
 
         if self.root.state().needs_window_origin && !self.root.state().needs_layout {
@@ -209,11 +264,20 @@ impl<T: Data> Window<T> {
             self.handle.request_anim_frame();
         }
         self.invalid.union_with(&widget_state.invalid);
+        self.invalid.union_with(&widget_state.window_invalid);
+        widget_state.window_invalid.clear();
         for ime_field in widget_state.text_registrations.drain(..) {
             let token = self.handle.add_text_field();
             tracing::debug!("{:?} added", token);
             self.ime_handlers.push((token, ime_field));
         }
+        self.interactions.append(&mut widget_state.interactions);
+        if let Some(drag) = widget_state.start_drag.take() {
+            if self.drag.is_none() {
+                self.drag = Some(drag);
+                self.invalidate_drag_image();
+            }
+        }
 
         // If there are any commands and they should be processed
         if process_commands && !queue.is_empty() {
@@ -234,8 +298,89 @@ impl<T: Data> Window<T> {
         data: &mut T,
         env: &Env,
     ) -> Handled {
+        let drag = match &self.drag {
+            Some(drag) => drag,
+            None => return self.route_event(queue, event, data, env),
+        };
+        // While a drag is in progress, the mouse also moves the drag.
+        let drag_event = match &event {
+            Event::MouseMove(mouse) => Some(Event::DragOver(DragEvent::from_mouse(
+                mouse,
+                drag.contents.clone(),
+            ))),
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left => Some(Event::Drop(
+                DragEvent::from_mouse(mouse, drag.contents.clone()),
+            )),
+            Event::Internal(InternalEvent::MouseLeave) => Some(Event::DragLeave),
+            Event::KeyDown(key) if key.key == KbKey::Escape => {
+                self.end_drag(queue, false, data, env);
+                return Handled::Yes;
+            }
+            _ => None,
+        };
+
+        self.invalidate_drag_image();
+        let handled = self.route_event(queue, event, data, env);
+        match drag_event {
+            Some(drop @ Event::Drop(_)) => {
+                let accepted = self.route_event(queue, drop, data, env).is_handled();
+                self.end_drag(queue, accepted, data, env);
+            }
+            Some(drag_event) => {
+                self.route_event(queue, drag_event, data, env);
+            }
+            None => (),
+        }
+        self.invalidate_drag_image();
+        handled
+    }
+
+    /// Finish the drag in progress, and tell the widget that started it.
+    fn end_drag(&mut self, queue: &mut CommandQueue, accepted: bool, data: &mut T, env: &Env) {
+        let drag = match self.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        if !accepted {
+            self.route_event(queue, Event::DragLeave, data, env);
+        }
+        if let (Some(image), Some(pos)) = (&drag.image, self.last_mouse_pos) {
+            self.invalid.add_rect(image.rect_at(pos));
+        }
+        queue.push_back(DRAG_ENDED.with(accepted).to(drag.source));
+    }
+
+    /// Repaint the area under the drag image, if there is one.
+    fn invalidate_drag_image(&mut self) {
+        let image = self.drag.as_ref().and_then(|drag| drag.image.as_ref());
+        if let (Some(image), Some(pos)) = (image, self.last_mouse_pos) {
+            self.invalid.add_rect(image.rect_at(pos));
+        }
+    }
+
+    /// The topmost widget raised above its siblings at `pos`, in content
+    /// coordinates.
+    fn raised_widget_at(&self, pos: Point) -> Option<RaisedWidget> {
+        // Of widgets with the same z-index, the one painted last is on top.
+        self.raised_widgets
+            .iter()
+            .filter(|raised| raised.rect.contains(pos))
+            .max_by_key(|raised| raised.z_index)
+            .cloned()
+    }
+
+    fn route_event(
+        &mut self,
+        queue: &mut CommandQueue,
+        event: Event,
+        data: &mut T,
+        env: &Env,
+    ) -> Handled {
+        if let Event::WindowSize(size) = &event {
+            self.size = *size;
+        }
+        let event = self.to_content_event(event);
         match &event {
-            Event::WindowSize(size) => self.size = *size,
             Event::MouseDown(e) | Event::MouseUp(e) | Event::MouseMove(e) | Event::Wheel(e) => {
                 self.last_mouse_pos = Some(e.pos)
             }
@@ -265,10 +410,23 @@ impl<T: Data> Window<T> {
             );
         }
 
-        let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
+        let mut widget_state = WidgetState::new(self.root.id(), Some(self.content_size()));
         let is_handled = {
-            let mut state =
-                ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+            let mut state = ContextState::new::<T>(
+                queue,
+                &self.ext_handle,
+                &self.handle,
+                self.id,
+                self.focus,
+                self.content_scale,
+            );
+            state.record_interactions = self.record_interactions;
+            state.raised_under_mouse = match &event {
+                Event::MouseDown(e) | Event::MouseUp(e) | Event::MouseMove(e) | Event::Wheel(e) => {
+                    self.raised_widget_at(e.pos)
+                }
+                _ => None,
+            };
             let mut notifications = VecDeque::new();
             let mut ctx = EventCtx {
                 state: &mut state,
@@ -308,6 +466,23 @@ impl<T: Data> Window<T> {
             self.handle.set_cursor(&Cursor::Arrow);
         }
 
+        // Tab moves the focus along the tab order, unless a widget used the key.
+        let is_handled = match &event {
+            Event::KeyDown(key)
+                if !is_handled.is_handled()
+                    && key.key == KbKey::Tab
+                    && !(key.mods.ctrl() || key.mods.alt() || key.mods.meta()) =>
+            {
+                widget_state.request_focus = Some(if key.mods.shift() {
+                    FocusChange::Previous
+                } else {
+                    FocusChange::Next
+                });
+                Handled::Yes
+            }
+            _ => is_handled,
+        };
+
         if matches!(
             (event, self.size_policy),
             (Event::WindowSize(_), WindowSizePolicy::Content)
@@ -330,9 +505,16 @@ impl<T: Data> Window<T> {
         env: &Env,
         process_commands: bool,
     ) {
-        let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut widget_state = WidgetState::new(self.root.id(), Some(self.content_size()));
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.content_scale,
+        );
+        state.record_interactions = self.record_interactions;
         let mut ctx = LifeCycleCtx {
             state: &mut state,
             widget_state: &mut widget_state,
@@ -350,9 +532,16 @@ impl<T: Data> Window<T> {
     pub(crate) fn update(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
         self.update_title(data, env);
 
-        let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut widget_state = WidgetState::new(self.root.id(), Some(self.content_size()));
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.content_scale,
+        );
+        state.record_interactions = self.record_interactions;
         let mut update_ctx = UpdateCtx {
             widget_state: &mut widget_state,
             state: &mut state,
@@ -378,7 +567,7 @@ impl<T: Data> Window<T> {
             self.handle.invalidate();
         } else {
             for rect in self.invalid.rects() {
-                self.handle.invalidate_rect(*rect);
+                self.handle.invalidate_rect(self.to_window_rect(*rect));
             }
         }
         self.invalid.clear();
@@ -432,20 +621,44 @@ impl<T: Data> Window<T> {
                 },
             );
         }
-        self.paint(piet, invalid, queue, data, env);
+        if self.content_scale == 1.0 {
+            self.paint(piet, invalid, queue, data, env);
+            return;
+        }
+
+        let mut content_invalid = Region::EMPTY;
+        for &r in invalid.rects() {
+            content_invalid.add_rect(r.scale_from_origin(self.content_scale.recip()).expand());
+        }
+        if let Err(e) = piet.save() {
+            error!("Failed to save RenderContext: '{}'", e);
+            return;
+        }
+        piet.transform(Affine::scale(self.content_scale));
+        self.paint(piet, &content_invalid, queue, data, env);
+        if let Err(e) = piet.restore() {
+            error!("Failed to restore RenderContext: '{}'", e);
+        }
     }
 
     fn layout(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
-        let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let mut widget_state = WidgetState::new(self.root.id(), Some(self.content_size()));
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.content_scale,
+        );
+        state.record_interactions = self.record_interactions;
         let mut layout_ctx = LayoutCtx {
             state: &mut state,
             widget_state: &mut widget_state,
             mouse_pos: self.last_mouse_pos,
         };
         let bc = match self.size_policy {
-            WindowSizePolicy::User => BoxConstraints::tight(self.size),
+            WindowSizePolicy::User => BoxConstraints::tight(self.content_size()),
             WindowSizePolicy::Content => BoxConstraints::UNBOUNDED,
         };
 
@@ -457,7 +670,7 @@ impl<T: Data> Window<T> {
 
         if let WindowSizePolicy::Content = self.size_policy {
             let insets = self.handle.content_insets();
-            let full_size = (content_size.to_rect() + insets).size();
+            let full_size = ((content_size * self.content_scale).to_rect() + insets).size();
             if self.size != full_size {
                 self.size = full_size;
                 self.handle.set_size(full_size)
@@ -489,9 +702,17 @@ impl<T: Data> Window<T> {
         data: &T,
         env: &Env,
     ) {
-        let widget_state = WidgetState::new(self.root.id(), Some(self.size));
-        let mut state =
-            ContextState::new::<T>(queue, &self.ext_handle, &self.handle, self.id, self.focus);
+        let widget_state = WidgetState::new(self.root.id(), Some(self.content_size()));
+        let mut state = ContextState::new::<T>(
+            queue,
+            &self.ext_handle,
+            &self.handle,
+            self.id,
+            self.focus,
+            self.content_scale,
+        );
+        state.record_interactions = self.record_interactions;
+        state.offscreen_device = self.offscreen_device.take();
         let mut ctx = PaintCtx {
             render_ctx: piet,
             state: &mut state,
@@ -517,6 +738,21 @@ impl<T: Data> Window<T> {
                 });
             });
         }
+        self.raised_widgets = mem::take(&mut ctx.state.raised_widgets);
+        self.offscreen_device = ctx.state.offscreen_device.take();
+
+        // the drag image is above everything else.
+        let image = self.drag.as_ref().and_then(|drag| drag.image.as_ref());
+        if let (Some(image), Some(pos)) = (image, self.last_mouse_pos) {
+            let origin = image.rect_at(pos).origin();
+            ctx.with_child_ctx(invalid.clone(), |ctx| {
+                ctx.with_save(|ctx| {
+                    ctx.render_ctx
+                        .transform(Affine::translate(origin.to_vec2()));
+                    image.paint(ctx, env);
+                });
+            });
+        }
 
         if self.wants_animation_frame() {
             self.handle.request_anim_frame();
@@ -547,11 +783,93 @@ impl<T: Data> Window<T> {
         req_token: TextFieldToken,
         mutable: bool,
     ) -> Box<dyn InputHandler> {
-        self.ime_handlers
+        let handler = self
+            .ime_handlers
             .iter()
             .find(|(token, _)| req_token == *token)
             .and_then(|(_, reg)| reg.document.acquire(mutable))
-            .unwrap()
+            .unwrap();
+        if self.content_scale == 1.0 {
+            handler
+        } else {
+            Box::new(ScaledInputHandler {
+                inner: handler,
+                scale: self.content_scale,
+            })
+        }
+    }
+
+    /// Sets the factor by which the content is scaled up to fill the window.
+    ///
+    /// The widgets get a new [`Event::WindowSize`], so they are laid out again
+    /// at their new size.
+    pub(crate) fn set_content_scale(
+        &mut self,
+        queue: &mut CommandQueue,
+        scale: f64,
+        data: &mut T,
+        env: &Env,
+    ) {
+        if !(scale.is_finite() && scale > 0.0) {
+            warn!("ignoring invalid content scale {}", scale);
+            return;
+        }
+        if scale == self.content_scale {
+            return;
+        }
+        self.content_scale = scale;
+        self.event(queue, Event::WindowSize(self.size), data, env);
+        self.handle.invalidate();
+    }
+
+    /// The size of the window in the coordinate space of the widgets.
+    fn content_size(&self) -> Size {
+        self.size / self.content_scale
+    }
+
+    /// Converts an event from the window's coordinate space to the widgets'.
+    fn to_content_event(&self, event: Event) -> Event {
+        if self.content_scale == 1.0 {
+            return event;
+        }
+        let scale = self.content_scale;
+        let scale_mouse = |mut mouse: MouseEvent| {
+            mouse.pos = Point::new(mouse.pos.x / scale, mouse.pos.y / scale);
+            mouse
+        };
+        let scale_touch = |mut touch: TouchEvent| {
+            touch.pos = Point::new(touch.pos.x / scale, touch.pos.y / scale);
+            touch
+        };
+        match event {
+            Event::WindowSize(size) => Event::WindowSize(size / scale),
+            Event::MouseDown(mouse) => Event::MouseDown(scale_mouse(mouse)),
+            Event::MouseUp(mouse) => Event::MouseUp(scale_mouse(mouse)),
+            Event::MouseMove(mouse) => Event::MouseMove(scale_mouse(mouse)),
+            Event::Wheel(mouse) => Event::Wheel(scale_mouse(mouse)),
+            Event::TouchDown(touch) => Event::TouchDown(scale_touch(touch)),
+            Event::TouchMove(touch) => Event::TouchMove(scale_touch(touch)),
+            Event::TouchUp(touch) => Event::TouchUp(scale_touch(touch)),
+            Event::Pen(mut pen) => {
+                pen.pos = Point::new(pen.pos.x / scale, pen.pos.y / scale);
+                Event::Pen(pen)
+            }
+            Event::DragEnter(ref drag) | Event::DragOver(ref drag) | Event::Drop(ref drag) => {
+                let mut scaled = drag.clone();
+                scaled.pos = Point::new(drag.pos.x / scale, drag.pos.y / scale);
+                event.with_drag(scaled)
+            }
+            other => other,
+        }
+    }
+
+    /// Converts a rectangle from the widgets' coordinate space to the window's.
+    fn to_window_rect(&self, rect: Rect) -> Rect {
+        if self.content_scale == 1.0 {
+            rect
+        } else {
+            rect.scale_from_origin(self.content_scale).expand()
+        }
     }
 
     fn update_focus(
@@ -562,6 +880,13 @@ impl<T: Data> Window<T> {
         env: &Env,
     ) {
         if let Some(focus_req) = widget_state.request_focus.take() {
+            if let FocusChange::FirstIn(widget) = focus_req {
+                // the widget finds its first focusable descendant, and requests
+                // focus for it.
+                let event = LifeCycle::Internal(InternalLifeCycle::RouteFocusFirstIn(widget));
+                self.lifecycle(queue, &event, data, env, false);
+                return;
+            }
             let old = self.focus;
             let new = self.widget_for_focus_request(focus_req);
             // Only send RouteFocusChanged in case there's actual change
@@ -581,11 +906,7 @@ impl<T: Data> Window<T> {
                             .any(|(_, sesh)| sesh.widget_id == old)
                     })
                     .unwrap_or(false);
-                let maybe_active_text_field = self
-                    .ime_handlers
-                    .iter()
-                    .find(|(_, sesh)| Some(sesh.widget_id) == self.focus)
-                    .map(|(token, _)| *token);
+                let maybe_active_text_field = self.active_text_field();
                 // we call this on every focus change; we could call it less but does it matter?
                 self.ime_focus_change = if maybe_active_text_field.is_some() {
                     Some(maybe_active_text_field)
@@ -598,6 +919,14 @@ impl<T: Data> Window<T> {
         }
     }
 
+    /// The IME session of the focused widget, if it has one.
+    pub(crate) fn active_text_field(&self) -> Option<TextFieldToken> {
+        self.ime_handlers
+            .iter()
+            .find(|(_, sesh)| Some(sesh.widget_id) == self.focus)
+            .map(|(token, _)| *token)
+    }
+
     /// Create a function that can invalidate the provided widget's text state.
     ///
     /// This will be called from outside the main app state in order to avoid
@@ -631,35 +960,36 @@ impl<T: Data> Window<T> {
             FocusChange::Focus(id) => Some(id),
             FocusChange::Next => self.widget_from_focus_chain(true),
             FocusChange::Previous => self.widget_from_focus_chain(false),
+            FocusChange::FirstIn(_) => self.focus,
         }
     }
 
     fn widget_from_focus_chain(&self, forward: bool) -> Option<WidgetId> {
-        self.focus.and_then(|focus| {
-            self.focus_chain()
-                .iter()
-                // Find where the focused widget is in the focus chain
-                .position(|id| id == &focus)
-                .map(|idx| {
-                    // Return the id that's next to it in the focus chain
-                    let len = self.focus_chain().len();
-                    let new_idx = if forward {
-                        (idx + 1) % len
-                    } else {
-                        (idx + len - 1) % len
-                    };
-                    self.focus_chain()[new_idx]
-                })
-                .or_else(|| {
-                    // If the currently focused widget isn't in the focus chain,
-                    // then we'll just return the first/last entry of the chain, if any.
-                    if forward {
-                        self.focus_chain().first().copied()
-                    } else {
-                        self.focus_chain().last().copied()
-                    }
-                })
-        })
+        let chain = &self.root.state().focus_chain;
+        let focused = self
+            .focus
+            .and_then(|focus| chain.iter().find(|entry| entry.id == focus));
+        let mut order = tab_order(chain);
+        // Traversal doesn't leave the innermost focus scope of the focused widget.
+        if let Some(scope) = focused.and_then(|entry| entry.scopes.first()) {
+            order.retain(|entry| entry.scopes.contains(scope));
+        }
+        if order.is_empty() {
+            return None;
+        }
+        let len = order.len();
+        let new_idx = match focused.and_then(|focused| {
+            // Find where the focused widget is in the focus chain
+            order.iter().position(|entry| entry.id == focused.id)
+        }) {
+            Some(idx) if forward => (idx + 1) % len,
+            Some(idx) => (idx + len - 1) % len,
+            // If the currently focused widget isn't in the focus chain,
+            // then we'll just return the first/last entry of the chain.
+            None if forward => 0,
+            None => len - 1,
+        };
+        Some(order[new_idx].id)
     }
 }
 
@@ -670,3 +1000,83 @@ impl WindowId {
         WindowId(WINDOW_COUNTER.next())
     }
 }
+
+/// An [`InputHandler`] for a window with a [content scale], which converts
+/// geometry between the widgets' coordinate space and the window's.
+///
+/// [content scale]: crate::WindowDesc::content_scale
+struct ScaledInputHandler {
+    inner: Box<dyn InputHandler>,
+    scale: f64,
+}
+
+impl InputHandler for ScaledInputHandler {
+    fn selection(&self) -> Selection {
+        self.inner.selection()
+    }
+
+    fn set_selection(&mut self, selection: Selection) {
+        self.inner.set_selection(selection)
+    }
+
+    fn composition_range(&self) -> Option<Range<usize>> {
+        self.inner.composition_range()
+    }
+
+    fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+        self.inner.set_composition_range(range)
+    }
+
+    fn is_char_boundary(&self, i: usize) -> bool {
+        self.inner.is_char_boundary(i)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn slice(&self, range: Range<usize>) -> Cow<'_, str> {
+        self.inner.slice(range)
+    }
+
+    fn utf8_to_utf16(&self, utf8_range: Range<usize>) -> usize {
+        self.inner.utf8_to_utf16(utf8_range)
+    }
+
+    fn utf16_to_utf8(&self, utf16_range: Range<usize>) -> usize {
+        self.inner.utf16_to_utf8(utf16_range)
+    }
+
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        self.inner.replace_range(range, text)
+    }
+
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        let point = Point::new(point.x / self.scale, point.y / self.scale);
+        self.inner.hit_test_point(point)
+    }
+
+    fn line_range(&self, index: usize, affinity: Affinity) -> Range<usize> {
+        self.inner.line_range(index, affinity)
+    }
+
+    fn bounding_box(&self) -> Option<Rect> {
+        self.inner
+            .bounding_box()
+            .map(|rect| rect.scale_from_origin(self.scale))
+    }
+
+    fn slice_bounding_box(&self, range: Range<usize>) -> Option<Rect> {
+        self.inner
+            .slice_bounding_box(range)
+            .map(|rect| rect.scale_from_origin(self.scale))
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.inner.handle_action(action)
+    }
+
+    fn input_hints(&self) -> InputHints {
+        self.inner.input_hints()
+    }
+}
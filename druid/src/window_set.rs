@@ -0,0 +1,275 @@
+// Copyright 2021 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Windows that are described by the application data.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{ArcStr, Data, Lens, WindowConfig, WindowDesc, WindowId};
+
+type BuildWindowFn<T> = Box<dyn Fn(&WindowEntry, &T) -> WindowDesc<T>>;
+type GetEntriesFn<T> = Box<dyn Fn(&T) -> Arc<Vec<WindowEntry>>>;
+type RemoveEntryFn<T> = Box<dyn Fn(&mut T, &ArcStr)>;
+
+/// A window that should be open, as an item of the list that a [`WindowSet`]
+/// follows.
+#[derive(Debug, Clone, Data)]
+pub struct WindowEntry {
+    /// Identifies the window from one update to the next, for instance the
+    /// path of the document it shows.
+    pub key: ArcStr,
+    /// The name of the [builder] that creates the window.
+    ///
+    /// [builder]: WindowSet::builder
+    pub builder: ArcStr,
+    /// The configuration of the window. Replacing it reconfigures the window
+    /// if it is open.
+    pub config: Arc<WindowConfig>,
+}
+
+/// A set of windows that follows a list of [`WindowEntry`] in the app data.
+///
+/// Whenever the data changes, windows are opened for new entries, closed for
+/// removed entries, and reconfigured when the config of their entry is
+/// replaced. When the user closes one of the windows, its entry is removed
+/// from the data, so the list always matches what is on screen.
+///
+/// This makes multi-window apps, like an editor with a window per document,
+/// declarative: opening a document is adding an entry to the list.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use druid::widget::Label;
+/// use druid::{AppLauncher, Data, Lens, WindowDesc, WindowEntry, WindowSet};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     windows: Arc<Vec<WindowEntry>>,
+/// }
+///
+/// let windows = WindowSet::new(AppState::windows).builder("document", |entry, _data| {
+///     WindowDesc::new(Label::new(format!("Editing {}", entry.key))).title("Document")
+/// });
+/// let state = AppState {
+///     windows: Arc::new(vec![WindowEntry::new("notes.txt", "document")]),
+/// };
+/// AppLauncher::with_window_set(windows)
+///     .launch(state)
+///     .expect("launch failed");
+/// ```
+pub struct WindowSet<T> {
+    entries: GetEntriesFn<T>,
+    remove: RemoveEntryFn<T>,
+    builders: HashMap<ArcStr, BuildWindowFn<T>>,
+    /// The entries we have acted on, by key, with the id of their window,
+    /// unless there is no builder for them.
+    open: HashMap<ArcStr, (WindowEntry, Option<WindowId>)>,
+    /// The list of entries we last acted on.
+    last: Option<Arc<Vec<WindowEntry>>>,
+}
+
+/// What needs to happen for the open windows to match the app data.
+pub(crate) struct WindowChanges<T> {
+    pub(crate) open: Vec<WindowDesc<T>>,
+    pub(crate) close: Vec<WindowId>,
+    pub(crate) configure: Vec<(WindowId, Arc<WindowConfig>)>,
+}
+
+impl WindowEntry {
+    /// Create an entry for a window with the default configuration, made by
+    /// the [builder] named `builder`.
+    ///
+    /// [builder]: WindowSet::builder
+    pub fn new(key: impl Into<ArcStr>, builder: impl Into<ArcStr>) -> Self {
+        WindowEntry {
+            key: key.into(),
+            builder: builder.into(),
+            config: Arc::new(WindowConfig::default()),
+        }
+    }
+
+    /// Builder-style method to set the window's configuration.
+    pub fn with_config(mut self, config: WindowConfig) -> Self {
+        self.config = Arc::new(config);
+        self
+    }
+}
+
+impl<T: Data> WindowSet<T> {
+    /// Create a set that follows the list of entries at `entries`.
+    pub fn new(entries: impl Lens<T, Arc<Vec<WindowEntry>>> + 'static) -> Self {
+        let entries = Rc::new(entries);
+        let lens = entries.clone();
+        WindowSet {
+            entries: Box::new(move |data| lens.with(data, |entries| entries.clone())),
+            remove: Box::new(move |data, key| {
+                entries.with_mut(data, |entries| {
+                    Arc::make_mut(entries).retain(|entry| &entry.key != key)
+                })
+            }),
+            builders: HashMap::new(),
+            open: HashMap::new(),
+            last: None,
+        }
+    }
+
+    /// Builder-style method to add a way of creating windows, used for the
+    /// entries whose [`builder`] is `name`.
+    ///
+    /// The window's content, title and menu come from the returned
+    /// [`WindowDesc`]; its configuration comes from the entry.
+    ///
+    /// [`builder`]: WindowEntry::builder
+    pub fn builder(
+        mut self,
+        name: impl Into<ArcStr>,
+        build: impl Fn(&WindowEntry, &T) -> WindowDesc<T> + 'static,
+    ) -> Self {
+        self.builders.insert(name.into(), Box::new(build));
+        self
+    }
+
+    /// Compare the entries in `data` to the windows we opened.
+    pub(crate) fn reconcile(&mut self, data: &T) -> WindowChanges<T> {
+        let mut changes = WindowChanges {
+            open: Vec::new(),
+            close: Vec::new(),
+            configure: Vec::new(),
+        };
+        let entries = (self.entries)(data);
+        if matches!(&self.last, Some(last) if last.same(&entries)) {
+            return changes;
+        }
+        self.last = Some(entries.clone());
+
+        let keys = entries
+            .iter()
+            .map(|entry| &entry.key)
+            .collect::<HashSet<_>>();
+        self.open.retain(|key, (_, id)| {
+            let keep = keys.contains(key);
+            if let (false, Some(id)) = (keep, id) {
+                changes.close.push(*id);
+            }
+            keep
+        });
+
+        for entry in entries.iter() {
+            if let Some((open, id)) = self.open.get_mut(&entry.key) {
+                if let (false, Some(id)) = (open.config.same(&entry.config), id) {
+                    changes.configure.push((*id, entry.config.clone()));
+                }
+                *open = entry.clone();
+                continue;
+            }
+            let id = match self.builders.get(&entry.builder) {
+                Some(build) => {
+                    let mut desc = build(entry, data);
+                    desc.config = (*entry.config).clone();
+                    let id = desc.id;
+                    changes.open.push(desc);
+                    Some(id)
+                }
+                None => {
+                    tracing::error!("no window builder named {:?}", entry.builder);
+                    None
+                }
+            };
+            self.open.insert(entry.key.clone(), (entry.clone(), id));
+        }
+        changes
+    }
+
+    /// Remove the entry of a window that was closed from `data`.
+    ///
+    /// Returns `true` if the window belonged to this set.
+    pub(crate) fn window_closed(&mut self, data: &mut T, window_id: WindowId) -> bool {
+        let key = self
+            .open
+            .iter()
+            .find(|(_, (_, id))| *id == Some(window_id))
+            .map(|(key, _)| key.clone());
+        match key {
+            Some(key) => {
+                self.open.remove(&key);
+                (self.remove)(data, &key);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Label;
+
+    #[derive(Clone, Data, Lens)]
+    struct State {
+        windows: Arc<Vec<WindowEntry>>,
+    }
+
+    fn set() -> WindowSet<State> {
+        WindowSet::new(State::windows).builder("doc", |entry, _| {
+            WindowDesc::new(Label::new(entry.key.to_string()))
+        })
+    }
+
+    #[test]
+    fn follows_entries() {
+        let mut set = set();
+        let mut state = State {
+            windows: Arc::new(vec![
+                WindowEntry::new("a", "doc"),
+                WindowEntry::new("b", "doc"),
+            ]),
+        };
+        let changes = set.reconcile(&state);
+        assert_eq!(changes.open.len(), 2);
+        let a_id = set.open[&ArcStr::from("a")].1.unwrap();
+
+        // unchanged data changes nothing
+        assert!(set.reconcile(&state).open.is_empty());
+
+        Arc::make_mut(&mut state.windows).retain(|entry| &*entry.key != "a");
+        Arc::make_mut(&mut state.windows)[0].config =
+            Arc::new(WindowConfig::default().resizable(false));
+        let changes = set.reconcile(&state);
+        assert_eq!(changes.close, vec![a_id]);
+        assert_eq!(changes.configure.len(), 1);
+        assert!(changes.open.is_empty());
+    }
+
+    #[test]
+    fn closing_removes_entry() {
+        let mut set = set();
+        let mut state = State {
+            windows: Arc::new(vec![
+                WindowEntry::new("a", "doc"),
+                WindowEntry::new("b", "doc"),
+            ]),
+        };
+        set.reconcile(&state);
+        let b_id = set.open[&ArcStr::from("b")].1.unwrap();
+
+        assert!(set.window_closed(&mut state, b_id));
+        assert_eq!(state.windows.len(), 1);
+        assert_eq!(&*state.windows[0].key, "a");
+        let changes = set.reconcile(&state);
+        assert!(changes.open.is_empty() && changes.close.is_empty());
+    }
+}